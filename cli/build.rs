@@ -48,6 +48,57 @@ fn main() {
             );
         }
     }
+
+    copy_feature_contract(Path::new(&out_dir), "inbox_contract.wasm", "inbox-contract");
+    copy_feature_contract(
+        Path::new(&out_dir),
+        "presence_contract.wasm",
+        "presence-contract",
+    );
+    copy_feature_contract(Path::new(&out_dir), "space_contract.wasm", "space-contract");
+}
+
+/// Bundle an optional contract (the DM inbox, presence, spaces) when its
+/// cargo feature is on.
+///
+/// The feature stays off until a released WASM is committed under
+/// `contracts/` with a signed pointer record; without it riverctl is built
+/// without the client paths' contract (`api::inbox_contract_available`
+/// reports `false`, so DMs stay in-room; `member list --online` reports that
+/// presence is unavailable; the `space` commands refuse to run). With the
+/// feature on, a missing WASM fails the build: bundling nothing would ship the
+/// feature inert.
+fn copy_feature_contract(out_dir: &Path, file: &str, feature: &str) {
+    let feature_var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+    if env::var_os(feature_var).is_none() {
+        return;
+    }
+    let dest_path = out_dir.join(file);
+    let possible_paths = [
+        format!("../ui/public/contracts/{file}"),
+        format!("ui/public/contracts/{file}"),
+        format!("contracts/{file}"),
+    ];
+    // Watch the directories, not the files: a rerun-if-changed on a path that
+    // does not exist re-runs this script on every build.
+    for dir in ["../ui/public/contracts", "ui/public/contracts", "contracts"] {
+        if Path::new(dir).is_dir() {
+            println!("cargo:rerun-if-changed={}", dir);
+        }
+    }
+    match possible_paths.iter().find(|p| Path::new(p).exists()) {
+        Some(path) => {
+            fs::copy(path, &dest_path).expect("Failed to copy optional WASM file");
+        }
+        None if env::var("DOCS_RS").is_ok() => {
+            fs::write(&dest_path, b"dummy").expect("Failed to create dummy WASM file");
+        }
+        None => panic!(
+            "{file} not found, but the `{feature}` feature is on. Commit the released WASM \
+             with `scripts/sync-wasm.sh`, or build without the feature. Looked in: {:?}",
+            possible_paths
+        ),
+    }
 }
 
 fn verify_matches_built_artifact(dest_path: &Path) {
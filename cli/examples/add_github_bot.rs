@@ -162,6 +162,7 @@ async fn main() -> Result<()> {
         version: 0,
         preferred_nickname: SealedBytes::public("GitHub Bot".to_string().into_bytes()),
         deputies: Vec::new(),
+        profile: None,
     };
     let authorized_member_info = AuthorizedMemberInfo::new(member_info, &github_bot_sk);
 
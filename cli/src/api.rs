@@ -18,20 +18,25 @@ use freenet_stdlib::prelude::{
     ContractCode, ContractContainer, ContractInstanceId, ContractKey, ContractWasmAPIVersion,
     Parameters, UpdateData, WrappedContract, WrappedState,
 };
+use river_core::inbox::{InboxParametersV1, InboxStateV1};
+use river_core::presence::{PresenceParametersV1, PresenceStateV1};
 use river_core::room_state::ban::{AuthorizedUserBan, UserBan};
 use river_core::room_state::configuration::{AuthorizedConfigurationV1, Configuration};
 use river_core::room_state::member::{AuthorizedMember, Member, MemberId, MembersDelta};
-use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfo};
+use river_core::room_state::member_info::{
+    AuthorizedMemberInfo, Avatar, MemberInfo, MemberProfile,
+};
 use river_core::room_state::privacy::{PrivacyMode, RoomDisplayMetadata, SealedBytes};
 use river_core::room_state::upgrade::OptionalUpgradeV1;
 use river_core::room_state::ChatRoomStateV1Delta;
 use river_core::room_state::{ChatRoomParametersV1, ChatRoomStateV1};
+use river_core::space::{SpaceDeltaV1, SpaceParametersV1, SpaceStateV1};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::sync::Mutex;
 use tokio_tungstenite::connect_async;
 use tracing::{debug, info, warn};
@@ -39,6 +44,41 @@ use tracing::{debug, info, warn};
 // Load the room contract WASM copied by build.rs
 const ROOM_CONTRACT_WASM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/room_contract.wasm"));
 
+// The DM inbox contract WASM copied by build.rs, behind the `inbox-contract`
+// feature — see [`inbox_contract_available`].
+#[cfg(feature = "inbox-contract")]
+const INBOX_CONTRACT_WASM: Option<&[u8]> = Some(include_bytes!(concat!(
+    env!("OUT_DIR"),
+    "/inbox_contract.wasm"
+)));
+#[cfg(not(feature = "inbox-contract"))]
+const INBOX_CONTRACT_WASM: Option<&[u8]> = None;
+
+// The presence contract WASM copied by build.rs, behind the
+// `presence-contract` feature like the inbox's.
+#[cfg(feature = "presence-contract")]
+const PRESENCE_CONTRACT_WASM: Option<&[u8]> = Some(include_bytes!(concat!(
+    env!("OUT_DIR"),
+    "/presence_contract.wasm"
+)));
+#[cfg(not(feature = "presence-contract"))]
+const PRESENCE_CONTRACT_WASM: Option<&[u8]> = None;
+
+// The space contract WASM copied by build.rs, behind the `space-contract`
+// feature like the inbox's.
+#[cfg(feature = "space-contract")]
+const SPACE_CONTRACT_WASM: Option<&[u8]> = Some(include_bytes!(concat!(
+    env!("OUT_DIR"),
+    "/space_contract.wasm"
+)));
+#[cfg(not(feature = "space-contract"))]
+const SPACE_CONTRACT_WASM: Option<&[u8]> = None;
+
+/// Timeout for a GET against a member's DM inbox. An absent inbox (the member
+/// has never run an inbox-aware client) only ever answers by timing out, and
+/// `dm send` pays this once per send to find out, so it is kept short.
+const INBOX_GET_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Timeout for the GET against the current room contract.
 const CURRENT_GET_TIMEOUT: Duration = Duration::from_secs(30);
 /// Per-probe timeout when searching older contract generations (freenet/river#292).
@@ -73,6 +113,77 @@ pub fn bundled_room_code_hash() -> [u8; 32] {
     *blake3::hash(ROOM_CONTRACT_WASM).as_bytes()
 }
 
+/// Whether this binary bundles the DM inbox contract (#230 Phase 4), i.e. was
+/// built with the `inbox-contract` feature. Until it is, every DM goes in-room
+/// and `dm list` reads the room alone.
+pub fn inbox_contract_available() -> bool {
+    INBOX_CONTRACT_WASM.is_some()
+}
+
+/// The inbox contract for `owner`, keyed by their `member_vk`.
+fn inbox_contract(owner: &VerifyingKey) -> Result<(ContractKey, ContractContainer)> {
+    let wasm = INBOX_CONTRACT_WASM
+        .ok_or_else(|| anyhow!("This riverctl build does not bundle the DM inbox contract"))?;
+    let mut params_bytes = Vec::new();
+    ciborium::ser::into_writer(&InboxParametersV1 { owner: *owner }, &mut params_bytes)
+        .map_err(|e| anyhow!("Failed to serialize inbox parameters: {}", e))?;
+    let code = ContractCode::from(wasm);
+    let key = ContractKey::from_params_and_code(Parameters::from(params_bytes.clone()), &code);
+    let container = ContractContainer::from(ContractWasmAPIVersion::V1(WrappedContract::new(
+        Arc::new(code),
+        Parameters::from(params_bytes),
+    )));
+    Ok((key, container))
+}
+
+/// Whether this binary bundles the presence contract, i.e. was built with the
+/// `presence-contract` feature. Until it is, `member list --online` has
+/// nothing to read.
+pub fn presence_contract_available() -> bool {
+    PRESENCE_CONTRACT_WASM.is_some()
+}
+
+/// Whether this binary bundles the space contract, i.e. was built with the
+/// `space-contract` feature. Until it is, the `space` commands refuse to run.
+pub fn space_contract_available() -> bool {
+    SPACE_CONTRACT_WASM.is_some()
+}
+
+/// The space contract for the space owned by `owner`.
+fn space_contract(owner: &VerifyingKey) -> Result<(ContractKey, ContractContainer)> {
+    let wasm = SPACE_CONTRACT_WASM
+        .ok_or_else(|| anyhow!("This riverctl build does not bundle the space contract"))?;
+    let mut params_bytes = Vec::new();
+    ciborium::ser::into_writer(&SpaceParametersV1 { owner: *owner }, &mut params_bytes)
+        .map_err(|e| anyhow!("Failed to serialize space parameters: {}", e))?;
+    let code = ContractCode::from(wasm);
+    let key = ContractKey::from_params_and_code(Parameters::from(params_bytes.clone()), &code);
+    let container = ContractContainer::from(ContractWasmAPIVersion::V1(WrappedContract::new(
+        Arc::new(code),
+        Parameters::from(params_bytes),
+    )));
+    Ok((key, container))
+}
+
+/// The presence contract for the room owned by `room_owner`.
+fn presence_contract_key(room_owner: &VerifyingKey) -> Result<ContractKey> {
+    let wasm = PRESENCE_CONTRACT_WASM
+        .ok_or_else(|| anyhow!("This riverctl build does not bundle the presence contract"))?;
+    let mut params_bytes = Vec::new();
+    ciborium::ser::into_writer(
+        &PresenceParametersV1 {
+            room_owner: *room_owner,
+        },
+        &mut params_bytes,
+    )
+    .map_err(|e| anyhow!("Failed to serialize presence parameters: {}", e))?;
+    let code = ContractCode::from(wasm);
+    Ok(ContractKey::from_params_and_code(
+        Parameters::from(params_bytes),
+        &code,
+    ))
+}
+
 /// [`PointerIo`] over riverctl's existing node connection.
 ///
 /// Deliberately not built on the crate's `ConservativeProbeIo` adapter: that
@@ -710,7 +821,22 @@ pub(crate) fn message_display_text_with_secrets(
     msg: &river_core::room_state::message::AuthorizedMessageV1,
     secrets: &HashMap<u32, [u8; 32]>,
 ) -> String {
-    let raw = room_state
+    render_mentions_for_terminal(
+        room_state,
+        &message_raw_text_with_secrets(room_state, msg, secrets),
+    )
+}
+
+/// The body [`message_display_text_with_secrets`] renders, before mention
+/// rendering: post-edit text, a decrypted private body, a decoded non-text
+/// public body, or `<encrypted>`. Mention tokens are left encoded so a caller
+/// with its own resolver (the transcript export) can render them itself.
+pub(crate) fn message_raw_text_with_secrets(
+    room_state: &ChatRoomStateV1,
+    msg: &river_core::room_state::message::AuthorizedMessageV1,
+    secrets: &HashMap<u32, [u8; 32]>,
+) -> String {
+    room_state
         .recent_messages
         .effective_text(msg)
         .or_else(|| decrypt_private_body_text(&msg.message.content, secrets))
@@ -720,8 +846,7 @@ pub(crate) fn message_display_text_with_secrets(
                 .decode_content()
                 .map(|decoded| decoded.to_display_string())
                 .unwrap_or_else(|| "<encrypted>".to_string())
-        });
-    render_mentions_for_terminal(room_state, &raw)
+        })
 }
 
 /// Decrypt a **private** message body to its display text, mirroring the UI's
@@ -895,6 +1020,74 @@ pub(crate) fn authorize_send(
     ))
 }
 
+/// One `member set-profile` edit. For each field, `None` leaves it as it is and
+/// `Some(None)` clears it.
+#[derive(Debug, Default)]
+pub struct ProfileUpdate {
+    pub avatar: Option<Option<Avatar>>,
+    pub status: Option<Option<String>>,
+    pub bio: Option<Option<String>>,
+}
+
+/// Apply `update` to `current`, sealing each new value with `seal`, and check
+/// the result against the room's profile limits before it is signed — the
+/// contract silently skips an over-limit record, so without this check the
+/// edit would appear to succeed and never land. Untouched fields that no
+/// longer fit a lowered limit are dropped for the same reason. Returns `None`
+/// once every field is cleared, so the record drops back to its pre-profile
+/// shape.
+pub(crate) fn apply_profile_update(
+    current: Option<MemberProfile>,
+    update: &ProfileUpdate,
+    config: &Configuration,
+    seal: impl Fn(&[u8]) -> Result<SealedBytes>,
+) -> Result<Option<MemberProfile>> {
+    let mut profile = current
+        .and_then(|p| p.fitted_to(config))
+        .unwrap_or_default();
+    if let Some(avatar) = &update.avatar {
+        let encoded = avatar.as_ref().map(Avatar::encode);
+        if let Some(bytes) = &encoded {
+            if bytes.len() > config.effective_max_avatar_size() {
+                return Err(anyhow!(
+                    "Avatar is {} bytes encoded; this room allows at most {}",
+                    bytes.len(),
+                    config.effective_max_avatar_size()
+                ));
+            }
+        }
+        profile.avatar = encoded.as_deref().map(&seal).transpose()?;
+    }
+    for (field, value, max, label) in [
+        (
+            &mut profile.status,
+            &update.status,
+            config.effective_max_status_size(),
+            "Status",
+        ),
+        (
+            &mut profile.bio,
+            &update.bio,
+            config.effective_max_bio_size(),
+            "Bio",
+        ),
+    ] {
+        let Some(value) = value else { continue };
+        if let Some(text) = value {
+            if text.len() > max {
+                return Err(anyhow!(
+                    "{} is {} bytes; this room allows at most {}",
+                    label,
+                    text.len(),
+                    max
+                ));
+            }
+        }
+        *field = value.as_deref().map(|t| seal(t.as_bytes())).transpose()?;
+    }
+    Ok(Some(profile).filter(|p| !p.is_empty()))
+}
+
 pub(crate) fn unseal_nickname_display(
     nickname: &river_core::room_state::privacy::SealedBytes,
     secrets: &HashMap<u32, [u8; 32]>,
@@ -936,6 +1129,39 @@ pub(crate) fn resolve_outgoing_mentions(room_state: &ChatRoomStateV1, text: &str
     })
 }
 
+/// Refuse an outgoing `@here` / `@room` the room's broadcast-mention policy
+/// does not let `sender` use. Readers would render it but never notify on it,
+/// so sending it would only look like it worked.
+pub(crate) fn authorize_broadcasts(
+    room_state: &ChatRoomStateV1,
+    sender: MemberId,
+    text: &str,
+) -> Result<()> {
+    use river_core::mention::{parse_broadcasts, BroadcastAudience};
+    use river_core::room_state::configuration::BroadcastMentionPolicy;
+
+    let Some(broadcast) = parse_broadcasts(text).into_iter().next() else {
+        return Ok(());
+    };
+    if BroadcastAudience::new(room_state).may_broadcast(sender) {
+        return Ok(());
+    }
+    let allowed = match room_state
+        .configuration
+        .configuration
+        .effective_broadcast_mentions()
+    {
+        BroadcastMentionPolicy::Nobody => "nobody",
+        BroadcastMentionPolicy::Owner => "the room owner",
+        BroadcastMentionPolicy::OwnerAndDeputies => "the room owner and their deputies",
+        BroadcastMentionPolicy::Members => "members",
+    };
+    Err(anyhow!(
+        "@{} is restricted to {allowed} in this room",
+        broadcast.keyword()
+    ))
+}
+
 /// Truncate a reply preview to at most [`REPLY_PREVIEW_MAX_CHARS`] characters
 /// for display, appending `"..."` **only when characters were actually
 /// dropped**. A preview that fits is shown verbatim; a clipped one carries a
@@ -1260,6 +1486,118 @@ pub(crate) fn reply_to_json(ctx: &ReplyContextDisplay) -> Option<serde_json::Val
     }
 }
 
+/// Nickname of `author`, decrypted with `secrets` in a private room.
+fn event_nickname(
+    room_state: &ChatRoomStateV1,
+    author: MemberId,
+    secrets: &HashMap<u32, [u8; 32]>,
+) -> Option<String> {
+    room_state
+        .member_info
+        .canonical(author)
+        .map(|info| unseal_nickname_display(&info.member_info.preferred_nickname, secrets))
+}
+
+/// The `message` / `edit` event of the JSON streams. Shared by
+/// `message stream --format json` and the `ndjson` snapshot diff so the two
+/// cannot disagree on a field.
+pub(crate) fn message_event_json(
+    room_state: &ChatRoomStateV1,
+    msg: &river_core::room_state::message::AuthorizedMessageV1,
+    room_owner_key: &VerifyingKey,
+    is_edit: bool,
+    secrets: &HashMap<u32, [u8; 32]>,
+) -> serde_json::Value {
+    let msg_id = msg.id();
+    let reactions_map: HashMap<String, usize> = room_state
+        .recent_messages
+        .reactions(&msg_id)
+        .map(|r| r.iter().map(|(k, v)| (k.clone(), v.len())).collect())
+        .unwrap_or_default();
+    let datetime: DateTime<Utc> = msg.message.time.into();
+    // Reply context (null for non-replies) so a relay can thread the
+    // message; previously absent from the monitor's JSON output.
+    let reply = reply_context_display_with_secrets(room_state, msg, secrets);
+    json!({
+        "type": if is_edit { "edit" } else { "message" },
+        "message_id": msg_id.0 .0.to_string(),
+        "room": bs58::encode(room_owner_key.as_bytes()).into_string(),
+        "author": msg.message.author.to_string(),
+        "nickname": event_nickname(room_state, msg.message.author, secrets),
+        "content": message_display_text_with_secrets(room_state, msg, secrets),
+        "timestamp": datetime.to_rfc3339(),
+        "edited": room_state.recent_messages.is_edited(&msg_id),
+        "reply_to": reply_to_json(&reply),
+        "reactions": reactions_map,
+    })
+}
+
+/// The `delete` event: the content is gone, so only identity, author and
+/// time are reported.
+pub(crate) fn deletion_event_json(
+    room_state: &ChatRoomStateV1,
+    msg: &river_core::room_state::message::AuthorizedMessageV1,
+    room_owner_key: &VerifyingKey,
+    secrets: &HashMap<u32, [u8; 32]>,
+) -> serde_json::Value {
+    let datetime: DateTime<Utc> = msg.message.time.into();
+    json!({
+        "type": "delete",
+        "message_id": msg.id().0 .0.to_string(),
+        "room": bs58::encode(room_owner_key.as_bytes()).into_string(),
+        "author": msg.message.author.to_string(),
+        "nickname": event_nickname(room_state, msg.message.author, secrets),
+        "timestamp": datetime.to_rfc3339(),
+    })
+}
+
+/// The `reaction` event, carrying the message's current reactions. See
+/// `ApiClient::output_reaction_change` for why `author` is the reacted-to
+/// message's author.
+pub(crate) fn reaction_event_json(
+    room_state: &ChatRoomStateV1,
+    msg: &river_core::room_state::message::AuthorizedMessageV1,
+    room_owner_key: &VerifyingKey,
+    secrets: &HashMap<u32, [u8; 32]>,
+) -> serde_json::Value {
+    let msg_id = msg.id();
+    let reactions = room_state.recent_messages.reactions(&msg_id);
+    let author_str = msg.message.author.to_string();
+    let nickname = event_nickname(room_state, msg.message.author, secrets);
+    let datetime: DateTime<Utc> = msg.message.time.into();
+    let reactions_map: std::collections::HashMap<String, usize> = reactions
+        .map(|r| r.iter().map(|(k, v)| (k.clone(), v.len())).collect())
+        .unwrap_or_default();
+    // WHO reacted, not just how many. `reactions` reports counts and
+    // is kept unchanged for existing consumers, but a count cannot
+    // attribute a reaction to anyone, which made reactions
+    // unmoderatable: nothing downstream could tell who to act on.
+    // The room state has always held this (`reactions()` returns
+    // `HashMap<String, Vec<MemberId>>`); only this boundary dropped it.
+    let reactors_map: std::collections::HashMap<String, Vec<String>> = reactions
+        .map(|r| {
+            r.iter()
+                .map(|(emoji, reactors)| {
+                    (
+                        emoji.clone(),
+                        reactors.iter().map(|id| id.to_string()).collect(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    json!({
+        "type": "reaction",
+        "reactors": reactors_map,
+        "message_id": msg_id.0 .0.to_string(),
+        "room": bs58::encode(room_owner_key.as_bytes()).into_string(),
+        "author": author_str,
+        "nickname": nickname,
+        "timestamp": datetime.to_rfc3339(),
+        "reactions": reactions_map,
+    })
+}
+
 /// Rebuild a room's message `actions_state` (edits / deletes / reactions) using
 /// decrypted content for **private** action messages.
 ///
@@ -1403,7 +1741,7 @@ fn deletions_to_suppress_at_start(
 /// string-escaping makes `{"a":[1],"b":[2]}` and `{"a=1|b":[2]}` distinct (a
 /// hand-rolled `|`/`=`/`,` separator scheme would collide them and silently drop
 /// the change). The JSON is used only for equality comparison, never parsed.
-fn reactions_fingerprint(reactions: Option<&HashMap<String, Vec<MemberId>>>) -> String {
+pub(crate) fn reactions_fingerprint(reactions: Option<&HashMap<String, Vec<MemberId>>>) -> String {
     let Some(reactions) = reactions else {
         return String::new();
     };
@@ -1604,6 +1942,7 @@ fn build_new_room_state(
         version: 0,
         preferred_nickname: seal(nickname.as_bytes()),
         deputies: Vec::new(),
+        profile: None,
     };
     room_state
         .member_info
@@ -1745,7 +2084,8 @@ impl ApiClient {
         // Create WebApi instance
         let web_api = WebApi::start(ws_stream);
 
-        let storage = Storage::new_with_override(config_dir, signing_key_override)?;
+        let storage = Storage::new_with_override(config_dir, signing_key_override)?
+            .with_configured_identities(config.identities()?);
 
         Ok(Self {
             web_api: Arc::new(Mutex::new(web_api)),
@@ -2135,6 +2475,35 @@ impl ApiClient {
         }
     }
 
+    /// Restore a room from a snapshot (`room restore`). The snapshot is
+    /// verified, merged into whatever the current generation still holds (see
+    /// [`crate::snapshot::merge_for_restore`]) and PUT. Unlike
+    /// [`Self::republish_room`] this needs no local credentials: anyone holding
+    /// a valid snapshot can put a lost room back, exactly as any peer can.
+    ///
+    /// Returns the state that was PUT and whether live state was found.
+    pub async fn restore_room(
+        &self,
+        snapshot: &crate::snapshot::RoomSnapshot,
+    ) -> Result<(ChatRoomStateV1, bool)> {
+        snapshot.verify().map_err(|e| anyhow!(e))?;
+        let owner = snapshot.parameters.owner;
+        let contract_key = self
+            .contract_key_for(&owner, KeyIntent::PublishCode)
+            .await?;
+        let live = self
+            .try_fetch_room(&owner, *contract_key.id(), CURRENT_GET_TIMEOUT)
+            .await
+            .map(|(state, _)| state);
+        let found_live = live.is_some();
+        let merged = crate::snapshot::merge_for_restore(snapshot, live).map_err(|e| anyhow!(e))?;
+        self.put_room_state(&owner, &merged).await?;
+        if self.storage.get_room(&owner)?.is_some() {
+            self.storage.update_room_state(&owner, merged.clone())?;
+        }
+        Ok((merged, found_live))
+    }
+
     /// Prepare a freshly-fetched `room_state` for **display** in a private
     /// room: collect the local member's decryption secrets and, in place,
     /// rebuild the message `actions_state` (edits/deletes/reactions) from the
@@ -2175,6 +2544,18 @@ impl ApiClient {
         secrets
     }
 
+    /// Drop what the members on the user's ignore list wrote from a
+    /// freshly-fetched `room_state` held for **display**: their messages and
+    /// their reactions (see [`IgnoreList::hide_in`]). Call it after
+    /// [`Self::room_display_secrets`], and never on state that is stored or
+    /// published. An unreadable ignore list hides nothing.
+    ///
+    /// [`IgnoreList::hide_in`]: river_core::chat_delegate::IgnoreList::hide_in
+    pub(crate) fn hide_ignored(&self, room_state: &mut ChatRoomStateV1) {
+        let ignored = self.storage.load_ignore_list().unwrap_or_default();
+        ignored.hide_in(&mut room_state.recent_messages);
+    }
+
     pub async fn get_room(
         &self,
         room_owner_key: &VerifyingKey,
@@ -2282,7 +2663,9 @@ impl ApiClient {
         let Some(info) = storage.rooms.get(&key_str) else {
             return Ok(state);
         };
-        let signing_key = self.storage.resolve_signing_key(&info.signing_key_bytes);
+        let signing_key = self
+            .storage
+            .resolve_signing_key(&key_str, &info.signing_key_bytes);
         let self_nickname = info.self_nickname.clone();
         let invitation_secrets = info.invitation_secrets.clone();
 
@@ -2758,6 +3141,43 @@ impl ApiClient {
         }
     }
 
+    /// Subscribe to a room's contract so its changes arrive through
+    /// [`Self::next_update`]. Returns the instance id those notifications
+    /// will carry. Call [`Self::get_room`] first: it migrates the room to the
+    /// current contract, and the key subscribed to here is the current one.
+    pub async fn subscribe_room(
+        &self,
+        room_owner_key: &VerifyingKey,
+    ) -> Result<ContractInstanceId> {
+        let contract_key = self
+            .contract_key_for(room_owner_key, KeyIntent::Read)
+            .await?;
+        let id = *contract_key.id();
+        self.subscribe_to_contract(id).await?;
+        Ok(id)
+    }
+
+    /// Wait up to `wait` for the next `UpdateNotification` on this connection
+    /// and return the contract it is for; `None` when nothing arrived. The
+    /// delta itself is discarded — as in [`Self::subscribe_and_stream`], the
+    /// caller re-reads full state — and any other response is logged and
+    /// dropped, since nothing else is in flight while a caller waits here.
+    pub async fn next_update(&self, wait: Duration) -> Result<Option<ContractInstanceId>> {
+        let mut web_api = self.web_api.lock().await;
+        match tokio::time::timeout(wait, web_api.recv()).await {
+            Ok(Ok(HostResponse::ContractResponse(ContractResponse::UpdateNotification {
+                key,
+                ..
+            }))) => Ok(Some(*key.id())),
+            Ok(Ok(other)) => {
+                debug!("Ignoring response while waiting for updates: {other:?}");
+                Ok(None)
+            }
+            Ok(Err(e)) => Err(anyhow!("Failed to receive update: {e}")),
+            Err(_) => Ok(None),
+        }
+    }
+
     pub async fn test_connection(&self) -> Result<()> {
         info!("Testing WebSocket connection...");
 
@@ -3075,6 +3495,7 @@ impl ApiClient {
                                 version: 0,
                                 preferred_nickname: sealed,
                                 deputies: Vec::new(),
+                                profile: None,
                             };
                             let authorized_info = river_core::room_state::member_info::AuthorizedMemberInfo::new_with_member_key(
                                 member_info, signing_key,
@@ -3242,7 +3663,7 @@ impl ApiClient {
 
         let signing_key = self
             .storage
-            .resolve_signing_key(&room_info.signing_key_bytes);
+            .resolve_signing_key(&owner_key_str, &room_info.signing_key_bytes);
         let room_state = room_info.state.clone();
         let previous_contract_key_str = room_info.previous_contract_key.clone();
 
@@ -3769,6 +4190,7 @@ impl ApiClient {
             // member_info (and any deputy grants) was already cleaned up; they
             // re-appoint deputies after rejoining if desired. (#410)
             deputies: Vec::new(),
+            profile: None,
         };
         let authorized_info = AuthorizedMemberInfo::new_with_member_key(member_info, signing_key);
 
@@ -3801,6 +4223,7 @@ impl ApiClient {
 
         // Resolve any bare @nickname mentions to full mention tokens.
         let message_content = resolve_outgoing_mentions(&room_state, &message_content);
+        authorize_broadcasts(&room_state, sender_member_id, &message_content)?;
 
         // Build the body — plaintext for a public room, AES-256-GCM sealed
         // for a private room. Any persisted invitation-carried secrets (when
@@ -3935,6 +4358,11 @@ impl ApiClient {
 
         // Resolve any bare @nickname mentions to full mention tokens.
         let message_content = resolve_outgoing_mentions(&room_state, &message_content);
+        authorize_broadcasts(
+            &room_state,
+            author_member_id(&signing_key),
+            &message_content,
+        )?;
 
         // Build the body — plaintext for a public room, AES-256-GCM sealed for
         // a private room (secret resolved from the contract's per-member
@@ -4088,6 +4516,241 @@ impl ApiClient {
         }
     }
 
+    /// GET `owner`'s DM inbox. `Ok(None)` when this build bundles no inbox
+    /// contract or the inbox has never been published — both mean "DM this
+    /// member in-room". A state that fails verification is treated the same
+    /// way rather than trusted.
+    pub async fn get_inbox(&self, owner: &VerifyingKey) -> Result<Option<InboxStateV1>> {
+        if !inbox_contract_available() {
+            return Ok(None);
+        }
+        let (key, _) = inbox_contract(owner)?;
+        let get_request = ContractRequest::Get {
+            key: *key.id(),
+            return_contract_code: false,
+            subscribe: false,
+            blocking_subscribe: false,
+        };
+        let mut web_api = self.web_api.lock().await;
+        web_api
+            .send(ClientRequest::ContractOp(get_request))
+            .await
+            .map_err(|e| anyhow!("Failed to send inbox GET: {}", e))?;
+        let recv = tokio::time::timeout(INBOX_GET_TIMEOUT, web_api.recv()).await;
+        drop(web_api);
+        let state = match recv {
+            Ok(Ok(HostResponse::ContractResponse(ContractResponse::GetResponse {
+                state, ..
+            }))) => state,
+            _ => return Ok(None),
+        };
+        if state.as_ref().is_empty() {
+            return Ok(Some(InboxStateV1::default()));
+        }
+        let inbox: InboxStateV1 = match ciborium::de::from_reader(state.as_ref()) {
+            Ok(inbox) => inbox,
+            Err(e) => {
+                warn!(
+                    "Inbox state for {:?} did not deserialize: {e}",
+                    MemberId::from(owner)
+                );
+                return Ok(None);
+            }
+        };
+        let params = InboxParametersV1 { owner: *owner };
+        if let Err(e) = inbox.verify(&inbox, &params) {
+            warn!(
+                "Inbox state for {:?} failed verification: {e}",
+                MemberId::from(owner)
+            );
+            return Ok(None);
+        }
+        Ok(Some(inbox))
+    }
+
+    /// GET the room's presence. `Ok(None)` when this build bundles no
+    /// presence contract or nobody in the room runs a presence-aware client;
+    /// a state that fails verification is treated the same way, as
+    /// [`Self::get_inbox`] does.
+    pub async fn get_presence(&self, room_owner: &VerifyingKey) -> Result<Option<PresenceStateV1>> {
+        if !presence_contract_available() {
+            return Ok(None);
+        }
+        let key = presence_contract_key(room_owner)?;
+        let get_request = ContractRequest::Get {
+            key: *key.id(),
+            return_contract_code: false,
+            subscribe: false,
+            blocking_subscribe: false,
+        };
+        let mut web_api = self.web_api.lock().await;
+        web_api
+            .send(ClientRequest::ContractOp(get_request))
+            .await
+            .map_err(|e| anyhow!("Failed to send presence GET: {}", e))?;
+        // Absent presence answers only by timing out, like an absent inbox.
+        let recv = tokio::time::timeout(INBOX_GET_TIMEOUT, web_api.recv()).await;
+        drop(web_api);
+        let state = match recv {
+            Ok(Ok(HostResponse::ContractResponse(ContractResponse::GetResponse {
+                state, ..
+            }))) => state,
+            _ => return Ok(None),
+        };
+        if state.as_ref().is_empty() {
+            return Ok(Some(PresenceStateV1::default()));
+        }
+        let presence: PresenceStateV1 = match ciborium::de::from_reader(state.as_ref()) {
+            Ok(presence) => presence,
+            Err(e) => {
+                warn!(
+                    "Presence state for {:?} did not deserialize: {e}",
+                    MemberId::from(room_owner)
+                );
+                return Ok(None);
+            }
+        };
+        let params = PresenceParametersV1 {
+            room_owner: *room_owner,
+        };
+        if let Err(e) = presence.verify(&presence, &params) {
+            warn!(
+                "Presence state for {:?} failed verification: {e}",
+                MemberId::from(room_owner)
+            );
+            return Ok(None);
+        }
+        Ok(Some(presence))
+    }
+
+    /// GET the space owned by `owner`. `Ok(None)` when the space is absent
+    /// (an absent contract answers only by timing out) or its state fails
+    /// verification.
+    pub async fn get_space(&self, owner: &VerifyingKey) -> Result<Option<SpaceStateV1>> {
+        let (key, _) = space_contract(owner)?;
+        let get_request = ContractRequest::Get {
+            key: *key.id(),
+            return_contract_code: false,
+            subscribe: false,
+            blocking_subscribe: false,
+        };
+        let mut web_api = self.web_api.lock().await;
+        web_api
+            .send(ClientRequest::ContractOp(get_request))
+            .await
+            .map_err(|e| anyhow!("Failed to send space GET: {}", e))?;
+        let recv = tokio::time::timeout(CURRENT_GET_TIMEOUT, web_api.recv()).await;
+        drop(web_api);
+        let state = match recv {
+            Ok(Ok(HostResponse::ContractResponse(ContractResponse::GetResponse {
+                state, ..
+            }))) => state,
+            _ => return Ok(None),
+        };
+        if state.as_ref().is_empty() {
+            return Ok(Some(SpaceStateV1::default()));
+        }
+        let space: SpaceStateV1 = match ciborium::de::from_reader(state.as_ref()) {
+            Ok(space) => space,
+            Err(e) => {
+                warn!(
+                    "Space state for {:?} did not deserialize: {e}",
+                    MemberId::from(owner)
+                );
+                return Ok(None);
+            }
+        };
+        let params = SpaceParametersV1 { owner: *owner };
+        if let Err(e) = space.verify(&space, &params) {
+            warn!(
+                "Space state for {:?} failed verification: {e}",
+                MemberId::from(owner)
+            );
+            return Ok(None);
+        }
+        Ok(Some(space))
+    }
+
+    /// Fold `delta` into the space's current state and PUT the result. A PUT
+    /// of a whole state rather than an UPDATE, as with the inbox, so the same
+    /// call creates the space. Returns the state published.
+    pub async fn publish_space_delta(
+        &self,
+        owner: &VerifyingKey,
+        delta: SpaceDeltaV1,
+    ) -> Result<SpaceStateV1> {
+        let (key, container) = space_contract(owner)?;
+        let params = SpaceParametersV1 { owner: *owner };
+        let mut state = self.get_space(owner).await?.unwrap_or_default();
+        let parent = state.clone();
+        state
+            .apply_delta(&parent, &params, &Some(delta))
+            .map_err(|e| anyhow!("Failed to apply space delta: {}", e))?;
+        let mut state_bytes = Vec::new();
+        ciborium::ser::into_writer(&state, &mut state_bytes)
+            .map_err(|e| anyhow!("Failed to serialize space state: {}", e))?;
+        let put_request = ContractRequest::Put {
+            contract: container,
+            state: WrappedState::new(state_bytes),
+            related_contracts: Default::default(),
+            subscribe: false,
+            blocking_subscribe: false,
+        };
+        let mut web_api = self.web_api.lock().await;
+        web_api
+            .send(ClientRequest::ContractOp(put_request))
+            .await
+            .map_err(|e| anyhow!("Failed to send space PUT: {}", e))?;
+        let response = match tokio::time::timeout(Duration::from_secs(60), web_api.recv()).await {
+            Ok(result) => result.map_err(|e| anyhow!("Failed to receive response: {}", e))?,
+            Err(_) => return Err(anyhow!("Timeout waiting for space PUT response")),
+        };
+        match response {
+            HostResponse::ContractResponse(ContractResponse::PutResponse { key: got })
+                if got == key =>
+            {
+                Ok(state)
+            }
+            HostResponse::Ok => Ok(state),
+            other => Err(anyhow!("Unexpected response to space PUT: {:?}", other)),
+        }
+    }
+
+    /// PUT `state` into `owner`'s DM inbox. The contract merges it with
+    /// whatever the inbox already holds, so this both creates an inbox (an
+    /// empty `state` publishes one) and delivers into an existing one.
+    pub async fn put_inbox(&self, owner: &VerifyingKey, state: &InboxStateV1) -> Result<()> {
+        let (key, container) = inbox_contract(owner)?;
+        let mut state_bytes = Vec::new();
+        ciborium::ser::into_writer(state, &mut state_bytes)
+            .map_err(|e| anyhow!("Failed to serialize inbox state: {}", e))?;
+        let put_request = ContractRequest::Put {
+            contract: container,
+            state: WrappedState::new(state_bytes),
+            related_contracts: Default::default(),
+            subscribe: false,
+            blocking_subscribe: false,
+        };
+        let mut web_api = self.web_api.lock().await;
+        web_api
+            .send(ClientRequest::ContractOp(put_request))
+            .await
+            .map_err(|e| anyhow!("Failed to send inbox PUT: {}", e))?;
+        let response = match tokio::time::timeout(Duration::from_secs(60), web_api.recv()).await {
+            Ok(result) => result.map_err(|e| anyhow!("Failed to receive response: {}", e))?,
+            Err(_) => return Err(anyhow!("Timeout waiting for inbox PUT response")),
+        };
+        match response {
+            HostResponse::ContractResponse(ContractResponse::PutResponse { key: got })
+                if got == key =>
+            {
+                Ok(())
+            }
+            HostResponse::Ok => Ok(()),
+            other => Err(anyhow!("Unexpected response to inbox PUT: {:?}", other)),
+        }
+    }
+
     /// Edit a message you sent
     pub async fn edit_message(
         &self,
@@ -4442,6 +5105,7 @@ impl ApiClient {
 
         // Resolve any bare @nickname mentions in the reply body.
         let reply_text = resolve_outgoing_mentions(&room_state, &reply_text);
+        authorize_broadcasts(&room_state, author_member_id(&signing_key), &reply_text)?;
 
         // Build the reply body — plaintext (public) or AES-256-GCM sealed
         // (private). See `edit_message` for the storage / secret rationale. The
@@ -4611,9 +5275,16 @@ impl ApiClient {
             let mut room_state = self.get_room(room_owner_key, false).await?;
             // Decrypt private-room content for display (no-op for public rooms).
             let secrets = self.room_display_secrets(room_owner_key, &mut room_state);
+            self.hide_ignored(&mut room_state);
 
-            // Use display_messages() to filter out action/deleted messages (matches `message list`)
-            let all_msgs: Vec<_> = room_state.recent_messages.display_messages().collect();
+            // Filter out action/deleted/expired messages (matches `message list`)
+            let all_msgs: Vec<_> = room_state
+                .recent_messages
+                .unexpired_display_messages(
+                    &room_state.configuration.configuration,
+                    SystemTime::now(),
+                )
+                .collect();
             let start = all_msgs.len().saturating_sub(initial_messages);
 
             for msg in &all_msgs[start..] {
@@ -4672,6 +5343,7 @@ impl ApiClient {
                 Ok(mut room_state) => {
                     // Decrypt private-room content for display (no-op for public rooms).
                     let secrets = self.room_display_secrets(room_owner_key, &mut room_state);
+                    self.hide_ignored(&mut room_state);
                     Self::emit_new_and_edited(
                         &room_state,
                         &mut seen_messages,
@@ -4716,6 +5388,113 @@ impl ApiClient {
         }
     }
 
+    /// `message stream --format ndjson`: print one typed event per line for
+    /// every change between successive snapshots of the room (see
+    /// [`crate::room_events`]). With `subscribe`, a new snapshot is read on
+    /// each update notification; otherwise every `poll_interval_ms`.
+    /// `max_messages` counts `message` events only, as the other streams do.
+    pub async fn stream_events(
+        &self,
+        room_owner_key: &VerifyingKey,
+        subscribe: bool,
+        poll_interval_ms: u64,
+        timeout_secs: u64,
+        max_messages: usize,
+        initial_messages: usize,
+    ) -> Result<()> {
+        use std::io::Write;
+
+        let (signing_key, _, _) = self.storage.get_room(room_owner_key)?.ok_or_else(|| {
+            anyhow!("Room not found in local storage. You may need to create or join it first.")
+        })?;
+        let mut prev = self.get_room(room_owner_key, false).await?;
+        let secrets = self.room_display_secrets(room_owner_key, &mut prev);
+        self.hide_ignored(&mut prev);
+
+        let print = |event: &serde_json::Value| -> Result<()> {
+            let mut stdout = std::io::stdout().lock();
+            writeln!(stdout, "{}", serde_json::to_string(event)?)?;
+            stdout.flush()?;
+            Ok(())
+        };
+
+        // The starting window goes out as plain `message` events and does not
+        // count toward `max_messages`, matching the json stream.
+        let shown: Vec<_> = prev
+            .recent_messages
+            .unexpired_display_messages(&prev.configuration.configuration, SystemTime::now())
+            .collect();
+        for msg in &shown[shown.len().saturating_sub(initial_messages)..] {
+            print(&message_event_json(
+                &prev,
+                msg,
+                room_owner_key,
+                false,
+                &secrets,
+            ))?;
+        }
+
+        let instance_id = if subscribe {
+            Some(self.subscribe_room(room_owner_key).await?)
+        } else {
+            None
+        };
+
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            tokio::signal::ctrl_c().await.ok();
+            let _ = shutdown_tx.send(()).await;
+        });
+
+        let start_time = std::time::Instant::now();
+        let mut new_message_count = 0;
+        loop {
+            if shutdown_rx.try_recv().is_ok() {
+                return Ok(());
+            }
+            if timeout_secs > 0 && start_time.elapsed().as_secs() >= timeout_secs {
+                debug!("Timeout reached, exiting event stream");
+                return Ok(());
+            }
+
+            match instance_id {
+                // Short waits so Ctrl+C and the timeout are noticed promptly.
+                Some(id) => match self.next_update(Duration::from_millis(500)).await? {
+                    Some(updated) if updated == id => {}
+                    _ => continue,
+                },
+                None => tokio::time::sleep(Duration::from_millis(poll_interval_ms)).await,
+            }
+
+            let mut next = match self.get_room(room_owner_key, false).await {
+                Ok(state) => state,
+                Err(e) => {
+                    debug!("Error fetching room state: {}", e);
+                    continue;
+                }
+            };
+            // Re-derived each time: a secret rotation mid-stream must not
+            // turn new private messages into `<encrypted>`.
+            let secrets = self.room_display_secrets(room_owner_key, &mut next);
+            self.hide_ignored(&mut next);
+            let ctx = crate::room_events::EventContext {
+                room_owner_key,
+                secrets: &secrets,
+                signing_key: Some(&signing_key),
+            };
+            for event in crate::room_events::diff_events(&prev, &next, &ctx) {
+                print(&event)?;
+                if event["type"] == "message" {
+                    new_message_count += 1;
+                    if max_messages > 0 && new_message_count >= max_messages {
+                        return Ok(());
+                    }
+                }
+            }
+            prev = next;
+        }
+    }
+
     /// Scan the room's display messages and emit any that are NEW or whose
     /// effective content changed (an EDIT) since last seen. `seen` maps each
     /// message's dedup key to the content last emitted for it, so a later edit
@@ -4743,7 +5522,11 @@ impl ApiClient {
         new_count: &mut usize,
         secrets: &HashMap<u32, [u8; 32]>,
     ) -> Result<()> {
-        for msg in room_state.recent_messages.display_messages() {
+        let now = SystemTime::now();
+        for msg in room_state
+            .recent_messages
+            .unexpired_display_messages(&room_state.configuration.configuration, now)
+        {
             let key = monitor_seen_key(msg);
             let content = message_display_text_with_secrets(room_state, msg, secrets);
             let is_edit = match classify_seen(seen, &key, &content) {
@@ -4910,37 +5693,7 @@ impl ApiClient {
                 );
             }
             OutputFormat::Json => {
-                let reactions_map: std::collections::HashMap<String, usize> = reactions
-                    .map(|r| r.iter().map(|(k, v)| (k.clone(), v.len())).collect())
-                    .unwrap_or_default();
-                // WHO reacted, not just how many. `reactions` reports counts and
-                // is kept unchanged for existing consumers, but a count cannot
-                // attribute a reaction to anyone, which made reactions
-                // unmoderatable: nothing downstream could tell who to act on.
-                // The room state has always held this (`reactions()` returns
-                // `HashMap<String, Vec<MemberId>>`); only this boundary dropped it.
-                let reactors_map: std::collections::HashMap<String, Vec<String>> = reactions
-                    .map(|r| {
-                        r.iter()
-                            .map(|(emoji, reactors)| {
-                                (
-                                    emoji.clone(),
-                                    reactors.iter().map(|id| id.to_string()).collect(),
-                                )
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                let json_msg = json!({
-                    "type": "reaction",
-                    "reactors": reactors_map,
-                    "message_id": msg_id.0 .0.to_string(),
-                    "room": bs58::encode(room_owner_key.as_bytes()).into_string(),
-                    "author": author_str,
-                    "nickname": nickname,
-                    "timestamp": datetime.to_rfc3339(),
-                    "reactions": reactions_map,
-                });
+                let json_msg = reaction_event_json(room_state, msg, room_owner_key, secrets);
                 println!("{}", serde_json::to_string(&json_msg)?);
             }
         }
@@ -4958,7 +5711,6 @@ impl ApiClient {
         format: &OutputFormat,
         secrets: &HashMap<u32, [u8; 32]>,
     ) -> Result<()> {
-        let msg_id = msg.id();
         let author_str = msg.message.author.to_string();
         let nickname = room_state
             .member_info
@@ -4979,14 +5731,7 @@ impl ApiClient {
                 );
             }
             OutputFormat::Json => {
-                let json_msg = json!({
-                    "type": "delete",
-                    "message_id": msg_id.0 .0.to_string(),
-                    "room": bs58::encode(room_owner_key.as_bytes()).into_string(),
-                    "author": author_str,
-                    "nickname": nickname,
-                    "timestamp": datetime.to_rfc3339(),
-                });
+                let json_msg = deletion_event_json(room_state, msg, room_owner_key, secrets);
                 println!("{}", serde_json::to_string(&json_msg)?);
             }
         }
@@ -5073,42 +5818,8 @@ impl ApiClient {
                 );
             }
             OutputFormat::Json => {
-                let author_str = msg.message.author.to_string();
-
-                let nickname = room_state
-                    .member_info
-                    .canonical(msg.message.author)
-                    .map(|info| {
-                        unseal_nickname_display(&info.member_info.preferred_nickname, secrets)
-                    });
-
-                let datetime: DateTime<Utc> = msg.message.time.into();
-
-                let reactions_map: std::collections::HashMap<String, usize> = reactions
-                    .map(|r| r.iter().map(|(k, v)| (k.clone(), v.len())).collect())
-                    .unwrap_or_default();
-
-                let message_id_str = msg_id.0 .0.to_string();
-
-                // Reply context (null for non-replies) so a relay can thread the
-                // message; previously absent from the monitor's JSON output.
-                let reply_to = reply_to_json(&reply);
-
-                // Output as JSONL (one JSON object per line). `type` is "edit"
-                // for a re-emitted message whose content changed, else "message".
-                let json_msg = json!({
-                    "type": if is_edit { "edit" } else { "message" },
-                    "message_id": message_id_str,
-                    "room": bs58::encode(room_owner_key.as_bytes()).into_string(),
-                    "author": author_str,
-                    "nickname": nickname,
-                    "content": content,
-                    "timestamp": datetime.to_rfc3339(),
-                    "edited": edited,
-                    "reply_to": reply_to,
-                    "reactions": reactions_map,
-                });
-
+                let json_msg =
+                    message_event_json(room_state, msg, room_owner_key, is_edit, secrets);
                 println!("{}", serde_json::to_string(&json_msg)?);
             }
         }
@@ -5163,9 +5874,9 @@ impl ApiClient {
         .map_err(|e| anyhow!(e))?;
 
         // Find our current member info to get the version AND our existing
-        // deputy grants — republishing member_info replaces the whole signed
-        // record, so we must carry `deputies` forward or a nickname change would
-        // silently revoke every deputy we appointed (#410). Routes through the
+        // deputy grants and profile — republishing member_info replaces the
+        // whole signed record, so we must carry `deputies` forward or a nickname
+        // change would silently revoke every deputy we appointed (#410). Routes through the
         // shared `resolve_own_member_info_base` (canonical, #411 round 8 item A)
         // so a duplicate-holding state can't resurrect a revoked record.
         let current_self_info = resolve_own_member_info_base(&room_state, my_member_id);
@@ -5173,9 +5884,11 @@ impl ApiClient {
             .as_ref()
             .map(|info| info.version)
             .unwrap_or(0);
-        let existing_deputies = current_self_info
-            .map(|info| info.deputies)
+        let (existing_deputies, existing_profile) = current_self_info
+            .map(|info| (info.deputies, info.profile))
             .unwrap_or_default();
+        let existing_profile =
+            existing_profile.and_then(|p| p.fitted_to(&room_state.configuration.configuration));
 
         // Create new member info with incremented version
         let new_member_info = MemberInfo {
@@ -5183,6 +5896,7 @@ impl ApiClient {
             version: current_version + 1,
             preferred_nickname: sealed_nickname,
             deputies: existing_deputies,
+            profile: existing_profile,
         };
 
         // Sign with our member key
@@ -5270,6 +5984,63 @@ impl ApiClient {
         }
     }
 
+    /// Edit the current user's profile (avatar, status line, bio) in a room.
+    ///
+    /// Republishes our own `member_info` at version + 1 with the nickname and
+    /// deputies carried forward, like [`Self::deputize`].
+    pub async fn set_profile(
+        &self,
+        room_owner_key: &VerifyingKey,
+        update: ProfileUpdate,
+    ) -> Result<()> {
+        let room_data = self.storage.get_room(room_owner_key)?.ok_or_else(|| {
+            anyhow!("Room not found. You must be a member of the room to change your profile.")
+        })?;
+        let (signing_key, _, _contract_key_str) = room_data;
+        let room_state = self.get_room(room_owner_key, false).await?;
+        let my_member_id: MemberId = signing_key.verifying_key().into();
+
+        let current_self_info = resolve_own_member_info_base(&room_state, my_member_id)
+            .ok_or_else(|| {
+                anyhow!(
+                    "You don't have a member_info entry in this room yet. \
+                     Set your nickname first (`member set-nickname`), then retry."
+                )
+            })?;
+
+        let invitation_secrets = self.storage.get_invitation_secrets(room_owner_key)?;
+        let secrets = crate::private_room::collect_secrets_for_room(
+            &room_state,
+            &signing_key,
+            &invitation_secrets,
+        );
+        let profile = apply_profile_update(
+            current_self_info.profile.clone(),
+            &update,
+            &room_state.configuration.configuration,
+            |plaintext| {
+                crate::private_room::seal_field_for_room(&room_state, &secrets, plaintext)
+                    .map_err(|e| anyhow!(e))
+            },
+        )?;
+
+        let new_member_info = MemberInfo {
+            member_id: my_member_id,
+            version: current_self_info.version + 1,
+            preferred_nickname: current_self_info.preferred_nickname,
+            deputies: current_self_info.deputies,
+            profile,
+        };
+        let authorized_member_info =
+            AuthorizedMemberInfo::new_with_member_key(new_member_info, &signing_key);
+
+        let delta = ChatRoomStateV1Delta {
+            member_info: Some(vec![authorized_member_info]),
+            ..Default::default()
+        };
+        self.send_delta(room_owner_key, delta).await
+    }
+
     /// Ban a member from the room
     ///
     /// The banning member must be either the room owner or an upstream member in the
@@ -5530,6 +6301,10 @@ impl ApiClient {
         let current_version = current_self_info.version;
         let preferred_nickname = current_self_info.preferred_nickname.clone();
         let mut deputies = current_self_info.deputies.clone();
+        let profile = current_self_info
+            .profile
+            .as_ref()
+            .and_then(|p| p.fitted_to(&room_state.configuration.configuration));
 
         // Resolve the target's full MemberId from the short id. Primary: the
         // member_info list (a present member), same lookup as `ban_member`.
@@ -5585,6 +6360,7 @@ impl ApiClient {
             version: current_version + 1,
             preferred_nickname,
             deputies,
+            profile,
         };
         let authorized_member_info =
             AuthorizedMemberInfo::new_with_member_key(new_member_info, &signing_key);
@@ -5733,10 +6509,17 @@ impl ApiClient {
             // Decrypt private-room content for display (no-op for public rooms).
             // Must run before the immutable `display_msgs` borrow below.
             let secrets = self.room_display_secrets(room_owner_key, &mut room_state);
+            self.hide_ignored(&mut room_state);
 
             // Determine which messages will be displayed initially (the last N
             // non-deleted). Only these count as "shown" for deletion purposes.
-            let display_msgs: Vec<_> = room_state.recent_messages.display_messages().collect();
+            let display_msgs: Vec<_> = room_state
+                .recent_messages
+                .unexpired_display_messages(
+                    &room_state.configuration.configuration,
+                    SystemTime::now(),
+                )
+                .collect();
             let display_start = if initial_messages > 0 {
                 display_msgs.len().saturating_sub(initial_messages)
             } else {
@@ -5933,6 +6716,7 @@ impl ApiClient {
                             // Decrypt private-room content for display (no-op for public rooms).
                             let secrets =
                                 self.room_display_secrets(room_owner_key, &mut room_state);
+                            self.hide_ignored(&mut room_state);
                             Self::emit_new_and_edited(
                                 &room_state,
                                 &mut seen_messages,
@@ -6191,6 +6975,78 @@ mod resolve_own_member_info_base_tests {
 /// `ui::components::members::Invitation` — the UI's tests
 /// (`members::tests::invitation_cbor_*`) pin the same shape on that side; keep
 /// the two suites in step.
+#[cfg(test)]
+mod profile_update_tests {
+    use super::{apply_profile_update, ProfileUpdate};
+    use anyhow::Result;
+    use river_core::room_state::configuration::Configuration;
+    use river_core::room_state::member_info::{Avatar, MemberProfile};
+    use river_core::room_state::privacy::SealedBytes;
+
+    fn public(plaintext: &[u8]) -> Result<SealedBytes> {
+        Ok(SealedBytes::public(plaintext.to_vec()))
+    }
+
+    #[test]
+    fn untouched_fields_survive_and_clearing_everything_drops_the_profile() {
+        let config = Configuration::default();
+        let current = MemberProfile {
+            status: Some(SealedBytes::public(b"away".to_vec())),
+            bio: Some(SealedBytes::public(b"hello".to_vec())),
+            ..Default::default()
+        };
+
+        let update = ProfileUpdate {
+            avatar: Some(Some(Avatar::Identicon(42))),
+            status: Some(None),
+            ..Default::default()
+        };
+        let profile = apply_profile_update(Some(current), &update, &config, public)
+            .unwrap()
+            .unwrap();
+        assert_eq!(profile.status, None);
+        assert_eq!(profile.bio, Some(SealedBytes::public(b"hello".to_vec())));
+        assert_eq!(
+            profile.avatar,
+            Some(SealedBytes::public(Avatar::Identicon(42).encode()))
+        );
+
+        let clear_rest = ProfileUpdate {
+            avatar: Some(None),
+            bio: Some(None),
+            ..Default::default()
+        };
+        assert_eq!(
+            apply_profile_update(Some(profile), &clear_rest, &config, public).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn over_limit_values_are_refused_before_signing() {
+        let config = Configuration {
+            max_status_size: Some(4),
+            max_avatar_size: Some(16),
+            ..Default::default()
+        };
+        let status = ProfileUpdate {
+            status: Some(Some("too long".to_string())),
+            ..Default::default()
+        };
+        let err = apply_profile_update(None, &status, &config, public).unwrap_err();
+        assert!(err.to_string().contains("at most 4"), "{err}");
+
+        let avatar = ProfileUpdate {
+            avatar: Some(Some(Avatar::Image {
+                media_type: "image/png".to_string(),
+                data: vec![0; 64],
+            })),
+            ..Default::default()
+        };
+        assert!(apply_profile_update(None, &avatar, &config, public).is_err());
+    }
+}
+
 #[cfg(test)]
 mod invitation_tests {
     use super::*;
@@ -8195,6 +9051,7 @@ mod display_text_tests {
                     version: 0,
                     preferred_nickname: SealedBytes::public(b"Alice".to_vec()),
                     deputies: Vec::new(),
+                    profile: None,
                 },
                 &alice_sk,
             ));
@@ -9219,6 +10076,7 @@ mod mention_cli_tests {
                 version: i as u32,
                 preferred_nickname: nickname.clone(),
                 deputies: Vec::new(),
+                profile: None,
             };
             state
                 .member_info
@@ -9363,6 +10221,23 @@ mod mention_cli_tests {
         assert_eq!(resolve_outgoing_mentions(&state, &token), token);
     }
 
+    #[test]
+    fn typed_room_mention_is_refused_unless_the_policy_allows_the_sender() {
+        let alice = SigningKey::from_bytes(&[1u8; 32]);
+        let mut state =
+            state_with_members(&[(alice.clone(), SealedBytes::public(b"alice".to_vec()))]);
+        let text = resolve_outgoing_mentions(&state, "@room standup in 5");
+        assert!(authorize_broadcasts(&state, member_id(&alice), "no broadcast here").is_ok());
+        let err = authorize_broadcasts(&state, member_id(&alice), &text).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "@room is restricted to the room owner and their deputies in this room"
+        );
+
+        state.configuration.configuration.owner_member_id = member_id(&alice);
+        assert!(authorize_broadcasts(&state, member_id(&alice), &text).is_ok());
+    }
+
     // --- render_mentions_for_terminal (display path) ---
 
     #[test]
@@ -0,0 +1,598 @@
+//! State and key handling for `riverctl chat`.
+//!
+//! Everything here is terminal-free so it can be tested: the command in
+//! `commands/chat.rs` owns the terminal, the node connection and the drawing,
+//! feeds key events into [`ChatModel::handle_key`] and carries out the
+//! [`ChatAction`] it gets back.
+//!
+//! The screen is a list of panes — each room, followed by the DM threads the
+//! local member has in it — and one scrollback plus input line for the
+//! selected pane. Typing goes to the input line. `Esc` (or `Up` on an empty
+//! line) enters select mode, where a message can be replied to, edited or
+//! reacted to.
+//!
+//! Mentions are completed with `Tab`. The completed `@name` stays readable in
+//! the input line and is turned into a `mention::encode_mention` token only
+//! when the message is sent, so the token always names the member that was
+//! picked even if two members later share a nickname.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ed25519_dalek::VerifyingKey;
+use river_core::mention::encode_mention;
+use river_core::room_state::group_direct_messages::GroupThreadId;
+use river_core::room_state::member::MemberId;
+use river_core::room_state::message::MessageId;
+
+/// How far `PageUp` / `PageDown` move the scrollback.
+const PAGE_LINES: usize = 10;
+
+/// What a pane shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneKey {
+    Room(VerifyingKey),
+    Dm {
+        room: VerifyingKey,
+        peer: MemberId,
+    },
+    GroupDm {
+        room: VerifyingKey,
+        thread: GroupThreadId,
+    },
+}
+
+impl PaneKey {
+    pub fn room(&self) -> VerifyingKey {
+        match self {
+            PaneKey::Room(room) | PaneKey::Dm { room, .. } | PaneKey::GroupDm { room, .. } => *room,
+        }
+    }
+}
+
+/// One message in a pane's scrollback, already rendered to text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatLine {
+    /// `None` for DMs, which cannot be replied to, edited or reacted to.
+    pub id: Option<MessageId>,
+    /// Local time, `HH:MM`.
+    pub time: String,
+    pub author: String,
+    /// Written by the local member, so it may be edited.
+    pub own: bool,
+    /// `[reply to …] ` or empty.
+    pub reply_prefix: String,
+    /// The message text with mentions rendered — also what an edit starts from.
+    pub body: String,
+    /// ` (edited)`, reactions, or empty.
+    pub suffix: String,
+}
+
+impl ChatLine {
+    pub fn text(&self) -> String {
+        format!("{}{}{}", self.reply_prefix, self.body, self.suffix)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pane {
+    pub key: PaneKey,
+    pub title: String,
+    /// Messages from others after the read marker. Rooms only; DMs have no
+    /// read marker.
+    pub unread: usize,
+    pub lines: Vec<ChatLine>,
+    /// `(member, display name)` candidates for mention completion.
+    pub members: Vec<(MemberId, String)>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Input,
+    /// Picking a message; holds the one currently highlighted.
+    Select(MessageId),
+}
+
+/// What the input line will do on `Enter`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Compose {
+    #[default]
+    Message,
+    Reply(MessageId),
+    Edit(MessageId),
+    React(MessageId),
+}
+
+/// Something the model wants done outside itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatAction {
+    None,
+    Quit,
+    Send(Box<Outgoing>),
+}
+
+/// A message ready to go out, mentions already encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outgoing {
+    pub pane: PaneKey,
+    pub compose: Compose,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ChatModel {
+    pub panes: Vec<Pane>,
+    pub current: usize,
+    pub mode: Mode,
+    pub compose: Compose,
+    pub input: String,
+    /// Lines scrolled up from the bottom of the current pane.
+    pub scroll: usize,
+    pub status: String,
+    /// Mentions completed into `input`: `(name as typed, member)`.
+    completed: Vec<(String, MemberId)>,
+}
+
+impl ChatModel {
+    pub fn new(panes: Vec<Pane>, current: usize) -> Self {
+        ChatModel {
+            current: current.min(panes.len().saturating_sub(1)),
+            panes,
+            ..Default::default()
+        }
+    }
+
+    pub fn current_pane(&self) -> Option<&Pane> {
+        self.panes.get(self.current)
+    }
+
+    /// Replace one room's panes (the room and its DM threads) after a
+    /// refresh, keeping the selection on the same pane where it still exists.
+    pub fn replace_room(&mut self, room: VerifyingKey, fresh: Vec<Pane>) {
+        let selected = self.current_pane().map(|p| p.key);
+        let at = self
+            .panes
+            .iter()
+            .position(|p| p.key.room() == room)
+            .unwrap_or(self.panes.len());
+        self.panes.retain(|p| p.key.room() != room);
+        let at = at.min(self.panes.len());
+        self.panes.splice(at..at, fresh);
+        if let Some(key) = selected {
+            self.current = self
+                .panes
+                .iter()
+                .position(|p| p.key == key)
+                .or_else(|| self.panes.iter().position(|p| p.key.room() == key.room()))
+                .unwrap_or(0);
+        }
+        if let Mode::Select(id) = &self.mode {
+            if self.line_index(id).is_none() {
+                self.mode = Mode::Input;
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> ChatAction {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => return ChatAction::Quit,
+            KeyCode::Char('n') if ctrl => {
+                self.switch_pane(1);
+                return ChatAction::None;
+            }
+            KeyCode::Char('p') if ctrl => {
+                self.switch_pane(-1);
+                return ChatAction::None;
+            }
+            KeyCode::PageUp => {
+                self.scroll = self.scroll.saturating_add(PAGE_LINES);
+                return ChatAction::None;
+            }
+            KeyCode::PageDown => {
+                self.scroll = self.scroll.saturating_sub(PAGE_LINES);
+                return ChatAction::None;
+            }
+            _ => {}
+        }
+        match self.mode.clone() {
+            Mode::Input => self.handle_input_key(key, ctrl),
+            Mode::Select(id) => self.handle_select_key(key, id),
+        }
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent, ctrl: bool) -> ChatAction {
+        match key.code {
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Tab => self.complete_mention(),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Esc if self.compose != Compose::Message => self.cancel_compose(),
+            KeyCode::Esc => self.enter_select(),
+            KeyCode::Up if self.input.is_empty() && self.compose == Compose::Message => {
+                self.enter_select()
+            }
+            KeyCode::Char(c) if !ctrl => self.input.push(c),
+            _ => {}
+        }
+        ChatAction::None
+    }
+
+    fn handle_select_key(&mut self, key: KeyEvent, id: MessageId) -> ChatAction {
+        match key.code {
+            KeyCode::Char('q') => return ChatAction::Quit,
+            KeyCode::Esc | KeyCode::Char('i') => self.mode = Mode::Input,
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(&id, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(&id, 1),
+            KeyCode::Char('r') => {
+                let who = self.line(&id).map(|l| l.author.clone()).unwrap_or_default();
+                self.start_compose(Compose::Reply(id), format!("Replying to {who}"));
+            }
+            KeyCode::Char('e') => match self.line(&id) {
+                Some(line) if line.own => {
+                    let body = line.body.clone();
+                    self.start_compose(Compose::Edit(id), "Editing".to_string());
+                    self.input = body;
+                }
+                _ => self.status = "Only your own messages can be edited".to_string(),
+            },
+            KeyCode::Char('+') => {
+                self.start_compose(Compose::React(id), "React with which emoji?".to_string())
+            }
+            _ => {}
+        }
+        ChatAction::None
+    }
+
+    fn switch_pane(&mut self, step: isize) {
+        if self.panes.is_empty() {
+            return;
+        }
+        let len = self.panes.len() as isize;
+        self.current = (self.current as isize + step).rem_euclid(len) as usize;
+        self.scroll = 0;
+        self.mode = Mode::Input;
+        self.cancel_compose();
+    }
+
+    fn enter_select(&mut self) {
+        let last = self
+            .current_pane()
+            .and_then(|p| p.lines.iter().rev().find_map(|l| l.id.clone()));
+        match last {
+            Some(id) => {
+                self.mode = Mode::Select(id);
+                self.status =
+                    "↑/↓ select · r reply · e edit · + react · Esc back · q quit".to_string();
+            }
+            None => self.status = "Nothing to select here".to_string(),
+        }
+    }
+
+    fn move_selection(&mut self, id: &MessageId, step: isize) {
+        let Some(pane) = self.current_pane() else {
+            return;
+        };
+        let Some(at) = self.line_index(id) else {
+            self.mode = Mode::Input;
+            return;
+        };
+        let ids: Vec<(usize, MessageId)> = pane
+            .lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| l.id.clone().map(|id| (i, id)))
+            .collect();
+        let pos = ids.iter().position(|(i, _)| *i == at).unwrap_or(0) as isize;
+        let next = (pos + step).clamp(0, ids.len() as isize - 1) as usize;
+        self.mode = Mode::Select(ids[next].1.clone());
+    }
+
+    fn start_compose(&mut self, compose: Compose, status: String) {
+        self.compose = compose;
+        self.mode = Mode::Input;
+        self.input.clear();
+        self.completed.clear();
+        self.status = format!("{status} — Enter to send, Esc to cancel");
+    }
+
+    fn cancel_compose(&mut self) {
+        if std::mem::take(&mut self.compose) != Compose::Message {
+            self.input.clear();
+            self.completed.clear();
+            self.status.clear();
+        }
+    }
+
+    fn submit(&mut self) -> ChatAction {
+        let Some(pane) = self.current_pane().map(|p| p.key) else {
+            return ChatAction::None;
+        };
+        let typed = self.input.trim();
+        if typed.is_empty() {
+            return ChatAction::None;
+        }
+        let text = expand_mentions(typed, &self.completed);
+        let compose = std::mem::take(&mut self.compose);
+        self.input.clear();
+        self.completed.clear();
+        self.scroll = 0;
+        ChatAction::Send(Box::new(Outgoing {
+            pane,
+            compose,
+            text,
+        }))
+    }
+
+    /// Complete the `@word` the input ends with against the current pane's
+    /// members: a unique match is filled in, several extend the word to
+    /// their common prefix and are listed in the status line.
+    fn complete_mention(&mut self) {
+        let Some(pane) = self.current_pane() else {
+            return;
+        };
+        match complete_mention(&self.input, &pane.members) {
+            Completion::None => {}
+            Completion::Unique { input, name, id } => {
+                self.input = input;
+                self.completed.push((name, id));
+                self.status.clear();
+            }
+            Completion::Ambiguous { input, names } => {
+                self.input = input;
+                self.status = names.join("  ");
+            }
+        }
+    }
+
+    fn line_index(&self, id: &MessageId) -> Option<usize> {
+        self.current_pane()?
+            .lines
+            .iter()
+            .position(|l| l.id.as_ref() == Some(id))
+    }
+
+    fn line(&self, id: &MessageId) -> Option<&ChatLine> {
+        self.current_pane()?
+            .lines
+            .iter()
+            .find(|l| l.id.as_ref() == Some(id))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Completion {
+    None,
+    Unique {
+        input: String,
+        name: String,
+        id: MemberId,
+    },
+    Ambiguous {
+        input: String,
+        names: Vec<String>,
+    },
+}
+
+/// Complete a trailing `@prefix` in `input`. Matching is case-insensitive on
+/// the start of the display name.
+pub fn complete_mention(input: &str, members: &[(MemberId, String)]) -> Completion {
+    let Some(at) = input.rfind('@') else {
+        return Completion::None;
+    };
+    let prefix = &input[at + 1..];
+    if prefix.contains(char::is_whitespace)
+        || input[..at]
+            .chars()
+            .next_back()
+            .is_some_and(|c| !c.is_whitespace())
+    {
+        return Completion::None;
+    }
+    let needle = prefix.to_lowercase();
+    let mut matches: Vec<&(MemberId, String)> = members
+        .iter()
+        .filter(|(_, name)| !name.is_empty() && name.to_lowercase().starts_with(&needle))
+        .collect();
+    matches.sort_by(|a, b| a.1.cmp(&b.1));
+    matches.dedup_by(|a, b| a.0 == b.0);
+    match matches.as_slice() {
+        [] => Completion::None,
+        [(id, name)] => Completion::Unique {
+            input: format!("{}@{} ", &input[..at], name),
+            name: name.clone(),
+            id: *id,
+        },
+        many => {
+            let names: Vec<String> = many.iter().map(|(_, n)| n.clone()).collect();
+            let common = common_prefix(&names);
+            let input = if common.chars().count() > prefix.chars().count() {
+                format!("{}@{}", &input[..at], common)
+            } else {
+                input.to_string()
+            };
+            Completion::Ambiguous { input, names }
+        }
+    }
+}
+
+fn common_prefix(names: &[String]) -> String {
+    let mut iters: Vec<_> = names.iter().map(|n| n.chars()).collect();
+    let mut out = String::new();
+    loop {
+        let mut next = None;
+        for it in iters.iter_mut() {
+            match (it.next(), next) {
+                (None, _) => return out,
+                (Some(c), None) => next = Some(c),
+                (Some(c), Some(n)) if c == n => {}
+                _ => return out,
+            }
+        }
+        match next {
+            Some(c) => out.push(c),
+            None => return out,
+        }
+    }
+}
+
+/// Replace each completed `@name` in `text` with its mention token. Names
+/// the user deleted from the line are simply not found.
+pub fn expand_mentions(text: &str, completed: &[(String, MemberId)]) -> String {
+    let mut out = text.to_string();
+    // Longest first, so `@Al` never eats the front of `@Alice`.
+    let mut completed: Vec<&(String, MemberId)> = completed.iter().collect();
+    completed.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+    for (name, id) in completed {
+        let typed = format!("@{name}");
+        if let Some(at) = out.find(&typed) {
+            out.replace_range(at..at + typed.len(), &encode_mention(*id, name));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use freenet_scaffold::util::FastHash;
+
+    fn member(seed: u8) -> MemberId {
+        MemberId::from(&SigningKey::from_bytes(&[seed; 32]).verifying_key())
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn line(id: i64, own: bool, body: &str) -> ChatLine {
+        ChatLine {
+            id: Some(MessageId(FastHash(id))),
+            time: "12:00".into(),
+            author: if own { "me" } else { "Bob" }.into(),
+            own,
+            reply_prefix: String::new(),
+            body: body.into(),
+            suffix: String::new(),
+        }
+    }
+
+    fn model() -> ChatModel {
+        let room = SigningKey::from_bytes(&[1; 32]).verifying_key();
+        ChatModel::new(
+            vec![
+                Pane {
+                    key: PaneKey::Room(room),
+                    title: "lobby".into(),
+                    unread: 0,
+                    lines: vec![line(1, false, "hi"), line(2, true, "hello")],
+                    members: vec![(member(2), "Alice".into()), (member(3), "Alan".into())],
+                },
+                Pane {
+                    key: PaneKey::Dm {
+                        room,
+                        peer: member(2),
+                    },
+                    title: "Alice".into(),
+                    unread: 0,
+                    lines: vec![],
+                    members: vec![],
+                },
+            ],
+            0,
+        )
+    }
+
+    fn type_str(m: &mut ChatModel, s: &str) {
+        for c in s.chars() {
+            m.handle_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn tab_completes_mentions_and_send_encodes_them() {
+        let mut m = model();
+        type_str(&mut m, "hey @a");
+        m.handle_key(key(KeyCode::Tab));
+        // Ambiguous: extended to the shared prefix, candidates listed.
+        assert_eq!(m.input, "hey @Al");
+        assert!(m.status.contains("Alan") && m.status.contains("Alice"));
+
+        type_str(&mut m, "i");
+        m.handle_key(key(KeyCode::Tab));
+        assert_eq!(m.input, "hey @Alice ");
+        type_str(&mut m, "there");
+
+        let ChatAction::Send(out) = m.handle_key(key(KeyCode::Enter)) else {
+            panic!("Enter should send");
+        };
+        assert_eq!(out.compose, Compose::Message);
+        assert_eq!(
+            out.text,
+            format!("hey {} there", encode_mention(member(2), "Alice"))
+        );
+        assert!(m.input.is_empty());
+    }
+
+    #[test]
+    fn completion_only_applies_to_a_trailing_word() {
+        let members = vec![(member(2), "Alice".to_string())];
+        assert_eq!(complete_mention("mail@al", &members), Completion::None);
+        assert_eq!(complete_mention("@al bob", &members), Completion::None);
+        assert_eq!(complete_mention("@zed", &members), Completion::None);
+    }
+
+    #[test]
+    fn select_mode_starts_reply_edit_and_react() {
+        let mut m = model();
+        m.handle_key(key(KeyCode::Up));
+        assert_eq!(m.mode, Mode::Select(MessageId(FastHash(2))));
+
+        // Own message: edit pre-fills the body.
+        m.handle_key(key(KeyCode::Char('e')));
+        assert_eq!(m.compose, Compose::Edit(MessageId(FastHash(2))));
+        assert_eq!(m.input, "hello");
+        m.handle_key(key(KeyCode::Esc));
+        assert_eq!(m.compose, Compose::Message);
+        assert!(m.input.is_empty());
+
+        // Someone else's message cannot be edited, but can be replied to.
+        m.handle_key(key(KeyCode::Esc));
+        m.handle_key(key(KeyCode::Char('k')));
+        m.handle_key(key(KeyCode::Char('e')));
+        assert_eq!(m.mode, Mode::Select(MessageId(FastHash(1))));
+        m.handle_key(key(KeyCode::Char('r')));
+        type_str(&mut m, "yes");
+        let ChatAction::Send(out) = m.handle_key(key(KeyCode::Enter)) else {
+            panic!("Enter should send");
+        };
+        assert_eq!(out.compose, Compose::Reply(MessageId(FastHash(1))));
+        assert_eq!(out.text, "yes");
+
+        m.handle_key(key(KeyCode::Esc));
+        m.handle_key(key(KeyCode::Char('+')));
+        type_str(&mut m, "👍");
+        let ChatAction::Send(out) = m.handle_key(key(KeyCode::Enter)) else {
+            panic!("Enter should send");
+        };
+        assert_eq!(out.compose, Compose::React(MessageId(FastHash(2))));
+    }
+
+    #[test]
+    fn refresh_keeps_the_selected_pane() {
+        let mut m = model();
+        m.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL));
+        assert_eq!(m.current, 1);
+        let room = m.panes[0].key.room();
+        let mut fresh = m.panes.clone();
+        fresh.swap(0, 1);
+        m.replace_room(room, fresh);
+        assert_eq!(m.current, 0);
+        assert!(matches!(m.panes[0].key, PaneKey::Dm { .. }));
+        assert_eq!(
+            m.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            ChatAction::Quit
+        );
+    }
+}
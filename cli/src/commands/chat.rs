@@ -0,0 +1,605 @@
+//! `riverctl chat`: a full-screen terminal client.
+//!
+//! The model and key bindings live in [`crate::chat`]; this file owns the
+//! terminal, talks to the node and draws. Every room in local storage is
+//! fetched once at start and subscribed to, and a room is re-read whenever
+//! its contract sends an update notification. DMs delivered to an inbox
+//! contract do not notify the room, so the selected room is also re-read
+//! every [`FALLBACK_REFRESH`].
+//!
+//! Reading a room's newest message in the scrollback advances the shared
+//! read marker, exactly like `room mark-read`.
+
+use crate::api::{
+    message_display_text_with_secrets, reply_context_display_with_secrets, reply_prefix_display,
+    ApiClient,
+};
+use crate::chat::{ChatAction, ChatLine, ChatModel, Compose, Mode, Outgoing, Pane, PaneKey};
+use crate::commands::dm;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Local, Utc};
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ed25519_dalek::VerifyingKey;
+use freenet_stdlib::prelude::ContractInstanceId;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use river_core::room_state::group_direct_messages::GroupThreadId;
+use river_core::room_state::member::MemberId;
+use river_core::room_state::message::MessageOrderKey;
+use river_core::room_state::ChatRoomStateV1;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Stdout;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How long one wait for a node notification lasts before the loop checks
+/// the refresh timer again. Keys interrupt the wait, so this is not latency.
+const UPDATE_POLL: Duration = Duration::from_secs(1);
+
+/// How often the selected room is re-read without a notification.
+const FALLBACK_REFRESH: Duration = Duration::from_secs(30);
+
+const HELP: &str = "Enter send · Tab complete @mention · Esc select message · \
+                    Ctrl-N/Ctrl-P switch pane · PgUp/PgDn scroll · Ctrl-C quit";
+
+/// One room's freshly built panes plus what marking it read needs.
+struct RoomPanes {
+    panes: Vec<Pane>,
+    latest: Option<MessageOrderKey>,
+}
+
+pub async fn execute(api: ApiClient, room: Option<String>) -> Result<()> {
+    if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stdout) {
+        bail!("`riverctl chat` needs an interactive terminal");
+    }
+    let rooms = api.list_rooms().await?;
+    if rooms.is_empty() {
+        bail!("No rooms yet. Create one with `riverctl room create` or accept an invitation.");
+    }
+    // No room named: start in the configured default room, if there is one.
+    let room = match room {
+        Some(room) => Some(crate::config::resolve_room_arg(&room)?),
+        None => crate::config::resolve_room_arg(crate::config::DEFAULT_ROOM_ARG)
+            .ok()
+            .filter(|room| room != crate::config::DEFAULT_ROOM_ARG),
+    };
+    let start = room
+        .map(|room| -> Result<VerifyingKey> {
+            let bytes = bs58::decode(&room)
+                .into_vec()
+                .map_err(|e| anyhow!("Invalid room owner key: {e}"))?;
+            let bytes: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| anyhow!("Invalid room owner key length"))?;
+            let key = VerifyingKey::from_bytes(&bytes)
+                .map_err(|e| anyhow!("Invalid room owner key: {e}"))?;
+            if !rooms.iter().any(|r| r.owner_vk == key) {
+                bail!("Room {room} is not in local storage");
+            }
+            Ok(key)
+        })
+        .transpose()?;
+
+    eprintln!("Loading {} room(s)...", rooms.len());
+    let names: HashMap<VerifyingKey, String> =
+        rooms.iter().map(|r| (r.owner_vk, r.name.clone())).collect();
+    let mut panes = Vec::new();
+    let mut latest: HashMap<VerifyingKey, MessageOrderKey> = HashMap::new();
+    let mut contracts: HashMap<ContractInstanceId, VerifyingKey> = HashMap::new();
+    for listing in &rooms {
+        let loaded = load_room(&api, &listing.owner_vk, &listing.name).await;
+        if let Some(key) = loaded.latest {
+            latest.insert(listing.owner_vk, key);
+        }
+        panes.extend(loaded.panes);
+        match api.subscribe_room(&listing.owner_vk).await {
+            Ok(id) => {
+                contracts.insert(id, listing.owner_vk);
+            }
+            Err(e) => tracing::warn!("Not subscribed to {}: {e:#}", listing.name),
+        }
+    }
+    let current = start
+        .and_then(|key| panes.iter().position(|p| p.key == PaneKey::Room(key)))
+        .unwrap_or(0);
+    let mut model = ChatModel::new(panes, current);
+    model.status = HELP.to_string();
+
+    let mut terminal = TerminalGuard::enter()?;
+    let (tx, mut events) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        // Polled rather than a bare `read()` so the thread notices the
+        // session ending and exits instead of swallowing the next key.
+        while !tx.is_closed() {
+            match event::poll(Duration::from_millis(200)) {
+                Ok(true) => match event::read() {
+                    Ok(ev) => {
+                        let _ = tx.send(ev);
+                    }
+                    Err(_) => break,
+                },
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+
+    let mut last_refresh = Instant::now();
+    loop {
+        mark_current_read(&api, &mut model, &latest);
+        terminal.0.draw(|frame| draw(frame, &model))?;
+
+        tokio::select! {
+            ev = events.recv() => {
+                let Some(ev) = ev else { break };
+                let Event::Key(key) = ev else { continue };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match model.handle_key(key) {
+                    ChatAction::None => {}
+                    ChatAction::Quit => break,
+                    ChatAction::Send(out) => {
+                        let room = out.pane.room();
+                        model.status = "Sending...".to_string();
+                        terminal.0.draw(|frame| draw(frame, &model))?;
+                        model.status = match send(&api, &out).await {
+                            Ok(done) => done,
+                            Err(e) => format!("Failed: {e:#}"),
+                        };
+                        refresh(&api, &mut model, &mut latest, &names, room).await;
+                    }
+                }
+            }
+            update = api.next_update(UPDATE_POLL) => {
+                if let Some(room) = update?.and_then(|id| contracts.get(&id).copied()) {
+                    refresh(&api, &mut model, &mut latest, &names, room).await;
+                }
+            }
+        }
+
+        if last_refresh.elapsed() >= FALLBACK_REFRESH {
+            if let Some(room) = model.current_pane().map(|p| p.key.room()) {
+                refresh(&api, &mut model, &mut latest, &names, room).await;
+            }
+            last_refresh = Instant::now();
+        }
+    }
+    Ok(())
+}
+
+/// Raw mode and the alternate screen, undone on drop — including when the
+/// session ends with an error, so the shell is never left unusable.
+struct TerminalGuard(Terminal<CrosstermBackend<Stdout>>);
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = std::io::stdout();
+        if let Err(e) = crossterm::execute!(stdout, EnterAlternateScreen) {
+            let _ = disable_raw_mode();
+            return Err(e.into());
+        }
+        Ok(TerminalGuard(Terminal::new(CrosstermBackend::new(stdout))?))
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = crossterm::execute!(self.0.backend_mut(), LeaveAlternateScreen);
+        let _ = self.0.show_cursor();
+    }
+}
+
+async fn refresh(
+    api: &ApiClient,
+    model: &mut ChatModel,
+    latest: &mut HashMap<VerifyingKey, MessageOrderKey>,
+    names: &HashMap<VerifyingKey, String>,
+    room: VerifyingKey,
+) {
+    let name = names.get(&room).cloned().unwrap_or_default();
+    let loaded = load_room(api, &room, &name).await;
+    match loaded.latest {
+        Some(key) => latest.insert(room, key),
+        None => latest.remove(&room),
+    };
+    model.replace_room(room, loaded.panes);
+}
+
+/// Advance the read marker when the newest message of the selected room is
+/// on screen.
+fn mark_current_read(
+    api: &ApiClient,
+    model: &mut ChatModel,
+    latest: &HashMap<VerifyingKey, MessageOrderKey>,
+) {
+    let current = model.current;
+    let Some(pane) = model.panes.get_mut(current) else {
+        return;
+    };
+    let PaneKey::Room(room) = pane.key else {
+        return;
+    };
+    if pane.unread == 0 || model.scroll > 0 {
+        return;
+    }
+    let Some(marker) = latest.get(&room).cloned() else {
+        return;
+    };
+    let room_bytes = room.to_bytes();
+    match api
+        .storage()
+        .mutate_read_markers(|store| store.advance(room_bytes, marker))
+    {
+        Ok(_) => pane.unread = 0,
+        Err(e) => tracing::warn!("Failed to save read marker: {e:#}"),
+    }
+}
+
+/// Fetch one room and build its pane followed by its DM panes. A failed
+/// fetch still yields the room pane, with the error as its only line, so one
+/// unreachable room does not end the session.
+async fn load_room(api: &ApiClient, room: &VerifyingKey, name: &str) -> RoomPanes {
+    let mut state = match api.get_room(room, false).await {
+        Ok(state) => state,
+        Err(e) => {
+            return RoomPanes {
+                panes: vec![Pane {
+                    key: PaneKey::Room(*room),
+                    title: name.to_string(),
+                    unread: 0,
+                    lines: vec![notice_line(format!("Could not load this room: {e:#}"))],
+                    members: Vec::new(),
+                }],
+                latest: None,
+            }
+        }
+    };
+    let secrets = api.room_display_secrets(room, &mut state);
+    api.hide_ignored(&mut state);
+    let ignored = api.storage().load_ignore_list().unwrap_or_default();
+    let signing_key = api.storage().get_room(room).ok().flatten().map(|r| r.0);
+    let self_id = signing_key
+        .as_ref()
+        .map(|sk| MemberId::from(&sk.verifying_key()));
+    let nicknames = dm::member_nicknames(&state, &secrets);
+    let display_name = |id: &MemberId| {
+        nicknames
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string().chars().take(8).collect())
+    };
+
+    let mut messages: Vec<_> = state.recent_messages.display_messages().collect();
+    messages.sort_by_key(|m| m.order_key());
+    let markers = api.storage().load_read_markers().unwrap_or_default();
+    let room_bytes = room.to_bytes();
+    let unread = messages
+        .iter()
+        .filter(|m| {
+            Some(m.message.author) != self_id && markers.is_unread(&room_bytes, &m.order_key())
+        })
+        .count();
+    let lines = messages
+        .iter()
+        .map(|msg| {
+            let id = msg.id();
+            let reactions = state
+                .recent_messages
+                .reactions(&id)
+                .filter(|r| !r.is_empty())
+                .map(|r| {
+                    let parts: Vec<_> = r
+                        .iter()
+                        .map(|(emoji, who)| format!("{}×{}", emoji, who.len()))
+                        .collect();
+                    format!(" [{}]", parts.join(" "))
+                })
+                .unwrap_or_default();
+            let edited = if state.recent_messages.is_edited(&id) {
+                " (edited)"
+            } else {
+                ""
+            };
+            ChatLine {
+                id: Some(id),
+                time: clock(msg.message.time),
+                author: display_name(&msg.message.author),
+                own: Some(msg.message.author) == self_id,
+                reply_prefix: reply_prefix_display(&reply_context_display_with_secrets(
+                    &state, msg, &secrets,
+                )),
+                body: message_display_text_with_secrets(&state, msg, &secrets),
+                suffix: format!("{edited}{reactions}"),
+            }
+        })
+        .collect();
+    let mut members: Vec<(MemberId, String)> = nicknames
+        .iter()
+        .filter(|(id, _)| Some(**id) != self_id)
+        .map(|(id, name)| (*id, name.clone()))
+        .collect();
+    members.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let mut panes = vec![Pane {
+        key: PaneKey::Room(*room),
+        title: name.to_string(),
+        unread,
+        lines,
+        members,
+    }];
+    if let Some(sk) = &signing_key {
+        let mut by_peer: BTreeMap<MemberId, Vec<ChatLine>> = BTreeMap::new();
+        for dm in dm::load_pairwise_dms(api, room, sk, &state, &nicknames)
+            .await
+            .into_iter()
+            .filter(|dm| !ignored.is_ignored(&dm.counterparty))
+        {
+            by_peer.entry(dm.counterparty).or_default().push(ChatLine {
+                id: None,
+                time: clock_unix(dm.timestamp),
+                author: if dm.outgoing {
+                    "you".to_string()
+                } else {
+                    display_name(&dm.counterparty)
+                },
+                own: dm.outgoing,
+                reply_prefix: String::new(),
+                body: dm.body,
+                suffix: String::new(),
+            });
+        }
+        for (peer, lines) in by_peer {
+            panes.push(Pane {
+                key: PaneKey::Dm { room: *room, peer },
+                title: format!("@{}", display_name(&peer)),
+                unread: 0,
+                lines,
+                members: Vec::new(),
+            });
+        }
+
+        let mut by_thread: BTreeMap<GroupThreadId, (Vec<MemberId>, Vec<ChatLine>)> =
+            BTreeMap::new();
+        for dm in dm::collect_group_dms(&state, sk, None, None)
+            .into_iter()
+            .filter(|dm| !ignored.is_ignored(&dm.sender))
+        {
+            let own = Some(dm.sender) == self_id;
+            by_thread
+                .entry(dm.thread)
+                .or_insert_with(|| (dm.participants.clone(), Vec::new()))
+                .1
+                .push(ChatLine {
+                    id: None,
+                    time: clock_unix(dm.timestamp),
+                    author: if own {
+                        "you".to_string()
+                    } else {
+                        display_name(&dm.sender)
+                    },
+                    own,
+                    reply_prefix: String::new(),
+                    body: dm.body,
+                    suffix: String::new(),
+                });
+        }
+        for (thread, (participants, lines)) in by_thread {
+            let others: Vec<String> = participants
+                .iter()
+                .filter(|id| Some(**id) != self_id)
+                .map(display_name)
+                .collect();
+            panes.push(Pane {
+                key: PaneKey::GroupDm {
+                    room: *room,
+                    thread,
+                },
+                title: format!("@{}", others.join(", @")),
+                unread: 0,
+                lines,
+                members: Vec::new(),
+            });
+        }
+    }
+    RoomPanes {
+        panes,
+        latest: messages.last().map(|m| m.order_key()),
+    }
+}
+
+/// Carry out an [`Outgoing`] and describe the result for the status line.
+async fn send(api: &ApiClient, out: &Outgoing) -> Result<String> {
+    let room = out.pane.room();
+    let text = out.text.clone();
+    match (out.pane, &out.compose) {
+        (PaneKey::Room(_), Compose::Message) => {
+            api.send_message(&room, text).await?;
+            Ok("Sent".to_string())
+        }
+        (PaneKey::Room(_), Compose::Reply(id)) => {
+            api.send_reply(&room, id.clone(), text).await?;
+            Ok("Reply sent".to_string())
+        }
+        (PaneKey::Room(_), Compose::Edit(id)) => {
+            api.edit_message(&room, id.clone(), text).await?;
+            Ok("Edited".to_string())
+        }
+        (PaneKey::Room(_), Compose::React(id)) => {
+            api.add_reaction(&room, id.clone(), text).await?;
+            Ok("Reaction added".to_string())
+        }
+        (PaneKey::Dm { peer, .. }, _) => {
+            let (sk, state) = dm_context(api, &room).await?;
+            let recipient = dm::resolve_recipient_vk(&state, &room, &peer.to_string())?;
+            dm::send_dm(
+                api,
+                room,
+                &sk,
+                &state,
+                recipient,
+                text.as_bytes(),
+                text.clone(),
+            )
+            .await?;
+            Ok("DM sent".to_string())
+        }
+        (PaneKey::GroupDm { thread, .. }, _) => {
+            let (sk, state) = dm_context(api, &room).await?;
+            let self_id = MemberId::from(&sk.verifying_key());
+            let participants: Vec<MemberId> = state
+                .group_direct_messages
+                .messages
+                .iter()
+                .find(|m| m.message.thread_id() == thread)
+                .map(|m| m.message.participants().collect())
+                .ok_or_else(|| anyhow!("This group thread is no longer in the room"))?;
+            let recipients = participants
+                .iter()
+                .filter(|id| **id != self_id)
+                .map(|id| dm::resolve_recipient_vk(&state, &room, &id.to_string()))
+                .collect::<Result<Vec<_>>>()?;
+            dm::send_group_dm(api, room, &sk, &state, &recipients, &text).await?;
+            Ok("Group DM sent".to_string())
+        }
+    }
+}
+
+async fn dm_context(
+    api: &ApiClient,
+    room: &VerifyingKey,
+) -> Result<(ed25519_dalek::SigningKey, ChatRoomStateV1)> {
+    let (sk, _, _) = api
+        .storage()
+        .get_room(room)?
+        .ok_or_else(|| anyhow!("Room not found in local storage"))?;
+    Ok((sk, api.get_room(room, false).await?))
+}
+
+fn draw(frame: &mut Frame, model: &ChatModel) {
+    let [side, main] =
+        Layout::horizontal([Constraint::Length(28), Constraint::Min(20)]).areas(frame.area());
+    let [scrollback, status, input] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(3),
+    ])
+    .areas(main);
+
+    let items: Vec<ListItem> = model
+        .panes
+        .iter()
+        .map(|pane| {
+            let label = match pane.key {
+                PaneKey::Room(_) if pane.unread > 0 => format!("{} ({})", pane.title, pane.unread),
+                PaneKey::Room(_) => pane.title.clone(),
+                _ => format!("  {}", pane.title),
+            };
+            let style = if pane.unread > 0 {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(label).style(style)
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(model.current));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Rooms"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        side,
+        &mut list_state,
+    );
+
+    let pane = model.current_pane();
+    let selected = match &model.mode {
+        Mode::Select(id) => Some(id),
+        Mode::Input => None,
+    };
+    let lines: Vec<Line> = pane
+        .map(|p| p.lines.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|line| {
+            let mut style = Style::default();
+            if line.id.is_some() && line.id.as_ref() == selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let author_style = if line.own {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::UNDERLINED)
+            };
+            Line::from(vec![
+                Span::raw(format!("{} ", line.time)),
+                Span::styled(line.author.clone(), author_style),
+                Span::raw(": "),
+                Span::raw(line.text()),
+            ])
+            .style(style)
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(pane.map(|p| p.title.clone()).unwrap_or_default());
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    // Pin the bottom of the scrollback to the bottom of the box, then back
+    // off by however far the user has scrolled up.
+    let total = paragraph.line_count(scrollback.width) as u16;
+    let bottom = total.saturating_sub(scrollback.height);
+    let offset = bottom.saturating_sub(model.scroll.min(u16::MAX as usize) as u16);
+    frame.render_widget(paragraph.scroll((offset, 0)), scrollback);
+
+    frame.render_widget(Paragraph::new(model.status.as_str()), status);
+
+    let title = match model.compose {
+        Compose::Message => "Message",
+        Compose::Reply(_) => "Reply",
+        Compose::Edit(_) => "Edit",
+        Compose::React(_) => "Reaction",
+    };
+    frame.render_widget(
+        Paragraph::new(model.input.as_str())
+            .block(Block::default().borders(Borders::ALL).title(title)),
+        input,
+    );
+    if model.mode == Mode::Input {
+        let width = Line::raw(model.input.as_str()).width() as u16;
+        frame.set_cursor_position((
+            (input.x + 1 + width).min(input.right().saturating_sub(2)),
+            input.y + 1,
+        ));
+    }
+}
+
+fn notice_line(body: String) -> ChatLine {
+    ChatLine {
+        id: None,
+        time: String::new(),
+        author: "riverctl".to_string(),
+        own: false,
+        reply_prefix: String::new(),
+        body,
+        suffix: String::new(),
+    }
+}
+
+fn clock(time: SystemTime) -> String {
+    let utc: DateTime<Utc> = time.into();
+    utc.with_timezone(&Local).format("%H:%M").to_string()
+}
+
+fn clock_unix(secs: u64) -> String {
+    clock(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
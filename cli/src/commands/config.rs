@@ -0,0 +1,112 @@
+use crate::config::Config;
+use crate::output::{FormatArg, OutputFormat};
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use serde_json::json;
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print one setting.
+    ///
+    /// Keys: default_room, node_url, format, user_key_path, alias.<name>,
+    /// room.<room>.signing_key_file.
+    Get { key: String },
+    /// Change one setting. Room values may be given as an alias.
+    Set { key: String, value: String },
+    /// Remove one setting.
+    Unset { key: String },
+    /// Print every setting and the file's location.
+    List,
+}
+
+/// `riverctl config ...`: read or edit `config.toml`. Offline — it never
+/// builds an API client. Writes go through [`Config::set`]'s checks, so the
+/// file it saves always loads again.
+pub fn execute(
+    command: ConfigCommands,
+    config_dir: Option<&str>,
+    format: Option<OutputFormat>,
+) -> Result<()> {
+    let path = Config::path(config_dir)?;
+    let loaded = Config::load(config_dir);
+    let format = format
+        .or_else(|| {
+            loaded
+                .as_ref()
+                .ok()
+                .and_then(|c| c.output_format().ok().flatten())
+                .map(FormatArg::output)
+        })
+        .unwrap_or(OutputFormat::Human);
+
+    match command {
+        ConfigCommands::Get { key } => {
+            let value = loaded?.get(&key)?;
+            match format {
+                OutputFormat::Human => match &value {
+                    Some(value) => println!("{value}"),
+                    None => return Err(anyhow!("`{key}` is not set")),
+                },
+                OutputFormat::Json => {
+                    println!("{}", json!({ "key": key, "value": value }));
+                }
+            }
+        }
+        ConfigCommands::Set { key, value } => {
+            let mut config = loaded?;
+            config.set(&key, &value)?;
+            config.save(config_dir)?;
+            let stored = config.get(&key)?;
+            match format {
+                OutputFormat::Human => {
+                    println!("{key} = {}", stored.unwrap_or_default());
+                }
+                OutputFormat::Json => {
+                    println!("{}", json!({ "key": key, "value": stored }));
+                }
+            }
+        }
+        ConfigCommands::Unset { key } => {
+            let mut config = loaded?;
+            let was_set = config.unset(&key)?;
+            if was_set {
+                config.save(config_dir)?;
+            }
+            match format {
+                OutputFormat::Human if was_set => println!("Unset {key}"),
+                OutputFormat::Human => println!("{key} was not set"),
+                OutputFormat::Json => {
+                    println!("{}", json!({ "key": key, "was_set": was_set }));
+                }
+            }
+        }
+        ConfigCommands::List => {
+            let entries = loaded?.entries();
+            match format {
+                OutputFormat::Human => {
+                    println!("# {}", path.display());
+                    if entries.is_empty() {
+                        println!("# (nothing set)");
+                    }
+                    for (key, value) in entries {
+                        println!("{key} = {value}");
+                    }
+                }
+                OutputFormat::Json => {
+                    let settings: serde_json::Map<String, serde_json::Value> = entries
+                        .into_iter()
+                        .map(|(k, v)| (k, serde_json::Value::String(v)))
+                        .collect();
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&json!({
+                            "path": path.display().to_string(),
+                            "settings": settings,
+                        }))?
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
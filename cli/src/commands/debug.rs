@@ -1,15 +1,21 @@
 use crate::api::ApiClient;
+use crate::commands::dm::member_nicknames;
 use crate::deputies::{grant_status_line, party_label, DeputyGrant, RoomDeputies};
 use crate::output::OutputFormat;
-use anyhow::{anyhow, Result};
+use crate::storage::Storage;
+use anyhow::{anyhow, Context, Result};
 use clap::Subcommand;
 use ed25519_dalek::VerifyingKey;
+use freenet_scaffold::ComposableState;
 use river_core::room_state::ban::{AuthorizedUserBan, BansV1};
 use river_core::room_state::member::{AuthorizedMember, MemberId, MembersV1};
 use river_core::room_state::member_info::MemberInfoV1;
-use river_core::room_state::ChatRoomStateV1;
+use river_core::room_state::{ChatRoomParametersV1, ChatRoomStateV1, ChatRoomStateV1Delta};
+use river_core::simulation::{self, Trace};
+use river_core::state_size::{Compaction, SizeReport, Usage};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 #[derive(Subcommand)]
 pub enum DebugCommands {
@@ -40,6 +46,35 @@ pub enum DebugCommands {
         /// Room owner key (base58 encoded)
         room_owner_key: String,
     },
+    /// Break the room state's serialized size down by field and by member,
+    /// show usage against the configured limits, and list what could be
+    /// compacted
+    Size {
+        /// Room owner key (base58 encoded)
+        room_owner_key: String,
+    },
+    /// Compare two room state snapshots: which summary fields differ, what
+    /// only one side holds, and the delta each side is missing
+    Diff {
+        /// A CBOR-encoded `ChatRoomStateV1`
+        a: PathBuf,
+        /// The snapshot to compare it with; omit with --live
+        #[arg(required_unless_present = "live")]
+        b: Option<PathBuf>,
+        /// Compare `a` with the room's current state on the network
+        #[arg(long, conflicts_with = "b")]
+        live: bool,
+        /// Room the snapshots belong to [default: the joined room whose owner
+        /// signed `a`'s configuration]
+        #[arg(long)]
+        room: Option<String>,
+    },
+    /// Replay a recorded convergence-simulator trace and report whether the
+    /// simulated peers converge
+    Replay {
+        /// A trace written by `river_core::simulation::Trace::to_bytes`
+        trace: PathBuf,
+    },
 }
 
 /// What a ban is currently doing, as opposed to merely being stored
@@ -432,6 +467,7 @@ pub async fn execute(command: DebugCommands, api: ApiClient, format: OutputForma
     match command {
         DebugCommands::ContractGet { room_owner_key } => {
             // Decode the room owner key from base58
+            let room_owner_key = crate::config::resolve_room_arg(&room_owner_key)?;
             let decoded = bs58::decode(&room_owner_key)
                 .into_vec()
                 .map_err(|e| anyhow!("Failed to decode room owner key: {}", e))?;
@@ -544,6 +580,7 @@ pub async fn execute(command: DebugCommands, api: ApiClient, format: OutputForma
         }
         DebugCommands::ContractKey { room_owner_key } => {
             // Decode the room owner key from base58
+            let room_owner_key = crate::config::resolve_room_arg(&room_owner_key)?;
             let decoded = bs58::decode(&room_owner_key)
                 .into_vec()
                 .map_err(|e| anyhow!("Failed to decode room owner key: {}", e))?;
@@ -688,12 +725,411 @@ pub async fn execute(command: DebugCommands, api: ApiClient, format: OutputForma
             }
             Ok(())
         }
+        DebugCommands::Size { room_owner_key } => {
+            let owner_vk = parse_owner_key(&room_owner_key)?;
+            let mut room_state = api.get_room(&owner_vk, false).await?;
+            let report = SizeReport::new(&room_state);
+            let secrets = api.room_display_secrets(&owner_vk, &mut room_state);
+            let nicknames = member_nicknames(&room_state, &secrets);
+            print_size_report(&report, &nicknames, format)
+        }
+        DebugCommands::Diff { a, b, room, .. } => {
+            let state_a = read_snapshot(&a)?;
+            let owner_vk = diff_owner(room.as_deref(), &state_a, api.storage())?;
+            let (state_b, label_b) = match b {
+                Some(b) => (read_snapshot(&b)?, b.display().to_string()),
+                None => (api.get_room(&owner_vk, false).await?, "network".to_string()),
+            };
+            let diff = StateDiff::compute(
+                &owner_vk,
+                (&a.display().to_string(), &state_a),
+                (&label_b, &state_b),
+            );
+            print_state_diff(&diff, format)
+        }
+        DebugCommands::Replay { trace } => replay_trace(&trace, format),
+    }
+}
+
+/// `debug replay`: a trace carries its own states, so this never needs a node
+/// and is run before the client is built. Divergence is an error, so the exit
+/// status tells a script whether the trace still reproduces.
+pub fn replay_trace(path: &Path, format: OutputFormat) -> Result<()> {
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let trace = Trace::from_bytes(&bytes).map_err(|e| anyhow!("{}: {e}", path.display()))?;
+    let outcome = simulation::replay(&trace);
+    match format {
+        OutputFormat::Human => match &outcome {
+            Ok(report) => println!(
+                "Replayed {} steps across {} peers: converged on {} members and {} \
+                 messages (rejected updates: {}, settle rounds: {}).",
+                trace.steps.len(),
+                trace.peers,
+                report.state.members.members.len(),
+                report.state.recent_messages.messages.len(),
+                report.rejected_updates,
+                report.settle_rounds
+            ),
+            Err(divergence) => println!("Replayed {} steps: {divergence}", trace.steps.len()),
+        },
+        OutputFormat::Json => {
+            let json = match &outcome {
+                Ok(report) => serde_json::json!({
+                    "converged": true,
+                    "steps": trace.steps.len(),
+                    "peers": trace.peers,
+                    "members": report.state.members.members.len(),
+                    "messages": report.state.recent_messages.messages.len(),
+                    "rejected_updates": report.rejected_updates,
+                    "settle_rounds": report.settle_rounds,
+                }),
+                Err(divergence) => serde_json::json!({
+                    "converged": false,
+                    "steps": trace.steps.len(),
+                    "peers": trace.peers,
+                    "diverging_peers": [divergence.peers.0, divergence.peers.1],
+                    "differing_fields": divergence.fields,
+                }),
+            };
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+    match outcome {
+        Ok(_) => Ok(()),
+        Err(divergence) => Err(anyhow!("{divergence}")),
+    }
+}
+
+/// `debug diff` between two files, run before any client is built so that
+/// snapshots copied off another peer can be compared with no node running.
+pub fn diff_files(
+    a: &Path,
+    b: &Path,
+    room: Option<&str>,
+    storage: &Storage,
+    format: OutputFormat,
+) -> Result<()> {
+    let state_a = read_snapshot(a)?;
+    let state_b = read_snapshot(b)?;
+    let owner_vk = diff_owner(room, &state_a, storage)?;
+    let diff = StateDiff::compute(
+        &owner_vk,
+        (&a.display().to_string(), &state_a),
+        (&b.display().to_string(), &state_b),
+    );
+    print_state_diff(&diff, format)
+}
+
+fn read_snapshot(path: &Path) -> Result<ChatRoomStateV1> {
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    ciborium::de::from_reader(bytes.as_slice())
+        .with_context(|| format!("{} is not a CBOR ChatRoomStateV1", path.display()))
+}
+
+/// The room owner, which `summarize` and `delta` take as a parameter. A
+/// snapshot only names the owner by member ID, so without `--room` the
+/// joined rooms are searched for the one that ID belongs to.
+fn diff_owner(
+    room: Option<&str>,
+    state: &ChatRoomStateV1,
+    storage: &Storage,
+) -> Result<VerifyingKey> {
+    if let Some(room) = room {
+        return parse_owner_key(room);
+    }
+    let owner_id = state.configuration.configuration.owner_member_id;
+    storage
+        .load_rooms()?
+        .rooms
+        .keys()
+        .filter_map(|key| parse_owner_key(key).ok())
+        .find(|vk| MemberId::from(vk) == owner_id)
+        .ok_or_else(|| {
+            anyhow!(
+                "the snapshot's owner ({owner_id}) is not a room you have joined; \
+                 pass --room <room-owner-key>"
+            )
+        })
+}
+
+/// What [`ComposableState`] sees when comparing two snapshots of one room.
+#[derive(Serialize, Debug)]
+struct StateDiff {
+    room: String,
+    a: String,
+    b: String,
+    /// Top-level fields whose summaries differ.
+    differing_fields: Vec<&'static str>,
+    only_in_a: OneSided,
+    only_in_b: OneSided,
+    /// `b.delta(summary of a)`: what `a` must apply to catch up with `b`.
+    a_needs: Option<ChatRoomStateV1Delta>,
+    /// `a.delta(summary of b)`.
+    b_needs: Option<ChatRoomStateV1Delta>,
+}
+
+/// Entries one snapshot holds and the other does not.
+#[derive(Serialize, Debug, Default, PartialEq)]
+struct OneSided {
+    /// `<banned member> by <banner>`.
+    bans: Vec<String>,
+    members: Vec<String>,
+    /// Message IDs, with author.
+    messages: Vec<String>,
+}
+
+impl OneSided {
+    fn between(this: &ChatRoomStateV1, other: &ChatRoomStateV1) -> Self {
+        let other_bans: HashSet<_> = other.bans.0.iter().map(|b| b.id()).collect();
+        let other_members: HashSet<_> = other
+            .members
+            .members
+            .iter()
+            .map(|m| m.member.id())
+            .collect();
+        let other_messages: HashSet<_> = other
+            .recent_messages
+            .messages
+            .iter()
+            .map(|m| m.id())
+            .collect();
+        OneSided {
+            bans: this
+                .bans
+                .0
+                .iter()
+                .filter(|b| !other_bans.contains(&b.id()))
+                .map(|b| format!("{} by {}", b.ban.banned_user, b.banned_by))
+                .collect(),
+            members: this
+                .members
+                .members
+                .iter()
+                .map(|m| m.member.id())
+                .filter(|id| !other_members.contains(id))
+                .map(|id| id.to_string())
+                .collect(),
+            messages: this
+                .recent_messages
+                .messages
+                .iter()
+                .filter(|m| !other_messages.contains(&m.id()))
+                .map(|m| format!("{} by {}", m.id().0 .0, m.message.author))
+                .collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bans.is_empty() && self.members.is_empty() && self.messages.is_empty()
+    }
+}
+
+impl StateDiff {
+    fn compute(
+        owner: &VerifyingKey,
+        (label_a, a): (&str, &ChatRoomStateV1),
+        (label_b, b): (&str, &ChatRoomStateV1),
+    ) -> Self {
+        let params = ChatRoomParametersV1 { owner: *owner };
+        let summary_a = a.summarize(a, &params);
+        let summary_b = b.summarize(b, &params);
+        let differing_fields = summary_a.differing_fields(&summary_b);
+        StateDiff {
+            room: bs58::encode(owner.as_bytes()).into_string(),
+            a: label_a.to_string(),
+            b: label_b.to_string(),
+            differing_fields,
+            only_in_a: OneSided::between(a, b),
+            only_in_b: OneSided::between(b, a),
+            a_needs: b.delta(b, &params, &summary_a),
+            b_needs: a.delta(a, &params, &summary_b),
+        }
     }
 }
 
+fn print_state_diff(diff: &StateDiff, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Human => {
+            println!("Room {}", diff.room);
+            println!("  a: {}", diff.a);
+            println!("  b: {}", diff.b);
+            if diff.differing_fields.is_empty() {
+                println!("Summaries are identical.");
+            } else {
+                println!("Summaries differ in: {}", diff.differing_fields.join(", "));
+            }
+            for (side, one_sided) in [("a", &diff.only_in_a), ("b", &diff.only_in_b)] {
+                if one_sided.is_empty() {
+                    continue;
+                }
+                println!();
+                println!("Only in {side}:");
+                for ban in &one_sided.bans {
+                    println!("  ban      {ban}");
+                }
+                for member in &one_sided.members {
+                    println!("  member   {member}");
+                }
+                for message in &one_sided.messages {
+                    println!("  message  {message}");
+                }
+            }
+            println!();
+            for (side, other, delta) in [("a", "b", &diff.a_needs), ("b", "a", &diff.b_needs)] {
+                match delta {
+                    Some(delta) => println!(
+                        "{side} needs a delta from {other} in: {}",
+                        delta.present_fields().join(", ")
+                    ),
+                    None => println!("{side} needs nothing from {other}."),
+                }
+            }
+            println!("(--format json prints the deltas in full)");
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(diff)?);
+        }
+    }
+    Ok(())
+}
+
+/// Percent of `total`, for the size report's field column.
+fn share(bytes: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        bytes as f64 * 100.0 / total as f64
+    }
+}
+
+fn usage_json(usage: &Usage) -> serde_json::Value {
+    serde_json::json!({ "items": usage.items, "bytes": usage.bytes })
+}
+
+fn print_size_report(
+    report: &SizeReport,
+    nicknames: &HashMap<MemberId, String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let label = |id: &MemberId| match nicknames.get(id) {
+        Some(nickname) => format!("{nickname} ({id})"),
+        None => id.to_string(),
+    };
+    match format {
+        OutputFormat::Human => {
+            println!("Room state: {} bytes", report.total_bytes);
+            println!();
+            println!("By field:");
+            let mut counted = 0;
+            for field in &report.fields {
+                counted += field.usage.bytes;
+                println!(
+                    "  {:<22} {:>5} items {:>9} bytes {:>5.1}%",
+                    field.field,
+                    field.usage.items,
+                    field.usage.bytes,
+                    share(field.usage.bytes, report.total_bytes)
+                );
+            }
+            println!(
+                "  {:<22} {:>5}       {:>9} bytes",
+                "(field names, framing)",
+                "",
+                report.total_bytes.saturating_sub(counted)
+            );
+            println!();
+            println!("By member (largest first):");
+            for member in &report.members {
+                println!(
+                    "  {}: {} bytes",
+                    label(&member.member_id),
+                    member.total_bytes()
+                );
+                for (what, usage) in [
+                    ("messages", &member.messages),
+                    ("member_info", &member.member_info),
+                    ("direct messages", &member.direct_messages),
+                    ("group messages", &member.group_direct_messages),
+                    ("secret blobs", &member.secrets),
+                ] {
+                    if usage.items > 0 {
+                        println!(
+                            "    {:<16} {:>5} {:>9} bytes",
+                            what, usage.items, usage.bytes
+                        );
+                    }
+                }
+            }
+            println!();
+            println!("Limits:");
+            for limit in &report.limits {
+                println!("  {:<26} {:>5} / {}", limit.limit, limit.used, limit.max);
+            }
+            println!();
+            if report.compactions.is_empty() {
+                println!("Nothing to compact.");
+            } else {
+                println!("Compaction opportunities:");
+                for compaction in &report.compactions {
+                    match compaction.member().and_then(|id| nicknames.get(&id)) {
+                        Some(nickname) => println!("  - {compaction} ({nickname})"),
+                        None => println!("  - {compaction}"),
+                    }
+                }
+                println!(
+                    "Reclaimable: {} bytes ({:.1}% of the state)",
+                    report.reclaimable_bytes(),
+                    share(report.reclaimable_bytes(), report.total_bytes)
+                );
+            }
+        }
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "total_bytes": report.total_bytes,
+                "fields": report.fields.iter().map(|field| serde_json::json!({
+                    "field": field.field,
+                    "items": field.usage.items,
+                    "bytes": field.usage.bytes,
+                })).collect::<Vec<_>>(),
+                "members": report.members.iter().map(|member| serde_json::json!({
+                    "member_id": member.member_id.to_string(),
+                    "nickname": nicknames.get(&member.member_id),
+                    "total_bytes": member.total_bytes(),
+                    "messages": usage_json(&member.messages),
+                    "member_info": usage_json(&member.member_info),
+                    "direct_messages": usage_json(&member.direct_messages),
+                    "group_direct_messages": usage_json(&member.group_direct_messages),
+                    "secrets": usage_json(&member.secrets),
+                })).collect::<Vec<_>>(),
+                "limits": report.limits.iter().map(|limit| serde_json::json!({
+                    "limit": limit.limit,
+                    "used": limit.used,
+                    "max": limit.max,
+                })).collect::<Vec<_>>(),
+                "compactions": report.compactions.iter().map(|compaction| {
+                    let kind = match compaction {
+                        Compaction::StaleSecretVersion { .. } => "stale_secret_version",
+                        Compaction::SecretsForNonMember { .. } => "secrets_for_non_member",
+                        Compaction::DuplicateMemberInfo { .. } => "duplicate_member_info",
+                    };
+                    serde_json::json!({
+                        "kind": kind,
+                        "detail": compaction.to_string(),
+                        "reclaimable_bytes": compaction.reclaimable_bytes(),
+                    })
+                }).collect::<Vec<_>>(),
+                "reclaimable_bytes": report.reclaimable_bytes(),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+    Ok(())
+}
+
 /// Helper to parse a base58-encoded room owner key
 fn parse_owner_key(room_owner_key: &str) -> Result<VerifyingKey> {
-    let decoded = bs58::decode(room_owner_key)
+    let room_owner_key = crate::config::resolve_room_arg(room_owner_key)?;
+    let decoded = bs58::decode(&room_owner_key)
         .into_vec()
         .map_err(|e| anyhow!("Failed to decode room owner key: {}", e))?;
 
@@ -1476,4 +1912,42 @@ mod tests {
              `classify_ban` and can reintroduce a hardcoded verdict."
         );
     }
+
+    #[test]
+    fn diff_reports_one_sided_entries_and_the_delta_each_side_needs() {
+        let owner = key(1);
+        let (alice, bob, carol) = (key(2), key(3), key(4));
+        let mut a = ChatRoomStateV1::default();
+        push_member(&mut a, &owner, &owner, &alice);
+        let mut b = a.clone();
+        push_member(&mut a, &owner, &owner, &bob);
+        push_member(&mut b, &owner, &owner, &carol);
+        push_ban(&mut b, &owner, &owner, &alice);
+
+        let diff = StateDiff::compute(&owner.verifying_key(), ("a", &a), ("b", &b));
+        assert_eq!(diff.differing_fields, ["bans", "members"]);
+        assert_eq!(diff.only_in_a.members, [id(&bob).to_string()]);
+        assert_eq!(diff.only_in_b.members, [id(&carol).to_string()]);
+        assert_eq!(
+            diff.only_in_b.bans,
+            [format!("{} by {}", id(&alice), id(&owner))]
+        );
+        assert!(diff.only_in_a.bans.is_empty());
+        assert_eq!(
+            diff.a_needs.as_ref().unwrap().present_fields(),
+            ["bans", "members"]
+        );
+        assert_eq!(diff.b_needs.as_ref().unwrap().present_fields(), ["members"]);
+    }
+
+    #[test]
+    fn identical_snapshots_need_nothing() {
+        let owner = key(1);
+        let mut state = ChatRoomStateV1::default();
+        push_member(&mut state, &owner, &owner, &key(2));
+        let diff = StateDiff::compute(&owner.verifying_key(), ("a", &state), ("b", &state));
+        assert!(diff.differing_fields.is_empty());
+        assert!(diff.only_in_a.is_empty() && diff.only_in_b.is_empty());
+        assert!(diff.a_needs.is_none() && diff.b_needs.is_none());
+    }
 }
@@ -7,20 +7,47 @@
 //! WASM doesn't care which client posted the state — verification rests on
 //! the sender + recipient signatures, both produced from helpers that live
 //! in the `river-core` crate.
+//!
+//! # Inbox transition (#230 Phase 4)
+//!
+//! DMs are moving out of the room into per-member inbox contracts
+//! (`river_core::inbox`). While both exist, `dm list` and `dm purge` read the
+//! room AND the local member's inbox, and `dm send` delivers into the
+//! recipient's inbox only when they have published one. Only an inbox-aware
+//! client publishes an inbox (`dm list` does so on first read), so a recipient
+//! still on a room-only client keeps receiving DMs in-room. A build that
+//! bundles no inbox contract (`api::inbox_contract_available`) behaves exactly
+//! as before.
+//!
+//! # Group DMs
+//!
+//! Passing several comma-separated recipients to `dm send` posts one
+//! `river_core::room_state::group_direct_messages` message instead of a DM per
+//! recipient. Group messages always go in-room (the inbox contract holds
+//! pairwise DMs only). `dm list` shows each group as its own thread, and
+//! `dm purge` tombstones them through the same room purge envelope.
 
-use crate::api::{ApiClient, Invitation};
+use crate::api::{inbox_contract_available, ApiClient, Invitation};
 use crate::commands::invite::{print_invitation_accepted, resolve_nickname};
 use crate::output::OutputFormat;
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Local, Utc};
 use clap::Subcommand;
 use ed25519_dalek::{SigningKey, VerifyingKey};
-use river_core::chat_delegate::OutboundDmEntry;
+use river_core::chat_delegate::{IgnoreList, OutboundDmEntry};
+use river_core::inbox::{
+    sign_inbox_purges, AuthorizedInboxRoomsV1, InboxMessageV1, InboxParametersV1, InboxStateV1,
+    MembershipProofV1,
+};
 use river_core::room_state::direct_messages::{
-    advance_recipient_purges, compose_direct_message, open_direct_message, PurgeToken,
-    MAX_DM_MESSAGES_PER_PAIR,
+    advance_recipient_purges, compose_direct_message, open_direct_message, AuthorizedDirectMessage,
+    PurgeToken, MAX_DM_MESSAGES_PER_PAIR,
 };
 use river_core::room_state::dm_body::{decode_body, encode_body, DirectMessageBody, InvitePayload};
+use river_core::room_state::group_direct_messages::{
+    compose_group_direct_message, open_group_direct_message, GroupThreadId,
+    MAX_GROUP_DM_PARTICIPANTS, MIN_GROUP_DM_PARTICIPANTS,
+};
 use river_core::room_state::member::MemberId;
 use river_core::room_state::{ChatRoomParametersV1, ChatRoomStateV1, ChatRoomStateV1Delta};
 use serde_json::json;
@@ -32,11 +59,13 @@ pub enum DmCommands {
     /// Send a direct message to a co-member of a room.
     ///
     /// `recipient` accepts a short MemberId prefix (e.g. the first 8 chars
-    /// shown by `riverctl member list`) or a full MemberId.
+    /// shown by `riverctl member list`) or a full MemberId. Separate two to
+    /// seven of them with commas to send one group DM to all of them.
     Send {
         /// Room ID (base58-encoded room owner verifying key)
         room_id: String,
-        /// Recipient member ID (short prefix accepted)
+        /// Recipient member ID (short prefix accepted), or a comma-separated
+        /// list for a group DM
         recipient: String,
         /// Message body (plaintext, encrypted on send)
         message: String,
@@ -46,10 +75,12 @@ pub enum DmCommands {
     List {
         /// Room ID
         room_id: String,
-        /// Show only DMs exchanged with this counterparty (short prefix accepted)
+        /// Show only DMs exchanged with this counterparty (short prefix
+        /// accepted), or only the group thread with these comma-separated
+        /// members
         #[arg(long)]
         with: Option<String>,
-        /// Maximum messages to show per counterparty
+        /// Maximum messages to show per thread
         #[arg(short, long, default_value = "50")]
         limit: usize,
         /// Show only messages from the last N minutes
@@ -194,6 +225,24 @@ async fn execute_send(
 
     let room_state = api.get_room(&room_owner_key, false).await?;
 
+    let needles = split_member_list(recipient);
+    if needles.len() > 1 {
+        let recipient_vks = needles
+            .iter()
+            .map(|needle| resolve_recipient_vk(&room_state, &room_owner_key, needle))
+            .collect::<Result<Vec<_>>>()?;
+        return deliver_group_dm(
+            &api,
+            format,
+            room_owner_key,
+            &signing_key,
+            &room_state,
+            &recipient_vks,
+            message,
+        )
+        .await;
+    }
+
     let recipient_vk = resolve_recipient_vk(&room_state, &room_owner_key, recipient)?;
 
     // Text-variant DMs keep the legacy wire shape (raw UTF-8 bytes, no magic
@@ -249,6 +298,67 @@ async fn deliver_dm(
     cache_label: String,
     kind: DmKind,
 ) -> Result<()> {
+    let sent = send_dm(
+        api,
+        room_owner_key,
+        signing_key,
+        room_state,
+        recipient_vk,
+        body_bytes,
+        cache_label,
+    )
+    .await?;
+    let token_hex = hex_token(&sent.token);
+
+    let noun = match kind {
+        DmKind::Text => "DM",
+        DmKind::Invite => "Invitation DM",
+    };
+    match format {
+        OutputFormat::Human => {
+            println!(
+                "{} sent to {}{} (purge token: {})",
+                noun,
+                short_member_id(&sent.recipient),
+                if sent.via_inbox { "'s inbox" } else { "" },
+                token_hex
+            );
+        }
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&json!({
+                    "status": "success",
+                    "recipient": sent.recipient.to_string(),
+                    "purge_token": token_hex,
+                    "via": if sent.via_inbox { "inbox" } else { "room" },
+                }))?
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A pairwise DM that [`send_dm`] delivered.
+pub(crate) struct SentDm {
+    pub(crate) recipient: MemberId,
+    pub(crate) token: PurgeToken,
+    /// Delivered into the recipient's inbox contract rather than the room.
+    pub(crate) via_inbox: bool,
+}
+
+/// The silent half of [`deliver_dm`]: everything but the printing, so
+/// `riverctl chat` can send from its DM panes without writing to a terminal
+/// it has taken over.
+pub(crate) async fn send_dm(
+    api: &ApiClient,
+    room_owner_key: VerifyingKey,
+    signing_key: &SigningKey,
+    room_state: &ChatRoomStateV1,
+    recipient_vk: VerifyingKey,
+    body_bytes: &[u8],
+    cache_label: String,
+) -> Result<SentDm> {
     let recipient_id = MemberId::from(&recipient_vk);
     let self_id = MemberId::from(&signing_key.verifying_key());
 
@@ -330,19 +440,213 @@ async fn deliver_dm(
     )
     .map_err(|e| anyhow!("Failed to compose DM: {}", e))?;
 
+    // Inbox first when the recipient has one (#230 Phase 4); see the module
+    // docs for why "has one" is the transition signal.
+    let via_inbox = deliver_via_inbox(
+        api,
+        room_owner_key,
+        room_state,
+        &signing_key.verifying_key(),
+        &recipient_vk,
+        &auth,
+    )
+    .await?;
+
+    if !via_inbox {
+        let delta = ChatRoomStateV1Delta {
+            members: rejoin_members,
+            member_info: rejoin_member_info,
+            direct_messages: Some(
+                river_core::room_state::direct_messages::DirectMessagesDelta {
+                    new_messages: vec![auth.clone()],
+                    advanced_purges: vec![],
+                },
+            ),
+            ..Default::default()
+        };
+
+        // Local pre-flight: walk the same apply_delta the contract will run.
+        let params = ChatRoomParametersV1 {
+            owner: room_owner_key,
+        };
+        let mut local = room_state.clone();
+        {
+            use freenet_scaffold::ComposableState;
+            local
+                .apply_delta(room_state, &params, &Some(delta.clone()))
+                .map_err(|e| anyhow!("Local pre-flight apply_delta failed: {:?}", e))?;
+        }
+
+        // Codex P2 defence-in-depth (PR #269 review): `DirectMessagesV1::apply_delta`
+        // silent-drops DMs whose sender isn't a current member, so the pre-flight
+        // above can return Ok even though our DM was dropped. Verify the message
+        // we tried to send is actually present in the post-merge state before
+        // we report success to the user.
+        let landed = local
+            .direct_messages
+            .messages
+            .iter()
+            .any(|m| m.sender_signature == auth.sender_signature);
+        if !landed {
+            return Err(anyhow!(
+                "Local pre-flight: the DM was silently dropped by the contract \
+                 (likely because the sender or recipient is not a current member). \
+                 Refusing to claim a successful send."
+            ));
+        }
+
+        api.send_state_delta(&room_owner_key, &delta).await?;
+    }
+
+    let token = auth.purge_token();
+
+    // Persist a label in the local outbound-DM cache so a future `dm list`
+    // renders the sender's own bubble as text instead of
+    // `<sent: ciphertext only>` (the ciphertext is recipient-sealed, so the
+    // sender can't re-derive it). For a text DM this is the message; for an
+    // invite it's the same `[Invitation to room …]` summary the recipient's
+    // list shows. See issue freenet/river#256. Failure is logged but not
+    // fatal — the DM has already been sent.
+    if let Err(e) = persist_outbound_plaintext(
+        api,
+        room_owner_key,
+        self_id,
+        recipient_id,
+        token,
+        auth.message.timestamp,
+        cache_label,
+    ) {
+        tracing::warn!("Failed to persist outbound DM plaintext locally: {}", e);
+    }
+
+    Ok(SentDm {
+        recipient: recipient_id,
+        token,
+        via_inbox,
+    })
+}
+
+/// The group branch of `dm send`: one message, body encrypted once, key
+/// sealed to every participant. Same membership pre-flight as [`deliver_dm`],
+/// including the rejoin bundle for a pruned sender; unlike a pairwise DM the
+/// sender can decrypt their own copy, so nothing goes in the outbound cache.
+async fn deliver_group_dm(
+    api: &ApiClient,
+    format: OutputFormat,
+    room_owner_key: VerifyingKey,
+    signing_key: &SigningKey,
+    room_state: &ChatRoomStateV1,
+    recipient_vks: &[VerifyingKey],
+    message: &str,
+) -> Result<()> {
+    let sent = send_group_dm(
+        api,
+        room_owner_key,
+        signing_key,
+        room_state,
+        recipient_vks,
+        message,
+    )
+    .await?;
+    let participants = &sent.participants;
+    match format {
+        OutputFormat::Human => println!(
+            "Group DM sent to {} members (thread {})",
+            participants.len() - 1,
+            sent.thread
+        ),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "status": "success",
+                "participants": participants.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                "group_thread": sent.thread.to_string(),
+                "purge_token": hex_token(&sent.token),
+                "via": "room",
+            }))?
+        ),
+    }
+    Ok(())
+}
+
+/// A group message that [`send_group_dm`] posted.
+pub(crate) struct SentGroupDm {
+    /// Every participant, the sender included, sorted.
+    pub(crate) participants: Vec<MemberId>,
+    pub(crate) thread: GroupThreadId,
+    pub(crate) token: PurgeToken,
+}
+
+/// [`deliver_group_dm`] without the printing; see [`send_dm`].
+pub(crate) async fn send_group_dm(
+    api: &ApiClient,
+    room_owner_key: VerifyingKey,
+    signing_key: &SigningKey,
+    room_state: &ChatRoomStateV1,
+    recipient_vks: &[VerifyingKey],
+    message: &str,
+) -> Result<SentGroupDm> {
+    let self_vk = signing_key.verifying_key();
+    let self_id = MemberId::from(&self_vk);
+    let owner_id = MemberId::from(&room_owner_key);
+
+    let mut participants: Vec<MemberId> = recipient_vks.iter().map(MemberId::from).collect();
+    participants.push(self_id);
+    participants.sort();
+    participants.dedup();
+    if participants.len() < MIN_GROUP_DM_PARTICIPANTS {
+        return Err(anyhow!(
+            "A group DM needs at least {} other members; use a single recipient for a \
+             one-to-one DM.",
+            MIN_GROUP_DM_PARTICIPANTS - 1
+        ));
+    }
+    if participants.len() > MAX_GROUP_DM_PARTICIPANTS {
+        return Err(anyhow!(
+            "A group DM can include at most {} other members.",
+            MAX_GROUP_DM_PARTICIPANTS - 1
+        ));
+    }
+    if let Some(absent) = participants
+        .iter()
+        .find(|id| **id != self_id && !room_has_member(room_state, owner_id, **id))
+    {
+        return Err(anyhow!(
+            "{} is not currently a member of the room.",
+            short_member_id(absent)
+        ));
+    }
+
+    let (rejoin_members, rejoin_member_info) =
+        api.build_rejoin_delta(room_state, &room_owner_key, signing_key);
+    if !room_has_member(room_state, owner_id, self_id) && rejoin_members.is_none() {
+        return Err(anyhow!(
+            "Your member entry is not in the room and no stored rejoin credentials \
+             are available. Re-accept your invitation with `riverctl invite accept` \
+             before sending a DM."
+        ));
+    }
+
+    let now = unix_now()?;
+    let auth = compose_group_direct_message(
+        signing_key,
+        recipient_vks,
+        &room_owner_key,
+        now,
+        now,
+        message.as_bytes(),
+    )
+    .map_err(|e| anyhow!("Failed to compose group DM: {}", e))?;
+
     let delta = ChatRoomStateV1Delta {
         members: rejoin_members,
         member_info: rejoin_member_info,
-        direct_messages: Some(
-            river_core::room_state::direct_messages::DirectMessagesDelta {
-                new_messages: vec![auth.clone()],
-                advanced_purges: vec![],
-            },
-        ),
+        group_direct_messages: Some(vec![auth.clone()]),
         ..Default::default()
     };
 
-    // Local pre-flight: walk the same apply_delta the contract will run.
+    // Local pre-flight, as in `deliver_dm`: the contract silent-drops a group
+    // message it can't authorise, so confirm ours survives the merge.
     let params = ChatRoomParametersV1 {
         owner: room_owner_key,
     };
@@ -353,74 +657,187 @@ async fn deliver_dm(
             .apply_delta(room_state, &params, &Some(delta.clone()))
             .map_err(|e| anyhow!("Local pre-flight apply_delta failed: {:?}", e))?;
     }
-
-    // Codex P2 defence-in-depth (PR #269 review): `DirectMessagesV1::apply_delta`
-    // silent-drops DMs whose sender isn't a current member, so the pre-flight
-    // above can return Ok even though our DM was dropped. Verify the message
-    // we tried to send is actually present in the post-merge state before
-    // we report success to the user.
     let landed = local
-        .direct_messages
+        .group_direct_messages
         .messages
         .iter()
         .any(|m| m.sender_signature == auth.sender_signature);
     if !landed {
         return Err(anyhow!(
-            "Local pre-flight: the DM was silently dropped by the contract \
-             (likely because the sender or recipient is not a current member). \
+            "Local pre-flight: the group DM was silently dropped by the contract \
+             (likely because a participant is not a current member). \
              Refusing to claim a successful send."
         ));
     }
 
     api.send_state_delta(&room_owner_key, &delta).await?;
 
-    let token = auth.purge_token();
-    let token_hex = hex_token(&token);
+    Ok(SentGroupDm {
+        participants,
+        thread: auth.message.thread_id(),
+        token: auth.purge_token(),
+    })
+}
 
-    // Persist a label in the local outbound-DM cache so a future `dm list`
-    // renders the sender's own bubble as text instead of
-    // `<sent: ciphertext only>` (the ciphertext is recipient-sealed, so the
-    // sender can't re-derive it). For a text DM this is the message; for an
-    // invite it's the same `[Invitation to room …]` summary the recipient's
-    // list shows. See issue freenet/river#256. Failure is logged but not
-    // fatal — the DM has already been sent.
-    if let Err(e) = persist_outbound_plaintext(
-        api,
-        room_owner_key,
-        self_id,
-        recipient_id,
-        token,
-        auth.message.timestamp,
-        cache_label,
-    ) {
-        tracing::warn!("Failed to persist outbound DM plaintext locally: {}", e);
-    }
+/// Deliver `auth` into the recipient's inbox contract. Returns `false` — send
+/// in-room instead — when this build bundles no inbox contract, the recipient
+/// has never published an inbox or has not allowed this room in it, or we
+/// can't prove both memberships from the room state we hold.
+async fn deliver_via_inbox(
+    api: &ApiClient,
+    room_owner_key: VerifyingKey,
+    room_state: &ChatRoomStateV1,
+    sender_vk: &VerifyingKey,
+    recipient_vk: &VerifyingKey,
+    auth: &AuthorizedDirectMessage,
+) -> Result<bool> {
+    if !inbox_contract_available()
+        || !api
+            .get_inbox(recipient_vk)
+            .await?
+            .is_some_and(|inbox| inbox.allows_room(&room_owner_key))
+    {
+        return Ok(false);
+    }
+    let message =
+        match build_inbox_message(room_owner_key, room_state, sender_vk, recipient_vk, auth) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("Falling back to an in-room DM: {}", e);
+                return Ok(false);
+            }
+        };
+    api.put_inbox(
+        recipient_vk,
+        &InboxStateV1 {
+            messages: vec![message],
+            ..Default::default()
+        },
+    )
+    .await?;
+    Ok(true)
+}
 
-    let noun = match kind {
-        DmKind::Text => "DM",
-        DmKind::Invite => "Invitation DM",
+/// Wrap a composed DM with both parties' membership proofs, and run the
+/// contract's own check on it — the inbox counterpart of the in-room
+/// pre-flight `apply_delta`, which the contract would otherwise fail silently.
+fn build_inbox_message(
+    room_owner_key: VerifyingKey,
+    room_state: &ChatRoomStateV1,
+    sender_vk: &VerifyingKey,
+    recipient_vk: &VerifyingKey,
+    auth: &AuthorizedDirectMessage,
+) -> std::result::Result<InboxMessageV1, String> {
+    let message = InboxMessageV1 {
+        dm: auth.clone(),
+        sender_proof: MembershipProofV1::from_room_state(room_owner_key, room_state, sender_vk)?,
+        recipient_proof: MembershipProofV1::from_room_state(
+            room_owner_key,
+            room_state,
+            recipient_vk,
+        )?,
     };
-    match format {
-        OutputFormat::Human => {
-            println!(
-                "{} sent to {} (purge token: {})",
-                noun,
-                short_member_id(&recipient_id),
-                token_hex
-            );
+    message.verify(&InboxParametersV1 {
+        owner: *recipient_vk,
+    })?;
+    Ok(message)
+}
+
+/// Our own inbox, publishing it (or a new allowlist, when it lacks a room we
+/// hold this key in) if needed — the allowlist is what tells inbox-aware
+/// senders to start delivering there. Best-effort: `dm list` still shows the
+/// room's DMs if the inbox can't be read.
+async fn load_own_inbox(api: &ApiClient, signing_key: &SigningKey) -> Option<InboxStateV1> {
+    if !inbox_contract_available() {
+        return None;
+    }
+    let self_vk = signing_key.verifying_key();
+    match api.get_inbox(&self_vk).await {
+        Ok(inbox) => {
+            let held = match api.storage().rooms_held_by(&self_vk) {
+                Ok(held) => held,
+                Err(e) => {
+                    tracing::warn!("Failed to list rooms for the DM inbox: {}", e);
+                    return inbox;
+                }
+            };
+            let current = inbox.as_ref().and_then(|i| i.rooms.as_ref());
+            if current.is_some_and(|c| held.iter().all(|room| c.allows(room))) {
+                return inbox;
+            }
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let update = InboxStateV1 {
+                rooms: Some(AuthorizedInboxRoomsV1::new(signing_key, current, now, held)),
+                ..Default::default()
+            };
+            if let Err(e) = api.put_inbox(&self_vk, &update).await {
+                tracing::warn!("Failed to publish DM inbox: {}", e);
+            }
+            inbox
         }
-        OutputFormat::Json => {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&json!({
-                    "status": "success",
-                    "recipient": recipient_id.to_string(),
-                    "purge_token": token_hex,
-                }))?
-            );
+        Err(e) => {
+            tracing::warn!("Failed to read DM inbox: {}", e);
+            None
         }
     }
-    Ok(())
+}
+
+/// Our own DMs in this room that went to peers' inboxes rather than the room.
+/// Only peers we have cached sends to (`recipients`) are asked, and only when
+/// the room doesn't already hold a DM from us to them — so a room-only
+/// conversation costs no extra GETs.
+async fn load_sent_inbox_dms(
+    api: &ApiClient,
+    room_owner_key: &VerifyingKey,
+    room_state: &ChatRoomStateV1,
+    self_id: MemberId,
+    recipients: impl Iterator<Item = MemberId>,
+) -> Vec<AuthorizedDirectMessage> {
+    if !inbox_contract_available() {
+        return Vec::new();
+    }
+    let in_room: HashSet<MemberId> = room_state
+        .direct_messages
+        .messages
+        .iter()
+        .filter(|m| m.message.sender == self_id)
+        .map(|m| m.message.recipient)
+        .collect();
+    let peers: HashSet<MemberId> = recipients.filter(|r| !in_room.contains(r)).collect();
+
+    let mut sent = Vec::new();
+    for peer in peers {
+        let Ok(peer_vk) = resolve_recipient_vk(room_state, room_owner_key, &peer.to_string())
+        else {
+            continue;
+        };
+        match api.get_inbox(&peer_vk).await {
+            Ok(Some(inbox)) => sent.extend(
+                inbox
+                    .messages_for_room(room_owner_key)
+                    .filter(|m| m.message.sender == self_id)
+                    .cloned(),
+            ),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to read {}'s DM inbox: {}", peer, e),
+        }
+    }
+    sent
+}
+
+/// The in-room DMs followed by the inbox ones, skipping any inbox copy of a
+/// message the room already holds.
+fn merge_dm_sources<'a>(
+    room: &'a [AuthorizedDirectMessage],
+    inbox: impl Iterator<Item = &'a AuthorizedDirectMessage>,
+) -> Vec<&'a AuthorizedDirectMessage> {
+    let mut seen: HashSet<PurgeToken> = room.iter().map(|m| m.purge_token()).collect();
+    let mut merged: Vec<&AuthorizedDirectMessage> = room.iter().collect();
+    merged.extend(inbox.filter(|m| seen.insert(m.purge_token())));
+    merged
 }
 
 /// Whether `member_id` is currently a member of `state` (the room owner always
@@ -579,9 +996,35 @@ async fn execute_list(
     // before the immutable borrows below.
     let secrets = api.room_display_secrets(&room_owner_key, &mut room_state);
 
-    let with_filter = with
-        .map(|s| resolve_recipient_id(&room_state, &room_owner_key, s))
+    // Members on the ignore list: their threads are not shown, and their DMs
+    // are purged for good where the entry asks for it.
+    let ignored = api.storage().load_ignore_list()?;
+    if let Err(e) =
+        purge_ignored_dms(&api, &room_owner_key, &signing_key, &room_state, &ignored).await
+    {
+        tracing::warn!("Failed to purge DMs from ignored members: {}", e);
+    }
+
+    // One member selects a pairwise thread; several select the group thread
+    // of those members plus us.
+    let with_ids = with
+        .map(|s| {
+            split_member_list(s)
+                .into_iter()
+                .map(|needle| resolve_recipient_id(&room_state, &room_owner_key, needle))
+                .collect::<Result<Vec<_>>>()
+        })
         .transpose()?;
+    let (with_filter, group_filter) = match with_ids.as_deref() {
+        None => (None, None),
+        Some([one]) => (Some(*one), None),
+        Some(many) => (
+            None,
+            Some(GroupThreadId::for_participants(
+                many.iter().copied().chain([self_id]),
+            )),
+        ),
+    };
 
     let cutoff = since_minutes.map(|m| {
         unix_now()
@@ -589,102 +1032,20 @@ async fn execute_list(
             .unwrap_or(0)
     });
 
-    // Build a nickname lookup so output is human-readable (decrypted for a
-    // private room).
-    let nicknames: HashMap<MemberId, String> = room_state
-        .member_info
-        .member_info
-        .iter()
-        .map(|info| {
-            (
-                info.member_info.member_id,
-                crate::api::unseal_nickname_display(&info.member_info.preferred_nickname, &secrets),
-            )
-        })
-        .collect();
-
-    // Load the local outbound-DM plaintext cache so we can render the
-    // sender's own bubbles as plaintext instead of `<sent: ciphertext
-    // only>`. See issue freenet/river#256. Missing entries (e.g. DMs
-    // sent before this cache shipped, or from another device) still
-    // fall back to the legacy placeholder.
-    let outbound_lookup: HashMap<(MemberId, PurgeToken), String> = api
-        .storage()
-        .load_outbound_dms()
-        .map(|store| {
-            store
-                .entries
-                .into_iter()
-                .filter(|e| e.room_owner_vk == room_owner_key.to_bytes())
-                .map(|e| ((e.recipient, e.purge_token), e.plaintext))
-                .collect()
-        })
-        .unwrap_or_else(|e| {
-            tracing::warn!("Failed to load outbound DM cache: {}", e);
-            HashMap::new()
-        });
-
-    // Walk every DM we are party to; decrypt on display.
-    let mut decrypted: Vec<DecryptedDm> = Vec::new();
-    for msg in &room_state.direct_messages.messages {
-        let is_self_sender = msg.message.sender == self_id;
-        let is_self_recipient = msg.message.recipient == self_id;
-        if !is_self_sender && !is_self_recipient {
-            continue;
-        }
-
-        let counterparty = if is_self_sender {
-            msg.message.recipient
-        } else {
-            msg.message.sender
-        };
-
-        if let Some(filter) = with_filter {
-            if counterparty != filter {
-                continue;
-            }
-        }
-
-        if let Some(cut) = cutoff {
-            if msg.message.timestamp < cut {
-                continue;
-            }
-        }
-
-        // Render the body as a String. For inbound DMs we decrypt the
-        // ECIES envelope, then decode the structured `DirectMessageBody`
-        // (which falls back to legacy raw-UTF-8 → `Text` for pre-#XXX
-        // peers). Outbound DMs go through the local plaintext cache as
-        // before — the cache stores the user-facing string regardless of
-        // wire shape.
-        let (body_str, is_invite) = if is_self_recipient {
-            match open_direct_message(&signing_key, msg) {
-                Ok(bytes) => match decode_body(&bytes) {
-                    Ok(body) => {
-                        let invite = matches!(body, DirectMessageBody::Invite(_));
-                        (format_dm_body_for_cli(&body, &nicknames), invite)
-                    }
-                    Err(_) => ("<unable to decode body>".to_string(), false),
-                },
-                Err(_) => ("<unable to decrypt>".to_string(), false),
-            }
-        } else {
-            let plaintext = match outbound_lookup.get(&(msg.message.recipient, msg.purge_token())) {
-                Some(plaintext) => plaintext.clone(),
-                None => "<sent: ciphertext only>".to_string(),
-            };
-            (plaintext, false)
-        };
-
-        decrypted.push(DecryptedDm {
-            counterparty,
-            outgoing: is_self_sender,
-            timestamp: msg.message.timestamp,
-            body: body_str,
-            token: msg.purge_token(),
-            is_invite,
-        });
-    }
+    // Build a nickname lookup so output is human-readable (decrypted for a
+    // private room).
+    let nicknames = member_nicknames(&room_state, &secrets);
+
+    let mut decrypted = if group_filter.is_some() {
+        Vec::new()
+    } else {
+        load_pairwise_dms(&api, &room_owner_key, &signing_key, &room_state, &nicknames).await
+    };
+    decrypted.retain(|dm| {
+        with_filter.is_none_or(|filter| dm.counterparty == filter)
+            && cutoff.is_none_or(|cut| dm.timestamp >= cut)
+            && !ignored.is_ignored(&dm.counterparty)
+    });
 
     // Best-effort prune: drop cached entries whose ciphertext is gone
     // from this room's state (recipient purged or contract cap
@@ -719,9 +1080,40 @@ async fn execute_list(
         }
     }
 
+    // Group threads, unless `--with` named a single counterparty.
+    let mut groups: Vec<(Vec<MemberId>, Vec<DecryptedGroupDm>)> = Vec::new();
+    if with_filter.is_none() {
+        let mut by_thread: HashMap<GroupThreadId, (Vec<MemberId>, Vec<DecryptedGroupDm>)> =
+            HashMap::new();
+        for dm in collect_group_dms(&room_state, &signing_key, group_filter, cutoff)
+            .into_iter()
+            .filter(|dm| !ignored.is_ignored(&dm.sender))
+        {
+            by_thread
+                .entry(dm.thread)
+                .or_insert_with(|| (dm.participants.clone(), Vec::new()))
+                .1
+                .push(dm);
+        }
+        groups = by_thread.into_values().collect();
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, thread) in groups.iter_mut() {
+            if thread.len() > limit {
+                let take_from = thread.len() - limit;
+                *thread = thread.split_off(take_from);
+            }
+        }
+    }
+    let display_name = |id: &MemberId| {
+        nicknames
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| short_member_id(id))
+    };
+
     match format {
         OutputFormat::Human => {
-            if by_peer.is_empty() {
+            if by_peer.is_empty() && groups.is_empty() {
                 println!("No direct messages found.");
                 return Ok(());
             }
@@ -745,6 +1137,32 @@ async fn execute_list(
                 }
                 println!();
             }
+            for (participants, thread) in &groups {
+                let names: Vec<String> = participants
+                    .iter()
+                    .filter(|id| **id != self_id)
+                    .map(display_name)
+                    .collect();
+                println!("--- Group DM with {} ---", names.join(", "));
+                for (idx, dm) in thread.iter().enumerate() {
+                    let author = if dm.sender == self_id {
+                        "you".to_string()
+                    } else {
+                        display_name(&dm.sender)
+                    };
+                    println!(
+                        "[{:>3}] {} [{}] {}",
+                        idx + 1,
+                        author,
+                        format_unix_local(dm.timestamp),
+                        dm.body
+                    );
+                    if dm.sender != self_id {
+                        println!("        purge token: {}", hex_token(&dm.token));
+                    }
+                }
+                println!();
+            }
             // Discoverability: invite-via-DM (#252) bodies render as
             // `[Invitation to room …]` but aren't actionable until the user
             // knows the accept command. Point them at it when any is present.
@@ -783,6 +1201,28 @@ async fn execute_list(
                             .collect::<Vec<_>>(),
                     })
                 })
+                .chain(groups.into_iter().map(|(participants, dms)| {
+                    json!({
+                        "group_thread": dms.first().map(|dm| dm.thread.to_string()),
+                        "participants": participants
+                            .iter()
+                            .map(|id| json!({
+                                "member_id": id.to_string(),
+                                "nickname": nicknames.get(id).cloned(),
+                            }))
+                            .collect::<Vec<_>>(),
+                        "messages": dms
+                            .into_iter()
+                            .map(|dm| json!({
+                                "direction": if dm.sender == self_id { "outgoing" } else { "incoming" },
+                                "sender": dm.sender.to_string(),
+                                "timestamp_unix": dm.timestamp,
+                                "body": dm.body,
+                                "purge_token": hex_token(&dm.token),
+                            }))
+                            .collect::<Vec<_>>(),
+                    })
+                }))
                 .collect();
             println!("{}", serde_json::to_string_pretty(&threads)?);
         }
@@ -790,6 +1230,128 @@ async fn execute_list(
     Ok(())
 }
 
+/// Display names for every member with a `member_info` record, decrypted
+/// with `secrets` in a private room.
+pub(crate) fn member_nicknames(
+    room_state: &ChatRoomStateV1,
+    secrets: &HashMap<u32, [u8; 32]>,
+) -> HashMap<MemberId, String> {
+    room_state
+        .member_info
+        .member_info
+        .iter()
+        .map(|info| {
+            (
+                info.member_info.member_id,
+                crate::api::unseal_nickname_display(&info.member_info.preferred_nickname, secrets),
+            )
+        })
+        .collect()
+}
+
+/// Every pairwise DM `signing_key` is party to in this room — from the room
+/// and, during the inbox transition, from the inbox contracts — decrypted in
+/// merge order. Outgoing bodies come from the local outbound cache.
+pub(crate) async fn load_pairwise_dms(
+    api: &ApiClient,
+    room_owner_key: &VerifyingKey,
+    signing_key: &SigningKey,
+    room_state: &ChatRoomStateV1,
+    nicknames: &HashMap<MemberId, String>,
+) -> Vec<DecryptedDm> {
+    let self_vk = signing_key.verifying_key();
+    let self_id = MemberId::from(&self_vk);
+
+    // Load the local outbound-DM plaintext cache so we can render the
+    // sender's own bubbles as plaintext instead of `<sent: ciphertext
+    // only>`. See issue freenet/river#256. Missing entries (e.g. DMs
+    // sent before this cache shipped, or from another device) still
+    // fall back to the legacy placeholder.
+    let outbound_lookup: HashMap<(MemberId, PurgeToken), String> = api
+        .storage()
+        .load_outbound_dms()
+        .map(|store| {
+            store
+                .entries
+                .into_iter()
+                .filter(|e| e.room_owner_vk == room_owner_key.to_bytes())
+                .map(|e| ((e.recipient, e.purge_token), e.plaintext))
+                .collect()
+        })
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load outbound DM cache: {}", e);
+            HashMap::new()
+        });
+
+    // The inbox side of the transition (#230 Phase 4): DMs sent to us that
+    // landed in our inbox, plus our own sends that landed in a peer's. Both
+    // empty on a build without the inbox contract.
+    let own_inbox = load_own_inbox(api, signing_key).await;
+    let sent_to_inboxes = load_sent_inbox_dms(
+        api,
+        room_owner_key,
+        room_state,
+        self_id,
+        outbound_lookup.keys().map(|(recipient, _)| *recipient),
+    )
+    .await;
+    let inbox_dms = own_inbox
+        .iter()
+        .flat_map(|inbox| inbox.messages_for_room(room_owner_key))
+        .chain(sent_to_inboxes.iter());
+
+    // Walk every DM we are party to; decrypt on display.
+    let mut decrypted: Vec<DecryptedDm> = Vec::new();
+    for msg in merge_dm_sources(&room_state.direct_messages.messages, inbox_dms) {
+        let is_self_sender = msg.message.sender == self_id;
+        let is_self_recipient = msg.message.recipient == self_id;
+        if !is_self_sender && !is_self_recipient {
+            continue;
+        }
+
+        let counterparty = if is_self_sender {
+            msg.message.recipient
+        } else {
+            msg.message.sender
+        };
+
+        // Render the body as a String. For inbound DMs we decrypt the
+        // ECIES envelope, then decode the structured `DirectMessageBody`
+        // (which falls back to legacy raw-UTF-8 → `Text` for pre-#XXX
+        // peers). Outbound DMs go through the local plaintext cache as
+        // before — the cache stores the user-facing string regardless of
+        // wire shape.
+        let (body_str, is_invite) = if is_self_recipient {
+            match open_direct_message(signing_key, msg) {
+                Ok(bytes) => match decode_body(&bytes) {
+                    Ok(body) => {
+                        let invite = matches!(body, DirectMessageBody::Invite(_));
+                        (format_dm_body_for_cli(&body, nicknames), invite)
+                    }
+                    Err(_) => ("<unable to decode body>".to_string(), false),
+                },
+                Err(_) => ("<unable to decrypt>".to_string(), false),
+            }
+        } else {
+            let plaintext = match outbound_lookup.get(&(msg.message.recipient, msg.purge_token())) {
+                Some(plaintext) => plaintext.clone(),
+                None => "<sent: ciphertext only>".to_string(),
+            };
+            (plaintext, false)
+        };
+
+        decrypted.push(DecryptedDm {
+            counterparty,
+            outgoing: is_self_sender,
+            timestamp: msg.message.timestamp,
+            body: body_str,
+            token: msg.purge_token(),
+            is_invite,
+        });
+    }
+    decrypted
+}
+
 async fn execute_purge(
     api: ApiClient,
     format: OutputFormat,
@@ -820,11 +1382,14 @@ async fn execute_purge(
         .direct_messages
         .messages
         .iter()
-        .any(|m| m.message.recipient == self_id && m.purge_token() == resolved_token);
+        .any(|m| m.message.recipient == self_id && m.purge_token() == resolved_token)
+        || room_state.group_direct_messages.messages.iter().any(|m| {
+            m.message.recipients().any(|id| id == self_id) && m.purge_token() == resolved_token
+        });
     if !matched {
-        return Err(anyhow!(
-            "No inbound DM in this room matches that purge token. Run `dm list` to confirm."
-        ));
+        // The DM may have arrived through our inbox instead (#230 Phase 4);
+        // its tombstone then lives in the inbox's envelope, not the room's.
+        return purge_from_inbox(&api, format, &room_owner_key, &signing_key, resolved_token).await;
     }
 
     // Skip if the recipient already has this token in their current envelope.
@@ -881,6 +1446,104 @@ async fn execute_purge(
                 "version": envelope.state.version,
                 "tombstone_count": envelope.state.purged.len(),
                 "purge_token": hex_token(&resolved_token),
+                "via": "room",
+            }))?
+        ),
+    }
+    Ok(())
+}
+
+/// Purge the DMs in this room from members the user ignores with
+/// `--purge-dms` (see [`IgnoreList::dm_purge_tokens`]), in one envelope.
+/// Does nothing, and sends nothing, when there are none.
+async fn purge_ignored_dms(
+    api: &ApiClient,
+    room_owner_key: &VerifyingKey,
+    signing_key: &SigningKey,
+    room_state: &ChatRoomStateV1,
+    ignored: &IgnoreList,
+) -> Result<()> {
+    let self_id = MemberId::from(&signing_key.verifying_key());
+    let tokens = ignored.dm_purge_tokens(self_id, &room_state.direct_messages);
+    if tokens.is_empty() {
+        return Ok(());
+    }
+    let previous = room_state
+        .direct_messages
+        .purges
+        .iter()
+        .find(|p| p.recipient_id == self_id);
+    let envelope = advance_recipient_purges(signing_key, room_owner_key, previous, tokens)
+        .map_err(|e| anyhow!("Failed to build purge envelope: {}", e))?;
+    let delta = ChatRoomStateV1Delta {
+        direct_messages: Some(
+            river_core::room_state::direct_messages::DirectMessagesDelta {
+                new_messages: vec![],
+                advanced_purges: vec![envelope],
+            },
+        ),
+        ..Default::default()
+    };
+    api.send_state_delta(room_owner_key, &delta).await
+}
+
+/// The inbox branch of `dm purge`: tombstone an inbound DM that was delivered
+/// to our inbox. Same guards as the room branch — the token must name a DM we
+/// received in this room and must not already be purged.
+async fn purge_from_inbox(
+    api: &ApiClient,
+    format: OutputFormat,
+    room_owner_key: &VerifyingKey,
+    signing_key: &SigningKey,
+    token: PurgeToken,
+) -> Result<()> {
+    let self_vk = signing_key.verifying_key();
+    let inbox = if inbox_contract_available() {
+        api.get_inbox(&self_vk).await?
+    } else {
+        None
+    };
+    let Some(inbox) = inbox.filter(|inbox| {
+        inbox
+            .messages_for_room(room_owner_key)
+            .any(|m| m.purge_token() == token)
+    }) else {
+        return Err(anyhow!(
+            "No inbound DM in this room matches that purge token. Run `dm list` to confirm."
+        ));
+    };
+    if inbox
+        .purges
+        .as_ref()
+        .is_some_and(|p| p.state.purged.contains(&token))
+    {
+        return Err(anyhow!(
+            "That DM is already in your purge envelope; nothing to do."
+        ));
+    }
+
+    let envelope = sign_inbox_purges(signing_key, inbox.purges.as_ref(), [token])
+        .map_err(|e| anyhow!("Failed to build purge envelope: {}", e))?;
+    let update = InboxStateV1 {
+        purges: Some(envelope.clone()),
+        ..Default::default()
+    };
+    api.put_inbox(&self_vk, &update).await?;
+
+    match format {
+        OutputFormat::Human => println!(
+            "Purge envelope sent to your inbox (version {}, {} tombstones total).",
+            envelope.state.version,
+            envelope.state.purged.len()
+        ),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "status": "success",
+                "version": envelope.state.version,
+                "tombstone_count": envelope.state.purged.len(),
+                "purge_token": hex_token(&token),
+                "via": "inbox",
             }))?
         ),
     }
@@ -1069,16 +1732,60 @@ fn select_invite_to_accept(
 // Helpers
 // ---------------------------------------------------------------------------
 
-struct DecryptedDm {
-    counterparty: MemberId,
-    outgoing: bool,
-    timestamp: u64,
-    body: String,
-    token: PurgeToken,
+pub(crate) struct DecryptedDm {
+    pub(crate) counterparty: MemberId,
+    pub(crate) outgoing: bool,
+    pub(crate) timestamp: u64,
+    pub(crate) body: String,
+    pub(crate) token: PurgeToken,
     /// True when the decrypted body decoded to a
     /// `DirectMessageBody::Invite` — drives the `dm list` "accept with…"
     /// footer tip and the `is_invitation` JSON field.
-    is_invite: bool,
+    pub(crate) is_invite: bool,
+}
+
+/// One group message this member takes part in, decrypted for `dm list`.
+pub(crate) struct DecryptedGroupDm {
+    pub(crate) thread: GroupThreadId,
+    pub(crate) participants: Vec<MemberId>,
+    pub(crate) sender: MemberId,
+    pub(crate) timestamp: u64,
+    pub(crate) body: String,
+    pub(crate) token: PurgeToken,
+}
+
+/// Decrypt the group messages `signing_key` takes part in, oldest first,
+/// skipping those it has purged (a purge hides a group message only for the
+/// member who purged it; see `group_direct_messages`).
+pub(crate) fn collect_group_dms(
+    room_state: &ChatRoomStateV1,
+    signing_key: &SigningKey,
+    thread: Option<GroupThreadId>,
+    cutoff: Option<u64>,
+) -> Vec<DecryptedGroupDm> {
+    let self_id = MemberId::from(&signing_key.verifying_key());
+    let mut out: Vec<DecryptedGroupDm> = room_state
+        .group_direct_messages
+        .messages
+        .iter()
+        .filter(|m| m.message.participants().any(|id| id == self_id))
+        .filter(|m| thread.is_none_or(|t| m.message.thread_id() == t))
+        .filter(|m| cutoff.is_none_or(|cut| m.message.timestamp >= cut))
+        .filter(|m| !m.purged_by(self_id, room_state))
+        .map(|m| DecryptedGroupDm {
+            thread: m.message.thread_id(),
+            participants: m.message.participants().collect(),
+            sender: m.message.sender,
+            timestamp: m.message.timestamp,
+            body: match open_group_direct_message(signing_key, m) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(_) => "<unable to decrypt>".to_string(),
+            },
+            token: m.purge_token(),
+        })
+        .collect();
+    out.sort_by_key(|dm| (dm.timestamp, dm.token));
+    out
 }
 
 /// One inbound DM that decoded to a `DirectMessageBody::Invite`, surfaced by
@@ -1154,8 +1861,17 @@ fn decode_invitation_from_payload(payload: &InvitePayload) -> Result<Invitation>
     Ok(invitation)
 }
 
+/// Split a comma-separated member list, dropping blanks.
+fn split_member_list(list: &str) -> Vec<&str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 fn parse_room_id(room_id: &str) -> Result<VerifyingKey> {
-    let bytes = bs58::decode(room_id)
+    let room_id = crate::config::resolve_room_arg(room_id)?;
+    let bytes = bs58::decode(&room_id)
         .into_vec()
         .map_err(|e| anyhow!("Invalid room ID: {}", e))?;
     if bytes.len() != 32 {
@@ -1169,7 +1885,7 @@ fn parse_room_id(room_id: &str) -> Result<VerifyingKey> {
     VerifyingKey::from_bytes(&arr).map_err(|e| anyhow!("Invalid room ID: {}", e))
 }
 
-fn resolve_recipient_vk(
+pub(crate) fn resolve_recipient_vk(
     state: &river_core::ChatRoomStateV1,
     room_owner_key: &VerifyingKey,
     needle: &str,
@@ -1238,7 +1954,7 @@ fn parse_hex_token(s: &str) -> Option<PurgeToken> {
     Some(PurgeToken(out))
 }
 
-fn hex_token(t: &PurgeToken) -> String {
+pub(crate) fn hex_token(t: &PurgeToken) -> String {
     let mut s = String::with_capacity(32);
     for b in &t.0 {
         s.push_str(&format!("{:02x}", b));
@@ -1390,7 +2106,7 @@ fn unix_now() -> Result<u64> {
 ///   which decodes the embedded invitation; `dm list` prints a tip pointing
 ///   there whenever an invite DM is present. Personal message (when present)
 ///   is appended.
-fn format_dm_body_for_cli(
+pub(crate) fn format_dm_body_for_cli(
     body: &DirectMessageBody,
     _nicknames: &HashMap<MemberId, String>,
 ) -> String {
@@ -1967,6 +2683,109 @@ mod tests {
         ));
     }
 
+    /// `build_inbox_message` proves both parties from the room state, so a DM
+    /// between two members verifies against the recipient's inbox — and one
+    /// to a key that isn't in the room can't be built at all.
+    #[test]
+    fn build_inbox_message_requires_both_parties_in_the_room() {
+        let owner = key(1);
+        let member = key(2);
+        let stranger = key(3);
+        let owner_vk = owner.verifying_key();
+        let owner_id = MemberId::from(&owner_vk);
+
+        let mut state = ChatRoomStateV1::default();
+        state.members.members.push(AuthorizedMember::new(
+            Member {
+                owner_member_id: owner_id,
+                member_vk: member.verifying_key(),
+                invited_by: owner_id,
+            },
+            &owner,
+        ));
+
+        let dm = dm_to_me(&owner, &member, &owner_vk, b"hi", 10);
+        let message =
+            build_inbox_message(owner_vk, &state, &owner_vk, &member.verifying_key(), &dm)
+                .expect("owner -> member DM should be deliverable to the inbox");
+        assert_eq!(message.shared_room(), &owner_vk);
+
+        let to_stranger = dm_to_me(&owner, &stranger, &owner_vk, b"hi", 11);
+        assert!(build_inbox_message(
+            owner_vk,
+            &state,
+            &owner_vk,
+            &stranger.verifying_key(),
+            &to_stranger,
+        )
+        .is_err());
+    }
+
+    /// A DM present in both the room and an inbox is listed once, room copy
+    /// first; inbox-only DMs follow.
+    #[test]
+    fn merge_dm_sources_dedups_inbox_copies_of_room_dms() {
+        let owner = key(1);
+        let member = key(2);
+        let owner_vk = owner.verifying_key();
+        let in_room = dm_to_me(&owner, &member, &owner_vk, b"room", 10);
+        let inbox_only = dm_to_me(&owner, &member, &owner_vk, b"inbox", 11);
+
+        let room = vec![in_room.clone()];
+        let inbox = [in_room.clone(), inbox_only.clone()];
+        let merged = merge_dm_sources(&room, inbox.iter());
+
+        assert_eq!(merged, vec![&in_room, &inbox_only]);
+    }
+
+    #[test]
+    fn split_member_list_trims_and_drops_blanks() {
+        assert_eq!(split_member_list("abc"), vec!["abc"]);
+        assert_eq!(
+            split_member_list(" abc, def ,,ghi,"),
+            vec!["abc", "def", "ghi"]
+        );
+        assert!(split_member_list(" , ").is_empty());
+    }
+
+    /// `dm list` shows a group message to every participant, sender included,
+    /// until that participant purges it; `--with` narrows to one thread.
+    #[test]
+    fn collect_group_dms_decrypts_filters_and_hides_own_purges() {
+        let owner = key(1);
+        let alice = key(2);
+        let bob = key(3);
+        let owner_vk = owner.verifying_key();
+        let mut state = ChatRoomStateV1::default();
+        let msg = compose_group_direct_message(
+            &owner,
+            &[alice.verifying_key(), bob.verifying_key()],
+            &owner_vk,
+            10,
+            10,
+            b"team sync?",
+        )
+        .unwrap();
+        state.group_direct_messages.messages.push(msg.clone());
+
+        for sk in [&owner, &alice, &bob] {
+            let dms = collect_group_dms(&state, sk, None, None);
+            assert_eq!(dms.len(), 1);
+            assert_eq!(dms[0].body, "team sync?");
+            assert_eq!(dms[0].participants.len(), 3);
+        }
+        let other_thread = GroupThreadId::for_participants([MemberId::from(&owner_vk)]);
+        assert!(collect_group_dms(&state, &alice, Some(other_thread), None).is_empty());
+        assert!(collect_group_dms(&state, &alice, None, Some(11)).is_empty());
+        assert!(collect_group_dms(&state, &key(4), None, None).is_empty());
+
+        let alice_purge =
+            advance_recipient_purges(&alice, &owner_vk, None, [msg.purge_token()]).unwrap();
+        state.direct_messages.purges.push(alice_purge);
+        assert!(collect_group_dms(&state, &alice, None, None).is_empty());
+        assert_eq!(collect_group_dms(&state, &bob, None, None).len(), 1);
+    }
+
     /// A blank / whitespace-only `--message` becomes `None` so the recipient's
     /// UI hides the message box rather than rendering an empty line.
     #[test]
@@ -0,0 +1,292 @@
+use crate::output::OutputFormat;
+use crate::private_room::collect_secrets_for_room;
+use crate::storage::Storage;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use clap::Subcommand;
+use ed25519_dalek::VerifyingKey;
+use river_core::chat_delegate::{Draft, DraftBody};
+use river_core::room_state::member::MemberId;
+use river_core::ChatRoomStateV1;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Unsent text kept per room, or per DM thread with `--dm`. Offline: drafts
+/// live in `drafts.json`, sealed with the room secret in a private room.
+#[derive(Subcommand)]
+pub enum DraftCommands {
+    /// Save text as the draft, replacing any earlier one
+    Set {
+        /// Room ID
+        room_id: String,
+        /// Draft text
+        text: String,
+        /// The DM thread with this member (short prefix accepted) instead of
+        /// the room
+        #[arg(long)]
+        dm: Option<String>,
+    },
+    /// Print a draft
+    Show {
+        /// Room ID
+        room_id: String,
+        /// The DM thread with this member (short prefix accepted)
+        #[arg(long)]
+        dm: Option<String>,
+    },
+    /// List every room and DM thread with a saved draft
+    List,
+    /// Discard a draft
+    Clear {
+        /// Room ID
+        room_id: String,
+        /// The DM thread with this member (short prefix accepted)
+        #[arg(long)]
+        dm: Option<String>,
+    },
+}
+
+pub fn execute(command: DraftCommands, storage: &Storage, format: OutputFormat) -> Result<()> {
+    match command {
+        DraftCommands::Set { room_id, text, dm } => {
+            let room = RoomContext::load(storage, &room_id)?;
+            let dm_peer = room.dm_peer(dm.as_deref())?;
+            let body = room.seal(&text)?;
+            save(storage, room.owner, dm_peer, body)?;
+            match format {
+                OutputFormat::Human => println!("Draft saved."),
+                OutputFormat::Json => {
+                    println!("{}", json!({ "status": "success", "message": "saved" }))
+                }
+            }
+            Ok(())
+        }
+        DraftCommands::Show { room_id, dm } => {
+            let room = RoomContext::load(storage, &room_id)?;
+            let dm_peer = room.dm_peer(dm.as_deref())?;
+            let drafts = storage.load_drafts()?;
+            let text = match drafts.get(room.owner.as_bytes(), dm_peer) {
+                Some(draft) => room.open(&draft.body)?,
+                None => String::new(),
+            };
+            match format {
+                OutputFormat::Human => println!("{text}"),
+                OutputFormat::Json => println!("{}", json!({ "text": text })),
+            }
+            Ok(())
+        }
+        DraftCommands::List => {
+            let drafts = storage.load_drafts()?;
+            let mut rooms: HashMap<[u8; 32], Option<RoomContext>> = HashMap::new();
+            let mut rows = Vec::new();
+            for draft in drafts.entries.iter().filter(|d| !d.body.is_empty()) {
+                let room = rooms.entry(draft.room_owner_vk).or_insert_with(|| {
+                    let room_id = bs58::encode(draft.room_owner_vk).into_string();
+                    RoomContext::load(storage, &room_id).ok()
+                });
+                // A draft that cannot be opened is still listed, so the user
+                // knows it exists and can clear it.
+                let text = room
+                    .as_ref()
+                    .ok_or_else(|| "room is no longer stored".to_string())
+                    .and_then(|room| room.open(&draft.body).map_err(|e| e.to_string()));
+                rows.push((draft, text));
+            }
+            match format {
+                OutputFormat::Human => {
+                    if rows.is_empty() {
+                        println!("No drafts.");
+                    }
+                    for (draft, text) in rows {
+                        let text = text.unwrap_or_else(|e| format!("(cannot open: {e})"));
+                        println!(
+                            "{}  {:<14}  {}  {}",
+                            bs58::encode(draft.room_owner_vk).into_string(),
+                            target_label(draft),
+                            format_updated_at(draft.updated_at),
+                            text.lines().next().unwrap_or_default()
+                        );
+                    }
+                }
+                OutputFormat::Json => {
+                    let rows: Vec<_> = rows
+                        .into_iter()
+                        .map(|(draft, text)| {
+                            json!({
+                                "room_id": bs58::encode(draft.room_owner_vk).into_string(),
+                                "dm": draft.dm_peer.map(|p| p.to_string()),
+                                "updated_at": draft.updated_at,
+                                "text": text.as_ref().ok(),
+                                "error": text.as_ref().err(),
+                            })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&rows)?);
+                }
+            }
+            Ok(())
+        }
+        DraftCommands::Clear { room_id, dm } => {
+            let room = RoomContext::load(storage, &room_id)?;
+            let dm_peer = room.dm_peer(dm.as_deref())?;
+            save(
+                storage,
+                room.owner,
+                dm_peer,
+                DraftBody::Plain(String::new()),
+            )?;
+            match format {
+                OutputFormat::Human => println!("Draft cleared."),
+                OutputFormat::Json => {
+                    println!("{}", json!({ "status": "success", "message": "cleared" }))
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// What a draft command needs to know about a stored room: its state, for DM
+/// peers and privacy, and the secrets this member holds for it.
+struct RoomContext {
+    owner: VerifyingKey,
+    state: ChatRoomStateV1,
+    secrets: HashMap<u32, [u8; 32]>,
+}
+
+impl RoomContext {
+    fn load(storage: &Storage, room_id: &str) -> Result<Self> {
+        let room_id = crate::config::resolve_room_arg(room_id)?;
+        let bytes: [u8; 32] = bs58::decode(&room_id)
+            .into_vec()
+            .map_err(|e| anyhow!("Invalid room ID: {e}"))?
+            .try_into()
+            .map_err(|_| anyhow!("Invalid room ID: expected 32 bytes"))?;
+        let owner =
+            VerifyingKey::from_bytes(&bytes).map_err(|e| anyhow!("Invalid room ID: {e}"))?;
+        let (self_sk, state, _) = storage.get_room(&owner)?.ok_or_else(|| {
+            anyhow!("Room not found. You must be a member of the room to keep drafts.")
+        })?;
+        let secrets =
+            collect_secrets_for_room(&state, &self_sk, &storage.get_invitation_secrets(&owner)?);
+        Ok(Self {
+            owner,
+            state,
+            secrets,
+        })
+    }
+
+    fn dm_peer(&self, needle: Option<&str>) -> Result<Option<MemberId>> {
+        needle
+            .map(|needle| super::dm::resolve_recipient_vk(&self.state, &self.owner, needle))
+            .transpose()
+            .map(|vk| vk.map(|vk| MemberId::from(&vk)))
+    }
+
+    fn seal(&self, text: &str) -> Result<DraftBody> {
+        let version = self.state.secrets.current_version;
+        DraftBody::for_room(
+            text,
+            self.state.configuration.configuration.privacy_mode
+                == river_core::room_state::privacy::PrivacyMode::Private,
+            self.secrets.get(&version).map(|secret| (secret, version)),
+        )
+        .ok_or_else(|| {
+            anyhow!(
+                "Private room secret v{version} is not available yet, so the draft cannot be \
+                 sealed. riverctl will not keep private-room drafts in plaintext."
+            )
+        })
+    }
+
+    fn open(&self, body: &DraftBody) -> Result<String> {
+        body.open(|version| self.secrets.get(&version).copied())
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+fn save(
+    storage: &Storage,
+    owner: VerifyingKey,
+    dm_peer: Option<MemberId>,
+    body: DraftBody,
+) -> Result<()> {
+    let updated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    storage.mutate_drafts(|drafts| {
+        drafts.set(Draft {
+            room_owner_vk: owner.to_bytes(),
+            dm_peer,
+            updated_at,
+            body,
+        })
+    })?;
+    Ok(())
+}
+
+fn target_label(draft: &Draft) -> String {
+    match draft.dm_peer {
+        Some(peer) => format!(
+            "dm {}",
+            peer.to_string().chars().take(8).collect::<String>()
+        ),
+        None => "room".to_string(),
+    }
+}
+
+fn format_updated_at(updated_at: u64) -> String {
+    DateTime::from_timestamp_millis(updated_at as i64)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A draft saved for a private room is sealed on disk and `draft show`
+    /// opens it; `clear` then leaves an empty draft rather than the text.
+    #[test]
+    fn private_room_drafts_are_sealed_on_disk() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = Storage::new(Some(temp_dir.path().to_str().unwrap())).unwrap();
+        let room = RoomContext {
+            owner: ed25519_dalek::SigningKey::from_bytes(&[1; 32]).verifying_key(),
+            state: private_state(),
+            secrets: HashMap::from([(0, [9; 32])]),
+        };
+
+        save(&storage, room.owner, None, room.seal("not yet").unwrap()).unwrap();
+        let on_disk = storage.load_drafts().unwrap();
+        let body = &on_disk.get(room.owner.as_bytes(), None).unwrap().body;
+        assert!(matches!(body, DraftBody::Sealed { .. }));
+        assert_eq!(room.open(body).unwrap(), "not yet");
+
+        // A later write wins; SystemTime millis may not have advanced.
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        save(&storage, room.owner, None, DraftBody::Plain(String::new())).unwrap();
+        assert!(storage
+            .load_drafts()
+            .unwrap()
+            .get(room.owner.as_bytes(), None)
+            .unwrap()
+            .body
+            .is_empty());
+
+        let no_secret = RoomContext {
+            secrets: HashMap::new(),
+            ..room
+        };
+        assert!(no_secret.seal("not yet").is_err());
+    }
+
+    fn private_state() -> ChatRoomStateV1 {
+        let mut state = ChatRoomStateV1::default();
+        state.configuration.configuration.privacy_mode =
+            river_core::room_state::privacy::PrivacyMode::Private;
+        state
+    }
+}
@@ -98,7 +98,8 @@ pub async fn execute(
 /// The reported ID reflects the identity that would actually SIGN, across all
 /// three override mechanisms, in the same precedence `message send` uses:
 /// an inline `--signing-key` / `RIVER_SIGNING_KEY` beats `--signing-key-file` /
-/// `RIVER_SIGNING_KEY_FILE`, which beats the per-room key in `rooms.json`. The
+/// `RIVER_SIGNING_KEY_FILE`, which beats a key nominated in `config.toml`
+/// (reported as `config`), which beats the per-room key in `rooms.json`. The
 /// winner is disclosed as `signing_key_source`.
 pub fn whoami(
     storage: &Storage,
@@ -110,7 +111,7 @@ pub fn whoami(
         .map(parse_inline_signing_key)
         .transpose()?;
 
-    let source = match (&inline_key, storage.has_signing_key_override()) {
+    let mut source = match (&inline_key, storage.has_signing_key_override()) {
         // `message send` takes its inline-key branch before consulting
         // storage, so an inline key wins even with a file override also set.
         (Some(_), _) => "inline",
@@ -122,6 +123,10 @@ pub fn whoami(
         Some(room_key_str) => {
             let room_owner_key = parse_room_key(room_key_str)?;
             let room_key = bs58::encode(room_owner_key.as_bytes()).into_string();
+            // A key nominated in config.toml replaces the stored one.
+            if source == "stored" && storage.has_configured_identity(&room_key) {
+                source = "config";
+            }
             let stored = storage.self_identity_with(&room_owner_key, inline_key.as_ref())?;
             let identity = match (stored, &inline_key) {
                 (Some(identity), _) => identity,
@@ -136,7 +141,7 @@ pub fn whoami(
                          `riverctl identity whoami` with no argument to list the \
                          rooms you do have, or pass `--signing-key` to report an \
                          identity without local storage.",
-                        room_key_str
+                        room_key
                     ))
                 }
             };
@@ -551,7 +556,8 @@ fn import_overwrite_refusal(room_exists: bool, force: bool, room_key_str: &str)
 }
 
 fn parse_room_key(s: &str) -> Result<VerifyingKey> {
-    let bytes = bs58::decode(s)
+    let s = crate::config::resolve_room_arg(s)?;
+    let bytes = bs58::decode(&s)
         .into_vec()
         .map_err(|e| anyhow!("Invalid base58 room key: {}", e))?;
     let bytes: [u8; 32] = bytes
@@ -28,6 +28,7 @@ pub async fn execute(command: InviteCommands, api: ApiClient, format: OutputForm
     match command {
         InviteCommands::Create { room_owner_key } => {
             // Decode the room owner key from base58
+            let room_owner_key = crate::config::resolve_room_arg(&room_owner_key)?;
             let decoded = bs58::decode(&room_owner_key)
                 .into_vec()
                 .map_err(|e| anyhow!("Failed to decode room owner key: {}", e))?;
@@ -1,12 +1,21 @@
-use crate::api::ApiClient;
+use crate::api::{ApiClient, ProfileUpdate};
 use crate::deputies::{
     display_nickname, grant_status_line, party_label, DeputyParty, ResolveError, RoomDeputies,
 };
 use crate::output::OutputFormat;
+use crate::storage::Storage;
 use anyhow::{anyhow, Result};
 use clap::Subcommand;
 use colored::Colorize;
+use ed25519_dalek::VerifyingKey;
+use river_core::chat_delegate::{IgnoredMember, VerifiedKey};
+use river_core::presence::PresenceStateV1;
 use river_core::room_state::member::MemberId;
+use river_core::room_state::member_info::{Avatar, MemberProfile};
+use river_core::room_state::privacy::SealedBytes;
+use river_core::util::safety_number;
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Subcommand)]
 pub enum MemberCommands {
@@ -14,6 +23,10 @@ pub enum MemberCommands {
     List {
         /// Room ID (owner key in base58)
         room_id: String,
+        /// Only members with a recent presence heartbeat, i.e. with River
+        /// open right now
+        #[arg(long)]
+        online: bool,
     },
     /// Set your nickname in a room
     SetNickname {
@@ -22,6 +35,42 @@ pub enum MemberCommands {
         /// Your new nickname
         nickname: String,
     },
+    /// Show a member's profile: nickname, status line, bio, avatar and deputies
+    Info {
+        /// Room ID (owner key in base58)
+        room_id: String,
+        /// Member ID to show (8-character short ID from member list)
+        member_id: String,
+    },
+    /// Set or clear your avatar, status line and bio in a room
+    ///
+    /// Fields you do not mention are left as they are. In a private room every
+    /// field is encrypted with the room secret, like your nickname.
+    SetProfile {
+        /// Room ID (owner key in base58)
+        room_id: String,
+        /// A short status line
+        #[arg(long, conflicts_with = "clear_status")]
+        status: Option<String>,
+        /// Remove your status line
+        #[arg(long)]
+        clear_status: bool,
+        /// A short bio
+        #[arg(long, conflicts_with = "clear_bio")]
+        bio: Option<String>,
+        /// Remove your bio
+        #[arg(long)]
+        clear_bio: bool,
+        /// Use a generated identicon with this seed as your avatar
+        #[arg(long, conflicts_with_all = ["avatar_file", "clear_avatar"])]
+        identicon: Option<u64>,
+        /// Use a small PNG, JPEG, GIF or WebP image as your avatar
+        #[arg(long, conflicts_with = "clear_avatar")]
+        avatar_file: Option<std::path::PathBuf>,
+        /// Remove your avatar
+        #[arg(long)]
+        clear_avatar: bool,
+    },
     /// Ban a member from a room
     Ban {
         /// Room ID (owner key in base58)
@@ -79,16 +128,295 @@ pub enum MemberCommands {
         /// Member ID to look up (8-character short ID from member list)
         member_id: String,
     },
+    /// Print the safety number you share with a member, or check it
+    ///
+    /// Compare the number with the member in person or over a call. If it
+    /// matches theirs, nobody has swapped in a key of their own: pass what
+    /// they read out to `--matches` to mark them verified. The mark holds in
+    /// every room the member's key appears in. Offline: reads `rooms.json`.
+    Verify {
+        /// Room ID (owner key in base58)
+        room_id: String,
+        /// Member ID (short prefix accepted)
+        member_id: String,
+        /// The member's copy of the safety number; marks them verified if it
+        /// matches, fails if it does not
+        #[arg(long, conflicts_with = "unmark")]
+        matches: Option<String>,
+        /// Remove the verified mark
+        #[arg(long)]
+        unmark: bool,
+    },
+    /// Ignore a member: hide their messages, reactions, mentions and DMs
+    ///
+    /// Only your own riverctl output changes; nothing is published and they
+    /// are not told. The entry holds in every room you share with them.
+    /// Offline: reads `rooms.json`.
+    Ignore {
+        /// Room ID (owner key in base58) to look the member up in
+        room_id: String,
+        /// Member ID (short prefix accepted)
+        member_id: String,
+        /// Also delete their DMs to you, for good, the next time `dm list`
+        /// runs in a room they wrote to you in
+        #[arg(long)]
+        purge_dms: bool,
+    },
+    /// Stop ignoring a member
+    Unignore {
+        /// Member ID as shown by `member ignored` (short prefix accepted)
+        member_id: String,
+    },
+    /// List the members you ignore
+    Ignored,
+}
+
+impl MemberCommands {
+    /// Whether this command needs no node connection.
+    pub fn runs_offline(&self) -> bool {
+        matches!(
+            self,
+            MemberCommands::Verify { .. }
+                | MemberCommands::Ignore { .. }
+                | MemberCommands::Unignore { .. }
+                | MemberCommands::Ignored
+        )
+    }
+}
+
+/// Run a command that [`MemberCommands::runs_offline`].
+pub fn execute_offline(
+    command: MemberCommands,
+    storage: &Storage,
+    format: OutputFormat,
+) -> Result<()> {
+    match command {
+        MemberCommands::Verify {
+            room_id,
+            member_id,
+            matches,
+            unmark,
+        } => execute_verify(storage, format, &room_id, &member_id, matches, unmark),
+        MemberCommands::Ignore {
+            room_id,
+            member_id,
+            purge_dms,
+        } => execute_ignore(storage, format, &room_id, &member_id, purge_dms),
+        MemberCommands::Unignore { member_id } => execute_unignore(storage, format, &member_id),
+        MemberCommands::Ignored => execute_ignored(storage, format),
+        _ => Err(anyhow!("This member command needs a node connection")),
+    }
+}
+
+fn execute_verify(
+    storage: &Storage,
+    format: OutputFormat,
+    room_id: &str,
+    member_id: &str,
+    matches: Option<String>,
+    unmark: bool,
+) -> Result<()> {
+    let owner_vk = parse_room_id(room_id)?;
+    let (self_sk, state, _) = storage
+        .get_room(&owner_vk)?
+        .ok_or_else(|| anyhow!("Room not found. You must be a member of the room."))?;
+    let peer_vk = super::dm::resolve_recipient_vk(&state, &owner_vk, member_id)?;
+    let self_vk = self_sk.verifying_key();
+    if peer_vk == self_vk {
+        return Err(anyhow!("That is you; pick another member to verify"));
+    }
+    let number = safety_number(&self_vk, &peer_vk);
+    let peer_id = MemberId::from(&peer_vk);
+
+    if let Some(theirs) = matches {
+        if !safety_numbers_match(&number, &theirs) {
+            return Err(anyhow!(
+                "Safety numbers do NOT match. {peer_id} was not marked verified; \
+                 check you are comparing with the right person before trusting this key."
+            ));
+        }
+        mark_verified(storage, &peer_vk, true)?;
+    } else if unmark {
+        mark_verified(storage, &peer_vk, false)?;
+    }
+    let verified = storage
+        .load_verified_keys()?
+        .is_verified(peer_vk.as_bytes());
+
+    match format {
+        OutputFormat::Human => {
+            println!("Safety number with {peer_id}:\n");
+            for row in number.split(' ').collect::<Vec<_>>().chunks(4) {
+                println!("    {}", row.join(" "));
+            }
+            println!();
+            if verified {
+                println!("{}", format!("{peer_id} is verified.").green());
+            } else {
+                println!("{peer_id} is not verified.");
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "member_id": peer_id.to_string(),
+                "safety_number": number,
+                "verified": verified,
+            })
+        ),
+    }
+    Ok(())
+}
+
+fn execute_ignore(
+    storage: &Storage,
+    format: OutputFormat,
+    room_id: &str,
+    member_id: &str,
+    purge_dms: bool,
+) -> Result<()> {
+    let owner_vk = parse_room_id(room_id)?;
+    let (self_sk, state, _) = storage
+        .get_room(&owner_vk)?
+        .ok_or_else(|| anyhow!("Room not found. You must be a member of the room."))?;
+    let peer_id = MemberId::from(&super::dm::resolve_recipient_vk(
+        &state, &owner_vk, member_id,
+    )?);
+    if peer_id == MemberId::from(&self_sk.verifying_key()) {
+        return Err(anyhow!("You can't ignore yourself"));
+    }
+    set_ignored(storage, peer_id, true, purge_dms)?;
+    match format {
+        OutputFormat::Human => {
+            println!("Ignoring {peer_id}. Their messages, reactions and DMs are hidden.");
+            if purge_dms {
+                println!("Their DMs to you will be deleted the next time you run `dm list`.");
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "member_id": peer_id.to_string(),
+                "ignored": true,
+                "purge_dms": purge_dms,
+            })
+        ),
+    }
+    Ok(())
+}
+
+fn execute_unignore(storage: &Storage, format: OutputFormat, member_id: &str) -> Result<()> {
+    let list = storage.load_ignore_list()?;
+    let matches: Vec<MemberId> = list
+        .ignored()
+        .map(|e| e.member_id)
+        .filter(|id| id.to_string().starts_with(member_id))
+        .collect();
+    let peer_id = match matches.as_slice() {
+        [id] => *id,
+        [] => return Err(anyhow!("You don't ignore anyone matching '{member_id}'")),
+        _ => {
+            return Err(anyhow!(
+                "'{member_id}' matches {} ignored members; pass a longer prefix",
+                matches.len()
+            ))
+        }
+    };
+    set_ignored(storage, peer_id, false, false)?;
+    match format {
+        OutputFormat::Human => println!("No longer ignoring {peer_id}."),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({ "member_id": peer_id.to_string(), "ignored": false })
+        ),
+    }
+    Ok(())
+}
+
+fn execute_ignored(storage: &Storage, format: OutputFormat) -> Result<()> {
+    let list = storage.load_ignore_list()?;
+    match format {
+        OutputFormat::Human => {
+            if list.ignored().next().is_none() {
+                println!("You don't ignore anyone.");
+            }
+            for entry in list.ignored() {
+                let purge = if entry.purge_dms {
+                    "  (DMs purged)"
+                } else {
+                    ""
+                };
+                println!("{}{purge}", entry.member_id);
+            }
+        }
+        OutputFormat::Json => {
+            let rows: Vec<_> = list
+                .ignored()
+                .map(|e| {
+                    serde_json::json!({
+                        "member_id": e.member_id.to_string(),
+                        "purge_dms": e.purge_dms,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+    }
+    Ok(())
+}
+
+fn set_ignored(
+    storage: &Storage,
+    member_id: MemberId,
+    ignored: bool,
+    purge_dms: bool,
+) -> Result<()> {
+    let updated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    storage.mutate_ignore_list(|list| {
+        list.set(IgnoredMember {
+            member_id,
+            ignored,
+            purge_dms,
+            updated_at,
+        })
+    })?;
+    Ok(())
+}
+
+/// Compare safety numbers by their digits alone, so spacing and line breaks
+/// in what was read out or pasted don't matter.
+fn safety_numbers_match(ours: &str, theirs: &str) -> bool {
+    let digits = |s: &str| s.chars().filter(char::is_ascii_digit).collect::<String>();
+    digits(ours) == digits(theirs)
+}
+
+fn mark_verified(storage: &Storage, peer_vk: &VerifyingKey, verified: bool) -> Result<()> {
+    let updated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    storage.mutate_verified_keys(|store| {
+        store.set(VerifiedKey {
+            member_vk: peer_vk.to_bytes(),
+            verified,
+            updated_at,
+        })
+    })?;
+    Ok(())
 }
 
 pub async fn execute(command: MemberCommands, api: ApiClient, format: OutputFormat) -> Result<()> {
     match command {
-        MemberCommands::List { room_id } => {
+        MemberCommands::List { room_id, online } => {
             if !matches!(format, OutputFormat::Json) {
                 eprintln!("Listing members of room: {}", room_id);
             }
 
             // Parse the room owner key
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let owner_key_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow!("Invalid room ID: {}", e))?;
@@ -103,6 +431,20 @@ pub async fn execute(command: MemberCommands, api: ApiClient, format: OutputForm
             // Get the room state
             let mut room_state = api.get_room(&owner_vk, false).await?;
 
+            // Last-seen time per member, when filtering on presence. No
+            // presence state at all means nobody runs a presence-aware client.
+            let last_seen: Option<HashMap<MemberId, u64>> = if online {
+                if !crate::api::presence_contract_available() {
+                    return Err(anyhow!(
+                        "This riverctl build does not bundle the presence contract"
+                    ));
+                }
+                let presence = api.get_presence(&owner_vk).await?.unwrap_or_default();
+                Some(online_since(&presence, now_secs()))
+            } else {
+                None
+            };
+
             // For a private room, collect the local member's secrets so member
             // nicknames (which are AES-256-GCM sealed) decrypt instead of
             // rendering as "[Encrypted: N bytes, vN]". Empty / no-op for a
@@ -123,21 +465,24 @@ pub async fn execute(command: MemberCommands, api: ApiClient, format: OutputForm
             // deduplicated and `party` reads the canonical record.
             let members: Vec<_> = deputies
                 .members_with_info()
+                .filter(|id| last_seen.as_ref().is_none_or(|seen| seen.contains_key(id)))
                 .map(|id| {
                     let party = deputies.party(id);
                     let granted_by: Vec<MemberId> =
                         deputized_by.get(&id).cloned().unwrap_or_default();
-                    (party, deputies.deputies_of(id).to_vec(), granted_by)
+                    (id, party, deputies.deputies_of(id).to_vec(), granted_by)
                 })
                 .collect();
 
             match format {
                 OutputFormat::Human => {
-                    if members.is_empty() {
+                    if members.is_empty() && online {
+                        println!("No members online.");
+                    } else if members.is_empty() {
                         println!("No members found in room.");
                     } else {
                         println!("\n{} member(s) found:\n", members.len());
-                        for (party, _own_deputies, granted_by) in &members {
+                        for (_id, party, _own_deputies, granted_by) in &members {
                             // Escaped and quoted: an unescaped nickname can
                             // forge a row that reads as a real deputy grant,
                             // and `colored` drops the colour that would
@@ -163,8 +508,12 @@ pub async fn execute(command: MemberCommands, api: ApiClient, format: OutputForm
                 OutputFormat::Json => {
                     let json_members: Vec<_> = members
                         .into_iter()
-                        .map(|(party, own_deputies, granted_by)| {
-                            member_list_json(&party, &own_deputies, &granted_by)
+                        .map(|(id, party, own_deputies, granted_by)| {
+                            let mut json = member_list_json(&party, &own_deputies, &granted_by);
+                            if let Some(seen) = &last_seen {
+                                json["last_seen"] = serde_json::json!(seen.get(&id));
+                            }
+                            json
                         })
                         .collect();
                     println!("{}", serde_json::to_string_pretty(&json_members)?);
@@ -178,6 +527,7 @@ pub async fn execute(command: MemberCommands, api: ApiClient, format: OutputForm
             }
 
             // Parse the room owner key
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let owner_key_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow!("Invalid room ID: {}", e))?;
@@ -211,6 +561,120 @@ pub async fn execute(command: MemberCommands, api: ApiClient, format: OutputForm
             }
             Ok(())
         }
+        MemberCommands::Info { room_id, member_id } => {
+            let owner_vk = parse_room_id(&room_id)?;
+            let mut room_state = api.get_room(&owner_vk, false).await?;
+            let secrets = api.room_display_secrets(&owner_vk, &mut room_state);
+            let deputies = RoomDeputies::new(&room_state, &owner_vk, &secrets);
+
+            let subject_id = resolve_or_explain(&deputies, &member_id)?;
+            let party = deputies.party(subject_id);
+            let profile = room_state
+                .member_info
+                .canonical(subject_id)
+                .and_then(|info| info.member_info.profile.as_ref())
+                .map(|profile| ProfileView::new(profile, &secrets))
+                .unwrap_or_default();
+            let own_deputies = deputies.deputies_of(subject_id).to_vec();
+            let granted_by = deputies.deputizers_of(subject_id);
+
+            match format {
+                OutputFormat::Human => {
+                    println!();
+                    println!("  {}", party_label(&party).green());
+                    if party.is_owner {
+                        println!("  Role:     room owner");
+                    }
+                    let unset = || "(not set)".dimmed().to_string();
+                    println!(
+                        "  Status:   {}",
+                        profile
+                            .status
+                            .as_deref()
+                            .map(display_nickname)
+                            .unwrap_or_else(unset)
+                    );
+                    println!(
+                        "  Bio:      {}",
+                        profile
+                            .bio
+                            .as_deref()
+                            .map(display_nickname)
+                            .unwrap_or_else(unset)
+                    );
+                    println!(
+                        "  Avatar:   {}",
+                        profile.avatar.clone().unwrap_or_else(unset)
+                    );
+                    for (label, ids) in [("Deputies", &own_deputies), ("Deputy of", &granted_by)] {
+                        if !ids.is_empty() {
+                            let names: Vec<String> = ids
+                                .iter()
+                                .map(|id| party_label(&deputies.party(*id)))
+                                .collect();
+                            println!("  {:<9} {}", format!("{label}:"), names.join(", "));
+                        }
+                    }
+                    println!();
+                }
+                OutputFormat::Json => {
+                    let mut json = member_list_json(&party, &own_deputies, &granted_by);
+                    json["status"] = serde_json::json!(profile.status);
+                    json["bio"] = serde_json::json!(profile.bio);
+                    json["avatar"] = serde_json::json!(profile.avatar);
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+            }
+            Ok(())
+        }
+        MemberCommands::SetProfile {
+            room_id,
+            status,
+            clear_status,
+            bio,
+            clear_bio,
+            identicon,
+            avatar_file,
+            clear_avatar,
+        } => {
+            let owner_vk = parse_room_id(&room_id)?;
+            let avatar = match (identicon, avatar_file) {
+                (Some(seed), _) => Some(Some(Avatar::Identicon(seed))),
+                (None, Some(path)) => Some(Some(read_avatar_file(&path)?)),
+                (None, None) if clear_avatar => Some(None),
+                (None, None) => None,
+            };
+            let update = ProfileUpdate {
+                avatar,
+                status: edit(status, clear_status),
+                bio: edit(bio, clear_bio),
+            };
+            if update.avatar.is_none() && update.status.is_none() && update.bio.is_none() {
+                return Err(anyhow!(
+                    "Nothing to change. Pass --status, --bio, --identicon or --avatar-file \
+                     (or one of the --clear-* flags)."
+                ));
+            }
+            if !matches!(format, OutputFormat::Json) {
+                eprintln!("Updating your profile in room: {}", room_id);
+            }
+
+            match api.set_profile(&owner_vk, update).await {
+                Ok(()) => match format {
+                    OutputFormat::Human => {
+                        println!("{}", "Profile updated successfully!".green());
+                    }
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::json!({ "success": true }));
+                    }
+                },
+                Err(e) => {
+                    eprintln!("{} {}", "Error:".red(), e);
+                    return Err(e);
+                }
+            }
+            Ok(())
+        }
         MemberCommands::Ban {
             room_id,
             member_id,
@@ -223,6 +687,7 @@ pub async fn execute(command: MemberCommands, api: ApiClient, format: OutputForm
             }
 
             // Parse the room owner key
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let owner_key_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow!("Invalid room ID: {}", e))?;
@@ -461,6 +926,10 @@ pub async fn execute(command: MemberCommands, api: ApiClient, format: OutputForm
             }
             Ok(())
         }
+        MemberCommands::Verify { .. }
+        | MemberCommands::Ignore { .. }
+        | MemberCommands::Unignore { .. }
+        | MemberCommands::Ignored => unreachable!("runs offline, before the client is built"),
     }
 }
 
@@ -480,6 +949,24 @@ fn ids_to_strings(ids: &[MemberId]) -> Vec<String> {
 /// an unreadable nickname rendered as the lossy placeholder, and `party.nickname`
 /// is `None` only for a member with no `member_info` record, who cannot appear
 /// in this listing at all.
+/// Members with a heartbeat inside the online window, and when it was sent.
+fn online_since(presence: &PresenceStateV1, now: u64) -> HashMap<MemberId, u64> {
+    let online: HashSet<MemberId> = presence.online(now).collect();
+    presence
+        .heartbeats
+        .iter()
+        .filter(|h| online.contains(&h.member_id()))
+        .map(|h| (h.member_id(), h.heartbeat.sent_at))
+        .collect()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 fn member_list_json(
     party: &DeputyParty,
     own_deputies: &[MemberId],
@@ -493,6 +980,83 @@ fn member_list_json(
     })
 }
 
+/// A member's profile decoded for display. A field that cannot be unsealed
+/// (no secret for its version) shows the same placeholder a nickname would.
+#[derive(Debug, Default)]
+struct ProfileView {
+    status: Option<String>,
+    bio: Option<String>,
+    avatar: Option<String>,
+}
+
+impl ProfileView {
+    fn new(profile: &MemberProfile, secrets: &HashMap<u32, [u8; 32]>) -> Self {
+        let text = |field: &Option<SealedBytes>| {
+            field
+                .as_ref()
+                .map(|sealed| crate::api::unseal_nickname_display(sealed, secrets))
+        };
+        let avatar =
+            profile.avatar.as_ref().map(
+                |sealed| match river_core::ecies::unseal_bytes_with_secrets(sealed, secrets) {
+                    Ok(bytes) => match Avatar::decode(&bytes) {
+                        Ok(avatar) => avatar_summary(&avatar),
+                        Err(_) => "(unreadable)".to_string(),
+                    },
+                    Err(_) => sealed.to_string_lossy(),
+                },
+            );
+        Self {
+            status: text(&profile.status),
+            bio: text(&profile.bio),
+            avatar,
+        }
+    }
+}
+
+/// One-line description of an avatar; the CLI has no way to draw it.
+fn avatar_summary(avatar: &Avatar) -> String {
+    match avatar {
+        Avatar::Identicon(seed) => format!("identicon (seed {seed})"),
+        Avatar::Image { media_type, data } => format!("{media_type} image, {} bytes", data.len()),
+    }
+}
+
+/// Read an avatar image, taking its media type from the file extension.
+fn read_avatar_file(path: &std::path::Path) -> Result<Avatar> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    let media_type = match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => {
+            return Err(anyhow!(
+                "Unsupported avatar file '{}': use a .png, .jpg, .gif or .webp image",
+                path.display()
+            ))
+        }
+    };
+    let data = std::fs::read(path)
+        .map_err(|e| anyhow!("Failed to read avatar file '{}': {}", path.display(), e))?;
+    Ok(Avatar::Image {
+        media_type: media_type.to_string(),
+        data,
+    })
+}
+
+/// `--field VALUE` / `--clear-field` as a [`ProfileUpdate`] entry.
+fn edit(value: Option<String>, clear: bool) -> Option<Option<String>> {
+    match value {
+        Some(value) => Some(Some(value)),
+        None if clear => Some(None),
+        None => None,
+    }
+}
+
 /// `"1 grant"` / `"3 grants"`, so counted output reads as English rather than
 /// as the `N thing(s)` form.
 fn count(n: usize, noun: &str) -> String {
@@ -544,7 +1108,8 @@ fn own_member_id(
 
 /// Decode a base58 room id (owner verifying key) into a `VerifyingKey`.
 fn parse_room_id(room_id: &str) -> Result<ed25519_dalek::VerifyingKey> {
-    let owner_key_bytes = bs58::decode(room_id)
+    let room_id = crate::config::resolve_room_arg(room_id)?;
+    let owner_key_bytes = bs58::decode(&room_id)
         .into_vec()
         .map_err(|e| anyhow!("Invalid room ID: {}", e))?;
     if owner_key_bytes.len() != 32 {
@@ -647,6 +1212,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_profile_flags_parse_and_conflicting_ones_are_refused() {
+        match parse(&[
+            "set-profile",
+            "ROOM",
+            "--status",
+            "away",
+            "--clear-bio",
+            "--identicon",
+            "7",
+        ])
+        .expect("must parse")
+        {
+            MemberCommands::SetProfile {
+                status,
+                clear_bio,
+                identicon,
+                clear_avatar,
+                ..
+            } => {
+                assert_eq!(status.as_deref(), Some("away"));
+                assert!(clear_bio);
+                assert_eq!(identicon, Some(7));
+                assert!(!clear_avatar);
+            }
+            other => panic!("wrong subcommand: {:?}", std::mem::discriminant(&other)),
+        }
+
+        assert!(parse(&["set-profile", "ROOM", "--bio", "x", "--clear-bio"]).is_err());
+        assert!(parse(&["set-profile", "ROOM", "--identicon", "1", "--clear-avatar"]).is_err());
+        assert!(parse(&[
+            "set-profile",
+            "ROOM",
+            "--identicon",
+            "1",
+            "--avatar-file",
+            "a.png"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn avatar_files_need_a_known_image_extension() {
+        let err = read_avatar_file(std::path::Path::new("avatar.bmp")).unwrap_err();
+        assert!(err.to_string().contains("Unsupported avatar file"), "{err}");
+    }
+
     #[test]
     fn room_id_is_required_for_both_new_commands() {
         assert!(parse(&["deputies"]).is_err());
@@ -698,4 +1310,52 @@ mod tests {
         assert_eq!(ids_to_strings(&[id]), vec![id.to_string()]);
         assert!(ids_to_strings(&[]).is_empty());
     }
+
+    /// `verify` is the one member command that runs without a node, and a
+    /// read-out safety number matches however it was spaced.
+    #[test]
+    fn verify_runs_offline_and_compares_digits_only() {
+        let verify = parse(&["verify", "ROOM", "ABCD", "--matches", "12345 67890"]).unwrap();
+        assert!(verify.runs_offline());
+        assert!(!parse(&["list", "ROOM"]).unwrap().runs_offline());
+        assert!(parse(&["verify", "ROOM", "ABCD", "--matches", "1", "--unmark"]).is_err());
+
+        assert!(safety_numbers_match("12345 67890", "1234567890\n"));
+        assert!(safety_numbers_match("12345 67890", "12345-67890"));
+        assert!(!safety_numbers_match("12345 67890", "12345 67891"));
+    }
+
+    /// The ignore list is local, so editing and listing it needs no node.
+    #[test]
+    fn ignore_commands_run_offline() {
+        let ignore = parse(&["ignore", "ROOM", "ABCD", "--purge-dms"]).unwrap();
+        assert!(matches!(
+            ignore,
+            MemberCommands::Ignore {
+                purge_dms: true,
+                ..
+            }
+        ));
+        assert!(ignore.runs_offline());
+        assert!(parse(&["unignore", "ABCD"]).unwrap().runs_offline());
+        assert!(parse(&["ignored"]).unwrap().runs_offline());
+    }
+
+    #[test]
+    fn online_since_keeps_only_recent_heartbeats() {
+        use ed25519_dalek::SigningKey;
+        use river_core::presence::{AuthorizedHeartbeatV1, ONLINE_WINDOW_SECS};
+        // The room owner needs no invite chain, so each key owns its own room.
+        let beat = |seed: u8, sent_at: u64| {
+            let sk = SigningKey::from_bytes(&[seed; 32]);
+            AuthorizedHeartbeatV1::new(&sk, sk.verifying_key(), &Default::default(), sent_at, false)
+                .unwrap()
+        };
+        let fresh = beat(1, 1_000);
+        let presence = PresenceStateV1 {
+            heartbeats: vec![fresh.clone(), beat(2, 1_000 - ONLINE_WINDOW_SECS - 1)],
+        };
+        let online = online_since(&presence, 1_000);
+        assert_eq!(online, HashMap::from([(fresh.member_id(), 1_000)]));
+    }
 }
@@ -1,12 +1,20 @@
 use crate::api::ApiClient;
-use crate::output::OutputFormat;
+use crate::message_proof::MessageProof;
+use crate::output::{FormatArg, OutputFormat};
+use crate::storage::Storage;
+use crate::transcript::{Transcript, TranscriptFormat};
 use anyhow::Result;
 use base64::Engine;
 use chrono::{DateTime, Local, Utc};
 use clap::Subcommand;
 use ed25519_dalek::{SigningKey, VerifyingKey};
+use river_core::chat_delegate::ScheduledMessage;
+use river_core::room_state::member::MemberId;
 use river_core::room_state::message::MessageId;
+use river_core::room_state::ChatRoomParametersV1;
 use serde_json::json;
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Subcommand)]
 pub enum MessageCommands {
@@ -28,6 +36,15 @@ pub enum MessageCommands {
         /// Can also be set via RIVER_SIGNING_KEY environment variable.
         #[arg(long, env = "RIVER_SIGNING_KEY")]
         signing_key: Option<String>,
+        /// Queue the message to go out at this time instead of now, e.g.
+        /// "2026-11-01T09:00Z". It is sent by `message queue run`.
+        #[arg(long, value_parser = parse_send_at, conflicts_with = "signing_key")]
+        at: Option<DateTime<Utc>>,
+    },
+    /// Manage messages queued with `send --at`
+    Queue {
+        #[command(subcommand)]
+        command: QueueCommands,
     },
     /// List recent messages in a room
     List {
@@ -39,6 +56,14 @@ pub enum MessageCommands {
         /// Show messages from the last N minutes
         #[arg(long)]
         since_minutes: Option<u64>,
+        /// Show only messages from others after your read marker (see
+        /// `room mark-read`)
+        #[arg(long)]
+        unread: bool,
+        /// Show only messages that mention you, including `@here` and `@room`
+        /// from members the room lets broadcast
+        #[arg(long)]
+        mentions: bool,
     },
     /// Stream messages from a room in real-time
     Stream {
@@ -85,7 +110,7 @@ pub enum MessageCommands {
         /// Message ID (from 'message list --json', use the signature field)
         #[arg(allow_hyphen_values = true)]
         message_id: String,
-        /// Emoji to react with (e.g., "👍", "❤️", "😂")
+        /// Emoji to react with (e.g., "👍", "❤️", "😂", or a room emoji like ":shipit:")
         emoji: String,
     },
     /// Remove a reaction from a message
@@ -110,16 +135,222 @@ pub enum MessageCommands {
         /// Reply text. Write `@nickname` to mention a member.
         message: String,
     },
+    /// Write a proof that a member posted a message.
+    ///
+    /// The proof carries the signed message and the author's invite chain up
+    /// to the room owner, so anyone can check it with `riverctl verify`
+    /// without a Freenet node.
+    Prove {
+        /// Room ID
+        room_id: String,
+        /// Message ID (from 'message list --json')
+        #[arg(allow_hyphen_values = true)]
+        message_id: String,
+        /// File to write (default: stdout)
+        #[arg(short, long)]
+        out: Option<std::path::PathBuf>,
+    },
+    /// Export the room's transcript for people outside River.
+    ///
+    /// Renders every message in the room's history with edits applied,
+    /// deletions marked, replies, reactions and mentions resolved, and
+    /// private-room bodies decrypted. The JSONL variant also carries each
+    /// message's signature and signed bytes so authorship can be checked
+    /// offline.
+    Export {
+        /// Room ID
+        room_id: String,
+        /// Transcript format: md, html or jsonl. (`--format` is the global
+        /// human/json output flag, so this one is spelled `--to`.)
+        #[arg(long, default_value = "md")]
+        to: TranscriptFormat,
+        /// File to write (default: stdout)
+        #[arg(short, long)]
+        out: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum QueueCommands {
+    /// List queued messages, soonest first
+    List {
+        /// Only show messages queued for this room
+        room_id: Option<String>,
+    },
+    /// Drop a queued message before it is sent
+    Cancel {
+        /// Queue ID (from `message queue list`)
+        id: u64,
+    },
+    /// Send queued messages as they fall due. Runs until interrupted.
+    ///
+    /// Each message is signed when it is sent, not when it was queued, so it
+    /// carries its send time and the room's current secret.
+    Run {
+        /// Seconds between checks for due messages
+        #[arg(long, default_value = "30")]
+        poll_interval: u64,
+        /// Send whatever is due now and exit (for cron)
+        #[arg(long)]
+        once: bool,
+    },
 }
 
-pub async fn execute(command: MessageCommands, api: ApiClient, format: OutputFormat) -> Result<()> {
+impl MessageCommands {
+    /// Whether this command only touches the local queue, so `main` can run
+    /// it without connecting to a node.
+    pub fn runs_offline(&self) -> bool {
+        matches!(
+            self,
+            MessageCommands::Send { at: Some(_), .. }
+                | MessageCommands::Queue {
+                    command: QueueCommands::List { .. } | QueueCommands::Cancel { .. }
+                }
+        )
+    }
+}
+
+/// Run a command for which [`MessageCommands::runs_offline`] is true.
+pub fn execute_offline(
+    command: MessageCommands,
+    storage: &Storage,
+    format: OutputFormat,
+) -> Result<()> {
     match command {
+        MessageCommands::Send {
+            room_id,
+            message,
+            at: Some(at),
+            ..
+        } => {
+            let room_owner_key = parse_room_id(&room_id)?;
+            if storage.get_room(&room_owner_key)?.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Room not found. You must be a member of the room to queue messages."
+                ));
+            }
+            let send_at = at.timestamp() as u64;
+            let id = storage.mutate_scheduled_messages(|queue| {
+                let mut id = rand::random::<u32>() as u64;
+                while queue.entries.iter().any(|m| m.id == id) {
+                    id = rand::random::<u32>() as u64;
+                }
+                queue.add(ScheduledMessage {
+                    id,
+                    room_owner_vk: room_owner_key.to_bytes(),
+                    send_at,
+                    text: message,
+                });
+                id
+            })?;
+            match format {
+                OutputFormat::Human => println!(
+                    "Message queued for {} (queue id: {id}). `riverctl message queue run` sends it.",
+                    at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                ),
+                OutputFormat::Json => println!(
+                    "{}",
+                    json!({
+                        "status": "success",
+                        "message": "queued",
+                        "queue_id": id,
+                        "send_at": at.to_rfc3339(),
+                    })
+                ),
+            }
+            Ok(())
+        }
+        MessageCommands::Queue {
+            command: QueueCommands::List { room_id },
+        } => {
+            let room = room_id.as_deref().map(parse_room_id).transpose()?;
+            let queue = storage.load_scheduled_messages()?;
+            let entries: Vec<&ScheduledMessage> = queue
+                .entries
+                .iter()
+                .filter(|m| room.is_none_or(|vk| m.room_owner_vk == vk.to_bytes()))
+                .collect();
+            match format {
+                OutputFormat::Human => {
+                    if entries.is_empty() {
+                        println!("No queued messages.");
+                    }
+                    for m in entries {
+                        println!(
+                            "{:>10}  {}  {}  {}",
+                            m.id,
+                            format_send_at(m.send_at),
+                            bs58::encode(m.room_owner_vk).into_string(),
+                            m.text
+                        );
+                    }
+                }
+                OutputFormat::Json => {
+                    let entries: Vec<_> = entries
+                        .iter()
+                        .map(|m| {
+                            json!({
+                                "queue_id": m.id,
+                                "room_id": bs58::encode(m.room_owner_vk).into_string(),
+                                "send_at": DateTime::from_timestamp(m.send_at as i64, 0)
+                                    .map(|t| t.to_rfc3339()),
+                                "text": m.text,
+                            })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&entries)?);
+                }
+            }
+            Ok(())
+        }
+        MessageCommands::Queue {
+            command: QueueCommands::Cancel { id },
+        } => {
+            if !storage.mutate_scheduled_messages(|queue| queue.cancel(id))? {
+                return Err(anyhow::anyhow!("No queued message with id {id}"));
+            }
+            match format {
+                OutputFormat::Human => println!("Queued message {id} cancelled."),
+                OutputFormat::Json => println!(
+                    "{}",
+                    json!({ "status": "success", "message": "cancelled", "queue_id": id })
+                ),
+            }
+            Ok(())
+        }
+        _ => unreachable!("only called for commands where runs_offline() is true"),
+    }
+}
+
+pub async fn execute(command: MessageCommands, api: ApiClient, format: FormatArg) -> Result<()> {
+    let ndjson = format == FormatArg::Ndjson;
+    let format = format.output();
+    if command.runs_offline() {
+        return execute_offline(command, api.storage(), format);
+    }
+    match command {
+        MessageCommands::Queue {
+            command:
+                QueueCommands::Run {
+                    poll_interval,
+                    once,
+                },
+        } => loop {
+            dispatch_due_messages(&api, format).await?;
+            if once {
+                return Ok(());
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(poll_interval.max(1))).await;
+        },
+        MessageCommands::Queue { .. } => unreachable!("list and cancel run offline"),
         MessageCommands::Send {
             room_id,
             message,
             signing_key,
+            ..
         } => {
             // Parse room ID (base58-encoded verifying key)
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let room_owner_key_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
@@ -183,8 +414,11 @@ pub async fn execute(command: MessageCommands, api: ApiClient, format: OutputFor
             room_id,
             limit,
             since_minutes,
+            unread,
+            mentions,
         } => {
             // Parse room ID
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let room_owner_key_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
@@ -211,9 +445,17 @@ pub async fn execute(command: MessageCommands, api: ApiClient, format: OutputFor
             // display_messages() so a decrypted private *deletion* hides its
             // message.
             let secrets = api.room_display_secrets(&room_owner_key, &mut room_state);
+            api.hide_ignored(&mut room_state);
 
-            // Get only display messages (non-deleted, non-action)
-            let mut messages: Vec<_> = room_state.recent_messages.display_messages().collect();
+            // Get only display messages (non-deleted, non-action, not past the
+            // room's message TTL)
+            let mut messages: Vec<_> = room_state
+                .recent_messages
+                .unexpired_display_messages(
+                    &room_state.configuration.configuration,
+                    std::time::SystemTime::now(),
+                )
+                .collect();
 
             // Apply time filter if specified
             if let Some(minutes) = since_minutes {
@@ -222,6 +464,35 @@ pub async fn execute(command: MessageCommands, api: ApiClient, format: OutputFor
                 messages.retain(|msg| msg.message.time >= cutoff_time);
             }
 
+            if unread {
+                let markers = api.storage().load_read_markers()?;
+                let self_id = api
+                    .storage()
+                    .get_room(&room_owner_key)?
+                    .map(|(sk, _, _)| MemberId::from(&sk.verifying_key()));
+                let room_bytes = room_owner_key.to_bytes();
+                messages.retain(|msg| {
+                    Some(msg.message.author) != self_id
+                        && markers.is_unread(&room_bytes, &msg.order_key())
+                });
+            }
+
+            if mentions {
+                let self_id = api
+                    .storage()
+                    .get_room(&room_owner_key)?
+                    .map(|(sk, _, _)| MemberId::from(&sk.verifying_key()))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("--mentions needs this room's identity in local storage")
+                    })?;
+                let audience = river_core::mention::BroadcastAudience::new(&room_state);
+                messages.retain(|msg| {
+                    let text =
+                        crate::api::message_raw_text_with_secrets(&room_state, msg, &secrets);
+                    audience.addresses(&text, msg.message.author, self_id)
+                });
+            }
+
             // Sort by time (newest first) and limit
             messages.sort_by(|a, b| b.message.time.cmp(&a.message.time));
             messages.truncate(limit);
@@ -232,7 +503,16 @@ pub async fn execute(command: MessageCommands, api: ApiClient, format: OutputFor
             match format {
                 OutputFormat::Human => {
                     if messages.is_empty() {
-                        println!("No messages found");
+                        println!(
+                            "{}",
+                            if mentions {
+                                "No messages mention you"
+                            } else if unread {
+                                "No unread messages"
+                            } else {
+                                "No messages found"
+                            }
+                        );
                     } else {
                         for msg in &messages {
                             let author_str = msg.message.author.to_string();
@@ -417,6 +697,7 @@ pub async fn execute(command: MessageCommands, api: ApiClient, format: OutputFor
             subscribe,
         } => {
             // Parse room ID
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let room_owner_key_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
@@ -432,7 +713,17 @@ pub async fn execute(command: MessageCommands, api: ApiClient, format: OutputFor
                 VerifyingKey::from_bytes(&room_owner_key_bytes.try_into().unwrap())
                     .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
 
-            if subscribe {
+            if ndjson {
+                api.stream_events(
+                    &room_owner_key,
+                    subscribe,
+                    poll_interval,
+                    timeout,
+                    max_messages,
+                    initial_messages,
+                )
+                .await?;
+            } else if subscribe {
                 // Use real Freenet subscriptions for updates
                 api.subscribe_and_stream(
                     &room_owner_key,
@@ -557,12 +848,173 @@ pub async fn execute(command: MessageCommands, api: ApiClient, format: OutputFor
             }
             Ok(())
         }
+        MessageCommands::Prove {
+            room_id,
+            message_id,
+            out,
+        } => {
+            let room_owner_key = parse_room_id(&room_id)?;
+            let target = parse_message_id(&message_id)?;
+            let room_state = api.get_room(&room_owner_key, false).await?;
+            let proof = MessageProof::from_state(
+                &ChatRoomParametersV1 {
+                    owner: room_owner_key,
+                },
+                &room_state.members,
+                &room_state.recent_messages.messages,
+                &target,
+            )
+            .map_err(|e| anyhow::anyhow!(e))?;
+            // Never hand out a proof that would not verify.
+            proof.verify().map_err(|e| anyhow::anyhow!(e))?;
+            let armored = proof.to_armored_string();
+
+            let Some(path) = out else {
+                print!("{}", armored);
+                return Ok(());
+            };
+            std::fs::write(&path, &armored)
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {e}", path.display()))?;
+            match format {
+                OutputFormat::Human => println!(
+                    "Proof written to {} (check it with `riverctl verify {}`)",
+                    path.display(),
+                    path.display()
+                ),
+                OutputFormat::Json => println!(
+                    "{}",
+                    json!({
+                        "status": "success",
+                        "action": "prove",
+                        "path": path.display().to_string(),
+                        "message_id": message_id,
+                    })
+                ),
+            }
+            Ok(())
+        }
+        MessageCommands::Export { room_id, to, out } => {
+            let room_owner_key = parse_room_id(&room_id)?;
+            let mut room_state = api.get_room(&room_owner_key, false).await?;
+            // Must run before the transcript is built so a decrypted private
+            // edit or deletion is reflected, exactly as in `message list`.
+            let secrets = api.room_display_secrets(&room_owner_key, &mut room_state);
+            let transcript = Transcript::build(&room_owner_key, &room_state, &secrets);
+            let rendered = transcript.render(to);
+
+            let Some(path) = out else {
+                print!("{}", rendered);
+                return Ok(());
+            };
+            std::fs::write(&path, &rendered)
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {e}", path.display()))?;
+            match format {
+                OutputFormat::Human => println!(
+                    "Exported {} messages to {} ({})",
+                    transcript.entries.len(),
+                    path.display(),
+                    to.extension()
+                ),
+                OutputFormat::Json => println!(
+                    "{}",
+                    json!({
+                        "status": "success",
+                        "action": "export",
+                        "path": path.display().to_string(),
+                        "format": to.extension(),
+                        "messages": transcript.entries.len(),
+                    })
+                ),
+            }
+            Ok(())
+        }
     }
 }
 
+/// Claim every due queued message for a room this identity holds and send
+/// it. A message that fails to send goes back on the queue for the next pass.
+async fn dispatch_due_messages(api: &ApiClient, format: OutputFormat) -> Result<()> {
+    let held: HashSet<[u8; 32]> = api
+        .storage()
+        .load_rooms()?
+        .rooms
+        .keys()
+        .filter_map(|k| bs58::decode(k).into_vec().ok()?.try_into().ok())
+        .collect();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let due = api
+        .storage()
+        .mutate_scheduled_messages(|queue| queue.take_due(now, |vk| held.contains(vk)))?;
+
+    for entry in due {
+        let sent = match VerifyingKey::from_bytes(&entry.room_owner_vk) {
+            Ok(room_owner_key) => api.send_message(&room_owner_key, entry.text.clone()).await,
+            Err(e) => Err(anyhow::anyhow!("Invalid room ID: {e}")),
+        };
+        let room_id = bs58::encode(entry.room_owner_vk).into_string();
+        match sent {
+            Ok(message_id) => match format {
+                OutputFormat::Human => println!(
+                    "Sent queued message {} to {room_id} (id: {})",
+                    entry.id, message_id.0 .0
+                ),
+                OutputFormat::Json => println!(
+                    "{}",
+                    json!({
+                        "status": "success",
+                        "message": "sent",
+                        "queue_id": entry.id,
+                        "room_id": room_id,
+                        "message_id": message_id.0 .0.to_string(),
+                    })
+                ),
+            },
+            Err(e) => {
+                eprintln!(
+                    "Failed to send queued message {}: {e:#}; will retry",
+                    entry.id
+                );
+                api.storage()
+                    .mutate_scheduled_messages(|queue| queue.add(entry))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse `--at`: RFC 3339, with seconds optional ("2026-11-01T09:00Z").
+/// Past times are rejected rather than sent immediately, since that is
+/// almost always a typo in the date.
+fn parse_send_at(s: &str) -> Result<DateTime<Utc>, String> {
+    let normalized = match s.strip_suffix(['Z', 'z']) {
+        Some(rest) => format!("{rest}+00:00"),
+        None => s.to_string(),
+    };
+    let at = DateTime::parse_from_rfc3339(&normalized)
+        .or_else(|_| DateTime::parse_from_str(&normalized, "%Y-%m-%dT%H:%M%:z"))
+        .map_err(|_| {
+            format!("expected a time like 2026-11-01T09:00Z or 2026-11-01T09:00+02:00, got {s:?}")
+        })?
+        .with_timezone(&Utc);
+    if at <= Utc::now() {
+        return Err("time is in the past; drop --at to send now".to_string());
+    }
+    Ok(at)
+}
+
+fn format_send_at(send_at: u64) -> String {
+    DateTime::from_timestamp(send_at as i64, 0)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| send_at.to_string())
+}
+
 /// Helper to parse room ID from base58-encoded string
 fn parse_room_id(room_id: &str) -> Result<VerifyingKey> {
-    let room_owner_key_bytes = bs58::decode(room_id)
+    let room_id = crate::config::resolve_room_arg(room_id)?;
+    let room_owner_key_bytes = bs58::decode(&room_id)
         .into_vec()
         .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
 
@@ -588,6 +1040,24 @@ fn parse_message_id(message_id: &str) -> Result<MessageId> {
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn send_at_accepts_minute_precision_and_rejects_the_past() {
+        use chrono::TimeZone;
+
+        assert_eq!(
+            super::parse_send_at("2099-11-01T09:00Z").unwrap(),
+            chrono::Utc.with_ymd_and_hms(2099, 11, 1, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            super::parse_send_at("2099-11-01T09:00:30+02:00").unwrap(),
+            chrono::Utc.with_ymd_and_hms(2099, 11, 1, 7, 0, 30).unwrap()
+        );
+        assert!(super::parse_send_at("2020-01-01T00:00Z")
+            .unwrap_err()
+            .contains("past"));
+        assert!(super::parse_send_at("tomorrow").is_err());
+    }
+
     /// `message reply --format json` must return the new message's ID, and in
     /// the SAME form `message list` prints and `message delete` accepts.
     ///
@@ -1,7 +1,12 @@
+pub mod chat;
+pub mod config;
 pub mod debug;
 pub mod dm;
+pub mod draft;
 pub mod identity;
 pub mod invite;
 pub mod member;
 pub mod message;
 pub mod room;
+pub mod space;
+pub mod verify;
@@ -1,9 +1,13 @@
 use crate::api::ApiClient;
 use crate::output::OutputFormat;
+use crate::snapshot::{RoomSnapshot, SnapshotManifest};
 use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
+use river_core::room_state::configuration::BroadcastMentionPolicy;
+use river_core::room_state::emoji::{parse_shortcode, EmojiImage, EmojiPack, MAX_EMOJI_PACK_SIZE};
 use river_core::room_state::privacy::SealedBytes;
+use river_core::room_state::ChatRoomParametersV1;
 
 #[derive(Subcommand)]
 pub enum RoomCommands {
@@ -95,6 +99,110 @@ pub enum RoomCommands {
         /// Set maximum room description length
         #[arg(long)]
         max_room_description: Option<usize>,
+
+        /// Set who may use @here and @room: nobody, owner,
+        /// owner-and-deputies (the default) or members
+        #[arg(long, value_parser = parse_broadcast_mentions)]
+        broadcast_mentions: Option<BroadcastMentionPolicy>,
+
+        /// Set maximum encoded avatar size in bytes
+        #[arg(long)]
+        max_avatar_size: Option<usize>,
+
+        /// Set maximum profile status line size in bytes
+        #[arg(long)]
+        max_status_size: Option<usize>,
+
+        /// Set maximum profile bio size in bytes
+        #[arg(long)]
+        max_bio_size: Option<usize>,
+
+        /// Make messages disappear after this long, e.g. 30m, 24h or 7d
+        /// (plain numbers are seconds); "off" keeps them
+        #[arg(long, value_parser = parse_message_ttl)]
+        message_ttl: Option<u64>,
+    },
+    /// Export a verifiable offline backup of a room
+    ///
+    /// Writes the signed room state, its contract parameters and a manifest
+    /// to one CBOR file. Private-room content stays sealed.
+    Export {
+        /// Room owner key (base58)
+        room_id: String,
+
+        /// File to write
+        #[arg(short, long)]
+        out: std::path::PathBuf,
+    },
+    /// Restore a room from an exported snapshot
+    ///
+    /// Verifies every signature in the snapshot, merges it into any state the
+    /// network still holds (newer content is never lost), and PUTs the result
+    /// under the current contract generation.
+    Restore {
+        /// Snapshot file written by `room export`
+        file: std::path::PathBuf,
+
+        /// Verify the snapshot offline and stop
+        #[arg(long)]
+        verify_only: bool,
+    },
+    /// Apply a declarative room policy file (owner only)
+    ///
+    /// Diffs the TOML policy against the live room and sends only what
+    /// differs: a re-signed configuration, the owner's deputy list, and the
+    /// welcome message if it is no longer in the room's retained history.
+    /// Settings the policy does not mention are left alone.
+    Apply {
+        /// Room owner key (base58)
+        room_id: String,
+
+        /// Path to the policy file
+        policy: std::path::PathBuf,
+
+        /// Show the plan without sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Mark everything currently in a room as read
+    ///
+    /// Moves your read marker to the newest message, so
+    /// `message list --unread` shows only what arrives after it. Markers
+    /// only ever move forward.
+    MarkRead {
+        /// Room owner key (base58)
+        room_id: String,
+    },
+    /// Manage the room's custom emoji (changes are owner only)
+    Emoji {
+        #[command(subcommand)]
+        command: EmojiCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EmojiCommands {
+    /// Add or replace a custom emoji, used as `:name:` in messages and
+    /// reactions
+    Add {
+        /// Room owner key (base58)
+        room_id: String,
+        /// Shortcode name, without colons (lowercase letters, digits, _ - +)
+        name: String,
+        /// Image file: PNG, GIF, WebP or JPEG, at most 2 KiB
+        image: std::path::PathBuf,
+    },
+    /// Remove a custom emoji
+    Remove {
+        /// Room owner key (base58)
+        room_id: String,
+        /// Shortcode name, with or without colons
+        name: String,
+    },
+    /// List the room's custom emoji
+    List {
+        /// Room owner key (base58)
+        room_id: String,
     },
 }
 
@@ -275,6 +383,7 @@ pub async fn execute(command: RoomCommands, api: ApiClient, format: OutputFormat
         }
         RoomCommands::Leave { room_id } => {
             // Parse the room owner key (base58) into a verifying key.
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let owner_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
@@ -326,6 +435,11 @@ pub async fn execute(command: RoomCommands, api: ApiClient, format: OutputFormat
             max_nickname_size,
             max_room_name,
             max_room_description,
+            broadcast_mentions,
+            max_avatar_size,
+            max_status_size,
+            max_bio_size,
+            message_ttl,
         } => {
             let has_changes = name.is_some()
                 || description.is_some()
@@ -335,8 +449,14 @@ pub async fn execute(command: RoomCommands, api: ApiClient, format: OutputFormat
                 || max_message_size.is_some()
                 || max_nickname_size.is_some()
                 || max_room_name.is_some()
-                || max_room_description.is_some();
+                || max_room_description.is_some()
+                || broadcast_mentions.is_some()
+                || max_avatar_size.is_some()
+                || max_status_size.is_some()
+                || max_bio_size.is_some()
+                || message_ttl.is_some();
 
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let owner_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
@@ -379,6 +499,21 @@ pub async fn execute(command: RoomCommands, api: ApiClient, format: OutputFormat
                 println!("  max_nickname_size: {}", cfg.max_nickname_size);
                 println!("  max_room_name: {}", cfg.max_room_name);
                 println!("  max_room_description: {}", cfg.max_room_description);
+                println!(
+                    "  broadcast_mentions: {}",
+                    broadcast_mentions_label(cfg.effective_broadcast_mentions())
+                );
+                println!("  max_avatar_size: {}", cfg.effective_max_avatar_size());
+                println!("  max_status_size: {}", cfg.effective_max_status_size());
+                println!("  max_bio_size: {}", cfg.effective_max_bio_size());
+                println!("  message_ttl: {}", message_ttl_label(cfg.message_ttl));
+                match load_emoji_pack(&room_state, &secrets) {
+                    Ok(pack) => println!("  custom_emoji: {}", pack.emoji.len()),
+                    Err(e) => println!("  custom_emoji: ({e})"),
+                }
+                if let Some(space) = &cfg.space {
+                    println!("  space: {}", bs58::encode(space.as_bytes()).into_string());
+                }
                 return Ok(());
             }
 
@@ -454,6 +589,21 @@ pub async fn execute(command: RoomCommands, api: ApiClient, format: OutputFormat
                     if let Some(v) = max_room_description {
                         cfg.max_room_description = v;
                     }
+                    if let Some(v) = broadcast_mentions {
+                        cfg.broadcast_mentions = Some(v);
+                    }
+                    if let Some(v) = max_avatar_size {
+                        cfg.max_avatar_size = Some(v);
+                    }
+                    if let Some(v) = max_status_size {
+                        cfg.max_status_size = Some(v);
+                    }
+                    if let Some(v) = max_bio_size {
+                        cfg.max_bio_size = Some(v);
+                    }
+                    if let Some(v) = message_ttl {
+                        cfg.message_ttl = (v > 0).then_some(v);
+                    }
                 })
                 .await
             {
@@ -488,6 +638,24 @@ pub async fn execute(command: RoomCommands, api: ApiClient, format: OutputFormat
                             if let Some(v) = max_room_description {
                                 println!("  max_room_description: {}", v);
                             }
+                            if let Some(v) = broadcast_mentions {
+                                println!("  broadcast_mentions: {}", broadcast_mentions_label(v));
+                            }
+                            if let Some(v) = max_avatar_size {
+                                println!("  max_avatar_size: {}", v);
+                            }
+                            if let Some(v) = max_status_size {
+                                println!("  max_status_size: {}", v);
+                            }
+                            if let Some(v) = max_bio_size {
+                                println!("  max_bio_size: {}", v);
+                            }
+                            if let Some(v) = message_ttl {
+                                println!(
+                                    "  message_ttl: {}",
+                                    message_ttl_label((v > 0).then_some(v))
+                                );
+                            }
                         }
                         OutputFormat::Json => {
                             println!(
@@ -507,8 +675,76 @@ pub async fn execute(command: RoomCommands, api: ApiClient, format: OutputFormat
                 }
             }
         }
+        RoomCommands::Export { room_id, out } => {
+            let owner_key = parse_room_owner_key(&room_id)?;
+            let state = api.get_room(&owner_key, false).await?;
+            let contract_key = api
+                .contract_key_for(&owner_key, crate::pointer::KeyIntent::Read)
+                .await?;
+            let snapshot = RoomSnapshot::capture(
+                ChatRoomParametersV1 { owner: owner_key },
+                state,
+                contract_key.id().to_string(),
+            )
+            .map_err(|e| anyhow::anyhow!(e))?;
+            snapshot.verify().map_err(|e| {
+                anyhow::anyhow!("Refusing to export a state that does not verify: {e}")
+            })?;
+            std::fs::write(&out, snapshot.to_bytes().map_err(|e| anyhow::anyhow!(e))?)
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {e}", out.display()))?;
+            print_manifest(&snapshot.manifest, &out.display().to_string(), format)
+        }
+        RoomCommands::Restore { file, verify_only } => {
+            let bytes = std::fs::read(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", file.display()))?;
+            let snapshot = RoomSnapshot::from_bytes(&bytes).map_err(|e| anyhow::anyhow!(e))?;
+            snapshot.verify().map_err(|e| anyhow::anyhow!(e))?;
+            if verify_only {
+                return print_manifest(&snapshot.manifest, "verified", format);
+            }
+            if !matches!(format, OutputFormat::Json) {
+                eprintln!("Restoring room {}...", snapshot.manifest.room_owner_key);
+            }
+            let (state, found_live) = api.restore_room(&snapshot).await?;
+            match format {
+                OutputFormat::Human => {
+                    println!("{}", "Room restored.".green());
+                    if found_live {
+                        println!("Merged with the state the network still held.");
+                    } else {
+                        println!("The network held no state; the snapshot was published as-is.");
+                    }
+                    println!(
+                        "  {} members, {} messages",
+                        state.members.members.len(),
+                        state.recent_messages.messages.len()
+                    );
+                }
+                OutputFormat::Json => {
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "status": "success",
+                            "room_id": snapshot.manifest.room_owner_key,
+                            "merged_with_live_state": found_live,
+                            "members": state.members.members.len(),
+                            "messages": state.recent_messages.messages.len(),
+                        })
+                    );
+                }
+            }
+            Ok(())
+        }
+        RoomCommands::Apply {
+            room_id,
+            policy,
+            dry_run,
+        } => apply_policy(&api, &room_id, &policy, dry_run, format).await,
+        RoomCommands::MarkRead { room_id } => mark_read(&api, &room_id, format).await,
+        RoomCommands::Emoji { command } => execute_emoji(&api, command, format).await,
         RoomCommands::Republish { room_id } => {
             // Parse the room owner key
+            let room_id = crate::config::resolve_room_arg(&room_id)?;
             let owner_bytes = bs58::decode(&room_id)
                 .into_vec()
                 .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
@@ -552,6 +788,438 @@ pub async fn execute(command: RoomCommands, api: ApiClient, format: OutputFormat
     }
 }
 
+/// `room apply`: plan the policy against the live room and, unless
+/// `dry_run`, send the configuration and deputy changes as ONE delta, then
+/// the welcome message through the ordinary send path (which seals it for a
+/// private room).
+async fn apply_policy(
+    api: &ApiClient,
+    room_id: &str,
+    policy_path: &std::path::Path,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    use crate::room_policy::{plan, LiveRoomSettings, RoomPolicy};
+    use river_core::room_state::configuration::AuthorizedConfigurationV1;
+    use river_core::room_state::member::MemberId;
+    use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfo};
+    use river_core::room_state::ChatRoomStateV1Delta;
+
+    let policy = RoomPolicy::load(policy_path)?;
+    let owner_key = parse_room_owner_key(room_id)?;
+    let (signing_key, _, _) = api.storage().get_room(&owner_key)?.ok_or_else(|| {
+        anyhow::anyhow!("Room not found. You must be the room owner to apply a policy.")
+    })?;
+    if signing_key.verifying_key() != owner_key {
+        return Err(anyhow::anyhow!(
+            "Only the room owner can apply a room policy"
+        ));
+    }
+    let owner_id = MemberId::from(&owner_key);
+
+    let mut state = api.get_room(&owner_key, false).await?;
+    let secrets = api.room_display_secrets(&owner_key, &mut state);
+    let unseal = |sealed: &SealedBytes| {
+        river_core::ecies::unseal_bytes_with_secrets(sealed, &secrets)
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+            .unwrap_or_else(|_| sealed.to_string_lossy())
+    };
+    let cfg = &state.configuration.configuration;
+    let owner_info = state
+        .member_info
+        .canonical(owner_id)
+        .map(|i| &i.member_info);
+    let live = LiveRoomSettings {
+        configuration: cfg,
+        name: unseal(&cfg.display.name),
+        description: cfg.display.description.as_ref().map(unseal),
+        deputies: owner_info.map(|i| i.deputies.clone()).unwrap_or_default(),
+        member_info: &state.member_info,
+        owner_message_texts: state
+            .recent_messages
+            .display_messages()
+            .filter(|m| m.message.author == owner_id)
+            .map(|m| crate::api::message_display_text_with_secrets(&state, m, &secrets))
+            .collect(),
+    };
+    let plan = plan(&policy, &live).map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    match format {
+        OutputFormat::Human => {
+            if plan.is_empty() {
+                println!("{}", "Room already matches the policy.".green());
+            } else {
+                println!(
+                    "{}",
+                    if dry_run {
+                        "Planned changes:"
+                    } else {
+                        "Applying:"
+                    }
+                );
+                for c in &plan.configuration {
+                    println!("  {}: {} -> {}", c.field, c.current, c.desired);
+                }
+                if let Some(deputies) = &plan.deputies {
+                    let ids: Vec<_> = deputies.iter().map(|d| d.to_string()).collect();
+                    println!("  deputies: [{}]", ids.join(", "));
+                }
+                if let Some(welcome) = &plan.welcome_message {
+                    println!("  post welcome message: {welcome:?}");
+                }
+            }
+        }
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "room_id": room_id,
+                    "dry_run": dry_run,
+                    "plan": plan,
+                })
+            );
+        }
+    }
+    if dry_run || plan.is_empty() {
+        return Ok(());
+    }
+
+    let mut delta = ChatRoomStateV1Delta::default();
+    if plan.changes_configuration() {
+        let seal = |text: &str| {
+            crate::private_room::seal_field_for_room(&state, &secrets, text.as_bytes())
+                .map_err(|e| anyhow::anyhow!(e))
+        };
+        let mut next = cfg.clone();
+        next.configuration_version += 1;
+        plan.apply_limits(&mut next);
+        if let Some(name) = &plan.name {
+            next.display.name = seal(name)?;
+        }
+        if let Some(description) = &plan.description {
+            next.display.description = description.as_deref().map(seal).transpose()?;
+        }
+        delta.configuration = Some(AuthorizedConfigurationV1::new(next, &signing_key));
+    }
+    if let Some(deputies) = &plan.deputies {
+        let base = owner_info.ok_or_else(|| {
+            anyhow::anyhow!(
+                "The owner has no member_info entry yet. Set a nickname first \
+                 (`member set-nickname`), then retry."
+            )
+        })?;
+        // Fitted to the limits this same delta may be lowering.
+        let limits = delta
+            .configuration
+            .as_ref()
+            .map_or(cfg, |next| &next.configuration);
+        let info = MemberInfo {
+            member_id: owner_id,
+            version: base.version + 1,
+            preferred_nickname: base.preferred_nickname.clone(),
+            deputies: deputies.clone(),
+            profile: base.profile.as_ref().and_then(|p| p.fitted_to(limits)),
+        };
+        delta.member_info = Some(vec![AuthorizedMemberInfo::new_with_member_key(
+            info,
+            &signing_key,
+        )]);
+    }
+    if delta.configuration.is_some() || delta.member_info.is_some() {
+        api.send_state_delta(&owner_key, &delta).await?;
+    }
+    if let Some(welcome) = plan.welcome_message {
+        api.send_message(&owner_key, welcome).await?;
+    }
+    if matches!(format, OutputFormat::Human) {
+        println!("{}", "Policy applied.".green());
+    }
+    Ok(())
+}
+
+/// `room mark-read`: advance the local read marker to the room's newest
+/// display message.
+async fn mark_read(api: &ApiClient, room_id: &str, format: OutputFormat) -> Result<()> {
+    let owner_key = parse_room_owner_key(room_id)?;
+    let mut state = api.get_room(&owner_key, false).await?;
+    // Rebuild private actions first so a decrypted deletion can't leave the
+    // marker on a message the user never saw.
+    api.room_display_secrets(&owner_key, &mut state);
+    let Some(latest) = state
+        .recent_messages
+        .display_messages()
+        .map(|m| m.order_key())
+        .max()
+    else {
+        match format {
+            OutputFormat::Human => println!("No messages in room {room_id}"),
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "room": room_id,
+                    "status": "empty",
+                }))?
+            ),
+        }
+        return Ok(());
+    };
+
+    let room_bytes = owner_key.to_bytes();
+    let marker = latest.clone();
+    let changed = api
+        .storage()
+        .mutate_read_markers(|store| store.advance(room_bytes, marker))?;
+    let time: chrono::DateTime<chrono::Utc> = latest.time.into();
+    match format {
+        OutputFormat::Human if changed => println!(
+            "{} up to {}",
+            "Marked read".green(),
+            time.format("%Y-%m-%d %H:%M:%S UTC")
+        ),
+        OutputFormat::Human => println!(
+            "Already read up to {}",
+            time.format("%Y-%m-%d %H:%M:%S UTC")
+        ),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "room": room_id,
+                "status": if changed { "marked" } else { "unchanged" },
+                "last_read_message_id": latest.id.0 .0.to_string(),
+                "last_read_time": time.to_rfc3339(),
+            }))?
+        ),
+    }
+    Ok(())
+}
+
+/// The room's emoji pack, unsealed and decoded. A room without one has an
+/// empty pack.
+fn load_emoji_pack(
+    state: &river_core::ChatRoomStateV1,
+    secrets: &std::collections::HashMap<u32, [u8; 32]>,
+) -> Result<EmojiPack> {
+    match &state.configuration.configuration.emoji_pack {
+        None => Ok(EmojiPack::default()),
+        Some(sealed) => {
+            let bytes = river_core::ecies::unseal_bytes_with_secrets(sealed, secrets)
+                .map_err(|e| anyhow::anyhow!("Cannot read the emoji pack: {e}"))?;
+            EmojiPack::decode(&bytes).map_err(|e| anyhow::anyhow!(e))
+        }
+    }
+}
+
+/// `room emoji`: list the pack, or edit it and re-sign the configuration.
+async fn execute_emoji(
+    api: &ApiClient,
+    command: EmojiCommands,
+    format: OutputFormat,
+) -> Result<()> {
+    let room_id = match &command {
+        EmojiCommands::Add { room_id, .. }
+        | EmojiCommands::Remove { room_id, .. }
+        | EmojiCommands::List { room_id } => room_id.clone(),
+    };
+    let owner_key = parse_room_owner_key(&room_id)?;
+    let mut state = api.get_room(&owner_key, false).await?;
+    let secrets = api.room_display_secrets(&owner_key, &mut state);
+    let mut pack = load_emoji_pack(&state, &secrets)?;
+
+    let (name, added) = match command {
+        EmojiCommands::List { .. } => {
+            match format {
+                OutputFormat::Human if pack.is_empty() => println!("No custom emoji."),
+                OutputFormat::Human => {
+                    for (name, image) in &pack.emoji {
+                        println!(
+                            "  :{}:  {} ({} bytes)",
+                            name,
+                            image.media_type,
+                            image.data.len()
+                        );
+                    }
+                }
+                OutputFormat::Json => {
+                    let emoji: Vec<_> = pack
+                        .emoji
+                        .iter()
+                        .map(|(name, image)| {
+                            serde_json::json!({
+                                "name": name,
+                                "media_type": image.media_type,
+                                "size": image.data.len(),
+                            })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&emoji)?);
+                }
+            }
+            return Ok(());
+        }
+        EmojiCommands::Add { name, image, .. } => {
+            let media_type = emoji_media_type(&image).ok_or_else(|| {
+                anyhow::anyhow!("Unrecognised image type; use a .png, .gif, .webp or .jpg file")
+            })?;
+            let data = std::fs::read(&image)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", image.display(), e))?;
+            pack.insert(
+                &name,
+                EmojiImage {
+                    media_type: media_type.to_string(),
+                    data,
+                },
+            )
+            .map_err(|e| anyhow::anyhow!(e))?;
+            (name, true)
+        }
+        EmojiCommands::Remove { name, .. } => {
+            let name = parse_shortcode(&name).unwrap_or(&name).to_string();
+            if !pack.remove(&name) {
+                return Err(anyhow::anyhow!("No custom emoji named :{name}:"));
+            }
+            (name, false)
+        }
+    };
+
+    let sealed = if pack.is_empty() {
+        None
+    } else {
+        let encoded = pack.encode();
+        if encoded.len() > MAX_EMOJI_PACK_SIZE {
+            return Err(anyhow::anyhow!(
+                "The emoji pack would be {} bytes; rooms allow {}. Remove an emoji or use a smaller image.",
+                encoded.len(),
+                MAX_EMOJI_PACK_SIZE
+            ));
+        }
+        Some(
+            crate::private_room::seal_field_for_room(&state, &secrets, &encoded)
+                .map_err(|e| anyhow::anyhow!(e))?,
+        )
+    };
+    api.update_config(&owner_key, |cfg| cfg.emoji_pack = sealed)
+        .await?;
+
+    match format {
+        OutputFormat::Human if added => println!("{} :{}:", "Added".green(), name),
+        OutputFormat::Human => println!("{} :{}:", "Removed".green(), name),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "status": "success",
+                "room_id": room_id,
+                "name": name,
+                "action": if added { "added" } else { "removed" },
+            })
+        ),
+    }
+    Ok(())
+}
+
+/// The media type for an emoji image, from its file extension.
+fn emoji_media_type(path: &std::path::Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        _ => None,
+    }
+}
+
+fn print_manifest(manifest: &SnapshotManifest, label: &str, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Human => {
+            println!("{} {}", "Snapshot".green(), label);
+            println!("  room: {}", manifest.room_owner_key);
+            println!("  contract: {}", manifest.contract_key);
+            println!(
+                "  exported: {} by {}",
+                manifest.exported_at, manifest.exported_by
+            );
+            println!(
+                "  {} members, {} messages, {} direct messages, {} bans",
+                manifest.members, manifest.messages, manifest.direct_messages, manifest.bans
+            );
+            println!("  state blake3: {}", manifest.state_blake3);
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(manifest)?),
+    }
+    Ok(())
+}
+
+fn parse_broadcast_mentions(s: &str) -> Result<BroadcastMentionPolicy, String> {
+    match s {
+        "nobody" => Ok(BroadcastMentionPolicy::Nobody),
+        "owner" => Ok(BroadcastMentionPolicy::Owner),
+        "owner-and-deputies" => Ok(BroadcastMentionPolicy::OwnerAndDeputies),
+        "members" => Ok(BroadcastMentionPolicy::Members),
+        _ => Err(format!(
+            "unknown policy {s:?}; expected nobody, owner, owner-and-deputies or members"
+        )),
+    }
+}
+
+fn broadcast_mentions_label(policy: BroadcastMentionPolicy) -> &'static str {
+    match policy {
+        BroadcastMentionPolicy::Nobody => "nobody",
+        BroadcastMentionPolicy::Owner => "owner",
+        BroadcastMentionPolicy::OwnerAndDeputies => "owner-and-deputies",
+        BroadcastMentionPolicy::Members => "members",
+    }
+}
+
+/// Seconds from "90", "30m", "24h" or "7d"; "off" (or 0) is 0, which the
+/// caller stores as no TTL.
+fn parse_message_ttl(s: &str) -> Result<u64, String> {
+    if s == "off" {
+        return Ok(0);
+    }
+    let (digits, unit) = match s.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&s[..i], c),
+        _ => (s, 's'),
+    };
+    let multiplier = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => return Err(format!("unknown unit {unit:?}; expected s, m, h or d")),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid duration {s:?}; expected e.g. 30m, 24h, 7d or off"))
+}
+
+/// The largest whole unit for a TTL, or "off".
+fn message_ttl_label(ttl: Option<u64>) -> String {
+    match ttl {
+        None | Some(0) => "off".to_string(),
+        Some(secs) => [(86_400, 'd'), (3_600, 'h'), (60, 'm')]
+            .iter()
+            .find(|(unit, _)| secs % unit == 0)
+            .map(|(unit, suffix)| format!("{}{suffix}", secs / unit))
+            .unwrap_or_else(|| format!("{secs}s")),
+    }
+}
+
+pub(crate) fn parse_room_owner_key(room_id: &str) -> Result<ed25519_dalek::VerifyingKey> {
+    let room_id = crate::config::resolve_room_arg(room_id)?;
+    let owner_bytes = bs58::decode(&room_id)
+        .into_vec()
+        .map_err(|e| anyhow::anyhow!("Invalid room ID: {}", e))?;
+    ed25519_dalek::VerifyingKey::from_bytes(
+        owner_bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Invalid room ID length"))?,
+    )
+    .map_err(|e| anyhow::anyhow!("Invalid room owner key: {}", e))
+}
+
 #[derive(serde::Serialize)]
 struct CreateRoomResult {
     room_name: String,
@@ -574,4 +1242,41 @@ mod tests {
         assert!(json["reason"].as_str().unwrap().contains("invitation"));
         assert_eq!(json["hint"], "riverctl invite accept <invitation-code>");
     }
+
+    #[test]
+    fn broadcast_mentions_labels_parse_back() {
+        for policy in [
+            BroadcastMentionPolicy::Nobody,
+            BroadcastMentionPolicy::Owner,
+            BroadcastMentionPolicy::OwnerAndDeputies,
+            BroadcastMentionPolicy::Members,
+        ] {
+            assert_eq!(
+                parse_broadcast_mentions(broadcast_mentions_label(policy)),
+                Ok(policy)
+            );
+        }
+        assert!(parse_broadcast_mentions("everyone").is_err());
+    }
+
+    #[test]
+    fn emoji_media_type_follows_the_extension() {
+        use std::path::Path;
+        assert_eq!(emoji_media_type(Path::new("shipit.PNG")), Some("image/png"));
+        assert_eq!(emoji_media_type(Path::new("a/b.jpeg")), Some("image/jpeg"));
+        assert_eq!(emoji_media_type(Path::new("x.svg")), None);
+        assert_eq!(emoji_media_type(Path::new("noext")), None);
+    }
+
+    #[test]
+    fn message_ttl_labels_parse_back() {
+        for secs in [45, 90, 30 * 60, 24 * 3_600, 7 * 86_400] {
+            assert_eq!(parse_message_ttl(&message_ttl_label(Some(secs))), Ok(secs));
+        }
+        assert_eq!(parse_message_ttl("90"), Ok(90));
+        assert_eq!(parse_message_ttl("off"), Ok(0));
+        assert_eq!(message_ttl_label(None), "off");
+        assert!(parse_message_ttl("3w").is_err());
+        assert!(parse_message_ttl("h").is_err());
+    }
 }
@@ -0,0 +1,796 @@
+use crate::api::{ApiClient, BanSafety, Invitation};
+use crate::output::OutputFormat;
+use crate::storage::{Storage, StoredSpaceInfo};
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use river_core::room_state::member::{AuthorizedMember, Member, MemberId};
+use river_core::space::{
+    AuthorizedRoomGrantV1, AuthorizedSpaceBanV1, AuthorizedSpaceConfigV1, AuthorizedSpaceMemberV1,
+    SpaceConfigV1, SpaceDeltaV1, SpaceParametersV1, SpaceStateV1, MAX_SPACE_NAME_LEN,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Subcommand)]
+pub enum SpaceCommands {
+    /// Create a space
+    Create {
+        /// Space name
+        name: String,
+    },
+    /// Add a room to a space
+    ///
+    /// Run by the space owner, this lists the room in the space. Run by the
+    /// room owner, it points the room at the space. A room is grouped under a
+    /// space once both have happened (the same person may do both at once).
+    AddRoom {
+        /// Space owner key (base58) or name
+        space: String,
+        /// Room owner key (base58)
+        room: String,
+    },
+    /// Make a space member an admin (space owner only)
+    AddAdmin {
+        /// Space owner key (base58) or name
+        space: String,
+        /// Member ID, or a unique prefix of one
+        member: String,
+    },
+    /// Invite someone to a space (owner or admin)
+    ///
+    /// Also grants them access to every room of the space you belong to.
+    Invite {
+        /// Space owner key (base58) or name
+        space: String,
+    },
+    /// Join a space and every room it has granted you access to
+    Join {
+        /// Space invitation code
+        invitation_code: String,
+
+        /// Your nickname in the space's rooms
+        #[arg(short = 'N', long)]
+        nickname: Option<String>,
+    },
+    /// Ban a member from a space and from its rooms (owner or admin)
+    Ban {
+        /// Space owner key (base58) or name
+        space: String,
+        /// Member ID, or a unique prefix of one
+        member: String,
+    },
+    /// Bring a space's rooms up to date
+    ///
+    /// As an admin: grant every member access to the rooms you belong to and
+    /// apply space bans there. As a member: join rooms you have been granted.
+    Sync {
+        /// Space owner key (base58) or name
+        space: String,
+
+        /// Your nickname in newly joined rooms
+        #[arg(short = 'N', long)]
+        nickname: Option<String>,
+    },
+    /// List the spaces you have created or joined
+    List,
+    /// Show a space's rooms, admins, members and bans
+    Show {
+        /// Space owner key (base58) or name
+        space: String,
+    },
+}
+
+/// Space invitation artifact, base58 CBOR like a room [`Invitation`]. The
+/// member key is generated by the inviter and is a bearer credential in the
+/// same way.
+#[derive(Serialize, Deserialize)]
+struct SpaceInvitation {
+    space: VerifyingKey,
+    member_signing_key: SigningKey,
+}
+
+/// What [`sync_space`] did.
+#[derive(Default, Serialize)]
+struct SyncReport {
+    granted: usize,
+    rooms_banned_in: Vec<String>,
+    joined: Vec<String>,
+    warnings: Vec<String>,
+}
+
+pub async fn execute(command: SpaceCommands, api: ApiClient, format: OutputFormat) -> Result<()> {
+    if !crate::api::space_contract_available() {
+        return Err(anyhow!(
+            "This riverctl build does not bundle the space contract, so spaces are unavailable"
+        ));
+    }
+    match command {
+        SpaceCommands::Create { name } => create(&api, name, format).await,
+        SpaceCommands::AddRoom { space, room } => add_room(&api, &space, &room, format).await,
+        SpaceCommands::AddAdmin { space, member } => add_admin(&api, &space, &member, format).await,
+        SpaceCommands::Invite { space } => invite(&api, &space, format).await,
+        SpaceCommands::Join {
+            invitation_code,
+            nickname,
+        } => join(&api, &invitation_code, nickname, format).await,
+        SpaceCommands::Ban { space, member } => ban(&api, &space, &member, format).await,
+        SpaceCommands::Sync { space, nickname } => {
+            let (space_vk, sk) = resolve_space(&api, &space)?;
+            let report = sync_space(&api, &space_vk, &sk, nickname).await?;
+            print_report(format, &report);
+            Ok(())
+        }
+        SpaceCommands::List => list(api.storage(), format),
+        SpaceCommands::Show { space } => show(&api, &space, format).await,
+    }
+}
+
+fn space_id(space: &VerifyingKey) -> String {
+    bs58::encode(space.as_bytes()).into_string()
+}
+
+/// A stored space by base58 owner key or exact name, with the user's key in
+/// it.
+fn resolve_space(api: &ApiClient, arg: &str) -> Result<(VerifyingKey, SigningKey)> {
+    let spaces = api.storage().load_spaces()?;
+    let mut matches = spaces
+        .spaces
+        .iter()
+        .filter(|(id, info)| id.as_str() == arg || info.name == arg);
+    let (id, info) = matches
+        .next()
+        .ok_or_else(|| anyhow!("No space '{}'. Use 'space list' to see your spaces.", arg))?;
+    if matches.next().is_some() {
+        return Err(anyhow!(
+            "More than one space is named '{}'; use its key instead",
+            arg
+        ));
+    }
+    let bytes: [u8; 32] = bs58::decode(id)
+        .into_vec()
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| anyhow!("Stored space key '{}' is malformed", id))?;
+    let space_vk = VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| anyhow!("Stored space key '{}' is invalid: {}", id, e))?;
+    Ok((space_vk, SigningKey::from_bytes(&info.signing_key_bytes)))
+}
+
+/// The space's current state, which must have a config.
+async fn fetch_space(api: &ApiClient, space: &VerifyingKey) -> Result<SpaceStateV1> {
+    api.get_space(space)
+        .await?
+        .filter(|state| state.config.is_some())
+        .ok_or_else(|| anyhow!("Space {} was not found on the network", space_id(space)))
+}
+
+fn remember_space(
+    api: &ApiClient,
+    space: &VerifyingKey,
+    sk: &SigningKey,
+    name: &str,
+) -> Result<()> {
+    api.storage().mutate_spaces(|spaces| {
+        spaces.spaces.insert(
+            space_id(space),
+            StoredSpaceInfo {
+                signing_key_bytes: sk.to_bytes(),
+                name: name.to_string(),
+            },
+        );
+    })
+}
+
+/// The space member whose id is, or starts with, `arg`.
+fn find_member<'a>(state: &'a SpaceStateV1, arg: &str) -> Result<&'a AuthorizedSpaceMemberV1> {
+    let mut matches = state
+        .members
+        .iter()
+        .filter(|m| m.member_id().to_string().starts_with(arg));
+    let member = matches
+        .next()
+        .ok_or_else(|| anyhow!("No space member matches '{}'", arg))?;
+    if matches.next().is_some() {
+        return Err(anyhow!("'{}' matches more than one space member", arg));
+    }
+    Ok(member)
+}
+
+/// Re-sign the space config with `modify` applied and the version bumped.
+fn next_config(
+    state: &SpaceStateV1,
+    owner_sk: &SigningKey,
+    modify: impl FnOnce(&mut SpaceConfigV1),
+) -> Result<AuthorizedSpaceConfigV1> {
+    let mut config = state
+        .config
+        .as_ref()
+        .map(|c| c.config.clone())
+        .ok_or_else(|| anyhow!("Space has no configuration"))?;
+    modify(&mut config);
+    config.version += 1;
+    Ok(AuthorizedSpaceConfigV1::new(config, owner_sk))
+}
+
+fn require_owner(space: &VerifyingKey, sk: &SigningKey) -> Result<()> {
+    if sk.verifying_key() != *space {
+        return Err(anyhow!(
+            "Only the space owner can change the space's rooms and admins"
+        ));
+    }
+    Ok(())
+}
+
+async fn create(api: &ApiClient, name: String, format: OutputFormat) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_SPACE_NAME_LEN {
+        return Err(anyhow!("Space name must be 1-{} bytes", MAX_SPACE_NAME_LEN));
+    }
+    let owner_sk = SigningKey::from_bytes(&rand::Rng::gen::<[u8; 32]>(&mut rand::thread_rng()));
+    let space = owner_sk.verifying_key();
+    let config = AuthorizedSpaceConfigV1::new(
+        SpaceConfigV1 {
+            version: 1,
+            name: name.clone(),
+            rooms: Vec::new(),
+            admins: Vec::new(),
+        },
+        &owner_sk,
+    );
+    api.publish_space_delta(
+        &space,
+        SpaceDeltaV1 {
+            config: Some(config),
+            ..Default::default()
+        },
+    )
+    .await?;
+    remember_space(api, &space, &owner_sk, &name)?;
+
+    match format {
+        OutputFormat::Human => {
+            println!("{}", "Space created successfully!".green());
+            println!("Space key: {}", space_id(&space));
+            println!("\nAdd rooms with:");
+            println!("  riverctl space add-room {} <room>", space_id(&space));
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({ "status": "success", "space": space_id(&space), "name": name })
+        ),
+    }
+    Ok(())
+}
+
+async fn add_room(
+    api: &ApiClient,
+    space_arg: &str,
+    room: &str,
+    format: OutputFormat,
+) -> Result<()> {
+    let room_vk = super::room::parse_room_owner_key(room)?;
+    // The room owner may not be in the space at all, so a raw key is
+    // accepted for the back-pointer alone.
+    let stored = resolve_space(api, space_arg).ok();
+    let space = match &stored {
+        Some((space, _)) => *space,
+        None => super::room::parse_room_owner_key(space_arg).map_err(|_| {
+            anyhow!(
+                "No space '{}'. Use 'space list' to see your spaces.",
+                space_arg
+            )
+        })?,
+    };
+
+    let mut listed = false;
+    if let Some((_, sk)) = stored
+        .as_ref()
+        .filter(|(space, sk)| sk.verifying_key() == *space)
+    {
+        let state = fetch_space(api, &space).await?;
+        if !state.lists_room(&room_vk) {
+            let config = next_config(&state, sk, |c| c.rooms.push(room_vk))?;
+            config
+                .verify(&SpaceParametersV1 { owner: space })
+                .map_err(|e| anyhow!(e))?;
+            api.publish_space_delta(
+                &space,
+                SpaceDeltaV1 {
+                    config: Some(config),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        listed = true;
+    }
+
+    let owns_room = api
+        .storage()
+        .get_room(&room_vk)?
+        .is_some_and(|(sk, _, _)| sk.verifying_key() == room_vk);
+    if owns_room {
+        api.update_config(&room_vk, |cfg| cfg.space = Some(space))
+            .await?;
+    }
+
+    if !listed && !owns_room {
+        return Err(anyhow!(
+            "Only the space owner can list a room, and only the room owner can point it at a space"
+        ));
+    }
+    match format {
+        OutputFormat::Human => {
+            if listed {
+                println!("Room {} is listed in the space.", room);
+            }
+            if owns_room {
+                println!("Room {} now points at the space.", room);
+            }
+            if listed != owns_room {
+                let other = if listed { "room owner" } else { "space owner" };
+                println!(
+                    "The {} must also run 'riverctl space add-room {} {}'.",
+                    other,
+                    space_id(&space),
+                    room
+                );
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "space": space_id(&space),
+                "room": bs58::encode(room_vk.as_bytes()).into_string(),
+                "listed": listed,
+                "room_points_at_space": owns_room,
+            })
+        ),
+    }
+    Ok(())
+}
+
+async fn add_admin(
+    api: &ApiClient,
+    space_arg: &str,
+    member: &str,
+    format: OutputFormat,
+) -> Result<()> {
+    let (space, sk) = resolve_space(api, space_arg)?;
+    require_owner(&space, &sk)?;
+    let state = fetch_space(api, &space).await?;
+    let admin = find_member(&state, member)?;
+    let admin_vk = admin.member.member_vk;
+    let admin_id = admin.member_id();
+    if !state.is_admin(&admin_vk) {
+        let config = next_config(&state, &sk, |c| c.admins.push(admin_vk))?;
+        config
+            .verify(&SpaceParametersV1 { owner: space })
+            .map_err(|e| anyhow!(e))?;
+        api.publish_space_delta(
+            &space,
+            SpaceDeltaV1 {
+                config: Some(config),
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+    match format {
+        OutputFormat::Human => println!("{} is now an admin of the space.", admin_id),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({ "space": space_id(&space), "admin": admin_id.to_string() })
+        ),
+    }
+    Ok(())
+}
+
+/// Grants for `members` in every room of the space the user holds a key
+/// for. The room key signs the member entry, so the user is the member's
+/// inviter in that room.
+fn build_grants<'a>(
+    api: &ApiClient,
+    space: &VerifyingKey,
+    space_sk: &SigningKey,
+    state: &SpaceStateV1,
+    members: impl Fn(&VerifyingKey) -> Vec<&'a AuthorizedSpaceMemberV1>,
+) -> Result<Vec<AuthorizedRoomGrantV1>> {
+    let mut grants = Vec::new();
+    for room in state.rooms() {
+        let Some((room_sk, _, _)) = api.storage().get_room(room)? else {
+            continue;
+        };
+        for m in members(room) {
+            if m.member.member_vk == room_sk.verifying_key() {
+                continue;
+            }
+            let member = AuthorizedMember::new(
+                Member {
+                    owner_member_id: MemberId::from(room),
+                    invited_by: MemberId::from(&room_sk.verifying_key()),
+                    member_vk: m.member.member_vk,
+                },
+                &room_sk,
+            );
+            grants.push(AuthorizedRoomGrantV1::new(*space, *room, member, space_sk));
+        }
+    }
+    Ok(grants)
+}
+
+async fn invite(api: &ApiClient, space_arg: &str, format: OutputFormat) -> Result<()> {
+    let (space, sk) = resolve_space(api, space_arg)?;
+    let state = fetch_space(api, &space).await?;
+    let params = SpaceParametersV1 { owner: space };
+    if !state.can_moderate(&sk.verifying_key(), &params) {
+        return Err(anyhow!("Only the space owner or an admin can invite"));
+    }
+
+    let member_sk = SigningKey::from_bytes(&rand::Rng::gen::<[u8; 32]>(&mut rand::thread_rng()));
+    let member = AuthorizedSpaceMemberV1::new(space, member_sk.verifying_key(), &sk);
+    // Grants are checked against the members held, so the new member must
+    // be in the state the grants are built against.
+    let mut with_member = state.clone();
+    with_member.members.push(member.clone());
+    let grants = build_grants(api, &space, &sk, &with_member, |_| vec![&member])?;
+    let granted = grants.len();
+    api.publish_space_delta(
+        &space,
+        SpaceDeltaV1 {
+            members: vec![member],
+            grants,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let mut data = Vec::new();
+    ciborium::ser::into_writer(
+        &SpaceInvitation {
+            space,
+            member_signing_key: member_sk,
+        },
+        &mut data,
+    )
+    .map_err(|e| anyhow!("Failed to serialize space invitation: {}", e))?;
+    let code = bs58::encode(data).into_string();
+
+    match format {
+        OutputFormat::Human => {
+            println!("{}", "Space invitation created successfully!".green());
+            println!("Access granted to {} room(s).", granted);
+            println!("\nInvitation code:");
+            println!("{}", code.bright_yellow());
+            println!("\nThey can accept it with:");
+            println!("  riverctl space join {}", code);
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "status": "success",
+                "invitation_code": code,
+                "rooms_granted": granted,
+            })
+        ),
+    }
+    Ok(())
+}
+
+async fn join(
+    api: &ApiClient,
+    invitation_code: &str,
+    nickname: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let decoded = bs58::decode(invitation_code)
+        .into_vec()
+        .map_err(|e| anyhow!("Failed to decode space invitation: {}", e))?;
+    let invitation: SpaceInvitation = ciborium::de::from_reader(&decoded[..])
+        .map_err(|e| anyhow!("Failed to deserialize space invitation: {}", e))?;
+    let space = invitation.space;
+    let sk = invitation.member_signing_key;
+
+    let state = fetch_space(api, &space).await?;
+    if state.member(&MemberId::from(&sk.verifying_key())).is_none() {
+        return Err(anyhow!(
+            "This invitation is not (or no longer) valid for the space"
+        ));
+    }
+    remember_space(api, &space, &sk, state.name().unwrap_or_default())?;
+    let report = sync_space(api, &space, &sk, nickname).await?;
+
+    if matches!(format, OutputFormat::Human) {
+        println!(
+            "{} {}",
+            "Joined space".green(),
+            state.name().unwrap_or_default()
+        );
+        println!("Space key: {}", space_id(&space));
+    }
+    print_report(format, &report);
+    Ok(())
+}
+
+async fn ban(api: &ApiClient, space_arg: &str, member: &str, format: OutputFormat) -> Result<()> {
+    let (space, sk) = resolve_space(api, space_arg)?;
+    let state = fetch_space(api, &space).await?;
+    let params = SpaceParametersV1 { owner: space };
+    if !state.can_moderate(&sk.verifying_key(), &params) {
+        return Err(anyhow!("Only the space owner or an admin can ban"));
+    }
+    let target = find_member(&state, member)?;
+    if state.is_admin(&target.member.member_vk) {
+        return Err(anyhow!(
+            "Admins can't be banned; the space owner removes them from the admin list first"
+        ));
+    }
+    let banned_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let ban = AuthorizedSpaceBanV1::new(space, target.member_id(), banned_at, &sk);
+    let banned = target.member_id();
+    api.publish_space_delta(
+        &space,
+        SpaceDeltaV1 {
+            bans: vec![ban],
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    if matches!(format, OutputFormat::Human) {
+        println!("{} is banned from the space.", banned);
+    }
+    let report = sync_space(api, &space, &sk, None).await?;
+    print_report(format, &report);
+    Ok(())
+}
+
+/// The cross-contract half of a space. A moderator grants missing room
+/// access and mirrors space bans into the rooms they belong to (a room ban
+/// needs a member of that room to sign it); everyone joins rooms they have
+/// been granted, asked for a nickname only if there is one to join.
+/// Room-level failures are collected as warnings so one unreachable room
+/// does not stop the rest.
+async fn sync_space(
+    api: &ApiClient,
+    space: &VerifyingKey,
+    sk: &SigningKey,
+    mut nickname: Option<String>,
+) -> Result<SyncReport> {
+    let mut report = SyncReport::default();
+    let state = fetch_space(api, space).await?;
+    let params = SpaceParametersV1 { owner: *space };
+    let me = sk.verifying_key();
+
+    if state.can_moderate(&me, &params) {
+        let grants = build_grants(api, space, sk, &state, |room| {
+            state.members_without_grant(room).collect()
+        })?;
+        report.granted = grants.len();
+        if !grants.is_empty() {
+            api.publish_space_delta(
+                space,
+                SpaceDeltaV1 {
+                    grants,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+
+        for room in state.rooms() {
+            if state.bans.is_empty() || api.storage().get_room(room)?.is_none() {
+                continue;
+            }
+            let room_state = match api.get_room(room, false).await {
+                Ok(room_state) => room_state,
+                Err(e) => {
+                    report
+                        .warnings
+                        .push(format!("Room {}: {}", space_id(room), e));
+                    continue;
+                }
+            };
+            let present: Vec<MemberId> = room_state
+                .members
+                .members
+                .iter()
+                .map(|m| m.member.id())
+                .collect();
+            for ban in &state.bans {
+                let banned = ban.ban.banned;
+                let already = room_state
+                    .bans
+                    .0
+                    .iter()
+                    .any(|b| b.ban.banned_user == banned);
+                if already || !present.contains(&banned) {
+                    continue;
+                }
+                let safety = BanSafety {
+                    require_exact_member_id: true,
+                    ..Default::default()
+                };
+                match api
+                    .ban_member_with_safety(room, &banned.to_string(), safety)
+                    .await
+                {
+                    Ok(()) => report.rooms_banned_in.push(space_id(room)),
+                    Err(e) => report.warnings.push(format!(
+                        "Ban of {} in room {}: {}",
+                        banned,
+                        space_id(room),
+                        e
+                    )),
+                }
+            }
+        }
+    }
+
+    for grant in state.grants_for(&me) {
+        let room = grant.grant.room_owner;
+        if api.storage().get_room(&room)?.is_some() {
+            continue;
+        }
+        if nickname.is_none() {
+            nickname = Some(crate::commands::invite::resolve_nickname(None)?);
+        }
+        let invitation = Invitation {
+            room,
+            invitee_signing_key: sk.clone(),
+            invitee: grant.grant.member.clone(),
+            room_secrets: Vec::new(),
+        };
+        match api
+            .accept_invitation_struct(invitation, nickname.as_deref().unwrap_or_default())
+            .await
+        {
+            Ok(_) => report.joined.push(space_id(&room)),
+            Err(e) => report
+                .warnings
+                .push(format!("Joining room {}: {}", space_id(&room), e)),
+        }
+    }
+
+    if let Some(name) = state.name() {
+        api.storage().mutate_spaces(|spaces| {
+            if let Some(info) = spaces.spaces.get_mut(&space_id(space)) {
+                info.name = name.to_string();
+            }
+        })?;
+    }
+    Ok(report)
+}
+
+fn print_report(format: OutputFormat, report: &SyncReport) {
+    match format {
+        OutputFormat::Human => {
+            if report.granted > 0 {
+                println!("Granted {} room invite(s).", report.granted);
+            }
+            for room in &report.rooms_banned_in {
+                println!("Applied a space ban in room {}.", room);
+            }
+            for room in &report.joined {
+                println!("Joined room {}.", room);
+            }
+            for warning in &report.warnings {
+                eprintln!("{} {}", "Warning:".yellow(), warning);
+            }
+        }
+        OutputFormat::Json => match serde_json::to_string(report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("{} {}", "Error:".red(), e),
+        },
+    }
+}
+
+/// `space list` reads `spaces.json` only, so it runs without a node.
+pub fn list(storage: &Storage, format: OutputFormat) -> Result<()> {
+    let spaces = storage.load_spaces()?;
+    let mut rows: Vec<(String, &StoredSpaceInfo, bool)> = spaces
+        .spaces
+        .iter()
+        .map(|(id, info)| {
+            let is_owner =
+                space_id(&SigningKey::from_bytes(&info.signing_key_bytes).verifying_key()) == *id;
+            (id.clone(), info, is_owner)
+        })
+        .collect();
+    rows.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+    match format {
+        OutputFormat::Human if rows.is_empty() => println!("No spaces."),
+        OutputFormat::Human => {
+            for (id, info, is_owner) in rows {
+                let role = if is_owner { " (owner)" } else { "" };
+                println!("{}{}  {}", info.name, role, id);
+            }
+        }
+        OutputFormat::Json => {
+            let rows: Vec<_> = rows
+                .into_iter()
+                .map(|(id, info, is_owner)| {
+                    serde_json::json!({ "space": id, "name": info.name, "owner": is_owner })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+    }
+    Ok(())
+}
+
+async fn show(api: &ApiClient, space_arg: &str, format: OutputFormat) -> Result<()> {
+    let (space, _) = resolve_space(api, space_arg)?;
+    let state = fetch_space(api, &space).await?;
+    let rooms: Vec<String> = state.rooms().iter().map(space_id).collect();
+    let admins: Vec<String> = state
+        .config
+        .iter()
+        .flat_map(|c| c.config.admins.iter())
+        .map(|a| MemberId::from(a).to_string())
+        .collect();
+    let members: Vec<String> = state
+        .members
+        .iter()
+        .map(|m| m.member_id().to_string())
+        .collect();
+    let bans: Vec<String> = state
+        .bans
+        .iter()
+        .map(|b| b.ban.banned.to_string())
+        .collect();
+    match format {
+        OutputFormat::Human => {
+            println!("{}", state.name().unwrap_or_default().bold());
+            println!("  key: {}", space_id(&space));
+            println!("  rooms ({}):", rooms.len());
+            for room in &rooms {
+                println!("    {}", room);
+            }
+            println!("  admins: {}", admins.join(", "));
+            println!("  members: {}", members.len());
+            println!("  bans: {}", bans.join(", "));
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "space": space_id(&space),
+                "name": state.name(),
+                "rooms": rooms,
+                "admins": admins,
+                "members": members,
+                "bans": bans,
+            })
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn space_invitation_round_trips() {
+        let sk = SigningKey::from_bytes(&[7; 32]);
+        let space = SigningKey::from_bytes(&[9; 32]).verifying_key();
+        let mut data = Vec::new();
+        ciborium::ser::into_writer(
+            &SpaceInvitation {
+                space,
+                member_signing_key: sk.clone(),
+            },
+            &mut data,
+        )
+        .unwrap();
+        let code = bs58::encode(data).into_string();
+
+        let decoded: SpaceInvitation =
+            ciborium::de::from_reader(&bs58::decode(code).into_vec().unwrap()[..]).unwrap();
+        assert_eq!(decoded.space, space);
+        assert_eq!(decoded.member_signing_key.to_bytes(), sk.to_bytes());
+    }
+}
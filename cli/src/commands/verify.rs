@@ -0,0 +1,115 @@
+use crate::message_proof::{MessageProof, ProofStep, VerifiedProof};
+use crate::output::OutputFormat;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde_json::json;
+use std::path::Path;
+
+/// `riverctl verify <bundle>`: check a message proof written by
+/// `message prove`. Offline by construction — it never builds an API client —
+/// so it works on a machine with no Freenet node and no River identity.
+pub fn execute(bundle: &Path, format: OutputFormat) -> Result<()> {
+    let armored = std::fs::read_to_string(bundle)
+        .map_err(|e| anyhow!("Failed to read {}: {e}", bundle.display()))?;
+    let proof = MessageProof::from_armored_string(&armored).map_err(|e| anyhow!(e))?;
+    let verified = proof
+        .verify()
+        .map_err(|e| anyhow!("Proof does NOT verify: {e}"))?;
+
+    match format {
+        OutputFormat::Human => print_human(&proof, &verified),
+        OutputFormat::Json => {
+            let chain: Vec<_> = verified
+                .steps
+                .iter()
+                .map(|step| match step {
+                    ProofStep::Invited {
+                        inviter,
+                        member,
+                        member_key,
+                    } => json!({
+                        "step": "invited",
+                        "inviter": inviter.to_string(),
+                        "member": member.to_string(),
+                        "member_key": bs58::encode(member_key.as_bytes()).into_string(),
+                    }),
+                    ProofStep::Signed { author } => json!({
+                        "step": "signed",
+                        "author": author.to_string(),
+                    }),
+                })
+                .collect();
+            let sent: DateTime<Utc> = proof.message.message.time.into();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&json!({
+                    "valid": true,
+                    "room": bs58::encode(verified.room_owner.as_bytes()).into_string(),
+                    "message_id": verified.message_id.0 .0.to_string(),
+                    "author": verified.author.to_string(),
+                    "author_key": bs58::encode(verified.author_key.as_bytes()).into_string(),
+                    "author_is_owner": verified.author_is_owner,
+                    "timestamp": sent.to_rfc3339(),
+                    "text": proof.public_text(),
+                    "chain": chain,
+                }))?
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_human(proof: &MessageProof, verified: &VerifiedProof) {
+    let room = bs58::encode(verified.room_owner.as_bytes()).into_string();
+    let sent: DateTime<Utc> = proof.message.message.time.into();
+    println!("{}", "Message proof: VALID".green().bold());
+    println!();
+    println!("  Room:    {}", room);
+    println!("  Message: {}", verified.message_id.0 .0);
+    println!(
+        "  Author:  {} (key {})",
+        verified.author,
+        bs58::encode(verified.author_key.as_bytes()).into_string()
+    );
+    println!(
+        "  Sent:    {} (as stated by the author)",
+        sent.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    match proof.public_text() {
+        Some(text) => println!("  Text:    {}", text),
+        None => println!("  Text:    (private room; the body is sealed and not shown)"),
+    }
+    println!();
+    println!("Chain of authority:");
+    println!("  1. Room owner key {} is the room ID", room);
+    for (i, step) in verified.steps.iter().enumerate() {
+        match step {
+            ProofStep::Invited {
+                inviter,
+                member,
+                member_key,
+            } => println!(
+                "  {}. {} invited {} (key {}) — signature valid",
+                i + 2,
+                inviter,
+                member,
+                bs58::encode(member_key.as_bytes()).into_string()
+            ),
+            ProofStep::Signed { author } => {
+                println!(
+                    "  {}. {} signed the message — signature valid",
+                    i + 2,
+                    author
+                )
+            }
+        }
+    }
+    println!();
+    println!(
+        "This proves the author's key signed this message for this room, and that \
+         the key was admitted through the invitations above. It does not prove the \
+         member was not banned later, that the message is still in the room, or \
+         that the timestamp is accurate."
+    );
+}
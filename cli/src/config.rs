@@ -1,15 +1,465 @@
-use anyhow::Result;
+//! `config.toml`: per-user riverctl defaults, kept next to `rooms.json` in the
+//! data directory (see [`crate::storage::data_dir`]).
+//!
+//! ```toml
+//! default_room = "team"
+//! node_url = "ws://127.0.0.1:7509/v1/contract/command?encodingProtocol=native"
+//! format = "json"
+//! user_key_path = "/home/me/.river/bot.key"
+//!
+//! [aliases]
+//! team = "6Jx…"          # base58 room owner key
+//!
+//! [rooms."6Jx…"]
+//! signing_key_file = "/home/me/.river/team-owner.key"
+//! ```
+//!
+//! Command-line flags and their env vars always beat the file. Anywhere a
+//! room ID is accepted, an alias name resolves to its room and `.` resolves
+//! to `default_room`; see [`resolve_room_arg`]. Edited with
+//! `riverctl config get/set/unset/list`.
+
+use crate::output::FormatArg;
+use crate::storage::ConfiguredIdentities;
+use anyhow::{anyhow, Context, Result};
+use ed25519_dalek::{SigningKey, VerifyingKey};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// File name of the config inside the data directory.
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// The room argument that stands for `default_room`.
+pub const DEFAULT_ROOM_ARG: &str = ".";
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct Config {
+    /// Room used when a room argument is `.`: a room ID or an alias.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_room: Option<String>,
+    /// Raw 32-byte signing key used for rooms without their own
+    /// `signing_key_file`, in place of the key stored in `rooms.json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub user_key_path: Option<String>,
+    /// Freenet node WebSocket URL, when `--node-url` is not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_url: Option<String>,
+    /// Output format (`human` or `json`), when `--format` is not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Alias name to base58 room owner key.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// Per-room settings, keyed by base58 room owner key.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rooms: BTreeMap<String, RoomSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct RoomSettings {
+    /// Raw 32-byte signing key to act as in this room.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key_file: Option<String>,
 }
 
 impl Config {
-    pub fn load() -> Result<Self> {
-        // TODO: Load from config file
-        Ok(Self::default())
+    /// Path of the config file for `config_dir` (same resolution as storage).
+    pub fn path(config_dir: Option<&str>) -> Result<PathBuf> {
+        Ok(crate::storage::data_dir(config_dir)?.join(CONFIG_FILE_NAME))
+    }
+
+    /// Load the config, or the defaults when there is no file yet.
+    pub fn load(config_dir: Option<&str>) -> Result<Self> {
+        Self::load_from(&Self::path(config_dir)?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("reading {}", path.display()));
+            }
+        };
+        let config: Self =
+            toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        config
+            .validate()
+            .map_err(|e| anyhow!("{}: {:#}", path.display(), e))?;
+        Ok(config)
+    }
+
+    pub fn save(&self, config_dir: Option<&str>) -> Result<PathBuf> {
+        let path = Self::path(config_dir)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string_pretty(self)?;
+        fs::write(&path, text).with_context(|| format!("writing {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Checks a hand-edited file the same way [`Self::set`] checks values, so
+    /// a typo surfaces on load rather than as a baffling "invalid room ID".
+    fn validate(&self) -> Result<()> {
+        if let Some(format) = &self.format {
+            format.parse::<FormatArg>().map_err(|e| anyhow!(e))?;
+        }
+        for (name, room) in &self.aliases {
+            check_alias_name(name)?;
+            parse_room_key(room).with_context(|| format!("alias `{name}`"))?;
+        }
+        for room in self.rooms.keys() {
+            parse_room_key(room).with_context(|| format!("[rooms.\"{room}\"]"))?;
+        }
+        Ok(())
+    }
+
+    /// Resolve a room argument: an alias name maps to its room, `.` to
+    /// `default_room` (itself possibly an alias), anything else is returned
+    /// unchanged for the caller to parse as a room ID.
+    pub fn resolve_room(&self, arg: &str) -> Result<String> {
+        if arg == DEFAULT_ROOM_ARG {
+            let default = self.default_room.as_deref().ok_or_else(|| {
+                anyhow!(
+                    "`{DEFAULT_ROOM_ARG}` means the default room, but none is set \
+                     (riverctl config set default_room <room>)"
+                )
+            })?;
+            return Ok(self.aliases.get(default).cloned().unwrap_or(default.into()));
+        }
+        Ok(self.aliases.get(arg).cloned().unwrap_or_else(|| arg.into()))
+    }
+
+    /// The output format to use when `--format` was not given.
+    pub fn output_format(&self) -> Result<Option<FormatArg>> {
+        self.format
+            .as_deref()
+            .map(|f| f.parse::<FormatArg>().map_err(|e| anyhow!(e)))
+            .transpose()
+    }
+
+    /// Read the key files the config nominates.
+    pub fn identities(&self) -> Result<ConfiguredIdentities> {
+        let mut identities = ConfiguredIdentities::default();
+        if let Some(path) = &self.user_key_path {
+            identities.default = Some(
+                load_signing_key_from_file(Path::new(path)).context("config `user_key_path`")?,
+            );
+        }
+        for (room, settings) in &self.rooms {
+            if let Some(path) = &settings.signing_key_file {
+                let key = load_signing_key_from_file(Path::new(path))
+                    .with_context(|| format!("config `room.{room}.signing_key_file`"))?;
+                identities.per_room.insert(room.clone(), key);
+            }
+        }
+        Ok(identities)
+    }
+
+    /// The value of `key` (as named in [`Self::entries`]), if set.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match parse_key(key)? {
+            Key::DefaultRoom => self.default_room.clone(),
+            Key::UserKeyPath => self.user_key_path.clone(),
+            Key::NodeUrl => self.node_url.clone(),
+            Key::Format => self.format.clone(),
+            Key::Alias(name) => self.aliases.get(&name).cloned(),
+            Key::RoomSigningKeyFile(room) => {
+                let room = self.room_key_for(&room)?;
+                self.rooms
+                    .get(&room)
+                    .and_then(|s| s.signing_key_file.clone())
+            }
+        })
+    }
+
+    /// Set `key` to `value` after checking it. Room values may be aliases;
+    /// they are stored as room IDs.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match parse_key(key)? {
+            Key::DefaultRoom => {
+                if !self.aliases.contains_key(value) {
+                    parse_room_key(value)?;
+                }
+                self.default_room = Some(value.into());
+            }
+            Key::UserKeyPath => {
+                load_signing_key_from_file(Path::new(value))?;
+                self.user_key_path = Some(value.into());
+            }
+            Key::NodeUrl => {
+                let url = url::Url::parse(value).context("invalid node URL")?;
+                if !matches!(url.scheme(), "ws" | "wss") {
+                    return Err(anyhow!("node URL must be ws:// or wss://, got {value}"));
+                }
+                self.node_url = Some(value.into());
+            }
+            Key::Format => {
+                value.parse::<FormatArg>().map_err(|e| anyhow!(e))?;
+                self.format = Some(value.to_lowercase());
+            }
+            Key::Alias(name) => {
+                check_alias_name(&name)?;
+                let room = self.room_key_for(value)?;
+                self.aliases.insert(name, room);
+            }
+            Key::RoomSigningKeyFile(room) => {
+                let room = self.room_key_for(&room)?;
+                load_signing_key_from_file(Path::new(value))?;
+                self.rooms.entry(room).or_default().signing_key_file = Some(value.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Clear `key`. Returns whether it was set.
+    pub fn unset(&mut self, key: &str) -> Result<bool> {
+        Ok(match parse_key(key)? {
+            Key::DefaultRoom => self.default_room.take().is_some(),
+            Key::UserKeyPath => self.user_key_path.take().is_some(),
+            Key::NodeUrl => self.node_url.take().is_some(),
+            Key::Format => self.format.take().is_some(),
+            Key::Alias(name) => self.aliases.remove(&name).is_some(),
+            Key::RoomSigningKeyFile(room) => {
+                let room = self.room_key_for(&room)?;
+                let was_set = self
+                    .rooms
+                    .get_mut(&room)
+                    .and_then(|s| s.signing_key_file.take())
+                    .is_some();
+                if self.rooms.get(&room) == Some(&RoomSettings::default()) {
+                    self.rooms.remove(&room);
+                }
+                was_set
+            }
+        })
+    }
+
+    /// Every set key with its value, in a stable order.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        let scalars = [
+            ("default_room", &self.default_room),
+            ("node_url", &self.node_url),
+            ("format", &self.format),
+            ("user_key_path", &self.user_key_path),
+        ];
+        for (key, value) in scalars {
+            if let Some(value) = value {
+                out.push((key.to_string(), value.clone()));
+            }
+        }
+        for (name, room) in &self.aliases {
+            out.push((format!("alias.{name}"), room.clone()));
+        }
+        for (room, settings) in &self.rooms {
+            if let Some(path) = &settings.signing_key_file {
+                out.push((format!("room.{room}.signing_key_file"), path.clone()));
+            }
+        }
+        out
+    }
+
+    /// A room argument (alias or ID) as a validated room ID string.
+    fn room_key_for(&self, arg: &str) -> Result<String> {
+        let room = self.resolve_room(arg)?;
+        parse_room_key(&room)?;
+        Ok(room)
+    }
+}
+
+enum Key {
+    DefaultRoom,
+    UserKeyPath,
+    NodeUrl,
+    Format,
+    Alias(String),
+    RoomSigningKeyFile(String),
+}
+
+fn parse_key(key: &str) -> Result<Key> {
+    match key {
+        "default_room" => return Ok(Key::DefaultRoom),
+        "user_key_path" => return Ok(Key::UserKeyPath),
+        "node_url" => return Ok(Key::NodeUrl),
+        "format" => return Ok(Key::Format),
+        _ => {}
+    }
+    if let Some(name) = key.strip_prefix("alias.") {
+        return Ok(Key::Alias(name.to_string()));
+    }
+    if let Some(room) = key
+        .strip_prefix("room.")
+        .and_then(|rest| rest.strip_suffix(".signing_key_file"))
+    {
+        return Ok(Key::RoomSigningKeyFile(room.to_string()));
+    }
+    Err(anyhow!(
+        "unknown config key `{key}` (expected default_room, node_url, format, \
+         user_key_path, alias.<name> or room.<room>.signing_key_file)"
+    ))
+}
+
+/// An alias must not be mistakable for a room ID or for `.`.
+fn check_alias_name(name: &str) -> Result<()> {
+    if name.is_empty() || name == DEFAULT_ROOM_ARG || name.contains(char::is_whitespace) {
+        return Err(anyhow!("invalid alias name `{name}`"));
+    }
+    if parse_room_key(name).is_ok() {
+        return Err(anyhow!("alias `{name}` would shadow a room ID"));
+    }
+    Ok(())
+}
+
+fn parse_room_key(room: &str) -> Result<VerifyingKey> {
+    let bytes = bs58::decode(room)
+        .into_vec()
+        .map_err(|e| anyhow!("invalid room ID `{room}`: {e}"))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| anyhow!("invalid room ID `{room}`: {} bytes", b.len()))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| anyhow!("invalid room ID `{room}`: {e}"))
+}
+
+static ACTIVE: OnceLock<Config> = OnceLock::new();
+
+/// Make `config` the one [`resolve_room_arg`] consults for the rest of the
+/// process. Called once from `main`; later calls are ignored.
+pub fn install(config: Config) {
+    let _ = ACTIVE.set(config);
+}
+
+/// [`Config::resolve_room`] against the installed config. Every command's
+/// room-ID parser runs its argument through this first, which is what makes
+/// aliases and `.` work everywhere. Without an installed config (library
+/// use, tests) the argument is returned unchanged.
+pub fn resolve_room_arg(arg: &str) -> Result<String> {
+    match ACTIVE.get() {
+        Some(config) => config.resolve_room(arg),
+        None => Ok(arg.to_string()),
+    }
+}
+
+/// Load a raw 32-byte Ed25519 secret key from the given file path.
+/// Used by the `--signing-key-file` flag / `RIVER_SIGNING_KEY_FILE` env var
+/// and by the key paths in `config.toml`.
+/// Errors are surfaced with a clear message identifying the bad path
+/// and the actual length seen, so the user can tell "I pointed at the
+/// wrong file" from "I pointed at a base64-encoded file".
+pub fn load_signing_key_from_file(path: &Path) -> Result<SigningKey> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("failed to read signing key file: {}", path.display()))?;
+    parse_signing_key_bytes(&bytes)
+        .map_err(|reason| anyhow!("{} — file: {}", reason, path.display()))
+}
+
+/// Pure helper for testing the wrong-length / right-length validation
+/// without touching the filesystem.
+pub fn parse_signing_key_bytes(bytes: &[u8]) -> std::result::Result<SigningKey, String> {
+    if bytes.len() != 32 {
+        return Err(format!(
+            "signing key must be exactly 32 raw bytes, got {} bytes \
+             (was this file base64- or hex-encoded? the override expects raw \
+             bytes — the same format as the room-key backups under \
+             ~/.config/freenet-river-official/*.bin; NOT the armored output of \
+             `riverctl identity export`, which is a larger multi-field token)",
+            bytes.len()
+        ));
+    }
+    let mut buf = [0u8; 32];
+    buf.copy_from_slice(bytes);
+    Ok(SigningKey::from_bytes(&buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room_id(seed: u8) -> String {
+        let vk = SigningKey::from_bytes(&[seed; 32]).verifying_key();
+        bs58::encode(vk.as_bytes()).into_string()
+    }
+
+    #[test]
+    fn aliases_and_default_room_resolve() {
+        let mut config = Config::default();
+        let room = room_id(1);
+        config.set("alias.team", &room).unwrap();
+        config.set("default_room", "team").unwrap();
+
+        assert_eq!(config.resolve_room("team").unwrap(), room);
+        assert_eq!(config.resolve_room(".").unwrap(), room);
+        let other = room_id(2);
+        assert_eq!(config.resolve_room(&other).unwrap(), other);
+        assert!(Config::default().resolve_room(".").is_err());
+    }
+
+    #[test]
+    fn set_rejects_bad_values() {
+        let mut config = Config::default();
+        assert!(config.set("alias.team", "not-a-room").is_err());
+        assert!(config
+            .set(&format!("alias.{}", room_id(1)), &room_id(2))
+            .is_err());
+        assert!(config.set("alias..", &room_id(2)).is_err());
+        assert!(config.set("format", "yaml").is_err());
+        assert!(config.set("node_url", "http://localhost").is_err());
+        assert!(config.set("colour", "blue").is_err());
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn room_identity_round_trips_through_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("team.key");
+        fs::write(&key_path, [7u8; 32]).unwrap();
+        let config_dir = dir.path().to_str().unwrap();
+
+        let mut config = Config::default();
+        let room = room_id(1);
+        config.set("alias.team", &room).unwrap();
+        config
+            .set("room.team.signing_key_file", key_path.to_str().unwrap())
+            .unwrap();
+        config.set("format", "JSON").unwrap();
+        config.save(Some(config_dir)).unwrap();
+
+        let loaded = Config::load(Some(config_dir)).unwrap();
+        assert_eq!(loaded, config);
+        assert!(matches!(
+            loaded.output_format().unwrap(),
+            Some(FormatArg::Json)
+        ));
+        let identities = loaded.identities().unwrap();
+        assert_eq!(identities.per_room[&room].to_bytes(), [7u8; 32]);
+        assert_eq!(
+            loaded
+                .get(&format!("room.{room}.signing_key_file"))
+                .unwrap(),
+            Some(key_path.to_str().unwrap().to_string())
+        );
+
+        let mut loaded = loaded;
+        assert!(loaded.unset("room.team.signing_key_file").unwrap());
+        assert!(loaded.rooms.is_empty());
+    }
+
+    #[test]
+    fn missing_file_is_defaults_and_bad_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().to_str().unwrap();
+        assert_eq!(Config::load(Some(config_dir)).unwrap(), Config::default());
+
+        fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "[aliases]\nteam = \"nope\"\n",
+        )
+        .unwrap();
+        let err = Config::load(Some(config_dir)).unwrap_err();
+        assert!(format!("{err:#}").contains("alias `team`"), "{err:#}");
     }
 }
@@ -1,10 +1,16 @@
 pub mod api;
+pub mod chat;
 pub mod commands;
 pub mod config;
 pub mod deputies;
 pub mod error;
+pub mod message_proof;
 pub mod output;
 pub mod pointer;
 pub mod private_room;
+pub mod room_events;
+pub mod room_policy;
+pub mod snapshot;
 pub mod storage;
+pub mod transcript;
 pub mod version_check;
@@ -1,6 +1,5 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use ed25519_dalek::SigningKey;
 use std::path::{Path, PathBuf};
 use tracing::info;
 use tracing_appender::non_blocking::WorkerGuard;
@@ -8,10 +7,17 @@ use tracing_subscriber::EnvFilter;
 
 use riverctl::{
     api,
-    commands::{debug, dm, identity, invite, member, message, room},
-    config, output,
+    commands::{
+        chat, config as config_cmd, debug, dm, draft, identity, invite, member, message, room,
+        space, verify,
+    },
+    config::{self, load_signing_key_from_file},
+    output,
 };
 
+/// Node URL when neither `--node-url` nor `node_url` in config.toml is set.
+const DEFAULT_NODE_URL: &str = "ws://127.0.0.1:7509/v1/contract/command?encodingProtocol=native";
+
 #[derive(Parser)]
 #[command(name = "river")]
 #[command(about = "Command-line interface for River chat on Freenet")]
@@ -21,19 +27,18 @@ struct Cli {
     #[command(subcommand)]
     command: Commands,
 
-    /// Output format (human, json)
-    #[arg(short, long, global = true, default_value = "human")]
-    format: output::OutputFormat,
+    /// Output format (human, json, ndjson) [default: `format` in config.toml,
+    /// else human]. `ndjson` makes streaming commands emit one typed event per
+    /// line; elsewhere it means json.
+    #[arg(short, long, global = true)]
+    format: Option<output::FormatArg>,
 
-    /// Freenet node WebSocket URL
-    #[arg(
-        long,
-        global = true,
-        default_value = "ws://127.0.0.1:7509/v1/contract/command?encodingProtocol=native"
-    )]
-    node_url: String,
+    /// Freenet node WebSocket URL [default: `node_url` in config.toml, else
+    /// the local node]
+    #[arg(long, global = true)]
+    node_url: Option<String>,
 
-    /// Configuration directory for storing room data
+    /// Configuration directory for storing room data and `config.toml`
     #[arg(long, global = true)]
     config_dir: Option<String>,
 
@@ -65,7 +70,8 @@ struct Cli {
     /// the contract.
     ///
     /// Falls back to the `RIVER_SIGNING_KEY_FILE` env var if the flag
-    /// is not passed.
+    /// is not passed. Per-room `signing_key_file` and `user_key_path` in
+    /// `config.toml` apply only when neither is set.
     ///
     /// Distinct from `message send --signing-key`, which takes a
     /// base64-encoded key inline as a single-command override — the
@@ -117,6 +123,32 @@ enum Commands {
         #[command(subcommand)]
         command: dm::DmCommands,
     },
+    /// Unsent drafts per room and DM thread, kept locally
+    Draft {
+        #[command(subcommand)]
+        command: draft::DraftCommands,
+    },
+    /// Spaces: rooms grouped under a shared member list and bans
+    Space {
+        #[command(subcommand)]
+        command: space::SpaceCommands,
+    },
+    /// Full-screen chat: room list, scrollback, DMs and unread counts
+    Chat {
+        /// Room to open first (owner key or alias; defaults to the configured
+        /// default room)
+        room: Option<String>,
+    },
+    /// Read and edit riverctl's config file (default room, aliases, ...)
+    Config {
+        #[command(subcommand)]
+        command: config_cmd::ConfigCommands,
+    },
+    /// Verify a message proof written by `message prove`, offline
+    Verify {
+        /// Proof file (armored `RIVER MESSAGE PROOF`)
+        bundle: PathBuf,
+    },
 }
 
 #[tokio::main]
@@ -136,8 +168,28 @@ async fn main() -> Result<()> {
     let version_disabled =
         cli.no_version_check || std::env::var_os("RIVERCTL_NO_VERSION_CHECK").is_some();
 
-    // Load configuration
-    let config = config::Config::load()?;
+    // `config` is offline and loads the file itself.
+    if let Commands::Config { command } = cli.command {
+        return config_cmd::execute(
+            command,
+            cli.config_dir.as_deref(),
+            cli.format.map(output::FormatArg::output),
+        );
+    }
+
+    // Load configuration. Flags beat the file; the file beats built-ins.
+    let config = config::Config::load(cli.config_dir.as_deref())?;
+    config::install(config.clone());
+    let format_arg = match cli.format {
+        Some(format) => format,
+        None => config.output_format()?.unwrap_or(output::FormatArg::Human),
+    };
+    let format = format_arg.output();
+    let node_url = cli
+        .node_url
+        .clone()
+        .or_else(|| config.node_url.clone())
+        .unwrap_or_else(|| DEFAULT_NODE_URL.to_string());
 
     // Resolve optional --signing-key-file override (or RIVER_SIGNING_KEY_FILE env var).
     let signing_key_override = cli
@@ -157,21 +209,85 @@ async fn main() -> Result<()> {
         _ => None,
     };
 
-    if let Some((room, inline_signing_key)) = whoami_args {
+    if let Commands::Verify { bundle } = &cli.command {
+        // Offline by design: a reviewer outside River has no node to talk to.
+        verify::execute(bundle, format)?;
+    } else if let Commands::Debug {
+        command:
+            debug::DebugCommands::Diff {
+                a,
+                b: Some(b),
+                room,
+                ..
+            },
+    } = &cli.command
+    {
+        // Two files need no node; only `--live` falls through to the client.
+        let storage = riverctl::storage::Storage::new(cli.config_dir.as_deref())?;
+        debug::diff_files(a, b, room.as_deref(), &storage, format)?;
+    } else if let Commands::Debug {
+        command: debug::DebugCommands::Replay { trace },
+    } = &cli.command
+    {
+        debug::replay_trace(trace, format)?;
+    } else if matches!(&cli.command, Commands::Message { command } if command.runs_offline()) {
+        // Queueing with `send --at` and listing or cancelling the queue only
+        // touch `scheduled_messages.json`; `message queue run` needs the node.
+        let Commands::Message { command } = cli.command else {
+            unreachable!("matched above")
+        };
         let storage = riverctl::storage::Storage::new_with_override(
             cli.config_dir.as_deref(),
             signing_key_override,
-        )?;
+        )?
+        .with_configured_identities(config.identities()?);
+        message::execute_offline(command, &storage, format)?;
+    } else if matches!(&cli.command, Commands::Member { command } if command.runs_offline()) {
+        // `member verify` compares keys already in `rooms.json`.
+        let Commands::Member { command } = cli.command else {
+            unreachable!("matched above")
+        };
+        let storage = riverctl::storage::Storage::new_with_override(
+            cli.config_dir.as_deref(),
+            signing_key_override,
+        )?
+        .with_configured_identities(config.identities()?);
+        member::execute_offline(command, &storage, format)?;
+    } else if let Commands::Draft { command } = cli.command {
+        // Drafts live in `drafts.json` and are sealed with secrets already in
+        // `rooms.json`, so they never need the node.
+        let storage = riverctl::storage::Storage::new_with_override(
+            cli.config_dir.as_deref(),
+            signing_key_override,
+        )?
+        .with_configured_identities(config.identities()?);
+        draft::execute(command, &storage, format)?;
+    } else if let Commands::Space {
+        command: space::SpaceCommands::List,
+    } = cli.command
+    {
+        let storage = riverctl::storage::Storage::new_with_override(
+            cli.config_dir.as_deref(),
+            signing_key_override,
+        )?
+        .with_configured_identities(config.identities()?);
+        space::list(&storage, format)?;
+    } else if let Some((room, inline_signing_key)) = whoami_args {
+        let storage = riverctl::storage::Storage::new_with_override(
+            cli.config_dir.as_deref(),
+            signing_key_override,
+        )?
+        .with_configured_identities(config.identities()?);
         identity::whoami(
             &storage,
             room.as_deref(),
             inline_signing_key.as_deref(),
-            cli.format,
+            format,
         )?;
     } else {
         // Create API client
         let api_client = api::ApiClient::new_with_signing_key_override(
-            &cli.node_url,
+            &node_url,
             config,
             cli.config_dir.as_deref(),
             signing_key_override,
@@ -180,21 +296,23 @@ async fn main() -> Result<()> {
 
         // Execute command
         match cli.command {
-            Commands::Room { command } => room::execute(command, api_client, cli.format).await?,
+            Commands::Room { command } => room::execute(command, api_client, format).await?,
             Commands::Message { command } => {
-                message::execute(command, api_client, cli.format).await?
-            }
-            Commands::Member { command } => {
-                member::execute(command, api_client, cli.format).await?
-            }
-            Commands::Invite { command } => {
-                invite::execute(command, api_client, cli.format).await?
+                message::execute(command, api_client, format_arg).await?
             }
+            Commands::Member { command } => member::execute(command, api_client, format).await?,
+            Commands::Invite { command } => invite::execute(command, api_client, format).await?,
             Commands::Identity { command } => {
-                identity::execute(command, api_client, cli.format).await?
+                identity::execute(command, api_client, format).await?
+            }
+            Commands::Debug { command } => debug::execute(command, api_client, format).await?,
+            Commands::Dm { command } => dm::execute(command, api_client, format).await?,
+            Commands::Space { command } => space::execute(command, api_client, format).await?,
+            Commands::Chat { room } => chat::execute(api_client, room).await?,
+            Commands::Verify { .. } | Commands::Draft { .. } => {
+                unreachable!("handled before the client is built")
             }
-            Commands::Debug { command } => debug::execute(command, api_client, cli.format).await?,
-            Commands::Dm { command } => dm::execute(command, api_client, cli.format).await?,
+            Commands::Config { .. } => unreachable!("handled before the config is loaded"),
         }
     }
 
@@ -211,36 +329,6 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Load a raw 32-byte Ed25519 secret key from the given file path.
-/// Used by the `--signing-key-file` flag / `RIVER_SIGNING_KEY_FILE` env var.
-/// Errors are surfaced with a clear message identifying the bad path
-/// and the actual length seen, so the user can tell "I pointed at the
-/// wrong file" from "I pointed at a base64-encoded file".
-fn load_signing_key_from_file(path: &Path) -> Result<SigningKey> {
-    let bytes = std::fs::read(path)
-        .with_context(|| format!("failed to read signing key file: {}", path.display()))?;
-    parse_signing_key_bytes(&bytes)
-        .map_err(|reason| anyhow!("{} — file: {}", reason, path.display()))
-}
-
-/// Pure helper for testing the wrong-length / right-length validation
-/// without touching the filesystem.
-fn parse_signing_key_bytes(bytes: &[u8]) -> std::result::Result<SigningKey, String> {
-    if bytes.len() != 32 {
-        return Err(format!(
-            "signing key must be exactly 32 raw bytes, got {} bytes \
-             (was this file base64- or hex-encoded? the override expects raw \
-             bytes — the same format as the room-key backups under \
-             ~/.config/freenet-river-official/*.bin; NOT the armored output of \
-             `riverctl identity export`, which is a larger multi-field token)",
-            bytes.len()
-        ));
-    }
-    let mut buf = [0u8; 32];
-    buf.copy_from_slice(bytes);
-    Ok(SigningKey::from_bytes(&buf))
-}
-
 fn init_logging(debug: bool, log_path: Option<&Path>) -> Result<Option<WorkerGuard>> {
     use std::fs::OpenOptions;
     use tracing_subscriber::{fmt, layer::SubscriberExt, Registry};
@@ -279,6 +367,7 @@ fn init_logging(debug: bool, log_path: Option<&Path>) -> Result<Option<WorkerGua
 #[cfg(test)]
 mod cli_tests {
     use super::*;
+    use riverctl::config::parse_signing_key_bytes;
 
     #[test]
     fn parse_signing_key_bytes_accepts_32_byte_input() {
@@ -330,4 +419,27 @@ mod cli_tests {
             );
         }
     }
+
+    /// `send --at` and `queue list/cancel` must take the offline path;
+    /// `queue run` and a plain `send` need the node.
+    #[test]
+    fn scheduled_send_and_queue_edits_run_offline() {
+        let offline = |arguments: &[&str]| match Cli::try_parse_from(arguments).unwrap().command {
+            Commands::Message { command } => command.runs_offline(),
+            _ => panic!("not a message command"),
+        };
+        assert!(offline(&[
+            "river",
+            "message",
+            "send",
+            "room",
+            "hi",
+            "--at",
+            "2099-11-01T09:00Z"
+        ]));
+        assert!(offline(&["river", "message", "queue", "list"]));
+        assert!(offline(&["river", "message", "queue", "cancel", "42"]));
+        assert!(!offline(&["river", "message", "queue", "run", "--once"]));
+        assert!(!offline(&["river", "message", "send", "room", "hi"]));
+    }
 }
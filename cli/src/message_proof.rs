@@ -0,0 +1,334 @@
+//! Self-contained proof that a member of a room posted a message, for
+//! `riverctl message prove` and `riverctl verify`.
+//!
+//! A [`MessageProof`] carries the signed message, the author's
+//! `AuthorizedMember` and the invite chain linking it to the room owner — the
+//! same shape [`river_core::room_state::identity::IdentityExport`] uses, minus
+//! the private key. [`MessageProof::verify`] checks every link with no network
+//! and no room state, so a moderator can hand the armored text to someone
+//! outside River (an appeal reviewer, a compliance officer) and they can check
+//! it themselves.
+//!
+//! What a valid proof shows: the holder of the author key signed exactly this
+//! message for this room, and that key was admitted through a chain of
+//! invitations each signed by the previous inviter, ending at the room owner
+//! key (which IS the room ID). What it does not show: that the member was not
+//! banned later, that the message is still in the room, that the timestamp is
+//! accurate (it is author-asserted), or what a private-room body says (it
+//! stays sealed; the signature covers the ciphertext).
+
+use ed25519_dalek::VerifyingKey;
+use river_core::room_state::member::{AuthorizedMember, MemberId, MembersV1};
+use river_core::room_state::message::{AuthorizedMessageV1, MessageId, RoomMessageBody};
+use river_core::room_state::ChatRoomParametersV1;
+use serde::{Deserialize, Serialize};
+
+const ARMOR_BEGIN: &str = "-----BEGIN RIVER MESSAGE PROOF-----";
+const ARMOR_END: &str = "-----END RIVER MESSAGE PROOF-----";
+const LINE_WIDTH: usize = 64;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MessageProof {
+    /// The room owner's verifying key (the room ID).
+    pub room_owner: VerifyingKey,
+    pub message: AuthorizedMessageV1,
+    /// The author's signed membership. `None` when the owner wrote the
+    /// message: the owner is never in the members list.
+    pub author: Option<AuthorizedMember>,
+    /// Every inviter between `author` and the owner, nearest first.
+    pub invite_chain: Vec<AuthorizedMember>,
+}
+
+/// One verified link, from the owner down to the message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofStep {
+    /// `inviter` signed `member`'s membership.
+    Invited {
+        inviter: MemberId,
+        member: MemberId,
+        member_key: VerifyingKey,
+    },
+    /// `author` signed the message.
+    Signed { author: MemberId },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedProof {
+    pub room_owner: VerifyingKey,
+    pub message_id: MessageId,
+    pub author: MemberId,
+    pub author_key: VerifyingKey,
+    pub author_is_owner: bool,
+    /// Owner first, the message signature last.
+    pub steps: Vec<ProofStep>,
+}
+
+impl MessageProof {
+    /// Assemble a proof for `message_id` from room state. Fails when the
+    /// message has aged out or its author is no longer listed (pruned or
+    /// banned): the membership record is what links the key to the room.
+    pub fn from_state(
+        parameters: &ChatRoomParametersV1,
+        members: &MembersV1,
+        messages: &[AuthorizedMessageV1],
+        message_id: &MessageId,
+    ) -> Result<Self, String> {
+        let message = messages
+            .iter()
+            .find(|m| m.id() == *message_id)
+            .ok_or("Message not found in the room's recent history")?
+            .clone();
+        let owner_id = parameters.owner_id();
+        let (author, invite_chain) = if message.message.author == owner_id {
+            (None, Vec::new())
+        } else {
+            let member = members
+                .members
+                .iter()
+                .find(|m| m.member.id() == message.message.author)
+                .ok_or(
+                    "The author is no longer a member of the room (pruned or banned), \
+                     so their membership cannot be proven from current state",
+                )?;
+            let chain = members.get_invite_chain(member, parameters)?;
+            (Some(member.clone()), chain)
+        };
+        Ok(Self {
+            room_owner: parameters.owner,
+            message,
+            author,
+            invite_chain,
+        })
+    }
+
+    /// Check the whole proof offline. Every error names the link that failed.
+    pub fn verify(&self) -> Result<VerifiedProof, String> {
+        let owner_id = MemberId::from(&self.room_owner);
+        let msg = &self.message.message;
+        if msg.room_owner != owner_id {
+            return Err(format!(
+                "The message names room owner {} but the proof is for {}",
+                msg.room_owner, owner_id
+            ));
+        }
+
+        let mut steps = Vec::new();
+        let author_key = if msg.author == owner_id {
+            self.room_owner
+        } else {
+            let author = self
+                .author
+                .as_ref()
+                .ok_or("The message was not written by the owner, but the proof has no author")?;
+            if author.member.id() != msg.author {
+                return Err(format!(
+                    "The membership is for {} but the message author is {}",
+                    author.member.id(),
+                    msg.author
+                ));
+            }
+            steps = self.verify_chain(author)?;
+            author.member.member_vk
+        };
+
+        self.message.validate(&author_key).map_err(|e| {
+            format!(
+                "The message signature does not verify against {}'s key: {e}",
+                msg.author
+            )
+        })?;
+        steps.push(ProofStep::Signed { author: msg.author });
+
+        Ok(VerifiedProof {
+            room_owner: self.room_owner,
+            message_id: self.message.id(),
+            author: msg.author,
+            author_key,
+            author_is_owner: msg.author == owner_id,
+            steps,
+        })
+    }
+
+    /// Walk from `author` up to the owner, verifying each membership against
+    /// its inviter's key. Returns the links owner-first.
+    fn verify_chain(&self, author: &AuthorizedMember) -> Result<Vec<ProofStep>, String> {
+        let walked = author.verify_detached_invite_chain(&self.room_owner, &self.invite_chain)?;
+        Ok(walked
+            .into_iter()
+            .rev()
+            .map(|m| ProofStep::Invited {
+                inviter: m.member.invited_by,
+                member: m.member.id(),
+                member_key: m.member.member_vk,
+            })
+            .collect())
+    }
+
+    /// The body as a third party sees it: the text of a public message, or
+    /// `None` for a sealed private one.
+    pub fn public_text(&self) -> Option<String> {
+        match &self.message.message.content {
+            RoomMessageBody::Public { .. } => self
+                .message
+                .message
+                .content
+                .decode_content()
+                .map(|decoded| decoded.to_display_string()),
+            RoomMessageBody::Private { .. } => None,
+        }
+    }
+
+    /// Encode as an armored string with header/footer and line wrapping.
+    pub fn to_armored_string(&self) -> String {
+        let mut data = Vec::new();
+        ciborium::ser::into_writer(self, &mut data).expect("Serialization should not fail");
+        let encoded = bs58::encode(data).into_string();
+
+        let mut result = String::new();
+        result.push_str(ARMOR_BEGIN);
+        result.push('\n');
+        for chunk in encoded.as_bytes().chunks(LINE_WIDTH) {
+            result.push_str(std::str::from_utf8(chunk).unwrap());
+            result.push('\n');
+        }
+        result.push_str(ARMOR_END);
+        result.push('\n');
+        result
+    }
+
+    /// Decode from an armored string. Decoding does not verify; call
+    /// [`MessageProof::verify`].
+    pub fn from_armored_string(s: &str) -> Result<Self, String> {
+        if !s.contains(ARMOR_BEGIN) {
+            return Err("Not a River message proof (missing header)".to_string());
+        }
+        let payload: String = s
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+            .collect();
+        let decoded = bs58::decode(&payload)
+            .into_vec()
+            .map_err(|e| format!("Base58 decode error: {}", e))?;
+        ciborium::de::from_reader(&decoded[..]).map_err(|e| format!("Deserialization error: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use river_core::room_state::member::Member;
+    use river_core::room_state::message::MessageV1;
+    use std::time::SystemTime;
+
+    struct Room {
+        owner: SigningKey,
+        params: ChatRoomParametersV1,
+        members: MembersV1,
+    }
+
+    impl Room {
+        fn new() -> Self {
+            let owner = SigningKey::from_bytes(&[1; 32]);
+            let params = ChatRoomParametersV1 {
+                owner: owner.verifying_key(),
+            };
+            Room {
+                owner,
+                params,
+                members: MembersV1::default(),
+            }
+        }
+
+        fn invite(&mut self, inviter: &SigningKey, seed: u8) -> SigningKey {
+            let sk = SigningKey::from_bytes(&[seed; 32]);
+            let member = Member {
+                owner_member_id: self.params.owner_id(),
+                invited_by: MemberId::from(&inviter.verifying_key()),
+                member_vk: sk.verifying_key(),
+            };
+            self.members
+                .members
+                .push(AuthorizedMember::new(member, inviter));
+            sk
+        }
+
+        fn message(&self, author: &SigningKey) -> AuthorizedMessageV1 {
+            AuthorizedMessageV1::new(
+                MessageV1 {
+                    room_owner: self.params.owner_id(),
+                    author: MemberId::from(&author.verifying_key()),
+                    time: SystemTime::UNIX_EPOCH,
+                    content: RoomMessageBody::public("I said it".to_string()),
+                },
+                author,
+            )
+        }
+
+        fn prove(&self, msg: &AuthorizedMessageV1) -> MessageProof {
+            MessageProof::from_state(
+                &self.params,
+                &self.members,
+                std::slice::from_ref(msg),
+                &msg.id(),
+            )
+            .unwrap()
+        }
+    }
+
+    #[test]
+    fn a_two_hop_chain_verifies_from_armor() {
+        let mut room = Room::new();
+        let owner = room.owner.clone();
+        let alice = room.invite(&owner, 2);
+        let bob = room.invite(&alice, 3);
+        let msg = room.message(&bob);
+
+        let proof =
+            MessageProof::from_armored_string(&room.prove(&msg).to_armored_string()).unwrap();
+        let verified = proof.verify().unwrap();
+        assert_eq!(verified.author_key, bob.verifying_key());
+        assert_eq!(verified.steps.len(), 3);
+        assert_eq!(
+            verified.steps[0],
+            ProofStep::Invited {
+                inviter: room.params.owner_id(),
+                member: MemberId::from(&alice.verifying_key()),
+                member_key: alice.verifying_key(),
+            }
+        );
+        assert_eq!(proof.public_text().as_deref(), Some("I said it"));
+    }
+
+    #[test]
+    fn owner_messages_need_no_chain() {
+        let room = Room::new();
+        let msg = room.message(&room.owner);
+        let verified = room.prove(&msg).verify().unwrap();
+        assert!(verified.author_is_owner);
+        assert_eq!(verified.steps.len(), 1);
+    }
+
+    #[test]
+    fn tampering_with_any_link_is_rejected() {
+        let mut room = Room::new();
+        let owner = room.owner.clone();
+        let alice = room.invite(&owner, 2);
+        let bob = room.invite(&alice, 3);
+        let msg = room.message(&bob);
+
+        let mut edited = room.prove(&msg);
+        edited.message.message.content = RoomMessageBody::public("I never said this".into());
+        assert!(edited.verify().unwrap_err().contains("message signature"));
+
+        let mut broken = room.prove(&msg);
+        broken.invite_chain.clear();
+        assert!(broken.verify().unwrap_err().contains("missing"));
+
+        // A chain that ends at a different owner is a different room.
+        let mut other_room = room.prove(&msg);
+        other_room.room_owner = SigningKey::from_bytes(&[9; 32]).verifying_key();
+        assert!(other_room.verify().is_err());
+    }
+}
@@ -29,6 +29,49 @@ impl Display for OutputFormat {
     }
 }
 
+/// What `--format` (and `format` in config.toml) accepts. `ndjson` only
+/// changes streaming commands, which then emit one typed event per line (see
+/// [`crate::room_events`]); every other command treats it as `json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatArg {
+    Human,
+    Json,
+    Ndjson,
+}
+
+impl FormatArg {
+    /// The format for commands that don't stream.
+    pub fn output(self) -> OutputFormat {
+        match self {
+            FormatArg::Human => OutputFormat::Human,
+            FormatArg::Json | FormatArg::Ndjson => OutputFormat::Json,
+        }
+    }
+}
+
+impl FromStr for FormatArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ndjson" => Ok(FormatArg::Ndjson),
+            _ => match s.parse::<OutputFormat>()? {
+                OutputFormat::Human => Ok(FormatArg::Human),
+                OutputFormat::Json => Ok(FormatArg::Json),
+            },
+        }
+    }
+}
+
+impl Display for FormatArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatArg::Ndjson => write!(f, "ndjson"),
+            other => other.output().fmt(f),
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub fn print_output<T: Serialize + Display>(data: T, format: OutputFormat) {
     match format {
@@ -342,6 +342,7 @@ pub fn build_member_info_heal(
         version: 0,
         preferred_nickname: sealed,
         deputies: Vec::new(),
+        profile: None,
     };
     Some(AuthorizedMemberInfo::new_with_member_key(info, self_sk))
 }
@@ -896,6 +897,7 @@ mod tests {
             version: 0,
             preferred_nickname: sealed,
             deputies: Vec::new(),
+            profile: None,
         };
         state
             .member_info
@@ -0,0 +1,418 @@
+//! Typed room events derived by diffing two room-state snapshots, for
+//! `message stream --format ndjson`.
+//!
+//! The `json` stream reports only what it has itself shown (messages and what
+//! later happens to them). The `ndjson` stream instead reports every change
+//! between successive `ChatRoomStateV1` snapshots, one JSON object per line,
+//! each with a `type`:
+//!
+//! | `type` | Emitted when |
+//! |---|---|
+//! | `message` | a message is in the new snapshot and not the old one |
+//! | `edit` | a message's effective text changed |
+//! | `delete` | a message the old snapshot showed is now deleted |
+//! | `reaction` | a shown message's reactions changed |
+//! | `join` | a member was added |
+//! | `member_pruned` | a member disappeared without being banned |
+//! | `ban` | a ban was added |
+//! | `config_changed` | the room configuration's version moved |
+//! | `dm` | a DM or group DM addressed to the reading member arrived |
+//!
+//! `message`, `edit`, `delete` and `reaction` have exactly the shape the
+//! `json` stream uses. DMs delivered to an inbox contract are not in room
+//! state and so are not reported here.
+
+use crate::api::{
+    deletion_event_json, message_event_json, message_raw_text_with_secrets, reaction_event_json,
+    reactions_fingerprint,
+};
+use crate::commands::dm::{format_dm_body_for_cli, hex_token, member_nicknames};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use river_core::room_state::direct_messages::open_direct_message;
+use river_core::room_state::dm_body::decode_body;
+use river_core::room_state::group_direct_messages::open_group_direct_message;
+use river_core::room_state::member::MemberId;
+use river_core::room_state::message::{AuthorizedMessageV1, MessageId};
+use river_core::room_state::ChatRoomStateV1;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime};
+
+/// What the diff needs besides the two snapshots.
+pub struct EventContext<'a> {
+    pub room_owner_key: &'a VerifyingKey,
+    /// Room secrets for a private room (empty for a public one); both
+    /// snapshots must already have had their private actions rebuilt.
+    pub secrets: &'a HashMap<u32, [u8; 32]>,
+    /// The identity the stream reads as. Without one, no `dm` events.
+    pub signing_key: Option<&'a SigningKey>,
+}
+
+/// Every change from `prev` to `next`, in a fixed order: room-level events
+/// first, then messages, then DMs.
+pub fn diff_events(
+    prev: &ChatRoomStateV1,
+    next: &ChatRoomStateV1,
+    ctx: &EventContext,
+) -> Vec<Value> {
+    let room = bs58::encode(ctx.room_owner_key.as_bytes()).into_string();
+    let mut events = Vec::new();
+    config_events(prev, next, &room, &mut events);
+    member_events(prev, next, ctx, &room, &mut events);
+    message_events(prev, next, ctx, &mut events);
+    if let Some(signing_key) = ctx.signing_key {
+        dm_events(prev, next, ctx, signing_key, &room, &mut events);
+    }
+    events
+}
+
+fn config_events(prev: &ChatRoomStateV1, next: &ChatRoomStateV1, room: &str, out: &mut Vec<Value>) {
+    let before = &prev.configuration.configuration;
+    let after = &next.configuration.configuration;
+    if before.configuration_version == after.configuration_version {
+        return;
+    }
+    // Compared field by field through serde so a new configuration field is
+    // reported without this list having to learn about it.
+    let fields = |c| match serde_json::to_value(c) {
+        Ok(Value::Object(map)) => map,
+        _ => Default::default(),
+    };
+    let (before_fields, after_fields) = (fields(before), fields(after));
+    let mut changed: Vec<&String> = after_fields
+        .keys()
+        .chain(before_fields.keys())
+        .filter(|k| k.as_str() != "configuration_version")
+        .filter(|k| before_fields.get(*k) != after_fields.get(*k))
+        .collect();
+    changed.sort();
+    changed.dedup();
+    out.push(json!({
+        "type": "config_changed",
+        "room": room,
+        "version": after.configuration_version,
+        "previous_version": before.configuration_version,
+        "changed": changed,
+    }));
+}
+
+fn member_events(
+    prev: &ChatRoomStateV1,
+    next: &ChatRoomStateV1,
+    ctx: &EventContext,
+    room: &str,
+    out: &mut Vec<Value>,
+) {
+    // A member who just left still has a nickname in the old snapshot.
+    let nicknames = member_nicknames(next, ctx.secrets);
+    let old_nicknames = member_nicknames(prev, ctx.secrets);
+    let nickname = |id: &MemberId| nicknames.get(id).or(old_nicknames.get(id)).cloned();
+
+    let before: HashSet<MemberId> = prev.members.members.iter().map(|m| m.member.id()).collect();
+    let after: HashSet<MemberId> = next.members.members.iter().map(|m| m.member.id()).collect();
+    for member in &next.members.members {
+        let id = member.member.id();
+        if !before.contains(&id) {
+            out.push(json!({
+                "type": "join",
+                "room": room,
+                "member": id.to_string(),
+                "nickname": nickname(&id),
+                "invited_by": member.member.invited_by.to_string(),
+            }));
+        }
+    }
+
+    let old_bans: HashSet<_> = prev.bans.0.iter().map(|b| b.id()).collect();
+    let banned: HashSet<MemberId> = next.bans.0.iter().map(|b| b.ban.banned_user).collect();
+    for ban in &next.bans.0 {
+        if old_bans.contains(&ban.id()) {
+            continue;
+        }
+        let at: DateTime<Utc> = ban.ban.banned_at.into();
+        out.push(json!({
+            "type": "ban",
+            "room": room,
+            "member": ban.ban.banned_user.to_string(),
+            "nickname": nickname(&ban.ban.banned_user),
+            "banned_by": ban.banned_by.to_string(),
+            "timestamp": at.to_rfc3339(),
+        }));
+    }
+
+    for member in &prev.members.members {
+        let id = member.member.id();
+        if !after.contains(&id) && !banned.contains(&id) {
+            out.push(json!({
+                "type": "member_pruned",
+                "room": room,
+                "member": id.to_string(),
+                "nickname": nickname(&id),
+            }));
+        }
+    }
+}
+
+fn message_events(
+    prev: &ChatRoomStateV1,
+    next: &ChatRoomStateV1,
+    ctx: &EventContext,
+    out: &mut Vec<Value>,
+) {
+    let old: HashMap<MessageId, &AuthorizedMessageV1> = prev
+        .recent_messages
+        .display_messages()
+        .map(|m| (m.id(), m))
+        .collect();
+
+    let mut shown: Vec<&AuthorizedMessageV1> = next
+        .recent_messages
+        .unexpired_display_messages(&next.configuration.configuration, SystemTime::now())
+        .collect();
+    shown.sort_by_key(|m| m.order_key());
+    for msg in shown {
+        let id = msg.id();
+        match old.get(&id) {
+            None => {
+                out.push(message_event_json(
+                    next,
+                    msg,
+                    ctx.room_owner_key,
+                    false,
+                    ctx.secrets,
+                ));
+            }
+            Some(before) => {
+                if message_raw_text_with_secrets(prev, before, ctx.secrets)
+                    != message_raw_text_with_secrets(next, msg, ctx.secrets)
+                {
+                    out.push(message_event_json(
+                        next,
+                        msg,
+                        ctx.room_owner_key,
+                        true,
+                        ctx.secrets,
+                    ));
+                }
+                if reactions_fingerprint(prev.recent_messages.reactions(&id))
+                    != reactions_fingerprint(next.recent_messages.reactions(&id))
+                {
+                    out.push(reaction_event_json(
+                        next,
+                        msg,
+                        ctx.room_owner_key,
+                        ctx.secrets,
+                    ));
+                }
+            }
+        }
+    }
+
+    for msg in &next.recent_messages.messages {
+        let id = msg.id();
+        if old.contains_key(&id) && next.recent_messages.is_deleted(&id) {
+            out.push(deletion_event_json(
+                next,
+                msg,
+                ctx.room_owner_key,
+                ctx.secrets,
+            ));
+        }
+    }
+}
+
+fn dm_events(
+    prev: &ChatRoomStateV1,
+    next: &ChatRoomStateV1,
+    ctx: &EventContext,
+    signing_key: &SigningKey,
+    room: &str,
+    out: &mut Vec<Value>,
+) {
+    let self_id = MemberId::from(&signing_key.verifying_key());
+    let nicknames = member_nicknames(next, ctx.secrets);
+    let nickname = |id: &MemberId| nicknames.get(id).cloned();
+    let timestamp = |secs: u64| -> String {
+        let at: DateTime<Utc> = (SystemTime::UNIX_EPOCH + Duration::from_secs(secs)).into();
+        at.to_rfc3339()
+    };
+
+    let seen: HashSet<[u8; 64]> = prev
+        .direct_messages
+        .messages
+        .iter()
+        .map(|m| m.sender_signature.to_bytes())
+        .collect();
+    for msg in &next.direct_messages.messages {
+        if msg.message.recipient != self_id || seen.contains(&msg.sender_signature.to_bytes()) {
+            continue;
+        }
+        let body = open_direct_message(signing_key, msg)
+            .ok()
+            .and_then(|bytes| decode_body(&bytes).ok())
+            .map(|body| format_dm_body_for_cli(&body, &nicknames));
+        out.push(json!({
+            "type": "dm",
+            "room": room,
+            "sender": msg.message.sender.to_string(),
+            "nickname": nickname(&msg.message.sender),
+            "group_thread": null,
+            "timestamp": timestamp(msg.message.timestamp),
+            "body": body,
+            "purge_token": hex_token(&msg.purge_token()),
+        }));
+    }
+
+    let seen: HashSet<[u8; 64]> = prev
+        .group_direct_messages
+        .messages
+        .iter()
+        .map(|m| m.sender_signature.to_bytes())
+        .collect();
+    for msg in &next.group_direct_messages.messages {
+        if msg.message.sender == self_id
+            || !msg.message.participants().any(|id| id == self_id)
+            || seen.contains(&msg.sender_signature.to_bytes())
+            || msg.purged_by(self_id, next)
+        {
+            continue;
+        }
+        let body = open_group_direct_message(signing_key, msg)
+            .ok()
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+        out.push(json!({
+            "type": "dm",
+            "room": room,
+            "sender": msg.message.sender.to_string(),
+            "nickname": nickname(&msg.message.sender),
+            "group_thread": msg.message.thread_id().to_string(),
+            "participants": msg
+                .message
+                .participants()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>(),
+            "timestamp": timestamp(msg.message.timestamp),
+            "body": body,
+            "purge_token": hex_token(&msg.purge_token()),
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use river_core::room_state::member::{AuthorizedMember, Member};
+    use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfo};
+    use river_core::room_state::message::{MessageV1, RoomMessageBody};
+
+    struct Room {
+        owner: SigningKey,
+        state: ChatRoomStateV1,
+        secs: u64,
+    }
+
+    impl Room {
+        fn new() -> Self {
+            let owner = SigningKey::from_bytes(&[7; 32]);
+            let mut state = ChatRoomStateV1::default();
+            let info =
+                MemberInfo::new_public(MemberId::from(&owner.verifying_key()), 1, "Owner".into());
+            state
+                .member_info
+                .member_info
+                .push(AuthorizedMemberInfo::new_with_member_key(info, &owner));
+            Room {
+                owner,
+                state,
+                secs: 1_700_000_000,
+            }
+        }
+
+        fn post(&mut self, content: RoomMessageBody) -> AuthorizedMessageV1 {
+            self.secs += 1;
+            let id = MemberId::from(&self.owner.verifying_key());
+            let msg = AuthorizedMessageV1::new(
+                MessageV1 {
+                    room_owner: id,
+                    author: id,
+                    time: SystemTime::UNIX_EPOCH + Duration::from_secs(self.secs),
+                    content,
+                },
+                &self.owner,
+            );
+            self.state.recent_messages.messages.push(msg.clone());
+            self.state.recent_messages.rebuild_actions_state();
+            msg
+        }
+
+        fn diff(&self, prev: &ChatRoomStateV1) -> Vec<Value> {
+            let key = self.owner.verifying_key();
+            let secrets = HashMap::new();
+            let ctx = EventContext {
+                room_owner_key: &key,
+                secrets: &secrets,
+                signing_key: None,
+            };
+            diff_events(prev, &self.state, &ctx)
+        }
+    }
+
+    fn types(events: &[Value]) -> Vec<&str> {
+        events.iter().map(|e| e["type"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn message_lifecycle_is_reported_once_per_change() {
+        let mut room = Room::new();
+        let empty = room.state.clone();
+        let first = room.post(RoomMessageBody::public("hello".into()));
+        let events = room.diff(&empty);
+        assert_eq!(types(&events), ["message"]);
+        assert_eq!(events[0]["content"], "hello");
+
+        let before = room.state.clone();
+        room.post(RoomMessageBody::edit(first.id(), "hello, world".into()));
+        room.post(RoomMessageBody::reaction(first.id(), "👍".into()));
+        let events = room.diff(&before);
+        assert_eq!(types(&events), ["edit", "reaction"]);
+        assert_eq!(events[0]["content"], "hello, world");
+
+        let before = room.state.clone();
+        room.post(RoomMessageBody::delete(first.id()));
+        assert_eq!(types(&room.diff(&before)), ["delete"]);
+        // Nothing changed, nothing reported.
+        assert!(room.diff(&room.state).is_empty());
+    }
+
+    #[test]
+    fn membership_and_configuration_changes_are_reported() {
+        let mut room = Room::new();
+        let owner_id = MemberId::from(&room.owner.verifying_key());
+        let member = |seed: u8| {
+            AuthorizedMember::new(
+                Member {
+                    owner_member_id: owner_id,
+                    invited_by: owner_id,
+                    member_vk: SigningKey::from_bytes(&[seed; 32]).verifying_key(),
+                },
+                &room.owner,
+            )
+        };
+        let (alice, bob) = (member(1), member(2));
+        let empty = room.state.clone();
+        room.state.members.members = vec![alice.clone(), bob.clone()];
+        let events = room.diff(&empty);
+        assert_eq!(types(&events), ["join", "join"]);
+        assert_eq!(events[0]["member"], alice.member.id().to_string());
+
+        let before = room.state.clone();
+        room.state.members.members = vec![bob];
+        room.state.configuration.configuration.configuration_version += 1;
+        room.state.configuration.configuration.max_members += 1;
+        let events = room.diff(&before);
+        assert_eq!(types(&events), ["config_changed", "member_pruned"]);
+        assert_eq!(events[0]["changed"], json!(["max_members"]));
+        assert_eq!(events[1]["member"], alice.member.id().to_string());
+    }
+}
@@ -0,0 +1,504 @@
+//! Declarative room policy for `riverctl room apply`.
+//!
+//! A policy file is a TOML document naming the room settings an owner wants
+//! in force. Every key is optional: a key that is absent is left alone, so a
+//! policy only ever describes what it manages. Applying a policy diffs it
+//! against the live room and emits the smallest delta that closes the gap —
+//! a re-signed `Configuration` only when a configuration field differs, the
+//! owner's own `MemberInfo` only when the deputy set differs, and the welcome
+//! message only when the owner has no retained message with that text.
+//!
+//! ```toml
+//! name = "Platform team"
+//! description = "Deploys, incidents and on-call handover"
+//! deputies = ["a1b2c3d4", "e5f6g7h8"]
+//! welcome_message = "Welcome! Read the handbook before posting."
+//!
+//! [limits]
+//! max_recent_messages = 200
+//! max_members = 150
+//! max_direct_messages = 300
+//! ```
+//!
+//! Planning is a pure function of the policy and an already-unsealed view of
+//! the room ([`LiveRoomSettings`]) so it can be tested without a node; the
+//! command in `commands/room.rs` owns the fetch, the sealing and the send.
+//!
+//! River has no message pinning, so `welcome_message` is "present in retained
+//! history", not pinned: once retention evicts it, the next `apply` posts it
+//! again. That is what makes re-running the same policy converge.
+
+use river_core::room_state::configuration::Configuration;
+use river_core::room_state::member::MemberId;
+use river_core::room_state::member_info::{MemberInfoV1, MAX_DEPUTIES};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// The settings a policy file may manage. See the module docs for the format.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoomPolicy {
+    pub name: Option<String>,
+    /// An empty string clears the description.
+    pub description: Option<String>,
+    #[serde(default)]
+    pub limits: PolicyLimits,
+    /// Members the OWNER deputizes, by full or 8-character short member ID.
+    /// Deputy grants are per-deputizer (#410); this manages only the owner's
+    /// grants, which are the room-wide ones.
+    pub deputies: Option<Vec<String>>,
+    pub welcome_message: Option<String>,
+}
+
+/// Owner-tunable numeric limits, named exactly as their `Configuration` fields.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyLimits {
+    pub max_recent_messages: Option<usize>,
+    pub max_user_bans: Option<usize>,
+    pub max_message_size: Option<usize>,
+    pub max_nickname_size: Option<usize>,
+    pub max_members: Option<usize>,
+    pub max_room_name: Option<usize>,
+    pub max_room_description: Option<usize>,
+    pub max_direct_messages: Option<usize>,
+}
+
+impl RoomPolicy {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("failed to read policy file {}: {e}", path.display()))?;
+        Self::parse(&text)
+            .map_err(|e| anyhow::anyhow!("invalid policy file {}: {e}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+}
+
+/// The live room, already unsealed by the caller.
+pub struct LiveRoomSettings<'a> {
+    pub configuration: &'a Configuration,
+    pub name: String,
+    pub description: Option<String>,
+    /// The owner's current deputies, from their canonical `MemberInfo`.
+    pub deputies: Vec<MemberId>,
+    /// Used to resolve the policy's deputy IDs to present members.
+    pub member_info: &'a MemberInfoV1,
+    /// Display text of every retained owner-authored message.
+    pub owner_message_texts: Vec<String>,
+}
+
+/// One configuration field the policy would change.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub current: String,
+    pub desired: String,
+}
+
+/// What applying a policy would do. Empty when the room already complies.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PolicyPlan {
+    pub configuration: Vec<FieldChange>,
+    #[serde(skip)]
+    pub name: Option<String>,
+    #[serde(skip)]
+    pub description: Option<Option<String>>,
+    /// The policy's limits, applied wholesale: restating a current value is a
+    /// no-op, and `configuration` above says whether anything differs at all.
+    #[serde(skip)]
+    pub limits: PolicyLimits,
+    /// The owner's complete new deputy list, when it differs.
+    #[serde(serialize_with = "serialize_member_ids")]
+    pub deputies: Option<Vec<MemberId>>,
+    pub welcome_message: Option<String>,
+}
+
+impl PolicyPlan {
+    pub fn is_empty(&self) -> bool {
+        !self.changes_configuration() && self.deputies.is_none() && self.welcome_message.is_none()
+    }
+
+    pub fn changes_configuration(&self) -> bool {
+        !self.configuration.is_empty()
+    }
+
+    /// Write the planned limits into `cfg`. Name and description are not
+    /// touched: they must be sealed for the room's privacy mode first.
+    pub fn apply_limits(&self, cfg: &mut Configuration) {
+        let l = &self.limits;
+        let set = |field: &mut usize, value: Option<usize>| {
+            if let Some(v) = value {
+                *field = v;
+            }
+        };
+        set(&mut cfg.max_recent_messages, l.max_recent_messages);
+        set(&mut cfg.max_user_bans, l.max_user_bans);
+        set(&mut cfg.max_message_size, l.max_message_size);
+        set(&mut cfg.max_nickname_size, l.max_nickname_size);
+        set(&mut cfg.max_members, l.max_members);
+        set(&mut cfg.max_room_name, l.max_room_name);
+        set(&mut cfg.max_room_description, l.max_room_description);
+        if l.max_direct_messages.is_some() {
+            cfg.max_direct_messages = l.max_direct_messages;
+        }
+    }
+}
+
+fn serialize_member_ids<S: serde::Serializer>(
+    ids: &Option<Vec<MemberId>>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    ids.as_ref()
+        .map(|ids| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>())
+        .serialize(s)
+}
+
+/// Diff `policy` against `live`.
+///
+/// Errors, before anything is signed, on a value the contract would reject
+/// (a zero limit, a name or description longer than the limit that will be in
+/// force) or a deputy ID that does not resolve to exactly one member.
+pub fn plan(policy: &RoomPolicy, live: &LiveRoomSettings<'_>) -> Result<PolicyPlan, String> {
+    let cfg = live.configuration;
+    let mut out = PolicyPlan::default();
+
+    let l = &policy.limits;
+    out.limits = l.clone();
+    // `max_direct_messages` is compared against the EFFECTIVE cap, so a policy
+    // restating the default does not re-sign a legacy (`None`) configuration.
+    let limits = [
+        (
+            "max_recent_messages",
+            l.max_recent_messages,
+            cfg.max_recent_messages,
+        ),
+        ("max_user_bans", l.max_user_bans, cfg.max_user_bans),
+        ("max_message_size", l.max_message_size, cfg.max_message_size),
+        (
+            "max_nickname_size",
+            l.max_nickname_size,
+            cfg.max_nickname_size,
+        ),
+        ("max_members", l.max_members, cfg.max_members),
+        ("max_room_name", l.max_room_name, cfg.max_room_name),
+        (
+            "max_room_description",
+            l.max_room_description,
+            cfg.max_room_description,
+        ),
+        (
+            "max_direct_messages",
+            l.max_direct_messages,
+            cfg.effective_max_direct_messages(),
+        ),
+    ];
+    if l.max_direct_messages == Some(cfg.effective_max_direct_messages()) {
+        out.limits.max_direct_messages = None;
+    }
+    for (field, desired, current) in limits {
+        let Some(desired) = desired else { continue };
+        if desired == 0 {
+            return Err(format!("limits.{field} must be greater than zero"));
+        }
+        if desired != current {
+            out.configuration.push(FieldChange {
+                field,
+                current: current.to_string(),
+                desired: desired.to_string(),
+            });
+        }
+    }
+
+    // The name and description that will be in force are checked against the
+    // limits that will be in force, so lowering a limit below the live text
+    // fails here rather than in the contract.
+    let max_room_name = l.max_room_name.unwrap_or(cfg.max_room_name);
+    let name = policy.name.as_ref().unwrap_or(&live.name);
+    if policy.name.is_some() && name.is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    if name.len() > max_room_name {
+        return Err(format!(
+            "name is {} bytes, over max_room_name {max_room_name}",
+            name.len()
+        ));
+    }
+    if let Some(name) = &policy.name {
+        if *name != live.name {
+            out.name = Some(name.clone());
+            out.configuration.push(FieldChange {
+                field: "name",
+                current: live.name.clone(),
+                desired: name.clone(),
+            });
+        }
+    }
+
+    let max_room_description = l.max_room_description.unwrap_or(cfg.max_room_description);
+    let description = policy
+        .description
+        .as_deref()
+        .or(live.description.as_deref())
+        .unwrap_or_default();
+    if description.len() > max_room_description {
+        return Err(format!(
+            "description is {} bytes, over max_room_description {max_room_description}",
+            description.len()
+        ));
+    }
+    if let Some(description) = &policy.description {
+        let desired = (!description.is_empty()).then(|| description.clone());
+        if desired != live.description {
+            out.configuration.push(FieldChange {
+                field: "description",
+                current: live.description.clone().unwrap_or_default(),
+                desired: description.clone(),
+            });
+            out.description = Some(desired);
+        }
+    }
+
+    if let Some(wanted) = &policy.deputies {
+        let mut resolved = Vec::with_capacity(wanted.len());
+        for id in wanted {
+            let member = resolve_member(live.member_info, id)?;
+            if cfg.owner_member_id == member {
+                return Err("the room owner cannot be their own deputy".to_string());
+            }
+            if !resolved.contains(&member) {
+                resolved.push(member);
+            }
+        }
+        if resolved.len() > MAX_DEPUTIES {
+            return Err(format!(
+                "{} deputies listed, over the maximum of {MAX_DEPUTIES}",
+                resolved.len()
+            ));
+        }
+        let current: BTreeSet<_> = live.deputies.iter().copied().collect();
+        let desired: BTreeSet<_> = resolved.iter().copied().collect();
+        if current != desired {
+            out.deputies = Some(resolved);
+        }
+    }
+
+    if let Some(welcome) = &policy.welcome_message {
+        if !live.owner_message_texts.iter().any(|t| t == welcome) {
+            out.welcome_message = Some(welcome.clone());
+        }
+    }
+
+    Ok(out)
+}
+
+/// Resolve a full or short member ID against the room's `member_info`, with
+/// the same matching rule `member deputize` uses, but refusing an ambiguous
+/// prefix instead of taking the first hit: a policy is applied unattended.
+fn resolve_member(member_info: &MemberInfoV1, id: &str) -> Result<MemberId, String> {
+    let matches: BTreeSet<MemberId> = member_info
+        .member_info
+        .iter()
+        .map(|info| info.member_info.member_id)
+        .filter(|m| {
+            let s = m.to_string();
+            s == id || s.starts_with(id) || s[..8.min(s.len())].eq_ignore_ascii_case(id)
+        })
+        .collect();
+    match matches.len() {
+        1 => Ok(*matches.iter().next().unwrap()),
+        0 => Err(format!("deputy '{id}' is not a member of this room")),
+        n => Err(format!("deputy '{id}' is ambiguous ({n} members match)")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfo};
+
+    fn member_info_for(keys: &[&SigningKey]) -> MemberInfoV1 {
+        MemberInfoV1 {
+            member_info: keys
+                .iter()
+                .map(|sk| {
+                    let id: MemberId = sk.verifying_key().into();
+                    AuthorizedMemberInfo::new_with_member_key(
+                        MemberInfo::new_public(id, 0, "n".to_string()),
+                        sk,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn live<'a>(cfg: &'a Configuration, infos: &'a MemberInfoV1) -> LiveRoomSettings<'a> {
+        LiveRoomSettings {
+            configuration: cfg,
+            name: "Room".to_string(),
+            description: None,
+            deputies: Vec::new(),
+            member_info: infos,
+            owner_message_texts: vec!["hello".to_string()],
+        }
+    }
+
+    #[test]
+    fn parses_the_documented_format() {
+        let policy = RoomPolicy::parse(
+            r#"
+            name = "Platform team"
+            deputies = ["a1b2c3d4"]
+            welcome_message = "Welcome!"
+
+            [limits]
+            max_recent_messages = 200
+            max_direct_messages = 300
+            "#,
+        )
+        .unwrap();
+        assert_eq!(policy.name.as_deref(), Some("Platform team"));
+        assert_eq!(policy.limits.max_recent_messages, Some(200));
+        assert_eq!(policy.limits.max_members, None);
+        assert_eq!(policy.deputies, Some(vec!["a1b2c3d4".to_string()]));
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        // A typo must not silently become "leave this setting alone".
+        let err = RoomPolicy::parse("[limits]\nmax_recent_mesages = 5\n").unwrap_err();
+        assert!(err.contains("max_recent_mesages"), "{err}");
+    }
+
+    #[test]
+    fn compliant_room_plans_nothing() {
+        let cfg = Configuration::default();
+        let infos = MemberInfoV1::default();
+        let policy = RoomPolicy {
+            name: Some("Room".to_string()),
+            limits: PolicyLimits {
+                max_recent_messages: Some(cfg.max_recent_messages),
+                // Restating the default DM cap must not re-sign a legacy config.
+                max_direct_messages: Some(cfg.effective_max_direct_messages()),
+                ..Default::default()
+            },
+            welcome_message: Some("hello".to_string()),
+            ..Default::default()
+        };
+        assert!(plan(&policy, &live(&cfg, &infos)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn plans_only_the_fields_that_differ() {
+        let cfg = Configuration::default();
+        let infos = MemberInfoV1::default();
+        let policy = RoomPolicy {
+            description: Some("About".to_string()),
+            limits: PolicyLimits {
+                max_members: Some(cfg.max_members),
+                max_recent_messages: Some(42),
+                ..Default::default()
+            },
+            welcome_message: Some("Welcome!".to_string()),
+            ..Default::default()
+        };
+        let plan = plan(&policy, &live(&cfg, &infos)).unwrap();
+        let fields: Vec<_> = plan.configuration.iter().map(|c| c.field).collect();
+        assert_eq!(fields, ["max_recent_messages", "description"]);
+        assert_eq!(plan.description, Some(Some("About".to_string())));
+        assert_eq!(plan.welcome_message.as_deref(), Some("Welcome!"));
+
+        let mut next = cfg.clone();
+        plan.apply_limits(&mut next);
+        assert_eq!(next.max_recent_messages, 42);
+        assert_eq!(next.max_members, cfg.max_members);
+    }
+
+    #[test]
+    fn rejects_values_the_contract_would_reject() {
+        let cfg = Configuration::default();
+        let infos = MemberInfoV1::default();
+        let zero = RoomPolicy {
+            limits: PolicyLimits {
+                max_members: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(plan(&zero, &live(&cfg, &infos)).is_err());
+
+        // The name is checked against the limit the same policy sets.
+        let long_name = RoomPolicy {
+            name: Some("abcdef".to_string()),
+            limits: PolicyLimits {
+                max_room_name: Some(5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(plan(&long_name, &live(&cfg, &infos)).is_err());
+    }
+
+    #[test]
+    fn lowering_a_limit_below_the_live_name_or_description_is_rejected() {
+        // The policy leaves the text alone, but the contract would still
+        // reject the live name and description under the new limits.
+        let cfg = Configuration::default();
+        let infos = MemberInfoV1::default();
+        let mut room = live(&cfg, &infos);
+        room.description = Some("About this room".to_string());
+        let shrink_name = RoomPolicy {
+            limits: PolicyLimits {
+                max_room_name: Some(3),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = plan(&shrink_name, &room).unwrap_err();
+        assert!(err.contains("max_room_name 3"), "{err}");
+        let shrink_description = RoomPolicy {
+            limits: PolicyLimits {
+                max_room_description: Some(5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = plan(&shrink_description, &room).unwrap_err();
+        assert!(err.contains("max_room_description 5"), "{err}");
+    }
+
+    #[test]
+    fn deputies_are_compared_as_a_set_and_must_resolve() {
+        let a = SigningKey::from_bytes(&[1; 32]);
+        let b = SigningKey::from_bytes(&[2; 32]);
+        let (a_id, b_id): (MemberId, MemberId) =
+            (a.verifying_key().into(), b.verifying_key().into());
+        let cfg = Configuration::default();
+        let infos = member_info_for(&[&a, &b]);
+        let short = |id: MemberId| id.to_string()[..8].to_string();
+
+        let mut room = live(&cfg, &infos);
+        room.deputies = vec![b_id, a_id];
+        let same = RoomPolicy {
+            deputies: Some(vec![short(a_id), short(b_id)]),
+            ..Default::default()
+        };
+        assert_eq!(plan(&same, &room).unwrap().deputies, None);
+
+        let fewer = RoomPolicy {
+            deputies: Some(vec![short(a_id)]),
+            ..Default::default()
+        };
+        assert_eq!(plan(&fewer, &room).unwrap().deputies, Some(vec![a_id]));
+
+        let unknown = RoomPolicy {
+            deputies: Some(vec!["zzzzzzzz".to_string()]),
+            ..Default::default()
+        };
+        assert!(plan(&unknown, &room).is_err());
+    }
+}
@@ -8,7 +8,10 @@ use directories::ProjectDirs;
 use ed25519_dalek::{SigningKey, VerifyingKey};
 use freenet_stdlib::prelude::ContractKey;
 use fs2::FileExt;
-use river_core::chat_delegate::OutboundDmStore;
+use river_core::chat_delegate::{
+    DraftStore, IgnoreList, OutboundDmStore, ReadMarkerStore, ScheduledMessageStore,
+    VerifiedKeyStore,
+};
 use river_core::room_state::member::{AuthorizedMember, MemberId};
 use river_core::room_state::ChatRoomStateV1;
 use serde::{Deserialize, Serialize};
@@ -85,6 +88,24 @@ pub struct RoomStorage {
     pub rooms: HashMap<String, StoredRoomInfo>,
 }
 
+/// A space the user created or joined with `riverctl space`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredSpaceInfo {
+    /// The user's key in the space: the space owner key for its creator, the
+    /// key carried by the space invitation for everyone else. A member joins
+    /// the space's rooms with this same key.
+    pub signing_key_bytes: [u8; 32],
+    /// The space's name as of the last sync, so `space list` works offline.
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SpaceStorage {
+    /// Map from space owner verifying key (as base58) to space info
+    pub spaces: HashMap<String, StoredSpaceInfo>,
+}
+
 /// Who the local user is *within one room* (freenet/river#438).
 ///
 /// River identities are per-room: each room in `rooms.json` carries its own
@@ -250,7 +271,9 @@ pub enum ImportOutcome {
     },
 }
 
-/// Local on-disk persistence for riverctl (`rooms.json` + `outbound_dms.json`).
+/// Local on-disk persistence for riverctl (`rooms.json`, `outbound_dms.json`,
+/// `read_markers.json`, `scheduled_messages.json`, `drafts.json`,
+/// `verified_keys.json` and `ignored_members.json`).
 ///
 /// **Concurrency model (issue freenet/river#307).** riverctl is a CLI invoked
 /// one command at a time, but a script or cron job can run several invocations
@@ -284,6 +307,24 @@ pub struct Storage {
     /// Side file so the larger `rooms.json` blob stays untouched on
     /// each DM send. JSON-serialized [`OutboundDmStore`].
     outbound_dms_path: PathBuf,
+    /// Per-room read markers, JSON-serialized [`ReadMarkerStore`] — the same
+    /// type the UI keeps in the chat delegate, so both sides agree on what
+    /// "read" means and merge the same way.
+    read_markers_path: PathBuf,
+    /// Messages queued by `message send --at`, JSON-serialized
+    /// [`ScheduledMessageStore`]. Plaintext on disk, like `outbound_dms.json`.
+    scheduled_messages_path: PathBuf,
+    /// `riverctl draft` text, JSON-serialized [`DraftStore`]. Private-room
+    /// drafts are sealed with the room secret inside it.
+    drafts_path: PathBuf,
+    /// Members marked verified by `member verify`, JSON-serialized
+    /// [`VerifiedKeyStore`].
+    verified_keys_path: PathBuf,
+    /// Members ignored with `member ignore`, JSON-serialized [`IgnoreList`].
+    ignored_members_path: PathBuf,
+    /// Spaces created or joined with `riverctl space`, JSON-serialized
+    /// [`SpaceStorage`]. Holds signing keys, like `rooms.json`.
+    spaces_path: PathBuf,
     /// Dedicated advisory-lock file (`.river.lock`) guarding the whole
     /// `load → mutate → save` critical section against concurrent riverctl
     /// invocations (issue freenet/river#307). A SEPARATE file from the data
@@ -305,6 +346,9 @@ pub struct Storage {
     /// nominate the right identity at command time without touching
     /// `rooms.json`. See discussion on river#281.
     signing_key_override: Option<SigningKey>,
+    /// Identities nominated in `config.toml` (see [`crate::config`]). They
+    /// rank below [`Self::signing_key_override`] and above the stored key.
+    configured_identities: ConfiguredIdentities,
     /// The room-contract pointer's floor store (`pointer_floors.json`). A side
     /// file, like `outbound_dms.json`, so the anti-rollback floor is never
     /// entangled with the room blob's read-modify-write.
@@ -323,6 +367,36 @@ pub struct Storage {
     room_code_hash: OnceLock<[u8; 32]>,
 }
 
+/// Signing identities read from `config.toml`: one per room, plus an optional
+/// default (`user_key_path`) for rooms without their own.
+#[derive(Clone, Default)]
+pub struct ConfiguredIdentities {
+    /// Keyed by base58 room owner key.
+    pub per_room: HashMap<String, SigningKey>,
+    pub default: Option<SigningKey>,
+}
+
+impl ConfiguredIdentities {
+    fn for_room(&self, owner_key_str: &str) -> Option<&SigningKey> {
+        self.per_room.get(owner_key_str).or(self.default.as_ref())
+    }
+}
+
+/// The directory holding `rooms.json`, `config.toml` and the side files:
+/// `config_dir` if given, else `RIVER_CONFIG_DIR`, else the platform's data
+/// directory for River. Not created here.
+pub fn data_dir(config_dir: Option<&str>) -> Result<PathBuf> {
+    if let Some(dir) = config_dir {
+        Ok(PathBuf::from(dir))
+    } else if let Ok(config_dir) = std::env::var("RIVER_CONFIG_DIR") {
+        Ok(PathBuf::from(config_dir))
+    } else {
+        let proj_dirs = ProjectDirs::from("", "Freenet", "River")
+            .ok_or_else(|| anyhow!("Failed to determine project directories"))?;
+        Ok(proj_dirs.data_dir().to_path_buf())
+    }
+}
+
 impl Storage {
     pub fn new(config_dir: Option<&str>) -> Result<Self> {
         Self::new_with_override(config_dir, None)
@@ -334,35 +408,43 @@ impl Storage {
         config_dir: Option<&str>,
         signing_key_override: Option<SigningKey>,
     ) -> Result<Self> {
-        // Use provided config_dir, then check environment variable, then use default
-        let data_dir = if let Some(dir) = config_dir {
-            PathBuf::from(dir)
-        } else if let Ok(config_dir) = std::env::var("RIVER_CONFIG_DIR") {
-            PathBuf::from(config_dir)
-        } else {
-            // Fall back to default project directories
-            let proj_dirs = ProjectDirs::from("", "Freenet", "River")
-                .ok_or_else(|| anyhow!("Failed to determine project directories"))?;
-            proj_dirs.data_dir().to_path_buf()
-        };
-
+        let data_dir = data_dir(config_dir)?;
         fs::create_dir_all(&data_dir)?;
 
         let storage_path = data_dir.join("rooms.json");
         let outbound_dms_path = data_dir.join("outbound_dms.json");
+        let read_markers_path = data_dir.join("read_markers.json");
+        let scheduled_messages_path = data_dir.join("scheduled_messages.json");
+        let drafts_path = data_dir.join("drafts.json");
+        let verified_keys_path = data_dir.join("verified_keys.json");
+        let ignored_members_path = data_dir.join("ignored_members.json");
+        let spaces_path = data_dir.join("spaces.json");
         let lock_path = data_dir.join(".river.lock");
         let pointer_floors_path = data_dir.join("pointer_floors.json");
 
         Ok(Self {
             storage_path,
             outbound_dms_path,
+            read_markers_path,
+            scheduled_messages_path,
+            drafts_path,
+            verified_keys_path,
+            ignored_members_path,
+            spaces_path,
             lock_path,
             signing_key_override,
+            configured_identities: ConfiguredIdentities::default(),
             pointer_floors_path,
             room_code_hash: OnceLock::new(),
         })
     }
 
+    /// Install the signing identities nominated in `config.toml`.
+    pub fn with_configured_identities(mut self, identities: ConfiguredIdentities) -> Self {
+        self.configured_identities = identities;
+        self
+    }
+
     /// Record the room-contract code hash resolved from River's pointer record,
     /// so key regeneration in [`Self::load_rooms`] agrees with the keys the
     /// network paths are deriving. Idempotent; a second call is ignored.
@@ -500,19 +582,30 @@ impl Storage {
         result
     }
 
-    /// Resolve the signing key to use for the current command: prefer
-    /// the in-memory override if set, otherwise reconstruct from the
-    /// per-room `signing_key_bytes`. Used by both [`Storage::get_room`]
-    /// and [`crate::api::ApiClient::ensure_room_migrated`] (which has
-    /// its own load_rooms snapshot for migration purposes).
-    pub fn resolve_signing_key(&self, stored_bytes: &[u8; 32]) -> SigningKey {
+    /// Resolve the signing key to use for the current command in the room
+    /// whose base58 owner key is `owner_key_str`: the in-memory override if
+    /// set, then the room's identity from `config.toml`, then the config's
+    /// default identity, and finally the per-room `signing_key_bytes`. Used by
+    /// both [`Storage::get_room`] and
+    /// [`crate::api::ApiClient::ensure_room_migrated`] (which has its own
+    /// load_rooms snapshot for migration purposes).
+    pub fn resolve_signing_key(&self, owner_key_str: &str, stored_bytes: &[u8; 32]) -> SigningKey {
         if let Some(override_key) = &self.signing_key_override {
             override_key.clone()
+        } else if let Some(key) = self.configured_identities.for_room(owner_key_str) {
+            key.clone()
         } else {
             SigningKey::from_bytes(stored_bytes)
         }
     }
 
+    /// Whether `config.toml` nominates the identity for this room, either
+    /// per room or through its default. Only meaningful when no
+    /// [`Self::has_signing_key_override`] is active, since that outranks it.
+    pub fn has_configured_identity(&self, owner_key_str: &str) -> bool {
+        self.configured_identities.for_room(owner_key_str).is_some()
+    }
+
     /// The room's STORED signing key, IGNORING any `--signing-key-file` /
     /// `RIVER_SIGNING_KEY_FILE` override. Returns `None` if the room isn't in
     /// local storage.
@@ -747,6 +840,168 @@ impl Storage {
         Self::atomic_write(&self.outbound_dms_path, &contents)
     }
 
+    /// Load the local read markers. Returns an empty store if the file does
+    /// not exist.
+    pub fn load_read_markers(&self) -> Result<ReadMarkerStore> {
+        self.with_lock(|| self.load_read_markers_unlocked())
+    }
+
+    fn load_read_markers_unlocked(&self) -> Result<ReadMarkerStore> {
+        if !self.read_markers_path.exists() {
+            return Ok(ReadMarkerStore::default());
+        }
+        let contents = fs::read_to_string(&self.read_markers_path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Like [`Self::mutate_outbound_dms`] but for `read_markers.json`. Only
+    /// re-saves when `f` reports a change, so marking an already-read room
+    /// read leaves the file alone.
+    pub fn mutate_read_markers(
+        &self,
+        f: impl FnOnce(&mut ReadMarkerStore) -> bool,
+    ) -> Result<bool> {
+        self.with_lock(|| {
+            let mut store = self.load_read_markers_unlocked()?;
+            let changed = f(&mut store);
+            if changed {
+                let contents = serde_json::to_string_pretty(&store)?;
+                Self::atomic_write(&self.read_markers_path, &contents)?;
+            }
+            Ok(changed)
+        })
+    }
+
+    /// Load the queued messages. Returns an empty store if the file does not
+    /// exist.
+    pub fn load_scheduled_messages(&self) -> Result<ScheduledMessageStore> {
+        self.with_lock(|| self.load_scheduled_messages_unlocked())
+    }
+
+    fn load_scheduled_messages_unlocked(&self) -> Result<ScheduledMessageStore> {
+        if !self.scheduled_messages_path.exists() {
+            return Ok(ScheduledMessageStore::default());
+        }
+        let contents = fs::read_to_string(&self.scheduled_messages_path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Like [`Self::mutate_outbound_dms`] but for `scheduled_messages.json`.
+    /// The dispatcher claims due entries through this, so two concurrent
+    /// `message queue run` processes never both send one.
+    pub fn mutate_scheduled_messages<T>(
+        &self,
+        f: impl FnOnce(&mut ScheduledMessageStore) -> T,
+    ) -> Result<T> {
+        self.with_lock(|| {
+            let mut store = self.load_scheduled_messages_unlocked()?;
+            let out = f(&mut store);
+            let contents = serde_json::to_string_pretty(&store)?;
+            Self::atomic_write(&self.scheduled_messages_path, &contents)?;
+            Ok(out)
+        })
+    }
+
+    /// Load the saved drafts. Returns an empty store if the file does not
+    /// exist.
+    pub fn load_drafts(&self) -> Result<DraftStore> {
+        self.with_lock(|| self.load_drafts_unlocked())
+    }
+
+    fn load_drafts_unlocked(&self) -> Result<DraftStore> {
+        if !self.drafts_path.exists() {
+            return Ok(DraftStore::default());
+        }
+        let contents = fs::read_to_string(&self.drafts_path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Like [`Self::mutate_scheduled_messages`] but for `drafts.json`.
+    pub fn mutate_drafts<T>(&self, f: impl FnOnce(&mut DraftStore) -> T) -> Result<T> {
+        self.with_lock(|| {
+            let mut store = self.load_drafts_unlocked()?;
+            let out = f(&mut store);
+            let contents = serde_json::to_string_pretty(&store)?;
+            Self::atomic_write(&self.drafts_path, &contents)?;
+            Ok(out)
+        })
+    }
+
+    /// Load the members marked verified. Returns an empty store if the file
+    /// does not exist.
+    pub fn load_verified_keys(&self) -> Result<VerifiedKeyStore> {
+        self.with_lock(|| self.load_verified_keys_unlocked())
+    }
+
+    fn load_verified_keys_unlocked(&self) -> Result<VerifiedKeyStore> {
+        if !self.verified_keys_path.exists() {
+            return Ok(VerifiedKeyStore::default());
+        }
+        let contents = fs::read_to_string(&self.verified_keys_path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Like [`Self::mutate_scheduled_messages`] but for `verified_keys.json`.
+    pub fn mutate_verified_keys<T>(&self, f: impl FnOnce(&mut VerifiedKeyStore) -> T) -> Result<T> {
+        self.with_lock(|| {
+            let mut store = self.load_verified_keys_unlocked()?;
+            let out = f(&mut store);
+            let contents = serde_json::to_string_pretty(&store)?;
+            Self::atomic_write(&self.verified_keys_path, &contents)?;
+            Ok(out)
+        })
+    }
+
+    /// Load the ignore list. Returns an empty list if the file does not
+    /// exist.
+    pub fn load_ignore_list(&self) -> Result<IgnoreList> {
+        self.with_lock(|| self.load_ignore_list_unlocked())
+    }
+
+    fn load_ignore_list_unlocked(&self) -> Result<IgnoreList> {
+        if !self.ignored_members_path.exists() {
+            return Ok(IgnoreList::default());
+        }
+        let contents = fs::read_to_string(&self.ignored_members_path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Like [`Self::mutate_scheduled_messages`] but for `ignored_members.json`.
+    pub fn mutate_ignore_list<T>(&self, f: impl FnOnce(&mut IgnoreList) -> T) -> Result<T> {
+        self.with_lock(|| {
+            let mut list = self.load_ignore_list_unlocked()?;
+            let out = f(&mut list);
+            let contents = serde_json::to_string_pretty(&list)?;
+            Self::atomic_write(&self.ignored_members_path, &contents)?;
+            Ok(out)
+        })
+    }
+
+    /// Load the stored spaces. Returns an empty store if the file does not
+    /// exist.
+    pub fn load_spaces(&self) -> Result<SpaceStorage> {
+        self.with_lock(|| self.load_spaces_unlocked())
+    }
+
+    fn load_spaces_unlocked(&self) -> Result<SpaceStorage> {
+        if !self.spaces_path.exists() {
+            return Ok(SpaceStorage::default());
+        }
+        let contents = fs::read_to_string(&self.spaces_path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Like [`Self::mutate_scheduled_messages`] but for `spaces.json`.
+    pub fn mutate_spaces<T>(&self, f: impl FnOnce(&mut SpaceStorage) -> T) -> Result<T> {
+        self.with_lock(|| {
+            let mut spaces = self.load_spaces_unlocked()?;
+            let out = f(&mut spaces);
+            let contents = serde_json::to_string_pretty(&spaces)?;
+            Self::atomic_write(&self.spaces_path, &contents)?;
+            Ok(out)
+        })
+    }
+
     pub fn add_room(
         &self,
         owner_vk: &VerifyingKey,
@@ -1042,7 +1297,8 @@ impl Storage {
         let owner_key_str = bs58::encode(owner_vk.as_bytes()).into_string();
 
         if let Some(room_info) = storage.rooms.get(&owner_key_str) {
-            let signing_key = self.resolve_signing_key(&room_info.signing_key_bytes);
+            let signing_key =
+                self.resolve_signing_key(&owner_key_str, &room_info.signing_key_bytes);
             Ok(Some((
                 signing_key,
                 room_info.state.clone(),
@@ -1053,6 +1309,31 @@ impl Storage {
         }
     }
 
+    /// Owner keys of the stored rooms in which `member_vk` is the user's key,
+    /// for the DM inbox allowlist.
+    pub fn rooms_held_by(&self, member_vk: &VerifyingKey) -> Result<Vec<VerifyingKey>> {
+        let storage = self.load_rooms()?;
+        let mut rooms: Vec<VerifyingKey> = storage
+            .rooms
+            .iter()
+            .filter(|(owner_key_str, room_info)| {
+                self.resolve_signing_key(owner_key_str, &room_info.signing_key_bytes)
+                    .verifying_key()
+                    == *member_vk
+            })
+            .filter_map(|(owner_key_str, _)| {
+                let bytes: [u8; 32] = bs58::decode(owner_key_str)
+                    .into_vec()
+                    .ok()?
+                    .try_into()
+                    .ok()?;
+                VerifyingKey::from_bytes(&bytes).ok()
+            })
+            .collect();
+        rooms.sort_by_key(|vk| vk.to_bytes());
+        Ok(rooms)
+    }
+
     pub fn update_room_state(&self, owner_vk: &VerifyingKey, state: ChatRoomStateV1) -> Result<()> {
         self.with_lock(|| {
             let mut storage = self.load_rooms_unlocked()?;
@@ -1196,7 +1477,8 @@ impl Storage {
                 let mut key_array = [0u8; 32];
                 key_array.copy_from_slice(&owner_key_bytes);
                 if let Ok(owner_vk) = VerifyingKey::from_bytes(&key_array) {
-                    let stored_sk = self.resolve_signing_key(&room_info.signing_key_bytes);
+                    let stored_sk =
+                        self.resolve_signing_key(owner_key_str, &room_info.signing_key_bytes);
                     // The room NAME is sealed to the room secret, which the
                     // room's own stored key unwraps; an inline override key is
                     // an identity for signing, not necessarily one holding the
@@ -1279,7 +1561,7 @@ impl Storage {
         let storage = self.load_rooms()?;
         let key_str = bs58::encode(room_owner_vk.as_bytes()).into_string();
         Ok(storage.rooms.get(&key_str).map(|room_info| {
-            let stored_sk = self.resolve_signing_key(&room_info.signing_key_bytes);
+            let stored_sk = self.resolve_signing_key(&key_str, &room_info.signing_key_bytes);
             self_identity_from(
                 room_owner_vk,
                 signing_key.unwrap_or(&stored_sk),
@@ -1382,6 +1664,7 @@ mod tests {
             version: 0,
             preferred_nickname: nickname,
             deputies: Vec::new(),
+            profile: None,
         };
         state
             .member_info
@@ -2686,6 +2969,69 @@ mod tests {
         );
     }
 
+    /// `read_markers.json` starts empty, persists advances, and is not
+    /// rewritten when nothing moved.
+    #[test]
+    fn read_markers_persist_and_only_move_forward() {
+        use freenet_scaffold::util::FastHash;
+        use river_core::room_state::message::{MessageId, MessageOrderKey};
+        use std::time::{Duration, SystemTime};
+
+        let (storage, temp_dir) = create_test_storage();
+        let key = |secs| MessageOrderKey {
+            time: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            id: MessageId(FastHash(1)),
+        };
+        assert_eq!(storage.load_read_markers().unwrap().markers.len(), 0);
+
+        assert!(storage
+            .mutate_read_markers(|m| m.advance([3; 32], key(100)))
+            .unwrap());
+        assert!(!storage
+            .mutate_read_markers(|m| m.advance([3; 32], key(50)))
+            .unwrap());
+
+        let reopened = Storage::new(Some(temp_dir.path().to_str().unwrap())).unwrap();
+        assert_eq!(
+            reopened.load_read_markers().unwrap().get(&[3; 32]),
+            Some(&key(100))
+        );
+    }
+
+    /// Queued messages persist across `Storage` instances, and a claimed entry
+    /// is gone for the next claimer.
+    #[test]
+    fn scheduled_messages_persist_and_are_claimed_once() {
+        use river_core::chat_delegate::ScheduledMessage;
+
+        let (storage, temp_dir) = create_test_storage();
+        assert!(storage
+            .load_scheduled_messages()
+            .unwrap()
+            .entries
+            .is_empty());
+        storage
+            .mutate_scheduled_messages(|q| {
+                q.add(ScheduledMessage {
+                    id: 7,
+                    room_owner_vk: [3; 32],
+                    send_at: 100,
+                    text: "hello later".to_string(),
+                })
+            })
+            .unwrap();
+
+        let reopened = Storage::new(Some(temp_dir.path().to_str().unwrap())).unwrap();
+        let claimed = reopened
+            .mutate_scheduled_messages(|q| q.take_due(100, |_| true))
+            .unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert!(storage
+            .mutate_scheduled_messages(|q| q.take_due(100, |_| true))
+            .unwrap()
+            .is_empty());
+    }
+
     /// Leaving a room must also drop that room's cached outbound-DM plaintext
     /// and archived-thread entries from `outbound_dms.json`, so leaving does
     /// not leave orphaned plaintext on disk (Gemini review on PR #327). Other
@@ -0,0 +1,598 @@
+//! Room transcripts for `riverctl message export`.
+//!
+//! A transcript is what a room looked like to a member reading it: every
+//! message in `recent_messages` in order, with edits applied, deletions marked
+//! rather than silently dropped, replies resolved against live state and
+//! reactions summarised. It is meant for people outside River, so it is
+//! rendered to Markdown, standalone HTML or JSONL.
+//!
+//! Nicknames go through `river_core::display_name::sanitize_display_name`, the
+//! same rule the UI renders with, so a forged badge in a nickname does not
+//! reappear in a document handed to someone else. Mentions are rendered with
+//! `mention::render_plaintext` against the same sanitised names.
+//!
+//! The JSONL variant carries, per message, the author's verifying key, the
+//! Ed25519 signature and the exact CBOR bytes it covers, so a recipient can
+//! check authorship without River or a network. That proves the author signed
+//! the ORIGINAL body; edits, deletions and reactions are separate signed
+//! actions and are reported here as the room state resolved them.
+
+use crate::api::{
+    message_raw_text_with_secrets, reply_context_display_with_secrets, ReplyContextDisplay,
+};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::VerifyingKey;
+use river_core::display_name::sanitize_display_name;
+use river_core::room_state::member::MemberId;
+use river_core::room_state::message::AuthorizedMessageV1;
+use river_core::ChatRoomStateV1;
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// Identifies a JSONL transcript; the first line of the file carries it.
+pub const TRANSCRIPT_FORMAT: &str = "river-transcript";
+pub const TRANSCRIPT_FORMAT_VERSION: u32 = 1;
+
+/// Shown for an author with no `member_info` record, as in the UI.
+const UNKNOWN_MEMBER: &str = "Unknown";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+impl FromStr for TranscriptFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(TranscriptFormat::Markdown),
+            "html" => Ok(TranscriptFormat::Html),
+            "jsonl" => Ok(TranscriptFormat::Jsonl),
+            _ => Err(format!(
+                "Unknown transcript format: {} (expected md, html or jsonl)",
+                s
+            )),
+        }
+    }
+}
+
+impl TranscriptFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "md",
+            TranscriptFormat::Html => "html",
+            TranscriptFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    /// Room owner verifying key, base58.
+    pub room_owner_key: String,
+    pub room_name: String,
+    pub exported_at: DateTime<Utc>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptEntry {
+    pub message_id: String,
+    pub author_id: String,
+    /// Base58 verifying key of the author, when the room still lists them.
+    pub author_key: Option<String>,
+    pub author: String,
+    pub timestamp: DateTime<Utc>,
+    /// Effective text with mentions rendered; `None` for a deleted message.
+    pub text: Option<String>,
+    pub edited: bool,
+    pub reply: Option<TranscriptReply>,
+    /// `(emoji, count)`, most-used first.
+    pub reactions: Vec<(String, usize)>,
+    /// Base64 Ed25519 signature over `signed_message`.
+    pub signature: String,
+    /// Base64 CBOR encoding of the signed `MessageV1`.
+    pub signed_message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptReply {
+    Quote {
+        author: String,
+        message_id: String,
+        preview: String,
+    },
+    /// The quoted message is gone (deleted, aged out, or its author banned).
+    Unavailable,
+}
+
+impl Transcript {
+    /// Build a transcript from room state whose private actions have already
+    /// been rebuilt (see `ApiClient::room_display_secrets`). `secrets` is
+    /// empty for a public room.
+    pub fn build(
+        room_owner_key: &VerifyingKey,
+        state: &ChatRoomStateV1,
+        secrets: &HashMap<u32, [u8; 32]>,
+    ) -> Self {
+        let name_of = |id: MemberId| -> String {
+            state
+                .member_info
+                .canonical(id)
+                .map(|info| {
+                    let raw = river_core::ecies::unseal_bytes_with_secrets(
+                        &info.member_info.preferred_nickname,
+                        secrets,
+                    )
+                    .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+                    .unwrap_or_else(|_| info.member_info.preferred_nickname.to_string_lossy());
+                    sanitize_display_name(&raw)
+                })
+                .unwrap_or_else(|| UNKNOWN_MEMBER.to_string())
+        };
+        let owner_id = MemberId::from(room_owner_key);
+        let key_of = |id: MemberId| -> Option<String> {
+            if id == owner_id {
+                return Some(bs58::encode(room_owner_key.as_bytes()).into_string());
+            }
+            state
+                .members
+                .members
+                .iter()
+                .find(|m| m.member.id() == id)
+                .map(|m| bs58::encode(m.member.member_vk.as_bytes()).into_string())
+        };
+
+        // Disappearing messages stay out of exports too, even before the
+        // contract has dropped them.
+        let now = SystemTime::now();
+        let messages = &state.recent_messages;
+        let mut ordered: Vec<&AuthorizedMessageV1> = messages
+            .messages
+            .iter()
+            .filter(|m| !m.message.content.is_action())
+            .filter(|m| {
+                !state
+                    .configuration
+                    .configuration
+                    .message_has_expired(m.message.time, now)
+            })
+            .collect();
+        ordered.sort_by_key(|m| m.order_key());
+
+        let entries = ordered
+            .into_iter()
+            .map(|msg| {
+                let id = msg.id();
+                let text = (!messages.is_deleted(&id)).then(|| {
+                    let raw = message_raw_text_with_secrets(state, msg, secrets);
+                    render_mentions(state, &raw, &name_of)
+                });
+                let reply = match reply_context_display_with_secrets(state, msg, secrets) {
+                    ReplyContextDisplay::Quote {
+                        author,
+                        message_id,
+                        preview,
+                        ..
+                    } => Some(TranscriptReply::Quote {
+                        author: sanitize_display_name(&author),
+                        message_id,
+                        preview,
+                    }),
+                    ReplyContextDisplay::Unavailable => Some(TranscriptReply::Unavailable),
+                    ReplyContextDisplay::NotAReply => None,
+                };
+                let mut reactions: Vec<(String, usize)> = messages
+                    .reactions(&id)
+                    .map(|r| r.iter().map(|(e, who)| (e.clone(), who.len())).collect())
+                    .unwrap_or_default();
+                reactions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+                let mut signed = Vec::new();
+                ciborium::ser::into_writer(&msg.message, &mut signed)
+                    .expect("Serialization should not fail");
+                let b64 = base64::engine::general_purpose::STANDARD;
+                TranscriptEntry {
+                    message_id: id.0 .0.to_string(),
+                    author_id: msg.message.author.to_string(),
+                    author_key: key_of(msg.message.author),
+                    author: name_of(msg.message.author),
+                    timestamp: msg.message.time.into(),
+                    text,
+                    edited: messages.is_edited(&id),
+                    reply,
+                    reactions,
+                    signature: b64.encode(msg.signature.to_bytes()),
+                    signed_message: b64.encode(&signed),
+                }
+            })
+            .collect();
+
+        let room_name = river_core::ecies::unseal_bytes_with_secrets(
+            &state.configuration.configuration.display.name,
+            secrets,
+        )
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+        .unwrap_or_else(|_| {
+            state
+                .configuration
+                .configuration
+                .display
+                .name
+                .to_string_lossy()
+        });
+
+        Transcript {
+            room_owner_key: bs58::encode(room_owner_key.as_bytes()).into_string(),
+            room_name,
+            exported_at: Utc::now(),
+            entries,
+        }
+    }
+
+    pub fn render(&self, format: TranscriptFormat) -> String {
+        match format {
+            TranscriptFormat::Markdown => self.to_markdown(),
+            TranscriptFormat::Html => self.to_html(),
+            TranscriptFormat::Jsonl => self.to_jsonl(),
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# {}\n\nExported from River room `{}` on {}. {} messages.\n",
+            escape_markdown(&self.room_name),
+            self.room_owner_key,
+            format_time(&self.exported_at),
+            self.entries.len()
+        );
+        for e in &self.entries {
+            out.push_str(&format!(
+                "\n---\n\n**{}** · {}{}\n\n",
+                escape_markdown(&e.author),
+                format_time(&e.timestamp),
+                if e.edited { " · _edited_" } else { "" }
+            ));
+            match &e.reply {
+                Some(TranscriptReply::Quote {
+                    author, preview, ..
+                }) => out.push_str(&format!(
+                    "> **{}:** {}\n\n",
+                    escape_markdown(author),
+                    preview.replace('\n', " ")
+                )),
+                Some(TranscriptReply::Unavailable) => {
+                    out.push_str("> _Reply to an unavailable message_\n\n")
+                }
+                None => {}
+            }
+            match &e.text {
+                Some(text) => out.push_str(&format!("{}\n", text)),
+                None => out.push_str("_This message was deleted._\n"),
+            }
+            if !e.reactions.is_empty() {
+                out.push_str(&format!("\n{}\n", reactions_summary(&e.reactions)));
+            }
+        }
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let title = escape_html(&self.room_name);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; max-width: 48em; margin: 2em auto; }}\n\
+             .msg {{ border-top: 1px solid #ddd; padding: 0.5em 0; }}\n\
+             .meta {{ color: #666; font-size: 0.9em; }}\n\
+             .text {{ white-space: pre-wrap; margin: 0.3em 0; }}\n\
+             .reply {{ border-left: 3px solid #ccc; padding-left: 0.5em; color: #555; }}\n\
+             .deleted {{ font-style: italic; color: #888; }}\n</style>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n<p class=\"meta\">Exported from River room <code>{}</code> on {}. \
+             {} messages.</p>\n",
+            self.room_owner_key,
+            format_time(&self.exported_at),
+            self.entries.len()
+        );
+        for e in &self.entries {
+            out.push_str(&format!(
+                "<div class=\"msg\" id=\"m{}\">\n<div class=\"meta\"><strong>{}</strong> · {}{}</div>\n",
+                e.message_id,
+                escape_html(&e.author),
+                format_time(&e.timestamp),
+                if e.edited { " · <em>edited</em>" } else { "" }
+            ));
+            match &e.reply {
+                Some(TranscriptReply::Quote {
+                    author,
+                    message_id,
+                    preview,
+                }) => out.push_str(&format!(
+                    "<div class=\"reply\"><a href=\"#m{}\">{}</a>: {}</div>\n",
+                    message_id,
+                    escape_html(author),
+                    escape_html(preview)
+                )),
+                Some(TranscriptReply::Unavailable) => out.push_str(
+                    "<div class=\"reply deleted\">Reply to an unavailable message</div>\n",
+                ),
+                None => {}
+            }
+            match &e.text {
+                Some(text) => {
+                    out.push_str(&format!("<p class=\"text\">{}</p>\n", escape_html(text)))
+                }
+                None => out.push_str("<p class=\"text deleted\">This message was deleted.</p>\n"),
+            }
+            if !e.reactions.is_empty() {
+                out.push_str(&format!(
+                    "<div class=\"meta\">{}</div>\n",
+                    escape_html(&reactions_summary(&e.reactions))
+                ));
+            }
+            out.push_str("</div>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn to_jsonl(&self) -> String {
+        let mut lines = vec![json!({
+            "type": "room",
+            "format": TRANSCRIPT_FORMAT,
+            "format_version": TRANSCRIPT_FORMAT_VERSION,
+            "room_owner_key": self.room_owner_key,
+            "room_name": self.room_name,
+            "exported_at": self.exported_at.to_rfc3339(),
+            "messages": self.entries.len(),
+        })];
+        for e in &self.entries {
+            let reply_to = match &e.reply {
+                Some(TranscriptReply::Quote {
+                    author,
+                    message_id,
+                    preview,
+                }) => json!({
+                    "author": author,
+                    "message_id": message_id,
+                    "preview": preview,
+                }),
+                Some(TranscriptReply::Unavailable) => json!({ "unavailable": true }),
+                None => serde_json::Value::Null,
+            };
+            let reactions: serde_json::Map<String, serde_json::Value> = e
+                .reactions
+                .iter()
+                .map(|(emoji, n)| (emoji.clone(), json!(n)))
+                .collect();
+            lines.push(json!({
+                "type": "message",
+                "message_id": e.message_id,
+                "author_id": e.author_id,
+                "author_key": e.author_key,
+                "author": e.author,
+                "timestamp": e.timestamp.to_rfc3339(),
+                "text": e.text,
+                "deleted": e.text.is_none(),
+                "edited": e.edited,
+                "reply_to": reply_to,
+                "reactions": reactions,
+                "signature": e.signature,
+                "signed_message": e.signed_message,
+            }));
+        }
+        let mut out = String::new();
+        for line in lines {
+            out.push_str(&line.to_string());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Render mention tokens as `@name` with each member's current sanitised
+/// nickname. A member who is gone falls back to the token's snapshot name,
+/// which is part of the message body the author wrote and signed, so it is
+/// rendered like the rest of the body.
+fn render_mentions(
+    state: &ChatRoomStateV1,
+    text: &str,
+    name_of: &impl Fn(MemberId) -> String,
+) -> String {
+    river_core::mention::render_plaintext(text, |r| {
+        state
+            .member_info
+            .member_info
+            .iter()
+            .find(|info| r.matches(info.member_info.member_id))
+            .map(|info| name_of(info.member_info.member_id))
+    })
+}
+
+fn reactions_summary(reactions: &[(String, usize)]) -> String {
+    reactions
+        .iter()
+        .map(|(emoji, n)| format!("{emoji} ×{n}"))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+fn format_time(t: &DateTime<Utc>) -> String {
+    t.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// Escape the characters that would let a nickname or room name change the
+/// surrounding Markdown structure (emphasis, links, headings, inline HTML).
+fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '!'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, SigningKey};
+    use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfo};
+    use river_core::room_state::message::{MessageV1, RoomMessageBody};
+    use std::time::{Duration, SystemTime};
+
+    struct Room {
+        owner: SigningKey,
+        state: ChatRoomStateV1,
+        secs: u64,
+    }
+
+    impl Room {
+        fn new(nickname: &str) -> Self {
+            let owner = SigningKey::from_bytes(&[7; 32]);
+            let mut state = ChatRoomStateV1::default();
+            let info = MemberInfo::new_public(
+                MemberId::from(&owner.verifying_key()),
+                1,
+                nickname.to_string(),
+            );
+            state
+                .member_info
+                .member_info
+                .push(AuthorizedMemberInfo::new_with_member_key(info, &owner));
+            Room {
+                owner,
+                state,
+                secs: 1_700_000_000,
+            }
+        }
+
+        fn post(&mut self, content: RoomMessageBody) -> AuthorizedMessageV1 {
+            self.secs += 1;
+            let id = MemberId::from(&self.owner.verifying_key());
+            let msg = AuthorizedMessageV1::new(
+                MessageV1 {
+                    room_owner: id,
+                    author: id,
+                    time: SystemTime::UNIX_EPOCH + Duration::from_secs(self.secs),
+                    content,
+                },
+                &self.owner,
+            );
+            self.state.recent_messages.messages.push(msg.clone());
+            self.state.recent_messages.rebuild_actions_state();
+            msg
+        }
+
+        fn transcript(&self) -> Transcript {
+            Transcript::build(&self.owner.verifying_key(), &self.state, &HashMap::new())
+        }
+    }
+
+    #[test]
+    fn edits_deletions_replies_and_reactions_are_resolved() {
+        let mut room = Room::new("Alice");
+        let first = room.post(RoomMessageBody::public("first draft".into()));
+        room.post(RoomMessageBody::edit(first.id(), "final text".into()));
+        let gone = room.post(RoomMessageBody::public("oops".into()));
+        room.post(RoomMessageBody::delete(gone.id()));
+        room.post(RoomMessageBody::reply(
+            "agreed".into(),
+            first.id(),
+            "Alice".into(),
+            "stale snapshot".into(),
+        ));
+        room.post(RoomMessageBody::reaction(first.id(), "👍".into()));
+
+        let t = room.transcript();
+        // Actions are folded into the entries they target, not listed.
+        assert_eq!(t.entries.len(), 3);
+        assert_eq!(t.entries[0].text.as_deref(), Some("final text"));
+        assert!(t.entries[0].edited);
+        assert_eq!(t.entries[0].reactions, vec![("👍".to_string(), 1)]);
+        assert_eq!(t.entries[1].text, None);
+        // The quote is re-read from the live target, not the replier's snapshot.
+        assert_eq!(
+            t.entries[2].reply,
+            Some(TranscriptReply::Quote {
+                author: "Alice".into(),
+                message_id: first.id().0 .0.to_string(),
+                preview: "final text".into(),
+            })
+        );
+
+        let md = t.render(TranscriptFormat::Markdown);
+        assert!(md.contains("_This message was deleted._"));
+        assert!(!md.contains("oops"));
+        assert!(md.contains("👍 ×1"));
+    }
+
+    #[test]
+    fn nicknames_and_mentions_follow_the_ui_sanitiser() {
+        let mut room = Room::new("Alice 🛡");
+        let me = MemberId::from(&room.owner.verifying_key());
+        let token = river_core::mention::encode_mention(me, "Alice 🛡");
+        room.post(RoomMessageBody::public(format!("ping {token}")));
+
+        let t = room.transcript();
+        assert_eq!(t.entries[0].author, "Alice");
+        assert_eq!(t.entries[0].text.as_deref(), Some("ping @Alice"));
+    }
+
+    #[test]
+    fn html_escapes_attacker_controlled_text() {
+        let mut room = Room::new("<b>Eve</b>");
+        room.post(RoomMessageBody::public("<script>x</script>".into()));
+        let html = room.transcript().render(TranscriptFormat::Html);
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("&lt;b&gt;Eve&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn jsonl_signatures_verify_offline() {
+        let mut room = Room::new("Alice");
+        room.post(RoomMessageBody::public("hello".into()));
+        let jsonl = room.transcript().render(TranscriptFormat::Jsonl);
+        let lines: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines[0]["format"], TRANSCRIPT_FORMAT);
+        let msg = &lines[1];
+
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let key_bytes = bs58::decode(msg["author_key"].as_str().unwrap())
+            .into_vec()
+            .unwrap();
+        let key = VerifyingKey::from_bytes(&key_bytes.try_into().unwrap()).unwrap();
+        let signed = b64.decode(msg["signed_message"].as_str().unwrap()).unwrap();
+        let sig_bytes = b64.decode(msg["signature"].as_str().unwrap()).unwrap();
+        let sig = Signature::from_bytes(&sig_bytes.try_into().unwrap());
+        key.verify_strict(&signed, &sig).unwrap();
+    }
+}
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::room_state::direct_messages::PurgeToken;
+use crate::room_state::direct_messages::{DirectMessagesV1, PurgeToken};
 use crate::room_state::member::MemberId;
+use crate::room_state::message::{MessageOrderKey, MessagesV1};
 
 /// Room key identifier (owner's verifying key bytes)
 pub type RoomKey = [u8; 32];
@@ -94,6 +95,466 @@ pub struct OutboundDmEntry {
     pub plaintext: String,
 }
 
+/// Delegate storage key for the per-room read markers shared by every
+/// client the user runs (see [`ReadMarkerStore`]).
+pub const READ_MARKERS_STORAGE_KEY: &[u8] = b"read_markers";
+
+/// How far the user has read in each room, shared across devices.
+///
+/// The UI used to keep this only as `RoomData::last_read_message_id`, a
+/// `MessageId` that stops meaning anything once the message ages out of
+/// the bounded buffer and that the per-room merge deliberately does not
+/// reconcile — so reading on one device never cleared the badge on another.
+/// A [`MessageOrderKey`] stays comparable after its message is gone, which
+/// makes the marker a simple "everything at or before this is read".
+///
+/// Markers only move forward: [`Self::merge`] keeps the later key per room,
+/// so a writer that loses a `CasStoreRequest` race merges the
+/// `Conflict`'s `current_value` and retries without ever rolling another
+/// device back. That is why this needs no delegate support beyond the
+/// generic versioned get / CAS store.
+///
+/// `Vec` rather than `HashMap` for the same JSON reason as
+/// [`OutboundDmStore::entries`]; the list is bounded by the number of rooms.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReadMarkerStore {
+    #[serde(default)]
+    pub markers: Vec<ReadMarkerEntry>,
+}
+
+/// One room's read marker in a [`ReadMarkerStore`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReadMarkerEntry {
+    /// Room owner verifying key, raw bytes as elsewhere in this module.
+    pub room_owner_vk: [u8; 32],
+    /// The newest message the user has read in this room.
+    pub last_read: MessageOrderKey,
+}
+
+impl ReadMarkerStore {
+    pub fn get(&self, room_owner_vk: &[u8; 32]) -> Option<&MessageOrderKey> {
+        self.markers
+            .iter()
+            .find(|m| &m.room_owner_vk == room_owner_vk)
+            .map(|m| &m.last_read)
+    }
+
+    /// Move the room's marker to `last_read` if that is later than the
+    /// current one. Returns whether anything changed.
+    pub fn advance(&mut self, room_owner_vk: [u8; 32], last_read: MessageOrderKey) -> bool {
+        match self
+            .markers
+            .iter_mut()
+            .find(|m| m.room_owner_vk == room_owner_vk)
+        {
+            Some(entry) if entry.last_read >= last_read => false,
+            Some(entry) => {
+                entry.last_read = last_read;
+                true
+            }
+            None => {
+                self.markers.push(ReadMarkerEntry {
+                    room_owner_vk,
+                    last_read,
+                });
+                // Sorted so an unchanged store always encodes to the same
+                // bytes and a no-op save is recognised as one.
+                self.markers.sort_by_key(|m| m.room_owner_vk);
+                true
+            }
+        }
+    }
+
+    /// Fold `other` in, keeping the later marker per room. Commutative and
+    /// idempotent. Returns whether `self` changed.
+    pub fn merge(&mut self, other: &ReadMarkerStore) -> bool {
+        let mut changed = false;
+        for entry in &other.markers {
+            changed |= self.advance(entry.room_owner_vk, entry.last_read.clone());
+        }
+        changed
+    }
+
+    /// Whether a message at `key` is after the room's marker. With no marker
+    /// every message is unread.
+    pub fn is_unread(&self, room_owner_vk: &[u8; 32], key: &MessageOrderKey) -> bool {
+        self.get(room_owner_vk).is_none_or(|last| key > last)
+    }
+}
+
+/// Delegate storage key for messages queued to send later (see
+/// [`ScheduledMessageStore`]).
+pub const SCHEDULED_MESSAGES_STORAGE_KEY: &[u8] = b"scheduled_messages";
+
+/// Messages the user has queued to send at a later time.
+///
+/// Entries hold the PLAINTEXT, not a signed `AuthorizedMessageV1`: the
+/// message is built, sealed and signed when it is dispatched, exactly as if
+/// it were typed then. Signing ahead of time would freeze the private-room
+/// secret version (members added after a rotation could never read it) and
+/// stamp the message with the time it was queued rather than sent.
+///
+/// Unlike [`ReadMarkerStore`] this does not merge: a dispatcher CLAIMS due
+/// entries by removing them in a compare-and-swap write and sends only what
+/// it removed, so two clients sharing the store never both send one entry.
+/// Every change is therefore an operation re-applied to the freshly-read
+/// store, never a snapshot merged over it.
+///
+/// The claim needs nothing a CAS write does not already give, so the queue
+/// is stored under [`SCHEDULED_MESSAGES_STORAGE_KEY`] through the generic
+/// versioned get / CAS store instead of dedicated request variants: those
+/// would change the delegate's code, and with it the delegate key, for no
+/// extra guarantee.
+///
+/// Only the UI uses that key. riverctl does not talk to the chat delegate
+/// and keeps a separate queue in `scheduled_messages.json`; neither client
+/// sees or sends the other's queued messages.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScheduledMessageStore {
+    #[serde(default)]
+    pub entries: Vec<ScheduledMessage>,
+}
+
+/// One queued message in a [`ScheduledMessageStore`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScheduledMessage {
+    /// Random id the user cancels it by.
+    pub id: u64,
+    /// Room owner verifying key, raw bytes as elsewhere in this module.
+    pub room_owner_vk: [u8; 32],
+    /// Unix seconds at which it becomes due.
+    pub send_at: u64,
+    pub text: String,
+}
+
+impl ScheduledMessageStore {
+    /// Queue `message`, keeping entries ordered by due time.
+    pub fn add(&mut self, message: ScheduledMessage) {
+        self.entries.push(message);
+        self.entries.sort_by_key(|m| (m.send_at, m.id));
+    }
+
+    /// Remove the entry with `id`. Returns whether there was one.
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|m| m.id != id);
+        self.entries.len() != before
+    }
+
+    /// Remove and return every entry due at `now` (Unix seconds) in a room
+    /// `can_send` accepts, oldest first. Entries for other rooms stay queued
+    /// for a client that holds them.
+    pub fn take_due(
+        &mut self,
+        now: u64,
+        mut can_send: impl FnMut(&[u8; 32]) -> bool,
+    ) -> Vec<ScheduledMessage> {
+        let (due, rest) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|m| m.send_at <= now && can_send(&m.room_owner_vk));
+        self.entries = rest;
+        due
+    }
+}
+
+/// One entry of an [`LwwMap`].
+pub trait LwwEntry: Clone {
+    type Key: Ord;
+
+    /// What the entry is about; the map holds at most one entry per key.
+    fn key(&self) -> Self::Key;
+
+    /// Order two entries with the same key; the greater wins. Compares the
+    /// update time first and breaks ties on the content, so the order is
+    /// total and [`LwwMap::merge`] does not depend on which side goes first.
+    fn rank_cmp(&self, other: &Self) -> std::cmp::Ordering;
+}
+
+/// A last-writer-wins map, shared across tabs and devices through the
+/// generic versioned get / CAS store like [`ReadMarkerStore`].
+///
+/// Undoing something (clearing a draft, unverifying a key, unignoring a
+/// member) stores a later entry saying so rather than removing the entry:
+/// the undo then beats an older copy held elsewhere instead of being undone
+/// by it. Kept sorted by key so an unchanged map always encodes to the same
+/// bytes, and a `Vec` for the same JSON reason as
+/// [`OutboundDmStore::entries`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LwwMap<E> {
+    #[serde(default = "Vec::new")]
+    pub entries: Vec<E>,
+}
+
+impl<E> Default for LwwMap<E> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<E: LwwEntry> LwwMap<E> {
+    pub fn entry(&self, key: &E::Key) -> Option<&E> {
+        self.entries.iter().find(|e| &e.key() == key)
+    }
+
+    /// Store `entry` unless the map already holds a later one for the same
+    /// key. Returns whether anything changed.
+    pub fn set(&mut self, entry: E) -> bool {
+        let key = entry.key();
+        match self.entries.iter_mut().find(|e| e.key() == key) {
+            Some(existing) if existing.rank_cmp(&entry).is_ge() => false,
+            Some(existing) => {
+                *existing = entry;
+                true
+            }
+            None => {
+                self.entries.push(entry);
+                self.entries.sort_by_key(|e| e.key());
+                true
+            }
+        }
+    }
+
+    /// Fold `other` in, keeping the later entry per key. Commutative and
+    /// idempotent. Returns whether `self` changed.
+    pub fn merge(&mut self, other: &Self) -> bool {
+        let mut changed = false;
+        for entry in &other.entries {
+            changed |= self.set(entry.clone());
+        }
+        changed
+    }
+}
+
+/// Delegate storage key for unsent composer text (see [`DraftStore`]).
+pub const DRAFTS_STORAGE_KEY: &[u8] = b"drafts";
+
+/// What the user has typed but not sent, per room composer and per DM
+/// thread. Clearing a draft (sending it, or deleting the text) stores an
+/// empty one. Bounded by the rooms and threads the user has typed in.
+///
+/// In a private room the text is sealed with the room secret
+/// ([`DraftBody::Sealed`]), so a draft is no more readable at rest than the
+/// room's messages. The UI keeps this under [`DRAFTS_STORAGE_KEY`]; riverctl
+/// keeps its own copy in `drafts.json`.
+pub type DraftStore = LwwMap<Draft>;
+
+/// One composer's draft in a [`DraftStore`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Draft {
+    /// Room owner verifying key, raw bytes as elsewhere in this module.
+    pub room_owner_vk: [u8; 32],
+    /// The other member of a DM thread, or `None` for the room's composer.
+    pub dm_peer: Option<MemberId>,
+    /// Unix milliseconds of the edit this draft reflects.
+    pub updated_at: u64,
+    pub body: DraftBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DraftBody {
+    Plain(String),
+    /// AES-GCM under room secret `secret_version`, like a private message.
+    Sealed {
+        secret_version: u32,
+        ciphertext: Vec<u8>,
+        nonce: [u8; 12],
+    },
+}
+
+impl DraftBody {
+    /// The body to store for `text` in a room. Sealed with `secret` (the
+    /// room's current secret and its version) in a private room; `None` when
+    /// the room is private and no secret is held, so the draft is not kept
+    /// rather than kept in the clear. An empty draft carries nothing to hide.
+    #[cfg(feature = "ecies-randomized")]
+    pub fn for_room(
+        text: &str,
+        is_private: bool,
+        secret: Option<(&[u8; 32], u32)>,
+    ) -> Option<Self> {
+        if !is_private || text.is_empty() {
+            return Some(DraftBody::Plain(text.to_string()));
+        }
+        let (key, secret_version) = secret?;
+        let (ciphertext, nonce) = crate::ecies::encrypt_with_symmetric_key(key, text.as_bytes());
+        Some(DraftBody::Sealed {
+            secret_version,
+            ciphertext,
+            nonce,
+        })
+    }
+
+    /// The draft's text, unsealing with the room secret of the given version.
+    #[cfg(feature = "ecies")]
+    pub fn open(&self, secret: impl Fn(u32) -> Option<[u8; 32]>) -> Result<String, String> {
+        match self {
+            DraftBody::Plain(text) => Ok(text.clone()),
+            DraftBody::Sealed {
+                secret_version,
+                ciphertext,
+                nonce,
+            } => {
+                let key = secret(*secret_version)
+                    .ok_or_else(|| format!("room secret v{secret_version} is not held"))?;
+                let bytes = crate::ecies::decrypt_with_symmetric_key(&key, ciphertext, nonce)?;
+                String::from_utf8(bytes).map_err(|e| format!("draft is not UTF-8: {e}"))
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, DraftBody::Plain(text) if text.is_empty())
+    }
+}
+
+impl LwwEntry for Draft {
+    type Key = ([u8; 32], Option<MemberId>);
+
+    fn key(&self) -> Self::Key {
+        (self.room_owner_vk, self.dm_peer)
+    }
+
+    fn rank_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.updated_at, &self.body).cmp(&(other.updated_at, &other.body))
+    }
+}
+
+impl DraftStore {
+    pub fn get(&self, room_owner_vk: &[u8; 32], dm_peer: Option<MemberId>) -> Option<&Draft> {
+        self.entry(&(*room_owner_vk, dm_peer))
+    }
+}
+
+/// Delegate storage key for the members the user has verified (see
+/// [`VerifiedKeyStore`]).
+pub const VERIFIED_KEYS_STORAGE_KEY: &[u8] = b"verified_keys";
+
+/// Members whose safety number the user has compared and marked verified.
+///
+/// Keyed by the full `member_vk`, not the `MemberId`: the id is a short
+/// hash, and a lookalike with the same id but a different key must not
+/// inherit the mark. The mark holds across rooms, since it is a statement
+/// about the key. Unmarking stores `verified: false`.
+pub type VerifiedKeyStore = LwwMap<VerifiedKey>;
+
+/// One member's mark in a [`VerifiedKeyStore`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VerifiedKey {
+    pub member_vk: [u8; 32],
+    pub verified: bool,
+    /// Unix milliseconds of the mark or unmark.
+    pub updated_at: u64,
+}
+
+impl LwwEntry for VerifiedKey {
+    type Key = [u8; 32];
+
+    fn key(&self) -> Self::Key {
+        self.member_vk
+    }
+
+    fn rank_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.updated_at, self.verified).cmp(&(other.updated_at, other.verified))
+    }
+}
+
+impl VerifiedKeyStore {
+    pub fn is_verified(&self, member_vk: &[u8; 32]) -> bool {
+        self.entry(member_vk).is_some_and(|e| e.verified)
+    }
+}
+
+/// Delegate storage key for the user's ignore list (see [`IgnoreList`]).
+pub const IGNORED_MEMBERS_STORAGE_KEY: &[u8] = b"ignored_members";
+
+/// Members the user has chosen not to see.
+///
+/// Purely a view filter on this user's clients: nothing is published, and
+/// the ignored member can't tell. Keyed by `MemberId`, so one entry covers
+/// that member in every room the two share. Unignoring stores
+/// `ignored: false`.
+pub type IgnoreList = LwwMap<IgnoredMember>;
+
+/// One member's entry in an [`IgnoreList`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IgnoredMember {
+    pub member_id: MemberId,
+    pub ignored: bool,
+    /// Also delete the DMs they send the user, with a recipient purge, rather
+    /// than only hiding them.
+    #[serde(default)]
+    pub purge_dms: bool,
+    /// Unix milliseconds of the change.
+    pub updated_at: u64,
+}
+
+impl LwwEntry for IgnoredMember {
+    type Key = MemberId;
+
+    fn key(&self) -> Self::Key {
+        self.member_id
+    }
+
+    fn rank_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let rank = |e: &Self| (e.updated_at, e.ignored, e.purge_dms);
+        rank(self).cmp(&rank(other))
+    }
+}
+
+impl IgnoreList {
+    pub fn is_ignored(&self, member_id: &MemberId) -> bool {
+        self.entry(member_id).is_some_and(|e| e.ignored)
+    }
+
+    /// The members currently ignored.
+    pub fn ignored(&self) -> impl Iterator<Item = &IgnoredMember> {
+        self.entries.iter().filter(|e| e.ignored)
+    }
+
+    /// Drop ignored members from a reactions map (emoji -> reactors), and
+    /// any emoji left with no reactors.
+    pub fn hide_reactors(&self, reactions: &mut std::collections::HashMap<String, Vec<MemberId>>) {
+        for reactors in reactions.values_mut() {
+            reactors.retain(|id| !self.is_ignored(id));
+        }
+        reactions.retain(|_, reactors| !reactors.is_empty());
+    }
+
+    /// Remove what ignored members wrote from a copy of a room's messages
+    /// held for display: their messages and their reactions. Never apply
+    /// this to state that is stored or published.
+    pub fn hide_in(&self, messages: &mut MessagesV1) {
+        messages
+            .messages
+            .retain(|m| !self.is_ignored(&m.message.author));
+        for reactions in messages.actions_state.reactions.values_mut() {
+            self.hide_reactors(reactions);
+        }
+        messages
+            .actions_state
+            .reactions
+            .retain(|_, reactions| !reactions.is_empty());
+    }
+
+    /// Purge tokens for the DMs in `dms` sent to `self_id` by members whose
+    /// entry asks for their DMs to be purged, ready for
+    /// `advance_recipient_purges`.
+    pub fn dm_purge_tokens(&self, self_id: MemberId, dms: &DirectMessagesV1) -> Vec<PurgeToken> {
+        dms.messages
+            .iter()
+            .filter(|m| {
+                m.message.recipient == self_id
+                    && self
+                        .ignored()
+                        .any(|e| e.purge_dms && e.member_id == m.message.sender)
+            })
+            .map(|m| m.purge_token())
+            .collect()
+    }
+}
+
 /// Unique identifier for a signing request (for request/response correlation)
 pub type RequestId = u64;
 
@@ -407,6 +868,151 @@ mod tests {
         assert_eq!(parsed, store);
     }
 
+    #[test]
+    fn scheduled_messages_are_taken_only_when_due_and_sendable() {
+        let queued = |id, room: u8, send_at| ScheduledMessage {
+            id,
+            room_owner_vk: [room; 32],
+            send_at,
+            text: format!("message {id}"),
+        };
+        let mut store = ScheduledMessageStore::default();
+        store.add(queued(3, 1, 300));
+        store.add(queued(1, 1, 100));
+        store.add(queued(2, 2, 100));
+
+        let due = store.take_due(200, |room| room == &[1; 32]);
+        assert_eq!(due, vec![queued(1, 1, 100)]);
+        assert_eq!(store.entries, vec![queued(2, 2, 100), queued(3, 1, 300)]);
+
+        assert!(store.cancel(3));
+        assert!(!store.cancel(3));
+
+        let json = serde_json::to_string(&store).expect("serialize JSON");
+        let parsed: ScheduledMessageStore = serde_json::from_str(&json).expect("parse JSON");
+        assert_eq!(parsed, store);
+    }
+
+    /// The later entry wins whichever side it is merged from, a later undo
+    /// beats an older entry, and a timestamp tie is settled the same way in
+    /// either order.
+    #[test]
+    fn lww_map_merge_keeps_the_later_entry_in_either_order() {
+        let entry = |member: u8, verified, updated_at| VerifiedKey {
+            member_vk: [member; 32],
+            verified,
+            updated_at,
+        };
+        let mut a = VerifiedKeyStore::default();
+        a.set(entry(4, true, 10));
+        a.set(entry(2, true, 10));
+        let mut b = VerifiedKeyStore::default();
+        b.set(entry(4, false, 20));
+        b.set(entry(2, false, 10));
+
+        let mut ab = a.clone();
+        assert!(ab.merge(&b));
+        let mut ba = b.clone();
+        ba.merge(&a);
+        assert_eq!(ab, ba);
+        assert_eq!(ab.entries, vec![entry(2, true, 10), entry(4, false, 20)]);
+        assert!(!ab.merge(&a), "merging older entries must change nothing");
+        assert!(!ab.set(entry(4, true, 15)));
+    }
+
+    /// A room's composer and each of its DM threads hold separate drafts.
+    #[test]
+    fn drafts_are_kept_per_composer() {
+        let peer = MemberId(FastHash(5));
+        let draft = |dm_peer, text: &str| Draft {
+            room_owner_vk: [1; 32],
+            dm_peer,
+            updated_at: 10,
+            body: DraftBody::Plain(text.to_string()),
+        };
+        let mut drafts = DraftStore::default();
+        drafts.set(draft(None, "room text"));
+        drafts.set(draft(Some(peer), "dm text"));
+        assert_eq!(drafts.entries.len(), 2);
+        assert_eq!(
+            drafts.get(&[1; 32], Some(peer)),
+            Some(&draft(Some(peer), "dm text"))
+        );
+        assert_eq!(drafts.get(&[1; 32], None), Some(&draft(None, "room text")));
+    }
+
+    /// Only a current mark counts as verified.
+    #[test]
+    fn verified_keys_report_only_current_marks() {
+        let mut keys = VerifiedKeyStore::default();
+        keys.set(VerifiedKey {
+            member_vk: [4; 32],
+            verified: true,
+            updated_at: 10,
+        });
+        assert!(keys.is_verified(&[4; 32]));
+        assert!(!keys.is_verified(&[5; 32]));
+        keys.set(VerifiedKey {
+            member_vk: [4; 32],
+            verified: false,
+            updated_at: 20,
+        });
+        assert!(!keys.is_verified(&[4; 32]));
+    }
+
+    /// Reactions by an ignored member are hidden without touching anyone
+    /// else's.
+    #[test]
+    fn ignore_list_hides_reactors() {
+        let alice = MemberId(FastHash(1));
+        let bob = MemberId(FastHash(2));
+        let mut ignored = IgnoreList::default();
+        ignored.set(IgnoredMember {
+            member_id: alice,
+            ignored: true,
+            purge_dms: false,
+            updated_at: 10,
+        });
+        assert!(ignored.is_ignored(&alice));
+
+        let mut reactions = std::collections::HashMap::from([
+            ("👍".to_string(), vec![alice, bob]),
+            ("👎".to_string(), vec![alice]),
+        ]);
+        ignored.hide_reactors(&mut reactions);
+        assert_eq!(
+            reactions,
+            std::collections::HashMap::from([("👍".to_string(), vec![bob])])
+        );
+    }
+
+    /// A private-room draft is sealed and opens only with its secret; with
+    /// no secret held it is not stored at all.
+    #[cfg(feature = "ecies-randomized")]
+    #[test]
+    fn private_room_drafts_are_sealed() {
+        let secret = [7u8; 32];
+        let body = DraftBody::for_room("secret plans", true, Some((&secret, 3))).unwrap();
+        assert!(matches!(
+            body,
+            DraftBody::Sealed {
+                secret_version: 3,
+                ..
+            }
+        ));
+        assert_eq!(
+            body.open(|v| (v == 3).then_some(secret)).unwrap(),
+            "secret plans"
+        );
+        assert!(body.open(|_| None).is_err());
+
+        assert!(DraftBody::for_room("secret plans", true, None).is_none());
+        assert_eq!(
+            DraftBody::for_room("hi", false, None),
+            Some(DraftBody::Plain("hi".to_string()))
+        );
+    }
+
     /// CBOR is the on-the-wire encoding used by the chat delegate, so
     /// it also has to round-trip.
     #[test]
@@ -725,4 +1331,56 @@ mod tests {
             ChatDelegateResponseMsg::GetResponse { .. }
         ));
     }
+
+    fn order_key(secs: u64, id: i64) -> MessageOrderKey {
+        MessageOrderKey {
+            time: std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs),
+            id: crate::room_state::message::MessageId(FastHash(id)),
+        }
+    }
+
+    /// Two devices racing a CAS store resolve by merging, so the result must
+    /// not depend on who wins and must never move a marker backwards.
+    #[test]
+    fn read_marker_merge_keeps_the_later_marker_in_either_order() {
+        let mut laptop = ReadMarkerStore::default();
+        laptop.advance([1; 32], order_key(200, 1));
+        laptop.advance([2; 32], order_key(100, 1));
+        let mut phone = ReadMarkerStore::default();
+        phone.advance([1; 32], order_key(150, 1));
+        phone.advance([2; 32], order_key(300, 1));
+        phone.advance([3; 32], order_key(50, 1));
+
+        let mut a = laptop.clone();
+        assert!(a.merge(&phone));
+        let mut b = phone.clone();
+        assert!(b.merge(&laptop));
+        assert_eq!(a, b);
+        assert_eq!(a.get(&[1; 32]), Some(&order_key(200, 1)));
+        assert_eq!(a.get(&[2; 32]), Some(&order_key(300, 1)));
+        assert!(!a.clone().merge(&b), "merge is idempotent");
+
+        assert!(!a.advance([1; 32], order_key(10, 1)), "never moves back");
+        assert!(a.is_unread(&[1; 32], &order_key(200, 2)));
+        assert!(!a.is_unread(&[1; 32], &order_key(200, 1)));
+        assert!(a.is_unread(&[9; 32], &order_key(0, 0)));
+    }
+
+    /// The CLI mirrors this store as JSON; the delegate holds it as CBOR.
+    #[test]
+    fn read_marker_store_round_trips_json_and_cbor() {
+        let mut store = ReadMarkerStore::default();
+        store.advance([7; 32], order_key(1_700_000_000, -5));
+        let json = serde_json::to_string(&store).expect("serialize JSON");
+        assert_eq!(
+            serde_json::from_str::<ReadMarkerStore>(&json).unwrap(),
+            store
+        );
+        let mut buf = Vec::new();
+        ciborium::ser::into_writer(&store, &mut buf).expect("serialize CBOR");
+        assert_eq!(
+            ciborium::de::from_reader::<ReadMarkerStore, _>(&buf[..]).unwrap(),
+            store
+        );
+    }
 }
@@ -0,0 +1,422 @@
+//! Render-time sanitisation of member nicknames, shared by every client.
+//!
+//! A nickname is attacker-controlled bytes from the member's own signed
+//! `MemberInfoV1.preferred_nickname`, so a member who calls themselves
+//! `Alice 🛡` would otherwise render a moderator badge River never granted.
+//! [`sanitize_display_name`] strips emoji, pictographic symbols, invisible and
+//! blank characters and private-use codepoints while leaving real names in
+//! every script byte-identical.
+//!
+//! The full rationale, and the tests that pin it, live with the UI's
+//! `util/display_name.rs`, which re-exports this module. It moved here so that
+//! `riverctl` renders a nickname exactly as the UI does (a transcript handed to
+//! someone outside River must not carry a forged badge either). Enabled ONLY by
+//! the client crates via the `display-name` feature, like `mentions`, so the
+//! room-contract and chat-delegate WASM bytes are unchanged.
+
+/// Rendered in place of a nickname that is empty once sanitised (e.g. the
+/// nickname was nothing but emoji). Distinct from `"Unknown"`, which callers
+/// already use for "no `member_info` record at all", so the two cases stay
+/// distinguishable.
+pub const UNNAMED: &str = "Unnamed";
+
+/// Unicode general category `Cf` (Format): every character whose whole job is
+/// to be invisible and change how neighbouring text is laid out.
+///
+/// **Generated from Unicode 15.0 data, not hand-listed.** The list above grew
+/// one codepoint at a time as each was noticed, and the result had the exact
+/// signature of that process: U+06DE was in it but U+06DD was not; U+0488/U+0489
+/// were covered but U+0890/U+0891 were not. Thirteen `Cf` codepoints survived
+/// BOTH the sanitiser and the confusable fold — U+0600..U+0605, U+06DD, U+070F,
+/// U+0890, U+0891, U+08E2, U+110BD, U+110CD and U+13430..U+1343F — and because
+/// `is_display_hidden` also gates [`sanitize_display_name`], they survived into
+/// the RENDERED nickname. `Ian\u{070F} Clarke` is not merely a fold miss; it is
+/// a pixel-identical clone of another member's displayed name.
+///
+/// Regenerate by sweeping `0..0x110000` for `unicodedata.category(chr(cp)) ==
+/// 'Cf'` and collapsing to ranges; 170 codepoints in 21 ranges as of Unicode
+/// 15.0. `every_format_character_is_hidden` pins a representative of each range.
+///
+/// ## The other invisible categories
+///
+/// * `Cs` (surrogates) cannot exist in a Rust `char`, so there is nothing to do.
+/// * `Co` (private use) is covered by the PUA ranges in [`is_display_hidden`].
+/// * `Cn` (unassigned) is deliberately NOT covered. An unassigned codepoint
+///   renders as a visible replacement box, not as nothing, so it does not clone
+///   anyone's name — and the set SHRINKS with every Unicode release, so a frozen
+///   `Cn` table would start stripping newly-assigned letters out of real names.
+///   That is the one direction of error this module must not have.
+///
+/// ## The two exceptions, which are the same ones as everywhere else
+///
+/// U+200C ZWNJ and U+200D ZWJ are `Cf` and are deliberately NOT reported. They
+/// are orthography in Persian, Sinhala and Malayalam, and stripping them at
+/// RENDER time mangles real names — the reasoning is on the `0x200B` entry
+/// below. The UI's `util::confusable::skeleton` drops them anyway, because
+/// comparison is not rendering.
+fn is_format_control(c: char) -> bool {
+    if c == '\u{200C}' || c == '\u{200D}' {
+        return false;
+    }
+    matches!(u32::from(c),
+        0x00AD                  // SOFT HYPHEN
+        | 0x0600..=0x0605       // ARABIC NUMBER SIGN..ARABIC NUMBER MARK ABOVE
+        | 0x061C                // ARABIC LETTER MARK
+        | 0x06DD                // ARABIC END OF AYAH
+        | 0x070F                // SYRIAC ABBREVIATION MARK
+        | 0x0890..=0x0891       // ARABIC POUND/PIASTRE MARK ABOVE
+        | 0x08E2                // ARABIC DISPUTED END OF AYAH
+        | 0x180E                // MONGOLIAN VOWEL SEPARATOR
+        | 0x200B..=0x200F       // ZWSP, ZWNJ, ZWJ, LRM, RLM (joiners excepted above)
+        | 0x202A..=0x202E       // bidi embedding / override
+        | 0x2060..=0x2064       // word joiner, invisible operators
+        | 0x2066..=0x206F       // bidi isolates, deprecated formatting
+        | 0xFEFF                // ZERO WIDTH NO-BREAK SPACE / BOM
+        | 0xFFF9..=0xFFFB       // interlinear annotation anchors
+        | 0x110BD | 0x110CD     // KAITHI NUMBER SIGN, ...ABOVE
+        | 0x13430..=0x1343F     // Egyptian hieroglyph format controls
+        | 0x1BCA0..=0x1BCA3     // shorthand format controls
+        | 0x1D173..=0x1D17A     // musical beam/slur/phrase controls
+        | 0xE0001               // LANGUAGE TAG
+        | 0xE0020..=0xE007F     // TAG SPACE..CANCEL TAG
+    )
+}
+
+/// Whether `c` must never appear in rendered display text.
+///
+/// Ranges are Unicode *blocks* rather than the `Emoji` character property:
+/// River has no Unicode-property dependency and the UI's wasm bundle size is
+/// a standing concern, so a table of block ranges is the right trade. Blocks
+/// are slightly broader than the emoji property (they also catch arrows,
+/// geometric shapes and dingbats), which is the safe direction — those are
+/// symbols, not letters, and none of them belong in a person's name.
+pub fn is_display_hidden(c: char) -> bool {
+    // Control characters (C0/C1). A newline or NUL in a nickname is never
+    // legitimate and breaks layout.
+    if c.is_control() {
+        return true;
+    }
+    // Every Unicode FORMAT character, by category rather than by whichever ones
+    // someone happened to hit. See [`is_format_control`].
+    if is_format_control(c) {
+        return true;
+    }
+
+    matches!(u32::from(c),
+        // Symbols that Latin-1 inherited and that render as emoji: © ®
+        0x00A9 | 0x00AE
+        // ‼ ⁉
+        | 0x203C | 0x2049
+        // Zero-width space, and the LTR/RTL marks.
+        //
+        // NOT U+200C ZWNJ or U+200D ZWJ. Those look like emoji machinery — ZWJ
+        // is what joins 👩 + ZWJ + 💻 — but they are orthography in several
+        // scripts, and stripping them mangles real names: Persian compounds
+        // (`علی‌رضا` Alireza, `حسین‌زاده` Hosseinzadeh) need ZWNJ to keep the
+        // preceding letter in its final form, Sinhala touching letters
+        // (`සූර්‍ය` Surya) need ZWJ, and several Malayalam IMEs emit chillu as
+        // consonant + virama + ZWJ. Keeping them is safe here because every
+        // emoji a ZWJ could join is itself stripped, so the joiner has nothing
+        // left to assemble.
+        | 0x200B | 0x200E | 0x200F
+        // Line/paragraph separators.
+        | 0x2028..=0x2029
+        // Bidi embedding / override controls (the `\u{202E}` reversal trick).
+        | 0x202A..=0x202E
+        // Word joiner, invisible operators, bidi isolates.
+        | 0x2060..=0x2064 | 0x2066..=0x206F
+        // ™ ℹ
+        | 0x2122 | 0x2139
+        // Arrows.
+        | 0x2190..=0x21FF
+        // Miscellaneous Technical (⌚ ⌛ ⏰ …).
+        | 0x2300..=0x23FF
+        // Enclosed Alphanumerics (① Ⓐ …).
+        | 0x2460..=0x24FF
+        // Geometric Shapes, Miscellaneous Symbols (☀ ⚔ ⛨ …), Dingbats
+        // (✅ ❌ ❤ …) — one contiguous run.
+        | 0x25A0..=0x27BF
+        // Supplemental Arrows-B.
+        | 0x2900..=0x297F
+        // Miscellaneous Symbols and Arrows (⬛ ⭐ …).
+        | 0x2B00..=0x2BFF
+        // Combining Diacritical Marks for Symbols. Includes the keycap
+        // assembler (`1️⃣`) but also the ENCLOSING marks — U+20DD circle,
+        // U+20DE square, U+20E0 circle-backslash, U+20E4 triangle — which
+        // rebuild by composition the very glyphs `0x2460..=0x24FF` is stripped
+        // for: `A\u{20DD}` is Ⓐ, `!\u{20E4}` reads as ⚠. The block has no
+        // letter content.
+        | 0x20D0..=0x20F0
+        // The rest of Unicode general category Me (Enclosing_Mark). Me draws a
+        // shape AROUND the preceding character, so every one of them composes
+        // a badge the same way U+20DD does: `A\u{A670}` renders a circled A,
+        // which is byte-for-byte the `A\u{20DD}` attack above. Me has thirteen
+        // members; the block above covers seven and these are the other six.
+        // U+0488/U+0489 are the Cyrillic hundred-thousands and millions signs,
+        // U+A670..U+A672 the ten-millions family, U+1ABE the parentheses
+        // overlay. Stripping a whole general category is the exception to the
+        // "combining marks are preserved" rule in the module header: Mn marks
+        // decorate a letter, Me marks enclose it, and only the latter can draw
+        // a badge.
+        | 0x0488..=0x0489 | 0x1ABE | 0xA670..=0xA672
+        // 〰 〽 and the two emoji-presented enclosed ideographs ㊗ ㊙. The
+        // rest of the CJK punctuation and Enclosed CJK blocks is untouched.
+        | 0x3030 | 0x303D | 0x3297 | 0x3299
+        // Variation selectors — VS16 is what turns a text-presentation
+        // character into its emoji glyph.
+        | 0xFE00..=0xFE0F
+        // Zero-width no-break space / BOM.
+        | 0xFEFF
+        // The remaining invisible `Cf` formatting characters that no range
+        // above covers, and that `char::is_control()` (which is `Cc` only)
+        // misses. U+061C ARABIC LETTER MARK is a bidi control like U+200E/F;
+        // U+00AD SOFT HYPHEN and U+180E MONGOLIAN VOWEL SEPARATOR render as
+        // nothing; U+FFF9..U+FFFB are interlinear annotation anchors that hide
+        // the text between them.
+        | 0x00AD | 0x061C | 0x180E | 0xFFF0..=0xFFFB
+        // Blank glyphs that are not whitespace, so the space collapse below
+        // would not remove them: they let two members share a pixel-identical
+        // rendered name (`Alice` vs `Alice\u{3164}`), which undermines the
+        // "you can tell members apart by name" assumption the badge sits on.
+        // U+2800 BRAILLE PATTERN BLANK, and the Hangul fillers.
+        | 0x115F | 0x1160 | 0x2800 | 0x3164 | 0xFFA0
+        // The rest of the Default_Ignorable characters, which render as
+        // nothing. Without these a nickname made ENTIRELY of them is
+        // non-empty (so it never becomes `UNNAMED`) yet renders blank, which
+        // makes a message header look like a continuation of the group above
+        // it — including a badged moderator's group.
+        // U+034F combining grapheme joiner, the Mongolian free variation
+        // selectors, the Khmer inherent vowels, U+2065, the Variation
+        // Selectors Supplement (U+FE00..FE0F's big brother), and the
+        // shorthand-format and musical beam/slur/phrase controls.
+        | 0x034F | 0x180B..=0x180D | 0x180F | 0x17B4..=0x17B5 | 0x2065
+        | 0x1BCA0..=0x1BCA3 | 0x1D173..=0x1D17A
+        // Text-presentation symbols that read as a badge in the fonts that
+        // carry them: ۞ (ornate star, present wherever Arabic renders), ٭,
+        // ꙳, and the Phaistos shield. Plus Symbols for Legacy Computing and
+        // its supplement, which contain an inverse check mark and stick
+        // figures.
+        | 0x066D | 0x06DE | 0xA673
+        // Aegean/Phaistos: picking out only the shield (U+101DB) left the
+        // helmet, tiara, rosette and — the one that matters — U+10102 AEGEAN
+        // CHECK MARK, which renders as ✓ wherever Noto Sans Symbols is
+        // installed (stock Ubuntu/Fedora).
+        | 0x10100..=0x101FC
+        // Halfwidth clones of the geometric shapes stripped above.
+        | 0xFFED..=0xFFEE
+        | 0x1FB00..=0x1FBFF | 0x1CC00..=0x1CEBF
+        // Private Use Area (BMP). Font-defined glyphs, and River's own
+        // mention sentinels live at U+E000/U+E001.
+        | 0xE000..=0xF8FF
+        // The emoji planes: Mahjong/Domino/Cards, Enclosed Alphanumeric
+        // Supplement (regional-indicator flags), Miscellaneous Symbols and
+        // Pictographs, Emoticons, Transport, Supplemental Symbols and
+        // Pictographs, Symbols and Pictographs Extended-A. 🛡 is U+1F6E1.
+        | 0x1F000..=0x1FAFF
+        // Plane 14's Default_Ignorable range. `E0000..E0FFF` is the whole set
+        // Unicode marks ignorable in that plane, and the rest of plane 14
+        // (`E1000..EFFFF`) is not ignorable and is left alone. Naming only the
+        // tag block (`E0000..E007F`, the flag-sequence assembler 🏴󠁧󠁢󠁳󠁣󠁴󠁿) left
+        // ~3,700 invisible characters through, each of which clones another
+        // member's rendered name.
+        //
+        // The Ideographic Variation Selectors (`E0100..E01EF`) are INSIDE this
+        // range on purpose, even though they are the one part of it that is
+        // legitimate in a name. Their exception is applied ON TOP, in context,
+        // by [`sanitize_display_name`] and [`contains_hidden_chars`] — NOT by
+        // punching a hole here. TWO separate things depend on that.
+        //
+        // First, `confusable.rs::skeleton` folds names for impersonation
+        // detection by calling THIS function directly. Keeping the selectors
+        // inside the range is what lets it fold `"李\u{E0100}小龍"` and
+        // `"李小龍"` together, so the confusable warning covers the residual
+        // the in-context exception deliberately leaves open (module header).
+        // Punching a hole here would switch that warning off silently.
+        //
+        // Second, two complementary hand-written ranges drift:
+        // narrowing the carve-out by one codepoint leaves a character that is
+        // neither stripped nor judged, so it survives verbatim in every name
+        // while rendering as nothing. Layering makes that gap impossible, and
+        // `no_plane_14_codepoint_escapes_both_the_strip_and_the_carve_out`
+        // fails if anyone splits it again.
+        | 0xE0000..=0xE0FFF
+        // Supplementary Private Use Areas A and B.
+        | 0xF0000..=0xFFFFD
+        | 0x100000..=0x10FFFD
+    )
+}
+
+/// Whether `c` is an Ideographic Variation Selector (VS17..VS256).
+///
+/// These sit inside plane 14's Default_Ignorable range but are the one part of
+/// it that is NOT invisible: they SELECT A GLYPH. Japanese family names are
+/// routinely spelled with one — `辻` has the variant `辻󠄀` (U+8FBB U+E0100), and
+/// `邊`/`邉`, `﨑`, `髙` work the same way — and any font with an IVS table
+/// (Source Han, Noto CJK) renders the selected form. Stripping them blanket-
+/// wise rewrote real names, and because [`contains_hidden_chars`] gates the
+/// nickname `<input>`, it also told those users their own name "can't contain
+/// emoji" and refused to save it.
+///
+/// So they are judged in context instead, exactly like `U+200C`/`U+200D`: kept
+/// where they can be doing the work they exist for, dropped everywhere else.
+pub fn is_variation_selector_supplement(c: char) -> bool {
+    matches!(u32::from(c), 0xE0100..=0xE01EF)
+}
+
+/// The ideographs a variation selector may legitimately follow. These are the
+/// blocks the Ideographic Variation Database actually registers sequences for;
+/// after anything else a selector is invisible filler.
+fn is_ideograph(c: char) -> bool {
+    matches!(u32::from(c),
+        // CJK Unified Ideographs Extension A, and the main block.
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF
+        // CJK Compatibility Ideographs (where `﨑` lives).
+        | 0xF900..=0xFAFF
+        // Extensions B onwards, plus the compatibility supplement.
+        | 0x20000..=0x323AF
+    )
+}
+
+/// Whether the variation selector at `chars[i]` can be selecting a glyph, i.e.
+/// it directly follows an ideograph.
+///
+/// Anywhere else it renders as nothing and clones the surrounding name, so it
+/// is dropped for the same reason an orphaned joiner is. `"Ian\u{E0100}"` is a
+/// clone of `"Ian"`; `"辻\u{E0100}"` is a person's name.
+fn selects_an_ideograph_variant(chars: &[char], i: usize) -> bool {
+    i > 0 && chars.get(i - 1).copied().is_some_and(is_ideograph)
+}
+
+/// Whether `s` contains anything [`sanitize_display_name`] would remove.
+///
+/// Drives the nickname `<input>`'s "Nicknames can't contain emoji" message —
+/// UX only. Never rely on this for safety: the render-time strip is the
+/// boundary, because `riverctl` never runs this code.
+///
+/// Position-sensitive for the same characters [`sanitize_display_name`] judges
+/// in context, so it cannot reject a name the sanitiser would have kept: a
+/// variation selector after an ideograph is a legitimate Japanese name and is
+/// NOT flagged, while the same selector after `n` is invisible filler and is.
+pub fn contains_hidden_chars(s: &str) -> bool {
+    let chars: Vec<char> = s.chars().collect();
+    chars.iter().enumerate().any(|(i, c)| {
+        if is_variation_selector_supplement(*c) {
+            return !selects_an_ideograph_variant(&chars, i);
+        }
+        is_display_hidden(*c)
+    })
+}
+
+/// Strip everything [`is_display_hidden`] rejects and tidy the result.
+///
+/// Removing a character can leave a double space (`"Alice 🛡 Smith"`), so
+/// internal whitespace runs are collapsed to one space and the result is
+/// trimmed. A name that is empty afterwards becomes [`UNNAMED`] rather than a
+/// blank author line.
+///
+/// A removed character that was itself whitespace (a newline, a paragraph
+/// separator) becomes a space rather than vanishing, so `"Alice\nBob"` stays
+/// two words instead of collapsing to `"AliceBob"`. Zero-width characters are
+/// not whitespace, so `"A\u{200B}lice"` correctly rejoins as `"Alice"`.
+///
+/// Only runs of ASCII space are collapsed, and only interior ones. Non-ASCII
+/// spaces are left alone: U+3000 IDEOGRAPHIC SPACE is the conventional
+/// separator between a Japanese surname and given name (`山田　太郎`), and
+/// normalising it to `' '` would quietly rewrite a real name.
+pub fn sanitize_display_name(raw: &str) -> String {
+    // 1. Remove the hidden characters. A hidden character that was itself
+    //    whitespace becomes a space so words don't run together.
+    //
+    //    A variation selector is judged here rather than by the blanket strip,
+    //    because whether it is a Japanese name or invisible filler depends on
+    //    what precedes it. Same decision, same inputs as
+    //    [`contains_hidden_chars`], so the input check and the render strip
+    //    cannot disagree about a given name.
+    let raw_chars: Vec<char> = raw.chars().collect();
+    let stripped: Vec<char> = raw_chars
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &c)| {
+            if is_variation_selector_supplement(c) {
+                return selects_an_ideograph_variant(&raw_chars, i).then_some(c);
+            }
+            match (is_display_hidden(c), c.is_whitespace()) {
+                (true, true) => Some(' '),
+                (true, false) => None,
+                (false, _) => Some(c),
+            }
+        })
+        .collect();
+
+    // 2. Keep a joiner only where it can be doing orthographic work, which is
+    //    between two NON-ASCII letters (Persian `علی‌رضا`, Sinhala `සූර්‍ය`,
+    //    Malayalam chillu — which is consonant + virama + ZWJ and legitimately
+    //    ENDS a name, so a trailing joiner after a non-ASCII letter is kept
+    //    too). Everywhere else it is dropped, which covers two cases:
+    //
+    //    * Orphans left by step 1: `"Bob 👮🏽‍♀️"` strips down to `"Bob ‍"`.
+    //    * The clone attack `"Bo\u{200D}b"`, which renders exactly like
+    //      `"Bob"` in any Latin font. Latin script never needs a joiner, so
+    //      requiring a non-ASCII neighbour costs nothing and closes it.
+    //
+    //    A joiner between two non-ASCII letters is still kept, so a CJK name
+    //    can still be cloned this way. That is the residual documented in the
+    //    module header, and it is the same shape as the homoglyph problem.
+    let is_joiner = |c: char| c == '\u{200C}' || c == '\u{200D}';
+    let joins_letters =
+        |c: Option<&char>| c.is_some_and(|c| !c.is_ascii() && !c.is_whitespace() && !is_joiner(*c));
+    let kept: Vec<char> = stripped
+        .iter()
+        .enumerate()
+        .filter(|(i, c)| {
+            !is_joiner(**c)
+                || (*i > 0
+                    && joins_letters(stripped.get(i - 1))
+                    // A joiner is legitimate at the end of a WORD, not just at
+                    // the end of the string: Malayalam legacy chillu is
+                    // consonant + virama + ZWJ, so `"മോഹന\u{0D4D}\u{200D} കുമാർ"`
+                    // (Mohan Kumar) carries one mid-name, and word-final ZWNJ
+                    // does the same in Persian and Kurdish. Requiring
+                    // end-of-string dropped it and degraded the chillu `ൻ` to
+                    // `ന്`, showing a chandrakkala that is not part of the name.
+                    // So: no next character, a non-ASCII letter, or whitespace.
+                    //
+                    // This does not widen the Latin clone surface — the
+                    // PRECEDING character must still be a non-ASCII letter, so
+                    // `"Bo\u{200D}b"` stays closed. It does add word-final
+                    // positions to the non-ASCII residual already documented in
+                    // the module header (an interior joiner in a CJK name), so
+                    // no new capability, just more places for the same one.
+                    && stripped
+                        .get(i + 1)
+                        .is_none_or(|n| joins_letters(Some(n)) || n.is_whitespace()))
+        })
+        .map(|(_, c)| *c)
+        .collect();
+
+    // 3. Normalise the spaces that are visually IDENTICAL to U+0020 (NBSP and
+    //    friends) down to it, then collapse runs. Normalising loses nothing a
+    //    reader can see, and it closes the `"Alice\u{00A0}Smith"` clone of
+    //    `"Alice Smith"`. U+3000 IDEOGRAPHIC SPACE is deliberately NOT in this
+    //    set: it renders double-width, it is visibly different, and it is the
+    //    conventional separator in a Japanese name (`山田　太郎`).
+    let looks_like_a_plain_space =
+        |c: char| matches!(u32::from(c), 0x00A0 | 0x2000..=0x200A | 0x202F | 0x205F);
+    let mut collapsed = String::with_capacity(kept.len());
+    let mut last_was_space = false;
+    for c in kept {
+        let c = if looks_like_a_plain_space(c) { ' ' } else { c };
+        let is_space = c == ' ';
+        if !(is_space && last_was_space) {
+            collapsed.push(c);
+        }
+        last_was_space = is_space;
+    }
+
+    let trimmed = collapsed.trim();
+    if trimmed.is_empty() {
+        UNNAMED.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
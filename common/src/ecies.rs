@@ -405,6 +405,34 @@ pub fn unseal_dm_from_sender(
         })
 }
 
+/// Encrypt a group-DM body under a fresh 256-bit key. Returns the key (to be
+/// sealed per participant with [`seal_dm_for_recipient`]) and the body as
+/// `nonce (12) || AES-256-GCM ciphertext`.
+///
+/// Available only with the `ecies-randomized` feature.
+#[cfg(feature = "ecies-randomized")]
+pub fn seal_group_dm_body(plaintext: &[u8]) -> ([u8; 32], Vec<u8>) {
+    let key = generate_room_secret();
+    let (ciphertext, nonce) = encrypt_with_symmetric_key(&key, plaintext);
+    let mut body = Vec::with_capacity(12 + ciphertext.len());
+    body.extend_from_slice(&nonce);
+    body.extend_from_slice(&ciphertext);
+    (key, body)
+}
+
+/// Inverse of [`seal_group_dm_body`].
+pub fn open_group_dm_body(key: &[u8; 32], body: &[u8]) -> Result<Vec<u8>, String> {
+    if body.len() < 12 {
+        return Err(format!(
+            "Group DM body too short: {} bytes (need at least 12)",
+            body.len()
+        ));
+    }
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&body[..12]);
+    decrypt_with_symmetric_key(key, &body[12..], &nonce)
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
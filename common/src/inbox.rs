@@ -0,0 +1,980 @@
+//! Per-member direct-message inbox contract state (#230 Phase 4).
+//!
+//! In-room DMs ([`crate::room_state::direct_messages`]) share one global cap
+//! per room (`Configuration::effective_max_direct_messages`, 300 by default),
+//! so three busy pairs can evict every other conversation in the room. The
+//! inbox moves a member's inbound DMs into a contract of their own, keyed by
+//! [`InboxParametersV1::owner`] (the member's `member_vk`), so one room's
+//! chatter can no longer crowd out another conversation.
+//!
+//! This is NOT the inbox contract reverted in #238 (PR #234). That design
+//! keyed one contract per *pair* and had no way to tell a legitimate sender
+//! from a stranger. Here there is one contract per *recipient*, and every
+//! message carries its own authorisation:
+//!
+//! - The [`AuthorizedDirectMessage`] is byte-identical to the in-room wire
+//!   shape, signed over `room_owner_vk` of the room the two members share.
+//! - [`InboxMessageV1::sender_proof`] and [`InboxMessageV1::recipient_proof`]
+//!   are [`MembershipProofV1`]s: the invite chain from that room's owner down
+//!   to each party, every link signed by its inviter.
+//! - The room must be on the inbox owner's own signed allowlist,
+//!   [`InboxStateV1::rooms`]. An invite chain is signed by inviters only, so
+//!   anyone can create a room and sign a membership for the inbox owner's
+//!   key; the allowlist is what makes the room one the owner actually joined.
+//!   Until the owner publishes one, the inbox accepts nothing.
+//!
+//! # Allowlist
+//!
+//! [`AuthorizedInboxRoomsV1`] is last-writer-wins on its `version`, which
+//! clients set to the signing time in seconds (never below the held version
+//! plus one), so the owner's devices need no coordination. A new list drops
+//! held messages from rooms it no longer names.
+//!
+//! # Purges
+//!
+//! The inbox owner purges with the same [`AuthorizedRecipientPurges`] envelope
+//! and [`PurgeToken`] tombstones the room uses, with the same
+//! tombstone-as-block semantics. The envelope binds the inbox owner's own key
+//! where the room version binds `room_owner_vk` (see [`sign_inbox_purges`]), so
+//! a purge signed for one inbox can't be replayed against a room.
+//!
+//! # What a membership proof does NOT prove
+//!
+//! A proof shows the sender was invited, not that they are still in the room:
+//! the inbox contract cannot see the room's bans. A member banned from the
+//! shared room keeps write access until the recipient purges them. That is
+//! the same "tombstones block replay, not new spam" trade-off the room makes,
+//! and the recipient has the same remedy.
+//!
+//! # Bounds
+//!
+//! - [`MAX_DM_MESSAGES_PER_PAIR`] per sender, newest kept.
+//! - [`MAX_INBOX_MESSAGES`] across the whole inbox, newest kept.
+//! - [`MAX_DM_CIPHERTEXT_BYTES`] and [`MAX_PURGED_TOMBSTONES_PER_RECIPIENT`]
+//!   as in the room.
+//! - [`MAX_MEMBERSHIP_PROOF_LINKS`] per proof, so a hostile sender cannot make
+//!   every peer walk an arbitrarily long chain.
+//! - [`MAX_INBOX_ROOMS`] on the allowlist.
+//!
+//! Both caps trim newest-first as a pure function of the held set, exactly as
+//! `trim_pairs_to_cap` / `trim_to_global_cap` do in the room, and the summary
+//! carries the same [`DmPairHorizon`] / [`DmRetentionHorizon`] so a peer never
+//! re-offers a message the receiver would drop.
+
+use crate::room_state::direct_messages::{
+    advance_recipient_purges, AuthorizedDirectMessage, AuthorizedRecipientPurges, DmOrderKey,
+    DmPairHorizon, DmRetentionHorizon, PurgeToken, SignatureBytes, MAX_DM_CIPHERTEXT_BYTES,
+    MAX_DM_MESSAGES_PER_PAIR, MAX_PURGED_TOMBSTONES_PER_RECIPIENT,
+};
+use crate::room_state::member::{AuthorizedMember, MemberId};
+use crate::room_state::ChatRoomParametersV1;
+use crate::util::{sign_struct, verify_struct};
+use crate::ChatRoomStateV1;
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use freenet_scaffold::ComposableState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Maximum messages held across the whole inbox.
+pub const MAX_INBOX_MESSAGES: usize = 1000;
+
+/// Maximum invite-chain links in one [`MembershipProofV1`].
+pub const MAX_MEMBERSHIP_PROOF_LINKS: usize = 64;
+
+/// Maximum rooms on an inbox's allowlist.
+pub const MAX_INBOX_ROOMS: usize = 256;
+
+/// Inbox contract parameters. The contract key is derived from these, so a
+/// member's inbox is found from their `member_vk` alone.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InboxParametersV1 {
+    /// The recipient's `member_vk`. Only this key can sign purges.
+    pub owner: VerifyingKey,
+}
+
+impl InboxParametersV1 {
+    pub fn owner_id(&self) -> MemberId {
+        MemberId::from(&self.owner)
+    }
+}
+
+/// Evidence that `member_vk` was invited into the room owned by `room_owner`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MembershipProofV1 {
+    pub room_owner: VerifyingKey,
+    /// The member's own owner-signed entry. `None` when the member IS the
+    /// room owner, who has no `AuthorizedMember` of their own.
+    pub member: Option<AuthorizedMember>,
+    /// The member's inviters, nearest first, stopping short of the owner —
+    /// the shape `MembersV1::get_invite_chain` returns.
+    #[serde(default)]
+    pub invite_chain: Vec<AuthorizedMember>,
+}
+
+impl MembershipProofV1 {
+    /// Build a proof for `member_vk` from a room state the caller holds.
+    pub fn from_room_state(
+        room_owner: VerifyingKey,
+        state: &ChatRoomStateV1,
+        member_vk: &VerifyingKey,
+    ) -> Result<Self, String> {
+        if *member_vk == room_owner {
+            return Ok(Self {
+                room_owner,
+                member: None,
+                invite_chain: Vec::new(),
+            });
+        }
+        let member_id = MemberId::from(member_vk);
+        let member = state
+            .members
+            .members
+            .iter()
+            .find(|m| m.member.id() == member_id)
+            .ok_or_else(|| format!("{:?} is not a current member of the room", member_id))?;
+        let invite_chain = state
+            .members
+            .get_invite_chain(member, &ChatRoomParametersV1 { owner: room_owner })?;
+        Ok(Self {
+            room_owner,
+            member: Some(member.clone()),
+            invite_chain,
+        })
+    }
+
+    /// The key this proof vouches for, without checking it.
+    pub fn member_vk(&self) -> VerifyingKey {
+        match &self.member {
+            Some(m) => m.member.member_vk,
+            None => self.room_owner,
+        }
+    }
+
+    /// Walk from the member up to the room owner, verifying every link
+    /// against its inviter's key. Returns the proven `member_vk`.
+    pub fn verify(&self) -> Result<VerifyingKey, String> {
+        let Some(member) = &self.member else {
+            return Ok(self.room_owner);
+        };
+        if self.invite_chain.len() > MAX_MEMBERSHIP_PROOF_LINKS {
+            return Err(format!(
+                "Membership proof too long: {} > {}",
+                self.invite_chain.len(),
+                MAX_MEMBERSHIP_PROOF_LINKS
+            ));
+        }
+
+        member.verify_detached_invite_chain(&self.room_owner, &self.invite_chain)?;
+        Ok(member.member.member_vk)
+    }
+}
+
+/// A direct message delivered to an inbox, with the evidence that sender and
+/// recipient share the room its signature is bound to.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InboxMessageV1 {
+    pub dm: AuthorizedDirectMessage,
+    pub sender_proof: MembershipProofV1,
+    pub recipient_proof: MembershipProofV1,
+}
+
+impl InboxMessageV1 {
+    /// The room both parties proved membership of; also the `room_owner_vk`
+    /// the DM signature binds.
+    pub fn shared_room(&self) -> &VerifyingKey {
+        &self.sender_proof.room_owner
+    }
+
+    /// Check everything the contract checks for one message, bar the
+    /// allowlist, tombstones and caps, which depend on the rest of the inbox.
+    pub fn verify(&self, parameters: &InboxParametersV1) -> Result<(), String> {
+        let dm = &self.dm.message;
+        if dm.recipient != parameters.owner_id() {
+            return Err(format!(
+                "Inbox message addressed to {:?}, not the inbox owner",
+                dm.recipient
+            ));
+        }
+        if dm.sender == dm.recipient {
+            return Err("Inbox message sender and recipient must differ".to_string());
+        }
+        if dm.ciphertext.len() > MAX_DM_CIPHERTEXT_BYTES {
+            return Err(format!(
+                "DM ciphertext too large: {} > {}",
+                dm.ciphertext.len(),
+                MAX_DM_CIPHERTEXT_BYTES
+            ));
+        }
+        if self.recipient_proof.room_owner != self.sender_proof.room_owner {
+            return Err("Sender and recipient proofs are for different rooms".to_string());
+        }
+
+        let recipient_vk = self.recipient_proof.verify()?;
+        if recipient_vk != parameters.owner {
+            return Err("Recipient proof is not for the inbox owner".to_string());
+        }
+        let sender_vk = self.sender_proof.verify()?;
+        if MemberId::from(&sender_vk) != dm.sender {
+            return Err(format!("Sender proof is not for {:?}", dm.sender));
+        }
+
+        self.dm.verify_signature(&sender_vk, self.shared_room())
+    }
+
+    fn signature_bytes(&self) -> SignatureBytes {
+        SignatureBytes(self.dm.sender_signature.to_bytes())
+    }
+}
+
+/// The rooms whose members may write to an inbox.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InboxRoomsV1 {
+    /// The inbox owner's key, so a list can't be replayed into a contract
+    /// of another kind signed by the same key.
+    pub inbox: VerifyingKey,
+    /// Unix seconds at signing; see the module doc.
+    pub version: u64,
+    /// Owner keys of the allowed rooms.
+    pub rooms: Vec<VerifyingKey>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthorizedInboxRoomsV1 {
+    pub rooms: InboxRoomsV1,
+    pub signature: Signature,
+}
+
+impl AuthorizedInboxRoomsV1 {
+    /// Sign `rooms` as `owner_sk`'s allowlist, at `version` or one past
+    /// `previous`, whichever is higher.
+    pub fn new(
+        owner_sk: &SigningKey,
+        previous: Option<&AuthorizedInboxRoomsV1>,
+        version: u64,
+        rooms: Vec<VerifyingKey>,
+    ) -> Self {
+        let rooms = InboxRoomsV1 {
+            inbox: owner_sk.verifying_key(),
+            version: previous.map_or(version, |p| version.max(p.rooms.version + 1)),
+            rooms,
+        };
+        Self {
+            signature: sign_struct(&rooms, owner_sk),
+            rooms,
+        }
+    }
+
+    pub fn allows(&self, room_owner: &VerifyingKey) -> bool {
+        self.rooms.rooms.contains(room_owner)
+    }
+
+    fn verify(&self, parameters: &InboxParametersV1) -> Result<(), String> {
+        if self.rooms.inbox != parameters.owner {
+            return Err("Inbox allowlist is for a different inbox".to_string());
+        }
+        if self.rooms.rooms.len() > MAX_INBOX_ROOMS {
+            return Err(format!(
+                "Inbox allowlist too long: {} > {}",
+                self.rooms.rooms.len(),
+                MAX_INBOX_ROOMS
+            ));
+        }
+        verify_struct(&self.rooms, &self.signature, &parameters.owner)
+            .map_err(|e| format!("Invalid inbox allowlist signature: {}", e))
+    }
+}
+
+/// Sign the inbox owner's purge envelope, bumping `previous` by one version.
+/// The room-shaped envelope binds `room_owner_vk`; here it binds the owner's
+/// own key, which is what [`InboxStateV1`] verifies against.
+pub fn sign_inbox_purges(
+    owner_sk: &SigningKey,
+    previous: Option<&AuthorizedRecipientPurges>,
+    new_tokens: impl IntoIterator<Item = PurgeToken>,
+) -> Result<AuthorizedRecipientPurges, String> {
+    advance_recipient_purges(owner_sk, &owner_sk.verifying_key(), previous, new_tokens)
+}
+
+/// Inbox contract state.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InboxStateV1 {
+    #[serde(default)]
+    pub messages: Vec<InboxMessageV1>,
+    /// The owner's purge envelope, if they have ever purged anything.
+    #[serde(default)]
+    pub purges: Option<AuthorizedRecipientPurges>,
+    /// The owner's room allowlist. `None` until published, and then the
+    /// inbox holds no messages.
+    #[serde(default)]
+    pub rooms: Option<AuthorizedInboxRoomsV1>,
+}
+
+impl InboxStateV1 {
+    /// Whether the owner's allowlist names `room_owner`'s room.
+    pub fn allows_room(&self, room_owner: &VerifyingKey) -> bool {
+        self.rooms.as_ref().is_some_and(|r| r.allows(room_owner))
+    }
+
+    /// The DMs that arrived via `room_owner`'s room, for merging into that
+    /// room's threads alongside its in-room DMs.
+    pub fn messages_for_room<'a>(
+        &'a self,
+        room_owner: &'a VerifyingKey,
+    ) -> impl Iterator<Item = &'a AuthorizedDirectMessage> + 'a {
+        self.messages
+            .iter()
+            .filter(move |m| m.shared_room() == room_owner)
+            .map(|m| &m.dm)
+    }
+
+    fn tombstones(&self) -> HashSet<PurgeToken> {
+        self.purges
+            .as_ref()
+            .map(|p| p.state.purged.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    fn sender_horizons(&self, recipient: MemberId) -> Vec<DmPairHorizon> {
+        let mut by_sender: HashMap<MemberId, (usize, DmOrderKey)> = HashMap::new();
+        for m in &self.messages {
+            let key = m.dm.order_key();
+            by_sender
+                .entry(m.dm.message.sender)
+                .and_modify(|(count, oldest)| {
+                    *count += 1;
+                    if key < *oldest {
+                        *oldest = key.clone();
+                    }
+                })
+                .or_insert((1, key));
+        }
+        let mut horizons: Vec<DmPairHorizon> = by_sender
+            .into_iter()
+            .filter(|(_, (count, _))| *count >= MAX_DM_MESSAGES_PER_PAIR)
+            .map(|(sender, (_, oldest_retained))| DmPairHorizon {
+                sender,
+                recipient,
+                oldest_retained,
+            })
+            .collect();
+        horizons.sort();
+        horizons
+    }
+
+    fn global_horizon(&self) -> DmRetentionHorizon {
+        if self.messages.len() < MAX_INBOX_MESSAGES {
+            return DmRetentionHorizon::Open;
+        }
+        match self.messages.iter().map(|m| m.dm.order_key()).min() {
+            Some(oldest) => DmRetentionHorizon::OldestRetained(oldest),
+            None => DmRetentionHorizon::Open,
+        }
+    }
+
+    /// Dedup, trim per sender then globally (newest kept), and restore the
+    /// canonical `(sender, timestamp, signature)` order. Per-sender first for
+    /// the reason `enforce_caps_and_sort` gives in the room: the other order
+    /// spends the global budget on messages the sender trim then drops.
+    fn enforce_caps_and_sort(&mut self) {
+        let mut seen = HashSet::with_capacity(self.messages.len());
+        self.messages.retain(|m| seen.insert(m.signature_bytes()));
+
+        let mut by_sender: HashMap<MemberId, Vec<DmOrderKey>> = HashMap::new();
+        for m in &self.messages {
+            by_sender
+                .entry(m.dm.message.sender)
+                .or_default()
+                .push(m.dm.order_key());
+        }
+        let mut dropped: HashSet<SignatureBytes> = HashSet::new();
+        for keys in by_sender.values_mut() {
+            if keys.len() > MAX_DM_MESSAGES_PER_PAIR {
+                keys.sort();
+                let excess = keys.len() - MAX_DM_MESSAGES_PER_PAIR;
+                dropped.extend(keys.iter().take(excess).map(|k| k.signature));
+            }
+        }
+        self.messages
+            .retain(|m| !dropped.contains(&m.signature_bytes()));
+
+        if self.messages.len() > MAX_INBOX_MESSAGES {
+            let mut keys: Vec<DmOrderKey> =
+                self.messages.iter().map(|m| m.dm.order_key()).collect();
+            keys.sort();
+            let cutoff = keys[keys.len() - MAX_INBOX_MESSAGES].clone();
+            self.messages.retain(|m| m.dm.order_key() >= cutoff);
+        }
+
+        self.messages.sort_by(|a, b| {
+            a.dm.message
+                .sender
+                .cmp(&b.dm.message.sender)
+                .then_with(|| a.dm.order_key().cmp(&b.dm.order_key()))
+        });
+    }
+
+    fn verify_purges(
+        purges: &AuthorizedRecipientPurges,
+        parameters: &InboxParametersV1,
+    ) -> Result<(), String> {
+        if purges.recipient_id != parameters.owner_id() {
+            return Err(format!(
+                "Inbox purges signed for {:?}, not the inbox owner",
+                purges.recipient_id
+            ));
+        }
+        if purges.state.version == 0 {
+            return Err("Inbox purges: version 0 is reserved as the absent sentinel".to_string());
+        }
+        if purges.state.purged.len() > MAX_PURGED_TOMBSTONES_PER_RECIPIENT {
+            return Err(format!(
+                "Inbox purges exceed cap: {} > {}",
+                purges.state.purged.len(),
+                MAX_PURGED_TOMBSTONES_PER_RECIPIENT
+            ));
+        }
+        purges.verify_signature(&parameters.owner, &parameters.owner)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InboxSummaryV1 {
+    /// `BTreeSet` for deterministic summary bytes, as in
+    /// `DirectMessagesSummary::message_signatures`.
+    #[serde(default)]
+    pub message_signatures: BTreeSet<SignatureBytes>,
+    /// Version of the held purge envelope; `0` when there is none.
+    #[serde(default)]
+    pub purge_version: u64,
+    /// Senders at [`MAX_DM_MESSAGES_PER_PAIR`], with the oldest message kept.
+    #[serde(default)]
+    pub sender_horizons: Vec<DmPairHorizon>,
+    #[serde(default)]
+    pub global_horizon: DmRetentionHorizon,
+    /// Version of the held allowlist; `0` when there is none.
+    #[serde(default)]
+    pub rooms_version: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InboxDeltaV1 {
+    #[serde(default)]
+    pub new_messages: Vec<InboxMessageV1>,
+    #[serde(default)]
+    pub purges: Option<AuthorizedRecipientPurges>,
+    #[serde(default)]
+    pub rooms: Option<AuthorizedInboxRoomsV1>,
+}
+
+impl ComposableState for InboxStateV1 {
+    type ParentState = InboxStateV1;
+    type Summary = InboxSummaryV1;
+    type Delta = InboxDeltaV1;
+    type Parameters = InboxParametersV1;
+
+    fn verify(
+        &self,
+        _parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+    ) -> Result<(), String> {
+        if let Some(purges) = &self.purges {
+            Self::verify_purges(purges, parameters)?;
+        }
+        if self.messages.len() > MAX_INBOX_MESSAGES {
+            return Err(format!(
+                "Inbox exceeds cap: {} > {}",
+                self.messages.len(),
+                MAX_INBOX_MESSAGES
+            ));
+        }
+
+        if let Some(rooms) = &self.rooms {
+            rooms.verify(parameters)?;
+        }
+
+        let tombstones = self.tombstones();
+        let mut seen = HashSet::new();
+        let mut per_sender: HashMap<MemberId, usize> = HashMap::new();
+        for msg in &self.messages {
+            msg.verify(parameters)?;
+            if !self.allows_room(msg.shared_room()) {
+                return Err(format!(
+                    "Inbox message from {:?} is bound to a room the owner has not allowed",
+                    msg.dm.message.sender
+                ));
+            }
+            if !seen.insert(msg.signature_bytes()) {
+                return Err("Inbox holds the same message twice".to_string());
+            }
+            if tombstones.contains(&msg.dm.purge_token()) {
+                return Err(format!(
+                    "Inbox message from {:?} is present despite being purged",
+                    msg.dm.message.sender
+                ));
+            }
+            let count = per_sender.entry(msg.dm.message.sender).or_insert(0);
+            *count += 1;
+            if *count > MAX_DM_MESSAGES_PER_PAIR {
+                return Err(format!(
+                    "Inbox sender {:?} exceeds cap: {} > {}",
+                    msg.dm.message.sender, count, MAX_DM_MESSAGES_PER_PAIR
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn summarize(
+        &self,
+        _parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+    ) -> Self::Summary {
+        InboxSummaryV1 {
+            message_signatures: self.messages.iter().map(|m| m.signature_bytes()).collect(),
+            purge_version: self.purges.as_ref().map(|p| p.state.version).unwrap_or(0),
+            rooms_version: self.rooms.as_ref().map(|r| r.rooms.version).unwrap_or(0),
+            sender_horizons: self.sender_horizons(parameters.owner_id()),
+            global_horizon: self.global_horizon(),
+        }
+    }
+
+    fn delta(
+        &self,
+        _parent_state: &Self::ParentState,
+        _parameters: &Self::Parameters,
+        old_state_summary: &Self::Summary,
+    ) -> Option<Self::Delta> {
+        let horizons: HashMap<MemberId, &DmOrderKey> = old_state_summary
+            .sender_horizons
+            .iter()
+            .map(|h| (h.sender, &h.oldest_retained))
+            .collect();
+
+        let new_messages: Vec<InboxMessageV1> = self
+            .messages
+            .iter()
+            .filter(|m| {
+                !old_state_summary
+                    .message_signatures
+                    .contains(&m.signature_bytes())
+            })
+            .filter(|m| match horizons.get(&m.dm.message.sender) {
+                None => true,
+                Some(oldest) => m.dm.order_key() > **oldest,
+            })
+            .filter(|m| match &old_state_summary.global_horizon {
+                DmRetentionHorizon::Open => true,
+                DmRetentionHorizon::OldestRetained(oldest) => m.dm.order_key() > *oldest,
+                DmRetentionHorizon::Closed => false,
+            })
+            .cloned()
+            .collect();
+
+        let purges = self
+            .purges
+            .as_ref()
+            .filter(|p| p.state.version > old_state_summary.purge_version)
+            .cloned();
+
+        let rooms = self
+            .rooms
+            .as_ref()
+            .filter(|r| r.rooms.version > old_state_summary.rooms_version)
+            .cloned();
+
+        if new_messages.is_empty() && purges.is_none() && rooms.is_none() {
+            None
+        } else {
+            Some(InboxDeltaV1 {
+                new_messages,
+                purges,
+                rooms,
+            })
+        }
+    }
+
+    fn apply_delta(
+        &mut self,
+        _parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+        delta: &Option<Self::Delta>,
+    ) -> Result<(), String> {
+        let Some(delta) = delta else {
+            self.enforce_caps_and_sort();
+            return Ok(());
+        };
+
+        // Purges first, with the room's rules: strictly newer version wins,
+        // and only as a superset of what it replaces (no un-purging). A
+        // same-version or shrinking envelope is dropped rather than failing
+        // the whole delta.
+        if let Some(advance) = &delta.purges {
+            Self::verify_purges(advance, parameters)?;
+            let accept = match &self.purges {
+                None => true,
+                Some(current) => {
+                    advance.state.version > current.state.version
+                        && current
+                            .state
+                            .purged
+                            .iter()
+                            .all(|t| advance.state.purged.contains(t))
+                }
+            };
+            if accept {
+                self.purges = Some(advance.clone());
+            }
+        }
+
+        // The allowlist before messages, since it decides which are kept.
+        // Strictly newer wins, as with purges.
+        if let Some(rooms) = &delta.rooms {
+            rooms.verify(parameters)?;
+            if self
+                .rooms
+                .as_ref()
+                .is_none_or(|held| rooms.rooms.version > held.rooms.version)
+            {
+                self.rooms = Some(rooms.clone());
+            }
+        }
+
+        let tombstones = self.tombstones();
+        let mut existing: HashSet<SignatureBytes> =
+            self.messages.iter().map(|m| m.signature_bytes()).collect();
+        for msg in &delta.new_messages {
+            // Silent-drop, as the room does: one bad message must not poison
+            // the merge of every other message in the delta.
+            if existing.contains(&msg.signature_bytes())
+                || tombstones.contains(&msg.dm.purge_token())
+                || !self.allows_room(msg.shared_room())
+                || msg.verify(parameters).is_err()
+            {
+                continue;
+            }
+            existing.insert(msg.signature_bytes());
+            self.messages.push(msg.clone());
+        }
+
+        let rooms = self.rooms.clone();
+        self.messages.retain(|m| {
+            !tombstones.contains(&m.dm.purge_token())
+                && rooms.as_ref().is_some_and(|r| r.allows(m.shared_room()))
+        });
+        self.enforce_caps_and_sort();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room_state::direct_messages::sign_direct_message;
+    use crate::room_state::member::Member;
+    use rand::rngs::OsRng;
+
+    struct Room {
+        owner_sk: SigningKey,
+        state: ChatRoomStateV1,
+    }
+
+    impl Room {
+        fn new() -> Self {
+            Self {
+                owner_sk: SigningKey::generate(&mut OsRng),
+                state: ChatRoomStateV1::default(),
+            }
+        }
+
+        fn owner_vk(&self) -> VerifyingKey {
+            self.owner_sk.verifying_key()
+        }
+
+        /// Add a member invited by `inviter_sk` (the owner when `None`).
+        fn invite(&mut self, inviter_sk: Option<&SigningKey>) -> SigningKey {
+            let sk = SigningKey::generate(&mut OsRng);
+            let inviter_sk = inviter_sk.unwrap_or(&self.owner_sk);
+            let member = Member {
+                owner_member_id: MemberId::from(&self.owner_vk()),
+                invited_by: MemberId::from(&inviter_sk.verifying_key()),
+                member_vk: sk.verifying_key(),
+            };
+            self.state
+                .members
+                .members
+                .push(AuthorizedMember::new(member, inviter_sk));
+            sk
+        }
+
+        fn proof(&self, vk: &VerifyingKey) -> MembershipProofV1 {
+            MembershipProofV1::from_room_state(self.owner_vk(), &self.state, vk).unwrap()
+        }
+
+        fn message(
+            &self,
+            sender_sk: &SigningKey,
+            recipient_vk: &VerifyingKey,
+            timestamp: u64,
+        ) -> InboxMessageV1 {
+            let dm = sign_direct_message(
+                sender_sk,
+                MemberId::from(&sender_sk.verifying_key()),
+                MemberId::from(recipient_vk),
+                &self.owner_vk(),
+                timestamp,
+                vec![timestamp as u8; 8],
+            )
+            .unwrap();
+            InboxMessageV1 {
+                dm,
+                sender_proof: self.proof(&sender_sk.verifying_key()),
+                recipient_proof: self.proof(recipient_vk),
+            }
+        }
+    }
+
+    fn apply(state: &mut InboxStateV1, params: &InboxParametersV1, delta: InboxDeltaV1) {
+        state
+            .apply_delta(&state.clone(), params, &Some(delta))
+            .unwrap();
+    }
+
+    fn with_messages(messages: Vec<InboxMessageV1>) -> InboxDeltaV1 {
+        InboxDeltaV1 {
+            new_messages: messages,
+            ..Default::default()
+        }
+    }
+
+    fn allowing(owner_sk: &SigningKey, version: u64, rooms: Vec<VerifyingKey>) -> InboxDeltaV1 {
+        InboxDeltaV1 {
+            rooms: Some(AuthorizedInboxRoomsV1::new(owner_sk, None, version, rooms)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_member_of_a_shared_room_can_write_and_a_stranger_cannot() {
+        let mut room = Room::new();
+        let alice = room.invite(None);
+        let bob = room.invite(Some(&alice));
+        let params = InboxParametersV1 {
+            owner: alice.verifying_key(),
+        };
+
+        let mut inbox = InboxStateV1::default();
+        apply(
+            &mut inbox,
+            &params,
+            allowing(&alice, 1, vec![room.owner_vk()]),
+        );
+        apply(
+            &mut inbox,
+            &params,
+            with_messages(vec![room.message(&bob, &params.owner, 10)]),
+        );
+        assert_eq!(inbox.messages.len(), 1);
+        inbox.verify(&inbox, &params).unwrap();
+        assert_eq!(inbox.messages_for_room(&room.owner_vk()).count(), 1);
+
+        // Carol is only in another room. A DM bound to that room cannot pair
+        // her proof with Alice's, and Alice has no proof for Carol's room.
+        let mut other = Room::new();
+        let carol = other.invite(None);
+        let dm = sign_direct_message(
+            &carol,
+            MemberId::from(&carol.verifying_key()),
+            params.owner_id(),
+            &other.owner_vk(),
+            11,
+            vec![1; 8],
+        )
+        .unwrap();
+        let forged = InboxMessageV1 {
+            dm,
+            sender_proof: other.proof(&carol.verifying_key()),
+            recipient_proof: room.proof(&params.owner),
+        };
+        assert!(forged.verify(&params).is_err());
+        apply(&mut inbox, &params, with_messages(vec![forged]));
+        assert_eq!(inbox.messages.len(), 1);
+
+        // A proof with a link removed no longer reaches the owner.
+        let mut broken = room.message(&bob, &params.owner, 12);
+        broken.sender_proof.invite_chain.clear();
+        assert!(broken.verify(&params).is_err());
+    }
+
+    #[test]
+    fn a_room_the_owner_did_not_allow_cannot_write() {
+        let mut room = Room::new();
+        let alice = room.invite(None);
+        let bob = room.invite(None);
+        let params = InboxParametersV1 {
+            owner: alice.verifying_key(),
+        };
+
+        // Mallory creates a room and signs a membership for Alice's key
+        // herself. Both invite chains verify, so the message alone passes.
+        let mut evil = Room::new();
+        let mallory = evil.invite(None);
+        evil.state.members.members.push(AuthorizedMember::new(
+            Member {
+                owner_member_id: MemberId::from(&evil.owner_vk()),
+                invited_by: MemberId::from(&evil.owner_vk()),
+                member_vk: alice.verifying_key(),
+            },
+            &evil.owner_sk,
+        ));
+        let spam = evil.message(&mallory, &params.owner, 10);
+        spam.verify(&params).unwrap();
+
+        // Nothing is accepted before Alice publishes her allowlist.
+        let mut inbox = InboxStateV1::default();
+        let genuine = room.message(&bob, &params.owner, 11);
+        apply(&mut inbox, &params, with_messages(vec![genuine.clone()]));
+        assert!(inbox.messages.is_empty());
+
+        apply(
+            &mut inbox,
+            &params,
+            allowing(&alice, 1, vec![room.owner_vk()]),
+        );
+        apply(
+            &mut inbox,
+            &params,
+            with_messages(vec![spam.clone(), genuine]),
+        );
+        assert_eq!(inbox.messages.len(), 1);
+        assert!(!inbox.messages.contains(&spam));
+        inbox.verify(&inbox, &params).unwrap();
+
+        // Only Alice can sign her allowlist.
+        assert!(inbox
+            .apply_delta(
+                &inbox.clone(),
+                &params,
+                &Some(allowing(&mallory, 2, vec![evil.owner_vk()])),
+            )
+            .is_err());
+
+        // A stale list loses; a newer one without the room drops its DMs.
+        apply(&mut inbox, &params, allowing(&alice, 0, vec![]));
+        assert_eq!(inbox.messages.len(), 1);
+        apply(&mut inbox, &params, allowing(&alice, 2, vec![]));
+        assert!(inbox.messages.is_empty());
+        inbox.verify(&inbox, &params).unwrap();
+    }
+
+    #[test]
+    fn the_owner_purge_tombstones_a_message_against_replay() {
+        let mut room = Room::new();
+        let alice = room.invite(None);
+        let bob = room.invite(None);
+        let params = InboxParametersV1 {
+            owner: alice.verifying_key(),
+        };
+        let msg = room.message(&bob, &params.owner, 10);
+
+        let mut inbox = InboxStateV1::default();
+        apply(
+            &mut inbox,
+            &params,
+            allowing(&alice, 1, vec![room.owner_vk()]),
+        );
+        apply(&mut inbox, &params, with_messages(vec![msg.clone()]));
+
+        let purges = sign_inbox_purges(&alice, None, [msg.dm.purge_token()]).unwrap();
+        apply(
+            &mut inbox,
+            &params,
+            InboxDeltaV1 {
+                purges: Some(purges),
+                ..Default::default()
+            },
+        );
+        assert!(inbox.messages.is_empty());
+
+        // A stale peer re-offering the message cannot bring it back.
+        apply(&mut inbox, &params, with_messages(vec![msg]));
+        assert!(inbox.messages.is_empty());
+        inbox.verify(&inbox, &params).unwrap();
+
+        // Only the inbox owner can sign the envelope.
+        let forged = sign_inbox_purges(&bob, None, []).unwrap();
+        assert!(inbox
+            .apply_delta(
+                &inbox.clone(),
+                &params,
+                &Some(InboxDeltaV1 {
+                    purges: Some(forged),
+                    ..Default::default()
+                }),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn one_busy_sender_cannot_evict_another_and_peers_converge() {
+        let mut room = Room::new();
+        let alice = room.invite(None);
+        let bob = room.invite(None);
+        let carol = room.invite(None);
+        let params = InboxParametersV1 {
+            owner: alice.verifying_key(),
+        };
+
+        let carols = room.message(&carol, &params.owner, 1);
+        let bobs: Vec<InboxMessageV1> = (0..MAX_DM_MESSAGES_PER_PAIR as u64 + 20)
+            .map(|t| room.message(&bob, &params.owner, 100 + t))
+            .collect();
+
+        let mut a = InboxStateV1::default();
+        apply(&mut a, &params, allowing(&alice, 1, vec![room.owner_vk()]));
+        apply(&mut a, &params, with_messages(vec![carols.clone()]));
+        apply(&mut a, &params, with_messages(bobs.clone()));
+        a.verify(&a, &params).unwrap();
+        assert_eq!(a.messages.len(), MAX_DM_MESSAGES_PER_PAIR + 1);
+        assert!(a.messages.contains(&carols));
+
+        // A peer that saw the messages in the other order, then synced via
+        // summary/delta, ends with the same state and nothing more to send.
+        let mut b = InboxStateV1::default();
+        apply(&mut b, &params, allowing(&alice, 1, vec![room.owner_vk()]));
+        let mut reversed = bobs;
+        reversed.reverse();
+        apply(&mut b, &params, with_messages(reversed));
+        b.merge(&b.clone(), &params, &a).unwrap();
+        assert_eq!(a, b);
+        assert!(a.delta(&a, &params, &b.summarize(&b, &params)).is_none());
+    }
+
+    #[test]
+    fn the_owner_needs_no_member_entry_to_prove_membership() {
+        let mut room = Room::new();
+        let bob = room.invite(None);
+        let params = InboxParametersV1 {
+            owner: room.owner_vk(),
+        };
+        let msg = room.message(&bob, &params.owner, 10);
+        assert!(msg.recipient_proof.member.is_none());
+        msg.verify(&params).unwrap();
+
+        // And the inbox state round-trips through CBOR.
+        let inbox = InboxStateV1 {
+            messages: vec![msg],
+            purges: None,
+            rooms: Some(AuthorizedInboxRoomsV1::new(
+                &room.owner_sk,
+                None,
+                1,
+                vec![room.owner_vk()],
+            )),
+        };
+        inbox.verify(&inbox, &params).unwrap();
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&inbox, &mut bytes).unwrap();
+        let decoded: InboxStateV1 = ciborium::de::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, inbox);
+    }
+}
@@ -1,7 +1,17 @@
 pub mod chat_delegate;
 pub mod crypto_values;
+/// Nickname sanitisation shared by the UI and riverctl. Gated on the
+/// `display-name` feature for the same reason as `mentions` below.
+#[cfg(feature = "display-name")]
+pub mod display_name;
 #[cfg(feature = "ecies")]
 pub mod ecies;
+/// Per-member DM inbox contract state (#230 Phase 4). Gated on the `inbox`
+/// feature so the room-contract / chat-delegate WASM builds keep
+/// byte-identical WASM and stable keys; enabled by the inbox contract and the
+/// clients.
+#[cfg(feature = "inbox")]
+pub mod inbox;
 pub mod key_derivation;
 /// `@mention` codec (freenet/river @mentions feature). Gated on the `mentions`
 /// feature so the room-contract / chat-delegate WASM builds (which do not
@@ -14,7 +24,25 @@ pub mod mention;
 /// do not enable it) keep byte-identical WASM and stable keys.
 #[cfg(feature = "migration")]
 pub mod migration;
+/// Per-room presence contract state: online members and typing indicators.
+/// Gated on the `presence` feature like `inbox`, for the same reason.
+#[cfg(feature = "presence")]
+pub mod presence;
 pub mod room_state;
+/// Deterministic multi-peer convergence simulator. Gated on the `simulation`
+/// feature: it is a test and debugging tool (riverctl replays its traces) and
+/// must stay out of the room-contract and chat-delegate WASM.
+#[cfg(feature = "simulation")]
+pub mod simulation;
+/// Space contract state: rooms grouped under shared membership and bans.
+/// Gated on the `space` feature like `inbox`, for the same reason.
+#[cfg(feature = "space")]
+pub mod space;
+/// Per-field and per-member breakdown of a room state's serialized size, for
+/// `riverctl debug size`. Client-only behind the `state-size` feature, so the
+/// contract and delegate WASM never compile it.
+#[cfg(feature = "state-size")]
+pub mod state_size;
 pub mod util;
 pub mod web_container;
 
@@ -34,6 +34,17 @@
 //! forms are unambiguous: the current form is exactly 8 base32 chars, the
 //! legacy form is up to 16 hex chars, and the parser tries base32 first.
 //!
+//! ### Room-wide mentions
+//!
+//! `@here` and `@room` use the same shape with a lowercase keyword in place of
+//! the member reference: `@[here](rv:here)`, `@[room](rv:room)`. Neither
+//! keyword is 8 uppercase base32 chars or valid hex, so they can't be mistaken
+//! for a member reference, and a client that predates them renders the token
+//! as a plain `@here` link. Who may broadcast is the room's
+//! [`BroadcastMentionPolicy`]; whom a broadcast reaches is
+//! [`BroadcastAudience`]. Both are decided by the reader from room state, since
+//! the contract never sees message text.
+//!
 //! The token rides inside the existing `text` field, so it needs **no** change
 //! to `RoomMessageBody` / the contract, works in both public and private rooms
 //! (it sits inside the encrypted text), and applies equally to plain text and
@@ -46,8 +57,11 @@
 //! and the grammar is trivial. The parser also never has to be valid markdown —
 //! clients extract mentions *before* markdown runs over the text.
 
+use crate::room_state::configuration::BroadcastMentionPolicy;
 use crate::room_state::member::MemberId;
+use crate::ChatRoomStateV1;
 use freenet_scaffold::util::FastHash;
+use std::collections::HashSet;
 
 /// Scheme prefix inside the mention token's reference, e.g. `rv:FPVN6PUN`.
 pub const REF_SCHEME: &str = "rv:";
@@ -112,11 +126,41 @@ impl MemberRef {
     }
 }
 
-/// One piece of a message body: either a run of plain text or a mention token.
+/// A room-wide mention.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Broadcast {
+    /// `@here`: members who have a message in the room's retained history.
+    Here,
+    /// `@room`: every member.
+    Room,
+}
+
+impl Broadcast {
+    /// The keyword used both as the typed `@word` and as the token reference.
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Broadcast::Here => "here",
+            Broadcast::Room => "room",
+        }
+    }
+
+    /// Parse a token reference body. Exact and lowercase, like the wire form.
+    pub fn from_keyword(s: &str) -> Option<Self> {
+        match s {
+            "here" => Some(Broadcast::Here),
+            "room" => Some(Broadcast::Room),
+            _ => None,
+        }
+    }
+}
+
+/// One piece of a message body: a run of plain text, a mention of one member,
+/// or a room-wide mention.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum MentionSegment {
     Text(String),
     Mention(Mention),
+    Broadcast(Broadcast),
 }
 
 /// Encode a [`MemberId`] as the 16-char lowercase-hex form. This is the
@@ -195,6 +239,12 @@ pub fn encode_mention(id: MemberId, display_name: &str) -> String {
     )
 }
 
+/// Build the wire token `@[here](rv:here)` for a room-wide mention.
+pub fn encode_broadcast(broadcast: Broadcast) -> String {
+    let keyword = broadcast.keyword();
+    format!("@[{keyword}]({REF_SCHEME}{keyword})")
+}
+
 /// Split message text into ordered plain-text / mention segments. This is the
 /// core parser; the other helpers are thin wrappers over it.
 pub fn parse_segments(text: &str) -> Vec<MentionSegment> {
@@ -205,11 +255,11 @@ pub fn parse_segments(text: &str) -> Vec<MentionSegment> {
     let mut i = 0usize;
     while i < bytes.len() {
         if bytes[i] == b'@' {
-            if let Some((mention, end)) = try_parse_token_at(text, i) {
+            if let Some((segment, end)) = try_parse_token_at(text, i) {
                 if text_start < i {
                     segments.push(MentionSegment::Text(text[text_start..i].to_string()));
                 }
-                segments.push(MentionSegment::Mention(mention));
+                segments.push(segment);
                 i = end;
                 text_start = end;
                 continue;
@@ -224,10 +274,10 @@ pub fn parse_segments(text: &str) -> Vec<MentionSegment> {
 }
 
 /// Try to parse a complete mention token starting at byte index `at` (which
-/// must point at `@`). On success returns the parsed mention and the byte index
-/// just past the closing `)`. All delimiters (`[ ] ( ) :`) are ASCII, so byte
-/// scanning never lands mid-codepoint.
-fn try_parse_token_at(text: &str, at: usize) -> Option<(Mention, usize)> {
+/// must point at `@`). On success returns the parsed mention (or broadcast)
+/// and the byte index just past the closing `)`. All delimiters (`[ ] ( ) :`)
+/// are ASCII, so byte scanning never lands mid-codepoint.
+fn try_parse_token_at(text: &str, at: usize) -> Option<(MentionSegment, usize)> {
     let bytes = text.as_bytes();
     if bytes.get(at) != Some(&b'@') || bytes.get(at + 1) != Some(&b'[') {
         return None;
@@ -262,12 +312,17 @@ fn try_parse_token_at(text: &str, at: usize) -> Option<(Mention, usize)> {
     if k >= bytes.len() {
         return None; // unterminated `(`
     }
-    let member_ref = member_ref_from_str(&text[id_start..k])?;
+    let reference = &text[id_start..k];
+    // A broadcast's `[name]` is just its keyword again, so it is not kept.
+    if let Some(broadcast) = Broadcast::from_keyword(reference) {
+        return Some((MentionSegment::Broadcast(broadcast), k + 1));
+    }
+    let member_ref = member_ref_from_str(reference)?;
     Some((
-        Mention {
+        MentionSegment::Mention(Mention {
             member_ref,
             display_name: name.to_string(),
-        },
+        }),
         k + 1,
     ))
 }
@@ -278,7 +333,18 @@ pub fn parse_mentions(text: &str) -> Vec<Mention> {
         .into_iter()
         .filter_map(|s| match s {
             MentionSegment::Mention(m) => Some(m),
-            MentionSegment::Text(_) => None,
+            MentionSegment::Text(_) | MentionSegment::Broadcast(_) => None,
+        })
+        .collect()
+}
+
+/// All room-wide mentions in the text, in order of appearance.
+pub fn parse_broadcasts(text: &str) -> Vec<Broadcast> {
+    parse_segments(text)
+        .into_iter()
+        .filter_map(|s| match s {
+            MentionSegment::Broadcast(b) => Some(b),
+            MentionSegment::Text(_) | MentionSegment::Mention(_) => None,
         })
         .collect()
 }
@@ -308,16 +374,92 @@ where
                 out.push('@');
                 out.push_str(&name);
             }
+            MentionSegment::Broadcast(b) => {
+                out.push('@');
+                out.push_str(b.keyword());
+            }
         }
     }
     out
 }
 
+/// Who may broadcast in a room and whom a broadcast reaches, computed once
+/// from the room state so a batch of messages is checked without rescanning
+/// it.
+///
+/// The default value honours no broadcast at all.
+#[derive(Clone, Debug, Default)]
+pub struct BroadcastAudience {
+    /// Authors whose `@here` / `@room` is honoured under the room's
+    /// [`BroadcastMentionPolicy`].
+    senders: HashSet<MemberId>,
+    /// Authors of a message in the retained history, whom `@here` reaches.
+    active: HashSet<MemberId>,
+}
+
+impl BroadcastAudience {
+    pub fn new(state: &ChatRoomStateV1) -> Self {
+        let config = &state.configuration.configuration;
+        let owner = config.owner_member_id;
+        let members = state.members.members.iter().map(|m| m.member.id());
+        // The owner has no `members` entry, so it is added explicitly. A
+        // deputy must still be a member: a pruned deputy's id lingers in the
+        // owner's list but grants nothing, as for bans.
+        let senders = match config.effective_broadcast_mentions() {
+            BroadcastMentionPolicy::Nobody => HashSet::new(),
+            BroadcastMentionPolicy::Owner => HashSet::from([owner]),
+            BroadcastMentionPolicy::OwnerAndDeputies => {
+                let deputies = state.member_info.deputies_of(owner);
+                std::iter::once(owner)
+                    .chain(members.filter(|id| deputies.contains(id)))
+                    .collect()
+            }
+            BroadcastMentionPolicy::Members => std::iter::once(owner).chain(members).collect(),
+        };
+        let active = state
+            .recent_messages
+            .messages
+            .iter()
+            .map(|m| m.message.author)
+            .collect();
+        Self { senders, active }
+    }
+
+    /// Whether a broadcast by `author` is honoured in this room.
+    pub fn may_broadcast(&self, author: MemberId) -> bool {
+        self.senders.contains(&author)
+    }
+
+    /// Whether `broadcast` reaches `member`, assuming its author may send it.
+    pub fn reaches(&self, broadcast: Broadcast, member: MemberId) -> bool {
+        match broadcast {
+            Broadcast::Here => self.active.contains(&member),
+            Broadcast::Room => true,
+        }
+    }
+
+    /// Whether `text`, written by `author`, addresses `member`: it mentions
+    /// them by name, or carries an honoured broadcast that reaches them. An
+    /// author's own broadcast never addresses themselves.
+    pub fn addresses(&self, text: &str, author: MemberId, member: MemberId) -> bool {
+        parse_segments(text).into_iter().any(|seg| match seg {
+            MentionSegment::Text(_) => false,
+            MentionSegment::Mention(m) => m.member_ref.matches(member),
+            MentionSegment::Broadcast(b) => {
+                author != member && self.may_broadcast(author) && self.reaches(b, member)
+            }
+        })
+    }
+}
+
 /// Trailing characters that are punctuation rather than part of a typed name.
 const TRAILING_PUNCT: &[char] = &['.', ',', '!', '?', ';', ':', ')', ']', '}', '"', '\''];
 
 /// Convert bare `@name` mentions typed in free text into full `@[name](rv:id)`
 /// wire tokens. Used by surfaces without an autocomplete picker (riverctl).
+/// A typed `@here` or `@room` (any case) always becomes a broadcast token,
+/// before `resolve` is consulted; whether the sender may use it is the
+/// caller's check.
 ///
 /// A `@name` is a `@` at a word boundary (start of text or after whitespace)
 /// followed by a run of non-whitespace characters; trailing punctuation is not
@@ -350,6 +492,13 @@ where
             }
             let run = &text[run_start..j];
             let candidate = run.trim_end_matches(TRAILING_PUNCT);
+            if let Some(b) = Broadcast::from_keyword(&candidate.to_ascii_lowercase()) {
+                out.push_str(&encode_broadcast(b));
+                out.push_str(&run[candidate.len()..]);
+                i = j;
+                prev_was_ws = false;
+                continue;
+            }
             if !candidate.is_empty() {
                 if let Some((id, name)) = resolve(candidate) {
                     out.push_str(&encode_mention(id, &name));
@@ -551,7 +700,7 @@ mod tests {
             .iter()
             .find_map(|s| match s {
                 MentionSegment::Mention(m) => Some(m),
-                MentionSegment::Text(_) => None,
+                MentionSegment::Text(_) | MentionSegment::Broadcast(_) => None,
             })
             .expect("exactly one mention");
         assert!(m.member_ref.matches(id));
@@ -640,7 +789,7 @@ mod tests {
                 .into_iter()
                 .map(|seg| match seg {
                     MentionSegment::Text(t) => t,
-                    MentionSegment::Mention(_) => unreachable!(),
+                    MentionSegment::Mention(_) | MentionSegment::Broadcast(_) => unreachable!(),
                 })
                 .collect();
             assert_eq!(rebuilt, s);
@@ -732,4 +881,170 @@ mod tests {
         let rendered = render_plaintext(&text, |_| Some("Cat".to_string()));
         assert_eq!(rendered, "(see @Cat, thanks)");
     }
+
+    #[test]
+    fn broadcast_tokens_parse_and_never_read_as_member_refs() {
+        for b in [Broadcast::Here, Broadcast::Room] {
+            let text = format!("heads up {} now", encode_broadcast(b));
+            assert_eq!(parse_broadcasts(&text), vec![b]);
+            assert!(parse_mentions(&text).is_empty());
+            assert_eq!(member_ref_from_str(b.keyword()), None);
+            assert_eq!(
+                render_plaintext(&text, |_| None),
+                format!("heads up @{} now", b.keyword())
+            );
+        }
+        // The keyword is exact on the wire; anything else is left as text.
+        assert!(parse_broadcasts("@[Here](rv:Here)").is_empty());
+    }
+
+    #[test]
+    fn typed_here_and_room_become_broadcasts_ahead_of_nicknames() {
+        let here = mid(0x4e4e);
+        // A member literally nicknamed "here" does not shadow the keyword.
+        let resolve = |name: &str| -> Option<(MemberId, String)> {
+            name.eq_ignore_ascii_case("here")
+                .then(|| (here, "here".to_string()))
+        };
+        let out = resolve_typed_mentions("@Here! and @room, not a@room", resolve);
+        assert_eq!(
+            out,
+            format!(
+                "{}! and {}, not a@room",
+                encode_broadcast(Broadcast::Here),
+                encode_broadcast(Broadcast::Room)
+            )
+        );
+    }
+
+    mod audience {
+        use super::super::*;
+        use crate::room_state::configuration::{AuthorizedConfigurationV1, Configuration};
+        use crate::room_state::member::{AuthorizedMember, Member, MembersV1};
+        use crate::room_state::member_info::{AuthorizedMemberInfo, MemberInfo, MemberInfoV1};
+        use crate::room_state::message::{
+            AuthorizedMessageV1, MessageV1, MessagesV1, RoomMessageBody,
+        };
+        use ed25519_dalek::SigningKey;
+        use std::time::SystemTime;
+
+        struct Room {
+            state: ChatRoomStateV1,
+            owner: MemberId,
+            deputy: MemberId,
+            talker: MemberId,
+            lurker: MemberId,
+        }
+
+        /// Owner, a deputy the owner appointed, a member with a retained
+        /// message and a member without one.
+        fn room(policy: Option<BroadcastMentionPolicy>) -> Room {
+            let owner_sk = SigningKey::from_bytes(&[1; 32]);
+            let sks: Vec<SigningKey> = (2..5).map(|i| SigningKey::from_bytes(&[i; 32])).collect();
+            let owner: MemberId = owner_sk.verifying_key().into();
+            let ids: Vec<MemberId> = sks.iter().map(|sk| sk.verifying_key().into()).collect();
+            let members = sks
+                .iter()
+                .map(|sk| {
+                    AuthorizedMember::new(
+                        Member {
+                            owner_member_id: owner,
+                            invited_by: owner,
+                            member_vk: sk.verifying_key(),
+                        },
+                        &owner_sk,
+                    )
+                })
+                .collect();
+            let mut owner_info = MemberInfo::new_public(owner, 1, "owner".to_string());
+            owner_info.deputies = vec![ids[0]];
+            let message = AuthorizedMessageV1::new(
+                MessageV1 {
+                    room_owner: owner,
+                    author: ids[1],
+                    time: SystemTime::now(),
+                    content: RoomMessageBody::public("hello".to_string()),
+                },
+                &sks[1],
+            );
+            let state = ChatRoomStateV1 {
+                configuration: AuthorizedConfigurationV1::new(
+                    Configuration {
+                        owner_member_id: owner,
+                        broadcast_mentions: policy,
+                        ..Default::default()
+                    },
+                    &owner_sk,
+                ),
+                members: MembersV1 { members },
+                member_info: MemberInfoV1 {
+                    member_info: vec![AuthorizedMemberInfo::new_with_member_key(
+                        owner_info, &owner_sk,
+                    )],
+                },
+                recent_messages: MessagesV1 {
+                    messages: vec![message],
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            Room {
+                state,
+                owner,
+                deputy: ids[0],
+                talker: ids[1],
+                lurker: ids[2],
+            }
+        }
+
+        #[test]
+        fn only_the_policy_s_senders_are_honoured() {
+            let cases = [
+                (None, vec![true, true, false]),
+                (
+                    Some(BroadcastMentionPolicy::Nobody),
+                    vec![false, false, false],
+                ),
+                (
+                    Some(BroadcastMentionPolicy::Owner),
+                    vec![true, false, false],
+                ),
+                (
+                    Some(BroadcastMentionPolicy::OwnerAndDeputies),
+                    vec![true, true, false],
+                ),
+                (
+                    Some(BroadcastMentionPolicy::Members),
+                    vec![true, true, true],
+                ),
+            ];
+            for (policy, expected) in cases {
+                let r = room(policy);
+                let audience = BroadcastAudience::new(&r.state);
+                let got: Vec<bool> = [r.owner, r.deputy, r.lurker]
+                    .into_iter()
+                    .map(|id| audience.may_broadcast(id))
+                    .collect();
+                assert_eq!(got, expected, "policy {policy:?}");
+            }
+        }
+
+        #[test]
+        fn here_reaches_only_members_with_retained_messages() {
+            let r = room(None);
+            let audience = BroadcastAudience::new(&r.state);
+            let here = encode_broadcast(Broadcast::Here);
+            let everyone = encode_broadcast(Broadcast::Room);
+
+            assert!(audience.addresses(&here, r.owner, r.talker));
+            assert!(!audience.addresses(&here, r.owner, r.lurker));
+            assert!(audience.addresses(&everyone, r.owner, r.lurker));
+            // An unauthorised sender reaches nobody, and nobody's own
+            // broadcast addresses themselves.
+            assert!(!audience.addresses(&everyone, r.lurker, r.talker));
+            assert!(!audience.addresses(&everyone, r.owner, r.owner));
+            // A direct mention still addresses its target whoever sends it.
+            assert!(audience.addresses(&encode_mention(r.talker, "t"), r.lurker, r.talker));
+        }
+    }
 }
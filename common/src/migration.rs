@@ -108,9 +108,11 @@ mod tests {
         }
         // V31 registers the pre-summary-digest generation (freenet/river#571,
         // `MemberInfoV1::Summary` carrying a raw `Signature` per member), which
-        // re-keys the contract.
-        assert_eq!(LEGACY_ROOM_CONTRACT_CODE_HASHES.len(), 31);
-        assert_eq!(&hasher.finalize().to_hex()[..16], "b5f02d45b6370b4d");
+        // re-keys the contract. V32 registers the generation before the
+        // batched room-state re-key (group DMs, profiles, message TTL, emoji
+        // packs, spaces).
+        assert_eq!(LEGACY_ROOM_CONTRACT_CODE_HASHES.len(), 32);
+        assert_eq!(&hasher.finalize().to_hex()[..16], "f6e6f99520959a6d");
     }
 
     #[test]
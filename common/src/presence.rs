@@ -0,0 +1,539 @@
+//! Per-room presence contract state: who is online and who is typing.
+//!
+//! Presence changes every few seconds and is worthless a minute later, so it
+//! does not belong in [`ChatRoomStateV1`], whose retention is tuned for
+//! messages people will scroll back to. It lives instead in a small contract
+//! of its own, keyed by [`PresenceParametersV1::room_owner`], holding at most
+//! one [`AuthorizedHeartbeatV1`] per member: the newest one they signed.
+//!
+//! A heartbeat carries a [`MembershipProofV1`] (the same invite-chain
+//! evidence the DM inbox uses), so only someone invited into the room can
+//! announce themselves in it, and is signed by the member over the room it is
+//! for, so it can't be replayed into another room's presence.
+//!
+//! # Pruning
+//!
+//! A contract has no clock, so heartbeats are pruned by age *relative to the
+//! second-newest heartbeat held*: anything more than [`PRESENCE_TTL_SECS`]
+//! older than it is dropped. That is a pure function of the held set, so every
+//! peer prunes identically and merge stays commutative. The reference is the
+//! second-newest rather than the newest so that no single member's clock sets
+//! it: a heartbeat signed far in the future holds one slot and shows its
+//! sender as offline (see below), but it cannot age anyone else out. While the
+//! room is quiet the last few heartbeats linger; clients judge "online"
+//! against their own clock (see [`PresenceStateV1::online`]), so a stale entry
+//! reads as offline.
+//!
+//! # What a heartbeat does NOT prove
+//!
+//! As with the inbox, a proof shows the member was invited, not that they
+//! are still in the room. A banned member can keep heart-beating, and clients
+//! filter presence against the room's current member list. Two members who
+//! both sign heartbeats far in the future can still push everyone else past
+//! the TTL until real time catches up. That is the cost of a clockless
+//! contract, and this contract cannot remove them: it never sees the room's
+//! bans, and their heartbeats can only be replaced by newer ones they sign.
+
+use crate::inbox::MembershipProofV1;
+use crate::room_state::direct_messages::SignatureBytes;
+use crate::room_state::member::MemberId;
+use crate::util::{sign_struct, verify_struct};
+use crate::ChatRoomStateV1;
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use freenet_scaffold::ComposableState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Heartbeats older than the second-newest held one by more than this are
+/// pruned.
+pub const PRESENCE_TTL_SECS: u64 = 120;
+
+/// Maximum heartbeats held, newest kept.
+pub const MAX_PRESENCE_ENTRIES: usize = 256;
+
+/// How recent a heartbeat must be, by the reader's clock, to show its member
+/// as online. Clients heartbeat every [`HEARTBEAT_INTERVAL_SECS`], so this
+/// tolerates two missed beats.
+pub const ONLINE_WINDOW_SECS: u64 = 90;
+
+/// How often an open client re-announces itself.
+pub const HEARTBEAT_INTERVAL_SECS: u64 = 30;
+
+/// How long a `typing` heartbeat shows "is typing" for.
+pub const TYPING_WINDOW_SECS: u64 = 8;
+
+/// Presence contract parameters. The contract key is derived from these, so a
+/// room's presence is found from the room owner's key alone.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PresenceParametersV1 {
+    pub room_owner: VerifyingKey,
+}
+
+/// One member's announcement that they are here.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HeartbeatV1 {
+    /// Signed so a heartbeat can't be lifted into another room's presence.
+    pub room_owner: VerifyingKey,
+    pub member_vk: VerifyingKey,
+    /// Unix seconds, by the sender's clock.
+    pub sent_at: u64,
+    /// Whether the member is composing a message right now.
+    pub typing: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthorizedHeartbeatV1 {
+    pub heartbeat: HeartbeatV1,
+    pub proof: MembershipProofV1,
+    pub signature: Signature,
+}
+
+impl AuthorizedHeartbeatV1 {
+    /// Sign a heartbeat for `member_sk`, proving membership from a room state
+    /// the caller holds.
+    pub fn new(
+        member_sk: &SigningKey,
+        room_owner: VerifyingKey,
+        room_state: &ChatRoomStateV1,
+        sent_at: u64,
+        typing: bool,
+    ) -> Result<Self, String> {
+        let member_vk = member_sk.verifying_key();
+        let heartbeat = HeartbeatV1 {
+            room_owner,
+            member_vk,
+            sent_at,
+            typing,
+        };
+        Ok(Self {
+            signature: sign_struct(&heartbeat, member_sk),
+            proof: MembershipProofV1::from_room_state(room_owner, room_state, &member_vk)?,
+            heartbeat,
+        })
+    }
+
+    pub fn member_id(&self) -> MemberId {
+        MemberId::from(&self.heartbeat.member_vk)
+    }
+
+    /// Check the signature, the room binding and the membership proof.
+    pub fn verify(&self, parameters: &PresenceParametersV1) -> Result<(), String> {
+        let heartbeat = &self.heartbeat;
+        if heartbeat.room_owner != parameters.room_owner
+            || self.proof.room_owner != parameters.room_owner
+        {
+            return Err("Heartbeat is for a different room".to_string());
+        }
+        if self.proof.verify()? != heartbeat.member_vk {
+            return Err(format!(
+                "Membership proof is not for {:?}",
+                self.member_id()
+            ));
+        }
+        verify_struct(heartbeat, &self.signature, &heartbeat.member_vk)
+            .map_err(|e| format!("Invalid heartbeat signature: {}", e))
+    }
+
+    /// Last-writer-wins order between two heartbeats from one member; the
+    /// signature breaks a same-second tie so every peer keeps the same one.
+    fn order_key(&self) -> HeartbeatKey {
+        HeartbeatKey {
+            sent_at: self.heartbeat.sent_at,
+            signature: SignatureBytes(self.signature.to_bytes()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HeartbeatKey {
+    pub sent_at: u64,
+    pub signature: SignatureBytes,
+}
+
+/// Presence contract state.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PresenceStateV1 {
+    /// At most one per member, sorted by member id.
+    #[serde(default)]
+    pub heartbeats: Vec<AuthorizedHeartbeatV1>,
+}
+
+impl PresenceStateV1 {
+    /// Members whose latest heartbeat is at most [`ONLINE_WINDOW_SECS`] old
+    /// by `now` (unix seconds). A heartbeat up to the same window in the
+    /// future counts as fresh, since clocks disagree; one further ahead does
+    /// not, so a far-future heartbeat can't show its sender online forever.
+    pub fn online(&self, now: u64) -> impl Iterator<Item = MemberId> + '_ {
+        self.fresh(now, ONLINE_WINDOW_SECS)
+            .map(AuthorizedHeartbeatV1::member_id)
+    }
+
+    /// Members who announced they are typing in the last
+    /// [`TYPING_WINDOW_SECS`] by `now`.
+    pub fn typing(&self, now: u64) -> impl Iterator<Item = MemberId> + '_ {
+        self.fresh(now, TYPING_WINDOW_SECS)
+            .filter(|h| h.heartbeat.typing)
+            .map(AuthorizedHeartbeatV1::member_id)
+    }
+
+    fn fresh(&self, now: u64, window: u64) -> impl Iterator<Item = &AuthorizedHeartbeatV1> {
+        self.heartbeats
+            .iter()
+            .filter(move |h| now.abs_diff(h.heartbeat.sent_at) <= window)
+    }
+
+    fn expiry_cutoff(&self) -> u64 {
+        expiry_cutoff(self.heartbeats.iter().map(|h| h.heartbeat.sent_at))
+    }
+
+    /// Keep the newest heartbeat per member, prune by relative age, trim to
+    /// [`MAX_PRESENCE_ENTRIES`] (newest kept) and sort by member id.
+    fn prune_and_sort(&mut self) {
+        let mut latest: BTreeMap<MemberId, AuthorizedHeartbeatV1> = BTreeMap::new();
+        for h in self.heartbeats.drain(..) {
+            match latest.get(&h.member_id()) {
+                Some(held) if held.order_key() >= h.order_key() => {}
+                _ => {
+                    latest.insert(h.member_id(), h);
+                }
+            }
+        }
+        self.heartbeats = latest.into_values().collect();
+
+        let cutoff = self.expiry_cutoff();
+        self.heartbeats.retain(|h| h.heartbeat.sent_at >= cutoff);
+        if self.heartbeats.len() > MAX_PRESENCE_ENTRIES {
+            let mut keys: Vec<HeartbeatKey> =
+                self.heartbeats.iter().map(|h| h.order_key()).collect();
+            keys.sort();
+            let cutoff = keys[keys.len() - MAX_PRESENCE_ENTRIES];
+            self.heartbeats.retain(|h| h.order_key() >= cutoff);
+        }
+    }
+}
+
+/// [`PRESENCE_TTL_SECS`] before the second-newest of `sent_at` (one per
+/// member), or 0 while fewer than two are held.
+fn expiry_cutoff(sent_at: impl Iterator<Item = u64>) -> u64 {
+    let mut newest = [0u64; 2];
+    for t in sent_at {
+        if t > newest[0] {
+            newest = [t, newest[0]];
+        } else if t > newest[1] {
+            newest[1] = t;
+        }
+    }
+    newest[1].saturating_sub(PRESENCE_TTL_SECS)
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PresenceSummaryV1 {
+    /// The heartbeat held per member. `BTreeMap` for deterministic summary
+    /// bytes, as in `InboxSummaryV1::message_signatures`.
+    #[serde(default)]
+    pub latest: BTreeMap<MemberId, HeartbeatKey>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PresenceDeltaV1 {
+    #[serde(default)]
+    pub heartbeats: Vec<AuthorizedHeartbeatV1>,
+}
+
+impl ComposableState for PresenceStateV1 {
+    type ParentState = PresenceStateV1;
+    type Summary = PresenceSummaryV1;
+    type Delta = PresenceDeltaV1;
+    type Parameters = PresenceParametersV1;
+
+    fn verify(
+        &self,
+        _parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+    ) -> Result<(), String> {
+        if self.heartbeats.len() > MAX_PRESENCE_ENTRIES {
+            return Err(format!(
+                "Presence exceeds cap: {} > {}",
+                self.heartbeats.len(),
+                MAX_PRESENCE_ENTRIES
+            ));
+        }
+        let cutoff = self.expiry_cutoff();
+        let mut seen = HashSet::new();
+        for h in &self.heartbeats {
+            h.verify(parameters)?;
+            if !seen.insert(h.member_id()) {
+                return Err(format!(
+                    "Presence holds two heartbeats for {:?}",
+                    h.member_id()
+                ));
+            }
+            if h.heartbeat.sent_at < cutoff {
+                return Err(format!(
+                    "Presence holds an expired heartbeat for {:?}",
+                    h.member_id()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn summarize(
+        &self,
+        _parent_state: &Self::ParentState,
+        _parameters: &Self::Parameters,
+    ) -> Self::Summary {
+        PresenceSummaryV1 {
+            latest: self
+                .heartbeats
+                .iter()
+                .map(|h| (h.member_id(), h.order_key()))
+                .collect(),
+        }
+    }
+
+    fn delta(
+        &self,
+        _parent_state: &Self::ParentState,
+        _parameters: &Self::Parameters,
+        old_state_summary: &Self::Summary,
+    ) -> Option<Self::Delta> {
+        // Don't offer what the receiver would prune on arrival.
+        let cutoff = expiry_cutoff(old_state_summary.latest.values().map(|k| k.sent_at));
+        let heartbeats: Vec<AuthorizedHeartbeatV1> = self
+            .heartbeats
+            .iter()
+            .filter(|h| h.heartbeat.sent_at >= cutoff)
+            .filter(|h| {
+                old_state_summary
+                    .latest
+                    .get(&h.member_id())
+                    .is_none_or(|held| h.order_key() > *held)
+            })
+            .cloned()
+            .collect();
+        if heartbeats.is_empty() {
+            None
+        } else {
+            Some(PresenceDeltaV1 { heartbeats })
+        }
+    }
+
+    fn apply_delta(
+        &mut self,
+        _parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+        delta: &Option<Self::Delta>,
+    ) -> Result<(), String> {
+        if let Some(delta) = delta {
+            // Silent-drop, as the inbox does: one bad heartbeat must not
+            // poison the rest of the delta.
+            self.heartbeats.extend(
+                delta
+                    .heartbeats
+                    .iter()
+                    .filter(|h| h.verify(parameters).is_ok())
+                    .cloned(),
+            );
+        }
+        self.prune_and_sort();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room_state::member::{AuthorizedMember, Member};
+    use rand::rngs::OsRng;
+
+    struct Room {
+        owner_sk: SigningKey,
+        state: ChatRoomStateV1,
+    }
+
+    impl Room {
+        fn new() -> Self {
+            Self {
+                owner_sk: SigningKey::generate(&mut OsRng),
+                state: ChatRoomStateV1::default(),
+            }
+        }
+
+        fn params(&self) -> PresenceParametersV1 {
+            PresenceParametersV1 {
+                room_owner: self.owner_sk.verifying_key(),
+            }
+        }
+
+        fn invite(&mut self) -> SigningKey {
+            let sk = SigningKey::generate(&mut OsRng);
+            let member = Member {
+                owner_member_id: MemberId::from(&self.owner_sk.verifying_key()),
+                invited_by: MemberId::from(&self.owner_sk.verifying_key()),
+                member_vk: sk.verifying_key(),
+            };
+            self.state
+                .members
+                .members
+                .push(AuthorizedMember::new(member, &self.owner_sk));
+            sk
+        }
+
+        fn beat(&self, sk: &SigningKey, sent_at: u64, typing: bool) -> AuthorizedHeartbeatV1 {
+            AuthorizedHeartbeatV1::new(
+                sk,
+                self.owner_sk.verifying_key(),
+                &self.state,
+                sent_at,
+                typing,
+            )
+            .unwrap()
+        }
+    }
+
+    fn apply(
+        state: &mut PresenceStateV1,
+        params: &PresenceParametersV1,
+        heartbeats: Vec<AuthorizedHeartbeatV1>,
+    ) {
+        let parent = state.clone();
+        state
+            .apply_delta(&parent, params, &Some(PresenceDeltaV1 { heartbeats }))
+            .unwrap();
+    }
+
+    #[test]
+    fn newest_heartbeat_per_member_wins_and_old_ones_are_pruned() {
+        let mut room = Room::new();
+        let alice = room.invite();
+        let bob = room.invite();
+        let carol = room.invite();
+        let params = room.params();
+
+        let mut state = PresenceStateV1::default();
+        apply(
+            &mut state,
+            &params,
+            vec![
+                room.beat(&alice, 1_000, false),
+                room.beat(&bob, 1_010, true),
+            ],
+        );
+        // An older heartbeat from Alice does not replace her newer one.
+        apply(&mut state, &params, vec![room.beat(&alice, 900, true)]);
+        assert_eq!(state.heartbeats.len(), 2);
+        state.verify(&state, &params).unwrap();
+
+        assert_eq!(state.online(1_050).count(), 2);
+        assert_eq!(
+            state.typing(1_015).collect::<Vec<_>>(),
+            vec![MemberId::from(&bob.verifying_key())]
+        );
+        assert_eq!(state.typing(1_050).count(), 0);
+
+        // Bob and Carol moving far ahead age Alice's heartbeat out of the
+        // state; either one alone would not.
+        let later = 1_000 + PRESENCE_TTL_SECS + 1;
+        apply(&mut state, &params, vec![room.beat(&bob, later, false)]);
+        assert_eq!(state.heartbeats.len(), 2);
+        apply(&mut state, &params, vec![room.beat(&carol, later, false)]);
+        assert_eq!(state.heartbeats.len(), 2);
+        assert!(state
+            .heartbeats
+            .iter()
+            .all(|h| h.heartbeat.member_vk != alice.verifying_key()));
+    }
+
+    #[test]
+    fn a_far_future_heartbeat_does_not_evict_anyone() {
+        let mut room = Room::new();
+        let alice = room.invite();
+        let bob = room.invite();
+        let mallory = room.invite();
+        let params = room.params();
+
+        let mut state = PresenceStateV1::default();
+        apply(
+            &mut state,
+            &params,
+            vec![
+                room.beat(&alice, 1_000, false),
+                room.beat(&bob, 1_000, false),
+                room.beat(&mallory, u64::MAX - 1, true),
+            ],
+        );
+        assert_eq!(state.heartbeats.len(), 3);
+        state.verify(&state, &params).unwrap();
+
+        // Honest heartbeats keep landing, and the far-future one never shows
+        // its sender online or typing.
+        apply(&mut state, &params, vec![room.beat(&alice, 1_030, true)]);
+        state.verify(&state, &params).unwrap();
+        let mallory_id = MemberId::from(&mallory.verifying_key());
+        assert_eq!(state.online(1_030).count(), 2);
+        assert!(state.online(1_030).all(|id| id != mallory_id));
+        assert_eq!(
+            state.typing(1_030).collect::<Vec<_>>(),
+            vec![MemberId::from(&alice.verifying_key())]
+        );
+    }
+
+    #[test]
+    fn outsiders_and_other_rooms_are_dropped() {
+        let mut room = Room::new();
+        let alice = room.invite();
+        let params = room.params();
+
+        let mut other = Room::new();
+        let stranger = other.invite();
+
+        let mut state = PresenceStateV1::default();
+        apply(
+            &mut state,
+            &params,
+            vec![
+                other.beat(&stranger, 1_000, false),
+                room.beat(&alice, 1_000, false),
+            ],
+        );
+        assert_eq!(state.heartbeats.len(), 1);
+
+        // A heartbeat re-signed by someone else fails verification.
+        let mut forged = room.beat(&alice, 1_001, true);
+        forged.signature = sign_struct(&forged.heartbeat, &stranger);
+        assert!(forged.verify(&params).is_err());
+    }
+
+    #[test]
+    fn delta_carries_only_what_the_peer_lacks() {
+        let mut room = Room::new();
+        let alice = room.invite();
+        let bob = room.invite();
+        let params = room.params();
+
+        let mut ours = PresenceStateV1::default();
+        apply(
+            &mut ours,
+            &params,
+            vec![
+                room.beat(&alice, 1_000, false),
+                room.beat(&bob, 1_000, false),
+            ],
+        );
+        let mut theirs = PresenceStateV1::default();
+        apply(&mut theirs, &params, vec![room.beat(&alice, 1_030, false)]);
+
+        let delta = ours
+            .delta(&ours, &params, &theirs.summarize(&theirs, &params))
+            .unwrap();
+        assert_eq!(delta.heartbeats.len(), 1);
+        assert_eq!(delta.heartbeats[0].heartbeat.member_vk, bob.verifying_key());
+
+        let parent = theirs.clone();
+        theirs.apply_delta(&parent, &params, &Some(delta)).unwrap();
+        assert!(theirs
+            .delta(&theirs, &params, &theirs.summarize(&theirs, &params))
+            .is_none());
+    }
+}
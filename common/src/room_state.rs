@@ -3,6 +3,8 @@ pub mod configuration;
 pub mod content;
 pub mod direct_messages;
 pub mod dm_body;
+pub mod emoji;
+pub mod group_direct_messages;
 pub mod identity;
 pub mod member;
 pub mod member_info;
@@ -15,16 +17,18 @@ pub mod version;
 use crate::room_state::ban::BansV1;
 use crate::room_state::configuration::AuthorizedConfigurationV1;
 use crate::room_state::direct_messages::DirectMessagesV1;
+use crate::room_state::group_direct_messages::GroupDirectMessagesV1;
 use crate::room_state::member::{MemberId, MembersV1};
 use crate::room_state::member_info::MemberInfoV1;
-use crate::room_state::message::MessagesV1;
+use crate::room_state::message::{MessagesV1, RoomMessageBody};
+use crate::room_state::privacy::{SealedBytes, SecretVersion};
 use crate::room_state::secret::RoomSecretsV1;
 use crate::room_state::upgrade::OptionalUpgradeV1;
 use crate::room_state::version::StateVersion;
 use ed25519_dalek::VerifyingKey;
 use freenet_scaffold_macro::composable;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 #[composable(post_apply_delta = "post_apply_cleanup")]
 #[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
@@ -60,6 +64,12 @@ pub struct ChatRoomStateV1 {
     #[serde(default)]
     pub direct_messages: DirectMessagesV1,
 
+    /// In-room group direct messages among 3 to 8 members. Must come after
+    /// `direct_messages`: a group message is purged through its recipients'
+    /// pairwise purge envelopes.
+    #[serde(default)]
+    pub group_direct_messages: GroupDirectMessagesV1,
+
     /// If this contract has been replaced by a new contract this will contain the new contract address.
     /// This can only be set by the owner.
     pub upgrade: OptionalUpgradeV1,
@@ -71,12 +81,42 @@ pub struct ChatRoomStateV1 {
 }
 
 impl ChatRoomStateV1 {
+    /// Room-secret versions something in this state is encrypted under:
+    /// private messages (including edits and reactions), private nicknames
+    /// and profile fields in `member_info`, and the private room name,
+    /// description and emoji pack.
+    pub fn secret_versions_in_use(&self) -> BTreeSet<SecretVersion> {
+        let display = &self.configuration.configuration.display;
+        let messages =
+            self.recent_messages
+                .messages
+                .iter()
+                .filter_map(|m| match &m.message.content {
+                    RoomMessageBody::Private { secret_version, .. } => Some(*secret_version),
+                    RoomMessageBody::Public { .. } => None,
+                });
+        let member_fields = self.member_info.member_info.iter().flat_map(|info| {
+            let profile = info.member_info.profile.as_ref();
+            std::iter::once(&info.member_info.preferred_nickname)
+                .chain(profile.and_then(|p| p.avatar.as_ref()))
+                .chain(profile.and_then(|p| p.status.as_ref()))
+                .chain(profile.and_then(|p| p.bio.as_ref()))
+                .filter_map(SealedBytes::secret_version)
+        });
+        let room_display = std::iter::once(&display.name)
+            .chain(display.description.as_ref())
+            .chain(self.configuration.configuration.emoji_pack.as_ref())
+            .filter_map(SealedBytes::secret_version);
+        messages.chain(member_fields).chain(room_display).collect()
+    }
+
     /// Post-apply cleanup: prune members who have no recent messages, clean up
-    /// member_info for pruned members, remove orphaned bans, and sweep
-    /// direct messages whose participants are no longer in the room.
+    /// member_info for pruned members, remove orphaned bans, sweep
+    /// direct messages whose participants are no longer in the room, and
+    /// drop room-secret blobs for versions nothing retained still uses.
     ///
     /// Members are kept if they have at least one message in recent_messages,
-    /// are a sender/recipient of a currently-held direct message (see
+    /// are a participant in a currently-held direct or group message (see
     /// [`crate::room_state::direct_messages::DirectMessagesV1::active_participants`]),
     /// or are in the invite chain of someone who qualifies. The owner is
     /// never in the members list (they're implicit via parameters).
@@ -230,7 +270,8 @@ impl ChatRoomStateV1 {
             .iter()
             .map(|m| m.message.author)
             .collect();
-        let dm_participants: HashSet<MemberId> = self.direct_messages.active_participants();
+        let mut dm_participants: HashSet<MemberId> = self.direct_messages.active_participants();
+        dm_participants.extend(self.group_direct_messages.active_participants());
         let current_secret_version = self.secrets.current_version;
         let secret_recipients: HashSet<MemberId> = self
             .secrets
@@ -424,6 +465,18 @@ impl ChatRoomStateV1 {
             &active_member_ids_for_sweep,
             &enforced_banned_ids,
         );
+        self.group_direct_messages.sweep_after_membership_change(
+            owner_id,
+            &active_member_ids_for_sweep,
+            &enforced_banned_ids,
+        );
+
+        // 6a. Garbage-collect room-secret blobs older than the oldest version
+        //     anything retained above is encrypted under. Runs after every
+        //     sweep of messages and member_info so it reads the final set,
+        //     which keeps it idempotent.
+        let oldest_in_use = self.secret_versions_in_use().first().copied();
+        self.secrets.prune_superseded_blobs(oldest_in_use);
 
         // 7. Re-sort for deterministic ordering
         self.members.members.sort_by_key(|m| m.member.id());
@@ -435,6 +488,84 @@ impl ChatRoomStateV1 {
     }
 }
 
+impl ChatRoomStateV1 {
+    /// Names of the top-level fields, in declaration order. The state, its
+    /// summary and its delta share these fields, so debug output for all
+    /// three uses this list rather than spelling the fields out again.
+    pub const FIELD_NAMES: [&'static str; 10] = [
+        "configuration",
+        "bans",
+        "members",
+        "member_info",
+        "secrets",
+        "recent_messages",
+        "direct_messages",
+        "group_direct_messages",
+        "upgrade",
+        "version",
+    ];
+
+    /// The top-level fields of `self` and `other` that are not equal.
+    pub fn differing_fields(&self, other: &Self) -> Vec<&'static str> {
+        select_fields([
+            self.configuration != other.configuration,
+            self.bans != other.bans,
+            self.members != other.members,
+            self.member_info != other.member_info,
+            self.secrets != other.secrets,
+            self.recent_messages != other.recent_messages,
+            self.direct_messages != other.direct_messages,
+            self.group_direct_messages != other.group_direct_messages,
+            self.upgrade != other.upgrade,
+            self.version != other.version,
+        ])
+    }
+}
+
+impl ChatRoomStateV1Summary {
+    /// The top-level fields whose summaries are not equal.
+    pub fn differing_fields(&self, other: &Self) -> Vec<&'static str> {
+        select_fields([
+            self.configuration != other.configuration,
+            self.bans != other.bans,
+            self.members != other.members,
+            self.member_info != other.member_info,
+            self.secrets != other.secrets,
+            self.recent_messages != other.recent_messages,
+            self.direct_messages != other.direct_messages,
+            self.group_direct_messages != other.group_direct_messages,
+            self.upgrade != other.upgrade,
+            self.version != other.version,
+        ])
+    }
+}
+
+impl ChatRoomStateV1Delta {
+    /// The top-level fields this delta carries.
+    pub fn present_fields(&self) -> Vec<&'static str> {
+        select_fields([
+            self.configuration.is_some(),
+            self.bans.is_some(),
+            self.members.is_some(),
+            self.member_info.is_some(),
+            self.secrets.is_some(),
+            self.recent_messages.is_some(),
+            self.direct_messages.is_some(),
+            self.group_direct_messages.is_some(),
+            self.upgrade.is_some(),
+            self.version.is_some(),
+        ])
+    }
+}
+
+fn select_fields(selected: [bool; 10]) -> Vec<&'static str> {
+    ChatRoomStateV1::FIELD_NAMES
+        .into_iter()
+        .zip(selected)
+        .filter_map(|(name, selected)| selected.then_some(name))
+        .collect()
+}
+
 #[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
 pub struct ChatRoomParametersV1 {
     pub owner: VerifyingKey,
@@ -458,6 +589,38 @@ mod tests {
     use std::fmt::Debug;
     use std::time::SystemTime;
 
+    fn serialized_field_names<T: Serialize>(value: &T) -> Vec<String> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(value, &mut bytes).unwrap();
+        let value: ciborium::value::Value = ciborium::de::from_reader(bytes.as_slice()).unwrap();
+        value
+            .into_map()
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key.into_text().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn field_names_match_the_state_summary_and_delta() {
+        let state = ChatRoomStateV1::default();
+        let params = ChatRoomParametersV1::default();
+        let summary = state.summarize(&state, &params);
+        for names in [
+            serialized_field_names(&state),
+            serialized_field_names(&summary),
+            serialized_field_names(&ChatRoomStateV1Delta::default()),
+        ] {
+            assert_eq!(names, ChatRoomStateV1::FIELD_NAMES);
+        }
+
+        let mut other = state.clone();
+        other.version = StateVersion(1);
+        assert_eq!(state.differing_fields(&other), ["version"]);
+        let other_summary = other.summarize(&other, &params);
+        assert_eq!(summary.differing_fields(&other_summary), ["version"]);
+    }
+
     #[test]
     fn test_state() {
         let (state, parameters, owner_signing_key) = create_empty_chat_room_state();
@@ -844,6 +1007,7 @@ mod tests {
             version: 0,
             preferred_nickname: SealedBytes::public("NewUser".to_string().into_bytes()),
             deputies: Vec::new(),
+            profile: None,
         };
         let authorized_info = AuthorizedMemberInfo::new_with_member_key(member_info, &joiner_sk);
 
@@ -1,5 +1,6 @@
+use crate::room_state::emoji::MAX_EMOJI_PACK_SIZE;
 use crate::room_state::member::MemberId;
-use crate::room_state::privacy::{PrivacyMode, RoomDisplayMetadata};
+use crate::room_state::privacy::{PrivacyMode, RoomDisplayMetadata, SealedBytes};
 use crate::room_state::ChatRoomParametersV1;
 use crate::util::truncated_base64;
 use crate::ChatRoomStateV1;
@@ -8,6 +9,7 @@ use freenet_scaffold::util::{fast_hash, FastHash};
 use freenet_scaffold::ComposableState;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::{Duration, SystemTime};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct AuthorizedConfigurationV1 {
@@ -86,6 +88,10 @@ impl ComposableState for AuthorizedConfigurationV1 {
                 || delta.configuration.max_room_name == 0
                 || delta.configuration.max_room_description == 0
                 || delta.configuration.max_direct_messages == Some(0)
+                || delta.configuration.max_avatar_size == Some(0)
+                || delta.configuration.max_status_size == Some(0)
+                || delta.configuration.max_bio_size == Some(0)
+                || delta.configuration.message_ttl == Some(0)
             {
                 return Err("Invalid configuration values".to_string());
             }
@@ -109,12 +115,31 @@ impl ComposableState for AuthorizedConfigurationV1 {
                 }
             }
 
+            if let Some(pack) = &delta.configuration.emoji_pack {
+                if pack.declared_len() > MAX_EMOJI_PACK_SIZE {
+                    return Err(format!(
+                        "Emoji pack declared length {} exceeds {}",
+                        pack.declared_len(),
+                        MAX_EMOJI_PACK_SIZE
+                    ));
+                }
+            }
+
             // In private mode, ensure display metadata is encrypted
             if delta.configuration.privacy_mode == PrivacyMode::Private
                 && delta.configuration.display.name.is_public()
             {
                 return Err("Private room must have encrypted display metadata".to_string());
             }
+            if delta.configuration.privacy_mode == PrivacyMode::Private
+                && delta
+                    .configuration
+                    .emoji_pack
+                    .as_ref()
+                    .is_some_and(|pack| pack.is_public())
+            {
+                return Err("Private room must have an encrypted emoji pack".to_string());
+            }
 
             // If all checks pass, apply the delta
             self.configuration = delta.configuration.clone();
@@ -189,6 +214,13 @@ impl Default for Configuration {
             // gives new rooms the same bound while keeping the serialized
             // default configuration byte-identical to pre-#519 bytes.
             max_direct_messages: None,
+            broadcast_mentions: None,
+            max_avatar_size: None,
+            max_status_size: None,
+            max_bio_size: None,
+            message_ttl: None,
+            emoji_pack: None,
+            space: None,
         }
     }
 }
@@ -205,6 +237,12 @@ impl fmt::Debug for AuthorizedConfigurationV1 {
     }
 }
 
+/// Room settings, signed by the owner as [`AuthorizedConfigurationV1`].
+///
+/// Every field from [`Self::max_direct_messages`] on was added after rooms
+/// already existed. Each is an `Option` skipped when `None` and appended after
+/// the last, so configurations signed before it still verify; the reasoning is
+/// on `max_direct_messages`, and a new field must follow the same pattern.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Configuration {
     pub owner_member_id: MemberId,
@@ -256,6 +294,84 @@ pub struct Configuration {
     /// never sees state carrying this field. Do not weaken that coupling.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_direct_messages: Option<usize>,
+
+    /// Who may send a room-wide `@here` / `@room` mention that other members'
+    /// clients honour. `None` reads as [`BroadcastMentionPolicy::default`]
+    /// through [`Configuration::effective_broadcast_mentions`].
+    ///
+    /// Mentions ride inside the (possibly encrypted) message text, so the
+    /// contract cannot see them: this is enforced by every reading client,
+    /// which renders an unauthorised broadcast but never notifies on it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broadcast_mentions: Option<BroadcastMentionPolicy>,
+
+    /// Bound on the declared length of a member's encoded avatar
+    /// ([`crate::room_state::member_info::MemberProfile::avatar`]). `None`
+    /// reads as [`DEFAULT_MAX_AVATAR_SIZE`] through
+    /// [`Configuration::effective_max_avatar_size`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_avatar_size: Option<usize>,
+
+    /// Bound on the declared length of a member's status line; `None` reads as
+    /// [`DEFAULT_MAX_STATUS_SIZE`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_status_size: Option<usize>,
+
+    /// Bound on the declared length of a member's bio; `None` reads as
+    /// [`DEFAULT_MAX_BIO_SIZE`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bio_size: Option<usize>,
+
+    /// Disappearing messages: how long, in seconds, a room message is kept.
+    /// `None` keeps messages until `max_recent_messages` pushes them out.
+    ///
+    /// The contract has no clock, so `MessagesV1` measures age against the
+    /// NEWEST message it holds: a message is dropped once something at least
+    /// `message_ttl` newer exists. In a quiet room expired messages therefore
+    /// linger in state until the next message arrives; clients hide them by
+    /// wall clock in the meantime through [`Self::message_has_expired`].
+    ///
+    /// This trusts author timestamps, and the failure is worse than the count
+    /// cap's. A member who signs one far-future message expires the room's
+    /// whole history for every peer at once, and every honestly timed message
+    /// after it is dropped on arrival, so the room is mute until an owner or
+    /// upstream member bans them. The ban drops their message and new messages
+    /// land again; the expired history does not come back. The cutoff is not
+    /// bounded per author because any such bound can fall when the count cap
+    /// prunes, and a cutoff that falls makes peers disagree about what has
+    /// expired (see `MessagesV1::retention_horizon_for`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_ttl: Option<u64>,
+
+    /// The room's custom emoji: an encoded
+    /// [`crate::room_state::emoji::EmojiPack`], sealed like the room name
+    /// (so encrypted in a private room). `None` means no custom emoji. The
+    /// contract bounds only its declared length, by [`MAX_EMOJI_PACK_SIZE`];
+    /// readers validate the contents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji_pack: Option<SealedBytes>,
+
+    /// The space this room belongs to, by the space's owner key. The space
+    /// contract lists its rooms too; clients group a room under a space only
+    /// when both sides agree, so a space can't claim a room its owner did not
+    /// opt in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub space: Option<VerifyingKey>,
+}
+
+/// Who may address the whole room with `@here` / `@room`. See
+/// [`Configuration::broadcast_mentions`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BroadcastMentionPolicy {
+    /// Nobody: broadcast mentions render as text but never notify.
+    Nobody,
+    /// Only the room owner.
+    Owner,
+    /// The owner and the members the owner has deputized.
+    #[default]
+    OwnerAndDeputies,
+    /// Any member.
+    Members,
 }
 
 /// Global cap applied to `direct_messages.messages` when a room's
@@ -301,9 +417,11 @@ pub struct Configuration {
 /// # This bound is interim
 ///
 /// The intended long-term fix is moving DMs out of the room contract entirely,
-/// into dedicated per-member contracts. This cap exists to bound the damage
-/// until then, so it is deliberately the simplest correct thing: a count, a
-/// deterministic order, and a horizon. The trim lives wholly inside
+/// into dedicated per-member contracts — now `river_core::inbox` and
+/// `contracts/inbox-contract`, which clients read alongside the room while
+/// senders move over. This cap exists to bound the damage until then, so it
+/// is deliberately the simplest correct thing: a count, a deterministic order,
+/// and a horizon. The trim lives wholly inside
 /// `direct_messages.rs` and `post_apply_cleanup` was not touched, so the
 /// retention logic is a clean deletion later.
 ///
@@ -315,6 +433,17 @@ pub struct Configuration {
 /// it would have to be kept and ignored, not dropped.
 pub const DEFAULT_MAX_DIRECT_MESSAGES: usize = 300;
 
+/// Avatar cap when [`Configuration::max_avatar_size`] is unset. Every member's
+/// profile rides in the room state, so this is sized for a small thumbnail
+/// (a 64×64 WebP fits comfortably), not a photo.
+pub const DEFAULT_MAX_AVATAR_SIZE: usize = 4096;
+
+/// Status-line cap when [`Configuration::max_status_size`] is unset.
+pub const DEFAULT_MAX_STATUS_SIZE: usize = 100;
+
+/// Bio cap when [`Configuration::max_bio_size`] is unset.
+pub const DEFAULT_MAX_BIO_SIZE: usize = 500;
+
 impl Configuration {
     /// The global DM cap in force for this room: the owner's explicit
     /// [`Self::max_direct_messages`], or [`DEFAULT_MAX_DIRECT_MESSAGES`] when
@@ -325,6 +454,46 @@ impl Configuration {
         self.max_direct_messages
             .unwrap_or(DEFAULT_MAX_DIRECT_MESSAGES)
     }
+
+    /// The broadcast-mention policy in force: the owner's explicit
+    /// [`Self::broadcast_mentions`], or the default when unset.
+    pub fn effective_broadcast_mentions(&self) -> BroadcastMentionPolicy {
+        self.broadcast_mentions.unwrap_or_default()
+    }
+
+    /// [`Self::max_avatar_size`], or [`DEFAULT_MAX_AVATAR_SIZE`] when unset.
+    pub fn effective_max_avatar_size(&self) -> usize {
+        self.max_avatar_size.unwrap_or(DEFAULT_MAX_AVATAR_SIZE)
+    }
+
+    /// [`Self::max_status_size`], or [`DEFAULT_MAX_STATUS_SIZE`] when unset.
+    pub fn effective_max_status_size(&self) -> usize {
+        self.max_status_size.unwrap_or(DEFAULT_MAX_STATUS_SIZE)
+    }
+
+    /// [`Self::max_bio_size`], or [`DEFAULT_MAX_BIO_SIZE`] when unset.
+    pub fn effective_max_bio_size(&self) -> usize {
+        self.max_bio_size.unwrap_or(DEFAULT_MAX_BIO_SIZE)
+    }
+
+    /// [`Self::message_ttl`] as a `Duration`, or `None` when messages do not
+    /// expire. A stored zero (which `apply_delta` rejects but `verify` does
+    /// not) reads as unset rather than as "expire everything".
+    pub fn effective_message_ttl(&self) -> Option<Duration> {
+        self.message_ttl
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Whether a message sent at `sent` is past this room's TTL at `now`.
+    /// Client-side only: the contract applies the same age rule against its
+    /// newest message instead of a clock.
+    pub fn message_has_expired(&self, sent: SystemTime, now: SystemTime) -> bool {
+        match self.effective_message_ttl() {
+            Some(ttl) => now.duration_since(sent).is_ok_and(|age| age >= ttl),
+            None => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -603,4 +772,72 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Invalid configuration values");
     }
+
+    #[test]
+    fn emoji_pack_is_bounded_and_sealed_in_private_rooms() {
+        let owner_signing_key = SigningKey::generate(&mut OsRng);
+        let parameters = ChatRoomParametersV1 {
+            owner: owner_signing_key.verifying_key(),
+        };
+        let mut current =
+            AuthorizedConfigurationV1::new(Configuration::default(), &owner_signing_key);
+        let parent_state = ChatRoomStateV1::default();
+        let with_pack = |private: bool, pack: SealedBytes| {
+            let mut next = Configuration::default();
+            next.configuration_version += 1;
+            if private {
+                next.privacy_mode = PrivacyMode::Private;
+                next.display.name = SealedBytes::private(vec![0; 8], [0; 12], 0, 4);
+            }
+            next.emoji_pack = Some(pack);
+            Some(AuthorizedConfigurationV1::new(next, &owner_signing_key))
+        };
+
+        let oversized = SealedBytes::public(vec![0; MAX_EMOJI_PACK_SIZE + 1]);
+        assert!(current
+            .clone()
+            .apply_delta(&parent_state, &parameters, &with_pack(false, oversized))
+            .unwrap_err()
+            .contains("Emoji pack"));
+
+        let plaintext = SealedBytes::public(vec![0; 16]);
+        assert_eq!(
+            current
+                .clone()
+                .apply_delta(
+                    &parent_state,
+                    &parameters,
+                    &with_pack(true, plaintext.clone())
+                )
+                .unwrap_err(),
+            "Private room must have an encrypted emoji pack"
+        );
+
+        let sealed = SealedBytes::private(vec![0; 32], [0; 12], 0, 16);
+        current
+            .clone()
+            .apply_delta(&parent_state, &parameters, &with_pack(true, sealed))
+            .unwrap();
+        current
+            .apply_delta(&parent_state, &parameters, &with_pack(false, plaintext))
+            .unwrap();
+    }
+
+    #[test]
+    fn message_ttl_expires_at_exactly_the_ttl() {
+        let sent = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let mut config = Configuration::default();
+        assert!(!config.message_has_expired(sent, sent + Duration::from_secs(1_000_000)));
+
+        config.message_ttl = Some(60);
+        assert!(!config.message_has_expired(sent, sent + Duration::from_secs(59)));
+        assert!(config.message_has_expired(sent, sent + Duration::from_secs(60)));
+        // A message from the future is not expired.
+        assert!(!config.message_has_expired(sent, sent - Duration::from_secs(1)));
+
+        // A stored zero reads as unset, not "expire everything".
+        config.message_ttl = Some(0);
+        assert_eq!(config.effective_message_ttl(), None);
+        assert!(!config.message_has_expired(sent, sent + Duration::from_secs(60)));
+    }
 }
@@ -1527,7 +1527,7 @@ pub struct DirectMessagesDelta {
 /// Resolve a [`MemberId`] to its `VerifyingKey`. The owner is treated
 /// as an implicit member: their key lives in `parameters.owner`, not
 /// in `parent_state.members`.
-fn resolve_member_vk(
+pub(crate) fn resolve_member_vk(
     id: MemberId,
     owner_id: MemberId,
     parameters: &ChatRoomParametersV1,
@@ -0,0 +1,242 @@
+//! Custom room emoji.
+//!
+//! The room owner can define a pack of `:name:` shortcodes, each a small
+//! image. The pack is CBOR-encoded and carried in
+//! [`crate::room_state::configuration::Configuration::emoji_pack`], so it is
+//! signed with the rest of the configuration and sealed under the room secret
+//! in a private room. The room state has no separate blob storage, so the
+//! images are stored inline and the whole encoded pack is bounded by
+//! [`MAX_EMOJI_PACK_SIZE`].
+//!
+//! The contract only sees the sealed bytes, so name and image checks are
+//! applied by the writer ([`EmojiPack::insert`]) and again by every reader
+//! ([`EmojiPack::decode`]). Messages and reactions refer to custom emoji by
+//! shortcode text, so a client without the pack still shows `:name:`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bound on the declared length of the encoded, sealed pack, enforced by the
+/// contract.
+pub const MAX_EMOJI_PACK_SIZE: usize = 32 * 1024;
+
+/// Bound on a single emoji image. A 32×32 PNG or WebP fits comfortably.
+pub const MAX_EMOJI_IMAGE_SIZE: usize = 2048;
+
+/// Longest allowed shortcode name, without the colons.
+pub const MAX_EMOJI_NAME_LEN: usize = 32;
+
+/// Image types a custom emoji may use. SVG is left out: it can carry script.
+pub const EMOJI_MEDIA_TYPES: &[&str] = &["image/png", "image/gif", "image/webp", "image/jpeg"];
+
+/// A room's custom emoji, by shortcode name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmojiPack {
+    pub emoji: BTreeMap<String, EmojiImage>,
+}
+
+/// One custom emoji image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmojiImage {
+    pub media_type: String,
+    pub data: Vec<u8>,
+}
+
+/// A piece of text split by [`split_shortcodes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmojiSegment<'a> {
+    Text(&'a str),
+    /// A shortcode the pack defines, without its colons.
+    Emoji(&'a str),
+}
+
+/// Whether `name` can be a shortcode: 1 to [`MAX_EMOJI_NAME_LEN`] lowercase
+/// ASCII letters, digits, `_`, `-` or `+`.
+pub fn is_valid_emoji_name(name: &str) -> bool {
+    (1..=MAX_EMOJI_NAME_LEN).contains(&name.len())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"_-+".contains(&b))
+}
+
+/// The name inside a `:name:` shortcode, if `text` is exactly one.
+pub fn parse_shortcode(text: &str) -> Option<&str> {
+    text.strip_prefix(':')
+        .and_then(|rest| rest.strip_suffix(':'))
+        .filter(|name| is_valid_emoji_name(name))
+}
+
+/// Split `text` around the `:name:` shortcodes for which `is_known` holds.
+/// Anything that only looks like a shortcode (`12:30:45`, an unknown
+/// `:name:`) stays text.
+pub fn split_shortcodes<'a>(
+    text: &'a str,
+    is_known: impl Fn(&str) -> bool,
+) -> Vec<EmojiSegment<'a>> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut search = 0;
+    while let Some(open) = text[search..].find(':').map(|i| search + i) {
+        let Some(close) = text[open + 1..].find(':').map(|i| open + 1 + i) else {
+            break;
+        };
+        let name = &text[open + 1..close];
+        if is_known(name) {
+            if open > text_start {
+                segments.push(EmojiSegment::Text(&text[text_start..open]));
+            }
+            segments.push(EmojiSegment::Emoji(name));
+            text_start = close + 1;
+            search = close + 1;
+        } else {
+            // The closing colon may open the next shortcode.
+            search = close;
+        }
+    }
+    if text_start < text.len() {
+        segments.push(EmojiSegment::Text(&text[text_start..]));
+    }
+    segments
+}
+
+impl EmojiPack {
+    pub fn is_empty(&self) -> bool {
+        self.emoji.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EmojiImage> {
+        self.emoji.get(name)
+    }
+
+    /// Add or replace an emoji.
+    pub fn insert(&mut self, name: &str, image: EmojiImage) -> Result<(), String> {
+        if !is_valid_emoji_name(name) {
+            return Err(format!(
+                "Invalid emoji name {name:?}: use 1-{MAX_EMOJI_NAME_LEN} lowercase letters, digits, '_', '-' or '+'"
+            ));
+        }
+        image.check()?;
+        self.emoji.insert(name.to_string(), image);
+        Ok(())
+    }
+
+    /// Remove an emoji, returning whether it was there.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.emoji.remove(name).is_some()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        ciborium::into_writer(self, &mut data).expect("CBOR serialization should not fail");
+        data
+    }
+
+    /// Decode a pack, dropping any entry [`Self::insert`] would have refused.
+    /// Another client wrote these bytes, so they are not trusted.
+    pub fn decode(data: &[u8]) -> Result<Self, String> {
+        let mut pack: Self =
+            ciborium::from_reader(data).map_err(|e| format!("Failed to decode EmojiPack: {e}"))?;
+        pack.emoji
+            .retain(|name, image| is_valid_emoji_name(name) && image.check().is_ok());
+        Ok(pack)
+    }
+
+    /// Split `text` around the shortcodes this pack defines.
+    pub fn segments<'a>(&self, text: &'a str) -> Vec<EmojiSegment<'a>> {
+        split_shortcodes(text, |name| self.emoji.contains_key(name))
+    }
+}
+
+impl EmojiImage {
+    fn check(&self) -> Result<(), String> {
+        if !EMOJI_MEDIA_TYPES.contains(&self.media_type.as_str()) {
+            return Err(format!(
+                "Unsupported emoji image type {:?} (use one of {})",
+                self.media_type,
+                EMOJI_MEDIA_TYPES.join(", ")
+            ));
+        }
+        if self.data.len() > MAX_EMOJI_IMAGE_SIZE {
+            return Err(format!(
+                "Emoji image is {} bytes; the limit is {MAX_EMOJI_IMAGE_SIZE}",
+                self.data.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(data: &[u8]) -> EmojiImage {
+        EmojiImage {
+            media_type: "image/png".to_string(),
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn insert_validates_and_decode_drops_what_insert_refuses() {
+        let mut pack = EmojiPack::default();
+        pack.insert("shipit", png(&[1, 2, 3])).unwrap();
+        pack.insert("+1_ok-2", png(&[4])).unwrap();
+        assert!(pack.insert("ShipIt", png(&[1])).is_err());
+        assert!(pack.insert("", png(&[1])).is_err());
+        assert!(pack.insert(&"a".repeat(33), png(&[1])).is_err());
+        assert!(pack
+            .insert("big", png(&[0; MAX_EMOJI_IMAGE_SIZE + 1]))
+            .is_err());
+        let svg = EmojiImage {
+            media_type: "image/svg+xml".to_string(),
+            data: b"<svg onload=alert(1)/>".to_vec(),
+        };
+        assert!(pack.insert("svg", svg.clone()).is_err());
+
+        assert_eq!(EmojiPack::decode(&pack.encode()).unwrap(), pack);
+
+        // A pack written by a client that skipped the checks.
+        let mut forged = pack.clone();
+        forged.emoji.insert("svg".to_string(), svg);
+        forged.emoji.insert("Bad Name".to_string(), png(&[1]));
+        assert_eq!(EmojiPack::decode(&forged.encode()).unwrap(), pack);
+
+        assert!(pack.remove("shipit"));
+        assert!(!pack.remove("shipit"));
+    }
+
+    #[test]
+    fn segments_split_only_known_shortcodes() {
+        let mut pack = EmojiPack::default();
+        pack.insert("shipit", png(&[1])).unwrap();
+        pack.insert("30", png(&[1])).unwrap();
+
+        assert_eq!(
+            pack.segments("go :shipit: now"),
+            vec![
+                EmojiSegment::Text("go "),
+                EmojiSegment::Emoji("shipit"),
+                EmojiSegment::Text(" now"),
+            ]
+        );
+        assert_eq!(
+            pack.segments(":shipit::shipit:"),
+            vec![EmojiSegment::Emoji("shipit"), EmojiSegment::Emoji("shipit")]
+        );
+        // An unknown name does not swallow the colon that opens a known one.
+        assert_eq!(
+            pack.segments("a:nope:shipit:"),
+            vec![EmojiSegment::Text("a:nope"), EmojiSegment::Emoji("shipit")]
+        );
+        assert_eq!(
+            pack.segments("at 12:31:45"),
+            vec![EmojiSegment::Text("at 12:31:45")]
+        );
+        assert_eq!(pack.segments(""), vec![]);
+
+        assert_eq!(parse_shortcode(":shipit:"), Some("shipit"));
+        assert_eq!(parse_shortcode("👍"), None);
+        assert_eq!(parse_shortcode(":Nope:"), None);
+    }
+}
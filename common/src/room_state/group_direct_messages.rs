@@ -0,0 +1,609 @@
+//! In-room group direct messages: small private threads of 3 to 8 members.
+//!
+//! A pairwise [`DirectMessage`](crate::room_state::direct_messages::DirectMessage)
+//! has exactly one recipient, so a side conversation between several members
+//! would otherwise cost one copy of every message per recipient — against the
+//! same global DM budget every pair shares. A group message is stored ONCE:
+//!
+//! - the body is encrypted a single time under a fresh 256-bit key
+//!   ([`GroupDirectMessage::ciphertext`]), and
+//! - that key is ECIES-sealed separately to each participant
+//!   ([`GroupDirectMessage::sealed_keys`]), the sender included, so the
+//!   sender's other devices can read their own messages without a local
+//!   plaintext cache.
+//!
+//! # Thread identity
+//!
+//! The participants ARE the thread: every message among the same set of
+//! members belongs to the same [`GroupThreadId`], a hash of the sorted member
+//! ids. There is no separately created thread object to authorise, sync, or
+//! go missing. Adding someone means a new set and therefore a new thread.
+//!
+//! # Purges
+//!
+//! Purges reuse each participant's pairwise purge envelope
+//! ([`DirectMessagesV1::purges`](crate::room_state::direct_messages::DirectMessagesV1::purges)):
+//! a recipient purges a group message by listing its [`PurgeToken`] there,
+//! exactly as for a pairwise DM. A purge is per participant — it hides the
+//! message for that participant only — and the message leaves the state once
+//! EVERY recipient (all participants but the sender) has purged it, after
+//! which the tombstones block a stale peer from re-merging it. This field is
+//! declared after `direct_messages` in `ChatRoomStateV1`, so `apply_delta`
+//! sees purges from the same delta.
+//!
+//! # Authorisation
+//!
+//! Same rules as pairwise DMs (see that module): the sender signs canonical
+//! bytes ([`build_group_direct_message_signed_bytes`]) bound to the room; the
+//! sender and every participant must be current members, checked in `verify`;
+//! bans are enforced by the post-merge sweep
+//! ([`GroupDirectMessagesV1::sweep_after_membership_change`]), never in
+//! `verify`. A participant who leaves or is banned takes the thread's held
+//! messages with them, as the other party of a pairwise DM does.
+//!
+//! # Bounds
+//!
+//! - [`MIN_GROUP_DM_PARTICIPANTS`]..=[`MAX_GROUP_DM_PARTICIPANTS`] per message,
+//!   sender included. Two people is a pairwise DM.
+//! - [`MAX_GROUP_DIRECT_MESSAGES`]: room-wide cap, newest kept, with the
+//!   same [`DmRetentionHorizon`] convergence argument as the pairwise global
+//!   cap. There is no per-thread cap; one busy thread can push out another's
+//!   history, which is the price of keeping this sub-state simple until DMs
+//!   move out of the room entirely.
+//! - The body ciphertext shares [`MAX_DM_CIPHERTEXT_BYTES`] with pairwise DMs,
+//!   and each sealed key is exactly [`SEALED_GROUP_KEY_BYTES`].
+
+use crate::room_state::direct_messages::{
+    resolve_member_vk, DmOrderKey, DmRetentionHorizon, PurgeToken, SignatureBytes,
+    MAX_DM_CIPHERTEXT_BYTES,
+};
+use crate::room_state::member::MemberId;
+use crate::room_state::ChatRoomParametersV1;
+use crate::ChatRoomStateV1;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use freenet_scaffold::ComposableState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Domain-separation tag for [`build_group_direct_message_signed_bytes`],
+/// distinct from both pairwise tags so no signature crosses between them.
+pub const DOMAIN_TAG_GROUP_MESSAGE: u8 = b'G';
+
+/// Fewest participants in a group message, sender included.
+pub const MIN_GROUP_DM_PARTICIPANTS: usize = 3;
+
+/// Most participants in a group message, sender included.
+pub const MAX_GROUP_DM_PARTICIPANTS: usize = 8;
+
+/// Room-wide cap on held group messages.
+pub const MAX_GROUP_DIRECT_MESSAGES: usize = 200;
+
+/// Size of one sealed body key: the 32-byte key in a pairwise-DM ECIES
+/// envelope (ephemeral key 32 + nonce 12 + tag 16; `DM_ENVELOPE_OVERHEAD_BYTES`
+/// in `ecies`, which this module can't name without that feature).
+pub const SEALED_GROUP_KEY_BYTES: usize = 32 + 32 + 12 + 16;
+
+/// Identity of a group thread: the first 16 bytes of BLAKE3 over the sorted
+/// participant ids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GroupThreadId(pub [u8; 16]);
+
+impl GroupThreadId {
+    /// The thread for `participants`, in any order and with or without
+    /// duplicates.
+    pub fn for_participants(participants: impl IntoIterator<Item = MemberId>) -> Self {
+        let sorted: BTreeSet<MemberId> = participants.into_iter().collect();
+        let mut hasher = blake3::Hasher::new();
+        for id in &sorted {
+            hasher.update(&id.0 .0.to_le_bytes());
+        }
+        let mut out = [0u8; 16];
+        out.copy_from_slice(&hasher.finalize().as_bytes()[..16]);
+        GroupThreadId(out)
+    }
+}
+
+impl std::fmt::Display for GroupThreadId {
+    /// Lowercase hex, as clients print and accept it.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// Group direct-message sub-state, wired into [`ChatRoomStateV1`] as
+/// `group_direct_messages` with `#[serde(default)]`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GroupDirectMessagesV1 {
+    #[serde(default)]
+    pub messages: Vec<AuthorizedGroupDirectMessage>,
+}
+
+/// A sender-signed group message.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthorizedGroupDirectMessage {
+    pub message: GroupDirectMessage,
+    /// Sender's signature over [`build_group_direct_message_signed_bytes`].
+    pub sender_signature: Signature,
+}
+
+/// The signed payload of a group message.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GroupDirectMessage {
+    pub sender: MemberId,
+    /// Unix timestamp (seconds). Same skew rule as pairwise DMs.
+    pub timestamp: u64,
+    /// The body key sealed to each participant, sender included, strictly
+    /// ascending by `recipient`. The participant set is exactly these
+    /// recipients.
+    pub sealed_keys: Vec<SealedGroupKey>,
+    /// `nonce (12) || AES-256-GCM(body)`, under the key in `sealed_keys`.
+    pub ciphertext: Vec<u8>,
+}
+
+/// One participant's copy of a group message's body key.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SealedGroupKey {
+    pub recipient: MemberId,
+    pub sealed_key: Vec<u8>,
+}
+
+/// Build the bytes the sender signs for an [`AuthorizedGroupDirectMessage`].
+///
+/// ```text
+///     domain_tag                  ( 1 byte, = DOMAIN_TAG_GROUP_MESSAGE)
+///     sender_member_id_le_i64     ( 8 bytes)
+///     room_owner_vk               (32 bytes)
+///     timestamp_le_u64            ( 8 bytes)
+///     key_count_le_u32            ( 4 bytes)
+///     per key: recipient_le_i64 ( 8) + sealed_len_le_u32 (4) + sealed_key
+///     ciphertext_len_le_u32       ( 4 bytes)
+///     ciphertext                  (variable)
+/// ```
+pub fn build_group_direct_message_signed_bytes(
+    message: &GroupDirectMessage,
+    room_owner_vk: &VerifyingKey,
+) -> Result<Vec<u8>, String> {
+    let len_u32 = |len: usize, what: &str| -> Result<u32, String> {
+        len.try_into()
+            .map_err(|_| format!("group DM {} length {} does not fit in u32", what, len))
+    };
+    let mut out = Vec::with_capacity(
+        1 + 8
+            + 32
+            + 8
+            + 4
+            + message.sealed_keys.len() * (12 + SEALED_GROUP_KEY_BYTES)
+            + 4
+            + message.ciphertext.len(),
+    );
+    out.push(DOMAIN_TAG_GROUP_MESSAGE);
+    out.extend_from_slice(&message.sender.0 .0.to_le_bytes());
+    out.extend_from_slice(room_owner_vk.as_bytes());
+    out.extend_from_slice(&message.timestamp.to_le_bytes());
+    out.extend_from_slice(&len_u32(message.sealed_keys.len(), "key count")?.to_le_bytes());
+    for key in &message.sealed_keys {
+        out.extend_from_slice(&key.recipient.0 .0.to_le_bytes());
+        out.extend_from_slice(&len_u32(key.sealed_key.len(), "sealed key")?.to_le_bytes());
+        out.extend_from_slice(&key.sealed_key);
+    }
+    out.extend_from_slice(&len_u32(message.ciphertext.len(), "ciphertext")?.to_le_bytes());
+    out.extend_from_slice(&message.ciphertext);
+    Ok(out)
+}
+
+/// Sign a group message. `message.sender` MUST derive from `sender_sk`.
+pub fn sign_group_direct_message(
+    sender_sk: &SigningKey,
+    room_owner_vk: &VerifyingKey,
+    message: GroupDirectMessage,
+) -> Result<AuthorizedGroupDirectMessage, String> {
+    debug_assert_eq!(
+        message.sender,
+        MemberId::from(&sender_sk.verifying_key()),
+        "sender MemberId must derive from sender_sk"
+    );
+    message.check_shape()?;
+    let bytes = build_group_direct_message_signed_bytes(&message, room_owner_vk)?;
+    Ok(AuthorizedGroupDirectMessage {
+        sender_signature: sender_sk.sign(&bytes),
+        message,
+    })
+}
+
+/// Encrypt `body` once, seal its key to every participant, and sign as the
+/// sender. `others` are the participants besides the sender; duplicates and
+/// the sender's own key are ignored. The group counterpart of
+/// [`compose_direct_message`](crate::room_state::direct_messages::compose_direct_message).
+#[cfg(feature = "ecies-randomized")]
+pub fn compose_group_direct_message(
+    sender_sk: &SigningKey,
+    others: &[VerifyingKey],
+    room_owner_vk: &VerifyingKey,
+    timestamp: u64,
+    now_secs: u64,
+    body: &[u8],
+) -> Result<AuthorizedGroupDirectMessage, String> {
+    crate::room_state::direct_messages::check_dm_future_skew(timestamp, now_secs)?;
+
+    let sender_vk = sender_sk.verifying_key();
+    let mut participants: HashMap<MemberId, VerifyingKey> =
+        others.iter().map(|vk| (MemberId::from(vk), *vk)).collect();
+    participants.insert(MemberId::from(&sender_vk), sender_vk);
+
+    let (key, ciphertext) = crate::ecies::seal_group_dm_body(body);
+    if ciphertext.len() > MAX_DM_CIPHERTEXT_BYTES {
+        return Err(format!(
+            "Group DM body too large: {} bytes encrypted exceeds cap {}",
+            ciphertext.len(),
+            MAX_DM_CIPHERTEXT_BYTES
+        ));
+    }
+    let mut sealed_keys: Vec<SealedGroupKey> = participants
+        .iter()
+        .map(|(id, vk)| SealedGroupKey {
+            recipient: *id,
+            sealed_key: crate::ecies::seal_dm_for_recipient(vk, &key),
+        })
+        .collect();
+    sealed_keys.sort_by_key(|k| k.recipient);
+
+    sign_group_direct_message(
+        sender_sk,
+        room_owner_vk,
+        GroupDirectMessage {
+            sender: MemberId::from(&sender_vk),
+            timestamp,
+            sealed_keys,
+            ciphertext,
+        },
+    )
+}
+
+/// Decrypt a group message's body with a participant's signing key. Does NOT
+/// verify the sender signature.
+#[cfg(feature = "ecies")]
+pub fn open_group_direct_message(
+    participant_sk: &SigningKey,
+    msg: &AuthorizedGroupDirectMessage,
+) -> Result<Vec<u8>, String> {
+    let me = MemberId::from(&participant_sk.verifying_key());
+    let sealed = msg
+        .message
+        .sealed_keys
+        .iter()
+        .find(|k| k.recipient == me)
+        .ok_or_else(|| "Not a participant in this group DM".to_string())?;
+    let key = crate::ecies::unseal_dm_from_sender(participant_sk, &sealed.sealed_key)?;
+    let key: [u8; 32] = key
+        .as_slice()
+        .try_into()
+        .map_err(|_| format!("Group DM key is {} bytes, expected 32", key.len()))?;
+    crate::ecies::open_group_dm_body(&key, &msg.message.ciphertext)
+}
+
+impl GroupDirectMessage {
+    /// The participants, sender included, in ascending order.
+    pub fn participants(&self) -> impl Iterator<Item = MemberId> + '_ {
+        self.sealed_keys.iter().map(|k| k.recipient)
+    }
+
+    /// The participants other than the sender — whose purges remove it.
+    pub fn recipients(&self) -> impl Iterator<Item = MemberId> + '_ {
+        self.participants().filter(move |id| *id != self.sender)
+    }
+
+    pub fn thread_id(&self) -> GroupThreadId {
+        GroupThreadId::for_participants(self.participants())
+    }
+
+    /// Structural rules that need no room context: participant count, strict
+    /// ordering (so each participant has exactly one key and the bytes are
+    /// canonical), sender included, and the size caps.
+    fn check_shape(&self) -> Result<(), String> {
+        let count = self.sealed_keys.len();
+        if !(MIN_GROUP_DM_PARTICIPANTS..=MAX_GROUP_DM_PARTICIPANTS).contains(&count) {
+            return Err(format!(
+                "Group DM has {} participants; must be {}..={}",
+                count, MIN_GROUP_DM_PARTICIPANTS, MAX_GROUP_DM_PARTICIPANTS
+            ));
+        }
+        if !self
+            .sealed_keys
+            .windows(2)
+            .all(|w| w[0].recipient < w[1].recipient)
+        {
+            return Err("Group DM keys must be strictly ascending by recipient".to_string());
+        }
+        if !self.participants().any(|id| id == self.sender) {
+            return Err("Group DM sender must be a participant".to_string());
+        }
+        if let Some(key) = self
+            .sealed_keys
+            .iter()
+            .find(|k| k.sealed_key.len() != SEALED_GROUP_KEY_BYTES)
+        {
+            return Err(format!(
+                "Group DM key for {:?} is {} bytes, expected {}",
+                key.recipient,
+                key.sealed_key.len(),
+                SEALED_GROUP_KEY_BYTES
+            ));
+        }
+        if self.ciphertext.len() > MAX_DM_CIPHERTEXT_BYTES {
+            return Err(format!(
+                "Group DM ciphertext too large: {} > {}",
+                self.ciphertext.len(),
+                MAX_DM_CIPHERTEXT_BYTES
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl AuthorizedGroupDirectMessage {
+    pub fn verify_signature(
+        &self,
+        sender_vk: &VerifyingKey,
+        room_owner_vk: &VerifyingKey,
+    ) -> Result<(), String> {
+        let bytes = build_group_direct_message_signed_bytes(&self.message, room_owner_vk)?;
+        sender_vk
+            .verify(&bytes, &self.sender_signature)
+            .map_err(|e| format!("Invalid group DM sender signature: {}", e))
+    }
+
+    /// The token a participant lists in their purge envelope to purge this.
+    pub fn purge_token(&self) -> PurgeToken {
+        PurgeToken::from_signature(&self.sender_signature)
+    }
+
+    /// Retention order, the same `(timestamp, signature)` key pairwise DMs use.
+    pub fn order_key(&self) -> DmOrderKey {
+        DmOrderKey {
+            timestamp: self.message.timestamp,
+            signature: SignatureBytes(self.sender_signature.to_bytes()),
+        }
+    }
+
+    /// Has `member` purged this message? Clients hide it from them if so.
+    pub fn purged_by(&self, member: MemberId, state: &ChatRoomStateV1) -> bool {
+        let token = self.purge_token();
+        state
+            .direct_messages
+            .purges
+            .iter()
+            .any(|p| p.recipient_id == member && p.state.purged.contains(&token))
+    }
+
+    /// Has every recipient purged this message, so it must leave the state?
+    fn purged_by_all(&self, tombstones: &HashMap<MemberId, HashSet<PurgeToken>>) -> bool {
+        let token = self.purge_token();
+        self.message
+            .recipients()
+            .all(|id| tombstones.get(&id).is_some_and(|t| t.contains(&token)))
+    }
+}
+
+fn tombstones(parent_state: &ChatRoomStateV1) -> HashMap<MemberId, HashSet<PurgeToken>> {
+    parent_state
+        .direct_messages
+        .purges
+        .iter()
+        .map(|p| (p.recipient_id, p.state.purged.iter().copied().collect()))
+        .collect()
+}
+
+impl GroupDirectMessagesV1 {
+    /// Every sender and participant of a held group message, for the
+    /// inactivity-prune exemption in `ChatRoomStateV1::post_apply_cleanup`.
+    pub fn active_participants(&self) -> HashSet<MemberId> {
+        self.messages
+            .iter()
+            .flat_map(|m| m.message.participants())
+            .collect()
+    }
+
+    /// Drop any group message with a participant who is banned or no longer
+    /// a member; the pairwise sweep's counterpart.
+    pub fn sweep_after_membership_change(
+        &mut self,
+        owner_id: MemberId,
+        active_member_ids: &HashSet<MemberId>,
+        banned_ids: &HashSet<MemberId>,
+    ) {
+        let alive = |id: MemberId| -> bool {
+            id == owner_id || (active_member_ids.contains(&id) && !banned_ids.contains(&id))
+        };
+        self.messages
+            .retain(|m| m.message.participants().all(alive));
+    }
+
+    /// The messages of one thread, oldest first.
+    pub fn thread(&self, thread: GroupThreadId) -> Vec<&AuthorizedGroupDirectMessage> {
+        let mut out: Vec<&AuthorizedGroupDirectMessage> = self
+            .messages
+            .iter()
+            .filter(|m| m.message.thread_id() == thread)
+            .collect();
+        out.sort_by_key(|m| m.order_key());
+        out
+    }
+
+    fn retention_horizon(&self) -> DmRetentionHorizon {
+        if self.messages.len() < MAX_GROUP_DIRECT_MESSAGES {
+            return DmRetentionHorizon::Open;
+        }
+        match self.messages.iter().map(|m| m.order_key()).min() {
+            Some(oldest) => DmRetentionHorizon::OldestRetained(oldest),
+            None => DmRetentionHorizon::Open,
+        }
+    }
+
+    /// Dedup, keep the newest [`MAX_GROUP_DIRECT_MESSAGES`], and sort by
+    /// `(thread, order key)`. A pure function of the held set, so peers that
+    /// reach the same union converge.
+    fn enforce_cap_and_sort(&mut self) {
+        let mut seen: HashSet<SignatureBytes> = HashSet::with_capacity(self.messages.len());
+        self.messages
+            .retain(|m| seen.insert(SignatureBytes(m.sender_signature.to_bytes())));
+        if self.messages.len() > MAX_GROUP_DIRECT_MESSAGES {
+            self.messages
+                .sort_by_key(|m| std::cmp::Reverse(m.order_key()));
+            self.messages.truncate(MAX_GROUP_DIRECT_MESSAGES);
+        }
+        self.messages
+            .sort_by_cached_key(|m| (m.message.thread_id(), m.order_key()));
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GroupDirectMessagesSummary {
+    /// Signatures of held messages; a `BTreeSet` for deterministic bytes, as
+    /// in `DirectMessagesSummary`.
+    #[serde(default)]
+    pub message_signatures: BTreeSet<SignatureBytes>,
+    /// The oldest message this peer would still keep once at the cap.
+    #[serde(default)]
+    pub horizon: DmRetentionHorizon,
+}
+
+impl ComposableState for GroupDirectMessagesV1 {
+    type ParentState = ChatRoomStateV1;
+    type Summary = GroupDirectMessagesSummary;
+    type Delta = Vec<AuthorizedGroupDirectMessage>;
+    type Parameters = ChatRoomParametersV1;
+
+    fn verify(
+        &self,
+        parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+    ) -> Result<(), String> {
+        let owner_id = parameters.owner_id();
+        let members_by_id = parent_state.members.members_by_member_id();
+        let tombstones = tombstones(parent_state);
+
+        for msg in &self.messages {
+            msg.message.check_shape()?;
+            if let Some(absent) = msg
+                .message
+                .participants()
+                .find(|id| resolve_member_vk(*id, owner_id, parameters, &members_by_id).is_none())
+            {
+                return Err(format!(
+                    "Group DM participant {:?} is not a current member",
+                    absent
+                ));
+            }
+            // Shape guarantees the sender is a participant, resolved above.
+            let sender_vk =
+                resolve_member_vk(msg.message.sender, owner_id, parameters, &members_by_id)
+                    .ok_or_else(|| "Group DM sender is not a current member".to_string())?;
+            msg.verify_signature(&sender_vk, &parameters.owner)?;
+            if msg.purged_by_all(&tombstones) {
+                return Err(format!(
+                    "Group DM from {:?} is present despite being purged by every recipient",
+                    msg.message.sender
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn summarize(
+        &self,
+        _parent_state: &Self::ParentState,
+        _parameters: &Self::Parameters,
+    ) -> Self::Summary {
+        GroupDirectMessagesSummary {
+            message_signatures: self
+                .messages
+                .iter()
+                .map(|m| SignatureBytes(m.sender_signature.to_bytes()))
+                .collect(),
+            horizon: self.retention_horizon(),
+        }
+    }
+
+    fn delta(
+        &self,
+        _parent_state: &Self::ParentState,
+        _parameters: &Self::Parameters,
+        old_state_summary: &Self::Summary,
+    ) -> Option<Self::Delta> {
+        let delta: Vec<AuthorizedGroupDirectMessage> = self
+            .messages
+            .iter()
+            .filter(|m| {
+                !old_state_summary
+                    .message_signatures
+                    .contains(&SignatureBytes(m.sender_signature.to_bytes()))
+            })
+            // Never offer what the receiver's cap would discard on arrival;
+            // see `DmRetentionHorizon`.
+            .filter(|m| match &old_state_summary.horizon {
+                DmRetentionHorizon::Open => true,
+                DmRetentionHorizon::OldestRetained(oldest) => m.order_key() > *oldest,
+                DmRetentionHorizon::Closed => false,
+            })
+            .cloned()
+            .collect();
+        if delta.is_empty() {
+            None
+        } else {
+            Some(delta)
+        }
+    }
+
+    fn apply_delta(
+        &mut self,
+        parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+        delta: &Option<Self::Delta>,
+    ) -> Result<(), String> {
+        let tombstones = tombstones(parent_state);
+
+        if let Some(delta) = delta {
+            let owner_id = parameters.owner_id();
+            let members_by_id = parent_state.members.members_by_member_id();
+            let mut existing: HashSet<SignatureBytes> = self
+                .messages
+                .iter()
+                .map(|m| SignatureBytes(m.sender_signature.to_bytes()))
+                .collect();
+
+            // Silent-drop anything invalid, as pairwise DMs do, so one bad
+            // message can't poison the merge of the rest.
+            for msg in delta {
+                let sig = SignatureBytes(msg.sender_signature.to_bytes());
+                if existing.contains(&sig) || msg.message.check_shape().is_err() {
+                    continue;
+                }
+                if msg
+                    .message
+                    .participants()
+                    .any(|id| resolve_member_vk(id, owner_id, parameters, &members_by_id).is_none())
+                {
+                    continue;
+                }
+                let Some(sender_vk) =
+                    resolve_member_vk(msg.message.sender, owner_id, parameters, &members_by_id)
+                else {
+                    continue;
+                };
+                if msg.verify_signature(&sender_vk, &parameters.owner).is_err()
+                    || msg.purged_by_all(&tombstones)
+                {
+                    continue;
+                }
+                existing.insert(sig);
+                self.messages.push(msg.clone());
+            }
+        }
+
+        // Runs with or without a delta of our own: a purge that arrived in
+        // `direct_messages` this round can complete a message's removal.
+        self.messages.retain(|m| !m.purged_by_all(&tombstones));
+        self.enforce_cap_and_sort();
+        Ok(())
+    }
+}
@@ -282,6 +282,7 @@ mod tests {
             version: 1,
             preferred_nickname: SealedBytes::public("TestUser".as_bytes().to_vec()),
             deputies: Vec::new(),
+            profile: None,
         };
         let auth_member_info = AuthorizedMemberInfo::new_with_member_key(member_info, &member_b_sk);
 
@@ -662,6 +662,56 @@ impl AuthorizedMember {
         verify_struct(&self.member, &self.signature, inviter_vk)
             .map_err(|e| format!("Invalid signature: {}", e))
     }
+
+    /// Walk from this member up to `room_owner` through `invite_chain` alone,
+    /// with no room state, verifying each membership against its inviter's
+    /// key. `invite_chain` holds the inviters in any order (as carried by an
+    /// inbox membership proof or a message proof). Returns the memberships
+    /// walked, this one first and the one the owner signed last.
+    ///
+    /// Callers that accept a chain from someone else should cap its length
+    /// first.
+    pub fn verify_detached_invite_chain<'a>(
+        &'a self,
+        room_owner: &VerifyingKey,
+        invite_chain: &'a [AuthorizedMember],
+    ) -> Result<Vec<&'a AuthorizedMember>, String> {
+        let owner_id = MemberId::from(room_owner);
+        let by_id: HashMap<MemberId, &AuthorizedMember> =
+            invite_chain.iter().map(|m| (m.member.id(), m)).collect();
+        let mut walked = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = self;
+        loop {
+            let id = current.member.id();
+            if !visited.insert(id) {
+                return Err(format!("The invite chain loops back to {id}"));
+            }
+            if current.member.owner_member_id != owner_id {
+                return Err(format!("{id}'s membership is for a different room"));
+            }
+            let inviter = current.member.invited_by;
+            let inviter_key = if inviter == owner_id {
+                *room_owner
+            } else {
+                by_id
+                    .get(&inviter)
+                    .ok_or_else(|| {
+                        format!("The invite chain is missing {inviter}, who invited {id}")
+                    })?
+                    .member
+                    .member_vk
+            };
+            current
+                .verify_signature(&inviter_key)
+                .map_err(|e| format!("{inviter}'s invitation of {id} does not verify: {e}"))?;
+            walked.push(current);
+            if inviter == owner_id {
+                return Ok(walked);
+            }
+            current = by_id[&inviter];
+        }
+    }
 }
 
 impl Hash for AuthorizedMember {
@@ -1,3 +1,4 @@
+use crate::room_state::configuration::Configuration;
 use crate::room_state::member::MemberId;
 use crate::room_state::privacy::SealedBytes;
 use crate::room_state::ChatRoomParametersV1;
@@ -468,6 +469,18 @@ impl ComposableState for MemberInfoV1 {
                     continue;
                 }
 
+                // Profile fields over the room's limits are skipped for the same
+                // reason. The owner may lower a limit after members set their
+                // profiles, so a stored record is never re-checked; only new
+                // records have to fit. The record is signed whole, so the
+                // contract cannot drop just the oversize field — writers do
+                // that before signing, via `MemberProfile::fitted_to`.
+                if let Some(profile) = &member_info.member_info.profile {
+                    if !profile.fits(&parent_state.configuration.configuration) {
+                        continue;
+                    }
+                }
+
                 // Check if this is the room owner
                 if *member_id == parameters.owner_id() {
                     // If it's the owner, verify against the room owner's key
@@ -594,7 +607,7 @@ pub struct MemberInfo {
     /// subtree (deputy ban authority, #410). Empty for the vast majority of
     /// members.
     ///
-    /// LOAD-BEARING: this MUST be the LAST field and MUST keep BOTH
+    /// LOAD-BEARING: this MUST stay the FOURTH field and MUST keep BOTH
     /// `#[serde(default)]` (so pre-#410 records — which have no `deputies`
     /// key — still deserialize) AND `skip_serializing_if = "Vec::is_empty"`
     /// (so an EMPTY list serializes byte-identically to the old 3-field
@@ -604,8 +617,93 @@ pub struct MemberInfo {
     /// breaking their signature on migration and stranding every existing
     /// room. Never reorder the first three fields. Pinned by
     /// `empty_deputies_serializes_identically_to_legacy_member_info`.
+    ///
+    /// Fields added after this one follow the same rule: appended LAST, with
+    /// `#[serde(default)]` and a `skip_serializing_if` that holds for every
+    /// record written before the field existed.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub deputies: Vec<MemberId>,
+    /// Optional avatar, status line and bio. `None` for members who never set
+    /// one, which keeps their record byte-identical to the pre-profile layout
+    /// (pinned by `absent_profile_serializes_identically_to_legacy_member_info`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<MemberProfile>,
+}
+
+/// Self-described profile fields on a [`MemberInfo`]. Each is sealed like
+/// [`MemberInfo::preferred_nickname`] — plaintext in public rooms, encrypted
+/// with the room secret in private ones — and its declared length is bounded by
+/// the matching `Configuration` limit (`max_avatar_size`, `max_status_size`,
+/// `max_bio_size`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemberProfile {
+    /// An encoded [`Avatar`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<SealedBytes>,
+    /// Short UTF-8 status line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SealedBytes>,
+    /// Short UTF-8 bio.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bio: Option<SealedBytes>,
+}
+
+impl MemberProfile {
+    /// True when no field is set; writers store `None` rather than an empty
+    /// profile so the record stays in its smaller legacy shape.
+    pub fn is_empty(&self) -> bool {
+        self.avatar.is_none() && self.status.is_none() && self.bio.is_none()
+    }
+
+    /// Whether every field's declared length is within `config`'s limits.
+    pub fn fits(&self, config: &Configuration) -> bool {
+        let within = |field: &Option<SealedBytes>, max: usize| {
+            field.as_ref().is_none_or(|f| f.declared_len() <= max)
+        };
+        within(&self.avatar, config.effective_max_avatar_size())
+            && within(&self.status, config.effective_max_status_size())
+            && within(&self.bio, config.effective_max_bio_size())
+    }
+
+    /// This profile with every field over `config`'s limits dropped, or `None`
+    /// if nothing is left. Writers republishing a carried-forward profile pass
+    /// it through here: the owner may have lowered a limit since it was set,
+    /// and `apply_delta` skips a record whose profile no longer fits, which
+    /// would also drop the nickname or deputy change it was carrying.
+    pub fn fitted_to(&self, config: &Configuration) -> Option<MemberProfile> {
+        let keep = |field: &Option<SealedBytes>, max: usize| {
+            field.clone().filter(|f| f.declared_len() <= max)
+        };
+        let fitted = MemberProfile {
+            avatar: keep(&self.avatar, config.effective_max_avatar_size()),
+            status: keep(&self.status, config.effective_max_status_size()),
+            bio: keep(&self.bio, config.effective_max_bio_size()),
+        };
+        Some(fitted).filter(|p| !p.is_empty())
+    }
+}
+
+/// A member's avatar, carried (after CBOR encoding and sealing) in
+/// [`MemberProfile::avatar`]. The room state has no separate blob storage, so
+/// images are stored inline and kept small by `Configuration::max_avatar_size`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Avatar {
+    /// Seed for a client-rendered identicon; costs a few bytes of state.
+    Identicon(u64),
+    /// A small image, e.g. `image/png` or `image/webp`.
+    Image { media_type: String, data: Vec<u8> },
+}
+
+impl Avatar {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        ciborium::into_writer(self, &mut data).expect("CBOR serialization should not fail");
+        data
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, String> {
+        ciborium::from_reader(data).map_err(|e| format!("Failed to decode Avatar: {}", e))
+    }
 }
 
 impl MemberInfo {
@@ -616,6 +714,7 @@ impl MemberInfo {
             version,
             preferred_nickname: SealedBytes::public(nickname.into_bytes()),
             deputies: Vec::new(),
+            profile: None,
         }
     }
 
@@ -638,6 +737,7 @@ impl MemberInfo {
                 declared_len,
             ),
             deputies: Vec::new(),
+            profile: None,
         }
     }
 }
@@ -949,6 +1049,7 @@ mod tests {
             version: 7,
             preferred_nickname: nickname.clone(),
             deputies: Vec::new(),
+            profile: None,
         };
 
         // (a) direct byte-identity of the ciborium serialization.
@@ -978,6 +1079,7 @@ mod tests {
             version: 7,
             preferred_nickname: nickname,
             deputies: vec![member_id],
+            profile: None,
         };
         let mut with_deputy_bytes = Vec::new();
         ciborium::ser::into_writer(&with_deputy, &mut with_deputy_bytes).unwrap();
@@ -987,6 +1089,182 @@ mod tests {
         );
     }
 
+    /// Same guarantee for `profile`: a record that never set one keeps the
+    /// bytes (and so the signature) it had before the field existed.
+    #[test]
+    fn absent_profile_serializes_identically_to_legacy_member_info() {
+        use crate::util::{sign_struct, verify_struct};
+
+        #[derive(Serialize)]
+        struct OldMemberInfo {
+            member_id: MemberId,
+            version: u32,
+            preferred_nickname: SealedBytes,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            deputies: Vec<MemberId>,
+        }
+
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let member_id: MemberId = signing_key.verifying_key().into();
+        let nickname = SealedBytes::public(b"LegacyNick".to_vec());
+
+        for deputies in [Vec::new(), vec![member_id]] {
+            let old = OldMemberInfo {
+                member_id,
+                version: 3,
+                preferred_nickname: nickname.clone(),
+                deputies: deputies.clone(),
+            };
+            let new = MemberInfo {
+                member_id,
+                version: 3,
+                preferred_nickname: nickname.clone(),
+                deputies,
+                profile: None,
+            };
+
+            let mut old_bytes = Vec::new();
+            ciborium::ser::into_writer(&old, &mut old_bytes).unwrap();
+            let mut new_bytes = Vec::new();
+            ciborium::ser::into_writer(&new, &mut new_bytes).unwrap();
+            assert_eq!(old_bytes, new_bytes);
+
+            let signature = sign_struct(&old, &signing_key);
+            assert!(verify_struct(&new, &signature, &signing_key.verifying_key()).is_ok());
+
+            let with_profile = MemberInfo {
+                profile: Some(MemberProfile {
+                    status: Some(SealedBytes::public(b"away".to_vec())),
+                    ..Default::default()
+                }),
+                ..new
+            };
+            let mut profile_bytes = Vec::new();
+            ciborium::ser::into_writer(&with_profile, &mut profile_bytes).unwrap();
+            assert_ne!(old_bytes, profile_bytes);
+        }
+    }
+
+    #[test]
+    fn apply_delta_skips_profiles_over_the_configured_limits() {
+        let owner_signing_key = SigningKey::generate(&mut OsRng);
+        let owner_id: MemberId = owner_signing_key.verifying_key().into();
+        let parameters = ChatRoomParametersV1 {
+            owner: owner_signing_key.verifying_key(),
+        };
+        let mut parent_state = ChatRoomStateV1::default();
+        parent_state.configuration.configuration.owner_member_id = owner_id;
+        parent_state.configuration.configuration.max_bio_size = Some(10);
+
+        let with_bio = |version: u32, bio: &str| {
+            let mut info = MemberInfo::new_public(owner_id, version, "Owner".to_string());
+            info.profile = Some(MemberProfile {
+                avatar: Some(SealedBytes::public(Avatar::Identicon(7).encode())),
+                bio: Some(SealedBytes::public(bio.as_bytes().to_vec())),
+                ..Default::default()
+            });
+            AuthorizedMemberInfo::new(info, &owner_signing_key)
+        };
+
+        let mut member_info_v1 = MemberInfoV1::default();
+        let fits = with_bio(1, "short bio");
+        member_info_v1
+            .apply_delta(&parent_state, &parameters, &Some(vec![fits.clone()]))
+            .unwrap();
+        assert_eq!(member_info_v1.member_info, vec![fits.clone()]);
+
+        // A newer record whose bio is over the limit is skipped, not an error,
+        // and the stored record stays in place.
+        member_info_v1
+            .apply_delta(
+                &parent_state,
+                &parameters,
+                &Some(vec![with_bio(2, "a much longer bio")]),
+            )
+            .unwrap();
+        assert_eq!(member_info_v1.member_info, vec![fits]);
+    }
+
+    /// Lowering a limit under an existing profile must not lock the member
+    /// out: a nickname republished with the profile fitted to the new limits
+    /// lands, keeping the fields that still fit.
+    #[test]
+    fn nickname_republished_after_a_limit_is_lowered_lands() {
+        let owner_signing_key = SigningKey::generate(&mut OsRng);
+        let owner_id: MemberId = owner_signing_key.verifying_key().into();
+        let parameters = ChatRoomParametersV1 {
+            owner: owner_signing_key.verifying_key(),
+        };
+        let mut parent_state = ChatRoomStateV1::default();
+        parent_state.configuration.configuration.owner_member_id = owner_id;
+        parent_state.configuration.configuration.max_bio_size = Some(20);
+
+        let avatar = SealedBytes::public(Avatar::Identicon(7).encode());
+        let mut info = MemberInfo::new_public(owner_id, 1, "Owner".to_string());
+        info.profile = Some(MemberProfile {
+            avatar: Some(avatar.clone()),
+            bio: Some(SealedBytes::public(b"a ten byte".to_vec())),
+            ..Default::default()
+        });
+        let mut member_info_v1 = MemberInfoV1::default();
+        member_info_v1
+            .apply_delta(
+                &parent_state,
+                &parameters,
+                &Some(vec![AuthorizedMemberInfo::new(
+                    info.clone(),
+                    &owner_signing_key,
+                )]),
+            )
+            .unwrap();
+
+        parent_state.configuration.configuration.max_bio_size = Some(5);
+        let config = &parent_state.configuration.configuration;
+        let renamed = |profile| {
+            let mut renamed = MemberInfo::new_public(owner_id, 2, "Renamed".to_string());
+            renamed.profile = profile;
+            AuthorizedMemberInfo::new(renamed, &owner_signing_key)
+        };
+
+        // Carried forward as-is, the bio no longer fits and the rename is lost.
+        member_info_v1
+            .apply_delta(
+                &parent_state,
+                &parameters,
+                &Some(vec![renamed(info.profile.clone())]),
+            )
+            .unwrap();
+        assert_eq!(member_info_v1.member_info[0].member_info.version, 1);
+
+        let fitted = info.profile.as_ref().and_then(|p| p.fitted_to(config));
+        member_info_v1
+            .apply_delta(&parent_state, &parameters, &Some(vec![renamed(fitted)]))
+            .unwrap();
+        let stored = &member_info_v1.member_info[0].member_info;
+        assert_eq!(stored.version, 2);
+        assert_eq!(
+            stored.profile,
+            Some(MemberProfile {
+                avatar: Some(avatar),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn avatar_round_trips_through_cbor() {
+        for avatar in [
+            Avatar::Identicon(u64::MAX),
+            Avatar::Image {
+                media_type: "image/png".to_string(),
+                data: vec![0x89, b'P', b'N', b'G'],
+            },
+        ] {
+            assert_eq!(Avatar::decode(&avatar.encode()).unwrap(), avatar);
+        }
+        assert!(Avatar::decode(b"not cbor").is_err());
+    }
+
     #[test]
     fn test_member_info_v1_default() {
         let default_member_info = MemberInfoV1::default();
@@ -1,3 +1,4 @@
+use crate::room_state::configuration::Configuration;
 use crate::room_state::member::MemberId;
 use crate::room_state::privacy::{PrivacyMode, SecretVersion};
 use crate::room_state::ChatRoomParametersV1;
@@ -10,7 +11,7 @@ use freenet_scaffold::ComposableState;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeSet, HashMap};
 use std::fmt;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 /// Ciphertext overhead added by AES-256-GCM (`encrypt_with_symmetric_key`):
 /// the 16-byte authentication tag appended to the plaintext. The nonce lives
@@ -151,10 +152,10 @@ impl ComposableState for MessagesV1 {
     }
 
     /// NOTE: unlike every other field in `ChatRoomStateV1`, this `summarize`
-    /// READS `parent_state` (for `max_recent_messages`, which sizes the
-    /// retention horizon). Callers must pass the SUMMARIZING peer's own state,
-    /// which is what `summarize_state` in the contract and every `merge` call
-    /// site do. Passing a cheap `ChatRoomStateV1::default()` sentinel — as
+    /// READS `parent_state` (for `max_recent_messages` and `message_ttl`,
+    /// which size the retention horizon). Callers must pass the SUMMARIZING
+    /// peer's own state, which is what `summarize_state` in the contract and
+    /// every `merge` call site do. Passing a cheap `ChatRoomStateV1::default()` sentinel — as
     /// `room_synchronizer` used to — reads the DEFAULT cap instead of the
     /// room's, understating the horizon and re-opening the resend loop.
     /// Pinned by `merge_uses_room_state_as_parent_so_horizon_is_correct`.
@@ -165,8 +166,7 @@ impl ComposableState for MessagesV1 {
     ) -> Self::Summary {
         MessagesSummary {
             message_ids: self.messages.iter().map(|m| m.id()).collect(),
-            horizon: self
-                .retention_horizon(parent_state.configuration.configuration.max_recent_messages),
+            horizon: self.retention_horizon_for(&parent_state.configuration.configuration),
         }
     }
 
@@ -210,6 +210,10 @@ impl ComposableState for MessagesV1 {
         delta: &Option<Self::Delta>,
     ) -> Result<(), String> {
         let max_recent_messages = parent_state.configuration.configuration.max_recent_messages;
+        let message_ttl = parent_state
+            .configuration
+            .configuration
+            .effective_message_ttl();
         let max_message_size = parent_state.configuration.configuration.max_message_size;
         let privacy_mode = &parent_state.configuration.configuration.privacy_mode;
 
@@ -327,6 +331,19 @@ impl ComposableState for MessagesV1 {
                 .then_with(|| a.id().cmp(&b.id()))
         });
 
+        // Drop messages older than the room's TTL, measured from the newest
+        // message held (the contract has no clock). The newest message is never
+        // dropped, so re-running this removes nothing further. Covered by the
+        // retention horizon like the count cap below: see `expiry_key`. One
+        // far-future message therefore expires everything else; see
+        // `Configuration::message_ttl` for why that is not bounded here.
+        if let Some(ttl) = message_ttl {
+            if let Some(cutoff) = self.expiry_cutoff(ttl) {
+                let expired = self.messages.partition_point(|m| m.message.time <= cutoff);
+                self.messages.drain(0..expired);
+            }
+        }
+
         // Remove oldest messages if there are too many.
         //
         // This removal is what makes the merge non-monotonic, so it MUST stay
@@ -376,6 +393,52 @@ impl MessagesV1 {
         }
     }
 
+    /// [`Self::retention_horizon`] extended with the room's message TTL: the
+    /// higher of the count horizon and [`Self::expiry_key`]. This is what
+    /// `summarize` publishes.
+    ///
+    /// Both halves only ever under-state what the peer will discard, so their
+    /// maximum does too. The TTL half is exact for the peer's current newest
+    /// message, and a merge can only raise that message, never lower it.
+    pub fn retention_horizon_for(&self, config: &Configuration) -> RetentionHorizon {
+        let by_count = self.retention_horizon(config.max_recent_messages);
+        let Some(expiry) = config
+            .effective_message_ttl()
+            .and_then(|ttl| self.expiry_key(ttl))
+        else {
+            return by_count;
+        };
+        match by_count {
+            RetentionHorizon::Closed => RetentionHorizon::Closed,
+            RetentionHorizon::Open => RetentionHorizon::OldestRetained(expiry),
+            RetentionHorizon::OldestRetained(oldest) => {
+                RetentionHorizon::OldestRetained(oldest.max(expiry))
+            }
+        }
+    }
+
+    /// The newest message time minus `ttl`: `apply_delta` drops every message
+    /// at or before it. `None` when there are no messages, or the subtraction
+    /// would fall before the epoch (nothing can be that old).
+    fn expiry_cutoff(&self, ttl: Duration) -> Option<SystemTime> {
+        self.messages
+            .iter()
+            .map(|m| m.message.time)
+            .max()?
+            .checked_sub(ttl)
+    }
+
+    /// [`Self::expiry_cutoff`] as an order key. Every message at the cutoff
+    /// time is dropped whatever its id, so pairing the cutoff with the LARGEST
+    /// possible id makes "strictly above this key" mean exactly "survives the
+    /// TTL" — the horizon neither over- nor under-states.
+    fn expiry_key(&self, ttl: Duration) -> Option<MessageOrderKey> {
+        self.expiry_cutoff(ttl).map(|time| MessageOrderKey {
+            time,
+            id: MessageId(FastHash(i64::MAX)),
+        })
+    }
+
     /// Rebuild the computed actions state by scanning all action messages.
     ///
     /// This method only processes PUBLIC action messages. For private rooms,
@@ -550,6 +613,18 @@ impl MessagesV1 {
             !m.message.content.is_action() && !self.actions_state.deleted.contains(&m.id())
         })
     }
+
+    /// [`Self::display_messages`] without those past the room's message TTL at
+    /// `now`, so a disappearing message leaves the screen on time even while a
+    /// quiet room still holds it.
+    pub fn unexpired_display_messages<'a>(
+        &'a self,
+        config: &'a Configuration,
+        now: SystemTime,
+    ) -> impl Iterator<Item = &'a AuthorizedMessageV1> + 'a {
+        self.display_messages()
+            .filter(move |m| !config.message_has_expired(m.message.time, now))
+    }
 }
 
 /// Message body that can be either public or private (encrypted).
@@ -59,7 +59,7 @@ impl ComposableState for RoomSecretsV1 {
 
     fn summarize(
         &self,
-        _parent_state: &Self::ParentState,
+        parent_state: &Self::ParentState,
         _parameters: &Self::Parameters,
     ) -> Self::Summary {
         let version_ids: BTreeSet<SecretVersion> =
@@ -71,10 +71,12 @@ impl ComposableState for RoomSecretsV1 {
             .map(|s| (s.secret.secret_version, s.secret.member_id))
             .collect();
 
+        let oldest_in_use = parent_state.secret_versions_in_use().first().copied();
         SecretsSummary {
             current_version: self.current_version,
             version_ids,
             member_secrets,
+            blobs_from: self.blob_horizon(oldest_in_use),
         }
     }
 
@@ -95,9 +97,10 @@ impl ComposableState for RoomSecretsV1 {
             .encrypted_secrets
             .iter()
             .filter(|s| {
-                !old_state_summary
-                    .member_secrets
-                    .contains(&(s.secret.secret_version, s.secret.member_id))
+                s.secret.secret_version >= old_state_summary.blobs_from
+                    && !old_state_summary
+                        .member_secrets
+                        .contains(&(s.secret.secret_version, s.secret.member_id))
             })
             .cloned()
             .collect();
@@ -258,6 +261,13 @@ pub struct SecretsSummary {
     // and freenet/freenet-core#4857.
     pub version_ids: BTreeSet<SecretVersion>,
     pub member_secrets: BTreeSet<(SecretVersion, MemberId)>,
+    /// Oldest version whose blobs this peer keeps (see
+    /// [`RoomSecretsV1::prune_superseded_blobs`]). Older blobs are missing
+    /// from `member_secrets` because they were pruned, not because they are
+    /// wanted, so `delta` does not send them. Absent from a peer that
+    /// predates pruning, which still gets everything.
+    #[serde(default)]
+    pub blobs_from: SecretVersion,
 }
 
 /// Delta for room secrets state
@@ -490,6 +500,29 @@ pub fn build_rotation_encrypted_secrets(
 }
 
 impl RoomSecretsV1 {
+    /// Drop the encrypted blobs of every version older than `oldest_in_use`,
+    /// the oldest version anything retained in the room is encrypted under
+    /// (`None` when nothing is). Blobs at `current_version` are never
+    /// dropped, so [`Self::has_complete_distribution`] is unaffected, and
+    /// version records are kept: `MessagesV1::apply_delta` checks a late
+    /// message's `secret_version` against them.
+    ///
+    /// Each rotation adds one blob per member, so without this a long-lived
+    /// private room carries every secret it ever had for every member.
+    /// Called from `post_apply_cleanup`; a pure function of the converged
+    /// state, so peers prune identically. The horizon is also published in
+    /// [`SecretsSummary::blobs_from`] so a peer that still holds the dropped
+    /// blobs does not keep sending them back.
+    pub fn prune_superseded_blobs(&mut self, oldest_in_use: Option<SecretVersion>) {
+        let keep_from = self.blob_horizon(oldest_in_use);
+        self.encrypted_secrets
+            .retain(|s| s.secret.secret_version >= keep_from);
+    }
+
+    fn blob_horizon(&self, oldest_in_use: Option<SecretVersion>) -> SecretVersion {
+        oldest_in_use.map_or(self.current_version, |v| v.min(self.current_version))
+    }
+
     /// Check if all current members have encrypted blobs for the current version
     pub fn has_complete_distribution(
         &self,
@@ -787,6 +820,7 @@ mod tests {
             current_version: 0,
             version_ids: BTreeSet::new(),
             member_secrets: BTreeSet::new(),
+            blobs_from: 0,
         };
 
         let delta = secrets.delta(&state, &params, &old_summary).unwrap();
@@ -817,6 +851,7 @@ mod tests {
             current_version: 1,
             version_ids: BTreeSet::new(),
             member_secrets: BTreeSet::new(),
+            blobs_from: 0,
         };
         old_summary.version_ids.insert(1);
         old_summary.member_secrets.insert((1, owner_id));
@@ -1106,6 +1141,44 @@ mod tests {
         assert!(!secrets.has_complete_distribution(&members));
     }
 
+    #[test]
+    fn test_prune_superseded_blobs_never_touches_current_version() {
+        let (_state, params, owner_sk) = create_test_state_and_params();
+        let owner_id = params.owner_id();
+
+        let mut secrets = RoomSecretsV1 {
+            current_version: 2,
+            ..Default::default()
+        };
+        for version in 0..=2 {
+            secrets
+                .versions
+                .push(create_version_record(version, &owner_sk));
+            secrets
+                .encrypted_secrets
+                .push(create_encrypted_secret(owner_id, version, &owner_sk));
+        }
+        let blob_versions = |secrets: &RoomSecretsV1| -> Vec<SecretVersion> {
+            secrets
+                .encrypted_secrets
+                .iter()
+                .map(|s| s.secret.secret_version)
+                .collect()
+        };
+
+        let mut pruned = secrets.clone();
+        pruned.prune_superseded_blobs(Some(1));
+        assert_eq!(blob_versions(&pruned), vec![1, 2]);
+        assert_eq!(pruned.versions.len(), 3, "version records are kept");
+
+        // Nothing in use, or only versions newer than current: keep current.
+        for oldest_in_use in [None, Some(7)] {
+            let mut pruned = secrets.clone();
+            pruned.prune_superseded_blobs(oldest_in_use);
+            assert_eq!(blob_versions(&pruned), vec![2]);
+        }
+    }
+
     /// Regression test: apply_delta should succeed when the delta contains
     /// encrypted secrets for a member that was simultaneously removed from
     /// parent_state.members (e.g. ban or max_members eviction).
@@ -0,0 +1,481 @@
+//! Deterministic multi-peer convergence simulator for [`ChatRoomStateV1`].
+//!
+//! N virtual peers each hold a room state. Changes are authored on one peer
+//! and spread by the same two operations the room contract performs: a delta
+//! computed against the receiver's summary (`get_state_delta` then
+//! `update_state` with `UpdateData::Delta`), or a full-state merge
+//! (`UpdateData::State`). In between, the network reorders, duplicates and
+//! drops messages and partitions the peers, and each apply reruns
+//! `post_apply_cleanup` a varying number of times, as Freenet does. An update
+//! the state rejects leaves the peer untouched, as a failed `update_state`
+//! does.
+//!
+//! Every run is a [`Trace`]: the starting state plus the list of concrete
+//! [`Step`]s taken, with all random choices already resolved. [`replay`]
+//! re-executes a trace exactly, so a divergence found by a random run can be
+//! saved ([`Trace::to_bytes`]) and replayed under a debugger or by
+//! `riverctl debug replay`.
+//!
+//! What is not modelled: the contract's injection of message authors'
+//! member entries into outgoing deltas, `verify` on full-state PUTs, and
+//! anything timing-dependent beyond the states' own timestamps.
+//!
+//! ```ignore
+//! let mut sim = Simulation::new(owner_vk, initial, SimConfig { peers: 4, seed, ..Default::default() });
+//! sim.author(0, delta_from_alice);
+//! sim.author(2, delta_from_bob);
+//! sim.run_random(200);
+//! let report = sim.settle().expect("peers must converge");
+//! ```
+
+use crate::room_state::{ChatRoomParametersV1, ChatRoomStateV1, ChatRoomStateV1Delta};
+use ed25519_dalek::VerifyingKey;
+use freenet_scaffold::ComposableState;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Fault rates and sizes for a random run. Chances are in `[0, 1]`.
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    pub peers: usize,
+    pub seed: u64,
+    /// Chance that a send ships the full state rather than a delta.
+    pub full_state_chance: f64,
+    /// Chance per step that an in-flight message is delivered twice.
+    pub duplicate_chance: f64,
+    /// Chance per step that an in-flight message is lost.
+    pub drop_chance: f64,
+    /// Chance per step that the network splits in two, or heals if split.
+    pub partition_chance: f64,
+    /// Deliver in-flight messages in random order rather than first-in,
+    /// first-out.
+    pub reorder: bool,
+    /// `apply_delta` runs `post_apply_cleanup` once; each apply then reruns
+    /// it between zero and this many more times.
+    pub max_extra_cleanups: u32,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        SimConfig {
+            peers: 3,
+            seed: 0,
+            full_state_chance: 0.2,
+            duplicate_chance: 0.05,
+            drop_chance: 0.05,
+            partition_chance: 0.02,
+            reorder: true,
+            max_extra_cleanups: 2,
+        }
+    }
+}
+
+/// One resolved action of a run. Peer and queue positions are indices.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum Step {
+    /// A client on `peer` applies `delta` locally.
+    Author {
+        peer: u32,
+        delta: Box<ChatRoomStateV1Delta>,
+        cleanups: u32,
+    },
+    /// `from` sends `to` what `to`'s summary says it lacks, or its whole
+    /// state. Computed now, delivered later; lost if a partition separates
+    /// them.
+    Send {
+        from: u32,
+        to: u32,
+        full_state: bool,
+    },
+    /// Deliver the message at `index` in the in-flight queue.
+    Deliver {
+        index: u32,
+        cleanups: u32,
+    },
+    Duplicate {
+        index: u32,
+    },
+    Drop {
+        index: u32,
+    },
+    /// Peers in different groups can no longer reach each other.
+    Partition {
+        groups: Vec<u32>,
+    },
+    Heal,
+}
+
+/// A complete, replayable run.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Trace {
+    pub owner: VerifyingKey,
+    pub peers: u32,
+    pub initial: ChatRoomStateV1,
+    pub steps: Vec<Step>,
+}
+
+impl Trace {
+    /// CBOR, the encoding the contract uses for the states inside.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(self, &mut bytes).expect("a trace always serializes");
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        ciborium::de::from_reader(bytes).map_err(|e| format!("not a simulation trace: {e}"))
+    }
+}
+
+/// The converged outcome of a run.
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// The state every peer holds.
+    pub state: ChatRoomStateV1,
+    /// Its CBOR encoding, identical on every peer.
+    pub state_bytes: Vec<u8>,
+    /// Updates a peer rejected (and so did not apply) during the run.
+    pub rejected_updates: usize,
+    /// Anti-entropy rounds `settle` needed before nothing changed.
+    pub settle_rounds: usize,
+}
+
+/// Two peers still disagree after settling.
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    pub peers: (usize, usize),
+    /// The top-level fields that differ between them.
+    pub fields: Vec<&'static str>,
+    /// The run that led here, for [`replay`].
+    pub trace: Trace,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "peers {} and {} did not converge after {} steps; they differ in: {}",
+            self.peers.0,
+            self.peers.1,
+            self.trace.steps.len(),
+            self.fields.join(", ")
+        )
+    }
+}
+
+enum Payload {
+    Delta(Box<ChatRoomStateV1Delta>),
+    State(Box<ChatRoomStateV1>),
+}
+
+struct Envelope {
+    to: usize,
+    payload: Payload,
+}
+
+/// A network of peers holding one room. See the module docs.
+pub struct Simulation {
+    config: SimConfig,
+    parameters: ChatRoomParametersV1,
+    peers: Vec<ChatRoomStateV1>,
+    in_flight: VecDeque<Envelope>,
+    /// Partition group of each peer; all zero when the network is whole.
+    groups: Vec<u32>,
+    rng: SplitMix64,
+    trace: Trace,
+    rejected_updates: usize,
+}
+
+impl Simulation {
+    /// `config.peers` peers, each starting from `initial`.
+    pub fn new(owner: VerifyingKey, initial: ChatRoomStateV1, config: SimConfig) -> Self {
+        assert!(config.peers >= 2, "a simulation needs at least two peers");
+        Simulation {
+            parameters: ChatRoomParametersV1 { owner },
+            peers: vec![initial.clone(); config.peers],
+            in_flight: VecDeque::new(),
+            groups: vec![0; config.peers],
+            rng: SplitMix64(config.seed),
+            trace: Trace {
+                owner,
+                peers: config.peers as u32,
+                initial,
+                steps: Vec::new(),
+            },
+            rejected_updates: 0,
+            config,
+        }
+    }
+
+    pub fn peer(&self, index: usize) -> &ChatRoomStateV1 {
+        &self.peers[index]
+    }
+
+    /// The run so far.
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// A client on `peer` applies `delta` locally; the network spreads it
+    /// during later steps.
+    pub fn author(&mut self, peer: usize, delta: ChatRoomStateV1Delta) {
+        let cleanups = self.random_cleanups();
+        self.apply(Step::Author {
+            peer: peer as u32,
+            delta: Box::new(delta),
+            cleanups,
+        });
+    }
+
+    /// Take `steps` random network steps under the configured faults.
+    pub fn run_random(&mut self, steps: usize) {
+        for _ in 0..steps {
+            let step = self.random_step();
+            self.apply(step);
+        }
+    }
+
+    /// Heal any partition, deliver everything still in flight, then run
+    /// full anti-entropy (every peer sends every other what it lacks) until
+    /// a round changes nothing, and require every peer's encoded state to be
+    /// byte-identical.
+    pub fn settle(&mut self) -> Result<Report, Box<Divergence>> {
+        self.groups.fill(0);
+        while let Some(envelope) = self.in_flight.pop_front() {
+            self.deliver(envelope, 0);
+        }
+        // Each round can carry a change one hop further, so twice the peer
+        // count is ample; anything still moving after that is itself a bug.
+        let max_rounds = 2 * self.peers.len() + 2;
+        let mut settle_rounds = 0;
+        for round in 1..=max_rounds {
+            settle_rounds = round;
+            let before: Vec<Vec<u8>> = self.peers.iter().map(encode).collect();
+            for from in 0..self.peers.len() {
+                for to in 0..self.peers.len() {
+                    if from != to {
+                        if let Some(envelope) = self.envelope(from, to, false) {
+                            self.deliver(envelope, 0);
+                        }
+                    }
+                }
+            }
+            if self.peers.iter().map(encode).eq(before) {
+                break;
+            }
+        }
+
+        let first = encode(&self.peers[0]);
+        for (index, peer) in self.peers.iter().enumerate().skip(1) {
+            if encode(peer) != first {
+                return Err(Box::new(Divergence {
+                    peers: (0, index),
+                    fields: self.peers[0].differing_fields(peer),
+                    trace: self.trace.clone(),
+                }));
+            }
+        }
+        Ok(Report {
+            state: self.peers[0].clone(),
+            state_bytes: first,
+            rejected_updates: self.rejected_updates,
+            settle_rounds,
+        })
+    }
+
+    fn apply(&mut self, step: Step) {
+        match &step {
+            Step::Author {
+                peer,
+                delta,
+                cleanups,
+            } => {
+                let peer = *peer as usize;
+                self.apply_delta(peer, delta, *cleanups);
+            }
+            Step::Send {
+                from,
+                to,
+                full_state,
+            } => {
+                let (from, to) = (*from as usize, *to as usize);
+                if self.groups[from] == self.groups[to] {
+                    if let Some(envelope) = self.envelope(from, to, *full_state) {
+                        self.in_flight.push_back(envelope);
+                    }
+                }
+            }
+            Step::Deliver { index, cleanups } => {
+                if let Some(envelope) = self.in_flight.remove(*index as usize) {
+                    self.deliver(envelope, *cleanups);
+                }
+            }
+            Step::Duplicate { index } => {
+                if let Some(envelope) = self.in_flight.get(*index as usize) {
+                    let copy = Envelope {
+                        to: envelope.to,
+                        payload: match &envelope.payload {
+                            Payload::Delta(delta) => Payload::Delta(delta.clone()),
+                            Payload::State(state) => Payload::State(state.clone()),
+                        },
+                    };
+                    self.in_flight.push_back(copy);
+                }
+            }
+            Step::Drop { index } => {
+                self.in_flight.remove(*index as usize);
+            }
+            Step::Partition { groups } => {
+                self.groups.clone_from(groups);
+            }
+            Step::Heal => self.groups.fill(0),
+        }
+        self.trace.steps.push(step);
+    }
+
+    /// What `from` would send `to` right now, if anything.
+    fn envelope(&self, from: usize, to: usize, full_state: bool) -> Option<Envelope> {
+        let sender = &self.peers[from];
+        let payload = if full_state {
+            Payload::State(Box::new(sender.clone()))
+        } else {
+            let summary = self.peers[to].summarize(&self.peers[to], &self.parameters);
+            Payload::Delta(Box::new(sender.delta(
+                sender,
+                &self.parameters,
+                &summary,
+            )?))
+        };
+        Some(Envelope { to, payload })
+    }
+
+    fn deliver(&mut self, envelope: Envelope, cleanups: u32) {
+        match envelope.payload {
+            Payload::Delta(delta) => self.apply_delta(envelope.to, &delta, cleanups),
+            Payload::State(state) => {
+                let mut next = self.peers[envelope.to].clone();
+                let merged = next.merge(&next.clone(), &self.parameters, &state);
+                self.finish(envelope.to, next, merged, cleanups);
+            }
+        }
+    }
+
+    fn apply_delta(&mut self, peer: usize, delta: &ChatRoomStateV1Delta, cleanups: u32) {
+        let mut next = self.peers[peer].clone();
+        let applied = next.apply_delta(&next.clone(), &self.parameters, &Some(delta.clone()));
+        self.finish(peer, next, applied, cleanups);
+    }
+
+    /// Keep `next` only if the update (and every rerun cleanup) succeeded.
+    fn finish(
+        &mut self,
+        peer: usize,
+        mut next: ChatRoomStateV1,
+        result: Result<(), String>,
+        cleanups: u32,
+    ) {
+        let mut result = result;
+        for _ in 0..cleanups {
+            if result.is_err() {
+                break;
+            }
+            result = next.post_apply_cleanup(&self.parameters);
+        }
+        match result {
+            Ok(()) => self.peers[peer] = next,
+            Err(_) => self.rejected_updates += 1,
+        }
+    }
+
+    fn random_step(&mut self) -> Step {
+        let peers = self.peers.len();
+        let queued = self.in_flight.len();
+        let roll = self.rng.next_f64();
+        let config = self.config.clone();
+        let mut threshold = config.partition_chance;
+        if roll < threshold {
+            if self.groups.iter().any(|g| *g != 0) {
+                return Step::Heal;
+            }
+            let groups = (0..peers).map(|_| self.rng.below(2) as u32).collect();
+            return Step::Partition { groups };
+        }
+        if queued > 0 {
+            threshold += config.drop_chance;
+            if roll < threshold {
+                let index = self.rng.below(queued) as u32;
+                return Step::Drop { index };
+            }
+            threshold += config.duplicate_chance;
+            if roll < threshold {
+                let index = self.rng.below(queued) as u32;
+                return Step::Duplicate { index };
+            }
+            // Otherwise deliver and send about equally often, so the queue
+            // neither starves nor grows without bound.
+            if self.rng.below(2) == 0 {
+                let index = if config.reorder {
+                    self.rng.below(queued) as u32
+                } else {
+                    0
+                };
+                let cleanups = self.random_cleanups();
+                return Step::Deliver { index, cleanups };
+            }
+        }
+        let from = self.rng.below(peers);
+        let to = (from + 1 + self.rng.below(peers - 1)) % peers;
+        let full_state = self.rng.next_f64() < config.full_state_chance;
+        Step::Send {
+            from: from as u32,
+            to: to as u32,
+            full_state,
+        }
+    }
+
+    fn random_cleanups(&mut self) -> u32 {
+        (self.rng.next_u64() % (u64::from(self.config.max_extra_cleanups) + 1)) as u32
+    }
+}
+
+/// Re-run `trace` step by step, then settle.
+pub fn replay(trace: &Trace) -> Result<Report, Box<Divergence>> {
+    let config = SimConfig {
+        peers: trace.peers as usize,
+        ..SimConfig::default()
+    };
+    let mut sim = Simulation::new(trace.owner, trace.initial.clone(), config);
+    for step in &trace.steps {
+        sim.apply(step.clone());
+    }
+    sim.settle()
+}
+
+fn encode(state: &ChatRoomStateV1) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(state, &mut bytes).expect("a room state always serializes");
+    bytes
+}
+
+/// SplitMix64: tiny, seedable and identical on every platform, so a seed
+/// names the same run everywhere without pulling in `rand`.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
@@ -0,0 +1,865 @@
+//! Space contract state: a group of rooms with shared membership.
+//!
+//! A space is keyed by [`SpaceParametersV1::owner`], a key generated for the
+//! space alone, like a room owner key. Its state holds:
+//!
+//! - an owner-signed [`SpaceConfigV1`]: the space's name, the rooms listed in
+//!   it and the space-level admins, last-writer-wins on `version`;
+//! - [`AuthorizedSpaceMemberV1`] entries, one per member, each signed by the
+//!   owner or an admin who invited them;
+//! - [`AuthorizedSpaceBanV1`] entries, signed by the owner or an admin;
+//! - [`AuthorizedRoomGrantV1`] entries: for a member and a listed room, an
+//!   [`AuthorizedMember`] signed with an admin's key in that room. That is the
+//!   invite chain the member joins the room with, using their space key as
+//!   their room key. The contract can't see room state, so it checks only that
+//!   the grant was issued by the owner or an admin to a current member for a
+//!   listed room; the room contract checks the chain itself on join.
+//!
+//! Clients do the cross-contract work: an admin's client issues grants for
+//! members who lack one in rooms the admin belongs to, and mirrors each space
+//! ban into those rooms' `BansV1`. A room listed in a space points back at
+//! it through `Configuration::space`, set by the room owner, so listing
+//! someone else's room in a space does not make it part of the space.
+//!
+//! # Merge
+//!
+//! [`SpaceStateV1::apply_delta`] drops anything that fails verification
+//! rather than rejecting the delta, as the inbox does, then normalises: one
+//! entry per key (the lowest signature wins a tie), entries signed by someone
+//! who is no longer an admin and everything about a banned member removed,
+//! sorted, and capped. Normalisation is a pure function of the held set and
+//! the config, so merge order does not matter.
+
+use crate::room_state::direct_messages::SignatureBytes;
+use crate::room_state::member::{AuthorizedMember, MemberId};
+use crate::util::{sign_struct, verify_struct};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use freenet_scaffold::ComposableState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Maximum rooms listed in one space.
+pub const MAX_SPACE_ROOMS: usize = 32;
+
+/// Maximum space-level admins.
+pub const MAX_SPACE_ADMINS: usize = 16;
+
+/// Maximum members. With [`MAX_SPACE_ROOMS`] this bounds the grants too.
+pub const MAX_SPACE_MEMBERS: usize = 256;
+
+/// Maximum bans held.
+pub const MAX_SPACE_BANS: usize = 256;
+
+/// Longest space name, in bytes.
+pub const MAX_SPACE_NAME_LEN: usize = 64;
+
+/// Space contract parameters. The contract key is derived from these.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpaceParametersV1 {
+    pub owner: VerifyingKey,
+}
+
+impl SpaceParametersV1 {
+    pub fn owner_id(&self) -> MemberId {
+        MemberId::from(&self.owner)
+    }
+}
+
+/// The owner's description of the space.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpaceConfigV1 {
+    pub version: u32,
+    pub name: String,
+    /// Owner keys of the rooms in the space.
+    pub rooms: Vec<VerifyingKey>,
+    /// Space member keys that may invite, ban and grant room access.
+    pub admins: Vec<VerifyingKey>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthorizedSpaceConfigV1 {
+    pub config: SpaceConfigV1,
+    pub signature: Signature,
+}
+
+impl AuthorizedSpaceConfigV1 {
+    pub fn new(config: SpaceConfigV1, owner_sk: &SigningKey) -> Self {
+        Self {
+            signature: sign_struct(&config, owner_sk),
+            config,
+        }
+    }
+
+    pub fn verify(&self, parameters: &SpaceParametersV1) -> Result<(), String> {
+        let config = &self.config;
+        if config.name.len() > MAX_SPACE_NAME_LEN {
+            return Err(format!(
+                "Space name is {} bytes; the limit is {MAX_SPACE_NAME_LEN}",
+                config.name.len()
+            ));
+        }
+        if config.rooms.len() > MAX_SPACE_ROOMS {
+            return Err(format!(
+                "Space lists {} rooms; the limit is {MAX_SPACE_ROOMS}",
+                config.rooms.len()
+            ));
+        }
+        if config.admins.len() > MAX_SPACE_ADMINS {
+            return Err(format!(
+                "Space has {} admins; the limit is {MAX_SPACE_ADMINS}",
+                config.admins.len()
+            ));
+        }
+        verify_struct(config, &self.signature, &parameters.owner)
+            .map_err(|e| format!("Invalid space config signature: {}", e))
+    }
+
+    /// Last-writer-wins order between two configs; the signature breaks a
+    /// same-version tie so every peer keeps the same one.
+    fn order_key(&self) -> (u32, SignatureBytes) {
+        (
+            self.config.version,
+            SignatureBytes(self.signature.to_bytes()),
+        )
+    }
+}
+
+/// Admission of one member to the space.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpaceMemberV1 {
+    /// Signed so an admission can't be replayed into another space.
+    pub space: VerifyingKey,
+    pub member_vk: VerifyingKey,
+    /// The owner or an admin.
+    pub invited_by: VerifyingKey,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthorizedSpaceMemberV1 {
+    pub member: SpaceMemberV1,
+    pub signature: Signature,
+}
+
+impl AuthorizedSpaceMemberV1 {
+    pub fn new(space: VerifyingKey, member_vk: VerifyingKey, inviter_sk: &SigningKey) -> Self {
+        let member = SpaceMemberV1 {
+            space,
+            member_vk,
+            invited_by: inviter_sk.verifying_key(),
+        };
+        Self {
+            signature: sign_struct(&member, inviter_sk),
+            member,
+        }
+    }
+
+    pub fn member_id(&self) -> MemberId {
+        MemberId::from(&self.member.member_vk)
+    }
+}
+
+/// A space-level ban. Clients mirror it into each room of the space.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpaceBanV1 {
+    pub space: VerifyingKey,
+    pub banned: MemberId,
+    /// The owner or an admin.
+    pub banned_by: VerifyingKey,
+    /// Unix seconds, by the banner's clock.
+    pub banned_at: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthorizedSpaceBanV1 {
+    pub ban: SpaceBanV1,
+    pub signature: Signature,
+}
+
+impl AuthorizedSpaceBanV1 {
+    pub fn new(
+        space: VerifyingKey,
+        banned: MemberId,
+        banned_at: u64,
+        banner_sk: &SigningKey,
+    ) -> Self {
+        let ban = SpaceBanV1 {
+            space,
+            banned,
+            banned_by: banner_sk.verifying_key(),
+            banned_at,
+        };
+        Self {
+            signature: sign_struct(&ban, banner_sk),
+            ban,
+        }
+    }
+}
+
+/// A member's invite into one room of the space.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RoomGrantV1 {
+    pub space: VerifyingKey,
+    pub room_owner: VerifyingKey,
+    /// Signed with the granting admin's key in the room, for the member's
+    /// space key.
+    pub member: AuthorizedMember,
+    /// The granting admin's space key (or the owner's).
+    pub granted_by: VerifyingKey,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthorizedRoomGrantV1 {
+    pub grant: RoomGrantV1,
+    pub signature: Signature,
+}
+
+impl AuthorizedRoomGrantV1 {
+    pub fn new(
+        space: VerifyingKey,
+        room_owner: VerifyingKey,
+        member: AuthorizedMember,
+        granter_space_sk: &SigningKey,
+    ) -> Self {
+        let grant = RoomGrantV1 {
+            space,
+            room_owner,
+            member,
+            granted_by: granter_space_sk.verifying_key(),
+        };
+        Self {
+            signature: sign_struct(&grant, granter_space_sk),
+            grant,
+        }
+    }
+
+    pub fn member_id(&self) -> MemberId {
+        self.grant.member.member.id()
+    }
+
+    fn key(&self) -> GrantKey {
+        GrantKey {
+            room: MemberId::from(&self.grant.room_owner),
+            member: self.member_id(),
+        }
+    }
+}
+
+/// Identifies the grant for one member in one room.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GrantKey {
+    pub room: MemberId,
+    pub member: MemberId,
+}
+
+/// Space contract state.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpaceStateV1 {
+    /// `None` only before the owner has published one; nothing else is held
+    /// until then.
+    #[serde(default)]
+    pub config: Option<AuthorizedSpaceConfigV1>,
+    /// Sorted by member id.
+    #[serde(default)]
+    pub members: Vec<AuthorizedSpaceMemberV1>,
+    /// Sorted by banned member id.
+    #[serde(default)]
+    pub bans: Vec<AuthorizedSpaceBanV1>,
+    /// Sorted by [`GrantKey`].
+    #[serde(default)]
+    pub grants: Vec<AuthorizedRoomGrantV1>,
+}
+
+impl SpaceStateV1 {
+    pub fn name(&self) -> Option<&str> {
+        self.config.as_ref().map(|c| c.config.name.as_str())
+    }
+
+    pub fn rooms(&self) -> &[VerifyingKey] {
+        self.config
+            .as_ref()
+            .map_or(&[][..], |c| c.config.rooms.as_slice())
+    }
+
+    pub fn lists_room(&self, room_owner: &VerifyingKey) -> bool {
+        self.rooms().contains(room_owner)
+    }
+
+    pub fn is_admin(&self, vk: &VerifyingKey) -> bool {
+        self.config
+            .as_ref()
+            .is_some_and(|c| c.config.admins.contains(vk))
+    }
+
+    /// Whether `vk` may invite, ban and grant: the owner or an admin.
+    pub fn can_moderate(&self, vk: &VerifyingKey, parameters: &SpaceParametersV1) -> bool {
+        *vk == parameters.owner || self.is_admin(vk)
+    }
+
+    pub fn member(&self, id: &MemberId) -> Option<&AuthorizedSpaceMemberV1> {
+        self.members.iter().find(|m| &m.member_id() == id)
+    }
+
+    pub fn is_banned(&self, id: &MemberId) -> bool {
+        self.bans.iter().any(|b| &b.ban.banned == id)
+    }
+
+    /// The grants issued to `member_vk`, one per room.
+    pub fn grants_for<'a>(
+        &'a self,
+        member_vk: &'a VerifyingKey,
+    ) -> impl Iterator<Item = &'a AuthorizedRoomGrantV1> + 'a {
+        self.grants
+            .iter()
+            .filter(move |g| &g.grant.member.member.member_vk == member_vk)
+    }
+
+    /// Members without a grant for `room_owner`'s room yet.
+    pub fn members_without_grant(
+        &self,
+        room_owner: &VerifyingKey,
+    ) -> impl Iterator<Item = &AuthorizedSpaceMemberV1> {
+        let granted: HashSet<MemberId> = self
+            .grants
+            .iter()
+            .filter(|g| &g.grant.room_owner == room_owner)
+            .map(AuthorizedRoomGrantV1::member_id)
+            .collect();
+        self.members
+            .iter()
+            .filter(move |m| !granted.contains(&m.member_id()))
+    }
+
+    fn verify_member(
+        &self,
+        m: &AuthorizedSpaceMemberV1,
+        parameters: &SpaceParametersV1,
+    ) -> Result<(), String> {
+        if m.member.space != parameters.owner {
+            return Err("Space member is for a different space".to_string());
+        }
+        if !self.can_moderate(&m.member.invited_by, parameters) {
+            return Err(format!(
+                "Space member {:?} was not invited by the owner or an admin",
+                m.member_id()
+            ));
+        }
+        verify_struct(&m.member, &m.signature, &m.member.invited_by)
+            .map_err(|e| format!("Invalid space member signature: {}", e))
+    }
+
+    fn verify_ban(
+        &self,
+        b: &AuthorizedSpaceBanV1,
+        parameters: &SpaceParametersV1,
+    ) -> Result<(), String> {
+        if b.ban.space != parameters.owner {
+            return Err("Space ban is for a different space".to_string());
+        }
+        if !self.can_moderate(&b.ban.banned_by, parameters) {
+            return Err(format!(
+                "Space ban of {:?} was not issued by the owner or an admin",
+                b.ban.banned
+            ));
+        }
+        // Admins are removed through the config, not banned.
+        if b.ban.banned == parameters.owner_id()
+            || self.config.as_ref().is_some_and(|c| {
+                c.config
+                    .admins
+                    .iter()
+                    .any(|a| MemberId::from(a) == b.ban.banned)
+            })
+        {
+            return Err(format!(
+                "Space ban targets the owner or an admin: {:?}",
+                b.ban.banned
+            ));
+        }
+        verify_struct(&b.ban, &b.signature, &b.ban.banned_by)
+            .map_err(|e| format!("Invalid space ban signature: {}", e))
+    }
+
+    /// Grants are checked against the members held, so members must be
+    /// settled first.
+    fn verify_grant(
+        &self,
+        g: &AuthorizedRoomGrantV1,
+        parameters: &SpaceParametersV1,
+    ) -> Result<(), String> {
+        let grant = &g.grant;
+        if grant.space != parameters.owner {
+            return Err("Room grant is for a different space".to_string());
+        }
+        if !self.lists_room(&grant.room_owner) {
+            return Err("Room grant is for a room the space does not list".to_string());
+        }
+        if grant.member.member.owner_member_id != MemberId::from(&grant.room_owner) {
+            return Err("Room grant's member entry is for a different room".to_string());
+        }
+        if self.member(&g.member_id()).is_none() {
+            return Err(format!(
+                "Room grant is for {:?}, who is not a space member",
+                g.member_id()
+            ));
+        }
+        if !self.can_moderate(&grant.granted_by, parameters) {
+            return Err("Room grant was not issued by the owner or an admin".to_string());
+        }
+        verify_struct(grant, &g.signature, &grant.granted_by)
+            .map_err(|e| format!("Invalid room grant signature: {}", e))
+    }
+
+    /// Re-check everything against the current config, keep one entry per
+    /// key, drop everything about banned members, sort and cap. See the
+    /// module doc.
+    fn normalize(&mut self, parameters: &SpaceParametersV1) {
+        if self.config.is_none() {
+            self.members.clear();
+            self.bans.clear();
+            self.grants.clear();
+            return;
+        }
+
+        let mut bans: BTreeMap<MemberId, AuthorizedSpaceBanV1> = BTreeMap::new();
+        for b in std::mem::take(&mut self.bans) {
+            if self.verify_ban(&b, parameters).is_err() {
+                continue;
+            }
+            let rank = |b: &AuthorizedSpaceBanV1| {
+                (b.ban.banned_at, SignatureBytes(b.signature.to_bytes()))
+            };
+            match bans.get(&b.ban.banned) {
+                Some(held) if rank(held) <= rank(&b) => {}
+                _ => {
+                    bans.insert(b.ban.banned, b);
+                }
+            }
+        }
+        self.bans = bans.into_values().take(MAX_SPACE_BANS).collect();
+
+        let mut members: BTreeMap<MemberId, AuthorizedSpaceMemberV1> = BTreeMap::new();
+        for m in std::mem::take(&mut self.members) {
+            if self.is_banned(&m.member_id()) || self.verify_member(&m, parameters).is_err() {
+                continue;
+            }
+            match members.get(&m.member_id()) {
+                Some(held) if held.signature.to_bytes() <= m.signature.to_bytes() => {}
+                _ => {
+                    members.insert(m.member_id(), m);
+                }
+            }
+        }
+        self.members = members.into_values().take(MAX_SPACE_MEMBERS).collect();
+
+        let mut grants: BTreeMap<GrantKey, AuthorizedRoomGrantV1> = BTreeMap::new();
+        for g in std::mem::take(&mut self.grants) {
+            if self.verify_grant(&g, parameters).is_err() {
+                continue;
+            }
+            match grants.get(&g.key()) {
+                Some(held) if held.signature.to_bytes() <= g.signature.to_bytes() => {}
+                _ => {
+                    grants.insert(g.key(), g);
+                }
+            }
+        }
+        self.grants = grants.into_values().collect();
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpaceSummaryV1 {
+    #[serde(default)]
+    pub config: Option<(u32, SignatureBytes)>,
+    #[serde(default)]
+    pub members: BTreeSet<MemberId>,
+    #[serde(default)]
+    pub bans: BTreeSet<MemberId>,
+    #[serde(default)]
+    pub grants: BTreeSet<GrantKey>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpaceDeltaV1 {
+    #[serde(default)]
+    pub config: Option<AuthorizedSpaceConfigV1>,
+    #[serde(default)]
+    pub members: Vec<AuthorizedSpaceMemberV1>,
+    #[serde(default)]
+    pub bans: Vec<AuthorizedSpaceBanV1>,
+    #[serde(default)]
+    pub grants: Vec<AuthorizedRoomGrantV1>,
+}
+
+impl ComposableState for SpaceStateV1 {
+    type ParentState = SpaceStateV1;
+    type Summary = SpaceSummaryV1;
+    type Delta = SpaceDeltaV1;
+    type Parameters = SpaceParametersV1;
+
+    fn verify(
+        &self,
+        _parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+    ) -> Result<(), String> {
+        let Some(config) = &self.config else {
+            if self.members.is_empty() && self.bans.is_empty() && self.grants.is_empty() {
+                return Ok(());
+            }
+            return Err("Space holds entries but no config".to_string());
+        };
+        config.verify(parameters)?;
+        if self.members.len() > MAX_SPACE_MEMBERS || self.bans.len() > MAX_SPACE_BANS {
+            return Err(format!(
+                "Space exceeds caps: {} members, {} bans",
+                self.members.len(),
+                self.bans.len()
+            ));
+        }
+        let mut seen = HashSet::new();
+        for b in &self.bans {
+            self.verify_ban(b, parameters)?;
+            if !seen.insert(b.ban.banned) {
+                return Err(format!("Space holds two bans for {:?}", b.ban.banned));
+            }
+        }
+        let mut seen = HashSet::new();
+        for m in &self.members {
+            self.verify_member(m, parameters)?;
+            if self.is_banned(&m.member_id()) {
+                return Err(format!("Space member {:?} is banned", m.member_id()));
+            }
+            if !seen.insert(m.member_id()) {
+                return Err(format!("Space holds two entries for {:?}", m.member_id()));
+            }
+        }
+        let mut seen = HashSet::new();
+        for g in &self.grants {
+            self.verify_grant(g, parameters)?;
+            if !seen.insert(g.key()) {
+                return Err(format!(
+                    "Space holds two grants for {:?} in one room",
+                    g.member_id()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn summarize(
+        &self,
+        _parent_state: &Self::ParentState,
+        _parameters: &Self::Parameters,
+    ) -> Self::Summary {
+        SpaceSummaryV1 {
+            config: self.config.as_ref().map(AuthorizedSpaceConfigV1::order_key),
+            members: self.members.iter().map(|m| m.member_id()).collect(),
+            bans: self.bans.iter().map(|b| b.ban.banned).collect(),
+            grants: self.grants.iter().map(|g| g.key()).collect(),
+        }
+    }
+
+    fn delta(
+        &self,
+        _parent_state: &Self::ParentState,
+        _parameters: &Self::Parameters,
+        old_state_summary: &Self::Summary,
+    ) -> Option<Self::Delta> {
+        let config = self
+            .config
+            .as_ref()
+            .filter(|c| old_state_summary.config < Some(c.order_key()))
+            .cloned();
+        let delta = SpaceDeltaV1 {
+            config,
+            members: self
+                .members
+                .iter()
+                .filter(|m| !old_state_summary.members.contains(&m.member_id()))
+                .cloned()
+                .collect(),
+            bans: self
+                .bans
+                .iter()
+                .filter(|b| !old_state_summary.bans.contains(&b.ban.banned))
+                .cloned()
+                .collect(),
+            grants: self
+                .grants
+                .iter()
+                .filter(|g| !old_state_summary.grants.contains(&g.key()))
+                .cloned()
+                .collect(),
+        };
+        if delta == SpaceDeltaV1::default() {
+            None
+        } else {
+            Some(delta)
+        }
+    }
+
+    fn apply_delta(
+        &mut self,
+        _parent_state: &Self::ParentState,
+        parameters: &Self::Parameters,
+        delta: &Option<Self::Delta>,
+    ) -> Result<(), String> {
+        if let Some(delta) = delta {
+            // Silent-drop, as the inbox does: one bad entry must not poison
+            // the rest of the delta. The config goes first, since it decides
+            // who may sign everything else.
+            if let Some(config) = &delta.config {
+                let newer = self
+                    .config
+                    .as_ref()
+                    .is_none_or(|held| held.order_key() < config.order_key());
+                if newer && config.verify(parameters).is_ok() {
+                    self.config = Some(config.clone());
+                }
+            }
+            self.members.extend(delta.members.iter().cloned());
+            self.bans.extend(delta.bans.iter().cloned());
+            self.grants.extend(delta.grants.iter().cloned());
+        }
+        self.normalize(parameters);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room_state::member::Member;
+    use rand::rngs::OsRng;
+
+    struct Space {
+        owner_sk: SigningKey,
+        state: SpaceStateV1,
+    }
+
+    impl Space {
+        fn new(rooms: Vec<VerifyingKey>, admins: Vec<VerifyingKey>) -> Self {
+            let owner_sk = SigningKey::generate(&mut OsRng);
+            let mut space = Self {
+                owner_sk,
+                state: SpaceStateV1::default(),
+            };
+            let config = space.config(1, rooms, admins);
+            space.apply(SpaceDeltaV1 {
+                config: Some(config),
+                ..Default::default()
+            });
+            space
+        }
+
+        fn params(&self) -> SpaceParametersV1 {
+            SpaceParametersV1 {
+                owner: self.owner_sk.verifying_key(),
+            }
+        }
+
+        fn vk(&self) -> VerifyingKey {
+            self.owner_sk.verifying_key()
+        }
+
+        fn config(
+            &self,
+            version: u32,
+            rooms: Vec<VerifyingKey>,
+            admins: Vec<VerifyingKey>,
+        ) -> AuthorizedSpaceConfigV1 {
+            AuthorizedSpaceConfigV1::new(
+                SpaceConfigV1 {
+                    version,
+                    name: "Team".to_string(),
+                    rooms,
+                    admins,
+                },
+                &self.owner_sk,
+            )
+        }
+
+        fn apply(&mut self, delta: SpaceDeltaV1) {
+            let parent = self.state.clone();
+            let params = self.params();
+            self.state
+                .apply_delta(&parent, &params, &Some(delta))
+                .unwrap();
+            self.state.verify(&self.state, &params).unwrap();
+        }
+
+        fn admit(&mut self, inviter: &SigningKey) -> SigningKey {
+            let sk = SigningKey::generate(&mut OsRng);
+            let member = AuthorizedSpaceMemberV1::new(self.vk(), sk.verifying_key(), inviter);
+            self.apply(SpaceDeltaV1 {
+                members: vec![member],
+                ..Default::default()
+            });
+            sk
+        }
+    }
+
+    fn grant(
+        space: &Space,
+        room_sk: &SigningKey,
+        member: &SigningKey,
+        granter: &SigningKey,
+    ) -> AuthorizedRoomGrantV1 {
+        let room_owner = room_sk.verifying_key();
+        let member = AuthorizedMember::new(
+            Member {
+                owner_member_id: MemberId::from(&room_owner),
+                invited_by: MemberId::from(&room_owner),
+                member_vk: member.verifying_key(),
+            },
+            room_sk,
+        );
+        AuthorizedRoomGrantV1::new(space.vk(), room_owner, member, granter)
+    }
+
+    #[test]
+    fn only_owner_and_admins_admit_and_a_ban_removes_the_member_and_grants() {
+        let room_sk = SigningKey::generate(&mut OsRng);
+        let admin_sk = SigningKey::generate(&mut OsRng);
+        let mut space = Space::new(
+            vec![room_sk.verifying_key()],
+            vec![admin_sk.verifying_key()],
+        );
+        let owner_sk = space.owner_sk.clone();
+
+        let alice = space.admit(&owner_sk);
+        let bob = space.admit(&admin_sk);
+        // Alice is a plain member, so whoever she invites is dropped.
+        space.admit(&alice);
+        assert_eq!(space.state.members.len(), 2);
+
+        let grants = vec![
+            grant(&space, &room_sk, &alice, &admin_sk),
+            grant(&space, &room_sk, &bob, &admin_sk),
+            // Not an admin.
+            grant(&space, &room_sk, &bob, &alice),
+            // A room the space does not list.
+            grant(&space, &SigningKey::generate(&mut OsRng), &bob, &admin_sk),
+        ];
+        space.apply(SpaceDeltaV1 {
+            grants,
+            ..Default::default()
+        });
+        assert_eq!(space.state.grants.len(), 2);
+        assert_eq!(
+            space
+                .state
+                .members_without_grant(&room_sk.verifying_key())
+                .count(),
+            0
+        );
+
+        let bob_id = MemberId::from(&bob.verifying_key());
+        let ban = AuthorizedSpaceBanV1::new(space.vk(), bob_id, 1_000, &admin_sk);
+        // An admin cannot be banned; only the config removes them.
+        let admin_ban = AuthorizedSpaceBanV1::new(
+            space.vk(),
+            MemberId::from(&admin_sk.verifying_key()),
+            1_000,
+            &owner_sk,
+        );
+        space.apply(SpaceDeltaV1 {
+            bans: vec![ban, admin_ban],
+            ..Default::default()
+        });
+        assert_eq!(space.state.bans.len(), 1);
+        assert!(space.state.member(&bob_id).is_none());
+        assert_eq!(space.state.grants_for(&bob.verifying_key()).count(), 0);
+        assert_eq!(space.state.grants_for(&alice.verifying_key()).count(), 1);
+
+        // Re-admitting a banned member does nothing.
+        let again = AuthorizedSpaceMemberV1::new(space.vk(), bob.verifying_key(), &owner_sk);
+        space.apply(SpaceDeltaV1 {
+            members: vec![again],
+            ..Default::default()
+        });
+        assert!(space.state.member(&bob_id).is_none());
+    }
+
+    #[test]
+    fn removing_an_admin_drops_what_they_signed() {
+        let room_sk = SigningKey::generate(&mut OsRng);
+        let admin_sk = SigningKey::generate(&mut OsRng);
+        let mut space = Space::new(
+            vec![room_sk.verifying_key()],
+            vec![admin_sk.verifying_key()],
+        );
+        let carol = space.admit(&admin_sk);
+        space.apply(SpaceDeltaV1 {
+            grants: vec![grant(&space, &room_sk, &carol, &admin_sk)],
+            ..Default::default()
+        });
+        assert_eq!(space.state.grants.len(), 1);
+
+        // A stale config does not win over the held one.
+        let stale = space.config(0, vec![], vec![]);
+        space.apply(SpaceDeltaV1 {
+            config: Some(stale),
+            ..Default::default()
+        });
+        assert_eq!(space.state.members.len(), 1);
+
+        let demoted = space.config(2, vec![room_sk.verifying_key()], vec![]);
+        space.apply(SpaceDeltaV1 {
+            config: Some(demoted),
+            ..Default::default()
+        });
+        assert!(space.state.members.is_empty());
+        assert!(space.state.grants.is_empty());
+    }
+
+    #[test]
+    fn delta_carries_only_what_the_peer_lacks_and_merge_converges() {
+        let room_sk = SigningKey::generate(&mut OsRng);
+        let mut ours = Space::new(vec![room_sk.verifying_key()], vec![]);
+        let owner_sk = ours.owner_sk.clone();
+        let params = ours.params();
+        let mut theirs = SpaceStateV1::default();
+
+        let dave = ours.admit(&owner_sk);
+        ours.apply(SpaceDeltaV1 {
+            grants: vec![grant(&ours, &room_sk, &dave, &owner_sk)],
+            ..Default::default()
+        });
+
+        let delta = ours
+            .state
+            .delta(&ours.state, &params, &theirs.summarize(&theirs, &params))
+            .unwrap();
+        assert!(delta.config.is_some());
+        assert_eq!((delta.members.len(), delta.grants.len()), (1, 1));
+
+        let parent = theirs.clone();
+        theirs.apply_delta(&parent, &params, &Some(delta)).unwrap();
+        assert_eq!(theirs, ours.state);
+        assert!(ours
+            .state
+            .delta(&ours.state, &params, &theirs.summarize(&theirs, &params))
+            .is_none());
+
+        // Entries from another space are dropped.
+        let other = Space::new(vec![], vec![]);
+        let stranger = AuthorizedSpaceMemberV1::new(
+            other.vk(),
+            SigningKey::generate(&mut OsRng).verifying_key(),
+            &other.owner_sk,
+        );
+        let parent = theirs.clone();
+        theirs
+            .apply_delta(
+                &parent,
+                &params,
+                &Some(SpaceDeltaV1 {
+                    members: vec![stranger],
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+        assert_eq!(theirs, ours.state);
+    }
+}
@@ -0,0 +1,597 @@
+//! Where a room state's serialized bytes go.
+//!
+//! [`SizeReport::new`] measures the CBOR encoding of a [`ChatRoomStateV1`]
+//! (the form the contract stores and peers exchange) three ways: per
+//! top-level field, per member, and against the room's configured limits. It
+//! also lists [`Compaction`] opportunities: bytes the state carries that no
+//! reader needs any more.
+//!
+//! Member attribution follows authorship: a member is charged for the room
+//! messages they wrote, their `member_info` records, the direct and group
+//! messages they sent, their DM purge tombstones, and the room-secret blobs
+//! encrypted for them. Bans, the configuration and version records are
+//! room-wide and only show up per field.
+
+use crate::room_state::group_direct_messages::MAX_GROUP_DIRECT_MESSAGES;
+use crate::room_state::member::MemberId;
+use crate::room_state::privacy::SecretVersion;
+use crate::ChatRoomStateV1;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Serialized size of `value` as the contract stores it.
+pub fn encoded_len<T: Serialize>(value: &T) -> usize {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(value, &mut bytes).expect("room state values always serialize");
+    bytes.len()
+}
+
+/// A count of entries and their serialized bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub items: usize,
+    pub bytes: usize,
+}
+
+impl Usage {
+    fn record(&mut self, bytes: usize) {
+        self.items += 1;
+        self.bytes += bytes;
+    }
+
+    /// Count `value` and return its size.
+    fn add<T: Serialize>(&mut self, value: &T) -> usize {
+        let bytes = encoded_len(value);
+        self.record(bytes);
+        bytes
+    }
+}
+
+/// One top-level [`ChatRoomStateV1`] field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldSize {
+    pub field: &'static str,
+    pub usage: Usage,
+}
+
+/// What one member contributes to the state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemberSize {
+    pub member_id: MemberId,
+    pub messages: Usage,
+    pub member_info: Usage,
+    /// Direct messages sent, plus the purge tombstones this member holds as
+    /// a recipient.
+    pub direct_messages: Usage,
+    pub group_direct_messages: Usage,
+    /// Room-secret blobs encrypted for this member.
+    pub secrets: Usage,
+}
+
+impl MemberSize {
+    fn new(member_id: MemberId) -> Self {
+        MemberSize {
+            member_id,
+            messages: Usage::default(),
+            member_info: Usage::default(),
+            direct_messages: Usage::default(),
+            group_direct_messages: Usage::default(),
+            secrets: Usage::default(),
+        }
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.messages.bytes
+            + self.member_info.bytes
+            + self.direct_messages.bytes
+            + self.group_direct_messages.bytes
+            + self.secrets.bytes
+    }
+}
+
+fn charge(members: &mut BTreeMap<MemberId, MemberSize>, id: MemberId) -> &mut MemberSize {
+    members.entry(id).or_insert_with(|| MemberSize::new(id))
+}
+
+/// A count-based limit and how much of it is in use.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LimitUsage {
+    /// The `Configuration` field, or the protocol constant for limits an
+    /// owner can't change.
+    pub limit: &'static str,
+    pub used: usize,
+    pub max: usize,
+}
+
+/// Bytes the state carries that could be dropped without losing anything a
+/// member can still read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Compaction {
+    /// Secret blobs for a superseded secret version that nothing retained
+    /// (messages, nicknames, the room name) is encrypted under.
+    StaleSecretVersion {
+        version: SecretVersion,
+        blobs: usize,
+        bytes: usize,
+    },
+    /// Secret blobs for someone who is neither a member nor the owner.
+    SecretsForNonMember {
+        member_id: MemberId,
+        blobs: usize,
+        bytes: usize,
+    },
+    /// More than one `member_info` record for a member. Only the canonical
+    /// one is ever read; `post_apply_cleanup` drops the rest, but a state
+    /// fetched whole can still hold them.
+    DuplicateMemberInfo {
+        member_id: MemberId,
+        records: usize,
+        bytes: usize,
+    },
+}
+
+impl Compaction {
+    /// The member the bytes belong to, when they belong to one.
+    pub fn member(&self) -> Option<MemberId> {
+        match self {
+            Compaction::StaleSecretVersion { .. } => None,
+            Compaction::SecretsForNonMember { member_id, .. }
+            | Compaction::DuplicateMemberInfo { member_id, .. } => Some(*member_id),
+        }
+    }
+
+    pub fn reclaimable_bytes(&self) -> usize {
+        match self {
+            Compaction::StaleSecretVersion { bytes, .. }
+            | Compaction::SecretsForNonMember { bytes, .. }
+            | Compaction::DuplicateMemberInfo { bytes, .. } => *bytes,
+        }
+    }
+}
+
+impl fmt::Display for Compaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compaction::StaleSecretVersion {
+                version,
+                blobs,
+                bytes,
+            } => write!(
+                f,
+                "secret version {version} is superseded and nothing retained uses it: \
+                 {blobs} blobs, {bytes} bytes"
+            ),
+            Compaction::SecretsForNonMember {
+                member_id,
+                blobs,
+                bytes,
+            } => write!(
+                f,
+                "{member_id} is not a member but holds {blobs} secret blobs, {bytes} bytes"
+            ),
+            Compaction::DuplicateMemberInfo {
+                member_id,
+                records,
+                bytes,
+            } => write!(
+                f,
+                "{member_id} has {records} member_info records; the {} non-canonical \
+                 ones take {bytes} bytes",
+                records - 1
+            ),
+        }
+    }
+}
+
+/// The size breakdown of one room state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SizeReport {
+    /// The whole encoded state.
+    pub total_bytes: usize,
+    /// In declaration order. Their sum falls short of `total_bytes` by the
+    /// encoding's field names and framing.
+    pub fields: Vec<FieldSize>,
+    /// Largest first.
+    pub members: Vec<MemberSize>,
+    pub limits: Vec<LimitUsage>,
+    /// Largest first.
+    pub compactions: Vec<Compaction>,
+}
+
+impl SizeReport {
+    pub fn new(state: &ChatRoomStateV1) -> Self {
+        let config = &state.configuration.configuration;
+        let owner_id = config.owner_member_id;
+
+        let mut members: BTreeMap<MemberId, MemberSize> = BTreeMap::new();
+
+        let mut messages = Usage::default();
+        for message in &state.recent_messages.messages {
+            let bytes = messages.add(message);
+            charge(&mut members, message.message.author)
+                .messages
+                .record(bytes);
+        }
+
+        let mut member_info = Usage::default();
+        let mut info_records: BTreeMap<MemberId, Vec<usize>> = BTreeMap::new();
+        for info in &state.member_info.member_info {
+            let bytes = member_info.add(info);
+            let id = info.member_info.member_id;
+            charge(&mut members, id).member_info.record(bytes);
+            info_records.entry(id).or_default().push(bytes);
+        }
+
+        let mut direct_messages = Usage::default();
+        for dm in &state.direct_messages.messages {
+            let bytes = direct_messages.add(dm);
+            charge(&mut members, dm.message.sender)
+                .direct_messages
+                .record(bytes);
+        }
+        for purges in &state.direct_messages.purges {
+            let bytes = direct_messages.add(purges);
+            charge(&mut members, purges.recipient_id)
+                .direct_messages
+                .record(bytes);
+        }
+
+        let mut group_direct_messages = Usage::default();
+        for message in &state.group_direct_messages.messages {
+            let bytes = group_direct_messages.add(message);
+            charge(&mut members, message.message.sender)
+                .group_direct_messages
+                .record(bytes);
+        }
+
+        let mut secrets = Usage::default();
+        let mut secret_blobs: Vec<(MemberId, SecretVersion, usize)> = Vec::new();
+        for blob in &state.secrets.encrypted_secrets {
+            let bytes = secrets.add(blob);
+            let id = blob.secret.member_id;
+            charge(&mut members, id).secrets.record(bytes);
+            secret_blobs.push((id, blob.secret.secret_version, bytes));
+        }
+        for record in &state.secrets.versions {
+            secrets.add(record);
+        }
+
+        // In `ChatRoomStateV1::FIELD_NAMES` order.
+        let usages = [
+            Usage {
+                items: 1,
+                bytes: encoded_len(&state.configuration),
+            },
+            Usage {
+                items: state.bans.0.len(),
+                bytes: encoded_len(&state.bans),
+            },
+            Usage {
+                items: state.members.members.len(),
+                bytes: encoded_len(&state.members),
+            },
+            Usage {
+                bytes: encoded_len(&state.member_info),
+                ..member_info
+            },
+            Usage {
+                bytes: encoded_len(&state.secrets),
+                ..secrets
+            },
+            Usage {
+                bytes: encoded_len(&state.recent_messages),
+                ..messages
+            },
+            Usage {
+                bytes: encoded_len(&state.direct_messages),
+                ..direct_messages
+            },
+            Usage {
+                bytes: encoded_len(&state.group_direct_messages),
+                ..group_direct_messages
+            },
+            Usage {
+                items: usize::from(state.upgrade.0.is_some()),
+                bytes: encoded_len(&state.upgrade),
+            },
+            Usage {
+                items: 1,
+                bytes: encoded_len(&state.version),
+            },
+        ];
+        let fields = ChatRoomStateV1::FIELD_NAMES
+            .into_iter()
+            .zip(usages)
+            .map(|(field, usage)| FieldSize { field, usage })
+            .collect();
+
+        let limits = vec![
+            LimitUsage {
+                limit: "max_recent_messages",
+                used: state.recent_messages.messages.len(),
+                max: config.max_recent_messages,
+            },
+            LimitUsage {
+                limit: "max_members",
+                used: state.members.members.len(),
+                max: config.max_members,
+            },
+            LimitUsage {
+                limit: "max_user_bans",
+                used: state.bans.0.len(),
+                max: config.max_user_bans,
+            },
+            LimitUsage {
+                limit: "max_direct_messages",
+                used: state.direct_messages.messages.len(),
+                max: config.effective_max_direct_messages(),
+            },
+            LimitUsage {
+                limit: "MAX_GROUP_DIRECT_MESSAGES",
+                used: state.group_direct_messages.messages.len(),
+                max: MAX_GROUP_DIRECT_MESSAGES,
+            },
+        ];
+
+        let mut compactions = Vec::new();
+
+        // A superseded version is still needed while anything retained is
+        // encrypted under it. `post_apply_cleanup` drops the versions older
+        // than all of those, but not an unused one between two used ones.
+        let in_use = state.secret_versions_in_use();
+        let mut stale: BTreeMap<SecretVersion, (usize, usize)> = BTreeMap::new();
+        for &(_, version, bytes) in &secret_blobs {
+            if version != state.secrets.current_version && !in_use.contains(&version) {
+                let entry = stale.entry(version).or_default();
+                entry.0 += 1;
+                entry.1 += bytes;
+            }
+        }
+        compactions.extend(stale.into_iter().map(|(version, (blobs, bytes))| {
+            Compaction::StaleSecretVersion {
+                version,
+                blobs,
+                bytes,
+            }
+        }));
+
+        let member_ids: BTreeSet<MemberId> = state
+            .members
+            .members
+            .iter()
+            .map(|m| m.member.id())
+            .collect();
+        let mut outsiders: BTreeMap<MemberId, (usize, usize)> = BTreeMap::new();
+        for &(id, _, bytes) in &secret_blobs {
+            if id != owner_id && !member_ids.contains(&id) {
+                let entry = outsiders.entry(id).or_default();
+                entry.0 += 1;
+                entry.1 += bytes;
+            }
+        }
+        compactions.extend(outsiders.into_iter().map(|(member_id, (blobs, bytes))| {
+            Compaction::SecretsForNonMember {
+                member_id,
+                blobs,
+                bytes,
+            }
+        }));
+
+        for (member_id, records) in info_records {
+            if records.len() > 1 {
+                let canonical = state
+                    .member_info
+                    .canonical(member_id)
+                    .map(encoded_len)
+                    .unwrap_or(0);
+                compactions.push(Compaction::DuplicateMemberInfo {
+                    member_id,
+                    records: records.len(),
+                    bytes: records.iter().sum::<usize>() - canonical,
+                });
+            }
+        }
+        compactions.sort_by_key(|c| std::cmp::Reverse(c.reclaimable_bytes()));
+
+        let mut members: Vec<MemberSize> = members.into_values().collect();
+        members.sort_by_key(|m| std::cmp::Reverse(m.total_bytes()));
+
+        SizeReport {
+            total_bytes: encoded_len(state),
+            fields,
+            members,
+            limits,
+            compactions,
+        }
+    }
+
+    pub fn reclaimable_bytes(&self) -> usize {
+        self.compactions
+            .iter()
+            .map(Compaction::reclaimable_bytes)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room_state::configuration::{AuthorizedConfigurationV1, Configuration};
+    use crate::room_state::member::{AuthorizedMember, Member, MembersV1};
+    use crate::room_state::member_info::{AuthorizedMemberInfo, MemberInfo};
+    use crate::room_state::message::{AuthorizedMessageV1, MessageV1, RoomMessageBody};
+    use crate::room_state::privacy::RoomCipherSpec;
+    use crate::room_state::secret::{
+        AuthorizedEncryptedSecretForMember, AuthorizedSecretVersionRecord,
+        EncryptedSecretForMemberV1, SecretVersionRecordV1,
+    };
+    use ed25519_dalek::SigningKey;
+    use std::time::SystemTime;
+
+    fn id(sk: &SigningKey) -> MemberId {
+        sk.verifying_key().into()
+    }
+
+    fn room(owner: &SigningKey, members: &[&SigningKey]) -> ChatRoomStateV1 {
+        let config = Configuration {
+            owner_member_id: id(owner),
+            ..Default::default()
+        };
+        ChatRoomStateV1 {
+            configuration: AuthorizedConfigurationV1::new(config, owner),
+            members: MembersV1 {
+                members: members
+                    .iter()
+                    .map(|sk| {
+                        AuthorizedMember::new(
+                            Member {
+                                owner_member_id: id(owner),
+                                invited_by: id(owner),
+                                member_vk: sk.verifying_key(),
+                            },
+                            owner,
+                        )
+                    })
+                    .collect(),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn message(
+        owner: &SigningKey,
+        author: &SigningKey,
+        content: RoomMessageBody,
+    ) -> AuthorizedMessageV1 {
+        AuthorizedMessageV1::new(
+            MessageV1 {
+                room_owner: id(owner),
+                author: id(author),
+                time: SystemTime::UNIX_EPOCH,
+                content,
+            },
+            author,
+        )
+    }
+
+    fn secret(
+        owner: &SigningKey,
+        member: MemberId,
+        version: SecretVersion,
+    ) -> AuthorizedEncryptedSecretForMember {
+        AuthorizedEncryptedSecretForMember::new(
+            EncryptedSecretForMemberV1 {
+                member_id: member,
+                secret_version: version,
+                ciphertext: vec![0; 48],
+                nonce: [0; 12],
+                sender_ephemeral_public_key: [0; 32],
+                provider: id(owner),
+            },
+            owner,
+        )
+    }
+
+    #[test]
+    fn bytes_are_attributed_to_fields_and_authors() {
+        let owner = SigningKey::from_bytes(&[1; 32]);
+        let (alice, bob) = (
+            SigningKey::from_bytes(&[2; 32]),
+            SigningKey::from_bytes(&[3; 32]),
+        );
+        let mut state = room(&owner, &[&alice, &bob]);
+        state.recent_messages.messages = vec![
+            message(&owner, &alice, RoomMessageBody::public("a".repeat(400))),
+            message(&owner, &bob, RoomMessageBody::public("hi".to_string())),
+        ];
+
+        let report = SizeReport::new(&state);
+        assert_eq!(report.total_bytes, encoded_len(&state));
+        let field_sum: usize = report.fields.iter().map(|f| f.usage.bytes).sum();
+        assert!(field_sum < report.total_bytes);
+
+        assert_eq!(report.members[0].member_id, id(&alice));
+        assert_eq!(report.members[0].messages.items, 1);
+        assert!(report.members[0].messages.bytes > 400);
+        assert_eq!(report.members[1].member_id, id(&bob));
+
+        let messages = &report.limits[0];
+        assert_eq!((messages.limit, messages.used), ("max_recent_messages", 2));
+        assert!(report.compactions.is_empty());
+    }
+
+    #[test]
+    fn flags_stale_secrets_outsiders_and_duplicate_member_info() {
+        let owner = SigningKey::from_bytes(&[1; 32]);
+        let alice = SigningKey::from_bytes(&[2; 32]);
+        let gone = SigningKey::from_bytes(&[9; 32]);
+        let mut state = room(&owner, &[&alice]);
+
+        state.secrets.versions = (1..=3)
+            .map(|version| {
+                AuthorizedSecretVersionRecord::new(
+                    SecretVersionRecordV1 {
+                        version,
+                        cipher_spec: RoomCipherSpec::Aes256Gcm,
+                        created_at: SystemTime::UNIX_EPOCH,
+                    },
+                    &owner,
+                )
+            })
+            .collect();
+        state.secrets.current_version = 3;
+        state.secrets.encrypted_secrets = vec![
+            secret(&owner, id(&alice), 1),
+            secret(&owner, id(&alice), 2),
+            secret(&owner, id(&alice), 3),
+            secret(&owner, id(&gone), 3),
+        ];
+        // Version 2 is still in use; version 1 is not.
+        state.recent_messages.messages = vec![message(
+            &owner,
+            &alice,
+            RoomMessageBody::private(1, 1, vec![0; 16], [0; 12], 2),
+        )];
+        state.member_info.member_info = (1..=2)
+            .map(|version| {
+                AuthorizedMemberInfo::new_with_member_key(
+                    MemberInfo::new_public(id(&alice), version, "alice".to_string()),
+                    &alice,
+                )
+            })
+            .collect();
+
+        let report = SizeReport::new(&state);
+        // Blob sizes vary with the member ID's CBOR integer width.
+        let stale_blob = encoded_len(&state.secrets.encrypted_secrets[0]);
+        let outsider_blob = encoded_len(&state.secrets.encrypted_secrets[3]);
+        let info = encoded_len(&state.member_info.member_info[0]);
+        assert!(report
+            .compactions
+            .contains(&Compaction::StaleSecretVersion {
+                version: 1,
+                blobs: 1,
+                bytes: stale_blob,
+            }));
+        assert!(report
+            .compactions
+            .contains(&Compaction::SecretsForNonMember {
+                member_id: id(&gone),
+                blobs: 1,
+                bytes: outsider_blob,
+            }));
+        assert!(report
+            .compactions
+            .contains(&Compaction::DuplicateMemberInfo {
+                member_id: id(&alice),
+                records: 2,
+                bytes: info,
+            }));
+        assert_eq!(report.compactions.len(), 3);
+        assert_eq!(
+            report.reclaimable_bytes(),
+            stale_blob + outsider_blob + info
+        );
+    }
+}
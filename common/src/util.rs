@@ -29,6 +29,35 @@ pub fn truncated_base32(bytes: &[u8]) -> String {
     encoded.chars().take(8).collect()
 }
 
+/// The safety number two members compare to confirm each holds the other's
+/// real verifying key: 60 digits in groups of five, the same whichever side
+/// computes it.
+///
+/// A `MemberId` is a 64-bit hash of the key, short enough that a lookalike
+/// can be ground out, and a nickname proves nothing; this covers both full
+/// keys. Compared out of band (in person, over a call), a match means no one
+/// in between substituted a key of their own.
+pub fn safety_number(a: &VerifyingKey, b: &VerifyingKey) -> String {
+    let (lo, hi) = if a.as_bytes() <= b.as_bytes() {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let mut hasher = blake3::Hasher::new_derive_key("river safety number v1");
+    hasher.update(lo.as_bytes());
+    hasher.update(hi.as_bytes());
+    let mut bytes = [0u8; 60];
+    hasher.finalize_xof().fill(&mut bytes);
+    bytes
+        .chunks(5)
+        .map(|chunk| {
+            let value = chunk.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+            format!("{:05}", value % 100_000)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +73,16 @@ mod tests {
         let signature = sign_struct(message, &signing_key);
         assert!(verify_struct(&message, &signature, &verifying_key).is_ok());
     }
+
+    #[test]
+    fn safety_number_is_symmetric_and_pair_specific() {
+        let a = SigningKey::from_bytes(&[1; 32]).verifying_key();
+        let b = SigningKey::from_bytes(&[2; 32]).verifying_key();
+        let c = SigningKey::from_bytes(&[3; 32]).verifying_key();
+        let ab = safety_number(&a, &b);
+        assert_eq!(ab, safety_number(&b, &a));
+        assert_ne!(ab, safety_number(&a, &c));
+        assert_eq!(ab.split(' ').count(), 12);
+        assert!(ab.split(' ').all(|g| g.len() == 5));
+    }
 }
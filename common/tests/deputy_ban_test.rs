@@ -1375,6 +1375,7 @@ fn new_verify_accepts_legacy_state_with_nonmember_banner_bans() {
             version: 0,
             preferred_nickname: nick,
             deputies: vec![],
+            profile: None,
         };
         AuthorizedMemberInfo::with_signature(new_mi, sig)
     };
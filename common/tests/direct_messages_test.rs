@@ -443,6 +443,7 @@ fn pruned_sender_can_dm_when_bundling_rejoin_delta() {
             version: 0,
             preferred_nickname: SealedBytes::public(b"Alice".to_vec()),
             deputies: Vec::new(),
+            profile: None,
         },
         &f.alice_sk,
     );
@@ -0,0 +1,594 @@
+//! Integration tests for in-room group direct messages.
+
+use ed25519_dalek::SigningKey;
+use freenet_scaffold::ComposableState;
+use rand::rngs::OsRng;
+use river_core::room_state::ban::{AuthorizedUserBan, UserBan};
+use river_core::room_state::configuration::{AuthorizedConfigurationV1, Configuration};
+use river_core::room_state::direct_messages::{
+    sign_recipient_purges, AuthorizedRecipientPurges, DirectMessagesDelta, PurgeToken,
+    RecipientPurges,
+};
+use river_core::room_state::group_direct_messages::{
+    sign_group_direct_message, AuthorizedGroupDirectMessage, GroupDirectMessage, GroupThreadId,
+    SealedGroupKey, MAX_GROUP_DIRECT_MESSAGES, MAX_GROUP_DM_PARTICIPANTS, SEALED_GROUP_KEY_BYTES,
+};
+use river_core::room_state::member::{AuthorizedMember, Member, MemberId, MembersV1};
+use river_core::room_state::{ChatRoomParametersV1, ChatRoomStateV1, ChatRoomStateV1Delta};
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+// ---------------------------------------------------------------------------
+// Fixture builder: owner + 3 members (Alice, Bob, Carol) in a room.
+// ---------------------------------------------------------------------------
+
+struct Fixture {
+    params: ChatRoomParametersV1,
+    owner_sk: SigningKey,
+    owner_id: MemberId,
+    alice_sk: SigningKey,
+    alice_id: MemberId,
+    bob_sk: SigningKey,
+    bob_id: MemberId,
+    carol_sk: SigningKey,
+    carol_id: MemberId,
+    state: ChatRoomStateV1,
+}
+
+fn make_fixture() -> Fixture {
+    let owner_sk = SigningKey::generate(&mut OsRng);
+    let owner_vk = owner_sk.verifying_key();
+    let owner_id = MemberId::from(&owner_vk);
+
+    let member = || {
+        let sk = SigningKey::generate(&mut OsRng);
+        let auth = AuthorizedMember::new(
+            Member {
+                owner_member_id: owner_id,
+                invited_by: owner_id,
+                member_vk: sk.verifying_key(),
+            },
+            &owner_sk,
+        );
+        let id = MemberId::from(&sk.verifying_key());
+        (sk, id, auth)
+    };
+    let (alice_sk, alice_id, auth_alice) = member();
+    let (bob_sk, bob_id, auth_bob) = member();
+    let (carol_sk, carol_id, auth_carol) = member();
+
+    let config = Configuration {
+        max_members: 10,
+        max_recent_messages: 100,
+        max_user_bans: 10,
+        ..Default::default()
+    };
+    let state = ChatRoomStateV1 {
+        configuration: AuthorizedConfigurationV1::new(config, &owner_sk),
+        members: MembersV1 {
+            members: vec![auth_alice, auth_bob, auth_carol],
+        },
+        ..Default::default()
+    };
+
+    Fixture {
+        params: ChatRoomParametersV1 { owner: owner_vk },
+        owner_sk,
+        owner_id,
+        alice_sk,
+        alice_id,
+        bob_sk,
+        bob_id,
+        carol_sk,
+        carol_id,
+        state,
+    }
+}
+
+/// A signed group message with placeholder key material; these tests cover
+/// authorisation and merge rules, which never look inside the envelopes.
+fn group_at(
+    f: &Fixture,
+    sk: &SigningKey,
+    participants: &[MemberId],
+    timestamp: u64,
+) -> AuthorizedGroupDirectMessage {
+    let mut ids = participants.to_vec();
+    ids.sort();
+    sign_group_direct_message(
+        sk,
+        &f.params.owner,
+        GroupDirectMessage {
+            sender: MemberId::from(&sk.verifying_key()),
+            timestamp,
+            sealed_keys: ids
+                .into_iter()
+                .map(|recipient| SealedGroupKey {
+                    recipient,
+                    sealed_key: vec![0x5A; SEALED_GROUP_KEY_BYTES],
+                })
+                .collect(),
+            ciphertext: timestamp.to_le_bytes().to_vec(),
+        },
+    )
+    .expect("sign_group_direct_message")
+}
+
+fn abc(f: &Fixture) -> [MemberId; 3] {
+    [f.alice_id, f.bob_id, f.carol_id]
+}
+
+fn purges(
+    f: &Fixture,
+    sk: &SigningKey,
+    version: u64,
+    purged: Vec<PurgeToken>,
+) -> AuthorizedRecipientPurges {
+    sign_recipient_purges(
+        sk,
+        MemberId::from(&sk.verifying_key()),
+        &f.params.owner,
+        RecipientPurges { version, purged },
+    )
+    .expect("sign_recipient_purges")
+}
+
+fn apply_group(state: &mut ChatRoomStateV1, f: &Fixture, msgs: Vec<AuthorizedGroupDirectMessage>) {
+    let parent = state.clone();
+    state
+        .group_direct_messages
+        .apply_delta(&parent, &f.params, &Some(msgs))
+        .expect("apply_delta");
+}
+
+// ---------------------------------------------------------------------------
+// Shape and authorisation
+// ---------------------------------------------------------------------------
+
+#[test]
+fn group_message_verifies_in_full_room_state() {
+    let f = make_fixture();
+    let msg = group_at(&f, &f.alice_sk, &abc(&f), 1);
+    let mut state = f.state.clone();
+    state.group_direct_messages.messages.push(msg);
+    state.verify(&state, &f.params).expect("state must verify");
+}
+
+#[test]
+fn thread_id_ignores_participant_order_and_sender() {
+    let f = make_fixture();
+    let from_alice = group_at(&f, &f.alice_sk, &abc(&f), 1);
+    let from_carol = group_at(&f, &f.carol_sk, &[f.carol_id, f.alice_id, f.bob_id], 2);
+    assert_eq!(
+        from_alice.message.thread_id(),
+        from_carol.message.thread_id()
+    );
+    assert_eq!(
+        from_alice.message.thread_id(),
+        GroupThreadId::for_participants([f.carol_id, f.bob_id, f.alice_id, f.bob_id])
+    );
+    let with_owner = group_at(&f, &f.alice_sk, &[f.alice_id, f.bob_id, f.owner_id], 3);
+    assert_ne!(
+        from_alice.message.thread_id(),
+        with_owner.message.thread_id()
+    );
+}
+
+#[test]
+fn two_participants_rejected_at_signing_time() {
+    let f = make_fixture();
+    let err = sign_group_direct_message(
+        &f.alice_sk,
+        &f.params.owner,
+        GroupDirectMessage {
+            sender: f.alice_id,
+            timestamp: 1,
+            sealed_keys: [f.alice_id, f.bob_id]
+                .into_iter()
+                .map(|recipient| SealedGroupKey {
+                    recipient,
+                    sealed_key: vec![0; SEALED_GROUP_KEY_BYTES],
+                })
+                .collect(),
+            ciphertext: vec![1],
+        },
+    )
+    .unwrap_err();
+    assert!(err.contains("participants"), "{err}");
+}
+
+#[test]
+fn unsorted_or_duplicate_participants_rejected() {
+    let f = make_fixture();
+    let mut msg = group_at(&f, &f.alice_sk, &abc(&f), 1);
+    msg.message.sealed_keys.swap(0, 2);
+    let mut state = f.state.clone();
+    state.group_direct_messages.messages.push(msg.clone());
+    assert!(state.verify(&state, &f.params).is_err());
+
+    msg.message.sealed_keys.sort_by_key(|k| k.recipient);
+    msg.message.sealed_keys[1] = msg.message.sealed_keys[0].clone();
+    state.group_direct_messages.messages = vec![msg];
+    assert!(state.verify(&state, &f.params).is_err());
+}
+
+#[test]
+fn sender_must_be_a_participant() {
+    let f = make_fixture();
+    let owner_sent = group_at(&f, &f.alice_sk, &abc(&f), 1);
+    let mut forged = owner_sent.message.clone();
+    forged.sender = f.owner_id;
+    let err = sign_group_direct_message(&f.owner_sk, &f.params.owner, forged).unwrap_err();
+    assert!(err.contains("sender must be a participant"), "{err}");
+}
+
+#[test]
+fn wrong_sized_sealed_key_rejected() {
+    let f = make_fixture();
+    let mut msg = group_at(&f, &f.alice_sk, &abc(&f), 1).message;
+    msg.sealed_keys[0].sealed_key.push(0);
+    assert!(sign_group_direct_message(&f.alice_sk, &f.params.owner, msg).is_err());
+}
+
+#[test]
+fn non_member_participant_rejected_in_verify_and_dropped_in_apply_delta() {
+    let f = make_fixture();
+    let outsider = MemberId::from(&SigningKey::generate(&mut OsRng).verifying_key());
+    let msg = group_at(&f, &f.alice_sk, &[f.alice_id, f.bob_id, outsider], 1);
+
+    let mut state = f.state.clone();
+    state.group_direct_messages.messages.push(msg.clone());
+    let err = state.verify(&state, &f.params).unwrap_err();
+    assert!(err.contains("not a current member"), "{err}");
+
+    let mut state = f.state.clone();
+    apply_group(&mut state, &f, vec![msg]);
+    assert!(state.group_direct_messages.messages.is_empty());
+}
+
+#[test]
+fn tampered_signature_rejected() {
+    let f = make_fixture();
+    let mut msg = group_at(&f, &f.alice_sk, &abc(&f), 1);
+    msg.message.ciphertext.push(0xFF);
+    let mut state = f.state.clone();
+    state.group_direct_messages.messages.push(msg.clone());
+    assert!(state.verify(&state, &f.params).is_err());
+
+    let mut state = f.state.clone();
+    apply_group(&mut state, &f, vec![msg]);
+    assert!(state.group_direct_messages.messages.is_empty());
+}
+
+#[test]
+fn owner_can_join_a_group() {
+    let f = make_fixture();
+    let msg = group_at(&f, &f.owner_sk, &[f.owner_id, f.alice_id, f.bob_id], 1);
+    let mut state = f.state.clone();
+    apply_group(&mut state, &f, vec![msg]);
+    assert_eq!(state.group_direct_messages.messages.len(), 1);
+    state.verify(&state, &f.params).expect("state must verify");
+}
+
+// ---------------------------------------------------------------------------
+// Per-participant purges
+// ---------------------------------------------------------------------------
+
+#[test]
+fn purge_by_one_recipient_hides_it_for_them_only() {
+    let f = make_fixture();
+    let msg = group_at(&f, &f.alice_sk, &abc(&f), 1);
+    let mut state = f.state.clone();
+    state
+        .direct_messages
+        .purges
+        .push(purges(&f, &f.bob_sk, 1, vec![msg.purge_token()]));
+    apply_group(&mut state, &f, vec![msg]);
+
+    let held = &state.group_direct_messages.messages;
+    assert_eq!(held.len(), 1, "Carol hasn't purged it yet");
+    assert!(held[0].purged_by(f.bob_id, &state));
+    assert!(!held[0].purged_by(f.carol_id, &state));
+    assert!(!held[0].purged_by(f.alice_id, &state));
+    state.verify(&state, &f.params).expect("state must verify");
+}
+
+#[test]
+fn purge_by_every_recipient_drops_and_blocks_remerge() {
+    let f = make_fixture();
+    let msg = group_at(&f, &f.alice_sk, &abc(&f), 1);
+    let token = msg.purge_token();
+    let mut state = f.state.clone();
+    apply_group(&mut state, &f, vec![msg.clone()]);
+
+    // Both recipients purge in one room delta; the group field sees the new
+    // envelopes because it is applied after `direct_messages`.
+    let delta = ChatRoomStateV1Delta {
+        direct_messages: Some(DirectMessagesDelta {
+            new_messages: vec![],
+            advanced_purges: vec![
+                purges(&f, &f.bob_sk, 1, vec![token]),
+                purges(&f, &f.carol_sk, 1, vec![token]),
+            ],
+        }),
+        ..Default::default()
+    };
+    state = {
+        let mut next = state.clone();
+        next.apply_delta(&state, &f.params, &Some(delta)).unwrap();
+        next
+    };
+    assert!(state.group_direct_messages.messages.is_empty());
+    state.verify(&state, &f.params).expect("state must verify");
+
+    // A stale peer re-sends it.
+    apply_group(&mut state, &f, vec![msg.clone()]);
+    assert!(state.group_direct_messages.messages.is_empty());
+
+    // Holding it alongside every recipient's tombstone is invalid.
+    state.group_direct_messages.messages.push(msg);
+    assert!(state.verify(&state, &f.params).is_err());
+}
+
+#[test]
+fn senders_own_purge_is_not_needed_for_removal() {
+    let f = make_fixture();
+    let msg = group_at(&f, &f.bob_sk, &abc(&f), 1);
+    let token = msg.purge_token();
+    let mut state = f.state.clone();
+    state.direct_messages.purges.extend([
+        purges(&f, &f.alice_sk, 1, vec![token]),
+        purges(&f, &f.carol_sk, 1, vec![token]),
+    ]);
+    apply_group(&mut state, &f, vec![msg]);
+    assert!(state.group_direct_messages.messages.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Membership changes
+// ---------------------------------------------------------------------------
+
+#[test]
+fn post_apply_cleanup_sweeps_groups_with_a_banned_participant() {
+    let f = make_fixture();
+    let mut state = f.state.clone();
+    state
+        .group_direct_messages
+        .messages
+        .push(group_at(&f, &f.alice_sk, &abc(&f), 1));
+    state.bans.0.push(AuthorizedUserBan::new(
+        UserBan {
+            owner_member_id: f.owner_id,
+            banned_at: SystemTime::now(),
+            banned_user: f.carol_id,
+        },
+        f.owner_id,
+        &f.owner_sk,
+    ));
+    state.post_apply_cleanup(&f.params).unwrap();
+
+    assert!(state.group_direct_messages.messages.is_empty());
+    state.verify(&state, &f.params).expect("state must verify");
+}
+
+#[test]
+fn post_apply_cleanup_retains_group_participants_as_members() {
+    let f = make_fixture();
+    let mut state = f.state.clone();
+    state
+        .group_direct_messages
+        .messages
+        .push(group_at(&f, &f.alice_sk, &abc(&f), 1));
+    state.post_apply_cleanup(&f.params).unwrap();
+
+    let member_ids: HashSet<MemberId> = state
+        .members
+        .members
+        .iter()
+        .map(|m| m.member.id())
+        .collect();
+    for id in abc(&f) {
+        assert!(member_ids.contains(&id), "{id:?} must be retained");
+    }
+    assert_eq!(state.group_direct_messages.messages.len(), 1);
+    state.verify(&state, &f.params).expect("state must verify");
+}
+
+// ---------------------------------------------------------------------------
+// Cap, merge and wire format
+// ---------------------------------------------------------------------------
+
+#[test]
+fn cap_keeps_the_newest_messages() {
+    let f = make_fixture();
+    let msgs: Vec<_> = (1..=(MAX_GROUP_DIRECT_MESSAGES as u64 + 5))
+        .map(|ts| group_at(&f, &f.alice_sk, &abc(&f), ts))
+        .collect();
+    let mut state = f.state.clone();
+    apply_group(&mut state, &f, msgs);
+
+    let held = &state.group_direct_messages.messages;
+    assert_eq!(held.len(), MAX_GROUP_DIRECT_MESSAGES);
+    assert_eq!(held.iter().map(|m| m.message.timestamp).min(), Some(6));
+}
+
+#[test]
+fn delta_withholds_what_the_receivers_cap_would_discard() {
+    let f = make_fixture();
+    let mut full = f.state.clone();
+    apply_group(
+        &mut full,
+        &f,
+        (10..(10 + MAX_GROUP_DIRECT_MESSAGES as u64))
+            .map(|ts| group_at(&f, &f.alice_sk, &abc(&f), ts))
+            .collect(),
+    );
+    let summary = full.group_direct_messages.summarize(&full, &f.params);
+
+    let mut other = f.state.clone();
+    apply_group(
+        &mut other,
+        &f,
+        vec![
+            group_at(&f, &f.bob_sk, &abc(&f), 1),
+            group_at(&f, &f.bob_sk, &abc(&f), 500),
+        ],
+    );
+    let delta = other
+        .group_direct_messages
+        .delta(&other, &f.params, &summary)
+        .expect("the newer message is still offered");
+    assert_eq!(delta.len(), 1);
+    assert_eq!(delta[0].message.timestamp, 500);
+}
+
+#[test]
+fn apply_delta_is_commutative_and_idempotent() {
+    let f = make_fixture();
+    let a = group_at(&f, &f.alice_sk, &abc(&f), 1);
+    let b = group_at(&f, &f.bob_sk, &[f.owner_id, f.alice_id, f.bob_id], 2);
+    let c = group_at(&f, &f.carol_sk, &abc(&f), 3);
+
+    let mut left = f.state.clone();
+    apply_group(&mut left, &f, vec![a.clone(), b.clone()]);
+    apply_group(&mut left, &f, vec![c.clone()]);
+
+    let mut right = f.state.clone();
+    apply_group(&mut right, &f, vec![c, a.clone()]);
+    apply_group(&mut right, &f, vec![b, a]);
+
+    assert_eq!(left.group_direct_messages, right.group_direct_messages);
+    assert_eq!(left.group_direct_messages.messages.len(), 3);
+}
+
+#[test]
+fn thread_returns_one_threads_messages_oldest_first() {
+    let f = make_fixture();
+    let mut state = f.state.clone();
+    apply_group(
+        &mut state,
+        &f,
+        vec![
+            group_at(&f, &f.bob_sk, &abc(&f), 5),
+            group_at(&f, &f.alice_sk, &[f.owner_id, f.alice_id, f.bob_id], 4),
+            group_at(&f, &f.carol_sk, &abc(&f), 2),
+        ],
+    );
+    let thread = GroupThreadId::for_participants(abc(&f));
+    let timestamps: Vec<u64> = state
+        .group_direct_messages
+        .thread(thread)
+        .iter()
+        .map(|m| m.message.timestamp)
+        .collect();
+    assert_eq!(timestamps, vec![2, 5]);
+}
+
+#[test]
+fn serde_default_lets_pre_group_state_decode() {
+    let f = make_fixture();
+    let mut value = serde_json::to_value(&f.state).unwrap();
+    value
+        .as_object_mut()
+        .unwrap()
+        .remove("group_direct_messages");
+    let decoded: ChatRoomStateV1 = serde_json::from_value(value).unwrap();
+    assert_eq!(decoded, f.state);
+}
+
+#[test]
+fn cannot_exceed_max_participants() {
+    let f = make_fixture();
+    let mut ids: Vec<MemberId> = (0..MAX_GROUP_DM_PARTICIPANTS)
+        .map(|_| MemberId::from(&SigningKey::generate(&mut OsRng).verifying_key()))
+        .collect();
+    ids.push(f.alice_id);
+    ids.sort();
+    let err = sign_group_direct_message(
+        &f.alice_sk,
+        &f.params.owner,
+        GroupDirectMessage {
+            sender: f.alice_id,
+            timestamp: 1,
+            sealed_keys: ids
+                .into_iter()
+                .map(|recipient| SealedGroupKey {
+                    recipient,
+                    sealed_key: vec![0; SEALED_GROUP_KEY_BYTES],
+                })
+                .collect(),
+            ciphertext: vec![],
+        },
+    )
+    .unwrap_err();
+    assert!(err.contains("participants"), "{err}");
+}
+
+// ---------------------------------------------------------------------------
+// compose_group_direct_message / open_group_direct_message
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "ecies-randomized")]
+mod end_to_end_helpers {
+    use super::*;
+    use river_core::room_state::group_direct_messages::{
+        compose_group_direct_message, open_group_direct_message,
+    };
+
+    #[test]
+    fn every_participant_can_open_and_outsiders_cannot() {
+        let f = make_fixture();
+        let body = b"lunch at noon?";
+        let msg = compose_group_direct_message(
+            &f.alice_sk,
+            &[f.bob_sk.verifying_key(), f.carol_sk.verifying_key()],
+            &f.params.owner,
+            1,
+            1,
+            body,
+        )
+        .expect("compose_group_direct_message");
+
+        let mut state = f.state.clone();
+        state.group_direct_messages.messages.push(msg.clone());
+        state.verify(&state, &f.params).expect("state must verify");
+
+        for sk in [&f.alice_sk, &f.bob_sk, &f.carol_sk] {
+            assert_eq!(open_group_direct_message(sk, &msg).unwrap(), body);
+        }
+        assert!(open_group_direct_message(&f.owner_sk, &msg).is_err());
+    }
+
+    #[test]
+    fn compose_ignores_duplicate_and_self_keys() {
+        let f = make_fixture();
+        let alice_vk = f.alice_sk.verifying_key();
+        let bob_vk = f.bob_sk.verifying_key();
+        let carol_vk = f.carol_sk.verifying_key();
+        let msg = compose_group_direct_message(
+            &f.alice_sk,
+            &[bob_vk, alice_vk, carol_vk, bob_vk],
+            &f.params.owner,
+            1,
+            1,
+            b"hi",
+        )
+        .unwrap();
+        assert_eq!(msg.message.participants().count(), 3);
+    }
+
+    #[test]
+    fn compose_rejects_a_pair() {
+        let f = make_fixture();
+        let err = compose_group_direct_message(
+            &f.alice_sk,
+            &[f.bob_sk.verifying_key()],
+            &f.params.owner,
+            1,
+            1,
+            b"just us",
+        )
+        .unwrap_err();
+        assert!(err.contains("participants"), "{err}");
+    }
+}
@@ -967,6 +967,7 @@ fn issue_318_member_info_apply_delta_has_no_contract_privacy_guard() {
         version: 1,
         preferred_nickname: SealedBytes::public(b"PlaintextNick".to_vec()),
         deputies: Vec::new(),
+        profile: None,
     };
     let authorized = AuthorizedMemberInfo::new_with_member_key(public_nickname, &member_sk);
 
@@ -1515,3 +1516,305 @@ fn delta_with_rotation_plus_message_at_new_version_applies_atomically() {
         "message at v1 must be stored after combined rotation+message delta"
     );
 }
+
+// ---------------------------------------------------------------------------
+// Secret-version garbage collection
+// ---------------------------------------------------------------------------
+
+/// A private room with the owner and one member, `versions` secret versions
+/// (the last one current) and a blob for each of them at every version.
+fn room_with_rotations(
+    versions: u32,
+) -> (
+    ChatRoomStateV1,
+    ChatRoomParametersV1,
+    SigningKey,
+    SigningKey,
+) {
+    let owner_sk = SigningKey::generate(&mut OsRng);
+    let owner_vk = owner_sk.verifying_key();
+    let owner_id = MemberId::from(&owner_vk);
+    let member_sk = SigningKey::generate(&mut OsRng);
+
+    let mut state = ChatRoomStateV1 {
+        configuration: AuthorizedConfigurationV1::new(
+            Configuration {
+                privacy_mode: PrivacyMode::Private,
+                owner_member_id: owner_id,
+                ..Default::default()
+            },
+            &owner_sk,
+        ),
+        ..Default::default()
+    };
+    state.members.members.push(AuthorizedMember::new(
+        Member {
+            owner_member_id: owner_id,
+            invited_by: owner_id,
+            member_vk: member_sk.verifying_key(),
+        },
+        &owner_sk,
+    ));
+
+    state.secrets.current_version = versions - 1;
+    for version in 0..versions {
+        state
+            .secrets
+            .versions
+            .push(AuthorizedSecretVersionRecord::new(
+                SecretVersionRecordV1 {
+                    version,
+                    cipher_spec: RoomCipherSpec::Aes256Gcm,
+                    created_at: SystemTime::now(),
+                },
+                &owner_sk,
+            ));
+        let secret = generate_room_secret();
+        for vk in [owner_vk, member_sk.verifying_key()] {
+            let (ciphertext, nonce, ephemeral) = encrypt_secret_for_member(&secret, &vk);
+            state
+                .secrets
+                .encrypted_secrets
+                .push(AuthorizedEncryptedSecretForMember::new(
+                    EncryptedSecretForMemberV1 {
+                        member_id: MemberId::from(&vk),
+                        secret_version: version,
+                        ciphertext,
+                        nonce,
+                        sender_ephemeral_public_key: ephemeral,
+                        provider: owner_id,
+                    },
+                    &owner_sk,
+                ));
+        }
+    }
+    state
+        .secrets
+        .encrypted_secrets
+        .sort_by_key(|s| (s.secret.secret_version, s.secret.member_id));
+
+    (
+        state,
+        ChatRoomParametersV1 { owner: owner_vk },
+        owner_sk,
+        member_sk,
+    )
+}
+
+fn private_message(
+    owner_sk: &SigningKey,
+    author_sk: &SigningKey,
+    secret_version: u32,
+    secs: u64,
+) -> AuthorizedMessageV1 {
+    AuthorizedMessageV1::new(
+        MessageV1 {
+            room_owner: MemberId::from(&owner_sk.verifying_key()),
+            author: MemberId::from(&author_sk.verifying_key()),
+            time: SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000 + secs),
+            content: RoomMessageBody::private_text(vec![1, 2, 3, 4], [0u8; 12], secret_version),
+        },
+        author_sk,
+    )
+}
+
+fn blob_versions(state: &ChatRoomStateV1) -> Vec<u32> {
+    state
+        .secrets
+        .encrypted_secrets
+        .iter()
+        .map(|s| s.secret.secret_version)
+        .collect()
+}
+
+/// Blobs for versions older than every retained message go; the version
+/// records, and the current version's complete distribution, stay. When the
+/// last message at a version scrolls out of `recent_messages`, its blobs go
+/// with it.
+#[test]
+fn superseded_secret_blobs_are_dropped_once_no_retained_message_uses_them() {
+    use river_core::room_state::ChatRoomStateV1Delta;
+
+    let (mut state, params, owner_sk, member_sk) = room_with_rotations(3);
+    state.configuration = AuthorizedConfigurationV1::new(
+        Configuration {
+            max_recent_messages: 1,
+            ..state.configuration.configuration.clone()
+        },
+        &owner_sk,
+    );
+    state
+        .recent_messages
+        .messages
+        .push(private_message(&owner_sk, &member_sk, 1, 1));
+
+    state.post_apply_cleanup(&params).unwrap();
+    assert_eq!(blob_versions(&state), vec![1, 1, 2, 2]);
+    assert_eq!(state.secrets.versions.len(), 3);
+    assert!(state
+        .secrets
+        .has_complete_distribution(&state.members.members_by_member_id()));
+    state
+        .verify(&state, &params)
+        .expect("pruned state verifies");
+
+    let once = state.clone();
+    state.post_apply_cleanup(&params).unwrap();
+    assert_eq!(state, once, "cleanup must be idempotent");
+
+    // A newer message at v2 evicts the only v1 message.
+    let delta = ChatRoomStateV1Delta {
+        recent_messages: Some(vec![private_message(&owner_sk, &member_sk, 2, 2)]),
+        ..Default::default()
+    };
+    state
+        .apply_delta(&state.clone(), &params, &Some(delta))
+        .unwrap();
+    assert_eq!(blob_versions(&state), vec![2, 2]);
+    assert!(state
+        .secrets
+        .has_complete_distribution(&state.members.members_by_member_id()));
+}
+
+/// Private nicknames and the private room name pin the version they are
+/// sealed under just as messages do.
+#[test]
+fn sealed_nickname_and_room_name_keep_their_secret_version() {
+    use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfo};
+
+    let (mut state, params, owner_sk, member_sk) = room_with_rotations(4);
+    let member_id = MemberId::from(&member_sk.verifying_key());
+    let nickname = |version: u32, secret_version: u32| {
+        AuthorizedMemberInfo::new_with_member_key(
+            MemberInfo::new_private(member_id, version, vec![9; 16], [0; 12], secret_version, 5),
+            &member_sk,
+        )
+    };
+
+    state.member_info.member_info = vec![nickname(1, 0)];
+    let before = blob_versions(&state);
+    state.post_apply_cleanup(&params).unwrap();
+    assert_eq!(
+        blob_versions(&state),
+        before,
+        "a v0 nickname keeps every version"
+    );
+
+    // The member re-seals their nickname at v3, and the owner renames the
+    // room under v2: v0 and v1 are no longer used by anything.
+    state.member_info.member_info = vec![nickname(2, 3)];
+    state.configuration = AuthorizedConfigurationV1::new(
+        Configuration {
+            configuration_version: 2,
+            display: RoomDisplayMetadata {
+                name: SealedBytes::private(vec![8; 16], [0; 12], 2, 4),
+                description: None,
+            },
+            ..state.configuration.configuration.clone()
+        },
+        &owner_sk,
+    );
+    state.post_apply_cleanup(&params).unwrap();
+    assert_eq!(blob_versions(&state), vec![2, 2, 3, 3]);
+}
+
+/// A profile field sealed before a rotation pins its version too, through
+/// cleanup and through the prune horizon peers filter deltas by.
+#[test]
+fn sealed_profile_field_keeps_its_secret_version() {
+    use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfo, MemberProfile};
+
+    let (mut state, params, _owner_sk, member_sk) = room_with_rotations(3);
+    let member_id = MemberId::from(&member_sk.verifying_key());
+    // Nickname re-sealed at the current version; the bio still at v0.
+    let mut info = MemberInfo::new_private(member_id, 1, vec![9; 16], [0; 12], 2, 5);
+    info.profile = Some(MemberProfile {
+        bio: Some(SealedBytes::private(vec![7; 16], [0; 12], 0, 3)),
+        ..Default::default()
+    });
+    state.member_info.member_info =
+        vec![AuthorizedMemberInfo::new_with_member_key(info, &member_sk)];
+
+    let before = blob_versions(&state);
+    state.post_apply_cleanup(&params).unwrap();
+    assert_eq!(
+        blob_versions(&state),
+        before,
+        "the v0 bio keeps every version"
+    );
+
+    let mut behind = state.clone();
+    behind
+        .secrets
+        .encrypted_secrets
+        .retain(|s| s.secret.secret_version != 0);
+    let summary = behind.summarize(&behind, &params);
+    assert_eq!(summary.secrets.blobs_from, 0);
+    let offered = state
+        .delta(&state, &params, &summary)
+        .and_then(|delta| delta.secrets)
+        .expect("the v0 blobs are offered to a peer missing them");
+    assert!(offered
+        .new_encrypted_secrets
+        .iter()
+        .all(|s| s.secret.secret_version == 0));
+    assert_eq!(offered.new_encrypted_secrets.len(), 2);
+}
+
+/// A peer that has already pruned and one still holding the old blobs (a
+/// state that arrived by full-state PUT, which runs `verify` but not
+/// cleanup) converge on the pruned state.
+#[test]
+fn secret_gc_converges_between_pruned_and_unpruned_peers() {
+    use river_core::room_state::ChatRoomStateV1Delta;
+
+    let (mut unpruned, params, owner_sk, member_sk) = room_with_rotations(3);
+    unpruned
+        .recent_messages
+        .messages
+        .push(private_message(&owner_sk, &member_sk, 2, 1));
+    unpruned.verify(&unpruned, &params).unwrap();
+    let mut pruned = unpruned.clone();
+    pruned.post_apply_cleanup(&params).unwrap();
+    assert_eq!(blob_versions(&pruned), vec![2, 2]);
+
+    // Delta sync: the pruned peer's summary says which blobs it dropped, so
+    // the unpruned peer does not offer them back on every round.
+    let summary = pruned.summarize(&pruned, &params);
+    assert_eq!(unpruned.delta(&unpruned, &params, &summary), None);
+    let summary = unpruned.summarize(&unpruned, &params);
+    assert_eq!(pruned.delta(&pruned, &params, &summary), None);
+
+    // A peer that predates pruning summarizes no horizon and still gets the
+    // old blobs; they are accepted and dropped again.
+    let mut legacy = pruned.summarize(&pruned, &params);
+    legacy.secrets.blobs_from = 0;
+    let offered = unpruned
+        .delta(&unpruned, &params, &legacy)
+        .expect("the unpruned peer holds blobs the pruned one does not");
+    let mut synced = pruned.clone();
+    synced
+        .apply_delta(&pruned, &params, &Some(offered))
+        .expect("re-offered blobs are accepted");
+    assert_eq!(synced, pruned);
+
+    // Same for a full-state merge.
+    let mut merged = pruned.clone();
+    merged.merge(&pruned, &params, &unpruned).unwrap();
+    assert_eq!(merged, pruned);
+
+    // The pruned peer has nothing the unpruned one lacks, so the unpruned
+    // peer catches up with its next real update, whichever peer it came
+    // through first.
+    let next = ChatRoomStateV1Delta {
+        recent_messages: Some(vec![private_message(&owner_sk, &owner_sk, 2, 2)]),
+        ..Default::default()
+    };
+    let mut a = pruned.clone();
+    a.apply_delta(&pruned, &params, &Some(next.clone()))
+        .unwrap();
+    let mut b = unpruned.clone();
+    b.apply_delta(&unpruned, &params, &Some(next)).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(blob_versions(&b), vec![2, 2]);
+}
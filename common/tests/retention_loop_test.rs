@@ -739,6 +739,140 @@ fn ban_sweep_reopening_the_horizon_still_loops_known_gap() {
     }
 }
 
+// ---------------------------------------------------------------------------
+// MessagesV1 — message TTL
+// ---------------------------------------------------------------------------
+
+/// The same room, re-signed with a `message_ttl` of `secs`.
+fn state_with_ttl(r: &Room, secs: u64) -> ChatRoomStateV1 {
+    let mut cfg = r.state.configuration.configuration.clone();
+    cfg.message_ttl = Some(secs);
+    ChatRoomStateV1 {
+        configuration: AuthorizedConfigurationV1::new(cfg, &r.owner_sk),
+        ..r.state.clone()
+    }
+}
+
+/// Age is measured from the newest message held, not a clock, and a message
+/// exactly `message_ttl` older than the newest is already gone.
+#[test]
+fn ttl_expires_messages_relative_to_the_newest_held() {
+    let r = room(100);
+    let parent = state_with_ttl(&r, 4);
+
+    let m = messages_holding_under(&r, &parent, [0, 1, 2, 10, 12, 14]);
+    assert_eq!(
+        held(&m),
+        vec![12, 14],
+        "14 - 4 = 10, so 10 and older expire"
+    );
+
+    let summary = m.summarize(&parent, &r.params);
+    let RetentionHorizon::OldestRetained(key) = summary.horizon else {
+        panic!("a TTL room must publish its expiry horizon even below the cap");
+    };
+    assert_eq!(
+        key.time,
+        SystemTime::UNIX_EPOCH + Duration::from_secs(BASE_SECS + 10),
+        "the horizon sits at the expiry cutoff"
+    );
+}
+
+/// A quiet TTL room keeps its expired messages until something newer arrives;
+/// the next message then expires the old window in the same `apply_delta`.
+#[test]
+fn a_newer_message_expires_the_window_on_arrival() {
+    let r = room(100);
+    let parent = state_with_ttl(&r, 10);
+
+    let mut m = messages_holding_under(&r, &parent, 0..5);
+    assert_eq!(held(&m), vec![0, 1, 2, 3, 4], "nothing is 10s older than 4");
+
+    m.apply_delta(&parent, &r.params, &Some(vec![msg_at(&r, 12)]))
+        .expect("apply newer message");
+    assert_eq!(held(&m), vec![3, 4, 12], "12 - 10 = 2, so 0..=2 expire");
+
+    // Re-applying the normalised state expires nothing further.
+    let before = held(&m);
+    m.apply_delta(&parent, &r.params, &Some(vec![]))
+        .expect("re-apply");
+    assert_eq!(held(&m), before);
+}
+
+/// Pins the failure mode `Configuration::message_ttl` documents: one
+/// far-future message expires the history and every honestly timed message
+/// after it, until its author is removed from the room.
+#[test]
+fn a_far_future_message_mutes_a_ttl_room_until_its_author_is_removed() {
+    let r = room(100);
+    let parent = state_with_ttl(&r, 60);
+    let owner_msg = |secs: u64| {
+        AuthorizedMessageV1::new(
+            MessageV1 {
+                room_owner: r.owner_id,
+                author: r.owner_id,
+                time: SystemTime::UNIX_EPOCH + Duration::from_secs(BASE_SECS + secs),
+                content: RoomMessageBody::public(format!("owner {secs}")),
+            },
+            &r.owner_sk,
+        )
+    };
+
+    let mut m = MessagesV1::default();
+    m.apply_delta(&parent, &r.params, &Some((0..5).map(owner_msg).collect()))
+        .unwrap();
+    assert_eq!(held(&m), vec![0, 1, 2, 3, 4]);
+
+    let far = 10 * 365 * 24 * 3600;
+    m.apply_delta(&parent, &r.params, &Some(vec![msg_at(&r, far)]))
+        .unwrap();
+    assert_eq!(held(&m), vec![far], "the history is gone");
+    m.apply_delta(&parent, &r.params, &Some(vec![owner_msg(5)]))
+        .unwrap();
+    assert_eq!(
+        held(&m),
+        vec![far],
+        "and new messages are dropped on arrival"
+    );
+
+    let mut banned = parent.clone();
+    banned
+        .members
+        .members
+        .retain(|member| member.member.id() != r.author_id);
+    m.apply_delta(&banned, &r.params, &Some(vec![owner_msg(6)]))
+        .unwrap();
+    assert_eq!(
+        held(&m),
+        vec![6],
+        "removing the author lets messages land again"
+    );
+}
+
+/// The lagging-peer loop, with the receiver's TTL (not its cap) as the reason
+/// it discards the sender's older window. A peer that has not yet seen the TTL
+/// configuration must still stop offering what the TTL peer expires.
+#[test]
+fn gossip_to_a_ttl_peer_from_a_lagging_peer_terminates() {
+    let r = room(100);
+    let ttl_parent = state_with_ttl(&r, 5);
+
+    let mut a = messages_holding_under(&r, &ttl_parent, 20..26);
+    let b = messages_holding(&r, 10..23);
+    assert_eq!(held(&a), (21..26).collect::<Vec<_>>(), "test premise");
+
+    for round in 0..10 {
+        let offered = b.delta(&ttl_parent, &r.params, &a.summarize(&ttl_parent, &r.params));
+        let Some(offered) = offered else {
+            assert_eq!(held(&a), (21..26).collect::<Vec<_>>());
+            return;
+        };
+        a.apply_delta(&ttl_parent, &r.params, &Some(offered))
+            .unwrap_or_else(|e| panic!("round {round} apply failed: {e}"));
+    }
+    panic!("the lagging peer was still offering expired messages after 10 rounds");
+}
+
 // ---------------------------------------------------------------------------
 // DirectMessagesV1
 // ---------------------------------------------------------------------------
@@ -1270,6 +1270,216 @@ fn the_generator_reaches_peers_at_the_global_dm_cap() {
     );
 }
 
+// ---------------------------------------------------------------------------
+// MessagesV1 — message TTL (disappearing messages)
+// ---------------------------------------------------------------------------
+//
+// `message_ttl` is a second pruning rule alongside the count cap: drop every
+// message at or before `newest - ttl`. It is clock-free, so it is a pure
+// function of the held set and the same laws apply. Pool timestamps span
+// `MSG_POOL / MSG_PER_TIMESTAMP` seconds, so TTLs in `1..=MAX_TTL` range from
+// "keep only the newest second" to "expire nothing".
+
+/// The largest TTL (seconds) generated. One past the pool's time span, so the
+/// rule is sometimes slack.
+const MAX_TTL: u64 = (MSG_POOL / MSG_PER_TIMESTAMP) as u64 + 1;
+
+/// `parent_with_cap`, plus a message TTL.
+fn parent_with_ttl(cap: usize, ttl: Option<u64>) -> ChatRoomStateV1 {
+    let f = fixture();
+    ChatRoomStateV1 {
+        configuration: AuthorizedConfigurationV1::new(
+            Configuration {
+                max_recent_messages: cap,
+                max_message_size: 1_000,
+                max_members: 16,
+                message_ttl: ttl,
+                ..Default::default()
+            },
+            &f.owner_sk,
+        ),
+        members: f.members.clone(),
+        ..Default::default()
+    }
+}
+
+/// The TTL oracle: drop everything at or before `newest - ttl` in the union,
+/// then keep the newest `cap` of what survives. The two rules each keep a
+/// suffix of the sorted keys, so their order does not matter.
+fn unexpired_message_keys(
+    mut keys: Vec<MessageOrderKey>,
+    cap: usize,
+    ttl: Option<u64>,
+) -> Vec<MessageOrderKey> {
+    keys.sort();
+    keys.dedup();
+    if let (Some(ttl), Some(newest)) = (ttl, keys.last().map(|k| k.time)) {
+        let cutoff = newest - Duration::from_secs(ttl);
+        keys.retain(|k| k.time > cutoff);
+    }
+    newest_message_keys(keys, cap)
+}
+
+fn ttl_strategy() -> BoxedStrategy<Option<u64>> {
+    prop_oneof![1 => Just(None), 4 => (1..=MAX_TTL).prop_map(Some)].boxed()
+}
+
+/// Retention under a TTL: a merge keeps EXACTLY the unexpired newest-`cap` of
+/// the union, with age measured from the union's newest message. Exact
+/// equality, so an expiry key that over-states (withholding a message the
+/// receiver would have kept) fails as surely as one that stops expiring.
+#[test]
+fn a_ttl_merge_retains_exactly_the_unexpired_newest_cap_of_the_union() {
+    let f = fixture();
+    check(
+        512,
+        (
+            held_indices(MSG_POOL),
+            held_indices(MSG_POOL),
+            0..=MAX_CAP,
+            ttl_strategy(),
+            ttl_strategy(),
+        ),
+        |(idx_a, idx_b, cap, ttl_a, ttl_b)| {
+            let parent_a = parent_with_ttl(cap, ttl_a);
+            let parent_b = parent_with_ttl(MAX_CAP, ttl_b);
+
+            let mut a = messages_from(&parent_a, &idx_a);
+            let b = messages_from(&parent_b, &idx_b);
+            let expected = unexpired_message_keys(message_key_union(&a, &b), cap, ttl_a);
+
+            a.merge(&parent_a, &f.params, &b).expect("a.merge(b)");
+
+            prop_assert_eq!(
+                message_keys(&a),
+                expected,
+                "the merge did not retain exactly the unexpired newest {} (ttl {:?})",
+                cap,
+                ttl_a
+            );
+            Ok(())
+        },
+    );
+}
+
+/// Delta termination under a TTL, including peers whose TTLs differ: after A
+/// merges B, B has nothing A would keep, so its delta is `None`.
+#[test]
+fn merging_a_peer_under_a_ttl_leaves_it_with_nothing_further_to_offer() {
+    let f = fixture();
+    check(
+        512,
+        (
+            held_indices(MSG_POOL),
+            held_indices(MSG_POOL),
+            0..=MAX_CAP,
+            ttl_strategy(),
+            ttl_strategy(),
+        ),
+        |(idx_a, idx_b, cap, ttl_a, ttl_b)| {
+            let parent_a = parent_with_ttl(cap, ttl_a);
+            let parent_b = parent_with_ttl(MAX_CAP, ttl_b);
+
+            let mut a = messages_from(&parent_a, &idx_a);
+            let b = messages_from(&parent_b, &idx_b);
+
+            a.merge(&parent_a, &f.params, &b).expect("a.merge(b)");
+
+            prop_assert_eq!(
+                b.delta(&parent_a, &f.params, &a.summarize(&parent_a, &f.params)),
+                None,
+                "B still had a payload for A after A merged it under ttl {:?}",
+                ttl_a
+            );
+            Ok(())
+        },
+    );
+}
+
+/// Commutativity under a TTL: expiry is measured from the newest message, which
+/// is the same whichever neighbour delivered it.
+#[test]
+fn absorbing_two_peers_under_a_ttl_is_commutative() {
+    let f = fixture();
+    check(
+        256,
+        (
+            held_indices(MSG_POOL),
+            held_indices(MSG_POOL),
+            held_indices(MSG_POOL),
+            1..=MAX_CAP,
+            1..=MAX_TTL,
+        ),
+        |(idx_base, idx_b, idx_c, cap, ttl)| {
+            let parent = parent_with_ttl(cap, Some(ttl));
+            let base = messages_from(&parent, &idx_base);
+            let b = messages_from(&parent, &idx_b);
+            let c = messages_from(&parent, &idx_c);
+
+            let mut bc = base.clone();
+            bc.merge(&parent, &f.params, &b).expect("merge b");
+            bc.merge(&parent, &f.params, &c).expect("merge c");
+
+            let mut cb = base.clone();
+            cb.merge(&parent, &f.params, &c).expect("merge c");
+            cb.merge(&parent, &f.params, &b).expect("merge b");
+
+            prop_assert_eq!(
+                message_keys(&bc),
+                message_keys(&cb),
+                "the retained window depended on which neighbour was merged first"
+            );
+            Ok(())
+        },
+    );
+}
+
+/// Whole-state gossip under a TTL: the merge terminates, and running
+/// `post_apply_cleanup` again on the merged state changes nothing. Expiry can
+/// only remove messages, which can only lower the newest time, so cleanup never
+/// has a reason to expire more on a second pass.
+#[test]
+fn whole_room_state_under_a_ttl_terminates_and_cleanup_stays_idempotent() {
+    let f = fixture();
+    check(
+        64,
+        (
+            held_indices(MSG_POOL),
+            held_indices(MSG_POOL),
+            1..=MAX_CAP,
+            1..=MAX_TTL,
+        ),
+        |(msgs_a, msgs_b, cap, ttl)| {
+            let room = |msgs: &[usize]| {
+                let parent = parent_with_ttl(cap, Some(ttl));
+                ChatRoomStateV1 {
+                    recent_messages: messages_from(&parent, msgs),
+                    // Anchors both members as DM participants, as in `room_state`.
+                    direct_messages: dms_from(&parent, &[DM_POOL - 1], &[]),
+                    ..parent
+                }
+            };
+            let mut a = room(&msgs_a);
+            let b = room(&msgs_b);
+
+            merge_incoming_state(&mut a, &f.params, &b).expect("merge incoming state");
+            prop_assert_eq!(
+                b.delta(&b, &f.params, &a.summarize(&a, &f.params)),
+                None,
+                "B still had a payload for A after A merged it under a TTL"
+            );
+
+            // Cleanup once explicitly: a `None` delta above skips it, leaving the
+            // fixture's unsorted member list in place.
+            a.post_apply_cleanup(&f.params).expect("first cleanup");
+            let once = a.clone();
+            a.post_apply_cleanup(&f.params).expect("second cleanup");
+            prop_assert_eq!(a, once, "post_apply_cleanup was not idempotent under a TTL");
+            Ok(())
+        },
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Summary determinism
 // ---------------------------------------------------------------------------
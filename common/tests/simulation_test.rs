@@ -0,0 +1,162 @@
+//! Convergence under the multi-peer simulator (`river_core::simulation`).
+//!
+//! Each test authors a handful of membership, message and ban changes on
+//! different peers, lets the simulated network mangle their delivery, and
+//! requires every peer to hold byte-identical state once it settles. A
+//! failure prints the seed and writes the trace so the run can be replayed
+//! with `riverctl debug replay`.
+#![cfg(feature = "simulation")]
+
+use ed25519_dalek::SigningKey;
+use river_core::room_state::ban::{AuthorizedUserBan, UserBan};
+use river_core::room_state::member::{AuthorizedMember, Member, MemberId, MembersDelta};
+use river_core::room_state::message::{AuthorizedMessageV1, MessageV1, RoomMessageBody};
+use river_core::room_state::{ChatRoomStateV1, ChatRoomStateV1Delta};
+use river_core::simulation::{replay, SimConfig, Simulation, Trace};
+use std::time::{Duration, SystemTime};
+
+fn key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn id(sk: &SigningKey) -> MemberId {
+    sk.verifying_key().into()
+}
+
+fn at(secs: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+}
+
+/// `sk` joins (invited by `inviter`) and posts `text`, in one delta, as a
+/// client's first message does.
+fn join_and_post(
+    owner: &SigningKey,
+    inviter: &SigningKey,
+    sk: &SigningKey,
+    text: &str,
+    secs: u64,
+) -> ChatRoomStateV1Delta {
+    let member = AuthorizedMember::new(
+        Member {
+            owner_member_id: id(owner),
+            invited_by: id(inviter),
+            member_vk: sk.verifying_key(),
+        },
+        inviter,
+    );
+    ChatRoomStateV1Delta {
+        members: Some(MembersDelta::new(vec![member])),
+        recent_messages: Some(vec![post(owner, sk, text, secs)]),
+        ..Default::default()
+    }
+}
+
+fn post(owner: &SigningKey, sk: &SigningKey, text: &str, secs: u64) -> AuthorizedMessageV1 {
+    AuthorizedMessageV1::new(
+        MessageV1 {
+            room_owner: id(owner),
+            author: id(sk),
+            time: at(secs),
+            content: RoomMessageBody::public(text.to_string()),
+        },
+        sk,
+    )
+}
+
+fn ban(owner: &SigningKey, target: &SigningKey, secs: u64) -> ChatRoomStateV1Delta {
+    let ban = UserBan {
+        owner_member_id: id(owner),
+        banned_at: at(secs),
+        banned_user: id(target),
+    };
+    ChatRoomStateV1Delta {
+        bans: Some(vec![AuthorizedUserBan::new(ban, id(owner), owner)]),
+        ..Default::default()
+    }
+}
+
+/// Fail with the seed and a saved trace rather than a bare assertion.
+fn settle_or_report(sim: &mut Simulation, seed: u64) {
+    if let Err(divergence) = sim.settle() {
+        let path = std::env::temp_dir().join(format!("river-sim-{seed}.trace"));
+        std::fs::write(&path, divergence.trace.to_bytes()).unwrap();
+        panic!(
+            "seed {seed}: {divergence}; trace written to {}",
+            path.display()
+        );
+    }
+}
+
+/// Three members join on different peers, chat, and one is banned by the
+/// owner while the others' changes are still in flight.
+fn scenario(seed: u64, config: SimConfig) -> Simulation {
+    let owner = key(1);
+    let (alice, bob, carol) = (key(2), key(3), key(4));
+    let peers = config.peers;
+    let mut sim = Simulation::new(owner.verifying_key(), ChatRoomStateV1::default(), config);
+    sim.author(0, join_and_post(&owner, &owner, &alice, "hi from alice", 1));
+    sim.run_random(20);
+    sim.author(
+        1 % peers,
+        join_and_post(&owner, &alice, &bob, "bob here", 2),
+    );
+    sim.author(2 % peers, join_and_post(&owner, &owner, &carol, "carol", 3));
+    sim.run_random(20);
+    sim.author(
+        0,
+        ChatRoomStateV1Delta {
+            recent_messages: Some(vec![post(&owner, &owner, "welcome", 4)]),
+            ..Default::default()
+        },
+    );
+    sim.author((seed as usize) % peers, ban(&owner, &bob, 5));
+    sim.run_random(60);
+    sim
+}
+
+#[test]
+fn peers_converge_under_reordering_duplication_drops_and_partitions() {
+    for seed in 0..16 {
+        let config = SimConfig {
+            peers: 4,
+            seed,
+            ..SimConfig::default()
+        };
+        let mut sim = scenario(seed, config);
+        settle_or_report(&mut sim, seed);
+    }
+}
+
+#[test]
+fn peers_converge_when_every_send_is_a_full_state() {
+    for seed in 0..8 {
+        let config = SimConfig {
+            peers: 3,
+            seed,
+            full_state_chance: 1.0,
+            reorder: false,
+            ..SimConfig::default()
+        };
+        let mut sim = scenario(seed, config);
+        settle_or_report(&mut sim, seed);
+    }
+}
+
+#[test]
+fn a_saved_trace_replays_to_the_same_state() {
+    let config = SimConfig {
+        peers: 4,
+        seed: 7,
+        ..SimConfig::default()
+    };
+    let mut sim = scenario(7, config);
+    let report = sim.settle().expect("seed 7 converges");
+    // The ban removed bob and, with him, his message.
+    assert_eq!(report.state.members.members.len(), 2);
+    assert_eq!(report.state.recent_messages.messages.len(), 3);
+
+    let trace = Trace::from_bytes(&sim.trace().to_bytes()).unwrap();
+    assert_eq!(&trace, sim.trace());
+    let replayed = replay(&trace).expect("the replay converges too");
+    assert_eq!(replayed.state_bytes, report.state_bytes);
+}
@@ -22,6 +22,7 @@ use river_core::room_state::ban::{BanId, BansV1};
 use river_core::room_state::direct_messages::{
     DirectMessagesSummary, DmOrderKey, DmPairHorizon, DmRetentionHorizon, SignatureBytes,
 };
+use river_core::room_state::group_direct_messages::GroupDirectMessagesSummary;
 use river_core::room_state::member::{MemberId, MembersV1};
 use river_core::room_state::member_info::{
     AuthorizedMemberInfo, MemberInfo, MemberInfoV1, SigDigest,
@@ -133,11 +134,13 @@ fn secrets_summary_serialization_is_order_independent() {
         current_version: N as u32,
         version_ids: version_ids_fwd.into_iter().collect(),
         member_secrets: member_secrets_fwd.into_iter().collect(),
+        blobs_from: 0,
     };
     let s_rev = SecretsSummary {
         current_version: N as u32,
         version_ids: version_ids_rev.into_iter().collect(),
         member_secrets: member_secrets_rev.into_iter().collect(),
+        blobs_from: 0,
     };
 
     assert_eq!(
@@ -290,6 +293,7 @@ fn top_level_summary_serialization_is_order_independent() {
                     (j as u32, member_id(j))
                 })
                 .collect(),
+            blobs_from: 0,
         };
         // recent_messages carries a BTreeSet of ids plus a retention horizon;
         // the BTreeSet canonicalises whatever order the ids arrive in.
@@ -320,6 +324,12 @@ fn top_level_summary_serialization_is_order_independent() {
                 signature: SignatureBytes([1u8; 64]),
             }),
         };
+        let group_direct_messages = GroupDirectMessagesSummary {
+            message_signatures: (0..N)
+                .map(|i| SignatureBytes([order(i) as u8 + 100; 64]))
+                .collect(),
+            horizon: DmRetentionHorizon::Open,
+        };
 
         ChatRoomStateV1Summary {
             configuration: 7,
@@ -329,6 +339,7 @@ fn top_level_summary_serialization_is_order_independent() {
             secrets,
             recent_messages,
             direct_messages,
+            group_direct_messages,
             upgrade: None,
             version: 3,
         }
@@ -0,0 +1,141 @@
+//! Per-room presence contract: online members and typing indicators. All
+//! state rules live in `river_core::presence`; this crate only adapts them to
+//! `ContractInterface`, the same split as `inbox-contract`.
+
+use ciborium::{de::from_reader, ser::into_writer};
+use freenet_stdlib::prelude::*;
+
+use freenet_scaffold::ComposableState;
+use freenet_stdlib::prelude::ContractError;
+use river_core::presence::{
+    PresenceDeltaV1, PresenceParametersV1, PresenceStateV1, PresenceSummaryV1,
+};
+
+#[allow(dead_code)]
+struct Contract;
+
+#[contract]
+impl ContractInterface for Contract {
+    fn validate_state(
+        parameters: Parameters<'static>,
+        state: State<'static>,
+        _related: RelatedContracts<'static>,
+    ) -> Result<ValidateResult, freenet_stdlib::prelude::ContractError> {
+        let bytes = state.as_ref();
+        // A room nobody has announced themselves in yet is valid.
+        if bytes.is_empty() {
+            return Ok(ValidateResult::Valid);
+        }
+        let presence = from_reader::<PresenceStateV1, &[u8]>(bytes)
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let parameters = from_reader::<PresenceParametersV1, &[u8]>(parameters.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+
+        presence
+            .verify(&presence, &parameters)
+            .map(|_| ValidateResult::Valid)
+            .map_err(|e| ContractError::InvalidUpdateWithInfo {
+                reason: format!("State verification failed: {}", e),
+            })
+    }
+
+    fn update_state(
+        parameters: Parameters<'static>,
+        state: State<'static>,
+        data: Vec<UpdateData<'static>>,
+    ) -> Result<UpdateModification<'static>, freenet_stdlib::prelude::ContractError> {
+        let parameters = from_reader::<PresenceParametersV1, &[u8]>(parameters.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let mut presence = if state.as_ref().is_empty() {
+            PresenceStateV1::default()
+        } else {
+            from_reader::<PresenceStateV1, &[u8]>(state.as_ref())
+                .map_err(|e| ContractError::Deser(e.to_string()))?
+        };
+
+        for update in data {
+            match update {
+                UpdateData::State(new_state) => {
+                    if new_state.as_ref().is_empty() {
+                        continue;
+                    }
+                    let new_state = from_reader::<PresenceStateV1, &[u8]>(new_state.as_ref())
+                        .map_err(|e| ContractError::Deser(e.to_string()))?;
+                    presence
+                        .merge(&presence.clone(), &parameters, &new_state)
+                        .map_err(|e| ContractError::InvalidUpdateWithInfo { reason: e })?;
+                }
+                UpdateData::Delta(d) => {
+                    if d.as_ref().is_empty() {
+                        continue;
+                    }
+                    let delta = from_reader::<PresenceDeltaV1, &[u8]>(d.as_ref())
+                        .map_err(|e| ContractError::Deser(e.to_string()))?;
+                    presence
+                        .apply_delta(&presence.clone(), &parameters, &Some(delta))
+                        .map_err(|e| ContractError::InvalidUpdateWithInfo { reason: e })?;
+                }
+                // Presence has no related contracts. Reject rather than
+                // panic, as the room contract does for unknown variants.
+                _ => {
+                    return Err(ContractError::InvalidUpdate);
+                }
+            }
+        }
+
+        let mut updated_state = vec![];
+        into_writer(&presence, &mut updated_state)
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+
+        Ok(UpdateModification::valid(updated_state.into()))
+    }
+
+    fn summarize_state(
+        parameters: Parameters<'static>,
+        state: State<'static>,
+    ) -> Result<StateSummary<'static>, freenet_stdlib::prelude::ContractError> {
+        let state = state.as_ref();
+        if state.is_empty() {
+            return Ok(StateSummary::from(vec![]));
+        }
+        let parameters = from_reader::<PresenceParametersV1, &[u8]>(parameters.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let presence = from_reader::<PresenceStateV1, &[u8]>(state)
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let summary = presence.summarize(&presence, &parameters);
+        let mut summary_bytes = vec![];
+        into_writer(&summary, &mut summary_bytes)
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        Ok(StateSummary::from(summary_bytes))
+    }
+
+    fn get_state_delta(
+        parameters: Parameters<'static>,
+        state: State<'static>,
+        summary: StateSummary<'static>,
+    ) -> Result<StateDelta<'static>, freenet_stdlib::prelude::ContractError> {
+        if state.as_ref().is_empty() {
+            return Ok(StateDelta::from(vec![]));
+        }
+        let presence = from_reader::<PresenceStateV1, &[u8]>(state.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let parameters = from_reader::<PresenceParametersV1, &[u8]>(parameters.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        // An empty summary is a peer holding nothing.
+        let summary = if summary.as_ref().is_empty() {
+            PresenceSummaryV1::default()
+        } else {
+            from_reader::<PresenceSummaryV1, &[u8]>(summary.as_ref())
+                .map_err(|e| ContractError::Deser(e.to_string()))?
+        };
+        match presence.delta(&presence, &parameters, &summary) {
+            Some(d) => {
+                let mut delta_bytes = vec![];
+                into_writer(&d, &mut delta_bytes)
+                    .map_err(|e| ContractError::Deser(e.to_string()))?;
+                Ok(StateDelta::from(delta_bytes))
+            }
+            None => Ok(StateDelta::from(vec![])),
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! Fuzz harnesses for the room contract's four entry points.
+//!
+//! The targets in `fuzz_targets/` call `Contract::validate_state`,
+//! `update_state`, `summarize_state` and `get_state_delta` natively, with the
+//! same CBOR-encoded parameters, states, deltas and summaries the node passes
+//! across the WASM boundary:
+//!
+//! - `entry_points` feeds raw bytes to all four and only requires that
+//!   nothing panics. Its seed corpus in `seeds/entry_points/` is captured
+//!   from the integration-test fixtures by [`seeds`].
+//! - `apply_then_verify` grows the fixture room with signed edits chosen by
+//!   the fuzzer and requires every accepted update, and every delta synced
+//!   back through a summary, to leave a state that `validate_state` accepts.
+//! - `merge_laws` forks the room, grows both sides independently and
+//!   requires full-state merges to be commutative and idempotent.
+//!
+//! The target bodies live here rather than in the binaries so that
+//! `cargo test` can run them over the seeds on a stable toolchain.
+
+use arbitrary::Unstructured;
+use ciborium::{de::from_reader, ser::into_writer};
+use ed25519_dalek::SigningKey;
+use freenet_stdlib::prelude::{
+    ContractError, ContractInterface, Parameters, RelatedContracts, State, StateDelta,
+    StateSummary, UpdateData, ValidateResult,
+};
+use river_core::room_state::ban::{AuthorizedUserBan, UserBan};
+use river_core::room_state::configuration::{AuthorizedConfigurationV1, Configuration};
+use river_core::room_state::direct_messages::DirectMessagesV1;
+use river_core::room_state::member::{AuthorizedMember, Member, MemberId, MembersDelta, MembersV1};
+use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfo};
+use river_core::room_state::message::{
+    AuthorizedMessageV1, MessageId, MessageV1, MessagesV1, RoomMessageBody,
+};
+use river_core::room_state::{ChatRoomParametersV1, ChatRoomStateV1, ChatRoomStateV1Delta};
+use room_contract::Contract;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Keys the structured targets sign with: index 0 is the owner, 1..=3 are
+/// the fixture's members and the rest start outside the room. Seeds match
+/// `RoomTestState::new_test_room` in the contract's integration tests.
+const KEY_POOL: u8 = 8;
+
+const EMOJI: [&str; 4] = ["👍", "❤️", "😂", "🎉"];
+
+/// Upper bound on edits per room, so one input can't stall the fuzzer.
+const MAX_OPS: usize = 24;
+
+fn key(index: u8) -> SigningKey {
+    SigningKey::from_bytes(&[index + 1; 32])
+}
+
+fn id(sk: &SigningKey) -> MemberId {
+    sk.verifying_key().into()
+}
+
+fn at(secs: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+}
+
+fn encode<T: serde::Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    into_writer(value, &mut bytes).expect("CBOR encoding to a Vec can't fail");
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> ChatRoomStateV1 {
+    from_reader(bytes).expect("the contract only returns states it can decode")
+}
+
+pub fn parameters() -> Vec<u8> {
+    encode(&ChatRoomParametersV1 {
+        owner: key(0).verifying_key(),
+    })
+}
+
+pub fn validate(state: &[u8]) -> Result<ValidateResult, ContractError> {
+    Contract::validate_state(
+        Parameters::from(parameters()),
+        State::from(state.to_vec()),
+        RelatedContracts::new(),
+    )
+}
+
+/// `update_state` with a single update, returning the new state's bytes.
+pub fn update(state: &[u8], data: UpdateData<'static>) -> Result<Vec<u8>, ContractError> {
+    let modification = Contract::update_state(
+        Parameters::from(parameters()),
+        State::from(state.to_vec()),
+        vec![data],
+    )?;
+    Ok(modification
+        .new_state
+        .expect("update_state always returns the new state")
+        .as_ref()
+        .to_vec())
+}
+
+pub fn summarize(state: &[u8]) -> Result<Vec<u8>, ContractError> {
+    Contract::summarize_state(Parameters::from(parameters()), State::from(state.to_vec()))
+        .map(|summary| summary.as_ref().to_vec())
+}
+
+pub fn get_delta(state: &[u8], summary: &[u8]) -> Result<Vec<u8>, ContractError> {
+    Contract::get_state_delta(
+        Parameters::from(parameters()),
+        State::from(state.to_vec()),
+        StateSummary::from(summary.to_vec()),
+    )
+    .map(|delta| delta.as_ref().to_vec())
+}
+
+fn delta_update(delta: Vec<u8>) -> UpdateData<'static> {
+    UpdateData::Delta(StateDelta::from(delta))
+}
+
+fn state_update(state: Vec<u8>) -> UpdateData<'static> {
+    UpdateData::State(State::from(state))
+}
+
+fn post(author: &SigningKey, content: RoomMessageBody, secs: u64) -> AuthorizedMessageV1 {
+    AuthorizedMessageV1::new(
+        MessageV1 {
+            room_owner: id(&key(0)),
+            author: id(author),
+            time: at(secs),
+            content,
+        },
+        author,
+    )
+}
+
+/// `RoomTestState::new_test_room`: a default configuration and three members
+/// invited by the owner. Each member also has a message, otherwise the first
+/// update would prune them as inactive.
+pub fn fixture_room() -> ChatRoomStateV1 {
+    let owner = key(0);
+    let members = (1..=3)
+        .map(|i| {
+            AuthorizedMember::new(
+                Member {
+                    owner_member_id: id(&owner),
+                    invited_by: id(&owner),
+                    member_vk: key(i).verifying_key(),
+                },
+                &owner,
+            )
+        })
+        .collect();
+    let messages = (1..=3)
+        .map(|i| {
+            post(
+                &key(i),
+                RoomMessageBody::public(format!("hello {i}")),
+                i as u64,
+            )
+        })
+        .collect();
+    ChatRoomStateV1 {
+        configuration: AuthorizedConfigurationV1::new(Configuration::default(), &owner),
+        members: MembersV1 { members },
+        recent_messages: MessagesV1 {
+            messages,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// The fuzzer's view of a room while it authors edits against it.
+struct Author<'a, 'b> {
+    u: &'a mut Unstructured<'b>,
+    clock: u64,
+}
+
+impl Author<'_, '_> {
+    fn pool_key(&mut self) -> arbitrary::Result<SigningKey> {
+        Ok(key(self.u.int_in_range(0..=KEY_POOL - 1)?))
+    }
+
+    /// Mostly someone who may act in `state` (the owner or a member), but
+    /// sometimes anyone, so unauthorized edits get exercised too.
+    fn actor(&mut self, state: &ChatRoomStateV1) -> arbitrary::Result<SigningKey> {
+        let present: Vec<u8> = (0..KEY_POOL)
+            .filter(|&i| {
+                i == 0
+                    || state
+                        .members
+                        .members
+                        .iter()
+                        .any(|m| m.member.id() == id(&key(i)))
+            })
+            .collect();
+        if self.u.ratio(1, 8)? {
+            return self.pool_key();
+        }
+        Ok(key(*self.u.choose(&present)?))
+    }
+
+    fn text(&mut self) -> arbitrary::Result<String> {
+        let len = self.u.int_in_range(0..=48)?;
+        Ok(String::from_utf8_lossy(self.u.bytes(len)?).into_owned())
+    }
+
+    fn tick(&mut self) -> arbitrary::Result<u64> {
+        self.clock += self.u.int_in_range(0..=600)?;
+        Ok(self.clock)
+    }
+
+    /// A message already in the room, with its author's key when it's one
+    /// of ours.
+    fn target(
+        &mut self,
+        state: &ChatRoomStateV1,
+    ) -> arbitrary::Result<Option<(MessageId, Option<SigningKey>)>> {
+        if state.recent_messages.messages.is_empty() {
+            return Ok(None);
+        }
+        let message = self.u.choose(&state.recent_messages.messages)?;
+        let author = (0..KEY_POOL).find(|&i| id(&key(i)) == message.message.author);
+        Ok(Some((message.id(), author.map(key))))
+    }
+
+    fn delta(&mut self, state: &ChatRoomStateV1) -> arbitrary::Result<ChatRoomStateV1Delta> {
+        let owner = key(0);
+        let mut delta = ChatRoomStateV1Delta::default();
+        match self.u.int_in_range(0..=7)? {
+            0 => {
+                let inviter = self.actor(state)?;
+                let joiner = self.pool_key()?;
+                let member = AuthorizedMember::new(
+                    Member {
+                        owner_member_id: id(&owner),
+                        invited_by: id(&inviter),
+                        member_vk: joiner.verifying_key(),
+                    },
+                    &inviter,
+                );
+                let text = self.text()?;
+                delta.members = Some(MembersDelta::new(vec![member]));
+                delta.recent_messages = Some(vec![post(
+                    &joiner,
+                    RoomMessageBody::public(text),
+                    self.tick()?,
+                )]);
+            }
+            op @ 1..=4 => {
+                let author = self.actor(state)?;
+                let content = match (op, self.target(state)?) {
+                    (2, Some((target, by))) => {
+                        let text = self.text()?;
+                        let author = by.unwrap_or(author);
+                        let message =
+                            post(&author, RoomMessageBody::edit(target, text), self.tick()?);
+                        delta.recent_messages = Some(vec![message]);
+                        return Ok(delta);
+                    }
+                    (3, Some((target, by))) => {
+                        let author = by.unwrap_or(author);
+                        let message = post(&author, RoomMessageBody::delete(target), self.tick()?);
+                        delta.recent_messages = Some(vec![message]);
+                        return Ok(delta);
+                    }
+                    (4, Some((target, _))) => {
+                        RoomMessageBody::reaction(target, self.u.choose(&EMOJI)?.to_string())
+                    }
+                    _ => RoomMessageBody::public(self.text()?),
+                };
+                delta.recent_messages = Some(vec![post(&author, content, self.tick()?)]);
+            }
+            5 => {
+                let banner = self.actor(state)?;
+                let target = self.pool_key()?;
+                let ban = UserBan {
+                    owner_member_id: id(&owner),
+                    banned_at: at(self.tick()?),
+                    banned_user: id(&target),
+                };
+                delta.bans = Some(vec![AuthorizedUserBan::new(ban, id(&banner), &banner)]);
+            }
+            6 => {
+                let member = self.actor(state)?;
+                let version = self.u.int_in_range(0..=8)?;
+                let info = MemberInfo::new_public(id(&member), version, self.text()?);
+                delta.member_info = Some(vec![AuthorizedMemberInfo::new_with_member_key(
+                    info, &member,
+                )]);
+            }
+            _ => {
+                // Small caps push the room through its truncation paths.
+                let mut configuration = state.configuration.configuration.clone();
+                configuration.configuration_version += self.u.int_in_range(0..=2)?;
+                configuration.max_recent_messages = self.u.int_in_range(1..=16)?;
+                configuration.max_members = self.u.int_in_range(1..=8)?;
+                configuration.max_user_bans = self.u.int_in_range(1..=8)?;
+                let signer = if self.u.ratio(1, 8)? {
+                    self.pool_key()?
+                } else {
+                    owner
+                };
+                delta.configuration = Some(AuthorizedConfigurationV1::new(configuration, &signer));
+            }
+        }
+        Ok(delta)
+    }
+}
+
+/// Apply up to [`MAX_OPS`] fuzzer-chosen edits to `state`, keeping the ones
+/// the contract accepts.
+fn grow(u: &mut Unstructured, mut state: Vec<u8>) -> arbitrary::Result<Vec<u8>> {
+    let mut author = Author { u, clock: 100 };
+    for _ in 0..author.u.int_in_range(0..=MAX_OPS)? {
+        let delta = author.delta(&decode(&state))?;
+        if let Ok(next) = update(&state, delta_update(encode(&delta))) {
+            state = next;
+        }
+    }
+    Ok(state)
+}
+
+/// Raw input is `[len: u16 BE][a: len bytes][b: rest]`. `a` is the contract
+/// state; `b` is tried as a summary, a delta and a full state.
+pub fn entry_points(data: &[u8]) {
+    let Some((len, rest)) = data.split_first_chunk::<2>() else {
+        return;
+    };
+    let len = usize::from(u16::from_be_bytes(*len)).min(rest.len());
+    let (a, b) = rest.split_at(len);
+    let _ = validate(a);
+    let _ = summarize(a);
+    let _ = get_delta(a, b);
+    let _ = update(a, delta_update(b.to_vec()));
+    let _ = update(a, state_update(b.to_vec()));
+}
+
+pub fn apply_then_verify(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let mut author = Author {
+        u: &mut u,
+        clock: 100,
+    };
+    let mut state = encode(&fixture_room());
+    for _ in 0..MAX_OPS {
+        let Ok(delta) = author.delta(&decode(&state)) else {
+            return;
+        };
+        let Ok(next) = update(&state, delta_update(encode(&delta))) else {
+            continue;
+        };
+        assert_eq!(
+            validate(&next).ok(),
+            Some(ValidateResult::Valid),
+            "an accepted delta left an invalid state"
+        );
+
+        // What a peer still at `state` would be sent, and what it ends up with.
+        let summary = summarize(&state).expect("a valid state summarizes");
+        let sync = get_delta(&next, &summary).expect("a valid state produces a delta");
+        let synced = update(&state, delta_update(sync)).expect("a peer accepts the sync delta");
+        assert_eq!(
+            validate(&synced).ok(),
+            Some(ValidateResult::Valid),
+            "a sync delta left an invalid state"
+        );
+        state = next;
+    }
+}
+
+pub fn merge_laws(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let Ok(base) = grow(&mut u, encode(&fixture_room())) else {
+        return;
+    };
+    let (Ok(a), Ok(b)) = (grow(&mut u, base.clone()), grow(&mut u, base)) else {
+        return;
+    };
+    let (Ok(ab), Ok(ba)) = (
+        update(&a, state_update(b.clone())),
+        update(&b, state_update(a.clone())),
+    ) else {
+        return;
+    };
+    assert_eq!(ab, ba, "merge is not commutative");
+    assert_eq!(
+        update(&ab, state_update(b)).ok().as_ref(),
+        Some(&ab),
+        "merging the same state twice changed the result"
+    );
+    assert_eq!(
+        update(&ab, state_update(ab.clone())).ok().as_ref(),
+        Some(&ab),
+        "merging a state with itself changed it"
+    );
+}
+
+/// Frame `a` and `b` as an `entry_points` input.
+fn framed(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let len = u16::try_from(a.len()).expect("seed states fit a u16 length");
+    let mut input = len.to_be_bytes().to_vec();
+    input.extend_from_slice(a);
+    input.extend_from_slice(b);
+    input
+}
+
+/// The `entry_points` seed corpus, by file name: the fixture room paired
+/// with its own summary, a delta onto it and a newer full state, plus the
+/// locked direct-message wire format and the empty state.
+pub fn seeds() -> Vec<(&'static str, Vec<u8>)> {
+    let room = fixture_room();
+    let base = encode(&room);
+
+    let owner = key(0);
+    let first = room.recent_messages.messages[0].id();
+    let ban = UserBan {
+        owner_member_id: id(&owner),
+        banned_at: at(20),
+        banned_user: id(&key(3)),
+    };
+    let delta = ChatRoomStateV1Delta {
+        recent_messages: Some(vec![
+            post(&key(1), RoomMessageBody::public("reply".to_string()), 10),
+            post(
+                &key(2),
+                RoomMessageBody::reaction(first.clone(), "👍".to_string()),
+                11,
+            ),
+            post(
+                &key(1),
+                RoomMessageBody::edit(first, "hello, edited".to_string()),
+                12,
+            ),
+        ]),
+        bans: Some(vec![AuthorizedUserBan::new(ban, id(&owner), &owner)]),
+        ..Default::default()
+    };
+    let newer = update(&base, delta_update(encode(&delta))).expect("the seed delta applies");
+
+    let dm_hex = include_str!("../../../../common/tests/direct_messages_wire_format.hex");
+    let direct_messages: DirectMessagesV1 = from_reader(
+        data_encoding::HEXLOWER
+            .decode(dm_hex.trim().as_bytes())
+            .expect("the wire-format fixture is hex")
+            .as_slice(),
+    )
+    .expect("the wire-format fixture decodes");
+    let with_dms = encode(&ChatRoomStateV1 {
+        direct_messages,
+        ..room
+    });
+
+    vec![
+        ("own_summary", framed(&base, &summarize(&base).unwrap())),
+        ("delta", framed(&base, &encode(&delta))),
+        ("newer_state", framed(&base, &newer)),
+        ("stale_summary", framed(&newer, &summarize(&base).unwrap())),
+        ("direct_messages", framed(&with_dms, &[])),
+        ("empty", framed(&[], &[])),
+    ]
+}
+
+pub fn seeds_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("seeds")
+        .join("entry_points")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_room_is_valid() {
+        let state = encode(&fixture_room());
+        assert_eq!(validate(&state).unwrap(), ValidateResult::Valid);
+    }
+
+    /// The committed seeds are what [`seeds`] produces. After changing the
+    /// fixtures or the state format, recapture with
+    /// `RIVER_FUZZ_SEED_CAPTURE=1 cargo test seeds_are_current`.
+    #[test]
+    fn seeds_are_current() {
+        let dir = seeds_dir();
+        for (name, input) in seeds() {
+            let path = dir.join(name);
+            if std::env::var("RIVER_FUZZ_SEED_CAPTURE").is_ok() {
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(&path, &input).unwrap();
+            }
+            let committed = std::fs::read(&path)
+                .unwrap_or_else(|e| panic!("missing seed {}: {e}", path.display()));
+            assert_eq!(committed, input, "seed {name} is stale");
+        }
+    }
+
+    #[test]
+    fn targets_accept_the_seeds() {
+        for (_, input) in seeds() {
+            entry_points(&input);
+            apply_then_verify(&input);
+            merge_laws(&input);
+        }
+    }
+}
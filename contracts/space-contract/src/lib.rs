@@ -0,0 +1,138 @@
+//! Space contract: rooms grouped under shared membership and bans. All
+//! state rules live in `river_core::space`; this crate only adapts them to
+//! `ContractInterface`, the same split as `inbox-contract`.
+
+use ciborium::{de::from_reader, ser::into_writer};
+use freenet_stdlib::prelude::*;
+
+use freenet_scaffold::ComposableState;
+use freenet_stdlib::prelude::ContractError;
+use river_core::space::{SpaceDeltaV1, SpaceParametersV1, SpaceStateV1, SpaceSummaryV1};
+
+#[allow(dead_code)]
+struct Contract;
+
+#[contract]
+impl ContractInterface for Contract {
+    fn validate_state(
+        parameters: Parameters<'static>,
+        state: State<'static>,
+        _related: RelatedContracts<'static>,
+    ) -> Result<ValidateResult, freenet_stdlib::prelude::ContractError> {
+        let bytes = state.as_ref();
+        // A space whose owner has not published a config yet is valid.
+        if bytes.is_empty() {
+            return Ok(ValidateResult::Valid);
+        }
+        let space = from_reader::<SpaceStateV1, &[u8]>(bytes)
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let parameters = from_reader::<SpaceParametersV1, &[u8]>(parameters.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+
+        space
+            .verify(&space, &parameters)
+            .map(|_| ValidateResult::Valid)
+            .map_err(|e| ContractError::InvalidUpdateWithInfo {
+                reason: format!("State verification failed: {}", e),
+            })
+    }
+
+    fn update_state(
+        parameters: Parameters<'static>,
+        state: State<'static>,
+        data: Vec<UpdateData<'static>>,
+    ) -> Result<UpdateModification<'static>, freenet_stdlib::prelude::ContractError> {
+        let parameters = from_reader::<SpaceParametersV1, &[u8]>(parameters.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let mut space = if state.as_ref().is_empty() {
+            SpaceStateV1::default()
+        } else {
+            from_reader::<SpaceStateV1, &[u8]>(state.as_ref())
+                .map_err(|e| ContractError::Deser(e.to_string()))?
+        };
+
+        for update in data {
+            match update {
+                UpdateData::State(new_state) => {
+                    if new_state.as_ref().is_empty() {
+                        continue;
+                    }
+                    let new_state = from_reader::<SpaceStateV1, &[u8]>(new_state.as_ref())
+                        .map_err(|e| ContractError::Deser(e.to_string()))?;
+                    space
+                        .merge(&space.clone(), &parameters, &new_state)
+                        .map_err(|e| ContractError::InvalidUpdateWithInfo { reason: e })?;
+                }
+                UpdateData::Delta(d) => {
+                    if d.as_ref().is_empty() {
+                        continue;
+                    }
+                    let delta = from_reader::<SpaceDeltaV1, &[u8]>(d.as_ref())
+                        .map_err(|e| ContractError::Deser(e.to_string()))?;
+                    space
+                        .apply_delta(&space.clone(), &parameters, &Some(delta))
+                        .map_err(|e| ContractError::InvalidUpdateWithInfo { reason: e })?;
+                }
+                // A space has no related contracts. Reject rather than
+                // panic, as the room contract does for unknown variants.
+                _ => {
+                    return Err(ContractError::InvalidUpdate);
+                }
+            }
+        }
+
+        let mut updated_state = vec![];
+        into_writer(&space, &mut updated_state).map_err(|e| ContractError::Deser(e.to_string()))?;
+
+        Ok(UpdateModification::valid(updated_state.into()))
+    }
+
+    fn summarize_state(
+        parameters: Parameters<'static>,
+        state: State<'static>,
+    ) -> Result<StateSummary<'static>, freenet_stdlib::prelude::ContractError> {
+        let state = state.as_ref();
+        if state.is_empty() {
+            return Ok(StateSummary::from(vec![]));
+        }
+        let parameters = from_reader::<SpaceParametersV1, &[u8]>(parameters.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let space = from_reader::<SpaceStateV1, &[u8]>(state)
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let summary = space.summarize(&space, &parameters);
+        let mut summary_bytes = vec![];
+        into_writer(&summary, &mut summary_bytes)
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        Ok(StateSummary::from(summary_bytes))
+    }
+
+    fn get_state_delta(
+        parameters: Parameters<'static>,
+        state: State<'static>,
+        summary: StateSummary<'static>,
+    ) -> Result<StateDelta<'static>, freenet_stdlib::prelude::ContractError> {
+        if state.as_ref().is_empty() {
+            return Ok(StateDelta::from(vec![]));
+        }
+        let space = from_reader::<SpaceStateV1, &[u8]>(state.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        let parameters = from_reader::<SpaceParametersV1, &[u8]>(parameters.as_ref())
+            .map_err(|e| ContractError::Deser(e.to_string()))?;
+        // An empty summary is a peer holding nothing.
+        let summary = if summary.as_ref().is_empty() {
+            SpaceSummaryV1::default()
+        } else {
+            from_reader::<SpaceSummaryV1, &[u8]>(summary.as_ref())
+                .map_err(|e| ContractError::Deser(e.to_string()))?
+        };
+        match space.delta(&space, &parameters, &summary) {
+            Some(d) => {
+                let mut delta_bytes = vec![];
+                into_writer(&d, &mut delta_bytes)
+                    .map_err(|e| ContractError::Deser(e.to_string()))?;
+                Ok(StateDelta::from(delta_bytes))
+            }
+            None => Ok(StateDelta::from(vec![])),
+        }
+    }
+}
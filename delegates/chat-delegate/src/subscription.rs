@@ -480,6 +480,7 @@ pub(crate) fn handle_contract_notification(
         }),
         recent_messages: None,
         direct_messages: None,
+        group_direct_messages: None,
         upgrade: None,
         version: None,
     };
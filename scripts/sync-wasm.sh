@@ -15,13 +15,36 @@ echo "Building room-contract and chat-delegate WASMs..."
 # what CI verified.
 cargo build --locked --release --target wasm32-unknown-unknown -p room-contract -p chat-delegate --target-dir target
 
+# The inbox contract is a SEPARATE invocation on purpose: it enables
+# river-core's `inbox` feature, and building it alongside the two above would
+# unify that feature into them and change their keys.
+echo "Building inbox-contract WASM..."
+cargo build --locked --release --target wasm32-unknown-unknown -p inbox-contract --target-dir target
+
+# Likewise for the presence contract and river-core's `presence` feature.
+echo "Building presence-contract WASM..."
+cargo build --locked --release --target wasm32-unknown-unknown -p presence-contract --target-dir target
+
+# And for the space contract and river-core's `space` feature.
+echo "Building space-contract WASM..."
+cargo build --locked --release --target wasm32-unknown-unknown -p space-contract --target-dir target
+
 SRC_CONTRACT="target/wasm32-unknown-unknown/release/room_contract.wasm"
 SRC_DELEGATE="target/wasm32-unknown-unknown/release/chat_delegate.wasm"
+SRC_INBOX="target/wasm32-unknown-unknown/release/inbox_contract.wasm"
+SRC_PRESENCE="target/wasm32-unknown-unknown/release/presence_contract.wasm"
+SRC_SPACE="target/wasm32-unknown-unknown/release/space_contract.wasm"
 
 copies=(
     "$SRC_CONTRACT:ui/public/contracts/room_contract.wasm"
     "$SRC_CONTRACT:cli/contracts/room_contract.wasm"
     "$SRC_DELEGATE:ui/public/contracts/chat_delegate.wasm"
+    "$SRC_INBOX:ui/public/contracts/inbox_contract.wasm"
+    "$SRC_INBOX:cli/contracts/inbox_contract.wasm"
+    "$SRC_PRESENCE:ui/public/contracts/presence_contract.wasm"
+    "$SRC_PRESENCE:cli/contracts/presence_contract.wasm"
+    "$SRC_SPACE:ui/public/contracts/space_contract.wasm"
+    "$SRC_SPACE:cli/contracts/space_contract.wasm"
 )
 
 for pair in "${copies[@]}"; do
@@ -19,6 +19,9 @@ fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     generate_build_info();
     generate_legacy_delegates();
+    bundle_feature_contract("inbox_contract.wasm", "inbox-contract");
+    bundle_feature_contract("presence_contract.wasm", "presence-contract");
+    bundle_feature_contract("space_contract.wasm", "space-contract");
 }
 
 fn generate_build_info() {
@@ -153,3 +156,32 @@ fn generate_legacy_delegates() {
         );
     }
 }
+
+/// Bundle `public/contracts/<file>` into `OUT_DIR` when `feature` is on (the
+/// inbox, presence and space contracts).
+///
+/// Each feature stays off until its released WASM is committed with a signed
+/// pointer record, and the matching constant in `constants` is `None` without
+/// it, which leaves the UI reading DMs from the room alone, without presence,
+/// and with rooms ungrouped. With the feature on, a missing WASM fails the
+/// build rather than shipping the feature inert. The directory is watched
+/// rather than the file, since a `rerun-if-changed` on a missing path is
+/// permanently dirty (see `emit_git_ref_rerun_paths`).
+fn bundle_feature_contract(file: &str, feature: &str) {
+    let feature_var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+    if std::env::var_os(feature_var).is_none() {
+        return;
+    }
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set for build scripts");
+    let dest = Path::new(&out_dir).join(file);
+    println!("cargo:rerun-if-changed=public/contracts");
+    let src = Path::new("public/contracts").join(file);
+    if !src.exists() {
+        panic!(
+            "{} not found, but the `{feature}` feature is on. Commit the released WASM \
+             with `scripts/sync-wasm.sh`, or build without the feature.",
+            src.display()
+        );
+    }
+    std::fs::copy(&src, &dest).unwrap_or_else(|e| panic!("failed to bundle {file}: {e}"));
+}
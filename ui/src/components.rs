@@ -95,9 +95,13 @@ mod volatile_value_binding_audit {
     /// you have confirmed the control handles `oninput`.
     const EXPECTED_EDITABLE: &[&str] = &[
         r#"components/conversation.rs <textarea> "{edit_text}""#,
+        r#"components/conversation/emoji_picker.rs <input> "{query}""#,
         r#"components/conversation/message_input.rs <textarea> "{message_text}""#,
+        r#"components/conversation/message_input.rs <input> "{value}""#,
         r#"components/direct_messages/dm_thread_modal.rs <textarea> "{draft.read()}""#,
+        r#"components/direct_messages/group_dm_thread_modal.rs <textarea> "{draft.read()}""#,
         r#"components/direct_messages/invite_via_dm_picker_modal.rs <textarea> "{personal_message_value}""#,
+        r#"components/direct_messages/new_group_dm_modal.rs <textarea> "{draft.read()}""#,
         r#"components/members.rs <textarea> "{token_input}""#,
         r#"components/members/member_info_modal/nickname_field.rs <input> "{temp_nickname}""#,
         r#"components/room_list/create_room_modal.rs <input> "{nickname}""#,
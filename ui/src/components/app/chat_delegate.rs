@@ -13,8 +13,11 @@ use freenet_stdlib::prelude::{
 use futures::channel::oneshot;
 use futures::future::{select, Either};
 use river_core::chat_delegate::{
-    CasStoreResult, ChatDelegateKey, ChatDelegateRequestMsg, ChatDelegateResponseMsg,
-    HiddenDmThreadEntry, OutboundDmEntry, OutboundDmStore, RequestId, RoomKey,
+    CasStoreResult, ChatDelegateKey, ChatDelegateRequestMsg, ChatDelegateResponseMsg, Draft,
+    DraftBody, DraftStore, HiddenDmThreadEntry, IgnoreList, IgnoredMember, OutboundDmEntry,
+    OutboundDmStore, ReadMarkerStore, RequestId, RoomKey, ScheduledMessage, ScheduledMessageStore,
+    VerifiedKey, VerifiedKeyStore, DRAFTS_STORAGE_KEY, IGNORED_MEMBERS_STORAGE_KEY,
+    READ_MARKERS_STORAGE_KEY, SCHEDULED_MESSAGES_STORAGE_KEY, VERIFIED_KEYS_STORAGE_KEY,
 };
 use river_core::room_state::direct_messages::{PurgeToken, MAX_DM_MESSAGES_PER_PAIR};
 use river_core::room_state::member::MemberId;
@@ -1451,6 +1454,155 @@ mod tests {
         }
     }
 
+    /// The read-marker reconcile unions the two sides, writes nothing for a
+    /// user with no markers anywhere, and stores the same bytes when this
+    /// device adds nothing (so the CAS loop sees `Unchanged`).
+    #[test]
+    fn read_marker_reconcile_unions_and_skips_empty_writes() {
+        use river_core::room_state::message::{MessageId, MessageOrderKey};
+        let key = |secs| MessageOrderKey {
+            time: std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs),
+            id: MessageId(freenet_scaffold::util::FastHash(1)),
+        };
+
+        let (_, bytes) = reconcile_read_markers(None, &ReadMarkerStore::default()).unwrap();
+        assert!(bytes.is_none());
+
+        let mut remote = ReadMarkerStore::default();
+        remote.advance([1; 32], key(100));
+        let mut remote_bytes = Vec::new();
+        ciborium::ser::into_writer(&remote, &mut remote_bytes).unwrap();
+
+        let (_, same) =
+            reconcile_read_markers(Some(&remote_bytes), &ReadMarkerStore::default()).unwrap();
+        assert_eq!(same.as_deref(), Some(remote_bytes.as_slice()));
+
+        let mut local = ReadMarkerStore::default();
+        local.advance([1; 32], key(50));
+        local.advance([2; 32], key(70));
+        let (merged, _) = reconcile_read_markers(Some(&remote_bytes), &local).unwrap();
+        assert_eq!(merged.get(&[1; 32]), Some(&key(100)));
+        assert_eq!(merged.get(&[2; 32]), Some(&key(70)));
+
+        assert!(reconcile_read_markers(Some(b"not cbor"), &local).is_err());
+    }
+
+    /// A claim removes only due entries and stores the shortened queue; an
+    /// empty store with nothing queued writes nothing.
+    #[test]
+    fn scheduled_message_reconcile_claims_due_entries() {
+        let (_, _, bytes) =
+            reconcile_scheduled_messages(None, &mut |q: &mut ScheduledMessageStore| {
+                q.take_due(100, |_| true)
+            })
+            .unwrap();
+        assert!(bytes.is_none());
+
+        let mut queue = ScheduledMessageStore::default();
+        for (id, send_at) in [(1, 50), (2, 150)] {
+            queue.add(ScheduledMessage {
+                id,
+                room_owner_vk: [1; 32],
+                send_at,
+                text: format!("message {id}"),
+            });
+        }
+        let mut queue_bytes = Vec::new();
+        ciborium::ser::into_writer(&queue, &mut queue_bytes).unwrap();
+
+        let (after, claimed, bytes) = reconcile_scheduled_messages(
+            Some(&queue_bytes),
+            &mut |q: &mut ScheduledMessageStore| q.take_due(100, |_| true),
+        )
+        .unwrap();
+        assert_eq!(claimed.iter().map(|m| m.id).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(
+            after.entries.iter().map(|m| m.id).collect::<Vec<_>>(),
+            vec![2]
+        );
+        let stored: ScheduledMessageStore =
+            ciborium::de::from_reader(bytes.unwrap().as_slice()).unwrap();
+        assert_eq!(stored, after);
+    }
+
+    /// The drafts reconcile keeps the later draft per composer from either
+    /// side and writes nothing for a user who has never typed a draft.
+    #[test]
+    fn draft_reconcile_keeps_the_later_draft() {
+        let draft = |updated_at, text: &str| Draft {
+            room_owner_vk: [1; 32],
+            dm_peer: None,
+            updated_at,
+            body: DraftBody::Plain(text.to_string()),
+        };
+        let (_, bytes) = reconcile_drafts(None, &DraftStore::default()).unwrap();
+        assert!(bytes.is_none());
+
+        let mut remote = DraftStore::default();
+        remote.set(draft(20, "from the other tab"));
+        let mut remote_bytes = Vec::new();
+        ciborium::ser::into_writer(&remote, &mut remote_bytes).unwrap();
+
+        let mut local = DraftStore::default();
+        local.set(draft(10, "stale"));
+        let (merged, bytes) = reconcile_drafts(Some(&remote_bytes), &local).unwrap();
+        assert_eq!(merged, remote);
+        assert_eq!(bytes.as_deref(), Some(remote_bytes.as_slice()));
+
+        local.set(draft(30, ""));
+        let (merged, _) = reconcile_drafts(Some(&remote_bytes), &local).unwrap();
+        assert!(merged.get(&[1; 32], None).unwrap().body.is_empty());
+    }
+
+    /// An unmark made after another device's mark wins the reconcile, and
+    /// a user who has never verified anyone writes nothing.
+    #[test]
+    fn verified_keys_reconcile_keeps_the_later_mark() {
+        let mark = |verified, updated_at| VerifiedKey {
+            member_vk: [2; 32],
+            verified,
+            updated_at,
+        };
+        let (_, bytes) = reconcile_verified_keys(None, &VerifiedKeyStore::default()).unwrap();
+        assert!(bytes.is_none());
+
+        let mut remote = VerifiedKeyStore::default();
+        remote.set(mark(true, 10));
+        let mut remote_bytes = Vec::new();
+        ciborium::ser::into_writer(&remote, &mut remote_bytes).unwrap();
+
+        let mut local = VerifiedKeyStore::default();
+        local.set(mark(false, 20));
+        let (merged, bytes) = reconcile_verified_keys(Some(&remote_bytes), &local).unwrap();
+        assert!(!merged.is_verified(&[2; 32]));
+        assert!(bytes.is_some());
+    }
+
+    /// The ignore list reconciles like the other stores: the later entry per
+    /// member wins, and an empty list is never written.
+    #[test]
+    fn ignore_list_reconcile_keeps_the_later_entry() {
+        let member = MemberId(freenet_scaffold::util::FastHash(7));
+        let entry = |ignored, updated_at| IgnoredMember {
+            member_id: member,
+            ignored,
+            purge_dms: false,
+            updated_at,
+        };
+        let (_, bytes) = reconcile_ignored_members(None, &IgnoreList::default()).unwrap();
+        assert!(bytes.is_none());
+
+        let mut remote = IgnoreList::default();
+        remote.set(entry(true, 30));
+        let mut remote_bytes = Vec::new();
+        ciborium::ser::into_writer(&remote, &mut remote_bytes).unwrap();
+
+        let mut local = IgnoreList::default();
+        local.set(entry(false, 20));
+        let (merged, _) = reconcile_ignored_members(Some(&remote_bytes), &local).unwrap();
+        assert!(merged.is_ignored(&member));
+    }
+
     /// Exhaustion after `ROOMS_CAS_MAX_ATTEMPTS` consecutive conflicts errors.
     #[test]
     fn cas_write_key_exhaustion_errors() {
@@ -3251,7 +3403,7 @@ mod tests {
     /// (freenet/river#398 moved codegen to `freenet-migrate-build`) must
     /// reproduce it byte-identically, or every user silently re-runs legacy
     /// migration once. Pinned to the value computed from the current
-    /// `legacy_delegates.toml` (27 entries spanning V1..V30 — V4–V6 removed —
+    /// `legacy_delegates.toml` (28 entries spanning V1..V31 — V4–V6 removed —
     /// in file order). This value
     /// SHOULD change when a genuinely new legacy entry is added — update the
     /// constant then — but must NEVER change from a codegen/tooling swap.
@@ -3266,9 +3418,13 @@ mod tests {
     /// published, so without the bump `cargo install riverctl` would resolve a
     /// river-core WITHOUT the new entry. So a version bump alone re-keys the
     /// delegate, and that is enough to require an entry.
+    ///
+    /// Updated for V31, the batched room-state re-key (group DMs, profiles,
+    /// message TTL, emoji packs, spaces), which came with the V32 room-contract
+    /// entry and the same kind of river-core bump, 0.1.19 -> 0.1.20.
     #[test]
     fn legacy_set_fingerprint_is_stable_across_codegen_changes() {
-        assert_eq!(legacy_set_fingerprint(), "c43e66ee147e3739");
+        assert_eq!(legacy_set_fingerprint(), "c32c326c1328de9a");
     }
 
     /// The "migration in progress" and "migration done" localStorage keys MUST
@@ -4605,7 +4761,7 @@ mod tests {
         let bytes = include_bytes!("../../../public/contracts/chat_delegate.wasm");
         assert_eq!(
             blake3::hash(bytes).to_hex().as_str(),
-            "a44c64014d60fd245fe8fb5172f8fd7397039b248b3b6a8e95e89a0bb539fb5e",
+            "3b67115746276515e6c69b1ff80d832d223ddc80856d962fe5cb29ce3b43192e",
             "chat_delegate.wasm changed — this branch must not alter the delegate WASM; \
              if the change is intentional, follow .claude/rules/delegate-migration.md \
              (add-migration BEFORE rebuilding) and update this pin in the same commit"
@@ -7541,6 +7697,557 @@ pub fn prune_dm_state_for_room(owner_vk: ed25519_dalek::VerifyingKey, new_member
     });
 }
 
+// =============================================================================
+// READ MARKERS
+//
+// How far the user has read in each room, shared with their other devices
+// (and the format riverctl mirrors locally) under one delegate key. Markers
+// are `MessageOrderKey`s that only move forward — see `ReadMarkerStore` — so
+// this rides on the generic versioned get / CAS store and needed no new
+// delegate request variants, which would have changed the delegate key.
+// =============================================================================
+
+/// Every marker this session has seen, local or synced. Not a signal: nothing
+/// renders from it. Markers reach the badges by being folded into each room's
+/// `last_read_message_id` ([`crate::components::app::document_title::apply_read_markers`]).
+/// Kept so a marker pointing past the messages held here is not lost on the
+/// next write.
+static READ_MARKERS: LazyLock<Mutex<ReadMarkerStore>> =
+    LazyLock::new(|| Mutex::new(ReadMarkerStore::default()));
+
+static READ_MARKERS_SAVE_STATE: CoalesceState = CoalesceState::new();
+
+/// Merge this device's read positions with the delegate's copy, store the
+/// union, and fold it back into the loaded rooms. Run once rooms have loaded
+/// (so reads made elsewhere clear the badges here) and after each local
+/// mark-as-read (so ours clear theirs). Coalesced like the other saves.
+pub async fn sync_read_markers_with_delegate() -> Result<(), String> {
+    coalesce_save(
+        &READ_MARKERS_SAVE_STATE,
+        "Read-markers",
+        do_sync_read_markers_with_delegate,
+    )
+    .await
+}
+
+async fn do_sync_read_markers_with_delegate() -> Result<(), String> {
+    let mut adopted = None;
+    cas_write_delegate_key(READ_MARKERS_STORAGE_KEY.to_vec(), |current| {
+        // Read the local positions inside the reconcile rather than before
+        // the call: it runs after the versioned GET has round-tripped, by
+        // which point a just-hydrated room list has landed in ROOMS.
+        let mut local = READ_MARKERS.lock().map(|m| m.clone()).unwrap_or_default();
+        for (owner_vk, room_data) in ROOMS.read().map.iter() {
+            if let Some(position) =
+                crate::components::app::document_title::room_read_position(room_data)
+            {
+                local.advance(owner_vk.to_bytes(), position);
+            }
+        }
+        let (merged, bytes) = reconcile_read_markers(current, &local)?;
+        adopted = Some(merged);
+        Ok(bytes)
+    })
+    .await?;
+    if let Some(merged) = adopted {
+        if let Ok(mut markers) = READ_MARKERS.lock() {
+            markers.merge(&merged);
+        }
+        crate::components::app::document_title::apply_read_markers(merged);
+    }
+    Ok(())
+}
+
+/// Pure reconcile for the read-marker CAS: the union of the delegate's
+/// `current` blob and `local`, plus the bytes to store. No bytes when both
+/// are empty, so a user who has never read anything writes nothing.
+///
+/// An unparseable blob is an error rather than an overwrite: it can only
+/// come from a newer client, and clobbering it would roll that device back.
+fn reconcile_read_markers(
+    current: Option<&[u8]>,
+    local: &ReadMarkerStore,
+) -> Result<(ReadMarkerStore, Option<Vec<u8>>), String> {
+    let mut merged = match current {
+        Some(bytes) => ciborium::de::from_reader::<ReadMarkerStore, _>(bytes)
+            .map_err(|e| format!("Unparseable read markers: {e}"))?,
+        None => ReadMarkerStore::default(),
+    };
+    merged.merge(local);
+    if current.is_none() && merged.markers.is_empty() {
+        return Ok((merged, None));
+    }
+    let mut buf = Vec::new();
+    ciborium::ser::into_writer(&merged, &mut buf)
+        .map_err(|e| format!("Failed to serialize read markers: {e}"))?;
+    Ok((merged, Some(buf)))
+}
+
+// =============================================================================
+// SCHEDULED MESSAGES
+//
+// "Send later" messages, queued under one delegate key so any of the user's
+// devices holding the room can send them. Entries are plaintext and signed
+// only when sent (see `ScheduledMessageStore`), and a sender claims due
+// entries by removing them in a CAS write, so two open tabs never both send
+// one. Like the read markers this rides on the generic versioned get / CAS
+// store rather than new delegate request variants.
+// =============================================================================
+
+/// The queue as of this session's last read or write of it, for the
+/// composer's list of scheduled messages. Refreshed by every dispatch pass,
+/// so edits made on another device show up within one interval.
+pub static SCHEDULED_MESSAGES: GlobalSignal<ScheduledMessageStore> =
+    Global::new(ScheduledMessageStore::default);
+
+/// How often the dispatcher looks for due messages. A scheduled message may
+/// go out up to this late.
+const SCHEDULED_DISPATCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+static SCHEDULED_DISPATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Queue `text` for `room_owner_vk`, due at `send_at` (Unix seconds).
+pub async fn schedule_message(
+    room_owner_vk: VerifyingKey,
+    text: String,
+    send_at: u64,
+) -> Result<(), String> {
+    update_scheduled_messages(|queue| {
+        let mut id = rand::random::<u32>() as u64;
+        while queue.entries.iter().any(|m| m.id == id) {
+            id = rand::random::<u32>() as u64;
+        }
+        queue.add(ScheduledMessage {
+            id,
+            room_owner_vk: room_owner_vk.to_bytes(),
+            send_at,
+            text: text.clone(),
+        });
+    })
+    .await
+}
+
+/// Drop the queued message `id`. Returns whether it was still queued.
+pub async fn cancel_scheduled_message(id: u64) -> Result<bool, String> {
+    update_scheduled_messages(|queue| queue.cancel(id)).await
+}
+
+/// Start the once-per-session loop that sends queued messages as they fall
+/// due. Called when rooms have loaded: only rooms held here can be sent to.
+pub fn start_scheduled_message_dispatcher() {
+    if SCHEDULED_DISPATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    crate::util::safe_spawn_local(async {
+        loop {
+            if let Err(e) = dispatch_due_scheduled_messages().await {
+                warn!("Failed to dispatch scheduled messages: {}", e);
+            }
+            crate::util::sleep(SCHEDULED_DISPATCH_INTERVAL).await;
+        }
+    });
+}
+
+/// Claim every due message for a room this session can sign for and send
+/// it. A message that fails to send is queued again for the next pass.
+async fn dispatch_due_scheduled_messages() -> Result<(), String> {
+    let now = crate::util::get_current_system_time()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let held: HashSet<[u8; 32]> = match ROOMS.try_read() {
+        Ok(rooms) => rooms
+            .map
+            .iter()
+            .filter(|(_, room)| room.signing_key().is_some())
+            .map(|(key, _)| key.to_bytes())
+            .collect(),
+        // Claim nothing this pass rather than everything for no room.
+        Err(_) => HashSet::new(),
+    };
+    let due =
+        update_scheduled_messages(|queue| queue.take_due(now, |vk| held.contains(vk))).await?;
+
+    let mut failed = Vec::new();
+    for entry in due {
+        let sent = VerifyingKey::from_bytes(&entry.room_owner_vk)
+            .map_err(|e| format!("invalid room key: {e}"))
+            .and_then(|room_key| {
+                crate::components::conversation::send_scheduled_text(room_key, entry.text.clone())
+            });
+        match sent {
+            Ok(()) => info!("Sent scheduled message {}", entry.id),
+            Err(e) => {
+                warn!(
+                    "Failed to send scheduled message {}: {}; will retry",
+                    entry.id, e
+                );
+                failed.push(entry);
+            }
+        }
+    }
+    if !failed.is_empty() {
+        update_scheduled_messages(|queue| {
+            for entry in &failed {
+                queue.add(entry.clone());
+            }
+        })
+        .await?;
+    }
+    Ok(())
+}
+
+/// Apply `op` to the delegate's queue in a CAS write and publish the result
+/// to [`SCHEDULED_MESSAGES`]. `op` is re-run on the re-read queue after a
+/// conflict, so it must be an operation, not a snapshot.
+async fn update_scheduled_messages<T>(
+    mut op: impl FnMut(&mut ScheduledMessageStore) -> T,
+) -> Result<T, String> {
+    let mut outcome = None;
+    cas_write_delegate_key(SCHEDULED_MESSAGES_STORAGE_KEY.to_vec(), |current| {
+        let (queue, out, bytes) = reconcile_scheduled_messages(current, &mut op)?;
+        outcome = Some((queue, out));
+        Ok(bytes)
+    })
+    .await?;
+    let (queue, out) = outcome.ok_or("scheduled-message write never read the queue")?;
+    crate::util::defer(move || *SCHEDULED_MESSAGES.write() = queue);
+    Ok(out)
+}
+
+/// Pure reconcile for the scheduled-message CAS: `op` applied to the
+/// delegate's `current` queue, plus the bytes to store. No bytes when there
+/// was no queue and still is none. An unparseable blob is an error, as for
+/// the read markers.
+fn reconcile_scheduled_messages<T>(
+    current: Option<&[u8]>,
+    op: &mut impl FnMut(&mut ScheduledMessageStore) -> T,
+) -> Result<(ScheduledMessageStore, T, Option<Vec<u8>>), String> {
+    let mut queue = match current {
+        Some(bytes) => ciborium::de::from_reader::<ScheduledMessageStore, _>(bytes)
+            .map_err(|e| format!("Unparseable scheduled messages: {e}"))?,
+        None => ScheduledMessageStore::default(),
+    };
+    let out = op(&mut queue);
+    if current.is_none() && queue.entries.is_empty() {
+        return Ok((queue, out, None));
+    }
+    let mut buf = Vec::new();
+    ciborium::ser::into_writer(&queue, &mut buf)
+        .map_err(|e| format!("Failed to serialize scheduled messages: {e}"))?;
+    Ok((queue, out, Some(buf)))
+}
+
+// =============================================================================
+// DRAFTS
+//
+// Unsent composer text per room and per DM thread, so it survives a reload
+// and follows the user to their other tabs and devices. Last-writer-wins per
+// composer (see `DraftStore`), merged through the generic versioned get / CAS
+// store like the read markers.
+// =============================================================================
+
+/// Every draft this session has seen, local or synced. Composers seed from
+/// it and write through it; they `peek` it, so typing re-renders nothing.
+pub static DRAFTS: GlobalSignal<DraftStore> = Global::new(DraftStore::default);
+
+static DRAFTS_SAVE_STATE: CoalesceState = CoalesceState::new();
+
+/// Keep a composer's `text` saved as the draft for `room` (or the DM thread
+/// with `dm_peer`), and fill the composer from the saved draft if it is
+/// still empty when one loads. Call once, at the top of the composer.
+///
+/// Mount a composer per target (give it a `key`): the saved text is tracked
+/// per mount.
+pub fn use_synced_draft(room: VerifyingKey, dm_peer: Option<MemberId>, mut text: Signal<String>) {
+    // What the store holds for this composer, so an unchanged composer never
+    // writes (an empty composer at mount must not clear a saved draft).
+    let mut saved = use_signal(String::new);
+    use_effect(move || {
+        DRAFTS.read();
+        if text.peek().is_empty() && saved.peek().is_empty() {
+            if let Some(draft) = draft_text(&room, dm_peer) {
+                saved.set(draft.clone());
+                text.set(draft);
+            }
+        }
+    });
+    use_effect(move || {
+        let current = text.read().clone();
+        if current != *saved.peek() {
+            saved.set(current.clone());
+            set_draft(room, dm_peer, &current);
+        }
+    });
+}
+
+/// The saved text for a composer, opened with the room's secrets. `None`
+/// when there is none or it cannot be opened here.
+fn draft_text(room: &VerifyingKey, dm_peer: Option<MemberId>) -> Option<String> {
+    let drafts = DRAFTS.peek();
+    let draft = drafts.get(room.as_bytes(), dm_peer)?;
+    if draft.body.is_empty() {
+        return None;
+    }
+    // Peeked: the seeding effect must not re-run on every ROOMS change.
+    let rooms = ROOMS.try_peek().ok()?;
+    let room_data = rooms.map.get(room)?;
+    draft
+        .body
+        .open(|version| room_data.get_secret_for_version(version).copied())
+        .ok()
+}
+
+/// Record `text` as a composer's draft and save the drafts to the delegate.
+/// In a private room whose secret is not held yet nothing is kept, rather
+/// than keeping the text in the clear.
+fn set_draft(room: VerifyingKey, dm_peer: Option<MemberId>, text: &str) {
+    let body = match ROOMS.try_peek() {
+        Ok(rooms) => rooms.map.get(&room).and_then(|room_data| {
+            DraftBody::for_room(text, room_data.is_private(), room_data.get_secret())
+        }),
+        Err(_) => None,
+    };
+    let Some(body) = body else {
+        return;
+    };
+    let draft = Draft {
+        room_owner_vk: room.to_bytes(),
+        dm_peer,
+        updated_at: crate::util::get_current_system_time()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        body,
+    };
+    crate::util::defer(move || {
+        DRAFTS.with_mut(|drafts| drafts.set(draft));
+        crate::util::safe_spawn_local(async {
+            if let Err(e) = sync_drafts_with_delegate().await {
+                warn!("Failed to save drafts: {}", e);
+            }
+        });
+    });
+}
+
+/// Merge this session's drafts with the delegate's copy, store the result
+/// and adopt it. Run once rooms have loaded (which restores drafts after a
+/// reload) and after each edit. Coalesced like the other saves, so typing
+/// costs at most one write in flight.
+pub async fn sync_drafts_with_delegate() -> Result<(), String> {
+    coalesce_save(&DRAFTS_SAVE_STATE, "Drafts", do_sync_drafts_with_delegate).await
+}
+
+async fn do_sync_drafts_with_delegate() -> Result<(), String> {
+    let mut adopted = None;
+    cas_write_delegate_key(DRAFTS_STORAGE_KEY.to_vec(), |current| {
+        let (merged, bytes) = reconcile_drafts(current, &DRAFTS.peek())?;
+        adopted = Some(merged);
+        Ok(bytes)
+    })
+    .await?;
+    if let Some(merged) = adopted {
+        crate::util::defer(move || {
+            DRAFTS.with_mut(|drafts| drafts.merge(&merged));
+        });
+    }
+    Ok(())
+}
+
+/// Pure reconcile for the drafts CAS, the same shape as
+/// [`reconcile_read_markers`].
+fn reconcile_drafts(
+    current: Option<&[u8]>,
+    local: &DraftStore,
+) -> Result<(DraftStore, Option<Vec<u8>>), String> {
+    let mut merged = match current {
+        Some(bytes) => ciborium::de::from_reader::<DraftStore, _>(bytes)
+            .map_err(|e| format!("Unparseable drafts: {e}"))?,
+        None => DraftStore::default(),
+    };
+    merged.merge(local);
+    if current.is_none() && merged.entries.is_empty() {
+        return Ok((merged, None));
+    }
+    let mut buf = Vec::new();
+    ciborium::ser::into_writer(&merged, &mut buf)
+        .map_err(|e| format!("Failed to serialize drafts: {e}"))?;
+    Ok((merged, Some(buf)))
+}
+
+// =============================================================================
+// VERIFIED KEYS
+//
+// Members whose safety number the user has compared out of band and marked
+// verified (see `VerifiedKeyStore`). Merged through the versioned get / CAS
+// store like the drafts, so a mark made in one tab or device shows in all.
+// =============================================================================
+
+/// Every verified mark this session has seen, local or synced.
+pub static VERIFIED_KEYS: GlobalSignal<VerifiedKeyStore> = Global::new(VerifiedKeyStore::default);
+
+static VERIFIED_KEYS_SAVE_STATE: CoalesceState = CoalesceState::new();
+
+/// Has the user marked `member_vk` verified?
+pub fn is_verified(member_vk: &VerifyingKey) -> bool {
+    VERIFIED_KEYS.read().is_verified(member_vk.as_bytes())
+}
+
+/// Mark or unmark `member_vk` as verified and save the marks to the delegate.
+pub fn set_verified(member_vk: VerifyingKey, verified: bool) {
+    let entry = VerifiedKey {
+        member_vk: member_vk.to_bytes(),
+        verified,
+        updated_at: crate::util::get_current_system_time()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+    };
+    crate::util::defer(move || {
+        VERIFIED_KEYS.with_mut(|marks| marks.set(entry));
+        crate::util::safe_spawn_local(async {
+            if let Err(e) = sync_verified_keys_with_delegate().await {
+                warn!("Failed to save verified keys: {}", e);
+            }
+        });
+    });
+}
+
+/// Merge this session's verified marks with the delegate's copy, store the
+/// result and adopt it. Run once rooms have loaded and after each change.
+pub async fn sync_verified_keys_with_delegate() -> Result<(), String> {
+    coalesce_save(
+        &VERIFIED_KEYS_SAVE_STATE,
+        "Verified keys",
+        do_sync_verified_keys_with_delegate,
+    )
+    .await
+}
+
+async fn do_sync_verified_keys_with_delegate() -> Result<(), String> {
+    let mut adopted = None;
+    cas_write_delegate_key(VERIFIED_KEYS_STORAGE_KEY.to_vec(), |current| {
+        let (merged, bytes) = reconcile_verified_keys(current, &VERIFIED_KEYS.peek())?;
+        adopted = Some(merged);
+        Ok(bytes)
+    })
+    .await?;
+    if let Some(merged) = adopted {
+        crate::util::defer(move || {
+            VERIFIED_KEYS.with_mut(|marks| marks.merge(&merged));
+        });
+    }
+    Ok(())
+}
+
+/// Pure reconcile for the verified-keys CAS, the same shape as
+/// [`reconcile_drafts`].
+fn reconcile_verified_keys(
+    current: Option<&[u8]>,
+    local: &VerifiedKeyStore,
+) -> Result<(VerifiedKeyStore, Option<Vec<u8>>), String> {
+    let mut merged = match current {
+        Some(bytes) => ciborium::de::from_reader::<VerifiedKeyStore, _>(bytes)
+            .map_err(|e| format!("Unparseable verified keys: {e}"))?,
+        None => VerifiedKeyStore::default(),
+    };
+    merged.merge(local);
+    if current.is_none() && merged.entries.is_empty() {
+        return Ok((merged, None));
+    }
+    let mut buf = Vec::new();
+    ciborium::ser::into_writer(&merged, &mut buf)
+        .map_err(|e| format!("Failed to serialize verified keys: {e}"))?;
+    Ok((merged, Some(buf)))
+}
+
+// =============================================================================
+// IGNORED MEMBERS
+//
+// The user's ignore list (see `IgnoreList`): members whose messages,
+// reactions, mentions and DMs this client hides. Merged through the versioned
+// get / CAS store like the verified keys, so it follows the user to their
+// other tabs and devices.
+// =============================================================================
+
+/// Every ignore-list entry this session has seen, local or synced.
+pub static IGNORED_MEMBERS: GlobalSignal<IgnoreList> = Global::new(IgnoreList::default);
+
+static IGNORED_MEMBERS_SAVE_STATE: CoalesceState = CoalesceState::new();
+
+/// Ignore or unignore `member`, optionally purging their DMs, and save the
+/// list to the delegate.
+pub fn set_ignored(member: MemberId, ignored: bool, purge_dms: bool) {
+    let entry = IgnoredMember {
+        member_id: member,
+        ignored,
+        purge_dms,
+        updated_at: crate::util::get_current_system_time()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+    };
+    crate::util::defer(move || {
+        IGNORED_MEMBERS.with_mut(|list| list.set(entry));
+        crate::components::direct_messages::purge_ignored_dms();
+        crate::util::safe_spawn_local(async {
+            if let Err(e) = sync_ignored_members_with_delegate().await {
+                warn!("Failed to save ignore list: {}", e);
+            }
+        });
+    });
+}
+
+/// Merge this session's ignore list with the delegate's copy, store the
+/// result and adopt it. Run once rooms have loaded and after each change;
+/// adopting a list purges the DMs it asks to.
+pub async fn sync_ignored_members_with_delegate() -> Result<(), String> {
+    coalesce_save(
+        &IGNORED_MEMBERS_SAVE_STATE,
+        "Ignore list",
+        do_sync_ignored_members_with_delegate,
+    )
+    .await
+}
+
+async fn do_sync_ignored_members_with_delegate() -> Result<(), String> {
+    let mut adopted = None;
+    cas_write_delegate_key(IGNORED_MEMBERS_STORAGE_KEY.to_vec(), |current| {
+        let (merged, bytes) = reconcile_ignored_members(current, &IGNORED_MEMBERS.peek())?;
+        adopted = Some(merged);
+        Ok(bytes)
+    })
+    .await?;
+    if let Some(merged) = adopted {
+        crate::util::defer(move || {
+            IGNORED_MEMBERS.with_mut(|list| list.merge(&merged));
+            crate::components::direct_messages::purge_ignored_dms();
+        });
+    }
+    Ok(())
+}
+
+/// Pure reconcile for the ignore-list CAS, the same shape as
+/// [`reconcile_drafts`].
+fn reconcile_ignored_members(
+    current: Option<&[u8]>,
+    local: &IgnoreList,
+) -> Result<(IgnoreList, Option<Vec<u8>>), String> {
+    let mut merged = match current {
+        Some(bytes) => ciborium::de::from_reader::<IgnoreList, _>(bytes)
+            .map_err(|e| format!("Unparseable ignore list: {e}"))?,
+        None => IgnoreList::default(),
+    };
+    merged.merge(local);
+    if current.is_none() && merged.entries.is_empty() {
+        return Ok((merged, None));
+    }
+    let mut buf = Vec::new();
+    ciborium::ser::into_writer(&merged, &mut buf)
+        .map_err(|e| format!("Failed to serialize ignore list: {e}"))?;
+    Ok((merged, Some(buf)))
+}
+
 fn create_chat_delegate_container() -> DelegateContainer {
     let delegate_bytes = include_bytes!("../../../public/contracts/chat_delegate.wasm");
     let delegate_code = DelegateCode::from(delegate_bytes.to_vec());
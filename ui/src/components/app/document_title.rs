@@ -6,14 +6,18 @@
 //! - Showing unread message count in title when tab is hidden
 //! - Tracking document visibility state
 //! - Marking messages as read when tab becomes visible
+//! - Folding read markers synced from the user's other devices into each room
 
-use crate::components::app::chat_delegate::{flush_rooms_to_delegate, save_rooms_to_delegate};
+use crate::components::app::chat_delegate::{
+    flush_rooms_to_delegate, save_rooms_to_delegate, sync_read_markers_with_delegate,
+};
 use crate::components::app::{CURRENT_ROOM, ROOMS};
 use crate::util::ecies::unseal_bytes_with_secrets;
 use dioxus::logger::tracing::{debug, info, warn};
 use dioxus::prelude::*;
+use river_core::chat_delegate::ReadMarkerStore;
 use river_core::room_state::member::MemberId;
-use river_core::room_state::message::MessageId;
+use river_core::room_state::message::{MessageId, MessageOrderKey};
 use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
@@ -285,8 +289,9 @@ fn unreadable_body_may_become_readable(
 ///
 /// * `All` — every unread other-authored display message
 ///   ([`count_unread_in_room_data`]).
-/// * `MentionsAndReplies` — only unread messages that @mention the user or
-///   reply to one of their messages (the same
+/// * `MentionsAndReplies` — only unread messages that @mention the user
+///   (directly, or by an honoured `@here` / `@room`) or reply to one of their
+///   messages (the same
 ///   [`crate::components::app::notifications::mentions_or_replies_to_self`]
 ///   predicate that gates browser notifications). Zero qualifying messages
 ///   means no badge even when other unreads exist.
@@ -352,6 +357,7 @@ fn compute_mention_unread_count(room_data: &crate::room_data::RoomData) -> usize
     // scan: the reply check would otherwise re-hash every message in the
     // buffer (`id()` is a 64-byte rolling hash) once per reply candidate.
     let self_authored = SelfAuthoredIndex::new(recent, self_member_id);
+    let broadcasts = river_core::mention::BroadcastAudience::new(&room_data.room_state);
 
     unread_candidate_messages(room_data)
         .filter(|m| {
@@ -361,9 +367,13 @@ fn compute_mention_unread_count(room_data: &crate::room_data::RoomData) -> usize
             ) {
                 // Readable: the ordinary decision, against the text we just
                 // decrypted so the body is not decrypted twice.
-                Some(text) => {
-                    text_mentions_or_replies_to_self(m, &text, &room_data.secrets, &self_authored)
-                }
+                Some(text) => text_mentions_or_replies_to_self(
+                    m,
+                    &text,
+                    &room_data.secrets,
+                    &self_authored,
+                    &broadcasts,
+                ),
                 // Unreadable: count it only while there is reason to expect it
                 // to become readable — see the predicate's docs.
                 None => unreadable_body_may_become_readable(m, &room_data.secrets),
@@ -412,7 +422,9 @@ const MENTION_COUNT_CACHE_MAX: usize = 256;
 ///   the length is unchanged and a stale under-count would survive the very
 ///   event that fixes it (freenet/river#500 review);
 /// * `deleted.len()` — deletions change which messages are candidates;
-/// * the local member id — an identity import re-keys "self".
+/// * the local member id — an identity import re-keys "self";
+/// * the configuration version, the owner's deputies and the member count —
+///   together they decide whose `@here` / `@room` is honoured.
 fn mention_count_fingerprint(room_data: &crate::room_data::RoomData) -> u64 {
     use std::hash::{Hash, Hasher};
     let recent = &room_data.room_state.recent_messages;
@@ -426,6 +438,14 @@ fn mention_count_fingerprint(room_data: &crate::room_data::RoomData) -> u64 {
     secrets.hash(&mut h);
     recent.actions_state.deleted.len().hash(&mut h);
     room_data.self_member_id().hash(&mut h);
+    let state = &room_data.room_state;
+    let config = &state.configuration.configuration;
+    config.configuration_version.hash(&mut h);
+    state
+        .member_info
+        .deputies_of(config.owner_member_id)
+        .hash(&mut h);
+    state.members.members.len().hash(&mut h);
     h.finish()
 }
 
@@ -509,7 +529,17 @@ fn count_unread_dms(rooms: &crate::room_data::Rooms) -> usize {
         Ok(g) => g.clone(),
         Err(_) => return 0,
     };
-    count_unread_dms_with(&rooms.map, &last_seen, &hidden)
+    // Contention here only delays inbox-delivered DMs' badge to the next
+    // recompute, so degrade to room-only rather than zero.
+    let inbox = crate::components::direct_messages::INBOX_DMS
+        .try_read()
+        .map(|g| g.clone())
+        .unwrap_or_default();
+    let ignored = match crate::components::app::chat_delegate::IGNORED_MEMBERS.try_read() {
+        Ok(g) => g.clone(),
+        Err(_) => return 0,
+    };
+    count_unread_dms_with(&rooms.map, &last_seen, &hidden, &inbox, &ignored)
 }
 
 /// Pure core of [`count_unread_dms`], mirroring the DM rail's per-thread
@@ -520,7 +550,8 @@ fn count_unread_dms(rooms: &crate::room_data::Rooms) -> usize {
 /// `filter_rail_entries` applies. Revival is INBOUND-only, exactly like the
 /// rail's `last_inbound_ts` (issue freenet/river#526). Without this
 /// filter the unread tallies (title, hamburger badge) count messages the
-/// user has no visible thread for and no way to clear.
+/// user has no visible thread for and no way to clear. Threads with ignored
+/// members are skipped for the same reason: the rail leaves them out.
 fn count_unread_dms_with(
     map: &std::collections::HashMap<ed25519_dalek::VerifyingKey, crate::room_data::RoomData>,
     last_seen: &std::collections::HashMap<(ed25519_dalek::VerifyingKey, MemberId), u64>,
@@ -528,6 +559,11 @@ fn count_unread_dms_with(
         (ed25519_dalek::VerifyingKey, MemberId),
         river_core::chat_delegate::HiddenDmThreadEntry,
     >,
+    inbox: &std::collections::HashMap<
+        ed25519_dalek::VerifyingKey,
+        Vec<river_core::room_state::direct_messages::AuthorizedDirectMessage>,
+    >,
+    ignored: &river_core::chat_delegate::IgnoreList,
 ) -> usize {
     let mut total = 0usize;
     for (owner_key, room_data) in map {
@@ -551,7 +587,12 @@ fn count_unread_dms_with(
         }
         let mut per_peer: std::collections::HashMap<MemberId, Acc> =
             std::collections::HashMap::new();
-        for msg in &room_data.room_state.direct_messages.messages {
+        // In-room and inbox-delivered DMs alike (#230 Phase 4).
+        let inbox_dms = inbox.get(owner_key).map(Vec::as_slice).unwrap_or_default();
+        for msg in crate::components::direct_messages::room_and_inbox_dms(
+            &room_data.room_state.direct_messages.messages,
+            inbox_dms,
+        ) {
             let is_self_sender = msg.message.sender == self_id;
             let is_self_recipient = msg.message.recipient == self_id;
             if !is_self_sender && !is_self_recipient {
@@ -578,6 +619,9 @@ fn count_unread_dms_with(
         }
 
         for (peer, acc) in per_peer {
+            if ignored.is_ignored(&peer) {
+                continue;
+            }
             // INBOUND-only, matching the rail filter (issue freenet/river#526).
             // Using `last_any_ts` here would let our OWN outbound DM's
             // locally-clocked timestamp decide whether a peer's message counts
@@ -737,6 +781,9 @@ pub fn mark_current_room_as_read() {
         if let Err(e) = save_rooms_to_delegate().await {
             warn!("Failed to save rooms after marking as read: {}", e);
         }
+        if let Err(e) = sync_read_markers_with_delegate().await {
+            warn!("Failed to sync read markers: {}", e);
+        }
     });
 
     // Update title
@@ -802,10 +849,84 @@ pub fn mark_all_rooms_as_read() {
             if let Err(e) = flush_rooms_to_delegate().await {
                 warn!("Failed to save rooms after marking all as read: {}", e);
             }
+            if let Err(e) = sync_read_markers_with_delegate().await {
+                warn!("Failed to sync read markers: {}", e);
+            }
         });
     });
 }
 
+/// The order key of the room's `last_read_message_id`, or `None` when there
+/// is no marker or its message has left the buffer. This is what a read on
+/// this device contributes to the shared [`ReadMarkerStore`].
+pub(crate) fn room_read_position(
+    room_data: &crate::room_data::RoomData,
+) -> Option<MessageOrderKey> {
+    let id = room_data.last_read_message_id.as_ref()?;
+    room_data
+        .room_state
+        .recent_messages
+        .messages
+        .iter()
+        .find(|m| &m.id() == id)
+        .map(|m| m.order_key())
+}
+
+/// Move `last_read_message_id` up to the newest message at or before
+/// `marker`, never back. A marker past everything held here (the other
+/// device had newer messages) marks the whole buffer read. Returns whether
+/// the room changed.
+fn advance_last_read_to(
+    room_data: &mut crate::room_data::RoomData,
+    marker: &MessageOrderKey,
+) -> bool {
+    let messages = &room_data.room_state.recent_messages.messages;
+    let Some(target) = messages.iter().rposition(|m| &m.order_key() <= marker) else {
+        return false;
+    };
+    let current = room_data
+        .last_read_message_id
+        .as_ref()
+        .and_then(|id| messages.iter().position(|m| &m.id() == id));
+    if current.is_some_and(|idx| idx >= target) {
+        return false;
+    }
+    room_data.last_read_message_id = Some(messages[target].id());
+    true
+}
+
+/// Fold read markers synced from the delegate into every loaded room, so a
+/// room read on another device stops counting as unread here.
+///
+/// Not saved back to the rooms blob on its own: the markers are already
+/// durable in the delegate, and the next ordinary save carries the advanced
+/// `last_read_message_id` along.
+pub fn apply_read_markers(markers: ReadMarkerStore) {
+    crate::util::defer(move || {
+        let mut advanced = 0;
+        ROOMS.with_mut(|rooms| {
+            for entry in &markers.markers {
+                let Ok(owner_vk) = ed25519_dalek::VerifyingKey::from_bytes(&entry.room_owner_vk)
+                else {
+                    continue;
+                };
+                if let Some(room_data) = rooms.map.get_mut(&owner_vk) {
+                    if advance_last_read_to(room_data, &entry.last_read) {
+                        advanced += 1;
+                    }
+                }
+            }
+        });
+        if advanced > 0 {
+            info!(
+                "Read markers from another device advanced {} room(s)",
+                advanced
+            );
+            update_document_title();
+        }
+    });
+}
+
 /// Handle visibility change event
 fn on_visibility_change() {
     let is_visible = get_visibility_state();
@@ -898,6 +1019,7 @@ pub fn DocumentTitleUpdater() -> Element {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use river_core::chat_delegate::{IgnoreList, IgnoredMember};
 
     /// Source-grep pin (freenet/river#500 review C2): every cross-room total
     /// must go through the MODE-AWARE sum.
@@ -1088,6 +1210,36 @@ mod tests {
         assert_eq!(count_unread_in_room_data(&rd), 0);
     }
 
+    #[test]
+    fn a_synced_marker_advances_but_never_rewinds_the_local_one() {
+        let (self_sk, _) = keypair();
+        let (owner_sk, owner_vk) = keypair();
+        let messages = vec![
+            msg(&owner_sk, &owner_vk, 1),
+            msg(&owner_sk, &owner_vk, 2),
+            msg(&owner_sk, &owner_vk, 3),
+        ];
+        let first = messages[0].id();
+        let second = messages[1].order_key();
+        let mut rd = room(self_sk, owner_vk, messages, Some(first));
+
+        assert!(advance_last_read_to(&mut rd, &second));
+        assert_eq!(count_unread_in_room_data(&rd), 1);
+        assert_eq!(room_read_position(&rd), Some(second.clone()));
+
+        // An older marker from a device that lagged behind changes nothing.
+        let mut older = second.clone();
+        older.time -= Duration::from_secs(1);
+        assert!(!advance_last_read_to(&mut rd, &older));
+
+        // A marker past everything held here (the other device saw newer
+        // messages) marks the whole buffer read.
+        let mut later = second;
+        later.time += Duration::from_secs(60);
+        assert!(advance_last_read_to(&mut rd, &later));
+        assert_eq!(count_unread_in_room_data(&rd), 0);
+    }
+
     #[test]
     fn pruned_marker_falls_back_to_all_other_authored() {
         // Regression: if last_read_message_id points at a message that has
@@ -1919,13 +2071,97 @@ mod tests {
 
         // No last-seen: both inbound messages count, outbound doesn't.
         assert_eq!(
-            count_unread_dms_with(&map, &HashMap::new(), &HashMap::new()),
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashMap::new(),
+                &IgnoreList::default()
+            ),
             2
         );
         // Seen up to ts=100: only the ts=200 inbound counts.
         let mut seen = HashMap::new();
         seen.insert((owner_vk, peer_id), 100u64);
-        assert_eq!(count_unread_dms_with(&map, &seen, &HashMap::new()), 1);
+        assert_eq!(
+            count_unread_dms_with(
+                &map,
+                &seen,
+                &HashMap::new(),
+                &HashMap::new(),
+                &IgnoreList::default()
+            ),
+            1
+        );
+    }
+
+    /// DMs from an ignored member never count: the rail has no thread for
+    /// them to be read in.
+    #[test]
+    fn dm_unread_skips_ignored_members() {
+        let (self_sk, self_vk) = keypair();
+        let (_owner_sk, owner_vk) = keypair();
+        let (peer_sk, peer_vk) = keypair();
+        let self_id: MemberId = (&self_vk).into();
+        let peer_id: MemberId = (&peer_vk).into();
+
+        let mut rd = room(self_sk, owner_vk, vec![], None);
+        rd.room_state.direct_messages.messages = vec![dm(peer_id, self_id, 100, &peer_sk)];
+        let mut map = HashMap::new();
+        map.insert(owner_vk, rd);
+
+        let mut ignored = IgnoreList::default();
+        ignored.set(IgnoredMember {
+            member_id: peer_id,
+            ignored: true,
+            purge_dms: false,
+            updated_at: 1,
+        });
+        assert_eq!(
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashMap::new(),
+                &ignored
+            ),
+            0
+        );
+    }
+
+    /// A DM that reached the user's inbox contract instead of the room
+    /// (#230 Phase 4) counts exactly like an in-room one.
+    #[test]
+    fn dm_unread_counts_inbox_delivered_dms() {
+        let (self_sk, self_vk) = keypair();
+        let (_owner_sk, owner_vk) = keypair();
+        let (peer_sk, peer_vk) = keypair();
+        let (other_signer, _) = keypair();
+        let self_id: MemberId = (&self_vk).into();
+        let peer_id: MemberId = (&peer_vk).into();
+
+        let mut rd = room(self_sk, owner_vk, vec![], None);
+        let in_room = dm(peer_id, self_id, 100, &peer_sk);
+        rd.room_state.direct_messages.messages = vec![in_room.clone()];
+        let mut map = HashMap::new();
+        map.insert(owner_vk, rd);
+
+        // The inbox repeats the in-room DM (counted once) and adds one more.
+        let mut inbox = HashMap::new();
+        inbox.insert(
+            owner_vk,
+            vec![in_room, dm(peer_id, self_id, 200, &other_signer)],
+        );
+        assert_eq!(
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &HashMap::new(),
+                &inbox,
+                &IgnoreList::default()
+            ),
+            2
+        );
     }
 
     #[test]
@@ -1955,7 +2191,16 @@ mod tests {
             },
         );
         // Hidden at the newest message's ts (<=): thread invisible → 0.
-        assert_eq!(count_unread_dms_with(&map, &HashMap::new(), &hidden), 0);
+        assert_eq!(
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &hidden,
+                &HashMap::new(),
+                &IgnoreList::default()
+            ),
+            0
+        );
 
         // A strictly newer inbound message revives the thread: both its
         // unread messages count again (matching the rail badge).
@@ -1965,7 +2210,16 @@ mod tests {
             .direct_messages
             .messages
             .push(dm(peer_id, self_id, 150, &peer_sk));
-        assert_eq!(count_unread_dms_with(&map, &HashMap::new(), &hidden), 2);
+        assert_eq!(
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &hidden,
+                &HashMap::new(),
+                &IgnoreList::default()
+            ),
+            2
+        );
     }
 
     #[test]
@@ -2008,7 +2262,13 @@ mod tests {
         // last_inbound_ts = 90 <= hidden_at 90 -> still archived. The
         // outbound at 150 does NOT drag it back into the tally.
         assert_eq!(
-            count_unread_dms_with(&map, &HashMap::new(), &hidden),
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &hidden,
+                &HashMap::new(),
+                &IgnoreList::default()
+            ),
             0,
             "an outbound reply must not revive the thread via the timestamp \
              filter (#526) - revival comes from the explicit unhide"
@@ -2017,7 +2277,13 @@ mod tests {
         // What `do_send` actually does: drop the entry. Now it counts.
         hidden.remove(&(owner_vk, peer_id));
         assert_eq!(
-            count_unread_dms_with(&map, &HashMap::new(), &hidden),
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &hidden,
+                &HashMap::new(),
+                &IgnoreList::default()
+            ),
             1,
             "the explicit unhide on outbound send is what revives the thread"
         );
@@ -2051,7 +2317,16 @@ mod tests {
             },
         );
         // last_inbound_ts = 150 > 90 -> revived, and BOTH inbound DMs count.
-        assert_eq!(count_unread_dms_with(&map, &HashMap::new(), &hidden), 2);
+        assert_eq!(
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &hidden,
+                &HashMap::new(),
+                &IgnoreList::default()
+            ),
+            2
+        );
     }
 
     #[test]
@@ -2071,7 +2346,13 @@ mod tests {
         map.insert(owner_vk, rd);
 
         assert_eq!(
-            count_unread_dms_with(&map, &HashMap::new(), &HashMap::new()),
+            count_unread_dms_with(
+                &map,
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashMap::new(),
+                &IgnoreList::default()
+            ),
             0
         );
     }
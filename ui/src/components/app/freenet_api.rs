@@ -10,7 +10,10 @@ pub mod constants;
 pub mod delegate_migration;
 pub mod error;
 pub mod freenet_synchronizer;
+pub mod inbox_sync;
+pub mod presence_sync;
 pub mod response_handler;
 pub mod room_synchronizer;
+pub mod space_sync;
 
 pub use freenet_synchronizer::FreenetSynchronizer;
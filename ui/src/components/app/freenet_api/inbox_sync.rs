@@ -0,0 +1,286 @@
+//! Reading the local user's DM inboxes (#230 Phase 4).
+//!
+//! In-room DMs share one global cap per room, so a few busy pairs can evict
+//! every other conversation (see `DEFAULT_MAX_DIRECT_MESSAGES`). The fix is a
+//! per-member inbox contract (`river_core::inbox`): each identity the user
+//! holds gets one, and inbox-aware senders deliver there instead of the room.
+//!
+//! This module publishes an inbox for every distinct identity in [`ROOMS`]
+//! the user can sign with, carrying that identity's signed allowlist of the
+//! rooms it is held in — which is also what tells senders to deliver there —
+//! subscribes to it, and exposes the verified inbound DMs through
+//! [`INBOX_DMS`](crate::components::direct_messages::INBOX_DMS), keyed by the
+//! room each DM was sent in. During the transition both sources are live, so
+//! every DM reader merges them. Sending from the UI still goes in-room.
+//!
+//! Nothing here runs unless the build enables the `inbox-contract` feature,
+//! which bundles the released `inbox_contract.wasm`:
+//! [`inbox_contract_available`] gates every entry point.
+//!
+//! ## Routing
+//!
+//! An inbox contract id is neither a room id nor in `SYNC_INFO`, so
+//! [`INBOXES`] maps each one back to its owner and the latest merged state,
+//! and the response handler routes any contract response for those ids here
+//! via [`is_inbox_instance`] before the room handlers see it. A plain `Mutex`
+//! map like `backward_probe::PROBE_ROUTES`: bookkeeping with no UI reactivity;
+//! only the derived [`INBOX_DMS`](crate::components::direct_messages::INBOX_DMS)
+//! is a signal.
+
+use crate::components::app::ROOMS;
+use crate::components::direct_messages::INBOX_DMS;
+use crate::constants::INBOX_CONTRACT_WASM;
+use crate::util::{safe_spawn_local, to_cbor_vec, try_from_cbor_slice};
+use dioxus::logger::tracing::{debug, info, warn};
+use dioxus::prelude::*;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use freenet_scaffold::ComposableState;
+use freenet_stdlib::client_api::{ClientRequest, ContractRequest, ContractResponse};
+use freenet_stdlib::prelude::{
+    ContractCode, ContractContainer, ContractInstanceId, ContractKey, ContractWasmAPIVersion,
+    Parameters, UpdateData, WrappedContract, WrappedState,
+};
+use river_core::inbox::{AuthorizedInboxRoomsV1, InboxDeltaV1, InboxParametersV1, InboxStateV1};
+use river_core::room_state::direct_messages::AuthorizedDirectMessage;
+use river_core::room_state::member::MemberId;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+
+/// Each requested inbox, by contract id: its owner and the merged state seen
+/// so far.
+static INBOXES: LazyLock<Mutex<HashMap<ContractInstanceId, (VerifyingKey, InboxStateV1)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn inboxes() -> MutexGuard<'static, HashMap<ContractInstanceId, (VerifyingKey, InboxStateV1)>> {
+    INBOXES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether this build bundles the inbox contract.
+pub fn inbox_contract_available() -> bool {
+    INBOX_CONTRACT_WASM.is_some()
+}
+
+/// Is `instance_id` one of the local user's inbox contracts?
+pub fn is_inbox_instance(instance_id: &ContractInstanceId) -> bool {
+    inboxes().contains_key(instance_id)
+}
+
+/// The contract id a contract response is about, if the variant carries one.
+pub fn response_instance_id(response: &ContractResponse) -> Option<&ContractInstanceId> {
+    match response {
+        ContractResponse::GetResponse { key, .. }
+        | ContractResponse::PutResponse { key }
+        | ContractResponse::UpdateNotification { key, .. }
+        | ContractResponse::UpdateResponse { key, .. }
+        | ContractResponse::SubscribeResponse { key, .. } => Some(key.id()),
+        _ => None,
+    }
+}
+
+/// `None` when this build bundles no inbox contract.
+fn inbox_contract(owner: &VerifyingKey) -> Option<(ContractKey, ContractContainer)> {
+    let params = Parameters::from(to_cbor_vec(&InboxParametersV1 { owner: *owner }));
+    let code = ContractCode::from(INBOX_CONTRACT_WASM?);
+    let key = ContractKey::from_params_and_code(params.clone(), &code);
+    let container = ContractContainer::from(ContractWasmAPIVersion::V1(WrappedContract::new(
+        Arc::new(code),
+        params,
+    )));
+    Some((key, container))
+}
+
+/// Publish and subscribe to an inbox for every identity the user can sign
+/// with in a loaded room. Idempotent: an inbox already tracked is only
+/// republished when its allowlist lacks a room the identity is now held in
+/// (a space joins several rooms with one key), so this is safe to call after
+/// every room load.
+pub fn request_own_inboxes() {
+    if !inbox_contract_available() {
+        return;
+    }
+    let owners: HashMap<VerifyingKey, (SigningKey, Vec<VerifyingKey>)> = {
+        let Ok(rooms) = ROOMS.try_read() else {
+            return;
+        };
+        let mut owners: HashMap<VerifyingKey, (SigningKey, Vec<VerifyingKey>)> = HashMap::new();
+        for (room_owner, room_data) in &rooms.map {
+            let Some(sk) = room_data.signing_key() else {
+                continue;
+            };
+            owners
+                .entry(sk.verifying_key())
+                .or_insert_with(|| (sk.clone(), Vec::new()))
+                .1
+                .push(*room_owner);
+        }
+        owners
+    };
+
+    for (owner, (sk, mut rooms)) in owners {
+        rooms.sort_by_key(|vk| vk.to_bytes());
+        let Some((key, container)) = inbox_contract(&owner) else {
+            return;
+        };
+        let (is_new, update) = {
+            let mut inboxes = inboxes();
+            let is_new = !inboxes.contains_key(key.id());
+            let (_, state) = inboxes
+                .entry(*key.id())
+                .or_insert_with(|| (owner, InboxStateV1::default()));
+            let current = state.rooms.as_ref();
+            if !is_new && current.is_some_and(|c| rooms.iter().all(|room| c.allows(room))) {
+                continue;
+            }
+            let now = crate::util::get_current_system_time()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let allowlist = AuthorizedInboxRoomsV1::new(&sk, current, now, rooms);
+            // Held locally at once, so the next call sees the rooms covered
+            // without waiting for the update notification.
+            let parent = state.clone();
+            let _ = state.apply_delta(
+                &parent,
+                &InboxParametersV1 { owner },
+                &Some(InboxDeltaV1 {
+                    rooms: Some(allowlist.clone()),
+                    ..Default::default()
+                }),
+            );
+            let update = InboxStateV1 {
+                rooms: Some(allowlist),
+                ..Default::default()
+            };
+            (is_new, update)
+        };
+        info!("Publishing DM inbox for {:?}", MemberId::from(owner));
+
+        safe_spawn_local(async move {
+            // PUT the allowlist: merged into the inbox if it already exists,
+            // and the inbox's creation if it doesn't. The first time,
+            // subscribing keeps new DMs arriving as update notifications and
+            // the GET picks up whatever landed while this client was away.
+            let put_request = ContractRequest::Put {
+                contract: container,
+                state: WrappedState::new(to_cbor_vec(&update)),
+                related_contracts: Default::default(),
+                subscribe: is_new,
+                blocking_subscribe: false,
+            };
+            let get_request = ContractRequest::Get {
+                key: *key.id(),
+                return_contract_code: false,
+                subscribe: false,
+                blocking_subscribe: false,
+            };
+            let requests = if is_new {
+                vec![put_request, get_request]
+            } else {
+                vec![put_request]
+            };
+            for request in requests {
+                let result = match crate::components::app::WEB_API.write().as_mut() {
+                    Some(web_api) => web_api.send(ClientRequest::ContractOp(request)).await,
+                    None => return,
+                };
+                if let Err(e) = result {
+                    warn!("Failed to send DM inbox request for {}: {}", key.id(), e);
+                }
+            }
+        });
+    }
+}
+
+/// Handle a contract response for an inbox id (see [`is_inbox_instance`]).
+pub fn handle_inbox_response(response: ContractResponse) {
+    match response {
+        ContractResponse::GetResponse { key, state, .. } => {
+            merge_full_state(key.id(), state.as_ref());
+        }
+        ContractResponse::UpdateNotification { key, update } => match update {
+            UpdateData::State(state) => merge_full_state(key.id(), state.as_ref()),
+            UpdateData::Delta(delta) => merge_delta(key.id(), delta.as_ref()),
+            UpdateData::StateAndDelta { state, .. } => merge_full_state(key.id(), state.as_ref()),
+            other => debug!("Ignoring DM inbox update for {}: {:?}", key.id(), other),
+        },
+        other => debug!("DM inbox response: {:?}", other),
+    }
+}
+
+fn merge_full_state(id: &ContractInstanceId, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    let Some(state) = try_from_cbor_slice::<InboxStateV1>(bytes) else {
+        warn!("DM inbox state for {} did not deserialize", id);
+        return;
+    };
+    merge_delta_value(
+        id,
+        InboxDeltaV1 {
+            new_messages: state.messages,
+            purges: state.purges,
+            rooms: state.rooms,
+        },
+    );
+}
+
+fn merge_delta(id: &ContractInstanceId, bytes: &[u8]) {
+    match try_from_cbor_slice::<InboxDeltaV1>(bytes) {
+        Some(delta) => merge_delta_value(id, delta),
+        None => warn!("DM inbox delta for {} did not deserialize", id),
+    }
+}
+
+/// Fold `delta` into the tracked state through the contract's own
+/// `apply_delta`, so every message is re-verified here and a forged one from
+/// a misbehaving peer is dropped exactly as the contract would drop it.
+fn merge_delta_value(id: &ContractInstanceId, delta: InboxDeltaV1) {
+    let (owner, merged) = {
+        let mut inboxes = inboxes();
+        let Some((owner, state)) = inboxes.get_mut(id) else {
+            return;
+        };
+        let params = InboxParametersV1 { owner: *owner };
+        let parent = state.clone();
+        if let Err(e) = state.apply_delta(&parent, &params, &Some(delta.clone())) {
+            // A bad purge envelope fails the whole delta; keep its messages.
+            warn!("DM inbox purge for {} rejected: {}", id, e);
+            let messages_only = InboxDeltaV1 {
+                new_messages: delta.new_messages,
+                purges: None,
+                rooms: delta.rooms,
+            };
+            let _ = state.apply_delta(&parent, &params, &Some(messages_only));
+        }
+        (*owner, state.clone())
+    };
+    publish_inbox_dms(owner, &merged);
+}
+
+/// Replace `owner`'s contribution to [`INBOX_DMS`]. Deferred: this runs from
+/// the response handler, where a synchronous signal write could re-enter a
+/// borrow a component holds.
+fn publish_inbox_dms(owner: VerifyingKey, state: &InboxStateV1) {
+    let owner_id = MemberId::from(owner);
+    let mut by_room: HashMap<VerifyingKey, Vec<AuthorizedDirectMessage>> = HashMap::new();
+    for message in &state.messages {
+        by_room
+            .entry(*message.shared_room())
+            .or_default()
+            .push(message.dm.clone());
+    }
+    crate::util::defer(move || {
+        INBOX_DMS.with_mut(|all| {
+            // Drop this inbox's previous DMs (a purge removes them), keeping
+            // other identities' — one room can hold DMs for only one of them.
+            for dms in all.values_mut() {
+                dms.retain(|dm| dm.message.recipient != owner_id);
+            }
+            for (room, dms) in by_room {
+                all.entry(room).or_default().extend(dms);
+            }
+            all.retain(|_, dms| !dms.is_empty());
+        });
+    });
+}
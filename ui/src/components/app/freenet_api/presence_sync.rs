@@ -0,0 +1,293 @@
+//! Room presence: who has River open, and who is typing.
+//!
+//! Presence lives in a per-room presence contract (`river_core::presence`),
+//! never in the room state: heartbeats churn every few seconds and would
+//! otherwise compete with messages for the room's retention budget. Every
+//! room the user holds a signing key for gets a heartbeat every
+//! [`HEARTBEAT_INTERVAL_SECS`], and the composer sends extra `typing` ones
+//! through [`note_typing`]. The verified state of each room's presence is
+//! exposed through [`ROOM_PRESENCE`] for the conversation header and the
+//! member list.
+//!
+//! Nothing here runs unless the build enables the `presence-contract`
+//! feature, which bundles the released `presence_contract.wasm`:
+//! [`presence_contract_available`] gates every entry point.
+//!
+//! ## Routing
+//!
+//! As with `inbox_sync`, a presence contract id is neither a room id nor in
+//! `SYNC_INFO`, so [`PRESENCE`] maps each one back to its room and the latest
+//! merged state, and the response handler routes responses for those ids
+//! here via [`is_presence_instance`].
+
+use super::inbox_sync::response_instance_id;
+use crate::components::app::{ROOMS, WEB_API};
+use crate::constants::PRESENCE_CONTRACT_WASM;
+use crate::util::{defer, safe_spawn_local, to_cbor_vec, try_from_cbor_slice};
+use dioxus::logger::tracing::{debug, warn};
+use dioxus::prelude::*;
+use ed25519_dalek::VerifyingKey;
+use freenet_scaffold::ComposableState;
+use freenet_stdlib::client_api::{ClientRequest, ContractRequest, ContractResponse};
+use freenet_stdlib::prelude::{
+    ContractCode, ContractContainer, ContractInstanceId, ContractKey, ContractWasmAPIVersion,
+    Parameters, UpdateData, WrappedContract, WrappedState,
+};
+use river_core::presence::{
+    AuthorizedHeartbeatV1, PresenceDeltaV1, PresenceParametersV1, PresenceStateV1,
+    HEARTBEAT_INTERVAL_SECS, TYPING_WINDOW_SECS,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Verified presence per room, by room owner key.
+pub static ROOM_PRESENCE: GlobalSignal<HashMap<VerifyingKey, PresenceStateV1>> =
+    Global::new(HashMap::new);
+
+/// Unix seconds, advanced every [`PRESENCE_TICK`]. Presence is judged
+/// against the reader's clock, so a view reads this to re-render when a
+/// heartbeat ages out rather than only when a new one arrives.
+pub static PRESENCE_NOW: GlobalSignal<u64> = Global::new(now_secs);
+
+/// How often the presence loop wakes: often enough that "is typing" clears
+/// promptly after [`TYPING_WINDOW_SECS`].
+const PRESENCE_TICK: Duration = Duration::from_secs(3);
+
+/// Each tracked presence contract, by contract id: its room and the merged
+/// state seen so far.
+static PRESENCE: LazyLock<Mutex<HashMap<ContractInstanceId, (VerifyingKey, PresenceStateV1)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// When this client last announced typing, per room, to throttle keystrokes
+/// down to one heartbeat per half typing window.
+static LAST_TYPING: LazyLock<Mutex<HashMap<VerifyingKey, u64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static PRESENCE_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn presence() -> MutexGuard<'static, HashMap<ContractInstanceId, (VerifyingKey, PresenceStateV1)>> {
+    PRESENCE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn now_secs() -> u64 {
+    crate::util::get_current_system_time()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Whether this build bundles the presence contract.
+pub fn presence_contract_available() -> bool {
+    PRESENCE_CONTRACT_WASM.is_some()
+}
+
+/// Is `instance_id` one of the tracked presence contracts?
+pub fn is_presence_instance(instance_id: &ContractInstanceId) -> bool {
+    presence().contains_key(instance_id)
+}
+
+/// Does this contract response belong to a presence contract?
+pub fn is_presence_response(response: &ContractResponse) -> bool {
+    response_instance_id(response).is_some_and(is_presence_instance)
+}
+
+/// `None` when this build bundles no presence contract.
+fn presence_contract(room_owner: &VerifyingKey) -> Option<(ContractKey, ContractContainer)> {
+    let params = Parameters::from(to_cbor_vec(&PresenceParametersV1 {
+        room_owner: *room_owner,
+    }));
+    let code = ContractCode::from(PRESENCE_CONTRACT_WASM?);
+    let key = ContractKey::from_params_and_code(params.clone(), &code);
+    let container = ContractContainer::from(ContractWasmAPIVersion::V1(WrappedContract::new(
+        Arc::new(code),
+        params,
+    )));
+    Some((key, container))
+}
+
+/// Start tracking presence for the loaded rooms and heart-beating in them.
+/// Idempotent; rooms joined later are picked up on the next tick.
+pub fn start_presence() {
+    if !presence_contract_available() || PRESENCE_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    safe_spawn_local(async {
+        let mut next_heartbeat = 0;
+        loop {
+            let now = now_secs();
+            defer(move || *PRESENCE_NOW.write() = now);
+            track_new_rooms();
+            if now >= next_heartbeat {
+                let rooms: Vec<VerifyingKey> = match ROOMS.try_read() {
+                    Ok(rooms) => rooms.map.keys().copied().collect(),
+                    Err(_) => Vec::new(),
+                };
+                for room in rooms {
+                    announce(room, false);
+                }
+                next_heartbeat = now + HEARTBEAT_INTERVAL_SECS;
+            }
+            crate::util::sleep(PRESENCE_TICK).await;
+        }
+    });
+}
+
+/// Tell the room the local user is typing. Called on every keystroke and
+/// throttled here.
+pub fn note_typing(room_owner: VerifyingKey) {
+    if !presence_contract_available() {
+        return;
+    }
+    let now = now_secs();
+    {
+        let mut last = LAST_TYPING.lock().unwrap_or_else(|e| e.into_inner());
+        if last
+            .get(&room_owner)
+            .is_some_and(|sent| now < sent + TYPING_WINDOW_SECS / 2)
+        {
+            return;
+        }
+        last.insert(room_owner, now);
+    }
+    announce(room_owner, true);
+}
+
+/// PUT an empty presence state (creating the contract if nobody has yet)
+/// with a subscription, then GET it, for every room not yet tracked.
+fn track_new_rooms() {
+    let rooms: Vec<VerifyingKey> = match ROOMS.try_read() {
+        Ok(rooms) => rooms.map.keys().copied().collect(),
+        Err(_) => return,
+    };
+    for room in rooms {
+        let Some((key, container)) = presence_contract(&room) else {
+            return;
+        };
+        {
+            let mut presence = presence();
+            if presence.contains_key(key.id()) {
+                continue;
+            }
+            presence.insert(*key.id(), (room, PresenceStateV1::default()));
+        }
+        safe_spawn_local(async move {
+            let put_request = ContractRequest::Put {
+                contract: container,
+                state: WrappedState::new(to_cbor_vec(&PresenceStateV1::default())),
+                related_contracts: Default::default(),
+                subscribe: true,
+                blocking_subscribe: false,
+            };
+            let get_request = ContractRequest::Get {
+                key: *key.id(),
+                return_contract_code: false,
+                subscribe: false,
+                blocking_subscribe: false,
+            };
+            for request in [put_request, get_request] {
+                send(request, &key).await;
+            }
+        });
+    }
+}
+
+/// Sign and send a heartbeat for `room_owner`, if the user can sign there.
+fn announce(room_owner: VerifyingKey, typing: bool) {
+    let heartbeat = {
+        let Ok(rooms) = ROOMS.try_read() else {
+            return;
+        };
+        let Some(room) = rooms.map.get(&room_owner) else {
+            return;
+        };
+        let Some(sk) = room.signing_key() else {
+            return;
+        };
+        match AuthorizedHeartbeatV1::new(sk, room_owner, &room.room_state, now_secs(), typing) {
+            Ok(heartbeat) => heartbeat,
+            Err(e) => {
+                debug!("No presence heartbeat for {:?}: {}", room_owner, e);
+                return;
+            }
+        }
+    };
+    let Some((key, _)) = presence_contract(&room_owner) else {
+        return;
+    };
+    let delta = PresenceDeltaV1 {
+        heartbeats: vec![heartbeat],
+    };
+    let request = ContractRequest::Update {
+        key,
+        data: UpdateData::Delta(to_cbor_vec(&delta).into()),
+    };
+    safe_spawn_local(async move { send(request, &key).await });
+}
+
+async fn send(request: ContractRequest<'static>, key: &ContractKey) {
+    let result = match WEB_API.write().as_mut() {
+        Some(web_api) => web_api.send(ClientRequest::ContractOp(request)).await,
+        None => return,
+    };
+    if let Err(e) = result {
+        warn!("Failed to send presence request for {}: {}", key.id(), e);
+    }
+}
+
+/// Handle a contract response for a presence id (see [`is_presence_instance`]).
+pub fn handle_presence_response(response: ContractResponse) {
+    match response {
+        ContractResponse::GetResponse { key, state, .. } => {
+            merge_full_state(key.id(), state.as_ref());
+        }
+        ContractResponse::UpdateNotification { key, update } => match update {
+            UpdateData::State(state) => merge_full_state(key.id(), state.as_ref()),
+            UpdateData::Delta(delta) => match try_from_cbor_slice::<PresenceDeltaV1>(&delta) {
+                Some(delta) => merge(key.id(), delta),
+                None => warn!("Presence delta for {} did not deserialize", key.id()),
+            },
+            UpdateData::StateAndDelta { state, .. } => merge_full_state(key.id(), state.as_ref()),
+            other => debug!("Ignoring presence update for {}: {:?}", key.id(), other),
+        },
+        other => debug!("Presence response: {:?}", other),
+    }
+}
+
+fn merge_full_state(id: &ContractInstanceId, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    match try_from_cbor_slice::<PresenceStateV1>(bytes) {
+        Some(state) => merge(
+            id,
+            PresenceDeltaV1 {
+                heartbeats: state.heartbeats,
+            },
+        ),
+        None => warn!("Presence state for {} did not deserialize", id),
+    }
+}
+
+/// Fold `delta` in through the contract's own `apply_delta`, so a forged
+/// heartbeat is dropped here exactly as the contract would drop it.
+fn merge(id: &ContractInstanceId, delta: PresenceDeltaV1) {
+    let (room, merged) = {
+        let mut presence = presence();
+        let Some((room, state)) = presence.get_mut(id) else {
+            return;
+        };
+        let params = PresenceParametersV1 { room_owner: *room };
+        let parent = state.clone();
+        if let Err(e) = state.apply_delta(&parent, &params, &Some(delta)) {
+            warn!("Presence delta for {} rejected: {}", id, e);
+        }
+        (*room, state.clone())
+    };
+    // Deferred: this runs from the response handler, where a synchronous
+    // signal write could re-enter a borrow a component holds.
+    defer(move || {
+        ROOM_PRESENCE.write().insert(room, merged);
+    });
+}
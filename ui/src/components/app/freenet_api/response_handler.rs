@@ -90,6 +90,26 @@ impl ResponseHandler {
             HostResponse::Ok => {
                 info!("Received OK response from API");
             }
+            // DM inbox contracts (#230 Phase 4) are not rooms: route them
+            // before any room handler tries to resolve their id to an owner.
+            HostResponse::ContractResponse(contract_response)
+                if super::inbox_sync::response_instance_id(&contract_response)
+                    .is_some_and(super::inbox_sync::is_inbox_instance) =>
+            {
+                super::inbox_sync::handle_inbox_response(contract_response);
+            }
+            // Presence contracts likewise.
+            HostResponse::ContractResponse(contract_response)
+                if super::presence_sync::is_presence_response(&contract_response) =>
+            {
+                super::presence_sync::handle_presence_response(contract_response);
+            }
+            // And space contracts.
+            HostResponse::ContractResponse(contract_response)
+                if super::space_sync::is_space_response(&contract_response) =>
+            {
+                super::space_sync::handle_space_response(contract_response);
+            }
             HostResponse::ContractResponse(contract_response) => match contract_response {
                 ContractResponse::GetResponse {
                     key,
@@ -934,6 +954,50 @@ async fn load_rooms_per_room(keys: Vec<ChatDelegateKey>) {
                 had_fetch_error,
             ));
 
+            // Pick up reads, drafts, verified marks and ignores made on the
+            // user's other devices (and publish this one's) now that the
+            // rooms they refer to are loaded, start reading the DM inboxes of
+            // the identities they hold and announcing presence in them,
+            // follow the spaces they belong to, and start sending messages
+            // scheduled for those rooms.
+            if !loaded_map_empty {
+                super::inbox_sync::request_own_inboxes();
+                super::presence_sync::start_presence();
+                super::space_sync::start_spaces();
+                crate::components::app::chat_delegate::start_scheduled_message_dispatcher();
+                crate::util::safe_spawn_local(async {
+                    if let Err(e) =
+                        crate::components::app::chat_delegate::sync_read_markers_with_delegate()
+                            .await
+                    {
+                        warn!("Failed to sync read markers: {}", e);
+                    }
+                });
+                crate::util::safe_spawn_local(async {
+                    if let Err(e) =
+                        crate::components::app::chat_delegate::sync_drafts_with_delegate().await
+                    {
+                        warn!("Failed to sync drafts: {}", e);
+                    }
+                });
+                crate::util::safe_spawn_local(async {
+                    if let Err(e) =
+                        crate::components::app::chat_delegate::sync_verified_keys_with_delegate()
+                            .await
+                    {
+                        warn!("Failed to sync verified keys: {}", e);
+                    }
+                });
+                crate::util::safe_spawn_local(async {
+                    if let Err(e) =
+                        crate::components::app::chat_delegate::sync_ignored_members_with_delegate()
+                            .await
+                    {
+                        warn!("Failed to sync ignore list: {}", e);
+                    }
+                });
+            }
+
             // Recover from an interrupted migration by re-running it to pick up
             // any room whose per-room key wasn't written before the previous
             // attempt was cut short. `migrate_current_blob_to_per_room`
@@ -376,6 +376,7 @@ pub async fn handle_get_response(
                             version: 0,
                             preferred_nickname,
                             deputies: Vec::new(),
+                            profile: None,
                         },
                         &self_sk,
                     )
@@ -1603,6 +1604,7 @@ mod tests {
                 version: 0,
                 preferred_nickname: SealedBytes::public(b"Tester".to_vec()),
                 deputies: Vec::new(),
+                profile: None,
             },
             sk,
         )
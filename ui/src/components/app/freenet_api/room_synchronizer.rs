@@ -27,6 +27,7 @@ use freenet_stdlib::{
     },
 };
 use river_core::room_state::direct_messages::{DirectMessagesSummary, DmRetentionHorizon};
+use river_core::room_state::group_direct_messages::GroupDirectMessagesSummary;
 use river_core::room_state::member::MemberId;
 use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfoV1};
 use river_core::room_state::message::{AuthorizedMessageV1, MessagesSummary, RetentionHorizon};
@@ -153,8 +154,9 @@ fn outbound_summary(
     //
     // Scope of the guard, stated precisely because overstating it is what let
     // freenet/river#519 through: the top-level destructure catches a new field
-    // on `ChatRoomStateV1Summary` ITSELF, and the two leaf destructures below
-    // catch one added to `MessagesSummary` or `DirectMessagesSummary`. The other
+    // on `ChatRoomStateV1Summary` ITSELF, and the three leaf destructures below
+    // catch one added to `MessagesSummary`, `DirectMessagesSummary` or
+    // `GroupDirectMessagesSummary`. The other
     // seven leaf summaries — `members`, `bans`, `member_info`, `secrets`,
     // `configuration`, `upgrade`, `version` — are bound whole and are NOT
     // guarded. So when the `MembersV1` follow-up adds `MembersSummary.horizon`,
@@ -168,6 +170,7 @@ fn outbound_summary(
         secrets,
         recent_messages,
         direct_messages,
+        group_direct_messages,
         upgrade,
         version,
     } = baseline.summarize(baseline, params);
@@ -208,6 +211,15 @@ fn outbound_summary(
         global_horizon: DmRetentionHorizon::Open,
     };
 
+    let GroupDirectMessagesSummary {
+        message_signatures,
+        horizon: _drop_group_horizon,
+    } = group_direct_messages;
+    let group_direct_messages = GroupDirectMessagesSummary {
+        message_signatures,
+        horizon: DmRetentionHorizon::Open,
+    };
+
     ChatRoomStateV1Summary {
         configuration,
         bans,
@@ -216,6 +228,7 @@ fn outbound_summary(
         secrets,
         recent_messages,
         direct_messages,
+        group_direct_messages,
         upgrade,
         version,
     }
@@ -1745,32 +1758,6 @@ mod tests {
     use river_core::room_state::message::{AuthorizedMessageV1, MessageV1, RoomMessageBody};
     use std::time::SystemTime;
 
-    /// Every `ChatRoomStateV1Delta` field that is `Some`, by name.
-    ///
-    /// The outbound tests build `current` and `baseline` differing in EXACTLY
-    /// one field, so the whole delta must be exactly that one field. Asserting
-    /// per-field instead would miss the failure mode where `outbound_summary`
-    /// over-clears some OTHER field: e.g. adding `summary.bans.clear()` makes
-    /// every outgoing update re-send the full ban list forever, and a test that
-    /// only reads `delta.recent_messages` never notices.
-    fn populated_delta_fields(d: &ChatRoomStateV1Delta) -> Vec<&'static str> {
-        [
-            ("configuration", d.configuration.is_some()),
-            ("bans", d.bans.is_some()),
-            ("members", d.members.is_some()),
-            ("member_info", d.member_info.is_some()),
-            ("secrets", d.secrets.is_some()),
-            ("recent_messages", d.recent_messages.is_some()),
-            ("direct_messages", d.direct_messages.is_some()),
-            ("upgrade", d.upgrade.is_some()),
-            ("version", d.version.is_some()),
-        ]
-        .into_iter()
-        .filter(|(_, populated)| *populated)
-        .map(|(name, _)| name)
-        .collect()
-    }
-
     /// Populate `members` and `bans` so the whole-delta assertion in the
     /// outbound tests actually has something to bite on.
     ///
@@ -2643,7 +2630,7 @@ mod tests {
         // means `outbound_summary` over-cleared something and every sync tick
         // now re-sends it.
         assert_eq!(
-            populated_delta_fields(&delta),
+            delta.present_fields(),
             vec!["configuration", "recent_messages"],
             "the outgoing update touched fields the baseline already has"
         );
@@ -2888,7 +2875,7 @@ mod tests {
         );
 
         assert_eq!(
-            populated_delta_fields(&delta),
+            delta.present_fields(),
             vec!["direct_messages"],
             "the outgoing update touched fields the baseline already has"
         );
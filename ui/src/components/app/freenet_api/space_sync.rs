@@ -0,0 +1,184 @@
+//! Spaces: the space each room belongs to, for grouping the room list.
+//!
+//! A room names its space in `Configuration::space`, and the space's own
+//! contract (`river_core::space`) lists its rooms; a room is grouped under a
+//! space only when both agree. This module follows every space some loaded
+//! room points at and exposes the verified state through [`SPACES`]. It only
+//! reads: creating spaces, inviting and banning are `riverctl space` for now.
+//!
+//! Nothing here runs unless the UI is built with the `space-contract`
+//! feature, which bundles the released `space_contract.wasm`:
+//! [`space_contract_available`] gates every entry point.
+//!
+//! ## Routing
+//!
+//! As with `presence_sync`, a space contract id is neither a room id nor in
+//! `SYNC_INFO`, so [`TRACKED`] maps each one back to its owner key and the
+//! latest merged state, and the response handler routes responses for those
+//! ids here via [`is_space_response`].
+
+use super::inbox_sync::response_instance_id;
+use crate::components::app::{ROOMS, WEB_API};
+use crate::constants::SPACE_CONTRACT_WASM;
+use crate::util::{defer, safe_spawn_local, to_cbor_vec, try_from_cbor_slice};
+use dioxus::logger::tracing::{debug, warn};
+use dioxus::prelude::*;
+use ed25519_dalek::VerifyingKey;
+use freenet_scaffold::ComposableState;
+use freenet_stdlib::client_api::{ClientRequest, ContractRequest, ContractResponse};
+use freenet_stdlib::prelude::{
+    ContractCode, ContractInstanceId, ContractKey, Parameters, UpdateData,
+};
+use river_core::space::{SpaceDeltaV1, SpaceParametersV1, SpaceStateV1};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Verified state per space, by space owner key.
+pub static SPACES: GlobalSignal<HashMap<VerifyingKey, SpaceStateV1>> = Global::new(HashMap::new);
+
+/// How often to look for spaces that newly joined or re-pointed rooms name.
+const SPACE_TICK: Duration = Duration::from_secs(30);
+
+/// Each tracked space contract, by contract id: its owner and the merged
+/// state seen so far.
+static TRACKED: LazyLock<Mutex<HashMap<ContractInstanceId, (VerifyingKey, SpaceStateV1)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static SPACES_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn tracked() -> MutexGuard<'static, HashMap<ContractInstanceId, (VerifyingKey, SpaceStateV1)>> {
+    TRACKED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether this build bundles the space contract.
+pub fn space_contract_available() -> bool {
+    SPACE_CONTRACT_WASM.is_some()
+}
+
+/// Does this contract response belong to a tracked space contract?
+pub fn is_space_response(response: &ContractResponse) -> bool {
+    response_instance_id(response).is_some_and(|id| tracked().contains_key(id))
+}
+
+/// `None` when this build bundles no space contract.
+fn space_contract_key(owner: &VerifyingKey) -> Option<ContractKey> {
+    let code = ContractCode::from(SPACE_CONTRACT_WASM?);
+    let params = Parameters::from(to_cbor_vec(&SpaceParametersV1 { owner: *owner }));
+    Some(ContractKey::from_params_and_code(params, &code))
+}
+
+/// Start following the spaces the loaded rooms point at. Idempotent; spaces
+/// named later are picked up on the next tick.
+pub fn start_spaces() {
+    if !space_contract_available() || SPACES_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    safe_spawn_local(async {
+        loop {
+            track_new_spaces();
+            crate::util::sleep(SPACE_TICK).await;
+        }
+    });
+}
+
+/// GET with a subscription every space a room names that is not yet
+/// tracked. Unlike presence, a space is never created from here: a room
+/// naming a space nobody has published simply stays ungrouped.
+fn track_new_spaces() {
+    let spaces: Vec<VerifyingKey> = match ROOMS.try_read() {
+        Ok(rooms) => rooms
+            .map
+            .values()
+            .filter_map(|room| room.room_state.configuration.configuration.space)
+            .collect(),
+        Err(_) => return,
+    };
+    for owner in spaces {
+        let Some(key) = space_contract_key(&owner) else {
+            return;
+        };
+        {
+            let mut tracked = tracked();
+            if tracked.contains_key(key.id()) {
+                continue;
+            }
+            tracked.insert(*key.id(), (owner, SpaceStateV1::default()));
+        }
+        safe_spawn_local(async move {
+            let request = ContractRequest::Get {
+                key: *key.id(),
+                return_contract_code: false,
+                subscribe: true,
+                blocking_subscribe: false,
+            };
+            let result = match WEB_API.write().as_mut() {
+                Some(web_api) => web_api.send(ClientRequest::ContractOp(request)).await,
+                None => return,
+            };
+            if let Err(e) = result {
+                warn!("Failed to request space {}: {}", key.id(), e);
+            }
+        });
+    }
+}
+
+/// Handle a contract response for a space id (see [`is_space_response`]).
+pub fn handle_space_response(response: ContractResponse) {
+    match response {
+        ContractResponse::GetResponse { key, state, .. } => {
+            merge_full_state(key.id(), state.as_ref());
+        }
+        ContractResponse::UpdateNotification { key, update } => match update {
+            UpdateData::State(state) => merge_full_state(key.id(), state.as_ref()),
+            UpdateData::Delta(delta) => match try_from_cbor_slice::<SpaceDeltaV1>(&delta) {
+                Some(delta) => merge(key.id(), delta),
+                None => warn!("Space delta for {} did not deserialize", key.id()),
+            },
+            UpdateData::StateAndDelta { state, .. } => merge_full_state(key.id(), state.as_ref()),
+            other => debug!("Ignoring space update for {}: {:?}", key.id(), other),
+        },
+        other => debug!("Space response: {:?}", other),
+    }
+}
+
+fn merge_full_state(id: &ContractInstanceId, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    match try_from_cbor_slice::<SpaceStateV1>(bytes) {
+        Some(state) => merge(
+            id,
+            SpaceDeltaV1 {
+                config: state.config,
+                members: state.members,
+                bans: state.bans,
+                grants: state.grants,
+            },
+        ),
+        None => warn!("Space state for {} did not deserialize", id),
+    }
+}
+
+/// Fold `delta` in through the contract's own `apply_delta`, so an entry
+/// the contract would drop is dropped here too.
+fn merge(id: &ContractInstanceId, delta: SpaceDeltaV1) {
+    let (owner, merged) = {
+        let mut tracked = tracked();
+        let Some((owner, state)) = tracked.get_mut(id) else {
+            return;
+        };
+        let params = SpaceParametersV1 { owner: *owner };
+        let parent = state.clone();
+        if let Err(e) = state.apply_delta(&parent, &params, &Some(delta)) {
+            warn!("Space delta for {} rejected: {}", id, e);
+        }
+        (*owner, state.clone())
+    };
+    // Deferred for the same reason as presence: this runs from the response
+    // handler, where a synchronous signal write could re-enter a borrow.
+    defer(move || {
+        SPACES.write().insert(owner, merged);
+    });
+}
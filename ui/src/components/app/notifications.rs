@@ -12,6 +12,7 @@ use crate::util::ecies::{decrypt_with_symmetric_key, unseal_bytes_with_secrets};
 use dioxus::logger::tracing::{debug, info, warn};
 use dioxus::prelude::*;
 use ed25519_dalek::VerifyingKey;
+use river_core::mention::BroadcastAudience;
 use river_core::room_state::member::MemberId;
 use river_core::room_state::member_info::MemberInfoV1;
 use river_core::room_state::message::{AuthorizedMessageV1, MessageId, RoomMessageBody};
@@ -1071,6 +1072,8 @@ pub(crate) fn self_authored_ids(
 /// Pure decision for [`crate::room_data::NotificationMode::MentionsAndReplies`]:
 /// does `msg` @mention `self_member_id`, OR is it a reply to a message that
 /// `self_member_id` authored among `recent`? No globals, so it is unit-testable.
+/// This wrapper honours no `@here` / `@room`; the indexed forms take the
+/// room's [`BroadcastAudience`].
 ///
 /// The reply check resolves the reply's target id against `recent` and compares
 /// that message's author to self. If the target has scrolled out of `recent` we
@@ -1113,6 +1116,7 @@ pub(crate) fn mentions_or_replies_to_self(
         msg,
         room_secrets,
         &SelfAuthoredIndex::new(recent, self_member_id),
+        &BroadcastAudience::default(),
     )
 }
 
@@ -1123,11 +1127,12 @@ pub(crate) fn mentions_or_replies_to_self_indexed(
     msg: &AuthorizedMessageV1,
     room_secrets: &std::collections::HashMap<u32, [u8; 32]>,
     self_authored: &SelfAuthoredIndex<'_>,
+    broadcasts: &BroadcastAudience,
 ) -> bool {
     use crate::components::conversation::decrypt_message_content;
 
     let text = decrypt_message_content(&msg.message.content, room_secrets);
-    text_mentions_or_replies_to_self(msg, &text, room_secrets, self_authored)
+    text_mentions_or_replies_to_self(msg, &text, room_secrets, self_authored, broadcasts)
 }
 
 /// [`mentions_or_replies_to_self_indexed`] against a body that has ALREADY
@@ -1139,11 +1144,16 @@ pub(crate) fn mentions_or_replies_to_self_indexed(
 /// This is the single definition of "qualifies" — the notification gate and
 /// the unread counter both end up here, so they cannot drift on what counts
 /// as a mention or a reply.
+///
+/// A room-wide `@here` / `@room` counts as a mention when `broadcasts` says
+/// the author may send one and it reaches self. The audience is required
+/// rather than defaulted so no caller can quietly drop broadcasts.
 pub(crate) fn text_mentions_or_replies_to_self(
     msg: &AuthorizedMessageV1,
     text: &str,
     room_secrets: &std::collections::HashMap<u32, [u8; 32]>,
     self_authored: &SelfAuthoredIndex<'_>,
+    broadcasts: &BroadcastAudience,
 ) -> bool {
     use crate::components::conversation::extract_reply_target_id;
 
@@ -1151,7 +1161,7 @@ pub(crate) fn text_mentions_or_replies_to_self(
     // as well let the mention arm and the reply arm disagree about whose
     // messages these are — the paired-field shape the project's bug-prevention
     // rules call out.
-    if river_core::mention::contains_mention_of(text, self_authored.self_member_id()) {
+    if broadcasts.addresses(text, msg.message.author, self_authored.self_member_id()) {
         return true;
     }
 
@@ -1227,11 +1237,19 @@ pub fn notify_new_messages(
     //     return;
     // }
 
-    // Filter to messages from other users, excluding room events.
+    // Filter to messages from other users, excluding room events and members
+    // the user ignores. An unreadable ignore list notifies nothing, the same
+    // safe direction as the muted-room check below.
+    let Ok(ignored) = crate::components::app::chat_delegate::IGNORED_MEMBERS.try_read() else {
+        warn!("Ignore list unreadable while deciding notifications; skipping");
+        return;
+    };
     let external_messages: Vec<_> = new_messages
         .iter()
         .filter(|msg| is_notifiable_external_message(msg, self_member_id))
+        .filter(|msg| !ignored.is_ignored(&msg.message.author))
         .collect();
+    drop(ignored);
 
     info!(
         "Filtered to {} external messages (from {} total, self_member_id: {:?})",
@@ -1294,6 +1312,12 @@ pub fn notify_new_messages(
         .get(room_key)
         .map(|rd| self_authored_ids(&rd.room_state.recent_messages.messages, self_member_id))
         .unwrap_or_default();
+    // Only the mentions filter reads this, and it is cheap next to the id set.
+    let broadcasts = rooms
+        .map
+        .get(room_key)
+        .map(|rd| BroadcastAudience::new(&rd.room_state))
+        .unwrap_or_default();
     drop(rooms);
     let self_authored = SelfAuthoredIndex::from_ids(self_authored_id_set, self_member_id);
 
@@ -1319,7 +1343,9 @@ pub fn notify_new_messages(
         crate::room_data::NotificationMode::Muted => return,
         crate::room_data::NotificationMode::MentionsAndReplies => external_messages
             .into_iter()
-            .filter(|msg| mentions_or_replies_to_self_indexed(msg, room_secrets, &self_authored))
+            .filter(|msg| {
+                mentions_or_replies_to_self_indexed(msg, room_secrets, &self_authored, &broadcasts)
+            })
             .collect(),
     };
     if external_messages.is_empty() {
@@ -2056,6 +2082,47 @@ mod notify_gate_tests {
         assert!(mentions_or_replies_to_self(&msg, id_of(&me), &secrets, &[]));
     }
 
+    #[test]
+    fn room_wide_mention_notifies_only_when_its_author_may_broadcast() {
+        use river_core::mention::{encode_broadcast, Broadcast};
+        use river_core::room_state::configuration::{AuthorizedConfigurationV1, Configuration};
+        use river_core::ChatRoomStateV1;
+
+        let me = key(1);
+        let owner = key(2);
+        let stranger = key(3);
+        let secrets = HashMap::new();
+        let state = ChatRoomStateV1 {
+            configuration: AuthorizedConfigurationV1::new(
+                Configuration {
+                    owner_member_id: id_of(&owner),
+                    ..Default::default()
+                },
+                &owner,
+            ),
+            ..Default::default()
+        };
+        let audience = BroadcastAudience::new(&state);
+        let index = SelfAuthoredIndex::new(&[], id_of(&me));
+        let body =
+            || RoomMessageBody::public(format!("{} standup", encode_broadcast(Broadcast::Room)));
+
+        let from_owner = public_msg(&owner, body());
+        assert!(mentions_or_replies_to_self_indexed(
+            &from_owner,
+            &secrets,
+            &index,
+            &audience
+        ));
+        let from_stranger = public_msg(&stranger, body());
+        assert!(!mentions_or_replies_to_self_indexed(
+            &from_stranger,
+            &secrets,
+            &index,
+            &audience
+        ));
+    }
+
     #[test]
     fn does_not_notify_on_mention_of_someone_else() {
         let me = key(1);
@@ -2595,7 +2662,12 @@ mod notify_gate_tests {
             "the action gate itself allows it (this is the `All` behaviour)"
         );
         assert!(
-            !mentions_or_replies_to_self_indexed(&reaction, &secrets, &index),
+            !mentions_or_replies_to_self_indexed(
+                &reaction,
+                &secrets,
+                &index,
+                &BroadcastAudience::default()
+            ),
             "…but the mentions-and-replies predicate does not, so the mode \
              stays exactly as quiet as it was before #598"
         );
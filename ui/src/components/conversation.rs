@@ -1,3 +1,4 @@
+use crate::components::app::chat_delegate::IGNORED_MEMBERS;
 use crate::components::app::document_title::count_unread_behind_rooms_panel;
 #[cfg(target_arch = "wasm32")]
 use crate::components::app::notifications::request_permission_on_first_message;
@@ -21,11 +22,15 @@ use crate::util::{
     date_separator_labels, format_utc_as_full_datetime, format_utc_as_local_time,
     get_current_system_time, local_message_date, local_today,
 };
+mod custom_emoji;
 mod emoji_picker;
 mod mention;
 mod message_actions;
 mod message_input;
 mod not_member_notification;
+use self::custom_emoji::{
+    emoji_glyph, shortcodes_to_html, use_current_room_emoji, CustomEmojiButtons, EmojiImages,
+};
 use self::emoji_picker::FREQUENT_EMOJIS;
 use self::not_member_notification::NotMemberNotification;
 use crate::components::conversation::message_input::MessageInput;
@@ -38,6 +43,8 @@ use dioxus_free_icons::icons::fa_solid_icons::{
 };
 use dioxus_free_icons::Icon;
 use freenet_scaffold::ComposableState;
+use river_core::chat_delegate::IgnoreList;
+use river_core::room_state::configuration::Configuration;
 use river_core::room_state::member::{MemberId, MembersDelta};
 use river_core::room_state::member_info::{AuthorizedMemberInfo, MemberInfoV1};
 use river_core::room_state::message::{
@@ -73,6 +80,125 @@ fn try_rejoin_delta(
     }
 }
 
+/// The body of a plain text message: sealed with the current room secret in
+/// a private room, public otherwise.
+fn text_message_body(
+    text: String,
+    is_private: bool,
+    secret: Option<([u8; 32], u32)>,
+) -> RoomMessageBody {
+    use river_core::room_state::content::{TextContentV1, CONTENT_TYPE_TEXT, TEXT_CONTENT_VERSION};
+
+    if !is_private {
+        return RoomMessageBody::public(text);
+    }
+    match secret {
+        Some((secret, version)) => {
+            let content_bytes = TextContentV1::new(text).encode();
+            let (ciphertext, nonce) = encrypt_with_symmetric_key(&secret, &content_bytes);
+            RoomMessageBody::private(
+                CONTENT_TYPE_TEXT,
+                TEXT_CONTENT_VERSION,
+                ciphertext,
+                nonce,
+                version,
+            )
+        }
+        None => {
+            warn!("Room is private but no secret available, sending as public");
+            RoomMessageBody::public(text)
+        }
+    }
+}
+
+/// Send a queued "send later" message to `room_key` now, as if it had just
+/// been typed there: signed with this session's key for the room, stamped
+/// with the current time and sealed with the room's current secret.
+///
+/// Unlike `handle_send_message` the room need not be open. Errors leave
+/// nothing sent, so the caller may queue the message again.
+pub(crate) fn send_scheduled_text(
+    room_key: ed25519_dalek::VerifyingKey,
+    text: String,
+) -> Result<(), String> {
+    let room_data = ROOMS
+        .try_read()
+        .map_err(|_| "ROOMS signal busy".to_string())?
+        .map
+        .get(&room_key)
+        .cloned()
+        .ok_or("room is no longer held here")?;
+    let self_sk = room_data
+        .signing_key()
+        .cloned()
+        .ok_or("local signing key unavailable for this room")?;
+    let content = text_message_body(
+        text,
+        room_data.is_private(),
+        room_data
+            .get_secret()
+            .map(|(secret, version)| (*secret, version)),
+    );
+    let max_size = room_data
+        .room_state
+        .configuration
+        .configuration
+        .max_message_size;
+    if content.content_len() > max_size {
+        return Err(format!(
+            "message is larger than the room's {max_size} byte limit"
+        ));
+    }
+
+    let message = MessageV1 {
+        room_owner: MemberId::from(room_key),
+        author: MemberId::from(&self_sk.verifying_key()),
+        content,
+        time: get_current_system_time(),
+    };
+    let mut message_bytes = Vec::new();
+    ciborium::ser::into_writer(&message, &mut message_bytes)
+        .map_err(|e| format!("Failed to serialize message for signing: {e}"))?;
+    let signature = crate::signing::sign_message_locally(&message_bytes, &self_sk);
+    let (members, member_info) = try_rejoin_delta(&room_key, "scheduled send");
+    let delta = ChatRoomStateV1Delta {
+        recent_messages: Some(vec![AuthorizedMessageV1::with_signature(
+            message, signature,
+        )]),
+        members,
+        member_info,
+        ..Default::default()
+    };
+
+    // Same deferred apply as `handle_send_message`, for the same reason.
+    let parent_state = room_data.room_state;
+    crate::util::defer(move || {
+        let applied = ROOMS.with_mut(|rooms| {
+            let Some(room_data) = rooms.map.get_mut(&room_key) else {
+                return false;
+            };
+            match room_data.room_state.apply_delta(
+                &parent_state,
+                &ChatRoomParametersV1 { owner: room_key },
+                &Some(delta),
+            ) {
+                Ok(()) => {
+                    room_data.rebuild_private_actions_state();
+                    true
+                }
+                Err(e) => {
+                    error!("Failed to apply scheduled message delta: {:?}", e);
+                    false
+                }
+            }
+        });
+        if applied {
+            crate::components::app::mark_needs_sync(room_key);
+        }
+    });
+    Ok(())
+}
+
 /// Snapshot the currently-open room's data, cloning it out of [`ROOMS`].
 ///
 /// Called at INTERACTION time by the message-action handlers rather than
@@ -484,6 +610,55 @@ fn resolve_member_nickname(
         .unwrap_or_else(|| crate::util::display_name::UNKNOWN_MEMBER.to_string())
 }
 
+/// "X is typing…" beneath the room header, from the room's presence
+/// heartbeats. Heartbeats from anyone no longer in the room, and from the
+/// user themselves, are ignored.
+#[component]
+fn TypingIndicator(room_key: ed25519_dalek::VerifyingKey) -> Element {
+    use crate::components::app::freenet_api::presence_sync::{PRESENCE_NOW, ROOM_PRESENCE};
+    let now = PRESENCE_NOW();
+    let presence = ROOM_PRESENCE.read();
+    // try_read: see `current_room_data` in the conversation.
+    let Ok(rooms) = ROOMS.try_read() else {
+        return rsx! {};
+    };
+    let (Some(state), Some(room_data)) = (presence.get(&room_key), rooms.map.get(&room_key)) else {
+        return rsx! {};
+    };
+    let self_id = room_data.self_verifying_key().map(MemberId::from);
+    let members = &room_data.room_state.members;
+    let names: Vec<String> = state
+        .typing(now)
+        .filter(|id| Some(*id) != self_id)
+        .filter(|id| {
+            *id == MemberId::from(room_key) || members.members.iter().any(|m| m.member.id() == *id)
+        })
+        .map(|id| {
+            resolve_member_nickname(&room_data.room_state.member_info, id, &room_data.secrets)
+        })
+        .collect();
+    match typing_line(&names) {
+        Some(line) => rsx! {
+            div {
+                "data-testid": "typing-indicator",
+                class: "text-xs text-text-muted italic truncate",
+                "{line}"
+            }
+        },
+        None => rsx! {},
+    }
+}
+
+/// The sentence the typing indicator shows for these members, if any.
+fn typing_line(names: &[String]) -> Option<String> {
+    match names {
+        [] => None,
+        [one] => Some(format!("{one} is typing…")),
+        [one, two] => Some(format!("{one} and {two} are typing…")),
+        [one, rest @ ..] => Some(format!("{one} and {} others are typing…", rest.len())),
+    }
+}
+
 /// How far a sender's timestamp may run ahead of when we received the message
 /// before we stop believing it.
 ///
@@ -496,6 +671,11 @@ fn resolve_member_nickname(
 /// which telling the reader the timestamp is untrustworthy earns its keep.
 const CLOCK_SKEW_TOLERANCE_SECS: i64 = 60;
 
+/// How often the conversation re-checks a TTL room for messages that have
+/// expired since the last render. Disappearing-message TTLs are minutes to
+/// days, so a few seconds late is invisible.
+const EXPIRY_TICK: Duration = Duration::from_secs(15);
+
 /// What a grouping pass knows about time.
 ///
 /// Both fields are read once for the whole pass. That is the point: reading
@@ -535,6 +715,9 @@ impl MessageClock<'_> {
 /// once for the whole pass rather than once per message.
 fn group_messages(
     messages_state: &MessagesV1,
+    // Read for the message TTL: a message past it at `clock.fallback_now` is
+    // hidden even while the room state still holds it.
+    config: &Configuration,
     member_info: &MemberInfoV1,
     // `None` when this build holds no locally-known identity for the room.
     // Every message still renders; the identity-relative cosmetics (the
@@ -554,6 +737,9 @@ fn group_messages(
     // The room owner, so an author line can tell whether the member it is
     // flagging holds privilege themselves — see `privilege_in_view`.
     owner_id: MemberId,
+    // The user's ignore list: these members' messages and reactions are left
+    // out entirely.
+    ignored: &IgnoreList,
     clock: MessageClock<'_>,
 ) -> Vec<DisplayItem> {
     let mut items: Vec<DisplayItem> = Vec::new();
@@ -562,12 +748,19 @@ fn group_messages(
     // Inputs to the per-message HTML cache (see MESSAGE_HTML_CACHE). The member
     // fingerprint is computed once per render, not per message.
     let members_fp = member_names_fingerprint(member_names);
+    // Custom emoji are swapped in after the cache, so a pack change never
+    // invalidates a cached body.
+    let room_emoji = custom_emoji::room_emoji_images(config, secrets);
     let mut seen_message_ids: std::collections::HashSet<MessageId> =
         std::collections::HashSet::new();
 
-    // Only iterate over displayable messages (non-deleted, non-action)
-    for message in messages_state.display_messages() {
+    // Only iterate over displayable messages (non-deleted, non-action,
+    // unexpired)
+    for message in messages_state.unexpired_display_messages(config, clock.fallback_now.into()) {
         let author_id = message.message.author;
+        if ignored.is_ignored(&author_id) {
+            continue;
+        }
         let message_id = message.id();
         let raw_time = DateTime::<Utc>::from(message.message.time);
         // Clamp target: when we first saw it, else the pass-wide "now".
@@ -609,12 +802,15 @@ fn group_messages(
             .effective_text(message)
             .unwrap_or_else(|| decrypt_message_content(&message.message.content, secrets));
         seen_message_ids.insert(message_id.clone());
-        let content_html = render_message_html_cached(
-            &message_id,
-            &content_text,
-            member_names,
-            members_fp,
-            self_member_id,
+        let content_html = shortcodes_to_html(
+            &render_message_html_cached(
+                &message_id,
+                &content_text,
+                member_names,
+                members_fp,
+                self_member_id,
+            ),
+            &room_emoji,
         );
         // With no locally-known identity nothing is "self", so every group
         // renders on the other-party side rather than claiming an author.
@@ -622,19 +818,33 @@ fn group_messages(
 
         // Get edited status and reactions
         let edited = messages_state.is_edited(&message_id);
-        let reactions = messages_state
+        let mut reactions = messages_state
             .reactions(&message_id)
             .cloned()
             .unwrap_or_default();
-
-        // Resolve the reply quote (if any) against live room state.
-        let reply_strip = resolve_reply_strip(
-            &message.message.content,
-            messages_state,
-            member_info,
-            secrets,
-            member_names,
-        );
+        ignored.hide_reactors(&mut reactions);
+
+        // Resolve the reply quote (if any) against live room state. A quote
+        // of an ignored member would show their words after all, so it reads
+        // as unavailable instead.
+        let quotes_ignored = extract_reply_target_id(&message.message.content, secrets)
+            .is_some_and(|target| {
+                messages_state
+                    .messages
+                    .iter()
+                    .any(|m| m.id() == target && ignored.is_ignored(&m.message.author))
+            });
+        let reply_strip = if quotes_ignored {
+            ReplyStrip::Unavailable
+        } else {
+            resolve_reply_strip(
+                &message.message.content,
+                messages_state,
+                member_info,
+                secrets,
+                member_names,
+            )
+        };
 
         // Look up propagation delay (send time → receive time)
         let send_time_ms = raw_time.timestamp_millis();
@@ -879,6 +1089,10 @@ fn clean_reply_preview(text: &str, member_names: &HashMap<MemberId, String>) ->
                 with_mentions.push('@');
                 with_mentions.push_str(&name);
             }
+            MentionSegment::Broadcast(b) => {
+                with_mentions.push('@');
+                with_mentions.push_str(b.keyword());
+            }
         }
     }
     strip_markdown(&with_mentions)
@@ -1009,9 +1223,9 @@ pub(crate) fn message_to_html_with_mentions(
 
     let segments = parse_segments(text);
     // Fast path: no mentions -> byte-identical to the plain renderer.
-    if !segments
+    if segments
         .iter()
-        .any(|s| matches!(s, MentionSegment::Mention(_)))
+        .all(|s| matches!(s, MentionSegment::Text(_)))
     {
         return message_to_html(text);
     }
@@ -1058,6 +1272,16 @@ pub(crate) fn message_to_html_with_mentions(
                 working.push_str(&idx.to_string());
                 working.push(CLOSE);
             }
+            // Inert (no member to open) and never self-highlighted: whether
+            // it reaches the viewer depends on its author, which this
+            // renderer does not know.
+            MentionSegment::Broadcast(b) => {
+                let idx = chips.len();
+                chips.push(render_mention_chip_html(None, b.keyword(), false));
+                working.push(OPEN);
+                working.push_str(&idx.to_string());
+                working.push(CLOSE);
+            }
         }
     }
 
@@ -2584,6 +2808,34 @@ pub fn Conversation() -> Element {
         }
     });
 
+    // Disappearing messages: while the open room has a message TTL, bump this
+    // every `EXPIRY_TICK` so the grouping memo below re-runs and drops messages
+    // that have just expired, rather than waiting for the next sync to touch
+    // ROOMS. The task is scoped to this component and ends with it.
+    let mut expiry_tick = use_signal(|| 0u64);
+    use_hook(move || {
+        spawn(async move {
+            loop {
+                crate::util::sleep(EXPIRY_TICK).await;
+                let has_ttl = CURRENT_ROOM.peek().owner_key.is_some_and(|key| {
+                    ROOMS.peek().map.get(&key).is_some_and(|room| {
+                        room.room_state
+                            .configuration
+                            .configuration
+                            .effective_message_ttl()
+                            .is_some()
+                    })
+                });
+                if has_ttl {
+                    *expiry_tick.write() += 1;
+                }
+            }
+        })
+    });
+
+    // The room's custom emoji, for reactions and the reaction picker.
+    let room_emoji = use_current_room_emoji();
+
     // Memoize expensive message grouping (decryption + markdown parsing)
     // This prevents re-computing on every render/keystroke
     // Returns (groups, self_member_id, member_names) so we can highlight user's reactions and show names in tooltips
@@ -2605,10 +2857,14 @@ pub fn Conversation() -> Element {
             };
             if let Some(room_data) = rooms.map.get(&key) {
                 let room_state = &room_data.room_state;
+                // Subscribes this memo to the expiry tick; `now` is the single
+                // wall-clock reading the whole pass uses.
+                expiry_tick.read();
+                let now = Utc::now();
                 // Check if there are any displayable messages
                 if room_state
                     .recent_messages
-                    .display_messages()
+                    .unexpired_display_messages(&room_state.configuration.configuration, now.into())
                     .next()
                     .is_some()
                 {
@@ -2675,6 +2931,7 @@ pub fn Conversation() -> Element {
                     let receive_times = crate::components::app::receive_times::RECEIVE_TIMES.read();
                     let groups = group_messages(
                         &room_state.recent_messages,
+                        &room_state.configuration.configuration,
                         &room_state.member_info,
                         self_member_id,
                         &room_data.secrets,
@@ -2682,11 +2939,12 @@ pub fn Conversation() -> Element {
                         &deputy_badges,
                         &impersonation,
                         MemberId::from(&key),
+                        &IGNORED_MEMBERS.read(),
                         MessageClock {
                             receive_times: &receive_times,
                             // One "now" for the whole pass. Only reached by
                             // messages with no recorded arrival time.
-                            fallback_now: Utc::now(),
+                            fallback_now: now,
                         },
                     );
                     return Some((groups, self_member_id, member_names));
@@ -3610,8 +3868,7 @@ pub fn Conversation() -> Element {
                 // handler's stack (freenet/river#512 review).
                 spawn_local(async move {
                     use river_core::room_state::content::{
-                        ReplyContentV1, TextContentV1, CONTENT_TYPE_REPLY, CONTENT_TYPE_TEXT,
-                        REPLY_CONTENT_VERSION, TEXT_CONTENT_VERSION,
+                        ReplyContentV1, CONTENT_TYPE_REPLY, REPLY_CONTENT_VERSION,
                     };
 
                     // Build content based on whether this is a reply or regular message
@@ -3653,27 +3910,7 @@ pub fn Conversation() -> Element {
                             )
                         }
                     } else {
-                        // Regular text message
-                        if is_private {
-                            if let Some((secret, version)) = secret_opt {
-                                let text_content = TextContentV1::new(message_text.clone());
-                                let content_bytes = text_content.encode();
-                                let (ciphertext, nonce) =
-                                    encrypt_with_symmetric_key(&secret, &content_bytes);
-                                RoomMessageBody::private(
-                                    CONTENT_TYPE_TEXT,
-                                    TEXT_CONTENT_VERSION,
-                                    ciphertext,
-                                    nonce,
-                                    version,
-                                )
-                            } else {
-                                warn!("Room is private but no secret available, sending as public");
-                                RoomMessageBody::public(message_text.clone())
-                            }
-                        } else {
-                            RoomMessageBody::public(message_text.clone())
-                        }
+                        text_message_body(message_text.clone(), is_private, secret_opt)
                     };
 
                     // Safety net: check encoded content size before signing.
@@ -3804,7 +4041,7 @@ pub fn Conversation() -> Element {
         div { class: "flex-1 flex flex-col min-w-0 bg-bg",
             // Room header
             {
-                current_room_data.as_ref().map(|_room_data| {
+                current_room_data.as_ref().map(|room_data| {
                     rsx! {
                         div { class: "flex-shrink-0 px-3 md:px-6 py-3 border-b border-border bg-panel",
                             div { class: "flex items-center justify-between gap-2 md:gap-3 max-w-4xl mx-auto",
@@ -3949,6 +4186,7 @@ pub fn Conversation() -> Element {
                                             dangerous_inner_html: "{desc_html}"
                                         }
                                     }
+                                    TypingIndicator { room_key: room_data.owner_vk }
                                 }
                                 // Mobile: button to open members panel
                                 button {
@@ -4309,6 +4547,7 @@ pub fn Conversation() -> Element {
                                                                 group: group,
                                                                 self_member_id: self_member_id,
                                                                 member_names: member_names,
+                                                                room_emoji: room_emoji.read().clone(),
                                                                 max_message_size: edit_max_size,
                                                                 is_private: edit_is_private,
                                                                 edit_trigger: edit_trigger,
@@ -4506,6 +4745,9 @@ pub fn Conversation() -> Element {
                                     .sort_by(|a, b| a.1.to_lowercase().cmp(&b.1.to_lowercase()));
                                 rsx! {
                                     MessageInput {
+                                        // One mount per room, so each room's
+                                        // draft is its own.
+                                        key: "{room_data.owner_vk:?}",
                                         handle_send_message: move |msg: (String, Option<ReplyContext>)| {
                                             let mut handle = handle_send_message.clone();
                                             handle(msg)
@@ -4515,6 +4757,7 @@ pub fn Conversation() -> Element {
                                         max_message_size: max_msg_size,
                                         is_private: room_is_private,
                                         members: mention_members,
+                                        room_owner_key: room_data.owner_vk,
                                     }
                                 }
                             },
@@ -4719,6 +4962,8 @@ fn MessageGroupComponent(
     /// answer.
     self_member_id: Option<MemberId>,
     member_names: HashMap<MemberId, String>,
+    /// The room's custom emoji, drawn in reactions and offered in the picker.
+    room_emoji: EmojiImages,
     /// Room max message size in ENCODED content bytes — bounds the edit
     /// action body (`RoomMessageBody::measure_edit`), not the raw text.
     max_message_size: usize,
@@ -5650,7 +5895,7 @@ fn MessageGroupComponent(
                                                                     on_react.call((msg_id_for_click.clone(), emoji_for_click.clone()));
                                                                 }
                                                             },
-                                                            "{emoji}"
+                                                            {emoji_glyph(emoji, &room_emoji)}
                                                             if count > 1 {
                                                                 span { class: "text-xs text-text-muted", "{count}" }
                                                             }
@@ -5737,6 +5982,19 @@ fn MessageGroupComponent(
                                                                 }
                                                             }
                                                         })}
+                                                        {
+                                                            let msg_id = msg_id_react.clone();
+                                                            rsx! {
+                                                                CustomEmojiButtons {
+                                                                    images: room_emoji.clone(),
+                                                                    current: user_reaction_for_picker.clone(),
+                                                                    on_pick: move |code: String| {
+                                                                        on_react.call((msg_id.clone(), code));
+                                                                        open_emoji_picker.set(None);
+                                                                    },
+                                                                }
+                                                            }
+                                                        }
                                                     }
                                                 }
                                             }
@@ -7692,6 +7950,21 @@ mod tests {
             "legacy self-mention must get the self class: {html}"
         );
     }
+
+    #[test]
+    fn typing_line_names_up_to_two_members() {
+        let names = |n: &[&str]| n.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(typing_line(&[]), None);
+        assert_eq!(typing_line(&names(&["Ann"])).unwrap(), "Ann is typing…");
+        assert_eq!(
+            typing_line(&names(&["Ann", "Bo"])).unwrap(),
+            "Ann and Bo are typing…"
+        );
+        assert_eq!(
+            typing_line(&names(&["Ann", "Bo", "Cy"])).unwrap(),
+            "Ann and 2 others are typing…"
+        );
+    }
 }
 
 /// Tests for [`resolve_reply_strip`] — the rule that a reply's quoted
@@ -8608,6 +8881,7 @@ mod group_messages_clock_tests {
     ) -> Vec<DisplayItem> {
         group_messages(
             messages,
+            &Configuration::default(),
             member_info,
             Some(me),
             &HashMap::new(),
@@ -8617,6 +8891,7 @@ mod group_messages_clock_tests {
             // about the clock, and an empty checker keeps them about only that.
             &ImpersonationChecker::default(),
             me,
+            &IgnoreList::default(),
             MessageClock {
                 receive_times,
                 fallback_now,
@@ -8652,6 +8927,7 @@ mod group_messages_clock_tests {
 
         let known = group_messages(
             &messages,
+            &Configuration::default(),
             &member_info,
             Some(member_id_of(&a)),
             &HashMap::new(),
@@ -8659,6 +8935,7 @@ mod group_messages_clock_tests {
             &HashMap::new(),
             &ImpersonationChecker::default(),
             owner_id,
+            &IgnoreList::default(),
             MessageClock {
                 receive_times: &receive_times,
                 fallback_now: now,
@@ -8666,6 +8943,7 @@ mod group_messages_clock_tests {
         );
         let unknown = group_messages(
             &messages,
+            &Configuration::default(),
             &member_info,
             None,
             &HashMap::new(),
@@ -8673,6 +8951,7 @@ mod group_messages_clock_tests {
             &HashMap::new(),
             &ImpersonationChecker::default(),
             owner_id,
+            &IgnoreList::default(),
             MessageClock {
                 receive_times: &receive_times,
                 fallback_now: now,
@@ -0,0 +1,221 @@
+//! The current room's custom emoji (`river_core::room_state::emoji`): inline
+//! in message bodies and reactions, and as extra buttons in the pickers.
+
+use crate::components::app::{CURRENT_ROOM, ROOMS};
+use crate::util::ecies::unseal_bytes_with_secrets;
+use base64::Engine;
+use dioxus::prelude::*;
+use river_core::room_state::configuration::Configuration;
+use river_core::room_state::emoji::{parse_shortcode, split_shortcodes, EmojiPack, EmojiSegment};
+use std::collections::{BTreeMap, HashMap};
+
+/// A room's custom emoji as `data:` URLs, by shortcode name.
+pub(crate) type EmojiImages = BTreeMap<String, String>;
+
+/// Sized to the surrounding text, like a native emoji glyph.
+const INLINE_EMOJI_STYLE: &str =
+    "height:1.375em;width:auto;display:inline-block;vertical-align:-0.3em";
+
+/// Decode a room's emoji pack into [`EmojiImages`]. A pack this client
+/// cannot unseal or decode reads as empty, so messages show the `:name:` text.
+pub(crate) fn room_emoji_images(
+    config: &Configuration,
+    secrets: &HashMap<u32, [u8; 32]>,
+) -> EmojiImages {
+    let Some(sealed) = &config.emoji_pack else {
+        return EmojiImages::new();
+    };
+    let Some(pack) = unseal_bytes_with_secrets(sealed, secrets)
+        .ok()
+        .and_then(|bytes| EmojiPack::decode(&bytes).ok())
+    else {
+        return EmojiImages::new();
+    };
+    // `decode` has already dropped any image type that is unsafe to inline.
+    pack.emoji
+        .into_iter()
+        .map(|(name, image)| {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(&image.data);
+            (
+                name,
+                format!("data:{};base64,{}", image.media_type, encoded),
+            )
+        })
+        .collect()
+}
+
+/// The current room's custom emoji, re-derived when the room or its
+/// configuration changes.
+pub(crate) fn use_current_room_emoji() -> Memo<EmojiImages> {
+    use_memo(|| {
+        crate::util::signal_guard::anchor();
+        let Some(key) = CURRENT_ROOM.read().owner_key else {
+            return EmojiImages::new();
+        };
+        let Ok(rooms) = ROOMS.try_read() else {
+            crate::util::signal_guard::schedule_nudge();
+            return EmojiImages::new();
+        };
+        rooms
+            .map
+            .get(&key)
+            .map(|room| {
+                room_emoji_images(&room.room_state.configuration.configuration, &room.secrets)
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// Replace known `:name:` shortcodes in rendered message HTML with inline
+/// images. Runs after markdown, so only text between tags is touched, and
+/// never inside `<code>` or `<pre>`, where a shortcode is meant literally.
+/// Names are restricted to `[a-z0-9_+-]` and URLs are base64 `data:` URLs of
+/// an allowed image type, so neither needs escaping.
+pub(crate) fn shortcodes_to_html(html: &str, images: &EmojiImages) -> String {
+    if images.is_empty() || !html.contains(':') {
+        return html.to_string();
+    }
+    let mut out = String::with_capacity(html.len());
+    let mut code_depth = 0usize;
+    let mut rest = html;
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+            let tag = &rest[..end];
+            let closing = tag.starts_with("</");
+            let name: String = tag
+                .trim_start_matches(['<', '/'])
+                .chars()
+                .take_while(char::is_ascii_alphanumeric)
+                .collect();
+            if name.eq_ignore_ascii_case("code") || name.eq_ignore_ascii_case("pre") {
+                code_depth = if closing {
+                    code_depth.saturating_sub(1)
+                } else {
+                    code_depth + 1
+                };
+            }
+            out.push_str(tag);
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            if code_depth > 0 {
+                out.push_str(text);
+            } else {
+                for segment in split_shortcodes(text, |name| images.contains_key(name)) {
+                    match segment {
+                        EmojiSegment::Text(t) => out.push_str(t),
+                        EmojiSegment::Emoji(name) => out.push_str(&format!(
+                            "<img class=\"river-emoji\" src=\"{}\" alt=\":{name}:\" \
+                             title=\":{name}:\" style=\"{INLINE_EMOJI_STYLE}\">",
+                            images[name]
+                        )),
+                    }
+                }
+            }
+            rest = &rest[end..];
+        }
+    }
+    out
+}
+
+/// The emoji whose name contains `query` (colons ignored).
+pub(crate) fn filter_emoji(images: &EmojiImages, query: &str) -> EmojiImages {
+    let query = query.trim().trim_matches(':').to_ascii_lowercase();
+    images
+        .iter()
+        .filter(|(name, _)| name.contains(&query))
+        .map(|(name, url)| (name.clone(), url.clone()))
+        .collect()
+}
+
+/// A reaction's emoji: the room's image for a known `:name:`, otherwise the
+/// text itself.
+pub(crate) fn emoji_glyph(emoji: &str, images: &EmojiImages) -> Element {
+    match parse_shortcode(emoji).and_then(|name| images.get(name)) {
+        Some(url) => rsx! {
+            img {
+                class: "inline-block h-5 w-auto align-middle",
+                src: "{url}",
+                alt: "{emoji}",
+            }
+        },
+        None => rsx! { "{emoji}" },
+    }
+}
+
+/// One picker button per custom emoji, for a 4-column picker grid. Each
+/// picks its `:name:` shortcode; `current` is highlighted.
+#[component]
+pub(crate) fn CustomEmojiButtons(
+    images: EmojiImages,
+    #[props(default)] current: Option<String>,
+    on_pick: EventHandler<String>,
+) -> Element {
+    rsx! {
+        for (name , url) in images {
+            {
+                let code = format!(":{name}:");
+                let is_current = current.as_deref() == Some(code.as_str());
+                rsx! {
+                    button {
+                        key: "{name}",
+                        r#type: "button",
+                        class: format!(
+                            "p-1 rounded hover:bg-surface transition-colors flex items-center justify-center {}",
+                            if is_current { "bg-accent/20 ring-2 ring-accent" } else { "" }
+                        ),
+                        title: "{code}",
+                        onclick: move |_| on_pick.call(code.clone()),
+                        img { class: "h-6 w-auto", src: "{url}", alt: "{name}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn images() -> EmojiImages {
+        EmojiImages::from([
+            (
+                "shipit".to_string(),
+                "data:image/png;base64,AQID".to_string(),
+            ),
+            (
+                "party".to_string(),
+                "data:image/gif;base64,BAU=".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn shortcodes_render_as_images_outside_code() {
+        let html = "<p>go :shipit: <code>:shipit:</code> at 12:30:00 :nope:</p>";
+        let out = shortcodes_to_html(html, &images());
+        assert_eq!(out.matches("<img class=\"river-emoji\"").count(), 1);
+        assert!(out.contains("src=\"data:image/png;base64,AQID\" alt=\":shipit:\""));
+        assert!(out.contains("<code>:shipit:</code>"));
+        assert!(out.contains("at 12:30:00 :nope:</p>"));
+
+        // Inside a fenced block the shortcode stays text.
+        let pre = "<pre><code class=\"language-text\">:party:\n</code></pre><p>:party:</p>";
+        let out = shortcodes_to_html(pre, &images());
+        assert!(out.starts_with("<pre><code class=\"language-text\">:party:\n</code></pre>"));
+        assert!(out.ends_with("alt=\":party:\" title=\":party:\" style=\"height:1.375em;width:auto;display:inline-block;vertical-align:-0.3em\"></p>"));
+
+        // Without a pack the HTML is untouched.
+        assert_eq!(shortcodes_to_html(html, &EmojiImages::new()), html);
+    }
+
+    #[test]
+    fn filter_matches_names_and_ignores_colons() {
+        assert_eq!(filter_emoji(&images(), ":ship").len(), 1);
+        assert_eq!(filter_emoji(&images(), "").len(), 2);
+        assert!(filter_emoji(&images(), "zzz").is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 //! Emoji picker component for inserting emojis into messages.
 
+use super::custom_emoji::{filter_emoji, use_current_room_emoji, CustomEmojiButtons};
 use dioxus::prelude::*;
 
 /// Emoji categories with their emojis
@@ -256,9 +257,17 @@ pub struct EmojiPickerProps {
     pub on_close: EventHandler<()>,
 }
 
-/// Simple emoji picker with a 4x4 grid of popular emojis
+/// Simple emoji picker with a 4x4 grid of popular emojis, followed by the
+/// room's custom emoji (searchable by name) when it has any.
 #[component]
 pub fn EmojiPicker(props: EmojiPickerProps) -> Element {
+    let room_emoji = use_current_room_emoji();
+    let mut query = use_signal(String::new);
+    let has_room_emoji = !room_emoji.read().is_empty();
+    let matching = filter_emoji(&room_emoji.read(), &query.read());
+    let on_select = props.on_select;
+    let on_close = props.on_close;
+
     rsx! {
         div {
             class: "bg-panel rounded-lg shadow-lg border border-border p-1.5",
@@ -273,6 +282,28 @@ pub fn EmojiPicker(props: EmojiPickerProps) -> Element {
                     {render_emoji_button(emoji, &props.on_select, &props.on_close)}
                 }
             }
+
+            if has_room_emoji {
+                input {
+                    r#type: "text",
+                    "data-testid": "emoji-picker-search",
+                    class: "mt-1.5 w-full px-2 py-1 text-sm bg-surface border border-border rounded text-text placeholder-text-muted",
+                    placeholder: "Room emoji…",
+                    value: "{query}",
+                    oninput: move |evt| query.set(evt.value()),
+                }
+                div {
+                    class: "grid mt-1 max-h-32 overflow-y-auto",
+                    style: "grid-template-columns: repeat(4, 1fr); gap: 2px;",
+                    CustomEmojiButtons {
+                        images: matching,
+                        on_pick: move |code: String| {
+                            on_select.call(code);
+                            on_close.call(());
+                        },
+                    }
+                }
+            }
         }
     }
 }
@@ -1,5 +1,12 @@
+use crate::components::app::chat_delegate::{
+    cancel_scheduled_message, schedule_message, use_synced_draft, SCHEDULED_MESSAGES,
+};
+use dioxus::logger::tracing::error;
 use dioxus::prelude::*;
+use ed25519_dalek::VerifyingKey;
+use river_core::chat_delegate::ScheduledMessage;
 use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
 
 use super::emoji_picker::EmojiPicker;
 use super::mention::{
@@ -140,16 +147,43 @@ pub fn MessageInput(
     /// name. Drives the `@` autocomplete. Changes only when membership changes,
     /// so it does not affect keystroke-level re-rendering.
     members: Vec<(MemberId, String)>,
+    /// The room this composer sends to, for "send later" and its draft.
+    room_owner_key: VerifyingKey,
 ) -> Element {
     // Own the message state locally - keystrokes only re-render this component
     let mut message_text = use_signal(String::new);
     let mut show_emoji_picker = use_signal(|| false);
     let mut mention = use_signal(|| None as Option<MentionAutocomplete>);
+    // `Some` while "send later" is on, holding the picker's value. Replies
+    // always go out immediately, so it is ignored while replying.
+    let mut send_later = use_signal(|| None as Option<String>);
+    use_synced_draft(room_owner_key, None, message_text);
 
     let mut send_message = move || {
         let text = message_text.peek().to_string();
         let within_limit =
             measure_draft(&text, replying_to.peek().as_ref(), is_private) <= max_message_size;
+        let later = send_later.peek().clone();
+        if replying_to.peek().is_none() {
+            if let Some(value) = later {
+                let Some(send_at) = future_send_at(&value) else {
+                    return;
+                };
+                if text.is_empty() || !within_limit {
+                    return;
+                }
+                message_text.set(String::new());
+                mention.set(None);
+                send_later.set(None);
+                spawn_local(async move {
+                    if let Err(e) = schedule_message(room_owner_key, text, send_at).await {
+                        error!("Failed to schedule message: {}", e);
+                    }
+                });
+                crate::util::defer(auto_resize_message_input);
+                return;
+            }
+        }
         if !text.is_empty() && within_limit {
             let reply_ctx = replying_to.peek().clone();
             message_text.set(String::new());
@@ -189,6 +223,7 @@ pub fn MessageInput(
         }
         div { class: "flex-shrink-0 border-t border-border bg-panel relative z-50",
             div { class: "max-w-4xl mx-auto px-4 py-3",
+                ScheduledMessageList { room_owner_key }
                 // Reply preview strip
                 {
                     let reply = replying_to.read();
@@ -280,6 +315,9 @@ pub fn MessageInput(
                                 auto_resize_message_input();
                                 // Detect / update the @mention autocomplete.
                                 update_mention_from_input("message-input", &value, &members, mention);
+                                if !value.is_empty() {
+                                    crate::components::app::freenet_api::presence_sync::note_typing(room_owner_key);
+                                }
                             },
                             onkeydown: move |evt| {
                                 let key = evt.key();
@@ -340,12 +378,45 @@ pub fn MessageInput(
                             }
                         }
                     }
+                    // "Send later": the clock toggles a date-time picker,
+                    // and while it is on Send queues the message instead.
+                    if replying_to.read().is_none() {
+                        div { class: "flex items-center gap-2 self-center",
+                            if let Some(value) = send_later() {
+                                input {
+                                    r#type: "datetime-local",
+                                    "data-testid": "send-later-time",
+                                    class: "px-2 py-2 bg-surface border border-border rounded-xl text-sm text-text",
+                                    value: "{value}",
+                                    oninput: move |evt| send_later.set(Some(evt.value())),
+                                }
+                            }
+                            button {
+                                r#type: "button",
+                                "data-testid": "send-later-toggle",
+                                class: "p-2.5 rounded-xl hover:bg-surface transition-colors",
+                                title: if send_later.read().is_some() { "Send now instead" } else { "Send later" },
+                                onclick: move |_| {
+                                    let on = send_later.peek().is_some();
+                                    send_later.set(if on { None } else { Some(String::new()) });
+                                },
+                                span {
+                                    class: "text-lg",
+                                    style: if send_later.read().is_some() { "" } else { "filter: grayscale(100%); opacity: 0.6;" },
+                                    "\u{23f0}"
+                                }
+                            }
+                        }
+                    }
                     {
                         let encoded_bytes = measure_draft(
                             &message_text.read(),
                             replying_to.read().as_ref(),
                             is_private,
                         );
+                        let scheduling = replying_to.read().is_none() && send_later.read().is_some();
+                        let bad_time = scheduling
+                            && send_later.read().as_deref().and_then(future_send_at).is_none();
                         let over_limit = encoded_bytes > max_message_size;
                         let btn_class = if over_limit {
                             "px-5 py-2.5 bg-gray-400 dark:bg-gray-600 text-white font-medium rounded-xl opacity-50 cursor-not-allowed"
@@ -354,22 +425,88 @@ pub fn MessageInput(
                         };
                         let tooltip = if over_limit {
                             format!("Message exceeds the {} byte limit", max_message_size)
+                        } else if bad_time {
+                            "Pick a time in the future".to_string()
                         } else {
                             String::new()
                         };
+                        let label = if scheduling { "Schedule" } else { "Send" };
                         rsx! {
                             button {
                                 r#type: "button",
                                 "data-testid": "send-message-button",
                                 class: "{btn_class}",
-                                disabled: over_limit,
+                                disabled: over_limit || bad_time,
                                 title: "{tooltip}",
                                 // Use explicit onclick instead of form submit — on iOS Safari,
                                 // tapping a submit button while the keyboard is visible causes
                                 // the keyboard to dismiss first, which triggers a viewport resize
                                 // that cancels the click→submit event chain.
                                 onclick: move |_| send_message(),
-                                "Send"
+                                "{label}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The "send later" picker's value as Unix seconds, if it names a time
+/// still in the future. A `datetime-local` value has no zone; `Date` reads it
+/// as local time, which is what the user picked it in.
+fn future_send_at(value: &str) -> Option<u64> {
+    if value.is_empty() {
+        return None;
+    }
+    let millis = js_sys::Date::new(&wasm_bindgen::JsValue::from_str(value)).get_time();
+    if millis.is_nan() || millis <= js_sys::Date::now() {
+        return None;
+    }
+    Some((millis / 1000.0) as u64)
+}
+
+/// This room's queued "send later" messages, soonest first, each with a
+/// button to cancel it. Renders nothing when there are none.
+#[component]
+fn ScheduledMessageList(room_owner_key: VerifyingKey) -> Element {
+    let room = room_owner_key.to_bytes();
+    let queued: Vec<ScheduledMessage> = SCHEDULED_MESSAGES
+        .read()
+        .entries
+        .iter()
+        .filter(|m| m.room_owner_vk == room)
+        .cloned()
+        .collect();
+    if queued.is_empty() {
+        return rsx! {};
+    }
+    rsx! {
+        div { class: "mb-2 space-y-1", "data-testid": "scheduled-messages",
+            for message in queued {
+                {
+                    let id = message.id;
+                    let when = chrono::DateTime::from_timestamp(message.send_at as i64, 0)
+                        .map(|t| t.with_timezone(&chrono::Local).format("%b %-d, %H:%M").to_string())
+                        .unwrap_or_default();
+                    rsx! {
+                        div {
+                            key: "{id}",
+                            class: "flex items-center gap-2 px-3 py-1.5 bg-surface rounded text-sm text-text-muted",
+                            span { class: "flex-shrink-0", "\u{23f0} {when}" }
+                            span { class: "flex-1 truncate", "{message.text}" }
+                            button {
+                                class: "text-text-muted hover:text-text transition-colors flex-shrink-0",
+                                title: "Cancel scheduled message",
+                                onclick: move |_| {
+                                    spawn_local(async move {
+                                        if let Err(e) = cancel_scheduled_message(id).await {
+                                            error!("Failed to cancel scheduled message: {}", e);
+                                        }
+                                    });
+                                },
+                                "\u{00d7}"
                             }
                         }
                     }
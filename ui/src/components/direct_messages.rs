@@ -11,6 +11,11 @@
 //! that (room, peer). Replaces the earlier per-room inbox button in the
 //! members panel (zorolin feedback, 2026-05-16).
 //!
+//! Group DMs (3 to 8 members) have their own [`GroupDmThreadModal`], opened
+//! from the same rail section. A group is started from the member-info
+//! modal's "Group DM" button, which opens [`NewGroupDmModal`] to pick the
+//! other members and write the first message.
+//!
 //! Persistence model: all message state lives in `ChatRoomStateV1`. This
 //! module only adds *view* state — currently open thread, last-seen
 //! timestamps per peer for unread tracking. Last-seen state is purely
@@ -19,17 +24,22 @@
 //! back as unread on every page load.
 
 mod dm_thread_modal;
+mod group_dm_thread_modal;
 mod invite_via_dm_picker_modal;
+mod new_group_dm_modal;
 
 pub use dm_thread_modal::DmThreadModal;
+pub use group_dm_thread_modal::GroupDmThreadModal;
 pub use invite_via_dm_picker_modal::InviteViaDmPickerModal;
+pub use new_group_dm_modal::NewGroupDmModal;
 
 use dioxus::prelude::*;
 use ed25519_dalek::VerifyingKey;
 use river_core::chat_delegate::{HiddenDmThreadEntry, OutboundDmEntry};
-use river_core::room_state::direct_messages::PurgeToken;
+use river_core::room_state::direct_messages::{AuthorizedDirectMessage, PurgeToken};
+use river_core::room_state::group_direct_messages::GroupThreadId;
 use river_core::room_state::member::MemberId;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Currently-open DM thread, addressed by (room_owner_vk, counterparty).
 /// `None` means no DM modal is open.
@@ -98,10 +108,36 @@ pub fn mark_thread_read(room: VerifyingKey, peer: MemberId, up_to_ts: u64) {
 /// thread first.
 pub fn open_dm_thread(room: VerifyingKey, peer: MemberId) {
     crate::util::defer(move || {
+        *OPEN_GROUP_DM_THREAD.write() = None;
         *OPEN_DM_THREAD.write() = Some((room, peer));
     });
 }
 
+/// Currently-open group DM thread, addressed by (room_owner_vk, thread).
+/// Independent of [`OPEN_DM_THREAD`]; opening one closes the other.
+pub static OPEN_GROUP_DM_THREAD: GlobalSignal<Option<(VerifyingKey, GroupThreadId)>> =
+    Global::new(|| None);
+
+/// Open the [`GroupDmThreadModal`] for `thread` in `room`.
+pub fn open_group_dm_thread(room: VerifyingKey, thread: GroupThreadId) {
+    crate::util::defer(move || {
+        *OPEN_DM_THREAD.write() = None;
+        *OPEN_GROUP_DM_THREAD.write() = Some((room, thread));
+    });
+}
+
+/// "New group DM" picker state. When `Some((room, seed))`, the
+/// [`NewGroupDmModal`] is visible for `room` with `seed` (the member whose
+/// info modal it was opened from) already picked.
+pub static NEW_GROUP_DM: GlobalSignal<Option<(VerifyingKey, MemberId)>> = Global::new(|| None);
+
+/// Open the [`NewGroupDmModal`] for `room`, starting from `seed`.
+pub fn open_new_group_dm(room: VerifyingKey, seed: MemberId) {
+    crate::util::defer(move || {
+        *NEW_GROUP_DM.write() = Some((room, seed));
+    });
+}
+
 /// "Share an invite via DM…" picker state. When `Some((room, peer))`, the
 /// [`InviteViaDmPickerModal`] is visible and offers to generate an invite
 /// for ANOTHER room and pre-fill a DM to `peer` in `room` with the invite
@@ -185,6 +221,27 @@ impl OutboundDmsCache {
     }
 }
 
+/// Inbound DMs that reached the local user through their DM inbox contract
+/// (#230 Phase 4) instead of the room, keyed by the room they were sent in.
+/// Filled by `freenet_api::inbox_sync`, and always empty on a build that
+/// bundles no inbox contract. Thread and unread rendering read it next to
+/// the room's own DMs via [`room_and_inbox_dms`].
+pub static INBOX_DMS: GlobalSignal<HashMap<VerifyingKey, Vec<AuthorizedDirectMessage>>> =
+    Global::new(HashMap::new);
+
+/// A room's in-room DMs followed by its inbox-delivered ones, skipping any
+/// inbox copy of a DM the room already holds. Both sources are live while
+/// senders move over to inboxes, so every DM reader goes through this.
+pub(crate) fn room_and_inbox_dms<'a>(
+    room: &'a [AuthorizedDirectMessage],
+    inbox: &'a [AuthorizedDirectMessage],
+) -> Vec<&'a AuthorizedDirectMessage> {
+    let mut seen: HashSet<PurgeToken> = room.iter().map(|m| m.purge_token()).collect();
+    let mut merged: Vec<&AuthorizedDirectMessage> = room.iter().collect();
+    merged.extend(inbox.iter().filter(|m| seen.insert(m.purge_token())));
+    merged
+}
+
 /// Local view-state for issue freenet/river#261: per-`(room, peer)`
 /// "hidden-at" cutoffs. A thread is hidden from
 /// [`crate::components::room_list::dm_rail_section::DmRailSection`]
@@ -215,6 +272,77 @@ pub fn is_thread_hidden_for(
         .is_some_and(|h| max_message_ts <= h.hidden_at_ts)
 }
 
+/// Purge, in every room, the DMs sent to the user by members they ignore
+/// with "also delete their DMs" (see `IgnoreList::dm_purge_tokens`): one
+/// recipient purge envelope per room that has any, applied locally and then
+/// synced like the thread view's "Delete their messages". Only room-held DMs
+/// are covered; an inbox-delivered one stays hidden rather than purged.
+///
+/// Writes `ROOMS`, so call it from a clean context (a `defer`), never while
+/// a ROOMS borrow is live.
+pub fn purge_ignored_dms() {
+    use freenet_scaffold::ComposableState;
+    use river_core::room_state::direct_messages::{advance_recipient_purges, DirectMessagesDelta};
+    use river_core::room_state::{ChatRoomParametersV1, ChatRoomStateV1Delta};
+
+    let ignored = crate::components::app::chat_delegate::IGNORED_MEMBERS
+        .peek()
+        .clone();
+    if !ignored.ignored().any(|e| e.purge_dms) {
+        return;
+    }
+    let mut purged_rooms = Vec::new();
+    crate::components::app::ROOMS.with_mut(|rooms| {
+        for (room, room_data) in rooms.map.iter_mut() {
+            let Some(self_sk) = room_data.signing_key().cloned() else {
+                continue;
+            };
+            let self_id = MemberId::from(&self_sk.verifying_key());
+            let state = &room_data.room_state;
+            let tokens = ignored.dm_purge_tokens(self_id, &state.direct_messages);
+            if tokens.is_empty() {
+                continue;
+            }
+            let previous = state
+                .direct_messages
+                .purges
+                .iter()
+                .find(|p| p.recipient_id == self_id);
+            let envelope = match advance_recipient_purges(&self_sk, room, previous, tokens) {
+                Ok(envelope) => envelope,
+                Err(e) => {
+                    dioxus::logger::tracing::warn!("Ignored-member DM purge failed: {}", e);
+                    continue;
+                }
+            };
+            let delta = ChatRoomStateV1Delta {
+                direct_messages: Some(DirectMessagesDelta {
+                    new_messages: vec![],
+                    advanced_purges: vec![envelope],
+                }),
+                ..Default::default()
+            };
+            let params = ChatRoomParametersV1 { owner: *room };
+            let parent = room_data.room_state.clone();
+            match room_data
+                .room_state
+                .apply_delta(&parent, &params, &Some(delta))
+            {
+                Ok(()) => {
+                    room_data.rebuild_private_actions_state();
+                    purged_rooms.push(*room);
+                }
+                Err(e) => {
+                    dioxus::logger::tracing::warn!("Ignored-member DM purge rejected: {:?}", e)
+                }
+            }
+        }
+    });
+    for room in purged_rooms {
+        crate::components::app::mark_needs_sync(room);
+    }
+}
+
 /// Pure helper: decide how to render an outbound DM bubble given the
 /// loaded plaintext cache.
 ///
@@ -774,7 +902,8 @@ pub fn seed_dm_last_seen_if_needed() {
 #[cfg(test)]
 mod tests {
     use super::{
-        parse_outbound_invite_summary, summarise_body_for_outbound_cache, OutboundInviteSummary,
+        parse_outbound_invite_summary, room_and_inbox_dms, summarise_body_for_outbound_cache,
+        OutboundInviteSummary,
     };
     use river_core::room_state::dm_body::{DirectMessageBody, InvitePayload};
 
@@ -786,6 +915,32 @@ mod tests {
         }))
     }
 
+    /// An inbox copy of a DM the room already holds is listed once; an
+    /// inbox-only DM is appended after the room's.
+    #[test]
+    fn inbox_dms_are_merged_after_the_rooms_without_duplicates() {
+        use river_core::room_state::direct_messages::compose_direct_message;
+        let owner = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let peer = ed25519_dalek::SigningKey::from_bytes(&[2u8; 32]);
+        let dm = |ts: u64| {
+            compose_direct_message(
+                &owner,
+                &peer.verifying_key(),
+                &owner.verifying_key(),
+                ts,
+                ts,
+                b"hi",
+            )
+            .unwrap()
+        };
+        let (in_room, inbox_only) = (dm(10), dm(11));
+        let room = vec![in_room.clone()];
+        let inbox = vec![in_room.clone(), inbox_only.clone()];
+
+        let merged = room_and_inbox_dms(&room, &inbox);
+        assert_eq!(merged, vec![&in_room, &inbox_only]);
+    }
+
     /// A sent invitation must be recognisable in the sender's own thread —
     /// it is the only signal their bubble has, since the outbound copy is
     /// sealed to the recipient.
@@ -13,7 +13,8 @@ use crate::components::app::chat_delegate::{save_outbound_dm, unhide_dm_thread};
 use crate::components::app::{mark_needs_sync, ROOMS};
 use crate::components::direct_messages::{
     lookup_outbound_plaintext, mark_thread_read, open_invite_via_dm_picker,
-    parse_outbound_invite_summary, DM_DRAFT, OPEN_DM_THREAD, OUTBOUND_DMS,
+    parse_outbound_invite_summary, room_and_inbox_dms, DM_DRAFT, INBOX_DMS, OPEN_DM_THREAD,
+    OUTBOUND_DMS,
 };
 use crate::components::members::Invitation;
 use crate::components::room_list::receive_invitation_modal::present_invitation;
@@ -107,13 +108,16 @@ pub fn DmThreadModal() -> Element {
     };
 
     rsx! {
-        DmThreadModalBody { room, peer }
+        // Keyed so switching threads remounts the composer with that
+        // thread's own draft.
+        DmThreadModalBody { key: "{room:?}{peer}", room, peer }
     }
 }
 
 #[component]
 fn DmThreadModalBody(room: VerifyingKey, peer: MemberId) -> Element {
     let mut draft = use_signal(String::new);
+    crate::components::app::chat_delegate::use_synced_draft(room, Some(peer), draft);
     let mut send_error: Signal<Option<String>> = use_signal(|| None);
 
     // Drain any DM_DRAFT seeded by the invite-via-DM picker (#252) once
@@ -224,14 +228,20 @@ fn DmThreadModalBody(room: VerifyingKey, peer: MemberId) -> Element {
                 .unwrap_or_else(|| short_member_id(&peer));
 
             // Peer must still be a member for sends to be accepted; surface
-            // that constraint to the user up front.
-            let peer_still_member = peer == owner_id
-                || room_data
+            // that constraint to the user up front. Their key is kept for
+            // the verified badge.
+            let peer_vk = if peer == owner_id {
+                Some(room)
+            } else {
+                room_data
                     .room_state
                     .members
                     .members
                     .iter()
-                    .any(|m| m.member.id() == peer);
+                    .find(|m| m.member.id() == peer)
+                    .map(|m| m.member.member_vk)
+            };
+            let peer_still_member = peer_vk.is_some();
 
             // Snapshot the outbound-DM cache once so each rendered
             // outbound bubble does an O(1) HashMap lookup instead of
@@ -254,9 +264,23 @@ fn DmThreadModalBody(room: VerifyingKey, peer: MemberId) -> Element {
                 }
             };
 
+            // DMs delivered to our inbox contract rather than the room
+            // (#230 Phase 4). Same fallible-read-plus-nudge as the outbound
+            // cache above; a contended read renders the room's DMs alone
+            // until the retry.
+            let inbox_dms = match INBOX_DMS.try_read() {
+                Ok(g) => g.get(&room).cloned().unwrap_or_default(),
+                Err(_) => {
+                    crate::util::signal_guard::schedule_nudge();
+                    Vec::new()
+                }
+            };
+
             let mut latest_inbound_ts: u64 = 0;
             let mut rendered: Vec<RenderedDm> = Vec::new();
-            for msg in &room_data.room_state.direct_messages.messages {
+            for msg in
+                room_and_inbox_dms(&room_data.room_state.direct_messages.messages, &inbox_dms)
+            {
                 // Both `false` when `self_id` is `None`, so `between_us` below
                 // is false for every message and the thread renders empty —
                 // the "never mislabel" degradation described above, with no
@@ -391,6 +415,7 @@ fn DmThreadModalBody(room: VerifyingKey, peer: MemberId) -> Element {
 
             Some(ViewData {
                 peer_nickname,
+                peer_vk,
                 peer_still_member,
                 messages: rendered,
                 latest_inbound_ts,
@@ -499,6 +524,9 @@ fn DmThreadModalBody(room: VerifyingKey, peer: MemberId) -> Element {
 
     let peer_label = view_data.peer_nickname.clone();
     let peer_still_member = view_data.peer_still_member;
+    let peer_verified = view_data
+        .peer_vk
+        .is_some_and(|vk| crate::components::app::chat_delegate::is_verified(&vk));
     let identity_known = view_data.identity_known;
     let can_send = view_data.can_send;
 
@@ -903,6 +931,14 @@ fn DmThreadModalBody(room: VerifyingKey, peer: MemberId) -> Element {
                     h2 { class: "text-lg font-semibold text-text",
                         "Direct messages with "
                         span { class: "text-accent", "{peer_label}" }
+                        if peer_verified {
+                            span {
+                                "data-testid": "dm-thread-verified-badge",
+                                class: "ml-2 inline-flex items-center px-2 py-0.5 rounded-full text-xs font-medium bg-green-500/20 text-green-400",
+                                title: "You compared safety numbers with this member",
+                                "✔ Verified"
+                            }
+                        }
                     }
                     button {
                         class: "p-1 text-text-muted hover:text-text transition-colors text-xl",
@@ -1194,6 +1230,8 @@ fn DmThreadModalBody(room: VerifyingKey, peer: MemberId) -> Element {
 #[derive(Clone, PartialEq)]
 struct ViewData {
     peer_nickname: String,
+    /// The peer's key while they are a member, for the verified badge.
+    peer_vk: Option<VerifyingKey>,
     peer_still_member: bool,
     messages: Vec<RenderedDm>,
     latest_inbound_ts: u64,
@@ -1605,7 +1643,7 @@ fn short_vk_prefix(vk: &VerifyingKey) -> String {
         .collect()
 }
 
-fn unix_now() -> u64 {
+pub(super) fn unix_now() -> u64 {
     // `SystemTime::now()` panics on `wasm32-unknown-unknown` (the JS
     // platform-time stub is not implemented). Route through
     // `crate::util::get_current_system_time` which uses the
@@ -1617,7 +1655,7 @@ fn unix_now() -> u64 {
         .unwrap_or(0)
 }
 
-fn format_local_time(unix_secs: u64) -> String {
+pub(super) fn format_local_time(unix_secs: u64) -> String {
     let datetime = SystemTime::UNIX_EPOCH
         .checked_add(std::time::Duration::from_secs(unix_secs))
         .unwrap_or(SystemTime::UNIX_EPOCH);
@@ -0,0 +1,505 @@
+//! Group DM thread modal: one small private thread among 3 to 8 members of a
+//! room (`river_core::room_state::group_direct_messages`).
+//!
+//! Opened from the group rows of
+//! [`crate::components::room_list::dm_rail_section::DmRailSection`]. A group
+//! thread is its participant set, so replying just sends a new group message
+//! to the same members. Unlike a pairwise DM the sender holds a sealed copy of
+//! the body key, so outbound bubbles decrypt directly and need no outbound
+//! plaintext cache. A new group is started from
+//! [`NewGroupDmModal`](super::NewGroupDmModal), which sends its first message
+//! through the same [`send_group_dm`].
+//!
+//! "Delete for me" tombstones the thread's inbound messages in the local
+//! member's pairwise purge envelope. That hides them for this member only; the
+//! room drops a group message once every recipient has purged it.
+
+use super::dm_thread_modal::{format_local_time, unix_now};
+use crate::components::app::{mark_needs_sync, ROOMS};
+use crate::components::direct_messages::OPEN_GROUP_DM_THREAD;
+use crate::room_data::RoomData;
+use dioxus::logger::tracing::{error, warn};
+use dioxus::prelude::*;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use freenet_scaffold::ComposableState;
+use river_core::room_state::direct_messages::{
+    advance_recipient_purges, DirectMessagesDelta, PurgeToken, MAX_DM_CIPHERTEXT_BYTES,
+};
+use river_core::room_state::group_direct_messages::{
+    compose_group_direct_message, open_group_direct_message, GroupThreadId,
+};
+use river_core::room_state::member::MemberId;
+use river_core::room_state::{ChatRoomParametersV1, ChatRoomStateV1, ChatRoomStateV1Delta};
+use std::collections::HashMap;
+
+/// Same margin under the ciphertext cap as the pairwise composer.
+const GROUP_DM_BODY_BYTE_CAP: usize = MAX_DM_CIPHERTEXT_BYTES - 256;
+
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct RenderedGroupDm {
+    pub(crate) sender: MemberId,
+    pub(crate) outgoing: bool,
+    pub(crate) timestamp: u64,
+    pub(crate) body: String,
+    pub(crate) token: PurgeToken,
+}
+
+/// The messages of `thread` as `self_sk` sees them, oldest first: decrypted,
+/// and without the ones this member has purged.
+pub(crate) fn render_group_thread(
+    state: &ChatRoomStateV1,
+    self_sk: &SigningKey,
+    thread: GroupThreadId,
+) -> Vec<RenderedGroupDm> {
+    let self_id = MemberId::from(&self_sk.verifying_key());
+    state
+        .group_direct_messages
+        .thread(thread)
+        .into_iter()
+        .filter(|m| !m.purged_by(self_id, state))
+        .map(|m| RenderedGroupDm {
+            sender: m.message.sender,
+            outgoing: m.message.sender == self_id,
+            timestamp: m.message.timestamp,
+            body: match open_group_direct_message(self_sk, m) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(err) => format!("unable to decrypt: {}", err),
+            },
+            token: m.purge_token(),
+        })
+        .collect()
+}
+
+#[derive(Clone, PartialEq)]
+struct GroupViewData {
+    /// Display names of the other participants.
+    others: Vec<String>,
+    /// Keys of the other participants, for replies; `None` once any has left.
+    other_vks: Option<Vec<VerifyingKey>>,
+    messages: Vec<RenderedGroupDm>,
+    nicknames: HashMap<MemberId, String>,
+}
+
+#[component]
+pub fn GroupDmThreadModal() -> Element {
+    let active = *OPEN_GROUP_DM_THREAD.read();
+    let Some((room, thread)) = active else {
+        return rsx! {};
+    };
+
+    rsx! {
+        GroupDmThreadModalBody { room, thread }
+    }
+}
+
+#[component]
+fn GroupDmThreadModalBody(room: VerifyingKey, thread: GroupThreadId) -> Element {
+    let mut draft = use_signal(String::new);
+    let mut send_error: Signal<Option<String>> = use_signal(|| None);
+    let mut confirm_delete_open: Signal<bool> = use_signal(|| false);
+
+    let view = use_memo(move || {
+        // Same anchor-then-fallible-read discipline as `DmThreadModalBody`:
+        // this modal is always mounted, so a contended pass must keep a
+        // subscription and retry. freenet/river#555.
+        crate::util::signal_guard::anchor();
+        let Ok(rooms) = ROOMS.try_read() else {
+            crate::util::signal_guard::schedule_nudge();
+            return None;
+        };
+        let room_data = rooms.map.get(&room)?;
+        let self_sk = room_data.signing_key()?.clone();
+        let self_id = MemberId::from(&self_sk.verifying_key());
+
+        let nicknames: HashMap<MemberId, String> = room_data
+            .room_state
+            .member_info
+            .member_info
+            .iter()
+            .map(|info| {
+                (
+                    info.member_info.member_id,
+                    crate::util::display_name::display_nickname(
+                        &info.member_info.preferred_nickname,
+                        &room_data.secrets,
+                    ),
+                )
+            })
+            .collect();
+
+        let participants: Vec<MemberId> = room_data
+            .room_state
+            .group_direct_messages
+            .thread(thread)
+            .first()
+            .map(|m| m.message.participants().collect())
+            .unwrap_or_default();
+        let others: Vec<MemberId> = participants
+            .into_iter()
+            .filter(|id| *id != self_id)
+            .collect();
+
+        Some(GroupViewData {
+            others: others
+                .iter()
+                .map(|id| display_name(&nicknames, id))
+                .collect(),
+            other_vks: others
+                .iter()
+                .map(|id| member_vk(room_data, room, *id))
+                .collect(),
+            messages: render_group_thread(&room_data.room_state, &self_sk, thread),
+            nicknames,
+        })
+    });
+
+    let view_value = view.read();
+    let Some(view_data) = view_value.as_ref() else {
+        return rsx! {};
+    };
+    let title = view_data.others.join(", ");
+    let can_reply = view_data.other_vks.is_some();
+
+    let close = move |_| {
+        crate::util::defer(move || {
+            *OPEN_GROUP_DM_THREAD.write() = None;
+        });
+    };
+
+    let mut do_send = move || {
+        let body = draft.read().clone();
+        if body.trim().is_empty() {
+            return;
+        }
+        // Read from the memo rather than capturing, so the closure stays
+        // `Copy` for both the Enter handler and the Send button.
+        let Some(other_vks) = view.peek().as_ref().and_then(|v| v.other_vks.clone()) else {
+            return;
+        };
+        let sent = send_group_dm(room, &other_vks, &body, move |landed| {
+            if landed.is_some() {
+                draft.set(String::new());
+            } else {
+                send_error.set(Some(
+                    "This message couldn't be added to the room — a participant may \
+                         have left."
+                        .into(),
+                ));
+            }
+        });
+        send_error.set(sent.err());
+    };
+
+    let delete_for_me = move |_| {
+        confirm_delete_open.set(false);
+        let Some(room_data) = ROOMS
+            .try_read()
+            .ok()
+            .and_then(|r| r.map.get(&room).cloned())
+        else {
+            return;
+        };
+        let Some(self_sk) = room_data.signing_key().cloned() else {
+            return;
+        };
+        let self_id = MemberId::from(&self_sk.verifying_key());
+        let tokens: Vec<PurgeToken> = render_group_thread(&room_data.room_state, &self_sk, thread)
+            .into_iter()
+            .filter(|m| m.sender != self_id)
+            .map(|m| m.token)
+            .collect();
+        if tokens.is_empty() {
+            send_error.set(Some("No messages from others to delete.".into()));
+            return;
+        }
+        let previous = room_data
+            .room_state
+            .direct_messages
+            .purges
+            .iter()
+            .find(|p| p.recipient_id == self_id)
+            .cloned();
+        let envelope = match advance_recipient_purges(&self_sk, &room, previous.as_ref(), tokens) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                warn!("advance_recipient_purges failed: {}", e);
+                send_error.set(Some(
+                    "Couldn't delete those messages — something went wrong.".into(),
+                ));
+                return;
+            }
+        };
+        let delta = ChatRoomStateV1Delta {
+            direct_messages: Some(DirectMessagesDelta {
+                new_messages: vec![],
+                advanced_purges: vec![envelope],
+            }),
+            ..Default::default()
+        };
+        let params = ChatRoomParametersV1 { owner: room };
+        crate::util::defer(move || {
+            let applied = ROOMS.with_mut(|rooms| {
+                let Some(rd) = rooms.map.get_mut(&room) else {
+                    return false;
+                };
+                let parent = rd.room_state.clone();
+                match rd.room_state.apply_delta(&parent, &params, &Some(delta)) {
+                    Ok(()) => {
+                        rd.rebuild_private_actions_state();
+                        true
+                    }
+                    Err(e) => {
+                        error!("Group DM purge apply_delta failed: {:?}", e);
+                        false
+                    }
+                }
+            });
+            if applied {
+                mark_needs_sync(room);
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "fixed inset-0 z-50 flex items-center justify-center",
+            div { class: "absolute inset-0 bg-black/50", onclick: close }
+            div { class: "relative z-10 w-full max-w-lg mx-4 bg-panel rounded-xl shadow-xl border border-border flex flex-col max-h-[80vh]",
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-border",
+                    h2 { class: "text-lg font-semibold text-text",
+                        "Group DM with "
+                        span { class: "text-accent", "{title}" }
+                    }
+                    button {
+                        class: "p-1 text-text-muted hover:text-text transition-colors text-xl",
+                        onclick: close,
+                        "✕"
+                    }
+                }
+                div { class: "flex-1 overflow-y-auto px-5 py-4 space-y-2",
+                    if view_data.messages.is_empty() {
+                        p { class: "text-sm text-text-muted italic", "No messages to show." }
+                    }
+                    for (idx, m) in view_data.messages.iter().enumerate() {
+                        div {
+                            key: "{idx}_{m.timestamp}",
+                            class: if m.outgoing { "flex flex-col items-end" } else { "flex flex-col items-start" },
+                            if !m.outgoing {
+                                span { class: "text-[10px] text-text-muted",
+                                    "{display_name(&view_data.nicknames, &m.sender)}"
+                                }
+                            }
+                            div {
+                                class: if m.outgoing { "max-w-[80%] px-3 py-2 rounded-lg text-sm break-words bg-accent/20 text-text" } else { "max-w-[80%] px-3 py-2 rounded-lg text-sm break-words bg-surface text-text" },
+                                "{m.body}"
+                            }
+                            span { class: "text-[10px] text-text-muted mt-0.5",
+                                "{format_local_time(m.timestamp)}"
+                            }
+                        }
+                    }
+                }
+                div { class: "border-t border-border px-5 py-3 space-y-2",
+                    if let Some(err) = send_error.read().as_ref() {
+                        div { class: "text-xs text-red-400", "{err}" }
+                    }
+                    if !can_reply {
+                        div { class: "text-xs text-yellow-400",
+                            "Someone in this group is no longer in the room, so replies would be rejected."
+                        }
+                    }
+                    div { class: "flex items-end gap-2",
+                        textarea {
+                            class: "flex-1 px-3 py-2 bg-surface border border-border rounded-lg text-sm text-text resize-none min-h-[2.5rem] max-h-32",
+                            placeholder: "Reply to the group...",
+                            value: "{draft.read()}",
+                            oninput: move |e| draft.set(e.value()),
+                            onkeydown: move |e| {
+                                if e.key() == Key::Enter && !e.modifiers().shift() {
+                                    e.prevent_default();
+                                    if can_reply {
+                                        do_send();
+                                    }
+                                }
+                            },
+                            disabled: !can_reply,
+                        }
+                        button {
+                            class: "px-3 py-2 bg-accent hover:bg-accent-hover disabled:opacity-50 text-white text-sm font-medium rounded-lg transition-colors",
+                            disabled: draft.read().trim().is_empty() || !can_reply,
+                            onclick: move |_| do_send(),
+                            "Send"
+                        }
+                    }
+                    div { class: "flex flex-wrap justify-between items-center gap-x-3 gap-y-1 pt-1",
+                        span { class: "text-[10px] text-text-muted",
+                            "Only the people in this group can read these messages."
+                        }
+                        if *confirm_delete_open.read() {
+                            span { class: "flex gap-2 text-xs",
+                                button {
+                                    class: "text-red-400 hover:underline",
+                                    onclick: delete_for_me,
+                                    "Delete for me"
+                                }
+                                button {
+                                    class: "text-text-muted hover:underline",
+                                    onclick: move |_| confirm_delete_open.set(false),
+                                    "Cancel"
+                                }
+                            }
+                        } else {
+                            button {
+                                class: "text-xs text-text-muted hover:text-red-400 transition-colors",
+                                title: "Remove the others' messages from your view. They still see them.",
+                                onclick: move |_| confirm_delete_open.set(true),
+                                "Delete their messages"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Compose a group message from the local member to `others` in `room`,
+/// apply it locally and mark the room for sync. Shared by the thread composer
+/// and [`NewGroupDmModal`](super::NewGroupDmModal).
+///
+/// Problems found before composing come back as the `Err` text to show. The
+/// apply is deferred, so whether the message landed (it doesn't if a
+/// participant has left) is reported to `on_applied` instead, with the
+/// message's thread when it did.
+pub(super) fn send_group_dm(
+    room: VerifyingKey,
+    others: &[VerifyingKey],
+    body: &str,
+    on_applied: impl FnOnce(Option<GroupThreadId>) + 'static,
+) -> Result<(), String> {
+    if body.len() > GROUP_DM_BODY_BYTE_CAP {
+        return Err(format!(
+            "Message too long: {} bytes (cap is {} bytes)",
+            body.len(),
+            GROUP_DM_BODY_BYTE_CAP
+        ));
+    }
+    let Some(room_data) = ROOMS
+        .try_read()
+        .ok()
+        .and_then(|r| r.map.get(&room).cloned())
+    else {
+        error!("Group DM send: room data missing");
+        return Err("This room isn't loaded yet — try again in a moment.".into());
+    };
+    let Some(self_sk) = room_data.signing_key().cloned() else {
+        return Err(
+            "This device doesn't hold your key for this room, so you can't send DMs here.".into(),
+        );
+    };
+    // Rejoin bundle for a pruned sender, as in the pairwise composer.
+    let (rejoin_members, rejoin_member_info) = room_data.build_rejoin_delta();
+    let now = unix_now();
+    let auth = compose_group_direct_message(&self_sk, others, &room, now, now, body.as_bytes())
+        .map_err(|e| format!("Failed to compose group DM: {}", e))?;
+    let thread = auth.message.thread_id();
+    let auth_sig = auth.sender_signature;
+    let delta = ChatRoomStateV1Delta {
+        members: rejoin_members,
+        member_info: rejoin_member_info,
+        group_direct_messages: Some(vec![auth]),
+        ..Default::default()
+    };
+    let params = ChatRoomParametersV1 { owner: room };
+    crate::util::defer(move || {
+        let landed = ROOMS.with_mut(|rooms| {
+            let Some(rd) = rooms.map.get_mut(&room) else {
+                return false;
+            };
+            let parent = rd.room_state.clone();
+            if let Err(e) = rd.room_state.apply_delta(&parent, &params, &Some(delta)) {
+                error!("Group DM apply_delta failed: {:?}", e);
+                return false;
+            }
+            rd.rebuild_private_actions_state();
+            rd.room_state
+                .group_direct_messages
+                .messages
+                .iter()
+                .any(|m| m.sender_signature == auth_sig)
+        });
+        if landed {
+            mark_needs_sync(room);
+        } else {
+            warn!("Group DM was dropped on apply (membership?)");
+        }
+        on_applied(landed.then_some(thread));
+    });
+    Ok(())
+}
+
+/// A participant's key: the owner's is the room key.
+fn member_vk(room_data: &RoomData, room: VerifyingKey, id: MemberId) -> Option<VerifyingKey> {
+    if id == MemberId::from(&room) {
+        return Some(room);
+    }
+    room_data
+        .room_state
+        .members
+        .members
+        .iter()
+        .find(|m| m.member.id() == id)
+        .map(|m| m.member.member_vk)
+}
+
+fn display_name(nicknames: &HashMap<MemberId, String>, id: &MemberId) -> String {
+    nicknames
+        .get(id)
+        .cloned()
+        .unwrap_or_else(|| id.to_string().chars().take(8).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sk(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    /// Every participant reads the same thread, the sender's own messages
+    /// included, and a purge hides messages only for the member who made it.
+    #[test]
+    fn render_group_thread_decrypts_for_each_participant_and_hides_own_purges() {
+        let owner = sk(1);
+        let alice = sk(2);
+        let bob = sk(3);
+        let room = owner.verifying_key();
+        let msg = compose_group_direct_message(
+            &alice,
+            &[owner.verifying_key(), bob.verifying_key()],
+            &room,
+            10,
+            10,
+            b"standup moved",
+        )
+        .unwrap();
+        let thread = msg.message.thread_id();
+        let mut state = ChatRoomStateV1::default();
+        state.group_direct_messages.messages.push(msg.clone());
+
+        let for_alice = render_group_thread(&state, &alice, thread);
+        assert_eq!(for_alice.len(), 1);
+        assert!(for_alice[0].outgoing);
+        assert_eq!(for_alice[0].body, "standup moved");
+        let for_bob = render_group_thread(&state, &bob, thread);
+        assert!(!for_bob[0].outgoing);
+        assert_eq!(for_bob[0].body, "standup moved");
+
+        state
+            .direct_messages
+            .purges
+            .push(advance_recipient_purges(&bob, &room, None, [msg.purge_token()]).unwrap());
+        assert!(render_group_thread(&state, &bob, thread).is_empty());
+        assert_eq!(render_group_thread(&state, &owner, thread).len(), 1);
+    }
+}
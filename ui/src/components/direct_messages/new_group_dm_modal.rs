@@ -0,0 +1,284 @@
+//! "New group DM" picker: start a group thread with 2 to 7 other members of
+//! a room.
+//!
+//! Opened from the member-info modal's "Group DM" button with that member
+//! already picked. A group thread has no existence apart from its messages
+//! (see `river_core::room_state::group_direct_messages`), so the picker is
+//! also the first message's composer: Send composes it through
+//! [`super::group_dm_thread_modal::send_group_dm`], and once it lands the
+//! picker closes and the new thread opens in [`super::GroupDmThreadModal`].
+//!
+//! Mounted unconditionally in `app.rs` like
+//! [`super::InviteViaDmPickerModal`], and renders nothing while
+//! [`NEW_GROUP_DM`] is `None`.
+
+use super::group_dm_thread_modal::send_group_dm;
+use crate::components::app::ROOMS;
+use crate::components::direct_messages::{open_group_dm_thread, NEW_GROUP_DM};
+use dioxus::prelude::*;
+use ed25519_dalek::VerifyingKey;
+use river_core::room_state::group_direct_messages::{
+    MAX_GROUP_DM_PARTICIPANTS, MIN_GROUP_DM_PARTICIPANTS,
+};
+use river_core::room_state::member::MemberId;
+use river_core::ChatRoomStateV1;
+use std::collections::{BTreeSet, HashMap};
+
+/// A member the local user can add to a new group.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct GroupDmCandidate {
+    pub(crate) id: MemberId,
+    pub(crate) vk: VerifyingKey,
+    pub(crate) name: String,
+}
+
+/// Pure helper: everyone in `room` but `self_id`, the owner included, sorted
+/// by display name.
+pub(crate) fn group_dm_candidates(
+    room: VerifyingKey,
+    state: &ChatRoomStateV1,
+    self_id: MemberId,
+    nicknames: &HashMap<MemberId, String>,
+) -> Vec<GroupDmCandidate> {
+    let name = |id: MemberId| {
+        nicknames
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string().chars().take(8).collect())
+    };
+    let mut out: Vec<GroupDmCandidate> = std::iter::once(room)
+        .chain(state.members.members.iter().map(|m| m.member.member_vk))
+        .map(|vk| (MemberId::from(&vk), vk))
+        .filter(|(id, _)| *id != self_id)
+        .map(|(id, vk)| GroupDmCandidate {
+            id,
+            vk,
+            name: name(id),
+        })
+        .collect();
+    out.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    out
+}
+
+#[component]
+pub fn NewGroupDmModal() -> Element {
+    // Hooks first, before the early return, as in `InviteViaDmPickerModal`.
+    let mut picked: Signal<BTreeSet<MemberId>> = use_signal(BTreeSet::new);
+    let mut draft = use_signal(String::new);
+    let mut send_error: Signal<Option<String>> = use_signal(|| None);
+
+    // Reset on every open / close / switch, seeding the pick with the member
+    // the picker was opened from. Reads only `NEW_GROUP_DM`, so the writes
+    // below don't re-trigger it.
+    use_effect(move || {
+        let active = *NEW_GROUP_DM.read();
+        picked.set(
+            active
+                .map(|(_, seed)| BTreeSet::from([seed]))
+                .unwrap_or_default(),
+        );
+        draft.set(String::new());
+        send_error.set(None);
+    });
+
+    let active = *NEW_GROUP_DM.read();
+    let Some((room, _seed)) = active else {
+        return rsx! {};
+    };
+
+    // Inline rather than `use_memo`: `room` is a captured value, not a
+    // signal, so a memo would keep the previous room's members on reopen.
+    let candidates: Vec<GroupDmCandidate> = ROOMS
+        .try_read()
+        .ok()
+        .and_then(|rooms| {
+            let room_data = rooms.map.get(&room)?;
+            let self_id = room_data.self_member_id()?;
+            let nicknames: HashMap<MemberId, String> = room_data
+                .room_state
+                .member_info
+                .member_info
+                .iter()
+                .map(|info| {
+                    (
+                        info.member_info.member_id,
+                        crate::util::display_name::display_nickname(
+                            &info.member_info.preferred_nickname,
+                            &room_data.secrets,
+                        ),
+                    )
+                })
+                .collect();
+            Some(group_dm_candidates(
+                room,
+                &room_data.room_state,
+                self_id,
+                &nicknames,
+            ))
+        })
+        .unwrap_or_default();
+
+    let min_others = MIN_GROUP_DM_PARTICIPANTS - 1;
+    let max_others = MAX_GROUP_DM_PARTICIPANTS - 1;
+    let picked_count = picked.read().len();
+    let can_send =
+        (min_others..=max_others).contains(&picked_count) && !draft.read().trim().is_empty();
+
+    let close = move |_| {
+        crate::util::defer(move || {
+            *NEW_GROUP_DM.write() = None;
+        });
+    };
+
+    let send_candidates = candidates.clone();
+    let send = move |_| {
+        let body = draft.read().clone();
+        let picked_now = picked.read().clone();
+        let others: Vec<VerifyingKey> = send_candidates
+            .iter()
+            .filter(|c| picked_now.contains(&c.id))
+            .map(|c| c.vk)
+            .collect();
+        if others.len() != picked_now.len() {
+            send_error.set(Some("Someone you picked is no longer in the room.".into()));
+            return;
+        }
+        let sent = send_group_dm(room, &others, &body, move |landed| match landed {
+            Some(thread) => {
+                crate::util::defer(move || {
+                    *NEW_GROUP_DM.write() = None;
+                });
+                open_group_dm_thread(room, thread);
+            }
+            None => send_error.set(Some(
+                "This message couldn't be added to the room — someone you picked may \
+                     have left."
+                    .into(),
+            )),
+        });
+        send_error.set(sent.err());
+    };
+
+    rsx! {
+        div { class: "fixed inset-0 z-50 flex items-center justify-center",
+            div { class: "absolute inset-0 bg-black/50", onclick: close }
+            div { class: "relative z-10 w-full max-w-lg mx-4 bg-panel rounded-xl shadow-xl border border-border flex flex-col max-h-[80vh]",
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-border",
+                    h2 { class: "text-lg font-semibold text-text", "New group DM" }
+                    button {
+                        class: "p-1 text-text-muted hover:text-text transition-colors text-xl",
+                        onclick: close,
+                        "✕"
+                    }
+                }
+                div { class: "flex-1 overflow-y-auto px-5 py-4 space-y-1",
+                    p { class: "text-xs text-text-muted mb-2",
+                        "Pick {min_others} to {max_others} other members. Adding someone later starts a new group."
+                    }
+                    if candidates.len() < min_others {
+                        p { class: "text-sm text-text-muted italic",
+                            "This room needs at least {min_others} other members for a group DM."
+                        }
+                    }
+                    for c in candidates.iter() {
+                        {
+                            let id = c.id;
+                            let checked = picked.read().contains(&id);
+                            rsx! {
+                                label {
+                                    key: "{id}",
+                                    class: "flex items-center gap-2 px-2 py-1 rounded hover:bg-surface-hover text-sm text-text cursor-pointer",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked,
+                                        disabled: !checked && picked_count >= max_others,
+                                        onchange: move |_| {
+                                            picked.with_mut(|p| {
+                                                if !p.remove(&id) {
+                                                    p.insert(id);
+                                                }
+                                            });
+                                        },
+                                    }
+                                    "{c.name}"
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "border-t border-border px-5 py-3 space-y-2",
+                    if let Some(err) = send_error.read().as_ref() {
+                        div { class: "text-xs text-red-400", "{err}" }
+                    }
+                    div { class: "flex items-end gap-2",
+                        textarea {
+                            class: "flex-1 px-3 py-2 bg-surface border border-border rounded-lg text-sm text-text resize-none min-h-[2.5rem] max-h-32",
+                            placeholder: "First message to the group...",
+                            value: "{draft.read()}",
+                            oninput: move |e| draft.set(e.value()),
+                        }
+                        button {
+                            class: "px-3 py-2 bg-accent hover:bg-accent-hover disabled:opacity-50 text-white text-sm font-medium rounded-lg transition-colors",
+                            disabled: !can_send,
+                            onclick: send,
+                            "Send"
+                        }
+                    }
+                    span { class: "text-[10px] text-text-muted",
+                        "Only the people in this group can read these messages."
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use river_core::room_state::member::{AuthorizedMember, Member};
+
+    fn sk(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    /// The owner is offered like any member, the local member never is, and
+    /// unnamed members fall back to their short id.
+    #[test]
+    fn candidates_include_the_owner_and_skip_self() {
+        let owner = sk(1);
+        let alice = sk(2);
+        let bob = sk(3);
+        let room = owner.verifying_key();
+        let mut state = ChatRoomStateV1::default();
+        for member in [&alice, &bob] {
+            state.members.members.push(AuthorizedMember::new(
+                Member {
+                    owner_member_id: room.into(),
+                    invited_by: room.into(),
+                    member_vk: member.verifying_key(),
+                },
+                &owner,
+            ));
+        }
+        let alice_id = MemberId::from(&alice.verifying_key());
+        let bob_id = MemberId::from(&bob.verifying_key());
+        let owner_id = MemberId::from(&room);
+        let nicknames = HashMap::from([(owner_id, "Owen".to_string()), (bob_id, "Bea".into())]);
+
+        let for_alice = group_dm_candidates(room, &state, alice_id, &nicknames);
+        let names: Vec<&str> = for_alice.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Bea", "Owen"]);
+        assert_eq!(for_alice[1].vk, room);
+
+        let for_owner = group_dm_candidates(room, &state, owner_id, &nicknames);
+        assert!(for_owner.iter().all(|c| c.id != owner_id));
+        assert_eq!(for_owner.len(), 2);
+        let alice_row = for_owner.iter().find(|c| c.id == alice_id).unwrap();
+        assert_eq!(
+            alice_row.name,
+            alice_id.to_string().chars().take(8).collect::<String>()
+        );
+    }
+}
@@ -1574,6 +1574,24 @@ pub fn MemberList() -> Element {
         return rsx! {};
     }
 
+    // Members with River open, from the room's presence heartbeats. Kept out
+    // of the memo above: `PRESENCE_NOW` ticks every few seconds, and only the
+    // dots need to follow it.
+    let online: HashSet<MemberId> = {
+        use crate::components::app::freenet_api::presence_sync::{PRESENCE_NOW, ROOM_PRESENCE};
+        let now = PRESENCE_NOW();
+        CURRENT_ROOM
+            .read()
+            .owner_key
+            .and_then(|room| {
+                ROOM_PRESENCE
+                    .read()
+                    .get(&room)
+                    .map(|presence| presence.online(now).collect())
+            })
+            .unwrap_or_default()
+    };
+
     rsx! {
         aside {
             // Stable hook for the connection-indicator regression tests
@@ -1625,6 +1643,14 @@ pub fn MemberList() -> Element {
                             class: "w-full text-left px-3 py-1.5 rounded-lg text-sm text-text hover:bg-surface transition-colors flex items-center min-w-0",
                             title: "Member ID: {member_id}",
                             onclick: move |_| handle_member_click(member_id),
+                            if online.contains(&member_id) {
+                                span {
+                                    "data-testid": "member-online-dot",
+                                    class: "h-2 w-2 mr-2 rounded-full bg-green-500 flex-shrink-0",
+                                    title: "Online",
+                                    "aria-label": "Online",
+                                }
+                            }
                             // Nickname rendered as a plain text node — attacker-controlled
                             // bytes from `MemberInfoV1.preferred_nickname` MUST NOT be
                             // routed through `dangerous_inner_html` (freenet/river#227).
@@ -2730,6 +2756,7 @@ mod tests {
                 nickname.as_bytes().to_vec(),
             ),
             deputies,
+            profile: None,
         };
         AuthorizedMemberInfo::new_with_member_key(mi, sk)
     }
@@ -2978,6 +3005,7 @@ mod tests {
                 b"nick".to_vec(),
             ),
             deputies: vec![],
+            profile: None,
         };
         AuthorizedMemberInfo::new_with_member_key(mi, sk)
     }
@@ -3819,6 +3847,7 @@ mod tests {
                     b"nick".to_vec(),
                 ),
                 deputies,
+                profile: None,
             };
             AuthorizedMemberInfo::new_with_member_key(mi, sk)
         };
@@ -5030,6 +5059,7 @@ mod tests {
                     b"n".to_vec(),
                 ),
                 deputies,
+                profile: None,
             };
             AuthorizedMemberInfo::new_with_member_key(mi, sk)
         };
@@ -5643,6 +5673,7 @@ mod tests {
                     version: 0,
                     preferred_nickname: nickname,
                     deputies: vec![],
+                    profile: None,
                 },
                 sk,
             )
@@ -5668,6 +5699,7 @@ mod tests {
                             version: 0,
                             preferred_nickname: sealed(12, 0),
                             deputies: vec![id(&mod_sk)],
+                            profile: None,
                         },
                         &owner_sk,
                     )
@@ -1,19 +1,29 @@
 mod ban_button;
 mod deputy_button;
+mod ignore_button;
 mod invited_by_field;
 mod nickname_field;
+mod profile_fields;
+mod safety_number_field;
 
 use crate::components::app::{CURRENT_ROOM, MEMBER_INFO_MODAL, ROOMS};
-use crate::components::direct_messages::{open_dm_thread, open_invite_via_dm_picker};
+use crate::components::direct_messages::{
+    open_dm_thread, open_invite_via_dm_picker, open_new_group_dm,
+};
 use crate::components::members::member_info_modal::ban_button::BanButton;
 use crate::components::members::member_info_modal::deputy_button::DeputyButton;
+use crate::components::members::member_info_modal::ignore_button::IgnoreButton;
 use crate::components::members::member_info_modal::invited_by_field::InvitedByField;
 use crate::components::members::member_info_modal::nickname_field::NicknameField;
+use crate::components::members::member_info_modal::profile_fields::ProfileFields;
+use crate::components::members::member_info_modal::safety_number_field::SafetyNumberField;
 use crate::components::members::{ban_gate, BanGate};
 use crate::util::display_name::display_nickname;
 use dioxus::logger::tracing::*;
 use dioxus::prelude::*;
+use river_core::room_state::group_direct_messages::MIN_GROUP_DM_PARTICIPANTS;
 use river_core::room_state::member::MemberId;
+use river_core::room_state::member_info::Avatar;
 use river_core::room_state::ChatRoomParametersV1;
 
 /// The viewer's own [`MemberId`] in this room, or `None` when this build holds
@@ -333,12 +343,39 @@ pub fn MemberInfoModal() -> Element {
             .as_ref()
             .map(super::DeputyBadge::tooltip)
             .unwrap_or_default();
+        // The target's key and the viewer's, for the safety number. Both are
+        // needed, so a viewer with no local identity sees no number.
+        let peer_vk = if is_owner {
+            *owner_key_signal.read()
+        } else {
+            member.map(|m| m.member.member_vk)
+        };
+        let safety_pair = room_state
+            .self_verifying_key()
+            .zip(peer_vk)
+            .filter(|(me, peer)| me != peer);
+        let is_verified =
+            peer_vk.is_some_and(|vk| crate::components::app::chat_delegate::is_verified(&vk));
         // Decrypted display nickname for the target (for the deputy action copy).
         let target_nickname = display_nickname(
             &member_info.member_info.preferred_nickname,
             &room_state.secrets,
         );
 
+        // Profile fields, unsealed like the nickname. A field whose secret has
+        // not synced yet is left out rather than shown as ciphertext.
+        let profile = member_info.member_info.profile.clone().unwrap_or_default();
+        let unseal = |field: &Option<river_core::room_state::privacy::SealedBytes>| {
+            field.as_ref().and_then(|sealed| {
+                crate::util::ecies::unseal_bytes_with_secrets(sealed, &room_state.secrets).ok()
+            })
+        };
+        let profile_avatar = unseal(&profile.avatar).and_then(|bytes| Avatar::decode(&bytes).ok());
+        let profile_status =
+            unseal(&profile.status).map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+        let profile_bio =
+            unseal(&profile.bio).map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+
         // The ⚠ impersonation warning (freenet/river#489), through the SAME
         // entry point the member-list row and the conversation's author line
         // use, so this surface cannot show a badge the others do not — the
@@ -488,6 +525,14 @@ pub fn MemberInfoModal() -> Element {
                                     }
                                 }
                             }
+                            if is_verified {
+                                span {
+                                    "data-testid": "member-info-verified-tag",
+                                    class: "inline-flex items-center px-2.5 py-0.5 rounded-full text-sm font-medium bg-green-500/20 text-green-400",
+                                    title: "You compared safety numbers with this member",
+                                    "✔ Verified"
+                                }
+                            }
                             // Deputy shield — mirrors the member-list 🛡 badge
                             // (freenet/river#451). Shown under the same
                             // viewer-relevant condition as the row, with the
@@ -589,6 +634,12 @@ pub fn MemberInfoModal() -> Element {
                             member_info: member_info.clone()
                         }
 
+                        ProfileFields {
+                            avatar: profile_avatar,
+                            status: profile_status,
+                            bio: profile_bio,
+                        }
+
                         div {
                             class: "mb-4",
                             label { class: "block text-sm font-medium text-text-muted mb-2", "Member ID" }
@@ -600,12 +651,16 @@ pub fn MemberInfoModal() -> Element {
                             }
                         }
 
+                        if let Some((self_vk, peer_vk)) = safety_pair {
+                            SafetyNumberField { self_vk, peer_vk }
+                        }
+
                         // Member-action buttons — skip for self (no self-DMs).
                         // Side-by-side flex row, equal-weight styling, short
-                        // labels: neither action is "primary" over the
-                        // other so giving one an accent colour and the
-                        // other surface (as we had) reads as arbitrary.
-                        // Both now use the surface style with a hover
+                        // labels: no action is "primary" over the
+                        // others so giving one an accent colour and the
+                        // rest surface (as we had) reads as arbitrary.
+                        // All now use the surface style with a hover
                         // accent border. Ban remains separate below
                         // because it's destructive — different styling
                         // is intentional there.
@@ -621,6 +676,10 @@ pub fn MemberInfoModal() -> Element {
                             {
                                 let dm_room = owner_key_signal.unwrap();
                                 let share_button_enabled = other_rooms_count > 0;
+                                // Members plus the owner; a group needs the
+                                // viewer and at least two others.
+                                let group_button_enabled =
+                                    members_list.len() + 1 >= MIN_GROUP_DM_PARTICIPANTS;
                                 rsx! {
                                     div { class: "mb-4 flex gap-2",
                                         button {
@@ -637,6 +696,33 @@ pub fn MemberInfoModal() -> Element {
                                             },
                                             "DM"
                                         }
+                                        button {
+                                            "data-testid": "member-info-group-dm-button",
+                                            class: format!(
+                                                "flex-1 px-3 py-1.5 text-sm font-medium rounded-lg transition-colors border border-border {}",
+                                                if group_button_enabled {
+                                                    "bg-surface hover:bg-surface-hover text-text"
+                                                } else {
+                                                    "bg-surface text-text-muted opacity-60 cursor-not-allowed"
+                                                }
+                                            ),
+                                            disabled: !group_button_enabled,
+                                            "aria-label": "Start a group direct message with this member and others",
+                                            title: if group_button_enabled {
+                                                "Start a private thread with this member and others in the room"
+                                            } else {
+                                                "A group DM needs at least two other members in the room"
+                                            },
+                                            onclick: move |_| {
+                                                crate::util::defer(move || {
+                                                    MEMBER_INFO_MODAL.with_mut(|signal| {
+                                                        signal.member = None;
+                                                    });
+                                                });
+                                                open_new_group_dm(dm_room, member_id);
+                                            },
+                                            "Group DM"
+                                        }
                                         button {
                                             "data-testid": "member-info-share-invite-button",
                                             class: format!(
@@ -670,6 +756,13 @@ pub fn MemberInfoModal() -> Element {
                             }
                         }
 
+                        if member_id != self_member_id {
+                            IgnoreButton {
+                                target: member_id,
+                                nickname: target_nickname.clone(),
+                            }
+                        }
+
                         if !is_owner {
                             InvitedByField {
                                 invited_by: invited_by.clone(),
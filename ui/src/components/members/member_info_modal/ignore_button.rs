@@ -0,0 +1,67 @@
+use crate::components::app::chat_delegate::{set_ignored, IGNORED_MEMBERS};
+use dioxus::prelude::*;
+use river_core::room_state::member::MemberId;
+
+/// Ignore or unignore a member. Ignoring asks first, and offers to delete
+/// the DMs they have sent as well as hiding them.
+#[component]
+pub fn IgnoreButton(target: MemberId, nickname: String) -> Element {
+    let is_ignored = IGNORED_MEMBERS.read().is_ignored(&target);
+    let mut confirming = use_signal(|| false);
+    let mut purge_dms = use_signal(|| false);
+
+    if is_ignored {
+        return rsx! {
+            button {
+                "data-testid": "member-info-unignore-button",
+                class: "w-full mb-4 px-3 py-1.5 bg-surface hover:bg-surface-hover text-text text-sm font-medium rounded-lg transition-colors border border-border",
+                onclick: move |_| set_ignored(target, false, false),
+                "Stop ignoring"
+            }
+        };
+    }
+
+    rsx! {
+        if *confirming.read() {
+            div {
+                "data-testid": "member-info-ignore-confirm",
+                class: "mb-4 p-3 rounded-lg bg-surface border border-border text-sm",
+                p { class: "text-text mb-2",
+                    "Hide messages, reactions, mentions and DMs from "
+                    span { class: "font-medium", "{nickname}" }
+                    " in every room you share? Only you are affected; they are not told."
+                }
+                label { class: "flex items-center gap-2 text-text-muted mb-3",
+                    input {
+                        r#type: "checkbox",
+                        checked: *purge_dms.read(),
+                        onchange: move |evt| purge_dms.set(evt.checked()),
+                    }
+                    "Also delete the DMs they send me"
+                }
+                div { class: "flex gap-2",
+                    button {
+                        class: "flex-1 px-3 py-1.5 bg-red-500/20 hover:bg-red-500/30 text-red-400 text-sm font-medium rounded-lg transition-colors",
+                        onclick: move |_| {
+                            confirming.set(false);
+                            set_ignored(target, true, *purge_dms.peek());
+                        },
+                        "Ignore"
+                    }
+                    button {
+                        class: "flex-1 px-3 py-1.5 bg-surface hover:bg-surface-hover text-text text-sm font-medium rounded-lg transition-colors border border-border",
+                        onclick: move |_| confirming.set(false),
+                        "Cancel"
+                    }
+                }
+            }
+        } else {
+            button {
+                "data-testid": "member-info-ignore-button",
+                class: "w-full mb-4 px-3 py-1.5 bg-surface hover:bg-surface-hover text-text text-sm font-medium rounded-lg transition-colors border border-border",
+                onclick: move |_| confirming.set(true),
+                "Ignore"
+            }
+        }
+    }
+}
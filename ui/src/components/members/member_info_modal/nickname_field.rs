@@ -212,12 +212,17 @@ pub fn NicknameField(member_info: AuthorizedMemberInfo) -> Element {
                         member_id: canonical_base.member_info.member_id,
                         version: next_version,
                         preferred_nickname: sealed_nickname,
-                        // Preserve existing deputy grants (#410): republishing
-                        // member_info replaces the whole signed record, so
-                        // dropping deputies would silently revoke them.
-                        // Preserved from the CANONICAL base, not the stale
-                        // prop, for the same reason as the version above.
+                        // Preserve existing deputy grants (#410) and the
+                        // profile: republishing member_info replaces the whole
+                        // signed record, so dropping deputies would silently
+                        // revoke them. Preserved from the CANONICAL base, not
+                        // the stale prop, for the same reason as the version
+                        // above. The profile is fitted to the room's current
+                        // limits, or a lowered limit would drop the rename.
                         deputies: canonical_base.member_info.deputies.clone(),
+                        profile: canonical_base.member_info.profile.as_ref().and_then(|p| {
+                            p.fitted_to(&room_data.room_state.configuration.configuration)
+                        }),
                     };
                     let new_authorized_member_info =
                         AuthorizedMemberInfo::new_with_member_key(new_member_info, &signing_key);
@@ -0,0 +1,120 @@
+use base64::Engine;
+use dioxus::prelude::*;
+use river_core::room_state::member_info::Avatar;
+
+/// Raster formats shown inline. Anything else (SVG above all, which can carry
+/// script) is never turned into a `data:` URL.
+const INLINE_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// The member's avatar, status line and bio, read-only. Renders nothing for a
+/// member who has set none of them.
+#[component]
+pub fn ProfileFields(
+    avatar: Option<Avatar>,
+    status: Option<String>,
+    bio: Option<String>,
+) -> Element {
+    if avatar.is_none() && status.is_none() && bio.is_none() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "mb-4 flex items-start gap-3",
+            "data-testid": "member-info-profile",
+            match avatar.as_ref() {
+                Some(Avatar::Identicon(seed)) => rsx! { Identicon { seed: *seed } },
+                Some(image) => match avatar_data_url(image) {
+                    Some(url) => rsx! {
+                        img {
+                            class: "w-12 h-12 rounded-lg object-cover border border-border",
+                            src: "{url}",
+                            alt: "Avatar",
+                        }
+                    },
+                    None => rsx! {},
+                },
+                None => rsx! {},
+            }
+            div { class: "min-w-0 flex-1",
+                if let Some(status) = status {
+                    p { class: "text-sm text-text break-words", "{status}" }
+                }
+                if let Some(bio) = bio {
+                    p { class: "mt-1 text-sm text-text-muted whitespace-pre-wrap break-words", "{bio}" }
+                }
+            }
+        }
+    }
+}
+
+/// A 5×5 horizontally-mirrored grid coloured from `seed`.
+#[component]
+fn Identicon(seed: u64) -> Element {
+    let hue = (seed >> 32) % 360;
+    let fill = format!("hsl({hue}, 55%, 55%)");
+    rsx! {
+        svg {
+            class: "w-12 h-12 rounded-lg bg-surface border border-border shrink-0",
+            view_box: "-0.5 -0.5 6 6",
+            "aria-label": "Identicon",
+            for (x, y) in identicon_cells(seed) {
+                rect { x: "{x}", y: "{y}", width: "1", height: "1", fill: "{fill}" }
+            }
+        }
+    }
+}
+
+/// Filled cells of the identicon for `seed`: the low 15 bits choose the left
+/// three columns, which are mirrored onto the right two.
+fn identicon_cells(seed: u64) -> Vec<(u8, u8)> {
+    let mut cells = Vec::new();
+    for y in 0..5u8 {
+        for x in 0..3u8 {
+            if seed >> (y * 3 + x) & 1 == 1 {
+                cells.push((x, y));
+                if x < 2 {
+                    cells.push((4 - x, y));
+                }
+            }
+        }
+    }
+    cells
+}
+
+/// `data:` URL for an image avatar of an allowed type.
+fn avatar_data_url(avatar: &Avatar) -> Option<String> {
+    match avatar {
+        Avatar::Image { media_type, data } if INLINE_IMAGE_TYPES.contains(&media_type.as_str()) => {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+            Some(format!("data:{media_type};base64,{encoded}"))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identicons_are_mirrored_and_svg_avatars_are_not_inlined() {
+        let cells = identicon_cells(0b111);
+        assert_eq!(cells, vec![(0, 0), (4, 0), (1, 0), (3, 0), (2, 0)]);
+        assert!(identicon_cells(0).is_empty());
+
+        let png = Avatar::Image {
+            media_type: "image/png".to_string(),
+            data: vec![1, 2, 3],
+        };
+        assert_eq!(
+            avatar_data_url(&png).as_deref(),
+            Some("data:image/png;base64,AQID")
+        );
+        let svg = Avatar::Image {
+            media_type: "image/svg+xml".to_string(),
+            data: b"<svg onload=alert(1)/>".to_vec(),
+        };
+        assert_eq!(avatar_data_url(&svg), None);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::components::app::chat_delegate::{is_verified, set_verified};
+use dioxus::prelude::*;
+use ed25519_dalek::VerifyingKey;
+use river_core::util::safety_number;
+
+/// The safety number the viewer shares with a member, and the toggle that
+/// marks the member verified once the two have compared it out of band.
+#[component]
+pub fn SafetyNumberField(self_vk: VerifyingKey, peer_vk: VerifyingKey) -> Element {
+    let number = safety_number(&self_vk, &peer_vk);
+    let groups: Vec<String> = number.split(' ').map(str::to_string).collect();
+    let verified = is_verified(&peer_vk);
+
+    rsx! {
+        div {
+            class: "mb-4",
+            "data-testid": "member-info-safety-number",
+            label { class: "block text-sm font-medium text-text-muted mb-2", "Safety Number" }
+            div {
+                class: "grid grid-cols-4 gap-x-3 gap-y-1 px-3 py-2 bg-surface border border-border rounded-lg text-text font-mono text-sm",
+                for group in groups {
+                    span { "{group}" }
+                }
+            }
+            p {
+                class: "mt-2 text-xs text-text-muted",
+                "Compare this with the number on their device, in person or over a call. If they match, nobody is impersonating either of you."
+            }
+            button {
+                "data-testid": "member-info-verify-button",
+                class: "mt-2 w-full px-3 py-1.5 bg-surface hover:bg-surface-hover text-text text-sm font-medium rounded-lg transition-colors border border-border",
+                onclick: move |_| set_verified(peer_vk, !verified),
+                if verified { "Unmark as verified" } else { "Mark as verified" }
+            }
+        }
+    }
+}
@@ -12,6 +12,7 @@ use crate::components::app::chat_delegate::{
 use crate::components::app::document_title::{
     count_unread_in_room_data_with_mode, mark_current_room_as_read,
 };
+use crate::components::app::freenet_api::space_sync::SPACES;
 use crate::components::app::sync_info::{RoomSyncStatus, SYNC_INFO};
 use crate::components::app::{MobileView, CREATE_ROOM_MODAL, CURRENT_ROOM, MOBILE_VIEW, ROOMS};
 use crate::components::members::{ConnectionStatusIndicator, ImportIdentityModal};
@@ -105,6 +106,25 @@ fn unread_badge_label(unread: usize, is_mentions: bool) -> String {
     }
 }
 
+/// Render order for the rail, as indices into `spaces` (each room's space,
+/// in the user's order): rooms outside any space first, then each space's
+/// rooms together, spaces in the order their first room appears. Order
+/// within a group is the user's, so reordering still works inside one.
+fn group_rooms_by_space<K: PartialEq>(spaces: &[Option<K>]) -> Vec<usize> {
+    let mut groups: Vec<(Option<&K>, Vec<usize>)> = vec![(None, Vec::new())];
+    for (idx, space) in spaces.iter().enumerate() {
+        let space = space.as_ref();
+        match groups.iter_mut().find(|(key, _)| *key == space) {
+            Some((_, members)) => members.push(idx),
+            None => groups.push((space, vec![idx])),
+        }
+    }
+    groups
+        .into_iter()
+        .flat_map(|(_, members)| members)
+        .collect()
+}
+
 /// [`room_list_display_state`] read from the live signals.
 ///
 /// Shared with the conversation panel's no-room screen (freenet/river#509).
@@ -216,8 +236,14 @@ pub fn RoomList() -> Element {
             crate::util::signal_guard::schedule_nudge();
             return Vec::new();
         };
+        // Spaces, for grouping. Nudges like the reads below; a contended
+        // pass only shows the rooms ungrouped until the re-poll.
+        let spaces = SPACES.try_read().ok();
+        if spaces.is_none() {
+            crate::util::signal_guard::schedule_nudge();
+        }
 
-        rooms
+        let items = rooms
             .ordered_room_keys()
             .into_iter()
             .filter_map(|room_key| {
@@ -297,6 +323,21 @@ pub fn RoomList() -> Element {
                 // messages otherwise. (Muted rooms never render the badge.)
                 let unread_is_mentions =
                     mode == crate::room_data::NotificationMode::MentionsAndReplies;
+                // The room's space, as (owner key, name): only when the room
+                // points at the space AND the space lists the room, so
+                // neither side can group a room on its own (see
+                // `space_sync`).
+                let space = room_data
+                    .room_state
+                    .configuration
+                    .configuration
+                    .space
+                    .and_then(|space_vk| {
+                        let state = spaces.as_ref()?.get(&space_vk)?;
+                        state
+                            .lists_room(&room_key)
+                            .then(|| (space_vk, state.name().unwrap_or("Space").to_string()))
+                    });
                 Some((
                     room_key,
                     room_name,
@@ -306,8 +347,18 @@ pub fn RoomList() -> Element {
                     unread,
                     unread_is_mentions,
                     sync_error_msg,
+                    space,
                 ))
             })
+            .collect::<Vec<_>>();
+        let space_keys: Vec<Option<VerifyingKey>> = items
+            .iter()
+            .map(|item| item.8.as_ref().map(|(space_vk, _)| *space_vk))
+            .collect();
+        let mut items: Vec<_> = items.into_iter().map(Some).collect();
+        group_rooms_by_space(&space_keys)
+            .into_iter()
+            .filter_map(|idx| items[idx].take())
             .collect::<Vec<_>>()
     });
 
@@ -323,6 +374,12 @@ pub fn RoomList() -> Element {
     // control on the last. Also gates the reorder toggle's visibility: with one
     // room there is nothing to reorder.
     let room_count: usize = room_items.read().len();
+    // Each row's space, for placing the space headers.
+    let row_spaces: Vec<Option<VerifyingKey>> = room_items
+        .read()
+        .iter()
+        .map(|item| item.8.as_ref().map(|(space_vk, _)| *space_vk))
+        .collect();
 
     // Rail display state (freenet/river#397): the list, a loading spinner, a
     // migrating spinner, the failed block, or the calm empty state.
@@ -477,7 +534,7 @@ pub fn RoomList() -> Element {
                     RoomListDisplay::List => rsx! {},
                 }
 
-                {room_items.read().iter().enumerate().map(|(idx, (room_key, room_name, is_current, awaiting_sync, is_private, unread, unread_is_mentions, sync_error_msg))| {
+                {room_items.read().iter().enumerate().flat_map(|(idx, (room_key, room_name, is_current, awaiting_sync, is_private, unread, unread_is_mentions, sync_error_msg, space))| {
                     let room_key = *room_key;
                     let room_name = room_name.clone();
                     let is_current = *is_current;
@@ -507,7 +564,27 @@ pub fn RoomList() -> Element {
                     let is_drag_over = drag_over_now == Some(room_key);
                     let room_testid =
                         format!("room-item-{}", bs58::encode(room_key.to_bytes()).into_string());
-                    rsx! {
+                    // Space header above the first room of each space group.
+                    // Grouped rooms sort after ungrouped ones and stay
+                    // together (`group_rooms_by_space`), so comparing with
+                    // the previous row is enough.
+                    let prev_space = idx.checked_sub(1).and_then(|prev| row_spaces[prev]);
+                    let space_header = space
+                        .as_ref()
+                        .filter(|(space_vk, _)| prev_space != Some(*space_vk))
+                        .map(|(space_vk, space_name)| {
+                            let space_testid =
+                                format!("space-header-{}", bs58::encode(space_vk.to_bytes()).into_string());
+                            rsx! {
+                                li {
+                                    key: "space-{space_vk:?}",
+                                    "data-testid": "{space_testid}",
+                                    class: "px-3 pt-3 pb-1 text-xs font-semibold text-text-muted uppercase tracking-wide truncate",
+                                    "{space_name}"
+                                }
+                            }
+                        });
+                    let row = rsx! {
                         li {
                             key: "{room_key:?}",
                             // Stable per-room hook for automation (freenet/river#25).
@@ -715,7 +792,8 @@ pub fn RoomList() -> Element {
                             }
                             }
                         }
-                    }
+                    };
+                    space_header.into_iter().chain(std::iter::once(row))
                 }).collect::<Vec<_>>().into_iter()}
 
                 // Tail drop zone: only present mid-drag. Every row drop inserts
@@ -898,6 +976,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn rooms_group_by_space_keeping_the_users_order() {
+        // Rooms 1 and 4 are in space 'a', room 3 in 'b'; 0 and 2 in none.
+        let spaces = [None, Some('a'), None, Some('b'), Some('a')];
+        assert_eq!(group_rooms_by_space(&spaces), vec![0, 2, 1, 4, 3]);
+        assert_eq!(group_rooms_by_space::<char>(&[None, None]), vec![0, 1]);
+        assert!(group_rooms_by_space::<char>(&[]).is_empty());
+    }
+
     /// freenet/river#397 (#1 safety invariant): a genuinely-new user, once the
     /// load resolves (`Loaded`) with zero rooms, lands on the calm empty state —
     /// never a perpetual spinner. (The resolution itself — Loading→Loaded via the
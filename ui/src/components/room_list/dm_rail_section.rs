@@ -25,6 +25,10 @@
 //! the section lists currently-archived threads and offers per-row
 //! Un-archive, closing #266.
 //!
+//! Group DM threads (3 to 8 members) are listed after the pairwise rows
+//! and open [`GroupDmThreadModal`]. They carry no unread badge or Archive
+//! button yet; "Delete for me" inside the thread is the way to clear one.
+//!
 //! Hidden when empty so the rail doesn't show an empty section on first
 //! load. Sorts unread threads first, then by most-recent message time.
 //!
@@ -37,7 +41,7 @@
 use crate::components::app::chat_delegate::{hide_dm_thread, unhide_dm_thread};
 use crate::components::app::ROOMS;
 use crate::components::direct_messages::{
-    is_thread_hidden_for, open_dm_thread, DM_LAST_SEEN, HIDDEN_DM_THREADS,
+    is_thread_hidden_for, open_dm_thread, open_group_dm_thread, DM_LAST_SEEN, HIDDEN_DM_THREADS,
 };
 use crate::util::ecies::unseal_bytes_with_secrets;
 use dioxus::prelude::*;
@@ -47,7 +51,9 @@ use dioxus_free_icons::{
 };
 use ed25519_dalek::VerifyingKey;
 use river_core::chat_delegate::HiddenDmThreadEntry;
+use river_core::room_state::group_direct_messages::GroupThreadId;
 use river_core::room_state::member::MemberId;
+use river_core::room_state::ChatRoomStateV1;
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -94,6 +100,8 @@ const ARCHIVE_TOAST_DURATION_MS: u64 = 5_000;
 pub fn DmRailSection() -> Element {
     let threads = use_memo(build_view);
     let threads_value = threads.read().clone();
+    let groups = use_memo(build_group_view);
+    let groups_value = groups.read().clone();
 
     // Reading the toast signal here subscribes the rail to its writes so
     // a `set(None)` from the timeout reaction re-renders this component
@@ -134,7 +142,8 @@ pub fn DmRailSection() -> Element {
     // If there's nothing to show in the rail AND no archive entries AND
     // no active toast, render nothing — keeps the rail visually quiet on
     // first load.
-    if threads_value.is_empty() && archived_count == 0 && toast.is_none() {
+    if threads_value.is_empty() && groups_value.is_empty() && archived_count == 0 && toast.is_none()
+    {
         return rsx! {};
     }
 
@@ -155,6 +164,13 @@ pub fn DmRailSection() -> Element {
                 }
             }
         }
+        if !groups_value.is_empty() {
+            ul { class: "px-2 py-1 space-y-0.5",
+                for entry in groups_value.iter() {
+                    GroupDmRailRow { key: "{entry.room:?}_{entry.thread}", entry: entry.clone() }
+                }
+            }
+        }
         if archived_count > 0 {
             div { class: "px-3 pb-2",
                 button {
@@ -250,6 +266,28 @@ fn DmRailRow(entry: DmRailEntry) -> Element {
     }
 }
 
+#[component]
+fn GroupDmRailRow(entry: GroupDmRailEntry) -> Element {
+    let room = entry.room;
+    let thread = entry.thread;
+    let title = entry.member_nicknames.join(", ");
+
+    rsx! {
+        li {
+            button {
+                class: "dm-rail-row-btn w-full text-left pl-3 pr-9 py-1.5 rounded-lg text-sm transition-colors text-text hover:bg-surface flex items-center gap-2",
+                onclick: move |_| open_group_dm_thread(room, thread),
+                div { class: "flex-1 min-w-0",
+                    div { class: "truncate text-sm", "{title}" }
+                    div { class: "truncate text-[10px] text-text-muted",
+                        "group · in {entry.room_name}"
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Pure helper extracted from `DmRailRow`'s archive ✕ click handler so
 /// the toast bookkeeping can be unit-tested without standing up a Dioxus
 /// runtime. Returns the toast that `ARCHIVE_TOAST` would be set to, or
@@ -507,6 +545,62 @@ pub(crate) struct DmRailEntry {
     pub(crate) unread: usize,
 }
 
+/// One group DM thread in the rail.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct GroupDmRailEntry {
+    pub(crate) room: VerifyingKey,
+    pub(crate) thread: GroupThreadId,
+    /// The other participants' display names, in participant-id order.
+    pub(crate) member_nicknames: Vec<String>,
+    pub(crate) room_name: String,
+    pub(crate) last_any_ts: u64,
+}
+
+/// Pure helper: the group threads `self_id` belongs to in one room, skipping
+/// messages they have purged, so a thread they deleted entirely drops off.
+/// Most recent first, thread id as the tiebreak.
+pub(crate) fn group_rail_entries_for_room(
+    room: VerifyingKey,
+    room_name: &str,
+    self_id: MemberId,
+    state: &ChatRoomStateV1,
+    nicknames: &HashMap<MemberId, String>,
+) -> Vec<GroupDmRailEntry> {
+    let mut by_thread: HashMap<GroupThreadId, GroupDmRailEntry> = HashMap::new();
+    for msg in &state.group_direct_messages.messages {
+        if !msg.message.participants().any(|id| id == self_id) || msg.purged_by(self_id, state) {
+            continue;
+        }
+        let entry = by_thread
+            .entry(msg.message.thread_id())
+            .or_insert_with(|| GroupDmRailEntry {
+                room,
+                thread: msg.message.thread_id(),
+                member_nicknames: msg
+                    .message
+                    .participants()
+                    .filter(|id| *id != self_id)
+                    .map(|id| {
+                        nicknames
+                            .get(&id)
+                            .cloned()
+                            .unwrap_or_else(|| short_member_id(&id))
+                    })
+                    .collect(),
+                room_name: room_name.to_string(),
+                last_any_ts: 0,
+            });
+        entry.last_any_ts = entry.last_any_ts.max(msg.message.timestamp);
+    }
+    let mut entries: Vec<GroupDmRailEntry> = by_thread.into_values().collect();
+    entries.sort_by(|a, b| {
+        b.last_any_ts
+            .cmp(&a.last_any_ts)
+            .then_with(|| a.thread.cmp(&b.thread))
+    });
+    entries
+}
+
 #[derive(Clone, PartialEq, Debug)]
 struct ArchivedEntry {
     room: VerifyingKey,
@@ -733,6 +827,11 @@ thread_local! {
     /// `LAST_GOOD_RAIL`.
     static LAST_GOOD_ARCHIVED_COUNT: Cell<usize> = const { Cell::new(0) };
 
+    /// Last group-thread rows built by a clean `build_group_view` pass,
+    /// served on a contended `ROOMS` read like `LAST_GOOD_RAIL`.
+    static LAST_GOOD_GROUP_RAIL: RefCell<Vec<GroupDmRailEntry>> =
+        const { RefCell::new(Vec::new()) };
+
     /// "A rail nudge is already queued" latch, so N degraded builder
     /// passes in one render schedule exactly ONE deferred tick bump
     /// (storm-proof). Cleared when the deferred bump runs.
@@ -1055,6 +1154,16 @@ fn build_view() -> Vec<DmRailEntry> {
         }
     };
 
+    // Threads with ignored members are left out. Contention degrades to the
+    // last good rail, like the hide-list read above.
+    let Ok(ignored) = crate::components::app::chat_delegate::IGNORED_MEMBERS
+        .try_read()
+        .map(|list| list.clone())
+    else {
+        schedule_rail_nudge();
+        return last_good_rail();
+    };
+
     let mut entries: Vec<DmRailEntry> = Vec::new();
     for (owner_vk, room_data) in &rooms.map {
         // A room whose local identity is unknown contributes no DM rows:
@@ -1111,6 +1220,9 @@ fn build_view() -> Vec<DmRailEntry> {
         // `filter_rail_entries_newer_outbound_does_not_revive_hidden`
         // test for why that clock is inbound-only.
         for (peer, activity) in per_peer {
+            if ignored.is_ignored(&peer) {
+                continue;
+            }
             entries.push(DmRailEntry {
                 room: *owner_vk,
                 peer,
@@ -1158,6 +1270,72 @@ fn build_view() -> Vec<DmRailEntry> {
     entries
 }
 
+fn build_group_view() -> Vec<GroupDmRailEntry> {
+    // Same subscription anchor as `build_view`, for the same reason.
+    let _ = RAIL_REBUILD_TICK.read();
+
+    let Ok(rooms) = ROOMS.try_read() else {
+        schedule_rail_nudge();
+        return LAST_GOOD_GROUP_RAIL.with(|c| c.borrow().clone());
+    };
+
+    let mut entries: Vec<GroupDmRailEntry> = Vec::new();
+    for (owner_vk, room_data) in &rooms.map {
+        let Some(self_id) = room_data.self_member_id() else {
+            continue;
+        };
+        if room_data
+            .room_state
+            .group_direct_messages
+            .messages
+            .is_empty()
+        {
+            continue;
+        }
+        let sealed_name = &room_data
+            .room_state
+            .configuration
+            .configuration
+            .display
+            .name;
+        let room_name = match unseal_bytes_with_secrets(sealed_name, &room_data.secrets) {
+            Ok(b) => String::from_utf8_lossy(&b).to_string(),
+            Err(_) => sealed_name.to_string_lossy(),
+        };
+        let nicknames: HashMap<MemberId, String> = room_data
+            .room_state
+            .member_info
+            .member_info
+            .iter()
+            .map(|info| {
+                (
+                    info.member_info.member_id,
+                    crate::util::display_name::display_nickname(
+                        &info.member_info.preferred_nickname,
+                        &room_data.secrets,
+                    ),
+                )
+            })
+            .collect();
+        entries.extend(group_rail_entries_for_room(
+            *owner_vk,
+            &room_name,
+            self_id,
+            &room_data.room_state,
+            &nicknames,
+        ));
+    }
+    // Rooms iterate in hash order; re-sort across rooms.
+    entries.sort_by(|a, b| {
+        b.last_any_ts
+            .cmp(&a.last_any_ts)
+            .then_with(|| a.thread.cmp(&b.thread))
+    });
+
+    LAST_GOOD_GROUP_RAIL.with(|c| *c.borrow_mut() = entries.clone());
+    entries
+}
+
 fn short_member_id(id: &MemberId) -> String {
     id.to_string().chars().take(8).collect()
 }
@@ -1195,6 +1373,62 @@ mod tests {
         ed25519_dalek::SigningKey::from_bytes(&[seed; 32])
     }
 
+    // ===== group_rail_entries_for_room =====
+
+    /// One row per group thread the local member is in, named by the other
+    /// participants; a thread they have purged in full drops off.
+    #[test]
+    fn group_rail_entries_list_own_threads_and_drop_purged_ones() {
+        use river_core::room_state::direct_messages::advance_recipient_purges;
+        use river_core::room_state::group_direct_messages::compose_group_direct_message;
+
+        let owner = sk(1);
+        let alice = sk(2);
+        let bob = sk(3);
+        let carol = sk(4);
+        let room = owner.verifying_key();
+        let mut state = ChatRoomStateV1::default();
+        let with_bob = compose_group_direct_message(
+            &alice,
+            &[owner.verifying_key(), bob.verifying_key()],
+            &room,
+            10,
+            10,
+            b"one",
+        )
+        .unwrap();
+        let with_carol = compose_group_direct_message(
+            &alice,
+            &[owner.verifying_key(), carol.verifying_key()],
+            &room,
+            20,
+            20,
+            b"two",
+        )
+        .unwrap();
+        state.group_direct_messages.messages = vec![with_bob.clone(), with_carol];
+        let nicknames: HashMap<MemberId, String> =
+            [(MemberId::from(&alice.verifying_key()), "alice".to_string())].into();
+
+        let bob_id = MemberId::from(&bob.verifying_key());
+        let rows = group_rail_entries_for_room(room, "r", bob_id, &state, &nicknames);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].thread, with_bob.message.thread_id());
+        assert!(rows[0].member_nicknames.contains(&"alice".to_string()));
+        assert_eq!(rows[0].member_nicknames.len(), 2);
+
+        let alice_id = MemberId::from(&alice.verifying_key());
+        let rows = group_rail_entries_for_room(room, "r", alice_id, &state, &nicknames);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].last_any_ts, 20, "most recent first");
+
+        state
+            .direct_messages
+            .purges
+            .push(advance_recipient_purges(&bob, &room, None, [with_bob.purge_token()]).unwrap());
+        assert!(group_rail_entries_for_room(room, "r", bob_id, &state, &nicknames).is_empty());
+    }
+
     // ===== archive_cutoff (issue freenet/river#526) =====
 
     /// The rendered row wins when it is ahead of live state.
@@ -2058,7 +2292,8 @@ mod tests {
         let cases = [
             ("fnbuild_archived_view()", "fncurrent_archived_count()", 2),
             ("fncurrent_archived_count()", "fnbuild_view()", 2),
-            ("fnbuild_view()", "fnshort_member_id(", 3),
+            ("fnbuild_view()", "fnbuild_group_view()", 3),
+            ("fnbuild_group_view()", "fnshort_member_id(", 1),
         ];
         for (head, next_head, expected_nudges) in cases {
             let start = stripped
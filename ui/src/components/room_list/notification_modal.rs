@@ -35,7 +35,7 @@ const MODES: [(NotificationMode, &str, &str); 3] = [
     (
         NotificationMode::MentionsAndReplies,
         "Mentions & replies only",
-        "Notify only when someone @mentions you or replies to your message.",
+        "Notify only when someone @mentions you (including @here and @room) or replies to your message.",
     ),
     (
         NotificationMode::Muted,
@@ -8,4 +8,35 @@ pub const ROOM_CONTRACT_WASM: &[u8] = include_bytes!("../public/contracts/room_c
 
 pub const CHAT_DELEGATE_WASM: &[u8] = include_bytes!("../public/contracts/chat_delegate.wasm");
 
+// The DM inbox contract, bundled by build.rs from the same directory behind
+// the `inbox-contract` feature, which stays off until a released
+// `inbox_contract.wasm` is committed there.
+#[cfg(feature = "inbox-contract")]
+pub const INBOX_CONTRACT_WASM: Option<&[u8]> = Some(include_bytes!(concat!(
+    env!("OUT_DIR"),
+    "/inbox_contract.wasm"
+)));
+#[cfg(not(feature = "inbox-contract"))]
+pub const INBOX_CONTRACT_WASM: Option<&[u8]> = None;
+
+// The room presence contract, bundled the same way behind the
+// `presence-contract` feature.
+#[cfg(feature = "presence-contract")]
+pub const PRESENCE_CONTRACT_WASM: Option<&[u8]> = Some(include_bytes!(concat!(
+    env!("OUT_DIR"),
+    "/presence_contract.wasm"
+)));
+#[cfg(not(feature = "presence-contract"))]
+pub const PRESENCE_CONTRACT_WASM: Option<&[u8]> = None;
+
+// The space contract, bundled the same way behind the `space-contract`
+// feature.
+#[cfg(feature = "space-contract")]
+pub const SPACE_CONTRACT_WASM: Option<&[u8]> = Some(include_bytes!(concat!(
+    env!("OUT_DIR"),
+    "/space_contract.wasm"
+)));
+#[cfg(not(feature = "space-contract"))]
+pub const SPACE_CONTRACT_WASM: Option<&[u8]> = None;
+
 // pub const ROOM_CONTRACT_CODE_HASH: CodeHash = CodeHash::from_code(ROOM_CONTRACT_WASM);
@@ -270,6 +270,7 @@ fn create_room(
                     (random_full_name() + " (Owner) \u{1F6E1}\u{1F451}").into_bytes(),
                 ),
                 deputies: vec![other_member_id],
+                profile: None,
             },
             owner_sk,
         ));
@@ -298,6 +299,7 @@ fn create_room(
                         (random_full_name() + " (You)").into_bytes(),
                     ),
                     deputies: Vec::new(),
+                    profile: None,
                 },
                 &self_sk,
             ));
@@ -340,6 +342,7 @@ fn create_room(
                 version: 0,
                 preferred_nickname: SealedBytes::public(deputy_nickname.clone().into_bytes()),
                 deputies: Vec::new(),
+                profile: None,
             },
             &other_member_sk,
         ));
@@ -374,6 +377,7 @@ fn create_room(
                     confusable_variant(&deputy_nickname).into_bytes(),
                 ),
                 deputies: Vec::new(),
+                profile: None,
             },
             &impostor_sk,
         ));
@@ -1459,6 +1463,7 @@ fn push_test_message(
                     version: 0,
                     preferred_nickname: SealedBytes::public(nickname.as_bytes().to_vec()),
                     deputies: Vec::new(),
+                    profile: None,
                 },
                 sk,
             ));
@@ -1176,7 +1176,7 @@ impl RoomData {
         }
 
         // Republish our own member_info at version+1, preserving the
-        // (already-sealed) nickname; only `deputies` changes. The new
+        // (already-sealed) nickname and profile; only `deputies` changes. The new
         // version is derived from the HIGHER of the canonical room_state
         // version and the cached `self_member_info` version — not from
         // room_state alone. On a stale/reset client the room_state max can
@@ -1194,6 +1194,11 @@ impl RoomData {
             version: next_version,
             preferred_nickname: current_self.member_info.preferred_nickname.clone(),
             deputies,
+            profile: current_self
+                .member_info
+                .profile
+                .as_ref()
+                .and_then(|p| p.fitted_to(&self.room_state.configuration.configuration)),
         };
         let authorized = AuthorizedMemberInfo::new_with_member_key(new_info, &self_sk);
 
@@ -1334,6 +1339,7 @@ impl RoomData {
                             version: existing_version,
                             preferred_nickname,
                             deputies: Vec::new(),
+                            profile: None,
                         },
                         self_sk,
                     )
@@ -1455,6 +1461,7 @@ impl RoomData {
                 version: 0,
                 preferred_nickname: seal_bytes(nickname.as_bytes(), &secret, version),
                 deputies: Vec::new(),
+                profile: None,
             };
             return Some(AuthorizedMemberInfo::new_with_member_key(info, self_sk));
         }
@@ -1477,6 +1484,7 @@ impl RoomData {
             version: 0,
             preferred_nickname: SealedBytes::public(nickname.into_bytes()),
             deputies: Vec::new(),
+            profile: None,
         };
         Some(AuthorizedMemberInfo::new_with_member_key(info, self_sk))
     }
@@ -1780,8 +1788,9 @@ pub enum NotificationMode {
     /// Notify for every message from another member (the historical default).
     #[default]
     All,
-    /// Notify only for messages that @mention the local user or reply to one
-    /// of their messages.
+    /// Notify only for messages that @mention the local user, reach them with
+    /// an `@here` / `@room` the room lets its author send, or reply to one of
+    /// their messages.
     MentionsAndReplies,
     /// Never notify for this room.
     Muted,
@@ -2061,6 +2070,7 @@ impl Rooms {
                 SealedBytes::public(nickname.into_bytes())
             },
             deputies: Vec::new(),
+            profile: None,
         };
         let authorized_owner_info = AuthorizedMemberInfo::new(owner_info, &self_sk);
         room_state
@@ -2825,6 +2835,7 @@ mod tests {
             version: 0,
             preferred_nickname: SealedBytes::public("Alice".to_string().into_bytes()),
             deputies: Vec::new(),
+            profile: None,
         };
         let authorized_info = AuthorizedMemberInfo::new_with_member_key(info, &invitee_sk);
         room_state.member_info.member_info.push(authorized_info);
@@ -2870,6 +2881,7 @@ mod tests {
             version: 1,
             preferred_nickname: SealedBytes::public("Bob".to_string().into_bytes()),
             deputies: Vec::new(),
+            profile: None,
         };
         let updated_authorized =
             AuthorizedMemberInfo::new_with_member_key(updated_info, &invitee_sk);
@@ -4513,6 +4525,7 @@ mod tests {
             version: 2,
             preferred_nickname: SealedBytes::public(b"PlainLeak".to_vec()),
             deputies: Vec::new(),
+            profile: None,
         };
         room.self_member_info = Some(AuthorizedMemberInfo::new_with_member_key(
             public_entry,
@@ -4560,6 +4573,7 @@ mod tests {
             version: 6,
             preferred_nickname: seal_bytes(b"SealedName", &v0_secret, 0),
             deputies: Vec::new(),
+            profile: None,
         };
         room.self_member_info = Some(AuthorizedMemberInfo::new_with_member_key(
             private_entry,
@@ -4599,6 +4613,7 @@ mod tests {
             version: 2,
             preferred_nickname: SealedBytes::public(b"Edited".to_vec()),
             deputies: Vec::new(),
+            profile: None,
         };
         let edited = AuthorizedMemberInfo::new_with_member_key(edited, &invitee_sk);
 
@@ -4625,6 +4640,7 @@ mod tests {
             version: 1,
             preferred_nickname: SealedBytes::public(b"Other".to_vec()),
             deputies: Vec::new(),
+            profile: None,
         };
         let other = AuthorizedMemberInfo::new_with_member_key(other, &other_sk);
 
@@ -4654,6 +4670,7 @@ mod tests {
             version: 5,
             preferred_nickname: SealedBytes::public("Alice".to_string().into_bytes()),
             deputies: Vec::new(),
+            profile: None,
         };
         room.self_member_info = Some(AuthorizedMemberInfo::new_with_member_key(info, &invitee_sk));
 
@@ -4728,6 +4745,7 @@ mod tests {
             version: 0,
             preferred_nickname: SealedBytes::public(b"Present".to_vec()),
             deputies: Vec::new(),
+            profile: None,
         };
         network_state
             .member_info
@@ -4773,6 +4791,7 @@ mod tests {
             version: 7,
             preferred_nickname: SealedBytes::public(b"ChosenName".to_vec()),
             deputies: Vec::new(),
+            profile: None,
         };
         room.self_member_info = Some(AuthorizedMemberInfo::new_with_member_key(
             stored,
@@ -4900,6 +4919,7 @@ mod tests {
             version: 3,
             preferred_nickname: SealedBytes::public(b"PlainName".to_vec()),
             deputies: Vec::new(),
+            profile: None,
         };
         room.self_member_info = Some(AuthorizedMemberInfo::new_with_member_key(
             public_entry,
@@ -5083,6 +5103,7 @@ mod tests {
             version: 9,
             preferred_nickname: SealedBytes::public(b"PublishedName".to_vec()),
             deputies: Vec::new(),
+            profile: None,
         };
         room.self_member_info = Some(AuthorizedMemberInfo::new_with_member_key(
             stored,
@@ -5125,6 +5146,7 @@ mod tests {
             version: 4,
             preferred_nickname: seal_bytes(b"PublishedName", &v0_secret, 0),
             deputies: Vec::new(),
+            profile: None,
         };
         room.self_member_info = Some(AuthorizedMemberInfo::new_with_member_key(
             stored_info,
@@ -7422,6 +7444,7 @@ mod tests {
                     version: 1,
                     preferred_nickname: SealedBytes::public(b"x".to_vec()),
                     deputies: Vec::new(),
+                    profile: None,
                 },
                 &invitee_sk,
             ),
@@ -7619,6 +7642,7 @@ mod tests {
                 version: 0,
                 preferred_nickname: SealedBytes::public(b"m".to_vec()),
                 deputies: Vec::new(),
+                profile: None,
             };
             room_state
                 .member_info
@@ -8030,6 +8054,7 @@ mod tests {
                     version: 1,
                     preferred_nickname: SealedBytes::public(b"D".to_vec()),
                     deputies: vec![],
+                    profile: None,
                 };
                 let clean_authorized = AuthorizedMemberInfo::new_with_member_key(clean, &d_sk);
                 let stale_grant = MemberInfo {
@@ -8037,6 +8062,7 @@ mod tests {
                     version: 1,
                     preferred_nickname: SealedBytes::public(b"D".to_vec()),
                     deputies: vec![t_id],
+                    profile: None,
                 };
                 let stale_grant_authorized =
                     AuthorizedMemberInfo::new_with_member_key(stale_grant, &d_sk);
@@ -8206,6 +8232,7 @@ mod tests {
             version: 2,
             preferred_nickname: SealedBytes::public(b"D".to_vec()),
             deputies: vec![],
+            profile: None,
         };
         let authorized_v2 = AuthorizedMemberInfo::new_with_member_key(info_v2, &d_sk);
         room_state
@@ -8227,6 +8254,7 @@ mod tests {
             version: 5,
             preferred_nickname: SealedBytes::public(b"D".to_vec()),
             deputies: vec![],
+            profile: None,
         };
         let authorized_v5 = AuthorizedMemberInfo::new_with_member_key(info_v5, &d_sk);
 
@@ -159,6 +159,10 @@ mod tests {
             "dm_thread_modal.rs view",
             include_str!("../components/direct_messages/dm_thread_modal.rs"),
         ),
+        (
+            "custom_emoji.rs use_current_room_emoji",
+            include_str!("../components/conversation/custom_emoji.rs"),
+        ),
     ];
 
     /// Cut production source at the test module so a needle appearing only in a
@@ -283,14 +287,14 @@ mod tests {
                  fallibly. Remove the entry rather than leaving a vacuous pin."
             );
         }
-        // EXACT count, not a floor. There are 12 fallible memos across the 8
+        // EXACT count, not a floor. There are 13 fallible memos across the 9
         // files (conversation.rs alone has 4, member_info_modal.rs 2). A floor of
         // 8 left exactly the slack this assertion exists to remove: the matcher
         // could stop finding all four conversation.rs bodies -- the file that
         // caused #555 -- and still pass.
         assert_eq!(
-            checked, 12,
-            "expected to check exactly the 12 known fallible memos, checked \
+            checked, 13,
+            "expected to check exactly the 13 known fallible memos, checked \
              {checked}. If you added or removed a fallible memo, update this \
              number deliberately; if you did not, the matcher has stopped \
              finding memo bodies and this pin has gone vacuous."